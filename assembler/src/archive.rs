@@ -0,0 +1,195 @@
+use crate::object::ObjectError;
+
+/// The Magic-Bytes at the Start of every Archive
+const MAGIC: &[u8; 8] = b"!<arch>\n";
+/// The Size in Bytes of the Header in front of every Member
+const HEADER_SIZE: usize = 60;
+/// The longest Name that fits into the Header itself, including the
+/// terminating `/`
+const NAME_SIZE: usize = 16;
+
+/// A single File in an Archive
+#[derive(Debug, PartialEq, Clone)]
+pub struct Member {
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+/// A static Archive of Objects in the common `ar`-Format, as used by GNU
+/// `ar`. The Linker only uses the Objects in it that define a Symbol that
+/// is still undefined
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Archive {
+    pub members: Vec<Member>,
+}
+
+impl Archive {
+    /// Whether or not the Data looks like an Archive
+    pub fn is_archive(data: &[u8]) -> bool {
+        data.starts_with(MAGIC)
+    }
+
+    /// Serializes the Archive, Names that are too long for the Header of
+    /// their Member are stored in the `//`-Member
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut result = MAGIC.to_vec();
+
+        let mut long_names = Vec::new();
+        let mut names = Vec::with_capacity(self.members.len());
+        for member in self.members.iter() {
+            if member.name.len() < NAME_SIZE {
+                names.push(format!("{}/", member.name));
+            } else {
+                names.push(format!("/{}", long_names.len()));
+                long_names.extend_from_slice(member.name.as_bytes());
+                long_names.extend_from_slice(b"/\n");
+            }
+        }
+
+        if !long_names.is_empty() {
+            write_member(&mut result, "//", &long_names);
+        }
+        for (member, name) in self.members.iter().zip(names.iter()) {
+            write_member(&mut result, name, &member.data);
+        }
+
+        result
+    }
+
+    /// Parses an Archive, the Symbol-Table that GNU `ar` generates is
+    /// skipped, as the Linker reads the Symbols of the Objects themselves
+    pub fn parse(data: &[u8]) -> Result<Self, ObjectError> {
+        if !Self::is_archive(data) {
+            return Err(ObjectError::WrongIdentifier);
+        }
+
+        let mut result = Self::default();
+        let mut long_names: &[u8] = &[];
+        let mut position = MAGIC.len();
+        while position < data.len() {
+            let header = data
+                .get(position..position + HEADER_SIZE)
+                .ok_or(ObjectError::UnexpectedEnd)?;
+            if &header[58..60] != b"`\n" {
+                return Err(ObjectError::Malformed(
+                    "invalid archive member header".to_owned(),
+                ));
+            }
+
+            let size = std::str::from_utf8(&header[48..58])
+                .ok()
+                .and_then(|size| size.trim().parse::<usize>().ok())
+                .ok_or_else(|| ObjectError::Malformed("invalid archive member size".to_owned()))?;
+            let start = position + HEADER_SIZE;
+            let content = data
+                .get(start..start + size)
+                .ok_or(ObjectError::UnexpectedEnd)?;
+            // Every Member starts at an even Offset
+            position = start + size + size % 2;
+
+            let name = String::from_utf8_lossy(&header[..NAME_SIZE]);
+            let name = name.trim_end();
+            match name {
+                "/" | "/SYM64/" => continue,
+                "//" => {
+                    long_names = content;
+                    continue;
+                }
+                _ => {}
+            };
+
+            let name = match name.strip_prefix('/') {
+                Some(offset) => {
+                    let offset = offset
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|offset| *offset < long_names.len())
+                        .ok_or_else(|| {
+                            ObjectError::Malformed("invalid archive member name".to_owned())
+                        })?;
+                    let rest = &long_names[offset..];
+                    let end = rest.iter().position(|c| *c == b'\n').unwrap_or(rest.len());
+                    String::from_utf8_lossy(&rest[..end])
+                        .trim_end_matches('/')
+                        .to_owned()
+                }
+                None => name.trim_end_matches('/').to_owned(),
+            };
+
+            result.members.push(Member {
+                name,
+                data: content.to_vec(),
+            });
+        }
+
+        Ok(result)
+    }
+}
+
+fn write_member(result: &mut Vec<u8>, name: &str, data: &[u8]) {
+    let header = format!(
+        "{:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`\n",
+        name,
+        0,
+        0,
+        0,
+        644,
+        data.len()
+    );
+    result.extend_from_slice(header.as_bytes());
+    result.extend_from_slice(data);
+    if !data.len().is_multiple_of(2) {
+        result.push(b'\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let archive = Archive {
+            members: vec![
+                Member {
+                    name: "a.o".to_owned(),
+                    data: vec![1, 2, 3],
+                },
+                Member {
+                    name: "a_very_long_member_name.o".to_owned(),
+                    data: vec![4, 5],
+                },
+            ],
+        };
+
+        let serialized = archive.serialize();
+        assert_eq!(b"!<arch>\n//", &serialized[..10]);
+        assert_eq!(Ok(archive), Archive::parse(&serialized));
+    }
+
+    #[test]
+    fn symbol_table() {
+        let mut data = MAGIC.to_vec();
+        write_member(&mut data, "/", &[0, 0, 0, 0]);
+        write_member(&mut data, "b.o/", &[7]);
+
+        assert_eq!(
+            Ok(Archive {
+                members: vec![Member {
+                    name: "b.o".to_owned(),
+                    data: vec![7],
+                }]
+            }),
+            Archive::parse(&data)
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        let mut data = MAGIC.to_vec();
+        write_member(&mut data, "b.o/", &[1, 2, 3, 4]);
+        data.truncate(data.len() - 1);
+
+        assert_eq!(Err(ObjectError::UnexpectedEnd), Archive::parse(&data));
+    }
+}
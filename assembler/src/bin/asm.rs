@@ -0,0 +1,88 @@
+use assembler::parser;
+
+use chrono::Utc;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+struct RizmAssemble {
+    #[structopt(short = "i")]
+    input: String,
+    #[structopt(short = "o")]
+    output: String,
+    /// Writes a relocatable Object, that still has to be linked, instead
+    /// of a G3A-File
+    #[structopt(short = "c")]
+    object: bool,
+    /// Writes the relocatable Object as an ELF-File
+    #[structopt(long = "elf", requires = "object")]
+    elf: bool,
+    /// Writes the raw ByteCode instead of a G3A-File
+    #[structopt(long = "raw")]
+    raw: bool,
+    /// The Name of the Application in the G3A-File
+    #[structopt(long = "name", default_value = "test")]
+    name: String,
+    /// Writes the Addresses of all Labels into the given Symbol-Map
+    #[structopt(long = "map")]
+    map: Option<String>,
+}
+
+fn main() {
+    let cmd = RizmAssemble::from_args();
+
+    let content = std::fs::read_to_string(&cmd.input).unwrap();
+    let instructions = match parser::parse(&content) {
+        Ok(instructions) => instructions,
+        Err(error) => {
+            eprintln!(
+                "error: {}\n --> {}:{}",
+                error.message, cmd.input, error.line
+            );
+            std::process::exit(1);
+        }
+    };
+    if cmd.object {
+        match assembler::assemble_object(instructions) {
+            Ok(object) if cmd.elf => {
+                std::fs::write(&cmd.output, assembler::elf::write_object(&object)).unwrap()
+            }
+            Ok(object) => std::fs::write(&cmd.output, object.serialize()).unwrap(),
+            Err(error) => {
+                eprintln!("error: {}\n --> {}", error, cmd.input);
+                std::process::exit(1);
+            }
+        };
+        return;
+    }
+
+    let (code, symbols) = match assembler::assemble_with_symbols(instructions) {
+        Ok(result) => result,
+        Err(error) => {
+            eprintln!("error: {}\n --> {}", error, cmd.input);
+            std::process::exit(1);
+        }
+    };
+    if let Some(map) = cmd.map.as_ref() {
+        std::fs::write(map, symbols.serialize()).unwrap();
+    }
+
+    let output_path = std::path::Path::new(&cmd.output);
+    if cmd.raw {
+        std::fs::write(output_path, code).unwrap();
+        return;
+    }
+
+    let mut file_builder = g3a::FileBuilder::new(cmd.name.clone(), Utc::now().naive_utc());
+    file_builder
+        .short_name(cmd.name.clone())
+        .internal_name(format!("@{}", cmd.name.to_uppercase()))
+        .code(code);
+    let file = file_builder.finish();
+
+    let output_name = output_path.file_name().unwrap();
+    std::fs::write(
+        output_path,
+        file.serialize(&format!("/{}", output_name.to_str().unwrap())),
+    )
+    .unwrap();
+}
@@ -0,0 +1,48 @@
+use assembler::{disassemble, symbols::Symbols, BASE_ADDRESS};
+
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+struct RizmDisassemble {
+    #[structopt(short = "i")]
+    input: String,
+    /// Reads the Input as raw ByteCode instead of a G3A-File
+    #[structopt(long = "raw")]
+    raw: bool,
+    /// A Symbol-Map, as written by `asm --map`, to show the Labels
+    #[structopt(short = "s", long = "symbols")]
+    symbols: Option<String>,
+}
+
+fn main() {
+    let cmd = RizmDisassemble::from_args();
+
+    let content = std::fs::read(&cmd.input).unwrap();
+    let code = if cmd.raw {
+        content
+    } else {
+        match g3a::File::parse(&content) {
+            Ok(file) => file.executable_code,
+            Err(error) => {
+                eprintln!("error: {} is not a valid G3A-File: {}", cmd.input, error);
+                std::process::exit(1);
+            }
+        }
+    };
+
+    let symbols = match cmd.symbols.as_ref() {
+        Some(path) => {
+            let content = std::fs::read_to_string(path).unwrap();
+            match Symbols::parse(&content) {
+                Ok(symbols) => symbols,
+                Err(error) => {
+                    eprintln!("error: {}\n --> {}:{}", error.message, path, error.line);
+                    std::process::exit(1);
+                }
+            }
+        }
+        None => Symbols::new(),
+    };
+
+    print!("{}", disassemble::listing(&code, BASE_ADDRESS, &symbols));
+}
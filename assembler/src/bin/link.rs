@@ -0,0 +1,113 @@
+use assembler::{
+    archive::Archive,
+    elf::{self, Executable, Segment},
+    link::BSS_END,
+    object::Object,
+    BASE_ADDRESS,
+};
+
+use chrono::Utc;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+struct RizmLink {
+    /// The Objects and Archives to link, either in our own Format or as
+    /// ELF, the first Object has to contain the Entry-Point of the Program
+    inputs: Vec<String>,
+    #[structopt(short = "o")]
+    output: String,
+    /// Bundles the Inputs into a static Archive instead of linking them
+    #[structopt(long = "archive")]
+    archive: bool,
+    /// Writes the raw ByteCode instead of a G3A-File
+    #[structopt(long = "raw")]
+    raw: bool,
+    /// Writes an ELF-Executable instead of a G3A-File
+    #[structopt(long = "elf")]
+    elf: bool,
+    /// The Name of the Application in the G3A-File
+    #[structopt(long = "name", default_value = "test")]
+    name: String,
+    /// Writes the Addresses of all Symbols into the given Symbol-Map
+    #[structopt(long = "map")]
+    map: Option<String>,
+}
+
+fn main() {
+    let cmd = RizmLink::from_args();
+
+    if cmd.archive {
+        let mut archive = Archive::default();
+        for input in cmd.inputs.iter() {
+            let data = std::fs::read(input).unwrap();
+            let name = std::path::Path::new(input).file_name().unwrap();
+            archive.members.push(assembler::archive::Member {
+                name: name.to_string_lossy().into_owned(),
+                data,
+            });
+        }
+        std::fs::write(&cmd.output, archive.serialize()).unwrap();
+        return;
+    }
+
+    let mut objects = Vec::new();
+    let mut archives = Vec::new();
+    for input in cmd.inputs.iter() {
+        let data = std::fs::read(input).unwrap();
+        let parsed = if Archive::is_archive(&data) {
+            Archive::parse(&data).map(|archive| archives.push(archive))
+        } else {
+            Object::parse(&data).map(|object| objects.push(object))
+        };
+        if let Err(error) = parsed {
+            eprintln!("error: {}: {}", input, error);
+            std::process::exit(1);
+        }
+    }
+
+    let (code, symbols) = match assembler::link::link(objects, &archives) {
+        Ok(result) => result,
+        Err(errors) => {
+            for error in errors.iter() {
+                eprintln!("error: {}", error);
+            }
+            std::process::exit(1);
+        }
+    };
+    if let Some(map) = cmd.map.as_ref() {
+        std::fs::write(map, symbols.serialize()).unwrap();
+    }
+
+    let output_path = std::path::Path::new(&cmd.output);
+    if cmd.raw {
+        std::fs::write(output_path, code).unwrap();
+        return;
+    }
+    if cmd.elf {
+        let size = symbols.address(BSS_END).unwrap() - BASE_ADDRESS;
+        let executable = Executable {
+            entry: BASE_ADDRESS,
+            segments: vec![Segment {
+                address: BASE_ADDRESS,
+                data: code,
+                size,
+            }],
+        };
+        std::fs::write(output_path, elf::write_executable(&executable, &symbols)).unwrap();
+        return;
+    }
+
+    let mut file_builder = g3a::FileBuilder::new(cmd.name.clone(), Utc::now().naive_utc());
+    file_builder
+        .short_name(cmd.name.clone())
+        .internal_name(format!("@{}", cmd.name.to_uppercase()))
+        .code(code);
+    let file = file_builder.finish();
+
+    let output_name = output_path.file_name().unwrap();
+    std::fs::write(
+        output_path,
+        file.serialize(&format!("/{}", output_name.to_str().unwrap())),
+    )
+    .unwrap();
+}
@@ -1,12 +1,34 @@
 use sh::asm;
 
-/// Converts the Assembly to the final ByteCode
-pub fn to_bytes(instr: Vec<asm::Instruction>) -> Vec<u8> {
+use crate::AssembleError;
+
+/// Converts the Assembly to the final ByteCode, the Bss-Section is
+/// expected to be the last Section and is not part of the ByteCode
+pub fn to_bytes(instr: Vec<asm::Instruction>) -> Result<Vec<u8>, AssembleError> {
     let mut result = Vec::with_capacity(instr.len() * 2);
 
     for tmp in instr.iter() {
-        result.extend_from_slice(&tmp.to_byte());
+        match tmp {
+            asm::Instruction::Section(asm::Section::Bss) => break,
+            asm::Instruction::Section(_) => {}
+            asm::Instruction::Data(data) => result.extend_from_slice(data),
+            asm::Instruction::Space(size) => result.resize(result.len() + *size as usize, 0),
+            asm::Instruction::Align(alignment) => {
+                let alignment = *alignment as usize;
+                let padding = (alignment - result.len() % alignment) % alignment;
+                result.resize(result.len() + padding, 0);
+            }
+            _ => match tmp.encode() {
+                Ok(bytes) => result.extend_from_slice(&bytes),
+                Err(error) => {
+                    return Err(AssembleError::Encode {
+                        instr: tmp.clone(),
+                        error,
+                    })
+                }
+            },
+        };
     }
 
-    result
+    Ok(result)
 }
@@ -0,0 +1,120 @@
+use crate::{asm, symbols::Symbols};
+
+/// Disassembles the ByteCode, which is mapped at the given Address, into a
+/// Listing with the Address, the Opcode and the Instruction on every Line.
+/// Labels from the Symbols are shown in front of the Instructions they
+/// belong to and next to the Targets of Branches and PC-relative Loads
+pub fn listing(code: &[u8], base: u32, symbols: &Symbols) -> String {
+    let mut result = String::new();
+
+    for (index, chunk) in code.chunks(2).enumerate() {
+        let address = base + index as u32 * 2;
+        for name in symbols.at(address) {
+            if !result.is_empty() {
+                result.push('\n');
+            }
+            result.push_str(&format!("{:08x} <{}>:\n", address, name));
+        }
+
+        if chunk.len() < 2 {
+            result.push_str(&format!(
+                "{:08x}:  {:02x}     .byte 0x{:02x}\n",
+                address, chunk[0], chunk[0]
+            ));
+            continue;
+        }
+
+        let instr = asm::Instruction::parse(u16::from_be_bytes([chunk[0], chunk[1]]));
+        let mut line = format!(
+            "{:08x}:  {:02x} {:02x}  {}",
+            address,
+            chunk[0],
+            chunk[1],
+            instr.display_at(address)
+        );
+        if let Some(target) = instr.target(address) {
+            line.push_str(&annotation(&instr, target, code, base, symbols));
+        }
+
+        result.push_str(line.trim_end());
+        result.push('\n');
+    }
+
+    result
+}
+
+/// The Names of the Target, and for PC-relative Loads also the Value that
+/// is loaded
+fn annotation(
+    instr: &asm::Instruction,
+    target: u32,
+    code: &[u8],
+    base: u32,
+    symbols: &Symbols,
+) -> String {
+    let names = |address: u32| -> String {
+        symbols
+            .at(address)
+            .iter()
+            .map(|name| format!(" <{}>", name))
+            .collect()
+    };
+
+    let offset = target.wrapping_sub(base) as usize;
+    let value = match instr {
+        asm::Instruction::MovL(_, _) => code
+            .get(offset..offset + 4)
+            .map(|raw| u32::from_be_bytes([raw[0], raw[1], raw[2], raw[3]])),
+        asm::Instruction::MovW(_, _) => code
+            .get(offset..offset + 2)
+            .map(|raw| i16::from_be_bytes([raw[0], raw[1]]) as u32),
+        asm::Instruction::MovA(_) => Some(target),
+        _ => return names(target),
+    };
+
+    match value {
+        Some(value) => format!(" ! 0x{:08x}{}", value, names(value)),
+        None => format!(" ! @0x{:08x}", target),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_and_targets() {
+        let (code, symbols) = crate::assemble_with_symbols(vec![
+            asm::Instruction::Label("main".to_owned()),
+            asm::Instruction::MovLabel(1, "function".to_owned()),
+            asm::Instruction::JsrLabel("function".to_owned()),
+            asm::Instruction::Label("function".to_owned()),
+            asm::Instruction::Rts,
+            asm::Instruction::Nop,
+        ])
+        .unwrap();
+
+        let expected = "00300000 <main>:
+00300000:  d1 02  mov.l @(8,pc),r1 ! 0x00300006 <function>
+00300002:  b0 00  bsr 0x00300006 <function>
+00300004:  00 09  nop
+
+00300006 <function>:
+00300006:  00 0b  rts
+00300008:  00 09  nop
+0030000a:  00 09  nop
+0030000c:  00 30  .word 0x0030
+0030000e:  00 06  mov.l r0,@(r0,r0)
+";
+
+        assert_eq!(expected, listing(&code, 0x00300000, &symbols));
+    }
+
+    #[test]
+    fn odd_length() {
+        assert_eq!(
+            "00300000:  00 09  nop\n00300002:  12     .byte 0x12\n",
+            listing(&[0x00, 0x09, 0x12], 0x00300000, &Symbols::new())
+        );
+    }
+}
@@ -0,0 +1,780 @@
+pub use sh::elf::{is_elf, Executable, Segment};
+use sh::elf::{
+    Header, Reader, HEADER_SIZE, PROGRAM_HEADER_SIZE, PT_LOAD, SECTION_HEADER_SIZE,
+    TYPE_EXECUTABLE, TYPE_RELOCATABLE,
+};
+
+use crate::{
+    asm,
+    object::{Object, ObjectError, Relocation, RelocationKind, Section, Symbol},
+    symbols::Symbols,
+};
+
+const SYMBOL_SIZE: usize = 16;
+const REL_SIZE: usize = 8;
+const RELA_SIZE: usize = 12;
+
+const SHT_PROGBITS: u32 = 1;
+const SHT_SYMTAB: u32 = 2;
+const SHT_STRTAB: u32 = 3;
+const SHT_RELA: u32 = 4;
+const SHT_NOBITS: u32 = 8;
+const SHT_REL: u32 = 9;
+const SHT_INIT_ARRAY: u32 = 14;
+const SHT_FINI_ARRAY: u32 = 15;
+
+const SHF_WRITE: u32 = 0x1;
+const SHF_ALLOC: u32 = 0x2;
+const SHF_EXECINSTR: u32 = 0x4;
+const SHF_INFO_LINK: u32 = 0x40;
+
+const SHN_UNDEF: u16 = 0;
+const SHN_ABS: u16 = 0xfff1;
+const SHN_COMMON: u16 = 0xfff2;
+
+const STB_LOCAL: u8 = 0;
+const STB_GLOBAL: u8 = 1;
+const STT_NOTYPE: u8 = 0;
+const STT_SECTION: u8 = 3;
+
+const PF_X: u32 = 0x1;
+const PF_W: u32 = 0x2;
+const PF_R: u32 = 0x4;
+
+const R_SH_NONE: u32 = 0;
+const R_SH_DIR32: u32 = 1;
+const R_SH_REL32: u32 = 2;
+const R_SH_IND12W: u32 = 4;
+/// The Relocations that GNU `as` emits for Linker-Relaxation, from
+/// `R_SH_SWITCH16` to `R_SH_LABEL`, they only describe the Code and do not
+/// need to be applied when the Code is not relaxed
+const R_SH_RELAX: std::ops::RangeInclusive<u32> = 25..=32;
+
+/// Serializes the Executable, every Segment is also described by a Section
+/// and the Symbols are stored in the Symbol-Table so the Program can be
+/// disassembled
+pub fn write_executable(executable: &Executable, symbols: &Symbols) -> Vec<u8> {
+    let mut result = vec![0; HEADER_SIZE + executable.segments.len() * PROGRAM_HEADER_SIZE];
+    let mut names = StringTable::new();
+    let mut headers = vec![SectionHeader::default()];
+
+    // The Start, End and Index of the Section of every Segment
+    let mut ranges = Vec::new();
+    for (index, segment) in executable.segments.iter().enumerate() {
+        // The Offset in the File has to match the Address
+        while result.len() % 4 != segment.address as usize % 4 {
+            result.push(0);
+        }
+        let offset = result.len() as u32;
+        result.extend_from_slice(&segment.data);
+
+        let program_header = HEADER_SIZE + index * PROGRAM_HEADER_SIZE;
+        let mut header = Vec::with_capacity(PROGRAM_HEADER_SIZE);
+        for value in [
+            PT_LOAD,
+            offset,
+            segment.address,
+            segment.address,
+            segment.data.len() as u32,
+            segment.size,
+            PF_R | PF_W | PF_X,
+            4,
+        ]
+        .iter()
+        {
+            header.extend_from_slice(&value.to_be_bytes());
+        }
+        result[program_header..program_header + PROGRAM_HEADER_SIZE].copy_from_slice(&header);
+
+        let data_end = segment.address + segment.data.len() as u32;
+        if !segment.data.is_empty() {
+            ranges.push((segment.address, data_end, headers.len()));
+            headers.push(SectionHeader {
+                name: names.add(".text"),
+                kind: SHT_PROGBITS,
+                flags: SHF_ALLOC | SHF_WRITE | SHF_EXECINSTR,
+                address: segment.address,
+                offset,
+                size: segment.data.len() as u32,
+                alignment: 4,
+                ..SectionHeader::default()
+            });
+        }
+        if segment.size > segment.data.len() as u32 {
+            let end = segment.address + segment.size;
+            ranges.push((data_end, end, headers.len()));
+            headers.push(SectionHeader {
+                name: names.add(".bss"),
+                kind: SHT_NOBITS,
+                flags: SHF_ALLOC | SHF_WRITE,
+                address: data_end,
+                offset: result.len() as u32,
+                size: end - data_end,
+                alignment: 4,
+                ..SectionHeader::default()
+            });
+        }
+    }
+
+    let mut strings = StringTable::new();
+    let mut symbol_table = vec![0; SYMBOL_SIZE];
+    for (address, name) in symbols.iter() {
+        // Symbols at the End of a Section, like `__bss_end`, still
+        // belong to it
+        let section = ranges
+            .iter()
+            .find(|(start, end, _)| (*start..*end).contains(&address))
+            .or_else(|| ranges.iter().find(|(_, end, _)| *end == address))
+            .map_or(SHN_ABS, |(_, _, index)| *index as u16);
+        write_symbol(
+            &mut symbol_table,
+            strings.add(name),
+            address,
+            STB_LOCAL,
+            STT_NOTYPE,
+            section,
+        );
+    }
+    let symbol_count = (symbol_table.len() / SYMBOL_SIZE) as u32;
+    let symbol_index = headers.len() as u32;
+    push_table(
+        &mut result,
+        &mut headers,
+        SectionHeader {
+            name: names.add(".symtab"),
+            kind: SHT_SYMTAB,
+            link: symbol_index + 1,
+            info: symbol_count,
+            alignment: 4,
+            entry_size: SYMBOL_SIZE as u32,
+            ..SectionHeader::default()
+        },
+        &symbol_table,
+    );
+    push_table(
+        &mut result,
+        &mut headers,
+        SectionHeader {
+            name: names.add(".strtab"),
+            kind: SHT_STRTAB,
+            alignment: 1,
+            ..SectionHeader::default()
+        },
+        &strings.data,
+    );
+
+    finish(
+        &mut result,
+        headers,
+        names,
+        Header {
+            kind: TYPE_EXECUTABLE,
+            entry: executable.entry,
+            program_headers: executable.segments.len() as u16,
+            ..Header::default()
+        },
+    );
+    result
+}
+
+/// Serializes the Object as a relocatable ELF-File, that can also be used
+/// by the GNU-Linker
+pub fn write_object(object: &Object) -> Vec<u8> {
+    let mut result = vec![0; HEADER_SIZE];
+    let mut names = StringTable::new();
+    let mut headers = vec![SectionHeader::default()];
+
+    for section in object.sections.iter() {
+        let alignment = section.alignment.max(1);
+        result.resize(result.len().next_multiple_of(alignment as usize), 0);
+        let (name, kind, flags) = match section.kind {
+            asm::Section::Text => (".text", SHT_PROGBITS, SHF_ALLOC | SHF_EXECINSTR),
+            asm::Section::RoData => (".rodata", SHT_PROGBITS, SHF_ALLOC),
+            asm::Section::Data => (".data", SHT_PROGBITS, SHF_ALLOC | SHF_WRITE),
+            asm::Section::Bss => (".bss", SHT_NOBITS, SHF_ALLOC | SHF_WRITE),
+        };
+        headers.push(SectionHeader {
+            name: names.add(name),
+            kind,
+            flags,
+            offset: result.len() as u32,
+            size: section.size,
+            alignment,
+            ..SectionHeader::default()
+        });
+        if section.kind != asm::Section::Bss {
+            result.extend_from_slice(&section.data);
+        }
+    }
+
+    // All local Symbols have to be in front of the global ones
+    let mut order: Vec<usize> = (0..object.symbols.len()).collect();
+    order.sort_by_key(|index| is_global(&object.symbols[*index]));
+    let mut symbol_indices = vec![0; object.symbols.len()];
+    let mut strings = StringTable::new();
+    let mut symbol_table = vec![0; SYMBOL_SIZE];
+    for (position, index) in order.iter().enumerate() {
+        symbol_indices[*index] = position as u32 + 1;
+
+        let symbol = &object.symbols[*index];
+        let binding = if is_global(symbol) {
+            STB_GLOBAL
+        } else {
+            STB_LOCAL
+        };
+        let section = symbol
+            .section
+            .map_or(SHN_UNDEF, |section| section as u16 + 1);
+        write_symbol(
+            &mut symbol_table,
+            strings.add(&symbol.name),
+            symbol.offset,
+            binding,
+            STT_NOTYPE,
+            section,
+        );
+    }
+    let locals = object
+        .symbols
+        .iter()
+        .filter(|symbol| !is_global(symbol))
+        .count() as u32;
+
+    let symbol_index = headers.len() as u32;
+    push_table(
+        &mut result,
+        &mut headers,
+        SectionHeader {
+            name: names.add(".symtab"),
+            kind: SHT_SYMTAB,
+            link: symbol_index + 1,
+            info: locals + 1,
+            alignment: 4,
+            entry_size: SYMBOL_SIZE as u32,
+            ..SectionHeader::default()
+        },
+        &symbol_table,
+    );
+    push_table(
+        &mut result,
+        &mut headers,
+        SectionHeader {
+            name: names.add(".strtab"),
+            kind: SHT_STRTAB,
+            alignment: 1,
+            ..SectionHeader::default()
+        },
+        &strings.data,
+    );
+
+    for (index, section) in object.sections.iter().enumerate() {
+        let mut table = Vec::new();
+        for relocation in object
+            .relocations
+            .iter()
+            .filter(|relocation| relocation.section == index)
+        {
+            let kind = match relocation.kind {
+                RelocationKind::Dir32 => R_SH_DIR32,
+                RelocationKind::Rel32 => R_SH_REL32,
+                RelocationKind::Ind12W => R_SH_IND12W,
+            };
+            let info = (symbol_indices[relocation.symbol] << 8) | kind;
+            table.extend_from_slice(&relocation.offset.to_be_bytes());
+            table.extend_from_slice(&info.to_be_bytes());
+            table.extend_from_slice(&relocation.addend.to_be_bytes());
+        }
+        if table.is_empty() {
+            continue;
+        }
+
+        let name = match section.kind {
+            asm::Section::Text => ".rela.text",
+            asm::Section::RoData => ".rela.rodata",
+            asm::Section::Data => ".rela.data",
+            asm::Section::Bss => ".rela.bss",
+        };
+        push_table(
+            &mut result,
+            &mut headers,
+            SectionHeader {
+                name: names.add(name),
+                kind: SHT_RELA,
+                flags: SHF_INFO_LINK,
+                link: symbol_index,
+                info: index as u32 + 1,
+                alignment: 4,
+                entry_size: RELA_SIZE as u32,
+                ..SectionHeader::default()
+            },
+            &table,
+        );
+    }
+
+    finish(
+        &mut result,
+        headers,
+        names,
+        Header {
+            kind: TYPE_RELOCATABLE,
+            ..Header::default()
+        },
+    );
+    result
+}
+
+/// Parses a relocatable ELF-File, like the ones generated by GCC or GNU
+/// `as`. Only the Sections that are loaded into Memory are kept, every
+/// common Symbol gets its own Bss-Section
+pub fn parse_object(data: &[u8]) -> Result<Object, ObjectError> {
+    let header = Header::parse(data)?;
+    if header.kind != TYPE_RELOCATABLE {
+        return Err(ObjectError::Malformed(
+            "expected a relocatable ELF-file".to_owned(),
+        ));
+    }
+
+    let mut sections = Vec::with_capacity(header.section_headers as usize);
+    for index in 0..header.section_headers as usize {
+        sections.push(SectionHeader::parse(
+            data,
+            header.section_offset as usize + index * SECTION_HEADER_SIZE,
+        )?);
+    }
+
+    let mut result = Object::default();
+    // The Index in the Object of every Section that is kept
+    let mut section_indices = vec![None; sections.len()];
+    for (index, section) in sections.iter().enumerate() {
+        if section.flags & SHF_ALLOC == 0 {
+            continue;
+        }
+        let kind = match section.kind {
+            SHT_NOBITS => asm::Section::Bss,
+            SHT_PROGBITS | SHT_INIT_ARRAY | SHT_FINI_ARRAY => {
+                if section.flags & SHF_EXECINSTR != 0 {
+                    asm::Section::Text
+                } else if section.flags & SHF_WRITE != 0 {
+                    asm::Section::Data
+                } else {
+                    asm::Section::RoData
+                }
+            }
+            _ => continue,
+        };
+        let content = if kind == asm::Section::Bss {
+            Vec::new()
+        } else {
+            section.content(data)?.to_vec()
+        };
+
+        section_indices[index] = Some(result.sections.len());
+        result.sections.push(Section {
+            kind,
+            alignment: section.alignment.max(1),
+            size: section.size,
+            data: content,
+        });
+    }
+
+    // The Index in the Object of every Symbol that is kept
+    let mut symbol_indices = Vec::new();
+    if let Some(table) = sections.iter().find(|section| section.kind == SHT_SYMTAB) {
+        let strings = sections
+            .get(table.link as usize)
+            .ok_or_else(|| ObjectError::Malformed("missing string table".to_owned()))?
+            .content(data)?;
+        let symbol_table = table.content(data)?;
+
+        symbol_indices.push(None);
+        for index in 1..symbol_table.len() / SYMBOL_SIZE {
+            let mut reader = Reader::at(symbol_table, index * SYMBOL_SIZE);
+            let name = reader.u32()?;
+            let value = reader.u32()?;
+            let size = reader.u32()?;
+            let info = reader.u8()?;
+            let _other = reader.u8()?;
+            let section_index = reader.u16()?;
+
+            let global = info >> 4 != STB_LOCAL;
+            let (section, offset) = match section_index {
+                SHN_UNDEF if global => (None, 0),
+                SHN_COMMON => {
+                    result.sections.push(Section {
+                        kind: asm::Section::Bss,
+                        alignment: value.max(1),
+                        size,
+                        data: Vec::new(),
+                    });
+                    (Some(result.sections.len() - 1), 0)
+                }
+                SHN_UNDEF | SHN_ABS => {
+                    symbol_indices.push(None);
+                    continue;
+                }
+                index => match section_indices.get(index as usize) {
+                    Some(Some(section)) => (Some(*section), value),
+                    _ => {
+                        symbol_indices.push(None);
+                        continue;
+                    }
+                },
+            };
+            let name = if info & 0xf == STT_SECTION {
+                String::new()
+            } else {
+                read_string(strings, name)?
+            };
+
+            symbol_indices.push(Some(result.symbols.len()));
+            result.symbols.push(Symbol {
+                name,
+                global,
+                section,
+                offset,
+            });
+        }
+    }
+
+    for table in sections
+        .iter()
+        .filter(|section| section.kind == SHT_REL || section.kind == SHT_RELA)
+    {
+        let section = match section_indices.get(table.info as usize) {
+            Some(Some(section)) => *section,
+            _ => continue,
+        };
+        let entry_size = if table.kind == SHT_RELA {
+            RELA_SIZE
+        } else {
+            REL_SIZE
+        };
+        let content = table.content(data)?;
+
+        for index in 0..content.len() / entry_size {
+            let mut reader = Reader::at(content, index * entry_size);
+            let offset = reader.u32()?;
+            let info = reader.u32()?;
+            let kind = match info & 0xff {
+                R_SH_DIR32 => RelocationKind::Dir32,
+                R_SH_REL32 => RelocationKind::Rel32,
+                R_SH_IND12W if table.kind == SHT_RELA => RelocationKind::Ind12W,
+                kind if kind == R_SH_NONE || R_SH_RELAX.contains(&kind) => continue,
+                kind => {
+                    return Err(ObjectError::Malformed(format!(
+                        "unsupported relocation type {}",
+                        kind
+                    )))
+                }
+            };
+            let symbol = symbol_indices
+                .get(info as usize >> 8)
+                .copied()
+                .flatten()
+                .ok_or_else(|| {
+                    ObjectError::Malformed("relocation against an unsupported symbol".to_owned())
+                })?;
+
+            let location = result.sections[section]
+                .data
+                .get(offset as usize..offset as usize + kind.size())
+                .ok_or_else(|| {
+                    ObjectError::Malformed("relocation outside of its section".to_owned())
+                })?;
+            // Without an explicit Addend, it is stored at the Location
+            let addend = if table.kind == SHT_RELA {
+                reader.u32()? as i32
+            } else {
+                Reader::at(location, 0).u32()? as i32
+            };
+
+            result.relocations.push(Relocation {
+                section,
+                offset,
+                kind,
+                symbol,
+                addend,
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+/// Whether or not the Symbol is global in the ELF-File, undefined Symbols
+/// always have to be
+fn is_global(symbol: &Symbol) -> bool {
+    symbol.global || symbol.section.is_none()
+}
+
+fn read_string(strings: &[u8], offset: u32) -> Result<String, ObjectError> {
+    let rest = strings
+        .get(offset as usize..)
+        .ok_or_else(|| ObjectError::Malformed("invalid symbol name".to_owned()))?;
+    let end = rest.iter().position(|c| *c == 0).unwrap_or(rest.len());
+    String::from_utf8(rest[..end].to_vec())
+        .map_err(|_| ObjectError::Malformed("invalid symbol name".to_owned()))
+}
+
+fn write_symbol(table: &mut Vec<u8>, name: u32, value: u32, binding: u8, kind: u8, section: u16) {
+    table.extend_from_slice(&name.to_be_bytes());
+    table.extend_from_slice(&value.to_be_bytes());
+    table.extend_from_slice(&0u32.to_be_bytes());
+    table.push((binding << 4) | kind);
+    table.push(0);
+    table.extend_from_slice(&section.to_be_bytes());
+}
+
+/// Appends the Content of a Section that is not loaded, like the Symbol-
+/// or String-Table, and its Header
+fn push_table(
+    result: &mut Vec<u8>,
+    headers: &mut Vec<SectionHeader>,
+    mut header: SectionHeader,
+    content: &[u8],
+) {
+    let alignment = header.alignment.max(1) as usize;
+    result.resize(result.len().next_multiple_of(alignment), 0);
+    header.offset = result.len() as u32;
+    header.size = content.len() as u32;
+    result.extend_from_slice(content);
+    headers.push(header);
+}
+
+/// Appends the Section-Names and all the Section-Headers and writes the
+/// ELF-Header at the Start of the File
+fn finish(
+    result: &mut Vec<u8>,
+    mut headers: Vec<SectionHeader>,
+    mut names: StringTable,
+    mut header: Header,
+) {
+    let name = names.add(".shstrtab");
+    header.names = headers.len() as u16;
+    push_table(
+        result,
+        &mut headers,
+        SectionHeader {
+            name,
+            kind: SHT_STRTAB,
+            alignment: 1,
+            ..SectionHeader::default()
+        },
+        &names.data,
+    );
+
+    result.resize(result.len().next_multiple_of(4), 0);
+    header.section_offset = result.len() as u32;
+    header.section_headers = headers.len() as u16;
+    for section in headers.iter() {
+        section.write(result);
+    }
+
+    if header.program_headers > 0 {
+        header.program_offset = HEADER_SIZE as u32;
+    }
+    result[..HEADER_SIZE].copy_from_slice(&header.serialize());
+}
+
+/// The Strings of a String-Table, every String is terminated by a Zero
+struct StringTable {
+    data: Vec<u8>,
+}
+
+impl StringTable {
+    fn new() -> Self {
+        Self { data: vec![0] }
+    }
+
+    /// Adds the String and returns its Offset in the Table
+    fn add(&mut self, name: &str) -> u32 {
+        if name.is_empty() {
+            return 0;
+        }
+        let offset = self.data.len() as u32;
+        self.data.extend_from_slice(name.as_bytes());
+        self.data.push(0);
+        offset
+    }
+}
+
+#[derive(Debug, Default)]
+struct SectionHeader {
+    /// The Offset of the Name in the Section-Names
+    name: u32,
+    kind: u32,
+    flags: u32,
+    address: u32,
+    offset: u32,
+    size: u32,
+    link: u32,
+    info: u32,
+    alignment: u32,
+    entry_size: u32,
+}
+
+impl SectionHeader {
+    fn write(&self, result: &mut Vec<u8>) {
+        for value in [
+            self.name,
+            self.kind,
+            self.flags,
+            self.address,
+            self.offset,
+            self.size,
+            self.link,
+            self.info,
+            self.alignment,
+            self.entry_size,
+        ]
+        .iter()
+        {
+            result.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+
+    fn parse(data: &[u8], offset: usize) -> Result<Self, ObjectError> {
+        let mut reader = Reader::at(data, offset);
+        Ok(Self {
+            name: reader.u32()?,
+            kind: reader.u32()?,
+            flags: reader.u32()?,
+            address: reader.u32()?,
+            offset: reader.u32()?,
+            size: reader.u32()?,
+            link: reader.u32()?,
+            info: reader.u32()?,
+            alignment: reader.u32()?,
+            entry_size: reader.u32()?,
+        })
+    }
+
+    /// The Content of the Section in the File
+    fn content<'a>(&self, data: &'a [u8]) -> Result<&'a [u8], ObjectError> {
+        Ok(Reader::at(data, self.offset as usize).bytes(self.size as usize)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object() -> Object {
+        Object {
+            sections: vec![
+                Section {
+                    kind: asm::Section::Text,
+                    alignment: 4,
+                    size: 12,
+                    data: vec![
+                        0xb0, 0x00, 0x00, 0x09, 0x00, 0x0b, 0x00, 0x09, 0x00, 0x00, 0x00, 0x00,
+                    ],
+                },
+                Section {
+                    kind: asm::Section::Bss,
+                    alignment: 4,
+                    size: 8,
+                    data: Vec::new(),
+                },
+            ],
+            symbols: vec![
+                Symbol {
+                    name: "counter".to_owned(),
+                    global: false,
+                    section: Some(1),
+                    offset: 4,
+                },
+                Symbol {
+                    name: "main".to_owned(),
+                    global: true,
+                    section: Some(0),
+                    offset: 0,
+                },
+                Symbol {
+                    name: "helper".to_owned(),
+                    global: true,
+                    section: None,
+                    offset: 0,
+                },
+            ],
+            relocations: vec![
+                Relocation {
+                    section: 0,
+                    offset: 0,
+                    kind: RelocationKind::Ind12W,
+                    symbol: 2,
+                    addend: 0,
+                },
+                Relocation {
+                    section: 0,
+                    offset: 8,
+                    kind: RelocationKind::Dir32,
+                    symbol: 0,
+                    addend: 2,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn object_roundtrip() {
+        let object = object();
+        let serialized = write_object(&object);
+
+        assert!(Object::is_object(&serialized));
+        assert_eq!(Ok(object), Object::parse(&serialized));
+    }
+
+    #[test]
+    fn local_symbols_first() {
+        let mut object = object();
+        object.symbols.rotate_left(1);
+        for relocation in object.relocations.iter_mut() {
+            relocation.symbol = (relocation.symbol + 2) % 3;
+        }
+
+        let parsed = parse_object(&write_object(&object)).unwrap();
+
+        let names: Vec<&str> = parsed.symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(vec!["counter", "main", "helper"], names);
+        assert_eq!(2, parsed.relocations[0].symbol);
+        assert_eq!(0, parsed.relocations[1].symbol);
+    }
+
+    #[test]
+    fn executable_roundtrip() {
+        let executable = Executable {
+            entry: 0x00300000,
+            segments: vec![Segment {
+                address: 0x00300000,
+                data: vec![0x00, 0x0b, 0x00, 0x09],
+                size: 16,
+            }],
+        };
+        let mut symbols = Symbols::new();
+        symbols.insert(0x00300000, "main".to_owned());
+
+        let serialized = write_executable(&executable, &symbols);
+
+        assert_eq!(Ok(executable), Executable::parse(&serialized));
+        assert_eq!(
+            Err(ObjectError::Malformed(
+                "expected a relocatable ELF-file".to_owned()
+            )),
+            parse_object(&serialized)
+        );
+    }
+
+    #[test]
+    fn wrong_machine() {
+        let mut serialized = write_object(&object());
+        serialized[19] = 3;
+
+        assert_eq!(
+            Err(ObjectError::Malformed(
+                "expected an ELF-file for SH, found machine 3".to_owned()
+            )),
+            Object::parse(&serialized)
+        );
+    }
+}
@@ -1,6 +1,33 @@
-use std::collections::HashMap;
+use crate::{asm, pool, relax::Layout, AssembleError, BASE_ADDRESS};
 
-use crate::asm;
+/// The Register used to hold the absolute Address of a far Jump, its
+/// Value is saved on the Stack and restored in the Delay-Slot
+const SCRATCH_REGISTER: u8 = 0;
+
+/// The Size in Bytes of a far Jump: the Save of the Scratch-Register, the
+/// Load of the Address, the Jump, the Restore in its Delay-Slot and the
+/// aligned Address itself
+pub const FAR_JUMP_SIZE: u32 = 14;
+/// The Size in Bytes of a far Call, which is a far Jump that additionally
+/// branches over the Address after returning
+pub const FAR_CALL_SIZE: u32 = 18;
+/// The Size in Bytes of a far conditional Branch: the inverted Branch, the
+/// `BRA` to the Target and the Nop in its Delay-Slot
+pub const FAR_BRANCH_SIZE: u32 = 6;
+/// The Size in Bytes of a far conditional Branch whose Target is out of
+/// Range for a `BRA` as well: the inverted Branch and a far Jump
+pub const DISTANT_BRANCH_SIZE: u32 = 2 + FAR_JUMP_SIZE;
+
+/// A Location in the final ByteCode that holds the absolute Address of a
+/// Label, which has to be adjusted by the Linker when the Code is not
+/// placed at the `BASE_ADDRESS`
+#[derive(Debug, PartialEq)]
+pub struct Reference {
+    /// The Offset of the Location
+    pub offset: u32,
+    /// The Name of the Label
+    pub name: String,
+}
 
 /// A Jump-Entry that reflects a single Jump
 #[derive(Debug)]
@@ -17,53 +44,191 @@ pub enum Entry {
     Instruction(asm::Instruction),
     Jump(Jump),
     Jsr(Jump),
+    /// A Jump to a Target that is too far away for a Branch
+    FarJump(Jump),
+    /// A Call to a Target that is too far away for a Branch
+    FarJsr(Jump),
+    /// Loads the Value at the Target, in a Literal-Pool, into the Register
+    Load(u8, Jump),
+    /// A Literal-Pool with the final Values, which starts at the given
+    /// Offset
+    Pool(pool::Pool, Vec<u32>),
+    /// A relative Branch from the Input, whose Displacement has to be
+    /// adjusted to the final Layout
+    Branch(asm::Instruction, Jump),
+    /// A conditional Branch from the Input whose Target is too far away,
+    /// which is inverted to skip over a `BRA` to the Target
+    FarBranch(asm::Instruction, Jump),
+    /// A far conditional Branch whose Target is out of Range for a `BRA` as
+    /// well, which is inverted to skip over a far Jump to the Target
+    DistantBranch(asm::Instruction, Jump),
+    /// A PC-relative `MOVA` or Load from a Label, whose Displacement is
+    /// only known in the final Layout
+    PcRelative(asm::Instruction, Jump),
+}
+
+/// The Size in Bytes that the given Instruction will take up in the
+/// final ByteCode, when placed at the given Offset. Jumps to Labels are
+/// expanded into the Branch itself and the Nop in its Delay-Slot
+pub fn instr_size(instr: &asm::Instruction, offset: u32) -> u32 {
+    match instr {
+        asm::Instruction::Label(_) | asm::Instruction::Section(_) | asm::Instruction::Global(_) => {
+            0
+        }
+        asm::Instruction::JmpLabel(_)
+        | asm::Instruction::JsrLabel(_)
+        | asm::Instruction::LabelAddress(_) => 4,
+        asm::Instruction::Data(data) => data.len() as u32,
+        asm::Instruction::Space(size) => *size,
+        asm::Instruction::Align(alignment) => (alignment - offset % alignment) % alignment,
+        _ => 2,
+    }
 }
 
 /// Converts the given Instructions into a List of Entries that will be used for the
-/// Rest of the Assembling Stages
-pub fn to_entry_list(instr: &[asm::Instruction], targets: &HashMap<String, u32>) -> Vec<Entry> {
+/// Rest of the Assembling Stages, together with all the Locations that hold
+/// the absolute Address of a Label. Labels that are not defined are placed
+/// at Offset 0, their Address is only filled in by the Linker
+pub fn to_entry_list(instr: &[asm::Instruction], layout: &Layout) -> (Vec<Entry>, Vec<Reference>) {
     let mut result = Vec::new();
+    let mut references = Vec::new();
+
+    let target = |name: &String| layout.labels.get(name).copied().unwrap_or(0);
 
-    for tmp in instr {
+    for (index, tmp) in instr.iter().enumerate() {
+        let current = layout.offsets[index];
         match tmp {
-            asm::Instruction::Label(_) => {}
+            asm::Instruction::Label(_) | asm::Instruction::Global(_) => {}
             asm::Instruction::JmpLabel(name) => {
-                let current = result.len() as u32;
-                let target = *targets.get(name).unwrap();
-                result.push(Entry::Jump(Jump {
-                    start: current * 2,
-                    target,
-                }));
+                let jump = Jump {
+                    start: current,
+                    target: target(name),
+                };
+                match layout.veneers.get(&index) {
+                    Some(veneer) => result.push(Entry::Jump(Jump {
+                        start: current,
+                        target: *veneer,
+                    })),
+                    None if layout.far.contains(&index) => {
+                        references.push(Reference {
+                            offset: far_literal(current, false),
+                            name: name.clone(),
+                        });
+                        result.push(Entry::FarJump(jump));
+                    }
+                    None => result.push(Entry::Jump(jump)),
+                };
             }
             asm::Instruction::JsrLabel(name) => {
-                let current = result.len() as u32;
-                let target = *targets.get(name).unwrap();
-                result.push(Entry::Jsr(Jump {
-                    start: current * 2,
-                    target,
-                }));
+                let jump = Jump {
+                    start: current,
+                    target: target(name),
+                };
+                if layout.far.contains(&index) {
+                    references.push(Reference {
+                        offset: far_literal(current, true),
+                        name: name.clone(),
+                    });
+                    result.push(Entry::FarJsr(jump));
+                } else {
+                    result.push(Entry::Jsr(jump));
+                }
+            }
+            asm::Instruction::LabelAddress(name) => {
+                references.push(Reference {
+                    offset: current,
+                    name: name.clone(),
+                });
+                let address = BASE_ADDRESS + target(name);
+                result.push(Entry::Instruction(asm::Instruction::Data(
+                    address.to_be_bytes().to_vec(),
+                )));
+            }
+            asm::Instruction::MovALabel(name)
+            | asm::Instruction::LoadWord(_, name)
+            | asm::Instruction::LoadLong(_, name) => {
+                result.push(Entry::PcRelative(
+                    tmp.to_owned(),
+                    Jump {
+                        start: current,
+                        target: target(name),
+                    },
+                ));
             }
-            _ => {
-                result.push(Entry::Instruction(tmp.to_owned()));
+            asm::Instruction::MovLabel(register, _) | asm::Instruction::MovConst(register, _) => {
+                result.push(Entry::Load(
+                    *register,
+                    Jump {
+                        start: current,
+                        target: layout.loads[&index],
+                    },
+                ));
             }
+            _ => match layout.branches.get(&index) {
+                Some(branch_target) => {
+                    let jump = Jump {
+                        start: current,
+                        target: layout.offsets[*branch_target],
+                    };
+                    if layout.distant.contains(&index) {
+                        result.push(Entry::DistantBranch(tmp.to_owned(), jump));
+                    } else if layout.far.contains(&index) {
+                        result.push(Entry::FarBranch(tmp.to_owned(), jump));
+                    } else {
+                        result.push(Entry::Branch(tmp.to_owned(), jump));
+                    }
+                }
+                None => result.push(Entry::Instruction(tmp.to_owned())),
+            },
         }
-    }
 
-    result
-}
-
-/// Moves all the affected entries by the given Offset
-pub fn move_entries(entries: &mut Vec<Entry>, start: usize, offset: u32) {
-    for entrie in entries.iter_mut() {
-        if let Entry::Jump(tmp) = entrie {
-            if tmp.start > start as u32 {
-                tmp.start += offset;
+        // The Veneers for the far Entries come right after their Table
+        if let Some(table) = layout.tables.iter().find(|table| table.end == index + 1) {
+            for entry in table.clone() {
+                if let (Some(veneer), asm::Instruction::JmpLabel(name)) =
+                    (layout.veneers.get(&entry), &instr[entry])
+                {
+                    references.push(Reference {
+                        offset: far_literal(*veneer, false),
+                        name: name.clone(),
+                    });
+                    result.push(Entry::FarJump(Jump {
+                        start: *veneer,
+                        target: target(name),
+                    }));
+                }
             }
-            if tmp.target > start as u32 {
-                tmp.target += offset;
+        }
+
+        if let Some(placed) = layout.pools.get(&index) {
+            for (position, value) in placed.values.iter().enumerate() {
+                if let pool::Value::Address(name) = value {
+                    references.push(Reference {
+                        offset: placed.values_start() + position as u32 * 4,
+                        name: name.clone(),
+                    });
+                }
             }
+            let values = placed
+                .values
+                .iter()
+                .map(|value| match value {
+                    pool::Value::Constant(value) => *value,
+                    pool::Value::Address(name) => BASE_ADDRESS + target(name),
+                })
+                .collect();
+            result.push(Entry::Pool(placed.clone(), values));
         }
     }
+
+    (result, references)
+}
+
+/// The Offset of the absolute Address in a far Jump or Call at the given
+/// Offset, which comes after the Jump itself and is 4-Byte aligned
+fn far_literal(start: u32, call: bool) -> u32 {
+    let literal = if call { start + 12 } else { start + 8 };
+    (literal + 3) & !3
 }
 
 /// Calculates the Distant that a Jump spans over
@@ -79,18 +244,9 @@ fn calc_delta(jmp: Jump) -> u16 {
     }
 }
 
-const SMALL_JUMP_OFFSET: u32 = 2;
-pub fn entries_to_asm(mut entries: Vec<Entry>) -> Vec<asm::Instruction> {
-    let length = entries.len();
-    let mut offset = 0;
-    for index in 0..length {
-        if let Entry::Jump(_) = entries.get(index).unwrap() {
-            move_entries(&mut entries, offset, SMALL_JUMP_OFFSET);
-            offset += 2;
-        }
-        offset += 2;
-    }
-
+/// Generates the final Instructions for the Entries, which fails if a
+/// relative Branch from the Input can not reach its Target
+pub fn entries_to_asm(mut entries: Vec<Entry>) -> Result<Vec<asm::Instruction>, AssembleError> {
     let mut result = Vec::new();
     for tmp in entries.drain(..) {
         match tmp {
@@ -104,11 +260,202 @@ pub fn entries_to_asm(mut entries: Vec<Entry>) -> Vec<asm::Instruction> {
                 result.push(asm::Instruction::BSR(delta));
                 result.push(asm::Instruction::Nop);
             }
+            Entry::FarJump(jmp) => {
+                result.extend(far_jump(jmp, false));
+            }
+            Entry::FarJsr(jmp) => {
+                result.extend(far_jump(jmp, true));
+            }
+            Entry::Branch(instr, jmp) => {
+                result.push(retarget(instr, jmp)?);
+            }
+            Entry::DistantBranch(instr, jmp) => {
+                // The far Jump always has the same Size, wherever it starts
+                let skip = ((FAR_JUMP_SIZE - 2) / 2) as u8;
+                result.push(match instr {
+                    asm::Instruction::BT(_) => asm::Instruction::BF(skip),
+                    _ => asm::Instruction::BT(skip),
+                });
+                result.extend(relative_far_jump(Jump {
+                    start: jmp.start + 2,
+                    target: jmp.target,
+                }));
+            }
+            Entry::FarBranch(instr, jmp) => {
+                let inverted = match instr {
+                    asm::Instruction::BT(_) => asm::Instruction::BF(1),
+                    _ => asm::Instruction::BT(1),
+                };
+                let bra = Jump {
+                    start: jmp.start + 2,
+                    target: jmp.target,
+                };
+                result.push(inverted);
+                result.push(retarget(asm::Instruction::BRA(0), bra)?);
+                result.push(asm::Instruction::Nop);
+            }
+            Entry::PcRelative(instr, jmp) => {
+                result.push(pc_relative(instr, jmp)?);
+            }
+            Entry::Load(register, jmp) => {
+                let disp = (jmp.target - ((jmp.start & !3) + 4)) / 4;
+                result.push(asm::Instruction::MovL(
+                    asm::Operand::Register(register),
+                    asm::Operand::Displacement8(disp as u8),
+                ));
+            }
+            Entry::Pool(placed, values) => {
+                if placed.island {
+                    let delta = calc_delta(Jump {
+                        start: placed.start,
+                        target: placed.end(),
+                    });
+                    result.push(asm::Instruction::BRA(delta));
+                    result.push(asm::Instruction::Nop);
+                }
+                // The Values are aligned to 4 Bytes
+                let branch_end = if placed.island {
+                    placed.start + pool::ISLAND_BRANCH_SIZE
+                } else {
+                    placed.start
+                };
+                if placed.values_start() != branch_end {
+                    result.push(asm::Instruction::Nop);
+                }
+                for value in values {
+                    let bytes = value.to_be_bytes();
+                    result.push(asm::Instruction::Literal(bytes[0], bytes[1]));
+                    result.push(asm::Instruction::Literal(bytes[2], bytes[3]));
+                }
+            }
             Entry::Instruction(instr) => {
                 result.push(instr);
             }
         };
     }
 
+    Ok(result)
+}
+
+/// Generates a Jump or Call to an absolute Address, which is loaded from
+/// the next 4-Byte aligned Location after the Jump
+fn far_jump(jmp: Jump, call: bool) -> Vec<asm::Instruction> {
+    // The Address is either right after the Jump or after the Branch that
+    // skips over it after returning from a Call
+    let disp = if call { 2 } else { 1 };
+    let mut result = vec![
+        asm::Instruction::Push(SCRATCH_REGISTER),
+        asm::Instruction::MovL(
+            asm::Operand::Register(SCRATCH_REGISTER),
+            asm::Operand::Displacement8(disp),
+        ),
+    ];
+    if call {
+        result.push(asm::Instruction::Jsr(SCRATCH_REGISTER));
+    } else {
+        result.push(asm::Instruction::Jmp(SCRATCH_REGISTER));
+    }
+    // The Target-Address is already read, so the Register can be restored
+    // in the Delay-Slot
+    result.push(asm::Instruction::Pop(SCRATCH_REGISTER));
+    if call {
+        result.push(asm::Instruction::BRA(3));
+        result.push(asm::Instruction::Nop);
+    }
+
+    append_literal(&mut result, jmp.start, BASE_ADDRESS + jmp.target);
+    result
+}
+
+/// Generates a Jump to the Target just like `far_jump`, but loads the
+/// Offset to the Target for a `BRAF` instead of its absolute Address. The
+/// Jump therefore works wherever the Code is placed and is used for the
+/// Targets of conditional Branches, which have no Label for the Linker
+fn relative_far_jump(jmp: Jump) -> Vec<asm::Instruction> {
+    let mut result = vec![
+        asm::Instruction::Push(SCRATCH_REGISTER),
+        asm::Instruction::MovL(
+            asm::Operand::Register(SCRATCH_REGISTER),
+            asm::Operand::Displacement8(1),
+        ),
+        asm::Instruction::Braf(SCRATCH_REGISTER),
+        asm::Instruction::Pop(SCRATCH_REGISTER),
+    ];
+
+    // The Offset is relative to the Address after the Delay-Slot of the
+    // `BRAF`
+    let offset = jmp.target.wrapping_sub(jmp.start + 8);
+    append_literal(&mut result, jmp.start, offset);
     result
 }
+
+/// Appends the Value after the Jump starting at the given Offset, aligned
+/// to 4 Bytes with a Nop either in front of or after it
+fn append_literal(result: &mut Vec<asm::Instruction>, start: u32, value: u32) {
+    let bytes = value.to_be_bytes();
+    let literal = [
+        asm::Instruction::Literal(bytes[0], bytes[1]),
+        asm::Instruction::Literal(bytes[2], bytes[3]),
+    ];
+
+    if start % 4 == 2 {
+        result.push(asm::Instruction::Nop);
+        result.extend_from_slice(&literal);
+    } else {
+        result.extend_from_slice(&literal);
+        result.push(asm::Instruction::Nop);
+    }
+}
+
+/// Encodes the PC-relative Instruction with the Displacement to its Label
+/// in the final Layout. The Displacement is unsigned and scaled by the Size
+/// of the Access, so the Label has to come after the Instruction and be
+/// aligned to that Size
+fn pc_relative(instr: asm::Instruction, jmp: Jump) -> Result<asm::Instruction, AssembleError> {
+    let (size, base) = match instr {
+        asm::Instruction::LoadWord(_, _) => (2, jmp.start + 4),
+        _ => (4, (jmp.start & !3) + 4),
+    };
+    let delta = jmp.target as i64 - base as i64;
+    if delta % size != 0 || !(0..=255 * size).contains(&delta) {
+        return Err(AssembleError::LabelOutOfRange(instr));
+    }
+    let disp = (delta / size) as u8;
+
+    Ok(match instr {
+        asm::Instruction::LoadWord(register, _) => asm::Instruction::MovW(
+            asm::Operand::Register(register),
+            asm::Operand::Displacement8(disp),
+        ),
+        asm::Instruction::LoadLong(register, _) => asm::Instruction::MovL(
+            asm::Operand::Register(register),
+            asm::Operand::Displacement8(disp),
+        ),
+        _ => asm::Instruction::MovA(disp),
+    })
+}
+
+/// Encodes the Branch with the Displacement to its Target in the final
+/// Layout, which fails if the Displacement does not fit into the Branch
+fn retarget(instr: asm::Instruction, jmp: Jump) -> Result<asm::Instruction, AssembleError> {
+    let delta = (jmp.target as i64 - (jmp.start as i64 + 4)) / 2;
+
+    let range = match instr {
+        asm::Instruction::BRA(_) | asm::Instruction::BSR(_) => -2048..=2047,
+        _ => -128..=127,
+    };
+    if !range.contains(&delta) {
+        return Err(AssembleError::BranchOutOfRange(instr));
+    }
+    let short = delta as i8 as u8;
+
+    Ok(match instr {
+        asm::Instruction::BT(_) => asm::Instruction::BT(short),
+        asm::Instruction::BTs(_) => asm::Instruction::BTs(short),
+        asm::Instruction::BF(_) => asm::Instruction::BF(short),
+        asm::Instruction::BFs(_) => asm::Instruction::BFs(short),
+        asm::Instruction::BRA(_) => asm::Instruction::BRA(calc_delta(jmp)),
+        asm::Instruction::BSR(_) => asm::Instruction::BSR(calc_delta(jmp)),
+        other => other,
+    })
+}
@@ -1,20 +1,210 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
 use sh::asm;
 
+pub mod archive;
 mod convert;
+pub mod disassemble;
+pub mod elf;
 mod entry;
-mod find_labels;
+pub mod link;
+pub mod object;
+pub mod parser;
+mod pool;
+mod relax;
+mod sections;
+pub mod symbols;
+
+/// The Address at which the ByteCode is mapped on the Calculator, which is
+/// needed to calculate the absolute Address of a Label
+pub const BASE_ADDRESS: u32 = 0x00300000;
+
+/// The minimum Alignment of every Section in an Object
+const SECTION_ALIGNMENT: u32 = 4;
+
+/// The Reasons why Instructions can not be assembled
+#[derive(Debug, PartialEq)]
+pub enum AssembleError {
+    /// The Instruction can not be encoded, because it is not valid SH
+    Encode {
+        instr: asm::Instruction,
+        error: asm::EncodeError,
+    },
+    /// The Label is used, but never defined
+    UnknownLabel(String),
+    /// The relative Branch can not reach its Target in the final Layout
+    BranchOutOfRange(asm::Instruction),
+    /// The PC-relative Instruction can not reach its Label in the final
+    /// Layout, or the Label is not aligned to the Size of the Access
+    LabelOutOfRange(asm::Instruction),
+}
+
+impl fmt::Display for AssembleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Encode { instr, error } => write!(f, "can not encode `{}`: {}", instr, error),
+            Self::UnknownLabel(name) => write!(f, "undefined label `{}`", name),
+            Self::BranchOutOfRange(instr) => {
+                write!(f, "branch `{}` can not reach its target", instr)
+            }
+            Self::LabelOutOfRange(instr) => write!(
+                f,
+                "`{}` can not reach its label, which has to be aligned and close after it",
+                instr
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AssembleError {}
 
 /// Assembles the final Instructions generated by the Backend of the
 /// Compiler and generates the final ByteCode that can be executed
 /// on the Calculator
-pub fn assemble(instr: Vec<asm::Instruction>) -> Vec<u8> {
-    let targets = find_labels::find(&instr);
+pub fn assemble(instr: Vec<asm::Instruction>) -> Result<Vec<u8>, AssembleError> {
+    assemble_with_symbols(instr).map(|(code, _)| code)
+}
+
+/// Assembles the Instructions just like `assemble`, but also returns the
+/// absolute Address of every Label
+pub fn assemble_with_symbols(
+    instr: Vec<asm::Instruction>,
+) -> Result<(Vec<u8>, symbols::Symbols), AssembleError> {
+    let instr = sections::order(instr);
+
+    let layout = relax::relax(&instr);
+
+    let mut symbols = symbols::Symbols::new();
+    for (name, offset) in layout.labels.iter() {
+        symbols.insert(BASE_ADDRESS + offset, name.clone());
+    }
 
-    let entries = entry::to_entry_list(&instr, &targets);
+    let (entries, references) = entry::to_entry_list(&instr, &layout);
+    // Only an Object can refer to Labels that are defined somewhere else
+    if let Some(unknown) = references
+        .iter()
+        .find(|reference| !layout.labels.contains_key(&reference.name))
+    {
+        return Err(AssembleError::UnknownLabel(unknown.name.clone()));
+    }
 
-    let generated = entry::entries_to_asm(entries);
+    let generated = entry::entries_to_asm(entries)?;
 
-    convert::to_bytes(generated)
+    Ok((convert::to_bytes(generated)?, symbols))
+}
+
+/// Assembles the Instructions into a relocatable Object, that is combined
+/// with other Objects by the Linker. Labels that are used but not defined
+/// are expected to be defined by another Object, and only the Labels that
+/// are marked with `Global` can be used by other Objects
+pub fn assemble_object(instr: Vec<asm::Instruction>) -> Result<object::Object, AssembleError> {
+    let globals: HashSet<String> = instr
+        .iter()
+        .filter_map(|tmp| match tmp {
+            asm::Instruction::Global(name) => Some(name.clone()),
+            _ => None,
+        })
+        .collect();
+
+    // Every Section starts aligned, so its Content stays aligned wherever
+    // the Linker places it
+    let ordered = sections::order(instr);
+    let mut kinds = vec![asm::Section::Text];
+    let mut alignments = vec![SECTION_ALIGNMENT];
+    for tmp in ordered.iter() {
+        match tmp {
+            asm::Instruction::Section(section) => {
+                kinds.push(section.clone());
+                alignments.push(SECTION_ALIGNMENT);
+            }
+            asm::Instruction::Align(alignment) => {
+                let current = alignments.last_mut().unwrap();
+                *current = (*current).max(alignment.next_power_of_two());
+            }
+            _ => {}
+        };
+    }
+    let mut instr = Vec::with_capacity(ordered.len() + kinds.len());
+    let mut markers = Vec::with_capacity(kinds.len());
+    for tmp in ordered {
+        if let asm::Instruction::Section(_) = tmp {
+            instr.push(asm::Instruction::Align(alignments[markers.len() + 1]));
+            markers.push(instr.len());
+        }
+        instr.push(tmp);
+    }
+
+    let layout = relax::relax(&instr);
+    let end = *layout.offsets.last().unwrap();
+    let starts: Vec<u32> = std::iter::once(0)
+        .chain(markers.iter().map(|index| layout.offsets[*index]))
+        .collect();
+    let ends: Vec<u32> = starts.iter().skip(1).copied().chain(Some(end)).collect();
+
+    let (entries, references) = entry::to_entry_list(&instr, &layout);
+    let code = convert::to_bytes(entry::entries_to_asm(entries)?)?;
+
+    let mut result = object::Object::default();
+    for (index, kind) in kinds.into_iter().enumerate() {
+        let (start, end) = (starts[index], ends[index]);
+        let data = if kind == asm::Section::Bss {
+            Vec::new()
+        } else {
+            code[start as usize..end as usize].to_vec()
+        };
+        result.sections.push(object::Section {
+            kind,
+            alignment: alignments[index],
+            size: end - start,
+            data,
+        });
+    }
+
+    let mut symbols = HashMap::new();
+    let mut section = 0;
+    for (index, tmp) in instr.iter().enumerate() {
+        match tmp {
+            asm::Instruction::Section(_) => section += 1,
+            asm::Instruction::Label(name) => {
+                symbols.insert(name.clone(), result.symbols.len());
+                result.symbols.push(object::Symbol {
+                    name: name.clone(),
+                    global: globals.contains(name),
+                    section: Some(section),
+                    offset: layout.offsets[index] - starts[section],
+                });
+            }
+            _ => {}
+        };
+    }
+
+    for reference in references {
+        let symbol = *symbols.entry(reference.name.clone()).or_insert_with(|| {
+            result.symbols.push(object::Symbol {
+                name: reference.name.clone(),
+                global: true,
+                section: None,
+                offset: 0,
+            });
+            result.symbols.len() - 1
+        });
+        let section = ends
+            .iter()
+            .position(|end| reference.offset < *end)
+            .expect("References are always in the Text-Section");
+        result.relocations.push(object::Relocation {
+            section,
+            offset: reference.offset - starts[section],
+            kind: object::RelocationKind::Dir32,
+            symbol,
+            addend: 0,
+        });
+    }
+
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -46,7 +236,7 @@ mod tests {
             asm::Instruction::Nop.to_byte(),
         ];
 
-        assert_eq!(to_u8(expected), assemble(input));
+        assert_eq!(Ok(to_u8(expected)), assemble(input));
     }
 
     #[test]
@@ -69,7 +259,7 @@ mod tests {
             asm::Instruction::Nop.to_byte(),
         ];
 
-        assert_eq!(to_u8(expected), assemble(input));
+        assert_eq!(Ok(to_u8(expected)), assemble(input));
     }
 
     #[test]
@@ -96,6 +286,438 @@ mod tests {
             asm::Instruction::Nop.to_byte(),
         ];
 
-        assert_eq!(to_u8(expected), assemble(input));
+        assert_eq!(Ok(to_u8(expected)), assemble(input));
+    }
+
+    #[test]
+    fn subroutine_after_jump() {
+        let input = vec![
+            asm::Instruction::JmpLabel("main".to_owned()),
+            asm::Instruction::Label("func".to_owned()),
+            asm::Instruction::Rts,
+            asm::Instruction::Nop,
+            asm::Instruction::Label("main".to_owned()),
+            asm::Instruction::JsrLabel("func".to_owned()),
+        ];
+
+        let expected = vec![
+            asm::Instruction::BRA(2).to_byte(),
+            asm::Instruction::Nop.to_byte(),
+            asm::Instruction::Rts.to_byte(),
+            asm::Instruction::Nop.to_byte(),
+            asm::Instruction::BSR((0x4 ^ 0xffff) + 1).to_byte(),
+            asm::Instruction::Nop.to_byte(),
+        ];
+
+        assert_eq!(Ok(to_u8(expected)), assemble(input));
+    }
+
+    #[test]
+    fn constant_pool_after_return() {
+        let input = vec![
+            asm::Instruction::MovConst(0, 0xac000000),
+            asm::Instruction::MovConst(1, 0x12345678),
+            asm::Instruction::MovConst(2, 0xac000000),
+            asm::Instruction::Rts,
+            asm::Instruction::Nop,
+            asm::Instruction::Label("next".to_owned()),
+            asm::Instruction::JmpLabel("next".to_owned()),
+        ];
+
+        let expected = vec![
+            asm::Instruction::MovL(asm::Operand::Register(0), asm::Operand::Displacement8(2))
+                .to_byte(),
+            asm::Instruction::MovL(asm::Operand::Register(1), asm::Operand::Displacement8(3))
+                .to_byte(),
+            asm::Instruction::MovL(asm::Operand::Register(2), asm::Operand::Displacement8(1))
+                .to_byte(),
+            asm::Instruction::Rts.to_byte(),
+            asm::Instruction::Nop.to_byte(),
+            // Padding to align the Pool, which is shared by equal Values
+            asm::Instruction::Nop.to_byte(),
+            [0xac, 0x00],
+            [0x00, 0x00],
+            [0x12, 0x34],
+            [0x56, 0x78],
+            asm::Instruction::BRA((0x2 ^ 0xffff) + 1).to_byte(),
+            asm::Instruction::Nop.to_byte(),
+        ];
+
+        assert_eq!(Ok(to_u8(expected)), assemble(input));
+    }
+
+    #[test]
+    fn constant_pool_island() {
+        // Without any unconditional Jump the Pool has to be placed in the
+        // Code, with a Branch over it, before the Load goes out of Range
+        let mut input = vec![asm::Instruction::MovConst(0, 0x12345678)];
+        input.resize(601, asm::Instruction::Nop);
+
+        let result = assemble(input).unwrap();
+
+        let load = asm::Instruction::parse(u16::from_be_bytes([result[0], result[1]]));
+        let disp = match load {
+            asm::Instruction::MovL(
+                asm::Operand::Register(0),
+                asm::Operand::Displacement8(disp),
+            ) => disp,
+            other => panic!("Expected a Load but got {:?}", other),
+        };
+        let value_offset = 4 + disp as usize * 4;
+        assert_eq!(
+            [0x12, 0x34, 0x56, 0x78],
+            result[value_offset..value_offset + 4]
+        );
+
+        // The Branch skips over the Pool
+        let branch_offset = value_offset - 4;
+        assert_eq!(
+            asm::Instruction::BRA(2).to_byte(),
+            [result[branch_offset], result[branch_offset + 1]]
+        );
+        assert_eq!(2 + 600 * 2 + 8, result.len());
+    }
+
+    #[test]
+    fn branch_over_pool() {
+        let input = vec![
+            asm::Instruction::MovConst(0, 0x12345678),
+            asm::Instruction::BF(1),
+            asm::Instruction::JmpLabel("end".to_owned()),
+            asm::Instruction::Nop,
+            asm::Instruction::Label("end".to_owned()),
+            asm::Instruction::Rts,
+            asm::Instruction::Nop,
+        ];
+
+        let expected = vec![
+            asm::Instruction::MovL(asm::Operand::Register(0), asm::Operand::Displacement8(1))
+                .to_byte(),
+            // Skips the Jump and the Pool after it
+            asm::Instruction::BF(3).to_byte(),
+            asm::Instruction::BRA(3).to_byte(),
+            asm::Instruction::Nop.to_byte(),
+            [0x12, 0x34],
+            [0x56, 0x78],
+            asm::Instruction::Nop.to_byte(),
+            asm::Instruction::Rts.to_byte(),
+            asm::Instruction::Nop.to_byte(),
+        ];
+
+        assert_eq!(Ok(to_u8(expected)), assemble(input));
+    }
+
+    #[test]
+    fn object() {
+        let input = vec![
+            asm::Instruction::Global("main".to_owned()),
+            asm::Instruction::Label("main".to_owned()),
+            asm::Instruction::MovLabel(4, "message".to_owned()),
+            asm::Instruction::JsrLabel("puts".to_owned()),
+            asm::Instruction::Rts,
+            asm::Instruction::Nop,
+            asm::Instruction::Section(asm::Section::RoData),
+            asm::Instruction::Label("message".to_owned()),
+            asm::Instruction::Data(vec![b'h', b'i', 0]),
+            asm::Instruction::Section(asm::Section::Bss),
+            asm::Instruction::Label("buffer".to_owned()),
+            asm::Instruction::Space(8),
+        ];
+
+        let result = assemble_object(input).unwrap();
+
+        let sections: Vec<(asm::Section, u32)> = result
+            .sections
+            .iter()
+            .map(|section| (section.kind.clone(), section.size))
+            .collect();
+        // The Sections are padded, so the next one starts aligned
+        assert_eq!(
+            vec![
+                (asm::Section::Text, 28),
+                (asm::Section::RoData, 4),
+                (asm::Section::Data, 0),
+                (asm::Section::Bss, 8),
+            ],
+            sections
+        );
+        assert_eq!(vec![b'h', b'i', 0, 0], result.sections[1].data);
+
+        let symbol = |name: &str, global: bool, section: Option<usize>| object::Symbol {
+            name: name.to_owned(),
+            global,
+            section,
+            offset: 0,
+        };
+        assert_eq!(
+            vec![
+                symbol("main", true, Some(0)),
+                symbol("message", false, Some(1)),
+                symbol("buffer", false, Some(3)),
+                symbol("puts", true, None),
+            ],
+            result.symbols
+        );
+
+        // The undefined Function is always called with a far Call and
+        // the Address of the Message is loaded from the Pool
+        let relocation = |offset: u32, symbol: usize| object::Relocation {
+            section: 0,
+            offset,
+            kind: object::RelocationKind::Dir32,
+            symbol,
+            addend: 0,
+        };
+        assert_eq!(
+            vec![relocation(16, 3), relocation(24, 1)],
+            result.relocations
+        );
+    }
+
+    #[test]
+    fn far_jump() {
+        let input = vec![
+            asm::Instruction::JmpLabel("end".to_owned()),
+            asm::Instruction::Space(5000),
+            asm::Instruction::Label("end".to_owned()),
+            asm::Instruction::Nop,
+        ];
+
+        let mut expected = to_u8(vec![
+            asm::Instruction::Push(0).to_byte(),
+            asm::Instruction::MovL(asm::Operand::Register(0), asm::Operand::Displacement8(1))
+                .to_byte(),
+            asm::Instruction::Jmp(0).to_byte(),
+            asm::Instruction::Pop(0).to_byte(),
+            // The Address of the Target after the Jump and the Space
+            [0x00, 0x30],
+            [0x13, 0x96],
+            asm::Instruction::Nop.to_byte(),
+        ]);
+        expected.resize(expected.len() + 5000, 0);
+        expected.extend_from_slice(&asm::Instruction::Nop.to_byte());
+
+        assert_eq!(Ok(expected), assemble(input));
+    }
+
+    #[test]
+    fn far_call() {
+        let input = vec![
+            asm::Instruction::Nop,
+            asm::Instruction::JsrLabel("func".to_owned()),
+            asm::Instruction::Space(5000),
+            asm::Instruction::Label("func".to_owned()),
+            asm::Instruction::Rts,
+        ];
+
+        let mut expected = to_u8(vec![
+            asm::Instruction::Nop.to_byte(),
+            asm::Instruction::Push(0).to_byte(),
+            asm::Instruction::MovL(asm::Operand::Register(0), asm::Operand::Displacement8(2))
+                .to_byte(),
+            asm::Instruction::Jsr(0).to_byte(),
+            asm::Instruction::Pop(0).to_byte(),
+            // Skip over the Address after returning
+            asm::Instruction::BRA(3).to_byte(),
+            asm::Instruction::Nop.to_byte(),
+            // Padding to align the Address
+            asm::Instruction::Nop.to_byte(),
+            [0x00, 0x30],
+            [0x13, 0x9c],
+        ]);
+        expected.resize(expected.len() + 5000, 0);
+        expected.extend_from_slice(&asm::Instruction::Rts.to_byte());
+
+        assert_eq!(Ok(expected), assemble(input));
+    }
+
+    #[test]
+    fn branch_over_far_jump() {
+        let input = vec![
+            asm::Instruction::BF(1),
+            asm::Instruction::JmpLabel("end".to_owned()),
+            asm::Instruction::Nop,
+            asm::Instruction::Space(5000),
+            asm::Instruction::Label("end".to_owned()),
+        ];
+
+        let result = assemble(input).unwrap();
+
+        // The Branch still skips the entire Jump, which grew to 14 Bytes
+        assert_eq!(asm::Instruction::BF(6).to_byte(), [result[0], result[1]]);
+        assert_eq!(asm::Instruction::Nop.to_byte(), [result[16], result[17]]);
+    }
+
+    #[test]
+    fn far_jump_table_entry() {
+        let input = vec![
+            asm::Instruction::Braf(0),
+            asm::Instruction::Nop,
+            asm::Instruction::JmpLabel("near".to_owned()),
+            asm::Instruction::JmpLabel("far".to_owned()),
+            asm::Instruction::Label("near".to_owned()),
+            asm::Instruction::Space(5000),
+            asm::Instruction::Label("far".to_owned()),
+        ];
+
+        let result = assemble(input).unwrap();
+
+        let expected_table = to_u8(vec![
+            asm::Instruction::Braf(0).to_byte(),
+            asm::Instruction::Nop.to_byte(),
+            // The near Entry skips the Veneer of the far one
+            asm::Instruction::BRA(9).to_byte(),
+            asm::Instruction::Nop.to_byte(),
+            // The far Entry keeps its Size and goes to the Veneer
+            asm::Instruction::BRA(0).to_byte(),
+            asm::Instruction::Nop.to_byte(),
+            asm::Instruction::Push(0).to_byte(),
+        ]);
+        assert_eq!(expected_table, result[..14]);
+    }
+
+    #[test]
+    fn data_after_code() {
+        let input = vec![
+            asm::Instruction::Section(asm::Section::Data),
+            asm::Instruction::Align(4),
+            asm::Instruction::Label("value".to_owned()),
+            asm::Instruction::Data(vec![0x12, 0x34, 0x56, 0x78]),
+            asm::Instruction::Section(asm::Section::Bss),
+            asm::Instruction::Label("zeroed".to_owned()),
+            asm::Instruction::Space(4),
+            asm::Instruction::Section(asm::Section::Text),
+            asm::Instruction::Nop,
+            asm::Instruction::MovLabel(1, "value".to_owned()),
+            asm::Instruction::Nop,
+            asm::Instruction::MovLabel(2, "zeroed".to_owned()),
+        ];
+
+        let mut expected = to_u8(vec![
+            asm::Instruction::Nop.to_byte(),
+            asm::Instruction::MovL(asm::Operand::Register(1), asm::Operand::Displacement8(1))
+                .to_byte(),
+            asm::Instruction::Nop.to_byte(),
+            asm::Instruction::MovL(asm::Operand::Register(2), asm::Operand::Displacement8(1))
+                .to_byte(),
+            // The Addresses are placed in a Pool at the End of the Code
+            [0x00, 0x30],
+            [0x00, 0x10],
+            [0x00, 0x30],
+            [0x00, 0x14],
+        ]);
+        // The Data is already aligned and the Bss-Section is not part
+        // of the ByteCode
+        expected.extend_from_slice(&[0x12, 0x34, 0x56, 0x78]);
+
+        assert_eq!(Ok(expected), assemble(input));
+    }
+
+    #[test]
+    fn label_address() {
+        let input = vec![
+            asm::Instruction::Rts,
+            asm::Instruction::Nop,
+            asm::Instruction::Section(asm::Section::Data),
+            asm::Instruction::Align(4),
+            asm::Instruction::Label("table".to_owned()),
+            asm::Instruction::LabelAddress("value".to_owned()),
+            asm::Instruction::Label("value".to_owned()),
+            asm::Instruction::Data(vec![0x12, 0x34, 0x56, 0x78]),
+        ];
+
+        let mut expected = to_u8(vec![
+            asm::Instruction::Rts.to_byte(),
+            asm::Instruction::Nop.to_byte(),
+        ]);
+        expected.extend_from_slice(&[0x00, 0x30, 0x00, 0x08, 0x12, 0x34, 0x56, 0x78]);
+
+        assert_eq!(Ok(expected), assemble(input.clone()));
+
+        // The Linker fills in the Address once the Data-Section is placed
+        let object = assemble_object(input).unwrap();
+        assert_eq!(
+            vec![object::Relocation {
+                section: 2,
+                offset: 0,
+                kind: object::RelocationKind::Dir32,
+                symbol: 1,
+                addend: 0,
+            }],
+            object.relocations
+        );
+    }
+
+    #[test]
+    fn unknown_label() {
+        let input = vec![
+            asm::Instruction::Nop,
+            asm::Instruction::JmpLabel("missing".to_owned()),
+        ];
+
+        assert_eq!(
+            Err(AssembleError::UnknownLabel("missing".to_owned())),
+            assemble(input)
+        );
+    }
+
+    #[test]
+    fn far_conditional_branch() {
+        // The far Jump pushes the Target of the Branch out of its Range
+        let input = |branch| {
+            vec![
+                branch,
+                asm::Instruction::JmpLabel("end".to_owned()),
+                asm::Instruction::Space(248),
+                asm::Instruction::Nop,
+                asm::Instruction::Space(5000),
+                asm::Instruction::Label("end".to_owned()),
+            ]
+        };
+
+        let expected = to_u8(vec![
+            asm::Instruction::BT(1).to_byte(),
+            asm::Instruction::BRA(131).to_byte(),
+            asm::Instruction::Nop.to_byte(),
+        ]);
+        let result = assemble(input(asm::Instruction::BF(125))).unwrap();
+        assert_eq!(expected, result[..6]);
+
+        // A delayed Branch can not be inverted, as its Delay-Slot follows it
+        assert_eq!(
+            Err(AssembleError::BranchOutOfRange(asm::Instruction::BFs(125))),
+            assemble(input(asm::Instruction::BFs(125)))
+        );
+    }
+
+    #[test]
+    fn distant_conditional_branch() {
+        // Once the Jump in between grew, the Alignment moves the Branch
+        // out of Range for a `BRA` to its Target as well
+        let input = vec![
+            asm::Instruction::Space(4088),
+            asm::Instruction::Nop,
+            asm::Instruction::JmpLabel("end".to_owned()),
+            asm::Instruction::Space(2),
+            asm::Instruction::Align(4096),
+            asm::Instruction::BF(0xfa),
+            asm::Instruction::Space(5000),
+            asm::Instruction::Label("end".to_owned()),
+        ];
+
+        let expected = to_u8(vec![
+            asm::Instruction::BT(6).to_byte(),
+            asm::Instruction::Push(0).to_byte(),
+            asm::Instruction::MovL(asm::Operand::Register(0), asm::Operand::Displacement8(1))
+                .to_byte(),
+            asm::Instruction::Braf(0).to_byte(),
+            asm::Instruction::Pop(0).to_byte(),
+            asm::Instruction::Nop.to_byte(),
+            // The Offset from the `BRAF` back to the Target
+            [0xff, 0xff],
+            [0xef, 0xee],
+        ]);
+        let result = assemble(input).unwrap();
+        assert_eq!(expected, result[8192..8208]);
     }
 }
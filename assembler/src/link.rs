@@ -0,0 +1,392 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt,
+};
+
+use crate::{
+    archive::Archive,
+    asm,
+    object::{Object, ObjectError, RelocationKind},
+    symbols::Symbols,
+    BASE_ADDRESS,
+};
+
+/// The Symbol the Linker defines at the Start of the combined Bss-Sections
+pub const BSS_START: &str = "__bss_start";
+/// The Symbol the Linker defines at the End of the combined Bss-Sections
+pub const BSS_END: &str = "__bss_end";
+
+/// The Order in which the Sections of all the Objects are placed
+const ORDER: [asm::Section; 4] = [
+    asm::Section::Text,
+    asm::Section::RoData,
+    asm::Section::Data,
+    asm::Section::Bss,
+];
+
+/// The Problems that prevent the Objects from being linked
+#[derive(Debug, PartialEq)]
+pub enum LinkError {
+    /// The Symbol is used, but no Object defines it
+    Undefined(String),
+    /// The Symbol is defined by more than one Object
+    Duplicate(String),
+    /// The Member of an Archive is not a valid Object
+    InvalidMember { name: String, error: ObjectError },
+    /// The Symbol is too far away from a Location that refers to it
+    OutOfRange(String),
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Undefined(name) => write!(f, "undefined reference to `{}`", name),
+            Self::Duplicate(name) => write!(f, "multiple definitions of `{}`", name),
+            Self::InvalidMember { name, error } => write!(f, "{}: {}", name, error),
+            Self::OutOfRange(name) => write!(f, "relocation truncated to fit: `{}`", name),
+        }
+    }
+}
+
+impl std::error::Error for LinkError {}
+
+/// Links the Objects, and the Members of the Archives that are needed by
+/// them, into the final ByteCode and returns it with the absolute Address
+/// of every Symbol.
+///
+/// All the Sections of the same Kind are placed next to each other,
+/// starting with the Text-Sections at the `BASE_ADDRESS`, in the Order of
+/// the Objects, so the first Object has to start with the Entry-Point of
+/// the Program. The Bss-Sections are placed last and are not part of the
+/// ByteCode, their Start and End can be used through the `__bss_start`
+/// and `__bss_end` Symbols, which are also part of the returned Symbols
+pub fn link(
+    mut objects: Vec<Object>,
+    archives: &[Archive],
+) -> Result<(Vec<u8>, Symbols), Vec<LinkError>> {
+    let mut errors = Vec::new();
+
+    let mut members = Vec::new();
+    for member in archives.iter().flat_map(|archive| archive.members.iter()) {
+        match Object::parse(&member.data) {
+            Ok(object) => members.push(Some(object)),
+            Err(error) => errors.push(LinkError::InvalidMember {
+                name: member.name.clone(),
+                error,
+            }),
+        };
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    // Members are added as long as they define a Symbol that is still
+    // undefined, as they can themselves use other Members
+    loop {
+        let undefined = undefined(&objects);
+        let needed = members.iter().position(|member| match member {
+            Some(member) => member
+                .defined()
+                .any(|symbol| undefined.contains(symbol.name.as_str())),
+            None => false,
+        });
+        match needed {
+            Some(index) => objects.push(members[index].take().unwrap()),
+            None => break,
+        };
+    }
+
+    let mut defined = HashSet::new();
+    for object in objects.iter() {
+        for symbol in object.defined() {
+            let error = LinkError::Duplicate(symbol.name.clone());
+            if !defined.insert(symbol.name.as_str()) && !errors.contains(&error) {
+                errors.push(error);
+            }
+        }
+    }
+    for name in undefined(&objects) {
+        errors.push(LinkError::Undefined(name.to_owned()));
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    // The Address of every Section of every Object
+    let mut addresses: Vec<Vec<u32>> = objects
+        .iter()
+        .map(|object| vec![0; object.sections.len()])
+        .collect();
+    let mut current = BASE_ADDRESS;
+    let mut bss_start = current;
+    for kind in ORDER.iter() {
+        if *kind == asm::Section::Bss {
+            bss_start = current;
+        }
+        for (index, object) in objects.iter().enumerate() {
+            for (section_index, section) in object.sections.iter().enumerate() {
+                if section.kind != *kind {
+                    continue;
+                }
+                let alignment = section.alignment.max(1);
+                current = current.next_multiple_of(alignment);
+                addresses[index][section_index] = current;
+                current += section.size;
+            }
+        }
+    }
+    let bss_end = current;
+
+    let mut code = vec![0; (bss_start - BASE_ADDRESS) as usize];
+    let mut symbols = Symbols::new();
+    symbols.insert(bss_start, BSS_START.to_owned());
+    symbols.insert(bss_end, BSS_END.to_owned());
+    let mut globals = HashMap::new();
+    globals.insert(BSS_START.to_owned(), bss_start);
+    globals.insert(BSS_END.to_owned(), bss_end);
+    for (index, object) in objects.iter().enumerate() {
+        for (section_index, section) in object.sections.iter().enumerate() {
+            let start = (addresses[index][section_index] - BASE_ADDRESS) as usize;
+            if section.kind != asm::Section::Bss {
+                code[start..start + section.data.len()].copy_from_slice(&section.data);
+            }
+        }
+
+        for symbol in object.symbols.iter() {
+            if let Some(section) = symbol.section {
+                let address = addresses[index][section] + symbol.offset;
+                // Symbols without a Name only refer to the Start of their
+                // Section
+                if !symbol.name.is_empty() {
+                    symbols.insert(address, symbol.name.clone());
+                }
+                if symbol.global {
+                    globals.insert(symbol.name.clone(), address);
+                }
+            }
+        }
+    }
+
+    for (index, object) in objects.iter().enumerate() {
+        for relocation in object.relocations.iter() {
+            let symbol = &object.symbols[relocation.symbol];
+            let target = match symbol.section {
+                Some(section) => addresses[index][section] + symbol.offset,
+                None => globals[&symbol.name],
+            };
+            let value = target.wrapping_add(relocation.addend as u32);
+
+            let address = addresses[index][relocation.section] + relocation.offset;
+            let location = (address - BASE_ADDRESS) as usize;
+            match relocation.kind {
+                RelocationKind::Dir32 => {
+                    code[location..location + 4].copy_from_slice(&value.to_be_bytes());
+                }
+                RelocationKind::Rel32 => {
+                    let distance = value.wrapping_sub(address);
+                    code[location..location + 4].copy_from_slice(&distance.to_be_bytes());
+                }
+                RelocationKind::Ind12W => {
+                    // The Displacement is relative to the Instruction after
+                    // the Delay-Slot and counts Instructions
+                    let distance = value.wrapping_sub(address + 4) as i32;
+                    if distance % 2 != 0 || !(-4096..4096).contains(&distance) {
+                        errors.push(LinkError::OutOfRange(symbol.name.clone()));
+                        continue;
+                    }
+                    let instr = u16::from_be_bytes([code[location], code[location + 1]]);
+                    let instr = (instr & 0xf000) | ((distance >> 1) as u16 & 0x0fff);
+                    code[location..location + 2].copy_from_slice(&instr.to_be_bytes());
+                }
+            };
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    Ok((code, symbols))
+}
+
+/// The Names of all the Symbols that are used by one of the Objects, but
+/// not defined by any of them or the Linker itself
+fn undefined(objects: &[Object]) -> BTreeSet<&str> {
+    let defined: BTreeSet<&str> = objects
+        .iter()
+        .flat_map(|object| object.defined())
+        .map(|symbol| symbol.name.as_str())
+        .chain([BSS_START, BSS_END].iter().copied())
+        .collect();
+
+    objects
+        .iter()
+        .flat_map(|object| object.undefined())
+        .map(|symbol| symbol.name.as_str())
+        .filter(|name| !defined.contains(name))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        archive::Member,
+        object::{Relocation, Section, Symbol},
+    };
+
+    fn object(instr: Vec<asm::Instruction>) -> Object {
+        crate::assemble_object(instr).unwrap()
+    }
+
+    fn function(name: &str, calls: Option<&str>) -> Object {
+        let mut instr = vec![
+            asm::Instruction::Global(name.to_owned()),
+            asm::Instruction::Label(name.to_owned()),
+        ];
+        if let Some(called) = calls {
+            instr.push(asm::Instruction::JsrLabel(called.to_owned()));
+        }
+        instr.extend_from_slice(&[asm::Instruction::Rts, asm::Instruction::Nop]);
+        object(instr)
+    }
+
+    fn read_long(code: &[u8], offset: usize) -> u32 {
+        u32::from_be_bytes([
+            code[offset],
+            code[offset + 1],
+            code[offset + 2],
+            code[offset + 3],
+        ])
+    }
+
+    #[test]
+    fn call_between_objects() {
+        let objects = vec![function("main", Some("helper")), function("helper", None)];
+
+        let (code, symbols) = link(objects, &[]).unwrap();
+
+        // The Call takes 18 Bytes, the Text-Section of `main` is padded to
+        // 24 Bytes
+        assert_eq!(vec!["main".to_owned()], symbols.at(0x00300000));
+        assert_eq!(vec!["helper".to_owned()], symbols.at(0x00300018));
+        assert_eq!(0x00300018, read_long(&code, 12));
+        assert_eq!(28, code.len());
+    }
+
+    #[test]
+    fn symbol_errors() {
+        let objects = vec![
+            function("main", Some("missing")),
+            function("main", None),
+            function("main", None),
+        ];
+
+        assert_eq!(
+            Err(vec![
+                LinkError::Duplicate("main".to_owned()),
+                LinkError::Undefined("missing".to_owned()),
+            ]),
+            link(objects, &[])
+        );
+    }
+
+    /// An Object that branches to `helper` with a `bsr`, like the ones GCC
+    /// generates
+    fn branch(padding: u32) -> Object {
+        let mut data = vec![0xb0, 0x00, 0x00, 0x09, 0x00, 0x0b, 0x00, 0x09];
+        data.resize(data.len() + padding as usize, 0);
+        Object {
+            sections: vec![Section {
+                kind: asm::Section::Text,
+                alignment: 4,
+                size: data.len() as u32,
+                data,
+            }],
+            symbols: vec![
+                Symbol {
+                    name: "main".to_owned(),
+                    global: true,
+                    section: Some(0),
+                    offset: 0,
+                },
+                Symbol {
+                    name: "helper".to_owned(),
+                    global: true,
+                    section: None,
+                    offset: 0,
+                },
+            ],
+            relocations: vec![Relocation {
+                section: 0,
+                offset: 0,
+                kind: RelocationKind::Ind12W,
+                symbol: 1,
+                addend: 0,
+            }],
+        }
+    }
+
+    #[test]
+    fn branch_relocation() {
+        let objects = vec![branch(0), function("helper", None)];
+
+        let (code, _) = link(objects, &[]).unwrap();
+
+        // `helper` starts 4 Bytes after the Delay-Slot
+        assert_eq!([0xb0, 0x02], code[0..2]);
+
+        let objects = vec![branch(4096), function("helper", None)];
+        assert_eq!(
+            Err(vec![LinkError::OutOfRange("helper".to_owned())]),
+            link(objects, &[]).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn archive_members() {
+        let member = |name: &str, object: Object| Member {
+            name: name.to_owned(),
+            data: object.serialize(),
+        };
+        let archive = Archive {
+            members: vec![
+                member("unused.o", function("unused", Some("missing"))),
+                member("first.o", function("first", Some("second"))),
+                member("second.o", function("second", None)),
+            ],
+        };
+
+        let (_, symbols) = link(vec![function("main", Some("first"))], &[archive]).unwrap();
+
+        // Only the Members that are needed are linked
+        let symbols = symbols.serialize();
+        assert!(symbols.contains(" first\n"));
+        assert!(symbols.contains(" second\n"));
+        assert!(!symbols.contains(" unused\n"));
+    }
+
+    #[test]
+    fn bss_symbols() {
+        let objects = vec![
+            object(vec![
+                asm::Instruction::MovLabel(1, BSS_START.to_owned()),
+                asm::Instruction::MovLabel(2, BSS_END.to_owned()),
+                asm::Instruction::Rts,
+                asm::Instruction::Nop,
+                asm::Instruction::Section(asm::Section::Bss),
+                asm::Instruction::Space(6),
+            ]),
+            object(vec![
+                asm::Instruction::Section(asm::Section::Bss),
+                asm::Instruction::Space(4),
+            ]),
+        ];
+
+        let (code, _) = link(objects, &[]).unwrap();
+
+        // The Bss-Section of the second Object starts aligned
+        assert_eq!(16, code.len());
+        assert_eq!(0x00300010, read_long(&code, 8));
+        assert_eq!(0x0030001c, read_long(&code, 12));
+    }
+}
@@ -0,0 +1,400 @@
+use std::fmt;
+
+use sh::elf::{ElfError, Reader};
+
+use crate::{asm, elf};
+
+/// The Magic-Bytes at the Start of every serialized Object
+const MAGIC: &[u8; 4] = b"RZOB";
+/// The Version of the serialized Format
+const VERSION: u8 = 1;
+
+/// The Section-Index of a Symbol that is not defined in the Object
+const UNDEFINED: u32 = 0xffff_ffff;
+
+/// The Reasons why an Object or Archive could not be parsed
+#[derive(Debug, PartialEq, Clone)]
+pub enum ObjectError {
+    /// The Data does not start with the expected Magic-Bytes
+    WrongIdentifier,
+    /// The Data ended before the whole Object was read
+    UnexpectedEnd,
+    /// The Data is not a valid Object, with the Reason
+    Malformed(String),
+}
+
+impl fmt::Display for ObjectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongIdentifier => write!(f, "not an object file"),
+            Self::UnexpectedEnd => write!(f, "unexpected end of the object file"),
+            Self::Malformed(reason) => write!(f, "malformed object file: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ObjectError {}
+
+impl From<ElfError> for ObjectError {
+    fn from(error: ElfError) -> Self {
+        match error {
+            ElfError::WrongIdentifier => Self::WrongIdentifier,
+            ElfError::UnexpectedEnd => Self::UnexpectedEnd,
+            ElfError::Malformed(reason) => Self::Malformed(reason),
+        }
+    }
+}
+
+/// A single Section of an Object, that is placed by the Linker
+#[derive(Debug, PartialEq, Clone)]
+pub struct Section {
+    /// The Kind of the Section, the Linker places all the Sections of the
+    /// same Kind next to each other
+    pub kind: asm::Section,
+    /// The Section has to be placed at a multiple of this Alignment
+    pub alignment: u32,
+    /// The Size in Bytes, for the Bss-Section this is not backed by Data
+    pub size: u32,
+    /// The Content of the Section, which is empty for the Bss-Section
+    pub data: Vec<u8>,
+}
+
+/// A Label that is defined or referenced by an Object
+#[derive(Debug, PartialEq, Clone)]
+pub struct Symbol {
+    pub name: String,
+    /// Whether or not the Symbol is visible to other Objects
+    pub global: bool,
+    /// The Index of the Section the Symbol is defined in, `None` if the
+    /// Symbol is defined in another Object
+    pub section: Option<usize>,
+    /// The Offset of the Symbol in its Section
+    pub offset: u32,
+}
+
+/// The different Ways a Relocation changes the Data at its Location
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RelocationKind {
+    /// The absolute 32-Bit Address of the Symbol plus the Addend
+    Dir32,
+    /// The 32-Bit Distance from the Location to the Symbol plus the Addend
+    Rel32,
+    /// The 12-Bit Displacement of a `bra` or `bsr` to the Symbol plus the
+    /// Addend
+    Ind12W,
+}
+
+impl RelocationKind {
+    /// The Size in Bytes of the Location that is changed
+    pub fn size(&self) -> usize {
+        match self {
+            Self::Dir32 | Self::Rel32 => 4,
+            Self::Ind12W => 2,
+        }
+    }
+}
+
+/// A Location in a Section, that depends on the final Address of a Symbol
+/// and is filled in by the Linker
+#[derive(Debug, PartialEq, Clone)]
+pub struct Relocation {
+    /// The Index of the Section that contains the Location
+    pub section: usize,
+    /// The Offset of the Location in its Section
+    pub offset: u32,
+    pub kind: RelocationKind,
+    /// The Index of the Symbol
+    pub symbol: usize,
+    pub addend: i32,
+}
+
+/// A relocatable Object, that contains the assembled Sections of a single
+/// Source and that is combined with other Objects by the Linker
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Object {
+    pub sections: Vec<Section>,
+    pub symbols: Vec<Symbol>,
+    pub relocations: Vec<Relocation>,
+}
+
+impl Object {
+    /// Whether or not the Data looks like a serialized Object or an ELF
+    /// Object
+    pub fn is_object(data: &[u8]) -> bool {
+        data.starts_with(MAGIC) || elf::is_elf(data)
+    }
+
+    /// The global Symbols that are defined in this Object
+    pub fn defined(&self) -> impl Iterator<Item = &Symbol> {
+        self.symbols
+            .iter()
+            .filter(|symbol| symbol.global && symbol.section.is_some())
+    }
+
+    /// The Symbols that are used by this Object, but defined in another
+    /// one
+    pub fn undefined(&self) -> impl Iterator<Item = &Symbol> {
+        self.symbols
+            .iter()
+            .filter(|symbol| symbol.section.is_none())
+    }
+
+    /// Serializes the Object, all Values are stored in Big-Endian
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut result = Vec::new();
+        result.extend_from_slice(MAGIC);
+        result.push(VERSION);
+
+        result.extend_from_slice(&(self.sections.len() as u32).to_be_bytes());
+        for section in self.sections.iter() {
+            result.push(section_id(&section.kind));
+            result.extend_from_slice(&section.alignment.to_be_bytes());
+            result.extend_from_slice(&section.size.to_be_bytes());
+            if section.kind != asm::Section::Bss {
+                result.extend_from_slice(&section.data);
+            }
+        }
+
+        result.extend_from_slice(&(self.symbols.len() as u32).to_be_bytes());
+        for symbol in self.symbols.iter() {
+            result.extend_from_slice(&(symbol.name.len() as u16).to_be_bytes());
+            result.extend_from_slice(symbol.name.as_bytes());
+            result.push(symbol.global as u8);
+            let section = symbol.section.map_or(UNDEFINED, |section| section as u32);
+            result.extend_from_slice(&section.to_be_bytes());
+            result.extend_from_slice(&symbol.offset.to_be_bytes());
+        }
+
+        result.extend_from_slice(&(self.relocations.len() as u32).to_be_bytes());
+        for relocation in self.relocations.iter() {
+            result.extend_from_slice(&(relocation.section as u32).to_be_bytes());
+            result.extend_from_slice(&relocation.offset.to_be_bytes());
+            result.push(match relocation.kind {
+                RelocationKind::Dir32 => 0,
+                RelocationKind::Rel32 => 1,
+                RelocationKind::Ind12W => 2,
+            });
+            result.extend_from_slice(&(relocation.symbol as u32).to_be_bytes());
+            result.extend_from_slice(&relocation.addend.to_be_bytes());
+        }
+
+        result
+    }
+
+    /// Parses an Object, as generated by `serialize`, or a relocatable
+    /// ELF-File
+    pub fn parse(data: &[u8]) -> Result<Self, ObjectError> {
+        if elf::is_elf(data) {
+            return elf::parse_object(data);
+        }
+        if !data.starts_with(MAGIC) {
+            return Err(ObjectError::WrongIdentifier);
+        }
+        let mut reader = Reader::at(data, MAGIC.len());
+        let version = reader.u8()?;
+        if version != VERSION {
+            return Err(ObjectError::Malformed(format!(
+                "unsupported version {}",
+                version
+            )));
+        }
+
+        let mut result = Self::default();
+
+        for _ in 0..reader.u32()? {
+            let kind = match reader.u8()? {
+                0 => asm::Section::Text,
+                1 => asm::Section::RoData,
+                2 => asm::Section::Data,
+                3 => asm::Section::Bss,
+                other => {
+                    return Err(ObjectError::Malformed(format!(
+                        "unknown section kind {}",
+                        other
+                    )))
+                }
+            };
+            let alignment = reader.u32()?;
+            if !alignment.is_power_of_two() {
+                return Err(ObjectError::Malformed(format!(
+                    "invalid section alignment {}",
+                    alignment
+                )));
+            }
+            let size = reader.u32()?;
+            let data = if kind == asm::Section::Bss {
+                Vec::new()
+            } else {
+                reader.bytes(size as usize)?.to_vec()
+            };
+            result.sections.push(Section {
+                kind,
+                alignment,
+                size,
+                data,
+            });
+        }
+
+        for _ in 0..reader.u32()? {
+            let length = reader.u16()? as usize;
+            let name = String::from_utf8(reader.bytes(length)?.to_vec())
+                .map_err(|_| ObjectError::Malformed("invalid symbol name".to_owned()))?;
+            let global = reader.u8()? != 0;
+            let section = match reader.u32()? {
+                UNDEFINED => None,
+                section if (section as usize) < result.sections.len() => Some(section as usize),
+                section => {
+                    return Err(ObjectError::Malformed(format!(
+                        "symbol `{}` is in the unknown section {}",
+                        name, section
+                    )))
+                }
+            };
+            let offset = reader.u32()?;
+            result.symbols.push(Symbol {
+                name,
+                global,
+                section,
+                offset,
+            });
+        }
+
+        for _ in 0..reader.u32()? {
+            let section = reader.u32()? as usize;
+            let offset = reader.u32()?;
+            let kind = match reader.u8()? {
+                0 => RelocationKind::Dir32,
+                1 => RelocationKind::Rel32,
+                2 => RelocationKind::Ind12W,
+                other => {
+                    return Err(ObjectError::Malformed(format!(
+                        "unknown relocation kind {}",
+                        other
+                    )))
+                }
+            };
+            let symbol = reader.u32()? as usize;
+            let addend = reader.u32()? as i32;
+
+            // The whole Location has to be part of the Data of the Section
+            let in_bounds = result
+                .sections
+                .get(section)
+                .is_some_and(|s| offset as usize + kind.size() <= s.data.len());
+            if !in_bounds || symbol >= result.symbols.len() {
+                return Err(ObjectError::Malformed(
+                    "relocation outside of its section".to_owned(),
+                ));
+            }
+
+            result.relocations.push(Relocation {
+                section,
+                offset,
+                kind,
+                symbol,
+                addend,
+            });
+        }
+
+        Ok(result)
+    }
+}
+
+fn section_id(section: &asm::Section) -> u8 {
+    match section {
+        asm::Section::Text => 0,
+        asm::Section::RoData => 1,
+        asm::Section::Data => 2,
+        asm::Section::Bss => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object() -> Object {
+        Object {
+            sections: vec![
+                Section {
+                    kind: asm::Section::Text,
+                    alignment: 4,
+                    size: 8,
+                    data: vec![0x00, 0x09, 0x00, 0x0b, 0x00, 0x00, 0x00, 0x00],
+                },
+                Section {
+                    kind: asm::Section::Bss,
+                    alignment: 4,
+                    size: 16,
+                    data: Vec::new(),
+                },
+            ],
+            symbols: vec![
+                Symbol {
+                    name: "main".to_owned(),
+                    global: true,
+                    section: Some(0),
+                    offset: 0,
+                },
+                Symbol {
+                    name: "counter".to_owned(),
+                    global: false,
+                    section: Some(1),
+                    offset: 4,
+                },
+                Symbol {
+                    name: "puts".to_owned(),
+                    global: true,
+                    section: None,
+                    offset: 0,
+                },
+            ],
+            relocations: vec![Relocation {
+                section: 0,
+                offset: 4,
+                kind: RelocationKind::Dir32,
+                symbol: 2,
+                addend: -4,
+            }],
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let object = object();
+        assert_eq!(Ok(object.clone()), Object::parse(&object.serialize()));
+    }
+
+    #[test]
+    fn symbols() {
+        let object = object();
+        let defined: Vec<&str> = object.defined().map(|s| s.name.as_str()).collect();
+        let undefined: Vec<&str> = object.undefined().map(|s| s.name.as_str()).collect();
+
+        assert_eq!(vec!["main"], defined);
+        assert_eq!(vec!["puts"], undefined);
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            Err(ObjectError::WrongIdentifier),
+            Object::parse(b"!<arch>\n")
+        );
+
+        let serialized = object().serialize();
+        assert_eq!(
+            Err(ObjectError::UnexpectedEnd),
+            Object::parse(&serialized[..serialized.len() - 1])
+        );
+
+        let mut object = object();
+        object.relocations[0].offset = 6;
+        assert_eq!(
+            Err(ObjectError::Malformed(
+                "relocation outside of its section".to_owned()
+            )),
+            Object::parse(&object.serialize())
+        );
+    }
+}
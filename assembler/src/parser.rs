@@ -0,0 +1,1460 @@
+use std::{collections::HashSet, fmt};
+
+use crate::asm;
+
+/// An Error in the Assembly-Source, with the Line on which it was found
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    /// The Line, starting at 1
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// A single Operand as written in the Source
+#[derive(Debug, PartialEq, Clone)]
+enum Operand {
+    /// `rn`
+    Register(u8),
+    /// `@rn`
+    AtRegister(u8),
+    /// `@rn+`
+    PostIncrement(u8),
+    /// `@-rn`
+    PreDecrement(u8),
+    /// `@(disp, rn)`
+    Displacement(i64, u8),
+    /// `@(r0, rn)`
+    OffsetR0(u8),
+    /// `@(disp, gbr)`
+    DisplacementGBR(i64),
+    /// `@(r0, gbr)`
+    OffsetR0GBR,
+    /// `@(disp, pc)`
+    DisplacementPC(i64),
+    /// `#value`
+    Immediate(i64),
+    /// `#label`, the Address of the Label
+    Address(String),
+    /// `label`
+    Label(String),
+    /// `.+offset` or `.-offset`, relative to the Address of the Instruction
+    Relative(i64),
+    /// The PR-Control-Register
+    Pr,
+    /// The MACL-Register
+    Macl,
+    /// The MACH-Register
+    Mach,
+    /// `sr`, `gbr` or `vbr`
+    Control(asm::ControlRegister),
+}
+
+/// The Branches to a Label that have a Delay-Slot
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Delayed {
+    Bra,
+    Bsr,
+    BTs,
+    BFs,
+}
+
+#[derive(Debug, PartialEq)]
+enum Kind {
+    Label(String),
+    /// A single Instruction, which can also be placed in a Delay-Slot
+    Instruction(asm::Instruction),
+    /// Directives and Instructions that expand to more than one Instruction
+    Expanded(Vec<asm::Instruction>),
+    /// A Branch to the Label, that still needs the Instruction from its
+    /// Delay-Slot
+    Delayed(Delayed, String),
+}
+
+#[derive(Debug)]
+struct Statement {
+    line: usize,
+    kind: Kind,
+}
+
+/// Parses Assembly-Source in the GNU-Syntax for SH into the Instructions
+/// that are then used by the Assembler.
+///
+/// Branches to Labels (`bra`, `bsr`, `bt`, `bf`, `bt/s`, `bf/s`) are turned
+/// into the `JmpLabel`- and `JsrLabel`-Instructions, so the Assembler can
+/// still relax them. The Instruction in the Delay-Slot of such a Branch is
+/// therefore moved in front of it. `mov.l #value, rn` loads any 32-Bit
+/// Value or the Address of a Label from a Literal-Pool, while `mova label,
+/// r0`, `mov.w label, rn` and `mov.l label, rn` access the Label itself
+/// PC-relative. Branches to `.+offset` are kept as they are, so the Output
+/// of the Disassembler can be assembled again.
+pub fn parse(content: &str) -> Result<Vec<asm::Instruction>, ParseError> {
+    let content = remove_block_comments(content)?;
+
+    let mut statements = Vec::new();
+    for (index, raw) in content.lines().enumerate() {
+        let line = index + 1;
+        for part in split_statements(raw) {
+            parse_statement(line, part, &mut statements)
+                .map_err(|message| ParseError { line, message })?;
+        }
+    }
+
+    check_labels(&statements)?;
+
+    let mut result = Vec::with_capacity(statements.len());
+    let mut iter = statements.into_iter();
+    while let Some(statement) = iter.next() {
+        match statement.kind {
+            Kind::Label(name) => result.push(asm::Instruction::Label(name)),
+            Kind::Instruction(instr) => result.push(instr),
+            Kind::Expanded(mut instr) => result.append(&mut instr),
+            Kind::Delayed(branch, target) => {
+                let slot = match iter.next() {
+                    Some(Statement {
+                        kind: Kind::Instruction(instr),
+                        ..
+                    }) if !instr.is_slot_illegal() => instr,
+                    _ => {
+                        return Err(ParseError {
+                            line: statement.line,
+                            message: "expected an Instruction for the Delay-Slot of the Branch"
+                                .to_owned(),
+                        })
+                    }
+                };
+                result.append(&mut expand_delayed(branch, target, slot));
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Replaces every `/* ... */` Comment with Spaces, while keeping its Line
+/// Breaks so the Lines of the following Statements stay the same
+fn remove_block_comments(content: &str) -> Result<String, ParseError> {
+    let mut result = String::with_capacity(content.len());
+    let mut line = 1;
+    // The Line on which the current Block-Comment started
+    let mut comment = None;
+    let mut line_comment = false;
+    // Whether or not the Line only contained Whitespace so far
+    let mut blank = true;
+    let mut quotes = Quotes::default();
+
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\n' {
+            line += 1;
+            line_comment = false;
+            blank = true;
+            quotes = Quotes::default();
+            result.push(c);
+            continue;
+        }
+
+        if comment.is_some() {
+            if c == '*' && chars.peek() == Some(&'/') {
+                chars.next();
+                comment = None;
+                result.push_str("  ");
+            } else {
+                result.push(' ');
+            }
+            continue;
+        }
+
+        if !line_comment && !quotes.update(c) {
+            match c {
+                '!' => line_comment = true,
+                '#' if blank => line_comment = true,
+                '/' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    comment = Some(line);
+                    result.push_str("  ");
+                    continue;
+                }
+                _ => {}
+            };
+        }
+        blank &= c.is_whitespace();
+        result.push(c);
+    }
+
+    match comment {
+        Some(line) => Err(ParseError {
+            line,
+            message: "unterminated comment".to_owned(),
+        }),
+        None => Ok(result),
+    }
+}
+
+/// Keeps track of whether the Characters of a Line are inside of a String
+/// or a Character-Literal, in which Separators and Comments have no Meaning
+#[derive(Default)]
+struct Quotes {
+    /// The Quote that started the current String or Character-Literal
+    open: Option<char>,
+    escaped: bool,
+}
+
+impl Quotes {
+    /// Updates the State with the next Character and returns whether or
+    /// not it belongs to a String or Character-Literal
+    fn update(&mut self, c: char) -> bool {
+        let quote = match self.open {
+            Some(quote) => quote,
+            None => {
+                if c == '"' || c == '\'' {
+                    self.open = Some(c);
+                    return true;
+                }
+                return false;
+            }
+        };
+
+        match c {
+            _ if self.escaped => self.escaped = false,
+            '\\' => self.escaped = true,
+            _ if c == quote => self.open = None,
+            _ => {}
+        };
+        true
+    }
+}
+
+/// Splits the Line into its Statements, which are separated by `;`, and
+/// removes the Comment, which starts with `!`, or `#` at the Start of a Line
+fn split_statements(line: &str) -> Vec<&str> {
+    if line.trim_start().starts_with('#') {
+        return Vec::new();
+    }
+
+    let mut result = Vec::new();
+    let mut start = 0;
+    let mut quotes = Quotes::default();
+    for (index, c) in line.char_indices() {
+        if quotes.update(c) {
+            continue;
+        }
+
+        match c {
+            ';' => {
+                result.push(&line[start..index]);
+                start = index + 1;
+            }
+            '!' => {
+                result.push(&line[start..index]);
+                return result;
+            }
+            _ => {}
+        };
+    }
+    result.push(&line[start..]);
+
+    result
+}
+
+fn parse_statement(
+    line: usize,
+    statement: &str,
+    result: &mut Vec<Statement>,
+) -> Result<(), String> {
+    let mut statement = statement.trim();
+
+    // Labels at the Start of the Statement
+    while let Some(position) = statement.find(':') {
+        let name = &statement[..position];
+        if !is_identifier(name) {
+            break;
+        }
+
+        result.push(Statement {
+            line,
+            kind: Kind::Label(name.to_owned()),
+        });
+        statement = statement[position + 1..].trim_start();
+    }
+
+    if statement.is_empty() {
+        return Ok(());
+    }
+
+    let (mnemonic, rest) = match statement.find(char::is_whitespace) {
+        Some(position) => (&statement[..position], statement[position..].trim()),
+        None => (statement, ""),
+    };
+    let mnemonic = mnemonic.to_lowercase();
+
+    let kind = if mnemonic.starts_with('.') {
+        directive(&mnemonic, rest)?
+    } else {
+        let operands = split_operands(rest)
+            .into_iter()
+            .map(parse_operand)
+            .collect::<Result<Vec<_>, _>>()?;
+        instruction(&mnemonic, &operands)?
+    };
+    result.push(Statement { line, kind });
+
+    Ok(())
+}
+
+/// Splits the Operands at every Comma that is not inside of Parentheses
+fn split_operands(operands: &str) -> Vec<&str> {
+    if operands.is_empty() {
+        return Vec::new();
+    }
+
+    let mut result = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    let mut quotes = Quotes::default();
+    for (index, c) in operands.char_indices() {
+        if quotes.update(c) {
+            continue;
+        }
+
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                result.push(operands[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        };
+    }
+    result.push(operands[start..].trim());
+
+    result
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '.' => {}
+        _ => return false,
+    };
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '$')
+}
+
+fn parse_register(name: &str) -> Option<u8> {
+    let name = name.to_lowercase();
+    if name == "sp" {
+        return Some(15);
+    }
+
+    let number = name.strip_prefix('r')?;
+    if number.is_empty() || (number.len() > 1 && number.starts_with('0')) {
+        return None;
+    }
+    match number.parse::<u8>() {
+        Ok(register) if register < 16 => Some(register),
+        _ => None,
+    }
+}
+
+fn parse_number(raw: &str) -> Option<i64> {
+    if let Some(inner) = raw
+        .strip_prefix('\'')
+        .and_then(|raw| raw.strip_suffix('\''))
+    {
+        return match unescape(inner).ok()?.as_slice() {
+            [value] => Some(*value as i64),
+            _ => None,
+        };
+    }
+
+    let (negative, raw) = match raw.strip_prefix('-') {
+        Some(raw) => (true, raw),
+        None => (false, raw),
+    };
+
+    let lower = raw.to_lowercase();
+    let value = if let Some(hex) = lower.strip_prefix("0x") {
+        i64::from_str_radix(hex, 16).ok()?
+    } else if let Some(binary) = lower.strip_prefix("0b") {
+        i64::from_str_radix(binary, 2).ok()?
+    } else if !lower.is_empty() && lower.chars().all(|c| c.is_ascii_digit()) {
+        lower.parse().ok()?
+    } else {
+        return None;
+    };
+
+    Some(if negative { -value } else { value })
+}
+
+fn parse_operand(raw: &str) -> Result<Operand, String> {
+    let unsupported = || format!("unsupported operand `{}`", raw);
+
+    if let Some(register) = parse_register(raw) {
+        return Ok(Operand::Register(register));
+    }
+    match raw.to_lowercase().as_str() {
+        "pr" => return Ok(Operand::Pr),
+        "macl" => return Ok(Operand::Macl),
+        "mach" => return Ok(Operand::Mach),
+        "sr" => return Ok(Operand::Control(asm::ControlRegister::Sr)),
+        "gbr" => return Ok(Operand::Control(asm::ControlRegister::Gbr)),
+        "vbr" => return Ok(Operand::Control(asm::ControlRegister::Vbr)),
+        _ => {}
+    };
+
+    if let Some(value) = raw.strip_prefix('#') {
+        if let Some(number) = parse_number(value) {
+            return Ok(Operand::Immediate(number));
+        }
+        if is_identifier(value) {
+            return Ok(Operand::Address(value.to_owned()));
+        }
+        return Err(unsupported());
+    }
+
+    if let Some(address) = raw.strip_prefix('@') {
+        if let Some(register) = address.strip_prefix('-').and_then(parse_register) {
+            return Ok(Operand::PreDecrement(register));
+        }
+        if let Some(register) = address.strip_suffix('+').and_then(parse_register) {
+            return Ok(Operand::PostIncrement(register));
+        }
+        if let Some(register) = parse_register(address) {
+            return Ok(Operand::AtRegister(register));
+        }
+        if let Some(inner) = address
+            .strip_prefix('(')
+            .and_then(|address| address.strip_suffix(')'))
+        {
+            return parse_indirect(inner).ok_or_else(unsupported);
+        }
+        return Err(unsupported());
+    }
+
+    if is_identifier(raw) {
+        return Ok(Operand::Label(raw.to_owned()));
+    }
+
+    if let Some(offset) = raw.strip_prefix('.') {
+        let offset = match offset.strip_prefix('+') {
+            Some(offset) => parse_number(offset),
+            None if offset.starts_with('-') => parse_number(offset),
+            None => None,
+        };
+        return offset.map(Operand::Relative).ok_or_else(unsupported);
+    }
+
+    Err(unsupported())
+}
+
+/// Parses the inside of the Parentheses of `@(disp, base)` and `@(r0, base)`
+fn parse_indirect(inner: &str) -> Option<Operand> {
+    let (offset, base) = match split_operands(inner).as_slice() {
+        [offset, base] => (*offset, base.to_lowercase()),
+        _ => return None,
+    };
+
+    if parse_register(offset) == Some(0) {
+        return match base.as_str() {
+            "gbr" => Some(Operand::OffsetR0GBR),
+            _ => parse_register(&base).map(Operand::OffsetR0),
+        };
+    }
+
+    let disp = parse_number(offset)?;
+    match base.as_str() {
+        "gbr" => Some(Operand::DisplacementGBR(disp)),
+        "pc" => Some(Operand::DisplacementPC(disp)),
+        _ => parse_register(&base).map(|register| Operand::Displacement(disp, register)),
+    }
+}
+
+/// Checks that the Value fits into a signed 8-Bit Immediate and returns its
+/// Encoding
+fn immediate8(value: i64) -> Result<u8, String> {
+    if (-128..=127).contains(&value) {
+        Ok(value as i8 as u8)
+    } else {
+        Err(format!(
+            "the immediate {} does not fit into 8 Bits, use `mov.l #{}, rn` instead",
+            value, value
+        ))
+    }
+}
+
+/// Checks that the Value fits into an unsigned 8-Bit Immediate, as used by
+/// the logic Instructions and `trapa`
+fn unsigned8(value: i64) -> Result<u8, String> {
+    if (0..=255).contains(&value) {
+        Ok(value as u8)
+    } else {
+        Err(format!("the immediate {} does not fit into 8 Bits", value))
+    }
+}
+
+fn instruction(mnemonic: &str, operands: &[Operand]) -> Result<Kind, String> {
+    use Operand::*;
+
+    let instr = match (mnemonic, operands) {
+        ("nop", []) => asm::Instruction::Nop,
+        ("rts", []) => asm::Instruction::Rts,
+        ("sett", []) => asm::Instruction::Sett,
+        ("clrt", []) => asm::Instruction::Clrt,
+        ("div0u", []) => asm::Instruction::Div0u,
+        ("sleep", []) => asm::Instruction::Sleep,
+        ("trapa", [Immediate(value)]) => asm::Instruction::Trapa(unsigned8(*value)?),
+        ("pref", [AtRegister(target)]) => asm::Instruction::Pref(*target),
+
+        ("mov", [Register(source), Register(target)]) => asm::Instruction::Mov(*target, *source),
+        ("mov", [Immediate(value), Register(target)]) => {
+            asm::Instruction::MovI(*target, immediate8(*value)?)
+        }
+        ("mov.l", [Immediate(value), Register(target)]) => {
+            if !(i32::MIN as i64..=u32::MAX as i64).contains(value) {
+                return Err(format!("the immediate {} does not fit into 32 Bits", value));
+            }
+            asm::Instruction::MovConst(*target, *value as u32)
+        }
+        ("mov.l", [Address(name), Register(target)]) => {
+            asm::Instruction::MovLabel(*target, name.clone())
+        }
+        ("mov.w", [Label(name), Register(target)]) => {
+            asm::Instruction::LoadWord(*target, name.clone())
+        }
+        ("mov.l", [Label(name), Register(target)]) => {
+            asm::Instruction::LoadLong(*target, name.clone())
+        }
+        ("mova", [Label(name), Register(0)]) => asm::Instruction::MovALabel(name.clone()),
+        ("mova", [DisplacementPC(disp), Register(0)]) => {
+            match move_operand(&DisplacementPC(*disp), 4)? {
+                Some(asm::Operand::Displacement8(disp)) => asm::Instruction::MovA(disp),
+                _ => return Err("unsupported operands for `mova`".to_owned()),
+            }
+        }
+        ("mov.l", [Register(source), PreDecrement(15)]) => asm::Instruction::Push(*source),
+        ("mov.l", [Register(source), PreDecrement(stack)]) => {
+            asm::Instruction::PushOther(*source, *stack)
+        }
+        ("mov.b", [Register(source), PreDecrement(stack)]) => {
+            asm::Instruction::PushOtherB(*source, *stack)
+        }
+        ("mov.l", [PostIncrement(15), Register(target)]) => asm::Instruction::Pop(*target),
+        ("mov.l", [PostIncrement(stack), Register(target)]) => {
+            asm::Instruction::PopOther(*target, *stack)
+        }
+        ("mov.b" | "mov.w" | "mov.l", [source, target]) => {
+            match move_instruction(mnemonic, source, target)? {
+                Some(instr) => instr,
+                None => return Err(format!("unsupported operands for `{}`", mnemonic)),
+            }
+        }
+        ("movt", [Register(target)]) => asm::Instruction::MovT(*target),
+        ("extu.b", [Register(source), Register(target)]) => {
+            asm::Instruction::ExtuB(*target, *source)
+        }
+        ("extu.w", [Register(source), Register(target)]) => {
+            asm::Instruction::ExtuW(*target, *source)
+        }
+        ("exts.b", [Register(source), Register(target)]) => {
+            asm::Instruction::ExtsB(*target, *source)
+        }
+        ("exts.w", [Register(source), Register(target)]) => {
+            asm::Instruction::ExtsW(*target, *source)
+        }
+        ("swap.b", [Register(source), Register(target)]) => {
+            asm::Instruction::SwapB(*target, *source)
+        }
+        ("swap.w", [Register(source), Register(target)]) => {
+            asm::Instruction::SwapW(*target, *source)
+        }
+        ("xtrct", [Register(source), Register(target)]) => {
+            asm::Instruction::Xtrct(*target, *source)
+        }
+
+        ("sts.l", [Pr, PreDecrement(15)]) => asm::Instruction::PushPR,
+        ("lds.l", [PostIncrement(15), Pr]) => asm::Instruction::PopPR,
+        ("sts", [Pr, Register(target)]) => asm::Instruction::StsPr(*target),
+        ("sts.l", [Pr, PreDecrement(stack)]) => asm::Instruction::PushPROther(*stack),
+        ("lds.l", [PostIncrement(stack), Pr]) => asm::Instruction::PopPROther(*stack),
+        ("sts", [Macl, Register(target)]) => asm::Instruction::StsMacl(*target),
+        ("sts.l", [Macl, PreDecrement(stack)]) => asm::Instruction::StsLMacl(*stack),
+        ("lds.l", [PostIncrement(stack), Macl]) => asm::Instruction::LdsLMacl(*stack),
+        ("sts", [Mach, Register(target)]) => asm::Instruction::StsMach(*target),
+        ("sts.l", [Mach, PreDecrement(stack)]) => asm::Instruction::StsLMach(*stack),
+        ("lds.l", [PostIncrement(stack), Mach]) => asm::Instruction::LdsLMach(*stack),
+        ("ldc", [Register(source), Control(control)]) => asm::Instruction::Ldc(*control, *source),
+        ("stc", [Control(control), Register(target)]) => asm::Instruction::Stc(*target, *control),
+
+        ("tst", [Register(other), Register(target)]) => asm::Instruction::Tst(*target, *other),
+        ("xor", [Register(other), Register(target)]) => asm::Instruction::Xor(*target, *other),
+        ("or", [Register(other), Register(target)]) => asm::Instruction::Or(*target, *other),
+        ("and", [Register(other), Register(target)]) => asm::Instruction::And(*target, *other),
+        ("not", [Register(source), Register(target)]) => asm::Instruction::Not(*target, *source),
+        ("tst", [Immediate(value), Register(0)]) => asm::Instruction::TstI(unsigned8(*value)?),
+        ("xor", [Immediate(value), Register(0)]) => asm::Instruction::XorI(unsigned8(*value)?),
+        ("or", [Immediate(value), Register(0)]) => asm::Instruction::OrI(unsigned8(*value)?),
+        ("and", [Immediate(value), Register(0)]) => asm::Instruction::AndI(unsigned8(*value)?),
+        ("tst.b", [Immediate(value), OffsetR0GBR]) => {
+            asm::Instruction::TstB(asm::Operand::OffsetR0GBR, unsigned8(*value)?)
+        }
+        ("xor.b", [Immediate(value), OffsetR0GBR]) => {
+            asm::Instruction::XorB(asm::Operand::OffsetR0GBR, unsigned8(*value)?)
+        }
+        ("or.b", [Immediate(value), OffsetR0GBR]) => {
+            asm::Instruction::OrB(asm::Operand::OffsetR0GBR, unsigned8(*value)?)
+        }
+        ("and.b", [Immediate(value), OffsetR0GBR]) => {
+            asm::Instruction::AndB(asm::Operand::OffsetR0GBR, unsigned8(*value)?)
+        }
+        ("neg", [Register(source), Register(target)]) => asm::Instruction::Neg(*target, *source),
+        ("negc", [Register(source), Register(target)]) => asm::Instruction::Negc(*target, *source),
+        ("addc", [Register(other), Register(target)]) => asm::Instruction::Addc(*target, *other),
+        ("addv", [Register(other), Register(target)]) => asm::Instruction::Addv(*target, *other),
+        ("subv", [Register(other), Register(target)]) => asm::Instruction::Subv(*target, *other),
+        ("add", [Register(other), Register(target)]) => asm::Instruction::Add(*target, *other),
+        ("add", [Immediate(value), Register(target)]) => {
+            asm::Instruction::AddI(*target, immediate8(*value)?)
+        }
+        ("sub", [Register(other), Register(target)]) => asm::Instruction::Sub(*target, *other),
+        ("subc", [Register(other), Register(target)]) => asm::Instruction::Subc(*target, *other),
+        ("mul.l", [Register(other), Register(target)]) => asm::Instruction::MulL(*target, *other),
+        ("muls.w", [Register(other), Register(target)]) => asm::Instruction::MulsW(*target, *other),
+        ("mulu.w", [Register(other), Register(target)]) => asm::Instruction::MuluW(*target, *other),
+        ("dmuls.l", [Register(other), Register(target)]) => {
+            asm::Instruction::DmulSL(*target, *other)
+        }
+        ("dmulu.l", [Register(other), Register(target)]) => {
+            asm::Instruction::DmuluL(*target, *other)
+        }
+        ("mac.l", [PostIncrement(second), PostIncrement(first)]) => {
+            asm::Instruction::MacL(*first, *second)
+        }
+        ("mac.w", [PostIncrement(second), PostIncrement(first)]) => {
+            asm::Instruction::MacW(*first, *second)
+        }
+        ("div0s", [Register(divisor), Register(dividend)]) => {
+            asm::Instruction::Div0s(*dividend, *divisor)
+        }
+        ("div1", [Register(divisor), Register(dividend)]) => {
+            asm::Instruction::Div1(*dividend, *divisor)
+        }
+
+        ("cmp/eq", [Immediate(value), Register(0)]) => {
+            asm::Instruction::CmpEqI(immediate8(*value)?)
+        }
+        ("cmp/eq", [Register(other), Register(target)]) => asm::Instruction::CmpEq(*target, *other),
+        ("cmp/hs", [Register(other), Register(target)]) => asm::Instruction::CmpHs(*target, *other),
+        ("cmp/ge", [Register(other), Register(target)]) => asm::Instruction::CmpGe(*target, *other),
+        ("cmp/hi", [Register(other), Register(target)]) => asm::Instruction::CmpHi(*target, *other),
+        ("cmp/gt", [Register(other), Register(target)]) => asm::Instruction::CmpGt(*target, *other),
+        ("cmp/pz", [Register(target)]) => asm::Instruction::CmpPz(*target),
+        ("dt", [Register(target)]) => asm::Instruction::Dt(*target),
+
+        ("shll", [Register(target)]) => asm::Instruction::Shll(*target),
+        ("shll2", [Register(target)]) => asm::Instruction::Shll2(*target),
+        ("shll8", [Register(target)]) => asm::Instruction::Shll8(*target),
+        ("shll16", [Register(target)]) => asm::Instruction::Shll16(*target),
+        ("shlr", [Register(target)]) => asm::Instruction::Shlr(*target),
+        ("shlr2", [Register(target)]) => asm::Instruction::Shlr2(*target),
+        ("shlr8", [Register(target)]) => asm::Instruction::Shlr8(*target),
+        ("shlr16", [Register(target)]) => asm::Instruction::Shlr16(*target),
+        ("shld", [Register(count), Register(target)]) => asm::Instruction::Shld(*target, *count),
+        ("shad", [Register(count), Register(target)]) => asm::Instruction::Shad(*target, *count),
+        ("shal", [Register(target)]) => asm::Instruction::Shal(*target),
+        ("shar", [Register(target)]) => asm::Instruction::Shar(*target),
+        ("rotl", [Register(target)]) => asm::Instruction::Rotl(*target),
+        ("rotr", [Register(target)]) => asm::Instruction::Rotr(*target),
+        ("rotcl", [Register(target)]) => asm::Instruction::Rotcl(*target),
+        ("rotcr", [Register(target)]) => asm::Instruction::Rotcr(*target),
+
+        ("jmp", [AtRegister(target)]) => asm::Instruction::Jmp(*target),
+        ("jsr", [AtRegister(target)]) => asm::Instruction::Jsr(*target),
+        ("braf", [Register(target)]) => asm::Instruction::Braf(*target),
+        ("bsrf", [Register(target)]) => asm::Instruction::Bsrf(*target),
+
+        ("bt", [Label(name)]) => {
+            return Ok(Kind::Expanded(vec![
+                asm::Instruction::BF(1),
+                asm::Instruction::JmpLabel(name.clone()),
+            ]))
+        }
+        ("bf", [Label(name)]) => {
+            return Ok(Kind::Expanded(vec![
+                asm::Instruction::BT(1),
+                asm::Instruction::JmpLabel(name.clone()),
+            ]))
+        }
+        ("bt" | "bf" | "bt/s" | "bf/s" | "bra" | "bsr", [Relative(offset)]) => {
+            relative_branch(mnemonic, *offset)?
+        }
+        ("bra", [Label(name)]) => return Ok(Kind::Delayed(Delayed::Bra, name.clone())),
+        ("bsr", [Label(name)]) => return Ok(Kind::Delayed(Delayed::Bsr, name.clone())),
+        ("bt/s", [Label(name)]) => return Ok(Kind::Delayed(Delayed::BTs, name.clone())),
+        ("bf/s", [Label(name)]) => return Ok(Kind::Delayed(Delayed::BFs, name.clone())),
+
+        _ if is_known(mnemonic) => {
+            return Err(format!("unsupported operands for `{}`", mnemonic));
+        }
+        _ => return Err(format!("unknown instruction `{}`", mnemonic)),
+    };
+
+    Ok(Kind::Instruction(instr))
+}
+
+/// Converts a Branch with a Target relative to its own Address, whose
+/// Displacement is counted in Instructions from the Address after its
+/// Delay-Slot
+fn relative_branch(mnemonic: &str, offset: i64) -> Result<asm::Instruction, String> {
+    let disp = (offset - 4) / 2;
+    let range = match mnemonic {
+        "bra" | "bsr" => -2048..=2047,
+        _ => -128..=127,
+    };
+    if offset % 2 != 0 || !range.contains(&disp) {
+        return Err(format!(
+            "the offset {} can not be reached by `{}`",
+            offset, mnemonic
+        ));
+    }
+
+    Ok(match mnemonic {
+        "bt" => asm::Instruction::BT(disp as u8),
+        "bf" => asm::Instruction::BF(disp as u8),
+        "bt/s" => asm::Instruction::BTs(disp as u8),
+        "bf/s" => asm::Instruction::BFs(disp as u8),
+        "bra" => asm::Instruction::BRA(disp as u16 & 0xfff),
+        _ => asm::Instruction::BSR(disp as u16 & 0xfff),
+    })
+}
+
+/// Converts a Move between the given Operands, if the Combination of
+/// Operands is supported by SH for the Size of the Move
+fn move_instruction(
+    mnemonic: &str,
+    source: &Operand,
+    target: &Operand,
+) -> Result<Option<asm::Instruction>, String> {
+    let size = match mnemonic {
+        "mov.b" => 1,
+        "mov.w" => 2,
+        _ => 4,
+    };
+    let (target, source) = match (move_operand(target, size)?, move_operand(source, size)?) {
+        (Some(target), Some(source)) => (target, source),
+        _ => return Ok(None),
+    };
+
+    let supported = match (&target, &source) {
+        (asm::Operand::Register(_), asm::Operand::Register(_)) => false,
+        (asm::Operand::Register(_), asm::Operand::AtRegister(_))
+        | (asm::Operand::Register(_), asm::Operand::PostIncrement(_))
+        | (asm::Operand::Register(_), asm::Operand::OffsetR0(_))
+        | (asm::Operand::AtRegister(_), asm::Operand::Register(_))
+        | (asm::Operand::PreDecrement(_), asm::Operand::Register(_))
+        | (asm::Operand::OffsetR0(_), asm::Operand::Register(_)) => true,
+        (asm::Operand::Register(target), asm::Operand::Displacement4Reg(_, _))
+        | (asm::Operand::Displacement4Reg(_, _), asm::Operand::Register(target)) => {
+            size == 4 || *target == 0
+        }
+        (asm::Operand::Register(0), asm::Operand::DisplacementGBR(_))
+        | (asm::Operand::DisplacementGBR(_), asm::Operand::Register(0)) => true,
+        (asm::Operand::Register(_), asm::Operand::Displacement8(_)) => size != 1,
+        _ => false,
+    };
+    if !supported {
+        return Ok(None);
+    }
+
+    Ok(Some(match size {
+        1 => asm::Instruction::MovB(target, source),
+        2 => asm::Instruction::MovW(target, source),
+        _ => asm::Instruction::MovL(target, source),
+    }))
+}
+
+/// Converts the Operand of a Move with the given Size in Bytes, checking
+/// that a Displacement is a Multiple of the Size and fits into the Encoding
+fn move_operand(operand: &Operand, size: i64) -> Result<Option<asm::Operand>, String> {
+    let scaled = |disp: i64, max: i64| {
+        if disp % size != 0 || !(0..=max * size).contains(&disp) {
+            Err(format!(
+                "the displacement {} is not a multiple of {} between 0 and {}",
+                disp,
+                size,
+                max * size
+            ))
+        } else {
+            Ok((disp / size) as u8)
+        }
+    };
+
+    Ok(Some(match operand {
+        Operand::Register(register) => asm::Operand::Register(*register),
+        Operand::AtRegister(register) => asm::Operand::AtRegister(*register),
+        Operand::PostIncrement(register) => asm::Operand::PostIncrement(*register),
+        Operand::PreDecrement(register) => asm::Operand::PreDecrement(*register),
+        Operand::Displacement(disp, register) => {
+            asm::Operand::Displacement4Reg(scaled(*disp, 15)?, *register)
+        }
+        Operand::OffsetR0(register) => asm::Operand::OffsetR0(*register),
+        Operand::DisplacementGBR(disp) => asm::Operand::DisplacementGBR(scaled(*disp, 255)?),
+        Operand::DisplacementPC(disp) => asm::Operand::Displacement8(scaled(*disp, 255)?),
+        _ => return Ok(None),
+    }))
+}
+
+/// Whether or not the Mnemonic is supported with some combination of
+/// Operands
+fn is_known(mnemonic: &str) -> bool {
+    const KNOWN: &[&str] = &[
+        "nop", "rts", "mov", "mov.b", "mov.w", "mov.l", "movt", "extu.b", "extu.w", "sts", "sts.l",
+        "lds.l", "tst", "xor", "or", "and", "not", "add", "sub", "subc", "mul.l", "cmp/eq",
+        "cmp/hs", "cmp/ge", "cmp/hi", "cmp/gt", "cmp/pz", "dt", "shll", "shll2", "shll8", "shll16",
+        "shlr", "shlr2", "shlr8", "shlr16", "shld", "shad", "jmp", "jsr", "braf", "bt", "bf",
+        "bra", "bsr", "bt/s", "bf/s", "sett", "clrt", "div0u", "sleep", "trapa", "pref", "exts.b",
+        "exts.w", "swap.b", "swap.w", "xtrct", "ldc", "stc", "neg", "negc", "addc", "addv", "subv",
+        "muls.w", "mulu.w", "dmuls.l", "dmulu.l", "mac.l", "mac.w", "div0s", "div1", "shal",
+        "shar", "rotl", "rotr", "rotcl", "rotcr", "bsrf", "tst.b", "xor.b", "or.b", "and.b",
+        "mova",
+    ];
+    KNOWN.contains(&mnemonic)
+}
+
+/// Expands the Branch to the Label with the given Instruction from its
+/// Delay-Slot. The `JmpLabel`- and `JsrLabel`-Instructions already contain
+/// a Nop in their Delay-Slot, so the Instruction is executed before them
+fn expand_delayed(
+    branch: Delayed,
+    target: String,
+    slot: asm::Instruction,
+) -> Vec<asm::Instruction> {
+    let nop = slot == asm::Instruction::Nop;
+
+    match branch {
+        Delayed::Bra | Delayed::Bsr => {
+            let jump = if branch == Delayed::Bra {
+                asm::Instruction::JmpLabel(target)
+            } else {
+                asm::Instruction::JsrLabel(target)
+            };
+
+            if nop {
+                vec![jump]
+            } else {
+                vec![slot, jump]
+            }
+        }
+        Delayed::BTs | Delayed::BFs => {
+            // The Instruction in the Delay-Slot is executed whether or not
+            // the Branch is taken, so it is needed on both Paths
+            let skip = |disp| {
+                if branch == Delayed::BTs {
+                    asm::Instruction::BF(disp)
+                } else {
+                    asm::Instruction::BT(disp)
+                }
+            };
+
+            if nop {
+                vec![skip(1), asm::Instruction::JmpLabel(target)]
+            } else {
+                vec![
+                    skip(2),
+                    slot.clone(),
+                    asm::Instruction::JmpLabel(target),
+                    slot,
+                ]
+            }
+        }
+    }
+}
+
+fn directive(name: &str, arguments: &str) -> Result<Kind, String> {
+    let instr = match name {
+        ".byte" => asm::Instruction::Data(
+            numbers(name, arguments, i8::MIN as i64, u8::MAX as i64)?
+                .into_iter()
+                .map(|value| value as u8)
+                .collect(),
+        ),
+        ".word" => asm::Instruction::Data(
+            numbers(name, arguments, i16::MIN as i64, u16::MAX as i64)?
+                .into_iter()
+                .flat_map(|value| (value as u16).to_be_bytes().to_vec())
+                .collect(),
+        ),
+        ".long" => return long(arguments),
+        ".ascii" | ".asciz" => {
+            let mut data = Vec::new();
+            for raw in split_operands(arguments) {
+                data.append(&mut parse_string(raw)?);
+                if name == ".asciz" {
+                    data.push(0);
+                }
+            }
+            asm::Instruction::Data(data)
+        }
+        ".align" => {
+            // Like the GNU-Assembler for SH, the Argument is the Power of 2
+            // to align to
+            let power = number(name, arguments)?;
+            if !(0..=16).contains(&power) {
+                return Err(format!("invalid alignment {} for `.align`", power));
+            }
+            asm::Instruction::Align(1 << power)
+        }
+        ".space" => {
+            let size = number(name, arguments)?;
+            if !(0..=u32::MAX as i64).contains(&size) {
+                return Err(format!("invalid size {} for `.space`", size));
+            }
+            asm::Instruction::Space(size as u32)
+        }
+        ".text" => asm::Instruction::Section(asm::Section::Text),
+        ".data" => asm::Instruction::Section(asm::Section::Data),
+        ".bss" => asm::Instruction::Section(asm::Section::Bss),
+        ".section" => asm::Instruction::Section(match arguments {
+            ".text" => asm::Section::Text,
+            ".rodata" => asm::Section::RoData,
+            ".data" => asm::Section::Data,
+            ".bss" => asm::Section::Bss,
+            _ => return Err(format!("unknown section `{}`", arguments)),
+        }),
+        ".global" | ".globl" | ".extern" => {
+            let names = split_operands(arguments);
+            if names.is_empty() {
+                return Err(format!("expected a label for `{}`", name));
+            }
+
+            let mut result = Vec::with_capacity(names.len());
+            for label in names {
+                if !is_identifier(label) {
+                    return Err(format!("expected a label for `{}`", name));
+                }
+                result.push(asm::Instruction::Global(label.to_owned()));
+            }
+            return Ok(Kind::Expanded(result));
+        }
+        _ => return Err(format!("unknown directive `{}`", name)),
+    };
+
+    Ok(Kind::Expanded(vec![instr]))
+}
+
+/// Parses the Arguments of `.long`, which are either Numbers or Labels,
+/// whose absolute Address is placed into the ByteCode
+fn long(arguments: &str) -> Result<Kind, String> {
+    let mut result = Vec::new();
+    for raw in split_operands(arguments) {
+        if is_identifier(raw) {
+            result.push(asm::Instruction::LabelAddress(raw.to_owned()));
+            continue;
+        }
+
+        let value = number_in(".long", raw, i32::MIN as i64, u32::MAX as i64)?;
+        let mut bytes = (value as u32).to_be_bytes().to_vec();
+        match result.last_mut() {
+            Some(asm::Instruction::Data(data)) => data.append(&mut bytes),
+            _ => result.push(asm::Instruction::Data(bytes)),
+        };
+    }
+
+    Ok(Kind::Expanded(result))
+}
+
+/// Parses the single Number that is the Argument of the Directive
+fn number(directive: &str, argument: &str) -> Result<i64, String> {
+    parse_number(argument).ok_or_else(|| format!("expected a number for `{}`", directive))
+}
+
+/// Parses the comma-separated Numbers of the Directive and checks that they
+/// are in the given Range
+fn numbers(directive: &str, arguments: &str, min: i64, max: i64) -> Result<Vec<i64>, String> {
+    split_operands(arguments)
+        .into_iter()
+        .map(|raw| number_in(directive, raw, min, max))
+        .collect()
+}
+
+/// Parses a single Number of the Directive and checks that it is in the
+/// given Range
+fn number_in(directive: &str, raw: &str, min: i64, max: i64) -> Result<i64, String> {
+    let value = number(directive, raw)?;
+    if value < min || value > max {
+        return Err(format!("{} does not fit into `{}`", value, directive));
+    }
+    Ok(value)
+}
+
+/// Parses a quoted String with the usual Escape-Sequences
+fn parse_string(raw: &str) -> Result<Vec<u8>, String> {
+    let inner = raw
+        .strip_prefix('"')
+        .and_then(|raw| raw.strip_suffix('"'))
+        .filter(|inner| !inner.is_empty() || raw.len() == 2)
+        .ok_or_else(|| format!("expected a string, found `{}`", raw))?;
+
+    unescape(inner)
+}
+
+/// Replaces the Escape-Sequences in the Content of a String or
+/// Character-Literal
+fn unescape(inner: &str) -> Result<Vec<u8>, String> {
+    let mut result = Vec::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buffer = [0; 4];
+            result.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+            continue;
+        }
+
+        let escaped = match chars.next() {
+            Some('n') => b'\n',
+            Some('t') => b'\t',
+            Some('r') => b'\r',
+            Some('0') => 0,
+            Some('\\') => b'\\',
+            Some('"') => b'"',
+            Some('\'') => b'\'',
+            Some(other) => return Err(format!("unknown escape sequence `\\{}`", other)),
+            None => return Err("unterminated escape sequence".to_owned()),
+        };
+        result.push(escaped);
+    }
+
+    Ok(result)
+}
+
+/// Checks that every Label is only defined once and that every referenced
+/// Label is actually defined
+fn check_labels(statements: &[Statement]) -> Result<(), ParseError> {
+    let mut defined = HashSet::new();
+    for statement in statements {
+        if let Kind::Label(name) = &statement.kind {
+            if !defined.insert(name.as_str()) {
+                return Err(ParseError {
+                    line: statement.line,
+                    message: format!("the label `{}` is already defined", name),
+                });
+            }
+        }
+    }
+
+    // Global Labels that are not defined here are defined in another Object
+    for statement in statements {
+        if let Kind::Expanded(instr) = &statement.kind {
+            for tmp in instr {
+                if let asm::Instruction::Global(name) = tmp {
+                    defined.insert(name.as_str());
+                }
+            }
+        }
+    }
+
+    for statement in statements {
+        let referenced: Vec<&str> = match &statement.kind {
+            Kind::Delayed(_, name) => vec![name],
+            Kind::Instruction(instr) => instr_label(instr).into_iter().collect(),
+            Kind::Expanded(instr) => instr.iter().filter_map(instr_label).collect(),
+            Kind::Label(_) => Vec::new(),
+        };
+
+        if let Some(name) = referenced.iter().find(|name| !defined.contains(*name)) {
+            return Err(ParseError {
+                line: statement.line,
+                message: format!("unknown label `{}`", name),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn instr_label(instr: &asm::Instruction) -> Option<&str> {
+    match instr {
+        asm::Instruction::JmpLabel(name)
+        | asm::Instruction::JsrLabel(name)
+        | asm::Instruction::MovLabel(_, name)
+        | asm::Instruction::LabelAddress(name)
+        | asm::Instruction::MovALabel(name)
+        | asm::Instruction::LoadWord(_, name)
+        | asm::Instruction::LoadLong(_, name) => Some(name),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves() {
+        let input = "mov r1, r2
+        mov #-1, r3
+        mov.l #0x12345678, r4
+        mov.l #data, r5
+        mov.l @r1+, r2
+        mov.l @r15+, r2
+        mov.l r2, @-r15
+        mov.b @r1, r0
+        mov.w r0, @r1
+        data:";
+
+        let expected = vec![
+            asm::Instruction::Mov(2, 1),
+            asm::Instruction::MovI(3, 0xff),
+            asm::Instruction::MovConst(4, 0x12345678),
+            asm::Instruction::MovLabel(5, "data".to_owned()),
+            asm::Instruction::PopOther(2, 1),
+            asm::Instruction::Pop(2),
+            asm::Instruction::Push(2),
+            asm::Instruction::MovB(asm::Operand::Register(0), asm::Operand::AtRegister(1)),
+            asm::Instruction::MovW(asm::Operand::AtRegister(1), asm::Operand::Register(0)),
+            asm::Instruction::Label("data".to_owned()),
+        ];
+
+        assert_eq!(Ok(expected), parse(input));
+    }
+
+    #[test]
+    fn system_instructions() {
+        let input = "and #0x0f, r0
+        exts.b r1, r2
+        mac.l @r1+, @r2+
+        ldc r3, gbr
+        stc sr, r4
+        trapa #32";
+
+        let expected = vec![
+            asm::Instruction::AndI(0x0f),
+            asm::Instruction::ExtsB(2, 1),
+            asm::Instruction::MacL(2, 1),
+            asm::Instruction::Ldc(asm::ControlRegister::Gbr, 3),
+            asm::Instruction::Stc(4, asm::ControlRegister::Sr),
+            asm::Instruction::Trapa(32),
+        ];
+
+        assert_eq!(Ok(expected), parse(input));
+        assert!(parse("and #1, r1").is_err());
+    }
+
+    #[test]
+    fn addressing_modes() {
+        let input = "mov.b @r1+, r2
+        mov.w r2, @-r1
+        mov.l @(8, r1), r2
+        mov.b r0, @(3, r1)
+        mov.w @(r0, r1), r2
+        mov.l @(12, gbr), r0
+        mov.w @(4, pc), r3
+        or.b #1, @(r0, gbr)";
+
+        let expected = vec![
+            asm::Instruction::MovB(asm::Operand::Register(2), asm::Operand::PostIncrement(1)),
+            asm::Instruction::MovW(asm::Operand::PreDecrement(1), asm::Operand::Register(2)),
+            asm::Instruction::MovL(
+                asm::Operand::Register(2),
+                asm::Operand::Displacement4Reg(2, 1),
+            ),
+            asm::Instruction::MovB(
+                asm::Operand::Displacement4Reg(3, 1),
+                asm::Operand::Register(0),
+            ),
+            asm::Instruction::MovW(asm::Operand::Register(2), asm::Operand::OffsetR0(1)),
+            asm::Instruction::MovL(asm::Operand::Register(0), asm::Operand::DisplacementGBR(3)),
+            asm::Instruction::MovW(asm::Operand::Register(3), asm::Operand::Displacement8(2)),
+            asm::Instruction::OrB(asm::Operand::OffsetR0GBR, 1),
+        ];
+
+        assert_eq!(Ok(expected), parse(input));
+    }
+
+    #[test]
+    fn unsupported_addressing_modes() {
+        // Only R0 can be the Target of a Byte-Load with a Displacement
+        assert!(parse("mov.b @(1, r1), r2").is_err());
+        // The Displacement has to be a Multiple of the Size
+        assert!(parse("mov.l @(6, r1), r2").is_err());
+        assert!(parse("mov.l @(64, r1), r2").is_err());
+        assert!(parse("mov.l r1, r2").is_err());
+    }
+
+    #[test]
+    fn labels_and_comments() {
+        let input = "# A simple Loop
+        start: add #1, r0 ! Increment
+        loop: dt r1; bf loop
+        rts
+        nop";
+
+        let expected = vec![
+            asm::Instruction::Label("start".to_owned()),
+            asm::Instruction::AddI(0, 1),
+            asm::Instruction::Label("loop".to_owned()),
+            asm::Instruction::Dt(1),
+            asm::Instruction::BT(1),
+            asm::Instruction::JmpLabel("loop".to_owned()),
+            asm::Instruction::Rts,
+            asm::Instruction::Nop,
+        ];
+
+        assert_eq!(Ok(expected), parse(input));
+    }
+
+    #[test]
+    fn block_comments() {
+        let input = "add #1, r0 /* first */ ; add #2, r0
+        /* a comment over
+        two lines ! with /* inside */ dt r1
+        .ascii \"/*\" ! /* not a comment
+        add #3, r0";
+
+        let expected = vec![
+            asm::Instruction::AddI(0, 1),
+            asm::Instruction::AddI(0, 2),
+            asm::Instruction::Dt(1),
+            asm::Instruction::Data(vec![b'/', b'*']),
+            asm::Instruction::AddI(0, 3),
+        ];
+
+        assert_eq!(Ok(expected), parse(input));
+        assert_eq!(
+            Err(ParseError {
+                line: 2,
+                message: "unterminated comment".to_owned()
+            }),
+            parse("nop\n/* nop\nnop")
+        );
+    }
+
+    #[test]
+    fn pc_relative() {
+        let input = "cmp/eq #-1, r0
+        mova table, r0
+        mova @(8, pc), r0
+        mov.l table, r1
+        mov.w half, r2
+        .align 2
+        table: .long table, 1
+        half: .word 2";
+
+        let expected = vec![
+            asm::Instruction::CmpEqI(0xff),
+            asm::Instruction::MovALabel("table".to_owned()),
+            asm::Instruction::MovA(2),
+            asm::Instruction::LoadLong(1, "table".to_owned()),
+            asm::Instruction::LoadWord(2, "half".to_owned()),
+            asm::Instruction::Align(4),
+            asm::Instruction::Label("table".to_owned()),
+            asm::Instruction::LabelAddress("table".to_owned()),
+            asm::Instruction::Data(vec![0, 0, 0, 1]),
+            asm::Instruction::Label("half".to_owned()),
+            asm::Instruction::Data(vec![0, 2]),
+        ];
+
+        assert_eq!(Ok(expected), parse(input));
+        assert!(parse("mova table, r1\ntable:").is_err());
+        assert!(parse(".long missing").is_err());
+    }
+
+    #[test]
+    fn delay_slots() {
+        let input = "target:
+        bra target
+        nop
+        bsr target
+        add r1, r0
+        bt/s target
+        add r2, r0";
+
+        let expected = vec![
+            asm::Instruction::Label("target".to_owned()),
+            asm::Instruction::JmpLabel("target".to_owned()),
+            asm::Instruction::Add(0, 1),
+            asm::Instruction::JsrLabel("target".to_owned()),
+            asm::Instruction::BF(2),
+            asm::Instruction::Add(0, 2),
+            asm::Instruction::JmpLabel("target".to_owned()),
+            asm::Instruction::Add(0, 2),
+        ];
+
+        assert_eq!(Ok(expected), parse(input));
+    }
+
+    #[test]
+    fn directives() {
+        let input = ".section .rodata
+        .byte 1, -1
+        .word 0x1234
+        .align 2
+        .long 0x12345678
+        .ascii \"a;!\\n\"
+        .asciz \"b\"
+        .byte 'a', ';', '\\n', ','
+        .ascii \"c,d\"
+        .global main
+        .text";
+
+        let expected = vec![
+            asm::Instruction::Section(asm::Section::RoData),
+            asm::Instruction::Data(vec![1, 0xff]),
+            asm::Instruction::Data(vec![0x12, 0x34]),
+            asm::Instruction::Align(4),
+            asm::Instruction::Data(vec![0x12, 0x34, 0x56, 0x78]),
+            asm::Instruction::Data(vec![b'a', b';', b'!', b'\n']),
+            asm::Instruction::Data(vec![b'b', 0]),
+            asm::Instruction::Data(vec![b'a', b';', b'\n', b',']),
+            asm::Instruction::Data(vec![b'c', b',', b'd']),
+            asm::Instruction::Global("main".to_owned()),
+            asm::Instruction::Section(asm::Section::Text),
+        ];
+
+        assert_eq!(Ok(expected), parse(input));
+    }
+
+    #[test]
+    fn global_labels() {
+        // Global Labels do not have to be defined, as they can be defined
+        // by another Object
+        let input = ".globl main, puts
+        main:
+            bsr puts
+            nop";
+
+        let expected = vec![
+            asm::Instruction::Global("main".to_owned()),
+            asm::Instruction::Global("puts".to_owned()),
+            asm::Instruction::Label("main".to_owned()),
+            asm::Instruction::JsrLabel("puts".to_owned()),
+        ];
+
+        assert_eq!(Ok(expected), parse(input));
+    }
+
+    #[test]
+    fn errors() {
+        let error = |line: usize, message: &str| {
+            Err(ParseError {
+                line,
+                message: message.to_owned(),
+            })
+        };
+
+        assert_eq!(
+            error(2, "unknown instruction `frob`"),
+            parse("nop\nfrob r1")
+        );
+        assert_eq!(
+            error(1, "unsupported operands for `add`"),
+            parse("add @r1, r0")
+        );
+        assert_eq!(
+            error(
+                1,
+                "the immediate 200 does not fit into 8 Bits, use `mov.l #200, rn` instead"
+            ),
+            parse("mov #200, r0")
+        );
+        assert_eq!(error(2, "unknown label `end`"), parse("nop\nbra end\nnop"));
+        assert_eq!(
+            error(2, "the label `a` is already defined"),
+            parse("a:\na:")
+        );
+        assert_eq!(
+            error(
+                1,
+                "expected an Instruction for the Delay-Slot of the Branch"
+            ),
+            parse("a: bra a\nrts")
+        );
+        assert_eq!(error(1, "unknown directive `.foo`"), parse(".foo 1"));
+        assert_eq!(
+            error(1, "256 does not fit into `.byte`"),
+            parse(".byte 256")
+        );
+    }
+
+    #[test]
+    fn assemble_source() {
+        let input = "mov #3, r1
+        loop:
+        dt r1
+        bf/s loop
+        add #2, r0
+        rts
+        nop";
+
+        let instructions = parse(input).unwrap();
+        let expected: Vec<u8> = [
+            asm::Instruction::MovI(1, 3),
+            asm::Instruction::Dt(1),
+            asm::Instruction::BT(2),
+            asm::Instruction::AddI(0, 2),
+            asm::Instruction::BRA((5 ^ 0xffff) + 1),
+            asm::Instruction::Nop,
+            asm::Instruction::AddI(0, 2),
+            asm::Instruction::Rts,
+            asm::Instruction::Nop,
+        ]
+        .iter()
+        .flat_map(|instr| instr.to_byte().to_vec())
+        .collect();
+
+        assert_eq!(Ok(expected), crate::assemble(instructions));
+    }
+
+    #[test]
+    fn assemble_pc_relative() {
+        let input = "mova table, r0
+        mov.l table, r1
+        mov.w half, r2
+        rts
+        nop
+        half: .word 0x1234
+        table: .long 0x12345678";
+
+        let expected: Vec<u8> = [
+            asm::Instruction::MovA(2),
+            asm::Instruction::MovL(asm::Operand::Register(1), asm::Operand::Displacement8(2)),
+            asm::Instruction::MovW(asm::Operand::Register(2), asm::Operand::Displacement8(1)),
+            asm::Instruction::Rts,
+            asm::Instruction::Nop,
+            asm::Instruction::Literal(0x12, 0x34),
+            asm::Instruction::Literal(0x12, 0x34),
+            asm::Instruction::Literal(0x56, 0x78),
+        ]
+        .iter()
+        .flat_map(|instr| instr.to_byte().to_vec())
+        .collect();
+
+        assert_eq!(Ok(expected), crate::assemble(parse(input).unwrap()));
+
+        // The Label of a Long has to be aligned
+        let misaligned = parse("mov.l value, r1\nnop\nnop\nvalue: .long 1").unwrap();
+        assert!(matches!(
+            crate::assemble(misaligned),
+            Err(crate::AssembleError::LabelOutOfRange(_))
+        ));
+    }
+
+    #[test]
+    fn disassembled_round_trip() {
+        // Every Value decodes to an Instruction or a Literal, and the
+        // disassembled Source of all of them has to assemble to the exact
+        // same ByteCode again
+        let code: Vec<u8> = (0..=u16::MAX).flat_map(|raw| raw.to_be_bytes()).collect();
+        let source: Vec<String> = (0..=u16::MAX)
+            .map(|raw| asm::Instruction::parse(raw).to_string())
+            .collect();
+
+        let instructions = parse(&source.join("\n")).unwrap();
+        assert_eq!(Ok(code), crate::assemble(instructions));
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use crate::asm;
+
+/// The biggest Displacement, in 4-Byte Steps, of a PC-relative `MOV.L`
+const MAX_DISPLACEMENT: u32 = 255;
+
+/// The Size in Bytes of the Branch, and its Delay-Slot, that skips over
+/// a Pool placed in the middle of the Code
+pub const ISLAND_BRANCH_SIZE: u32 = 4;
+
+/// A 32-Bit Value that is stored in a Literal-Pool
+#[derive(Debug, PartialEq, Clone)]
+pub enum Value {
+    /// A simple Constant
+    Constant(u32),
+    /// The absolute Address of the Label
+    Address(String),
+}
+
+impl Value {
+    /// The Register and the Value for Instructions that load their Value
+    /// from a Literal-Pool
+    pub fn from_instr(instr: &asm::Instruction) -> Option<(u8, Self)> {
+        match instr {
+            asm::Instruction::MovConst(register, value) => {
+                Some((*register, Self::Constant(*value)))
+            }
+            asm::Instruction::MovLabel(register, name) => {
+                Some((*register, Self::Address(name.to_owned())))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A Literal-Pool that is placed between the Instructions
+#[derive(Debug, PartialEq, Clone)]
+pub struct Pool {
+    /// Whether or not the Pool is placed in the middle of the Code and
+    /// therefore needs a Branch over it
+    pub island: bool,
+    /// The Offset at which the Pool starts, including the Branch of
+    /// an Island
+    pub start: u32,
+    /// The Values in the Pool
+    pub values: Vec<Value>,
+}
+
+impl Pool {
+    /// The Offset of the first Value, which is always 4-Byte aligned
+    pub fn values_start(&self) -> u32 {
+        values_start(self.start, self.island)
+    }
+
+    /// The Offset right after the Pool
+    pub fn end(&self) -> u32 {
+        self.values_start() + self.values.len() as u32 * 4
+    }
+}
+
+fn values_start(start: u32, island: bool) -> u32 {
+    let start = if island {
+        start + ISLAND_BRANCH_SIZE
+    } else {
+        start
+    };
+    (start + 3) & !3
+}
+
+/// The Loads that still need a Pool for their Values
+#[derive(Debug, Default)]
+pub struct Pending {
+    /// The Index and Offset of every Load, with the Index of its Value
+    loads: Vec<(usize, u32, usize)>,
+    /// The Values for the next Pool, every Value is only stored once
+    values: Vec<Value>,
+}
+
+impl Pending {
+    pub fn is_empty(&self) -> bool {
+        self.loads.is_empty()
+    }
+
+    /// Adds the Load at the given Index and Offset
+    pub fn add(&mut self, index: usize, offset: u32, value: Value) {
+        let position = match self.values.iter().position(|other| *other == value) {
+            Some(position) => position,
+            None => {
+                self.values.push(value);
+                self.values.len() - 1
+            }
+        };
+        self.loads.push((index, offset, position));
+    }
+
+    /// Whether or not every Load could still reach its Value, if the Pool
+    /// started at the given Offset and got the given Number of additional
+    /// Values. Every Load is checked against the last Value, as its own
+    /// Value could end up there
+    pub fn reachable(&self, start: u32, island: bool, additional: usize) -> bool {
+        let last = (self.values.len() + additional).saturating_sub(1) as u32;
+        let target = values_start(start, island) + last * 4;
+
+        self.loads
+            .iter()
+            .all(|(_, offset, _)| target.saturating_sub((offset & !3) + 4) / 4 <= MAX_DISPLACEMENT)
+    }
+
+    /// Places the Pool at the given Offset and stores the Offset of the
+    /// Value for every Load
+    pub fn place(&mut self, start: u32, island: bool, loads: &mut HashMap<usize, u32>) -> Pool {
+        let values_start = values_start(start, island);
+        for (index, _, position) in self.loads.drain(..) {
+            loads.insert(index, values_start + position as u32 * 4);
+        }
+
+        Pool {
+            island,
+            start,
+            values: std::mem::take(&mut self.values),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_values() {
+        let mut pending = Pending::default();
+        pending.add(0, 0, Value::Constant(0x12345678));
+        pending.add(1, 2, Value::Address("test".to_owned()));
+        pending.add(2, 4, Value::Constant(0x12345678));
+
+        let mut loads = HashMap::new();
+        let pool = pending.place(6, false, &mut loads);
+
+        assert_eq!(
+            vec![
+                Value::Constant(0x12345678),
+                Value::Address("test".to_owned())
+            ],
+            pool.values
+        );
+        assert_eq!(Some(&8), loads.get(&0));
+        assert_eq!(Some(&12), loads.get(&1));
+        assert_eq!(Some(&8), loads.get(&2));
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn reachable() {
+        let mut pending = Pending::default();
+        pending.add(0, 2, Value::Constant(0x12345678));
+
+        // The Value can be at most 1020 Bytes after the aligned PC + 4
+        assert!(pending.reachable(1024, false, 0));
+        assert!(!pending.reachable(1026, false, 0));
+        assert!(pending.reachable(1020, true, 0));
+        assert!(!pending.reachable(1022, true, 0));
+        assert!(!pending.reachable(1024, false, 1));
+    }
+
+    #[test]
+    fn island_alignment() {
+        let pool = Pool {
+            island: true,
+            start: 6,
+            values: vec![Value::Constant(1)],
+        };
+
+        assert_eq!(12, pool.values_start());
+        assert_eq!(16, pool.end());
+    }
+}
@@ -0,0 +1,478 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{asm, entry, pool};
+
+/// The Range of Displacements, in Instructions, that can be encoded in the
+/// 12-Bit Displacement of a `BRA` or `BSR`
+const BRANCH_RANGE: std::ops::RangeInclusive<i64> = -2048..=2047;
+/// The Range of Displacements, in Instructions, that can be encoded in the
+/// 8-Bit Displacement of a conditional Branch
+const CONDITIONAL_RANGE: std::ops::RangeInclusive<i64> = -128..=127;
+
+/// The final Location of every Instruction and Label, after all the Jumps
+/// have been relaxed
+#[derive(Debug)]
+pub struct Layout {
+    /// The Offset of every Instruction, with one extra Entry for the End
+    pub offsets: Vec<u32>,
+    /// The Offset of every Label
+    pub labels: HashMap<String, u32>,
+    /// The Jumps that are too far for a simple Branch and the conditional
+    /// Branches that are too far for their short Displacement
+    pub far: HashSet<usize>,
+    /// The far conditional Branches whose Target is even out of Range for
+    /// the `BRA`, which skip over a far Jump instead
+    pub distant: HashSet<usize>,
+    /// The Offsets of the Veneers for Entries of a Jump-Table that are too
+    /// far for a simple Branch, by the Index of the Entry
+    pub veneers: HashMap<usize, u32>,
+    /// The Index of the Instruction every relative Branch in the Input
+    /// was targeting, by the Index of the Branch
+    pub branches: HashMap<usize, usize>,
+    /// The Entries of all the Jump-Tables
+    pub tables: Vec<std::ops::Range<usize>>,
+    /// The Literal-Pools, by the Index of the Instruction they follow
+    pub pools: HashMap<usize, pool::Pool>,
+    /// The Offset of the Value every Load from a Literal-Pool uses, by the
+    /// Index of the Load
+    pub loads: HashMap<usize, u32>,
+}
+
+/// Lays out the Instructions and switches every Jump whose Target is out
+/// of Range for a `BRA` or `BSR`, or not defined at all, to its far Form.
+/// A `BT` or `BF` whose Target is out of Range is inverted to skip over a
+/// `BRA` to its Target instead, or over a far Jump if the Target is out of
+/// Range for the `BRA` as well. As far Jumps are bigger, this can push
+/// other Jumps out of Range as well, so the Layout is repeated until no
+/// more Jumps change
+pub fn relax(instr: &[asm::Instruction]) -> Layout {
+    let tables = find_tables(instr);
+
+    // The relative Branches in the Input were generated without knowing
+    // about any Pools or far Jumps
+    let mut far = HashSet::new();
+    let mut distant = HashSet::new();
+    let initial = layout(instr, &far, &distant, &tables, false);
+    let branches = find_branches(instr, &initial.offsets);
+
+    loop {
+        let current = layout(instr, &far, &distant, &tables, true);
+
+        let mut changed = false;
+        for (index, tmp) in instr.iter().enumerate() {
+            let name = match tmp {
+                asm::Instruction::JmpLabel(name) | asm::Instruction::JsrLabel(name) => name,
+                _ => continue,
+            };
+            if far.contains(&index) {
+                continue;
+            }
+
+            // Labels that are not defined could be placed anywhere by the
+            // Linker
+            let reachable = match current.labels.get(name) {
+                Some(target) => in_range(current.offsets[index], *target),
+                None => false,
+            };
+            if !reachable {
+                far.insert(index);
+                changed = true;
+            }
+        }
+        for (index, target) in branches.iter() {
+            let relaxable = matches!(
+                instr[*index],
+                asm::Instruction::BT(_) | asm::Instruction::BF(_)
+            );
+            if !relaxable || distant.contains(index) {
+                continue;
+            }
+
+            let start = current.offsets[*index];
+            let target = current.offsets[*target];
+            if !far.contains(index) && !CONDITIONAL_RANGE.contains(&displacement(start, target)) {
+                far.insert(*index);
+                changed = true;
+            }
+            // The `BRA` comes right after the inverted Branch
+            if far.contains(index) && !in_range(start + 2, target) {
+                distant.insert(*index);
+                changed = true;
+            }
+        }
+
+        if !changed {
+            return Layout {
+                offsets: current.offsets,
+                labels: current.labels,
+                far,
+                distant,
+                veneers: current.veneers,
+                branches,
+                tables,
+                pools: current.pools,
+                loads: current.loads,
+            };
+        }
+    }
+}
+
+/// Whether or not a Branch at the given Start can reach the Target
+fn in_range(start: u32, target: u32) -> bool {
+    BRANCH_RANGE.contains(&displacement(start, target))
+}
+
+/// The Displacement, in Instructions, of a Branch at the given Start to
+/// the Target
+fn displacement(start: u32, target: u32) -> i64 {
+    (target as i64 - (start as i64 + 4)) / 2
+}
+
+/// The Layout of the Instructions for a fixed Set of far Jumps
+struct Partial {
+    offsets: Vec<u32>,
+    labels: HashMap<String, u32>,
+    veneers: HashMap<usize, u32>,
+    pools: HashMap<usize, pool::Pool>,
+    loads: HashMap<usize, u32>,
+}
+
+/// Calculates the Offsets of all the Instructions, Labels, Veneers and
+/// Literal-Pools, when the given Jumps use their far Form.
+///
+/// A Pool is placed after every unconditional Jump, where it is never
+/// executed, and at the End of the Code. If a Load would otherwise be too
+/// far away from its Value, the Pool is placed right in the Code with a
+/// Branch over it
+fn layout(
+    instr: &[asm::Instruction],
+    far: &HashSet<usize>,
+    distant: &HashSet<usize>,
+    tables: &[std::ops::Range<usize>],
+    with_pools: bool,
+) -> Partial {
+    let mut offsets = Vec::with_capacity(instr.len() + 1);
+    let mut labels = HashMap::new();
+    let mut veneers = HashMap::new();
+    let mut pools = HashMap::new();
+    let mut loads = HashMap::new();
+
+    // Only the Text-Section, which comes first, contains Code
+    let text_end = instr
+        .iter()
+        .position(|tmp| matches!(tmp, asm::Instruction::Section(_)))
+        .unwrap_or(instr.len());
+
+    let size = |index: usize, offset: u32| {
+        let in_table = tables.iter().any(|table| table.contains(&index));
+        match &instr[index] {
+            asm::Instruction::JmpLabel(_) if far.contains(&index) && !in_table => {
+                entry::FAR_JUMP_SIZE
+            }
+            asm::Instruction::JsrLabel(_) if far.contains(&index) => entry::FAR_CALL_SIZE,
+            asm::Instruction::BT(_) | asm::Instruction::BF(_) if distant.contains(&index) => {
+                entry::DISTANT_BRANCH_SIZE
+            }
+            asm::Instruction::BT(_) | asm::Instruction::BF(_) if far.contains(&index) => {
+                entry::FAR_BRANCH_SIZE
+            }
+            tmp => entry::instr_size(tmp, offset),
+        }
+    };
+    // The far Entries of a Jump-Table, which get a Veneer after its End
+    let far_entries = |index: usize| {
+        tables
+            .iter()
+            .filter(|table| table.end == index + 1)
+            .flat_map(|table| table.clone())
+            .filter(|entry| far.contains(entry))
+            .collect::<Vec<_>>()
+    };
+
+    let mut pending = pool::Pending::default();
+    let mut current = 0;
+    for (index, tmp) in instr.iter().enumerate() {
+        if with_pools && !pending.is_empty() && island_allowed(instr, tables, index) {
+            // The Pool has to be placed now, if it could no longer be
+            // reached after the next Point at which it can be placed
+            let mut end = current;
+            let mut additional = 0;
+            for next in index..text_end {
+                if next > index && island_allowed(instr, tables, next) {
+                    break;
+                }
+                if pool::Value::from_instr(&instr[next]).is_some() {
+                    additional += 1;
+                }
+                end += size(next, end);
+                end += far_entries(next).len() as u32 * entry::FAR_JUMP_SIZE;
+            }
+
+            if !pending.reachable(end, true, additional) {
+                let placed = pending.place(current, true, &mut loads);
+                current = placed.end();
+                pools.insert(index - 1, placed);
+            }
+        }
+
+        offsets.push(current);
+        if let asm::Instruction::Label(name) = tmp {
+            labels.insert(name.to_owned(), current);
+        }
+        if let Some((_, value)) = pool::Value::from_instr(tmp) {
+            if with_pools && index < text_end {
+                pending.add(index, current, value);
+            }
+        }
+
+        current += size(index, current);
+
+        // Entries in a Jump-Table always keep their Size, a far Entry
+        // instead branches to a Veneer right after the Table
+        for entry in far_entries(index) {
+            veneers.insert(entry, current);
+            current += entry::FAR_JUMP_SIZE;
+        }
+
+        let pool_point = index + 1 == text_end || unconditional(instr, tables, index);
+        if with_pools && !pending.is_empty() && pool_point {
+            let placed = pending.place(current, false, &mut loads);
+            current = placed.end();
+            pools.insert(index, placed);
+        }
+    }
+    offsets.push(current);
+
+    Partial {
+        offsets,
+        labels,
+        veneers,
+        pools,
+        loads,
+    }
+}
+
+/// Whether or not the Execution never continues after the Instruction at
+/// the given Index, because it is an unconditional Jump or the Delay-Slot
+/// of one
+fn unconditional(
+    instr: &[asm::Instruction],
+    tables: &[std::ops::Range<usize>],
+    index: usize,
+) -> bool {
+    if tables.iter().any(|table| table.contains(&index)) {
+        return false;
+    }
+
+    match &instr[index] {
+        asm::Instruction::JmpLabel(_) => true,
+        asm::Instruction::Label(_) => false,
+        _ if index > 0 => matches!(
+            instr[index - 1],
+            asm::Instruction::BRA(_) | asm::Instruction::Jmp(_) | asm::Instruction::Rts
+        ),
+        _ => false,
+    }
+}
+
+/// Whether or not a Pool with a Branch over it can be placed right before
+/// the Instruction at the given Index, which is not possible in the
+/// Delay-Slot of a Branch or in a Jump-Table
+fn island_allowed(
+    instr: &[asm::Instruction],
+    tables: &[std::ops::Range<usize>],
+    index: usize,
+) -> bool {
+    index > 0
+        && !instr[index - 1].has_delay_slot()
+        && !tables.iter().any(|table| table.contains(&index))
+}
+
+/// Finds all the Jump-Tables, which are made up of all the Jumps directly
+/// following a `BRAF` and its Delay-Slot
+fn find_tables(instr: &[asm::Instruction]) -> Vec<std::ops::Range<usize>> {
+    let mut result = Vec::new();
+
+    for (index, window) in instr.windows(2).enumerate() {
+        if !matches!(window, [asm::Instruction::Braf(_), asm::Instruction::Nop]) {
+            continue;
+        }
+
+        let start = index + 2;
+        let length = instr[start..]
+            .iter()
+            .take_while(|tmp| matches!(tmp, asm::Instruction::JmpLabel(_)))
+            .count();
+        if length > 0 {
+            result.push(start..start + length);
+        }
+    }
+
+    result
+}
+
+/// Finds the Instruction every relative Branch in the Input is targeting,
+/// so they can be adjusted once far Jumps change the Layout
+fn find_branches(instr: &[asm::Instruction], offsets: &[u32]) -> HashMap<usize, usize> {
+    let mut result = HashMap::new();
+
+    for (index, tmp) in instr.iter().enumerate() {
+        let disp = match branch_displacement(tmp) {
+            Some(disp) => disp,
+            None => continue,
+        };
+
+        let target = offsets[index] as i64 + 4 + disp * 2;
+        if let Some(target_index) = offsets.iter().position(|offset| *offset as i64 == target) {
+            result.insert(index, target_index);
+        }
+    }
+
+    result
+}
+
+/// The signed Displacement, in Instructions, of a relative Branch
+fn branch_displacement(instr: &asm::Instruction) -> Option<i64> {
+    match instr {
+        asm::Instruction::BT(disp)
+        | asm::Instruction::BTs(disp)
+        | asm::Instruction::BF(disp)
+        | asm::Instruction::BFs(disp) => Some(*disp as i8 as i64),
+        asm::Instruction::BRA(disp) | asm::Instruction::BSR(disp) => {
+            // Sign-extend the 12-Bit Displacement
+            Some((((*disp as i64) & 0xfff) ^ 0x800) - 0x800)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn near_jump() {
+        let input = vec![
+            asm::Instruction::JmpLabel("end".to_owned()),
+            asm::Instruction::Space(4000),
+            asm::Instruction::Label("end".to_owned()),
+        ];
+
+        let result = relax(&input);
+        assert!(result.far.is_empty());
+        assert_eq!(Some(&4004), result.labels.get("end"));
+    }
+
+    #[test]
+    fn far_jump() {
+        let input = vec![
+            asm::Instruction::JmpLabel("end".to_owned()),
+            asm::Instruction::Space(5000),
+            asm::Instruction::Label("end".to_owned()),
+        ];
+
+        let result = relax(&input);
+        assert!(result.far.contains(&0));
+        assert_eq!(
+            Some(&(5000 + entry::FAR_JUMP_SIZE)),
+            result.labels.get("end")
+        );
+    }
+
+    #[test]
+    fn relaxed_jump_pushes_other_out_of_range() {
+        // The second Jump is only out of Range once the first one grew
+        let input = vec![
+            asm::Instruction::JmpLabel("middle".to_owned()),
+            asm::Instruction::JmpLabel("end".to_owned()),
+            asm::Instruction::Space(4088),
+            asm::Instruction::Label("middle".to_owned()),
+            asm::Instruction::Space(2000),
+            asm::Instruction::Label("end".to_owned()),
+        ];
+
+        let result = relax(&input);
+        assert!(result.far.contains(&0));
+        assert!(result.far.contains(&1));
+    }
+
+    #[test]
+    fn branch_targets() {
+        let input = vec![
+            asm::Instruction::BF(1),
+            asm::Instruction::JmpLabel("end".to_owned()),
+            asm::Instruction::Nop,
+            asm::Instruction::Label("end".to_owned()),
+            asm::Instruction::BRA((0x6 ^ 0xffff) + 1),
+        ];
+
+        let result = relax(&input);
+        assert_eq!(Some(&2), result.branches.get(&0));
+        assert_eq!(Some(&0), result.branches.get(&4));
+    }
+
+    #[test]
+    fn jump_table() {
+        let input = vec![
+            asm::Instruction::Braf(0),
+            asm::Instruction::Nop,
+            asm::Instruction::JmpLabel("first".to_owned()),
+            asm::Instruction::JmpLabel("second".to_owned()),
+            asm::Instruction::Label("first".to_owned()),
+            asm::Instruction::Space(5000),
+            asm::Instruction::Label("second".to_owned()),
+        ];
+
+        let result = relax(&input);
+        assert_eq!(vec![2..4], result.tables);
+        assert!(result.far.contains(&3));
+        // The Entry keeps its Size and the Veneer comes after the Table
+        assert_eq!(Some(&12), result.veneers.get(&3));
+        assert_eq!(
+            Some(&(12 + entry::FAR_JUMP_SIZE)),
+            result.labels.get("first")
+        );
+    }
+
+    #[test]
+    fn far_conditional_branch() {
+        // The Target is only out of Range once the Jump in between grew,
+        // so the Branch is inverted around a `BRA`
+        let input = vec![
+            asm::Instruction::BF(125),
+            asm::Instruction::JmpLabel("end".to_owned()),
+            asm::Instruction::Space(248),
+            asm::Instruction::Nop,
+            asm::Instruction::Space(5000),
+            asm::Instruction::Label("end".to_owned()),
+        ];
+
+        let result = relax(&input);
+        assert_eq!(Some(&3), result.branches.get(&0));
+        assert!(result.far.contains(&0));
+        assert!(result.far.contains(&1));
+        assert_eq!(entry::FAR_BRANCH_SIZE, result.offsets[1]);
+    }
+
+    #[test]
+    fn distant_conditional_branch() {
+        // Once the Jump in between grew, the Alignment moves the Branch
+        // out of Range for a `BRA` to its Target as well
+        let input = vec![
+            asm::Instruction::Space(4088),
+            asm::Instruction::Nop,
+            asm::Instruction::JmpLabel("end".to_owned()),
+            asm::Instruction::Space(2),
+            asm::Instruction::Align(4096),
+            asm::Instruction::BF(0xfa),
+            asm::Instruction::Space(5000),
+            asm::Instruction::Label("end".to_owned()),
+        ];
+
+        let result = relax(&input);
+        assert_eq!(Some(&1), result.branches.get(&5));
+        assert!(result.far.contains(&5));
+        assert!(result.distant.contains(&5));
+        assert_eq!(8192 + entry::DISTANT_BRANCH_SIZE, result.offsets[6]);
+    }
+}
@@ -0,0 +1,31 @@
+use crate::asm;
+
+/// Orders the Instructions by their Sections, so that the Text-Section comes
+/// first, followed by the RoData-, the Data- and then the Bss-Section. The Order of the
+/// Instructions inside of a single Section stays the same
+pub fn order(instr: Vec<asm::Instruction>) -> Vec<asm::Instruction> {
+    let mut text = Vec::with_capacity(instr.len());
+    let mut rodata = vec![asm::Instruction::Section(asm::Section::RoData)];
+    let mut data = vec![asm::Instruction::Section(asm::Section::Data)];
+    let mut bss = vec![asm::Instruction::Section(asm::Section::Bss)];
+
+    let mut current = asm::Section::Text;
+    for tmp in instr {
+        if let asm::Instruction::Section(section) = tmp {
+            current = section;
+            continue;
+        }
+
+        match current {
+            asm::Section::Text => text.push(tmp),
+            asm::Section::RoData => rodata.push(tmp),
+            asm::Section::Data => data.push(tmp),
+            asm::Section::Bss => bss.push(tmp),
+        };
+    }
+
+    text.append(&mut rodata);
+    text.append(&mut data);
+    text.append(&mut bss);
+    text
+}
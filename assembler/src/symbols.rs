@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+
+use crate::parser::ParseError;
+
+/// The absolute Addresses of the Labels in the assembled ByteCode, which
+/// can be stored next to it so a Listing can show the Names again
+#[derive(Debug, Default, PartialEq)]
+pub struct Symbols {
+    by_address: BTreeMap<u32, Vec<String>>,
+}
+
+impl Symbols {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, address: u32, name: String) {
+        let names = self.by_address.entry(address).or_default();
+        names.push(name);
+        names.sort();
+    }
+
+    /// All the Names of the Labels at the given Address
+    pub fn at(&self, address: u32) -> &[String] {
+        self.by_address
+            .get(&address)
+            .map(|names| names.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// The Address of the Label with the given Name
+    pub fn address(&self, name: &str) -> Option<u32> {
+        self.iter()
+            .find(|(_, other)| *other == name)
+            .map(|(address, _)| address)
+    }
+
+    /// All the Labels with their Address, ordered by their Address
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &str)> {
+        self.by_address
+            .iter()
+            .flat_map(|(address, names)| names.iter().map(move |name| (*address, name.as_str())))
+    }
+
+    /// Serializes the Symbols into a Symbol-Map, with one `<address> <name>`
+    /// Pair per Line
+    pub fn serialize(&self) -> String {
+        let mut result = String::new();
+        for (address, names) in self.by_address.iter() {
+            for name in names {
+                result.push_str(&format!("0x{:08x} {}\n", address, name));
+            }
+        }
+        result
+    }
+
+    /// Parses a Symbol-Map, as generated by `serialize`
+    pub fn parse(content: &str) -> Result<Self, ParseError> {
+        let mut result = Self::new();
+
+        for (index, line) in content.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let error = || ParseError {
+                line: line_number,
+                message: format!("expected `<address> <name>`, found `{}`", line),
+            };
+
+            let (address, name) = line.split_once(char::is_whitespace).ok_or_else(error)?;
+            let address = match address.strip_prefix("0x") {
+                Some(hex) => u32::from_str_radix(hex, 16),
+                None => address.parse(),
+            }
+            .map_err(|_| error())?;
+
+            result.insert(address, name.trim().to_owned());
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let mut symbols = Symbols::new();
+        symbols.insert(0x00300010, "loop".to_owned());
+        symbols.insert(0x00300000, "main".to_owned());
+        symbols.insert(0x00300000, "_start".to_owned());
+
+        let serialized = symbols.serialize();
+        assert_eq!(
+            "0x00300000 _start\n0x00300000 main\n0x00300010 loop\n",
+            serialized
+        );
+        assert_eq!(Ok(symbols), Symbols::parse(&serialized));
+    }
+
+    #[test]
+    fn invalid_line() {
+        assert_eq!(
+            Err(ParseError {
+                line: 2,
+                message: "expected `<address> <name>`, found `main`".to_owned()
+            }),
+            Symbols::parse("0x00300010 loop\nmain")
+        );
+    }
+}
@@ -1,28 +1,65 @@
-use std::collections::HashMap;
-
 use crate::asm;
 
-use super::ir;
+use super::mir;
 
-mod expression;
 mod function;
-mod internal;
-mod statement;
+mod globals;
+pub(crate) mod internal;
+mod isel;
+mod machine;
+mod regalloc;
 mod syscall;
 
-pub type Offsets = HashMap<String, u32>;
-pub type Functions = HashMap<String, ir::Function>;
+/// Generates the Assembly that corresponds to the given Program
+/// in the mid-level IR
+pub fn generate(program: mir::Program) -> Vec<asm::Instruction> {
+    let clear_bss = program
+        .globals
+        .iter()
+        .any(|global| global.values.is_empty());
+    let mut result = globals::startup(clear_bss);
+    result.append(&mut generate_program(program, true));
+
+    result
+}
+
+/// Generates the Assembly for a relocatable Object, in which all the
+/// Functions, except the `static` ones, and Globals can be used by other
+/// Objects. Only a Program with
+/// a `main` Function gets the Startup-Code, which then zeroes the combined
+/// Bss-Section of all the linked Objects
+pub fn generate_object(program: mir::Program) -> Vec<asm::Instruction> {
+    let mut result: Vec<asm::Instruction> = program
+        .functions
+        .iter()
+        .filter(|func| !func.attributes.local)
+        .map(|func| func.name.clone())
+        .chain(program.globals.iter().map(|global| global.var.name.clone()))
+        .map(asm::Instruction::Global)
+        .collect();
+
+    if program.functions.iter().any(|func| func.name == "main") {
+        result.append(&mut globals::startup(true));
+    }
+    result.append(&mut generate_program(program, false));
+
+    result
+}
 
-/// Generates the Assembly that corresponds to the given Functions
-/// and general IR
-pub fn generate(mut funcs: Vec<ir::Function>) -> Vec<asm::Instruction> {
-    let mut result = vec![asm::Instruction::JmpLabel("main".to_owned())];
+/// Generates the Functions, the Routines they need and the Globals, the
+/// Start and End of the Bss-Section are only marked with Labels if they
+/// are not defined by the Linker
+fn generate_program(program: mir::Program, bss_labels: bool) -> Vec<asm::Instruction> {
+    let mut result = Vec::new();
 
-    let functions = Functions::new();
-    let mut offsets = HashMap::new();
-    for tmp in funcs.drain(..) {
-        function::generate(&tmp, &mut result, &mut offsets, &functions);
+    for func in program.functions.iter() {
+        function::generate(func, &program.conventions, &mut result);
     }
 
+    let mut routines = internal::runtime::generate(&result);
+    result.append(&mut routines);
+
+    result.append(&mut globals::generate(&program.globals, bss_labels));
+
     result
 }
@@ -1,154 +1,74 @@
-use crate::ir;
+use crate::{backend::internal::get_size, ir};
 
-use super::VarOffset;
+// Every Parameter is pushed onto the Stack as a full 32bit Register
+const PARAM_SIZE: u32 = 4;
 
-mod params;
-mod vars;
-
-/// Calculates the Offsets for Variables and Arguments for the specific
-/// Function and then allows the rest of the backend to easily access
-/// Variables in the function
-pub fn get_offset(func: &ir::Function) -> (VarOffset, u8) {
-    let mut vars = VarOffset::new();
+/// Calculates the Offsets of the Slots relative to the Start of the Local
+/// Variables in the Frame, every Slot is aligned according to its Datatype.
+///
+/// Returns the Offsets and the Size of the Local Variables in the Frame
+pub fn slot_offsets(slots: &[ir::DataType]) -> (Vec<i32>, u32) {
     let mut final_offset = 0;
+    let mut offsets = Vec::with_capacity(slots.len());
+    for datatype in slots.iter() {
+        let offset = get_size::align(final_offset, get_size::alignment(datatype));
+        final_offset = offset + get_size::size(datatype);
+        offsets.push(offset as i32);
+    }
 
-    vars::offsets(&func.3, &mut vars, &mut final_offset);
-    params::offsets(&func.2, final_offset, &mut vars);
+    // Keep the Stack aligned for the Parameters and any following Calls
+    (offsets, get_size::align(final_offset, 4))
+}
 
-    (vars, final_offset)
+/// Calculates the Offsets of the Parameters relative to the first one
+pub fn param_offsets(params: &[ir::DataType]) -> Vec<u32> {
+    let mut current_offset = 0;
+    let mut result = Vec::with_capacity(params.len());
+    for datatype in params.iter() {
+        let size = get_size::size(&datatype);
+        // Structs and Unions can not be passed by Value, which is already
+        // reported by the semantic Checks
+        assert!(size <= PARAM_SIZE, "Parameter too big: {}", size);
+
+        // The Value is stored in the lower Bytes of the pushed Register and
+        // the Stack is Big-Endian, so smaller Values start further in
+        result.push(current_offset + (PARAM_SIZE - size));
+        current_offset += PARAM_SIZE;
+    }
+    result
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{
-        backend::function::{VariableMetaData, VariableSize},
-        ir::Variable,
-    };
 
     #[test]
-    fn no_vars() {
-        let func = ir::Function(
-            "test".to_owned(),
-            ir::DataType::Void,
-            vec![],
-            vec![ir::Statement::SingleExpression(ir::Expression::Call(
-                "test".to_owned(),
-                vec![],
-            ))],
-        );
-
-        let expected_varoffset = VarOffset::new();
-        let expected_total_offset = 0;
-
-        let (result_var, result_total) = get_offset(&func);
-
-        assert_eq!(expected_varoffset, result_var);
-        assert_eq!(expected_total_offset, result_total);
+    fn no_slots() {
+        assert_eq!((vec![], 0), slot_offsets(&[]));
     }
 
     #[test]
-    fn one_var() {
-        let func = ir::Function(
-            "test".to_owned(),
-            ir::DataType::Void,
-            vec![],
-            vec![
-                ir::Statement::Declaration(Variable::new_str("test", ir::DataType::U32)),
-                ir::Statement::Assignment(
-                    Variable::new_str("test", ir::DataType::U32),
-                    ir::Expression::Constant(ir::Value::U32(0)),
-                ),
-            ],
-        );
-
-        let mut expected_varoffset = VarOffset::new();
-        expected_varoffset.insert(
-            "test".to_owned(),
-            VariableMetaData {
-                offset: 0,
-                data_type: ir::DataType::U32,
-                data_size: VariableSize::Long,
-            },
-        );
-        let expected_total_offset = 4;
-
-        let (result_var, result_total) = get_offset(&func);
-
-        assert_eq!(expected_varoffset, result_var);
-        assert_eq!(expected_total_offset, result_total);
+    fn one_slot() {
+        // The Size is rounded up to keep the Stack aligned
+        assert_eq!((vec![0], 4), slot_offsets(&[ir::DataType::U16]));
     }
 
     #[test]
-    fn one_param() {
-        let func = ir::Function(
-            "test".to_owned(),
-            ir::DataType::Void,
-            vec![("var".to_owned(), ir::DataType::U32)],
-            vec![ir::Statement::SingleExpression(ir::Expression::Call(
-                "test".to_owned(),
-                vec![],
-            ))],
-        );
-
-        let mut expected_varoffset = VarOffset::new();
-        expected_varoffset.insert(
-            "var".to_owned(),
-            VariableMetaData {
-                offset: 12,
-                data_type: ir::DataType::U32,
-                data_size: VariableSize::Long,
-            },
-        );
-
-        let expected_total_offset = 0;
-
-        let (result_var, result_total) = get_offset(&func);
-
-        assert_eq!(expected_varoffset, result_var);
-        assert_eq!(expected_total_offset, result_total);
+    fn aligned_slots() {
+        let slots = vec![
+            ir::DataType::Array(Box::new(ir::DataType::U8), 3),
+            ir::DataType::U16,
+            ir::DataType::I32,
+        ];
+
+        assert_eq!((vec![0, 4, 8], 12), slot_offsets(&slots));
     }
 
     #[test]
-    fn one_param_one_var() {
-        let func = ir::Function(
-            "test".to_owned(),
-            ir::DataType::Void,
-            vec![("var".to_owned(), ir::DataType::U32)],
-            vec![
-                ir::Statement::Declaration(Variable::new_str("test", ir::DataType::U32)),
-                ir::Statement::Assignment(
-                    Variable::new_str("test", ir::DataType::U32),
-                    ir::Expression::Constant(ir::Value::U32(0)),
-                ),
-            ],
-        );
-
-        let mut expected_varoffset = VarOffset::new();
-        // The Variable
-        expected_varoffset.insert(
-            "test".to_owned(),
-            VariableMetaData {
-                offset: 0,
-                data_type: ir::DataType::U32,
-                data_size: VariableSize::Long,
-            },
-        );
-        // The Parameter
-        expected_varoffset.insert(
-            "var".to_owned(),
-            VariableMetaData {
-                offset: 16,
-                data_type: ir::DataType::U32,
-                data_size: VariableSize::Long,
-            },
-        );
-
-        let expected_total_offset = 4;
-
-        let (result_var, result_total) = get_offset(&func);
+    fn params() {
+        let params = vec![ir::DataType::U32, ir::DataType::U8, ir::DataType::I16];
 
-        assert_eq!(expected_varoffset, result_var);
-        assert_eq!(expected_total_offset, result_total);
+        // Smaller Parameters are stored in the last Bytes of their Slot
+        assert_eq!(vec![0, 7, 10], param_offsets(&params));
     }
 }
@@ -0,0 +1,174 @@
+use crate::{asm, backend::internal::get_size, ir};
+
+/// The Label at the Start of the Bss-Section, which has the same Name as
+/// the one the Linker defines for the combined Bss-Sections
+const BSS_START: &str = assembler::link::BSS_START;
+/// The Label at the End of the Bss-Section
+const BSS_END: &str = assembler::link::BSS_END;
+
+/// Generates the Data- and Bss-Sections containing all the Globals.
+/// Globals with initial Values are placed in the Data-Section, while all
+/// the others are placed in the Bss-Section and need to be zeroed at startup.
+/// The Start and End of the Bss-Section are marked with Labels, unless they
+/// are defined by the Linker
+pub fn generate(globals: &[ir::Global], bss_labels: bool) -> Vec<asm::Instruction> {
+    let mut data = vec![asm::Instruction::Section(asm::Section::Data)];
+    let mut bss = vec![asm::Instruction::Section(asm::Section::Bss)];
+    if bss_labels {
+        bss.push(asm::Instruction::Label(BSS_START.to_owned()));
+    }
+
+    for global in globals.iter() {
+        let ty = &global.var.ty;
+        let target = if global.values.is_empty() {
+            &mut bss
+        } else {
+            &mut data
+        };
+
+        target.push(asm::Instruction::Align(get_size::alignment(ty)));
+        target.push(asm::Instruction::Label(global.var.name.clone()));
+
+        if global.values.is_empty() {
+            target.push(asm::Instruction::Space(get_size::size(ty)));
+        } else {
+            target.append(&mut to_data(ty, &global.values));
+        }
+    }
+
+    if bss_labels {
+        bss.push(asm::Instruction::Label(BSS_END.to_owned()));
+    }
+
+    data.append(&mut bss);
+    data
+}
+
+/// Converts the initial Values into the Data for a Variable of the given
+/// Type, all the Bytes without a Value are zeroed. The Addresses of Labels
+/// are placed in between the raw Bytes and filled in by the Assembler
+fn to_data(ty: &ir::DataType, values: &[ir::Initializer]) -> Vec<asm::Instruction> {
+    let element_size = match ty {
+        ir::DataType::Array(inner, _) => get_size::size(inner),
+        _ => get_size::size(ty),
+    } as usize;
+
+    let mut result = Vec::new();
+    let mut bytes = Vec::with_capacity(get_size::size(ty) as usize);
+    for value in values.iter() {
+        match value {
+            ir::Initializer::Value(value) => {
+                bytes.extend_from_slice(&value.to_be_bytes()[4 - element_size..]);
+            }
+            ir::Initializer::Address(name) => {
+                if !bytes.is_empty() {
+                    result.push(asm::Instruction::Data(std::mem::take(&mut bytes)));
+                }
+                result.push(asm::Instruction::LabelAddress(name.clone()));
+            }
+        };
+    }
+    let rest = get_size::size(ty) as usize - values.len() * element_size;
+    bytes.resize(bytes.len() + rest, 0);
+    if !bytes.is_empty() {
+        result.push(asm::Instruction::Data(bytes));
+    }
+
+    result
+}
+
+/// Generates the Code that runs before the main Function, which can also
+/// zero the Bss-Section
+pub fn startup(clear_bss: bool) -> Vec<asm::Instruction> {
+    let mut result = Vec::new();
+
+    if clear_bss {
+        result.extend_from_slice(&[
+            asm::Instruction::MovLabel(1, BSS_START.to_owned()),
+            asm::Instruction::MovLabel(2, BSS_END.to_owned()),
+            asm::Instruction::Xor(0, 0),
+            asm::Instruction::Label("__bss_clear".to_owned()),
+            // Done once R1 >= R2
+            asm::Instruction::CmpHs(1, 2),
+            asm::Instruction::BT(3),
+            asm::Instruction::MovB(asm::Operand::AtRegister(1), asm::Operand::Register(0)),
+            asm::Instruction::AddI(1, 1),
+            asm::Instruction::JmpLabel("__bss_clear".to_owned()),
+        ]);
+    }
+
+    result.push(asm::Instruction::JmpLabel("main".to_owned()));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_and_bss() {
+        let globals = vec![
+            ir::Global {
+                var: ir::Variable::new_str("counter", ir::DataType::I32),
+                values: vec![],
+            },
+            ir::Global {
+                var: ir::Variable::new_str(
+                    "table",
+                    ir::DataType::Array(Box::new(ir::DataType::I16), 3),
+                ),
+                values: vec![
+                    ir::Initializer::Value(1),
+                    ir::Initializer::Value(-2i32 as u32),
+                ],
+            },
+        ];
+
+        let expected = vec![
+            asm::Instruction::Section(asm::Section::Data),
+            asm::Instruction::Align(2),
+            asm::Instruction::Label("table".to_owned()),
+            asm::Instruction::Data(vec![0x00, 0x01, 0xff, 0xfe, 0x00, 0x00]),
+            asm::Instruction::Section(asm::Section::Bss),
+            asm::Instruction::Label(BSS_START.to_owned()),
+            asm::Instruction::Align(4),
+            asm::Instruction::Label("counter".to_owned()),
+            asm::Instruction::Space(4),
+            asm::Instruction::Label(BSS_END.to_owned()),
+        ];
+
+        assert_eq!(expected, generate(&globals, true));
+    }
+
+    #[test]
+    fn label_addresses() {
+        let int_ptr = ir::DataType::Ptr(Box::new(ir::DataType::I32));
+        let globals = vec![ir::Global {
+            var: ir::Variable::new_str("table", ir::DataType::Array(Box::new(int_ptr), 3)),
+            values: vec![
+                ir::Initializer::Value(13120),
+                ir::Initializer::Address("counter".to_owned()),
+            ],
+        }];
+
+        let expected = vec![
+            asm::Instruction::Section(asm::Section::Data),
+            asm::Instruction::Align(4),
+            asm::Instruction::Label("table".to_owned()),
+            asm::Instruction::Data(vec![0x00, 0x00, 0x33, 0x40]),
+            asm::Instruction::LabelAddress("counter".to_owned()),
+            asm::Instruction::Data(vec![0x00, 0x00, 0x00, 0x00]),
+            asm::Instruction::Section(asm::Section::Bss),
+        ];
+
+        assert_eq!(expected, generate(&globals, false));
+    }
+
+    #[test]
+    fn startup_without_bss() {
+        assert_eq!(
+            vec![asm::Instruction::JmpLabel("main".to_owned())],
+            startup(false)
+        );
+    }
+}
@@ -1,4 +1,6 @@
-pub mod funcs;
+pub mod comparison;
+pub mod frame;
 pub mod get_size;
 pub mod mov_instr;
+pub mod runtime;
 pub mod store;
@@ -0,0 +1,94 @@
+use crate::{asm, ir};
+
+/// Maps the different Comparisons to the actual Comparison instructions,
+/// which set the T-Bit if the Comparison is true, unless the Comparison
+/// is inverted
+pub fn generate(
+    comp: &ir::Comparison,
+    left_reg: u8,
+    right_reg: u8,
+    signed: bool,
+) -> Option<asm::Instruction> {
+    match comp {
+        ir::Comparison::Equal | ir::Comparison::NotEqual => {
+            Some(asm::Instruction::CmpEq(left_reg, right_reg))
+        }
+        ir::Comparison::LessThan if signed => Some(asm::Instruction::CmpGt(right_reg, left_reg)),
+        ir::Comparison::LessThan if !signed => Some(asm::Instruction::CmpHi(right_reg, left_reg)),
+        ir::Comparison::LessEqual if signed => Some(asm::Instruction::CmpGe(right_reg, left_reg)),
+        ir::Comparison::LessEqual if !signed => Some(asm::Instruction::CmpHs(right_reg, left_reg)),
+        ir::Comparison::GreaterThan if signed => Some(asm::Instruction::CmpGt(left_reg, right_reg)),
+        ir::Comparison::GreaterThan if !signed => {
+            Some(asm::Instruction::CmpHi(left_reg, right_reg))
+        }
+        ir::Comparison::GreaterEqual if signed => {
+            Some(asm::Instruction::CmpGe(left_reg, right_reg))
+        }
+        ir::Comparison::GreaterEqual if !signed => {
+            Some(asm::Instruction::CmpHs(left_reg, right_reg))
+        }
+        _ => None,
+    }
+}
+
+/// Whether or not the T-Bit is cleared, instead of set, if the
+/// Comparison is true
+pub fn inverted(comp: &ir::Comparison) -> bool {
+    matches!(comp, ir::Comparison::NotEqual)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comp_eq_unsigned() {
+        let expected = Some(asm::Instruction::CmpEq(0, 1));
+
+        assert_eq!(expected, generate(&ir::Comparison::Equal, 0, 1, false));
+    }
+    #[test]
+    fn comp_eq_signed() {
+        let expected = Some(asm::Instruction::CmpEq(0, 1));
+
+        assert_eq!(expected, generate(&ir::Comparison::Equal, 0, 1, true));
+    }
+
+    #[test]
+    fn comp_not_equal() {
+        let expected = Some(asm::Instruction::CmpEq(0, 1));
+
+        assert_eq!(expected, generate(&ir::Comparison::NotEqual, 0, 1, true));
+        assert!(inverted(&ir::Comparison::NotEqual));
+        assert!(!inverted(&ir::Comparison::Equal));
+    }
+
+    #[test]
+    fn comp_less_than_unsigned() {
+        let expected = Some(asm::Instruction::CmpHi(1, 0));
+
+        assert_eq!(expected, generate(&ir::Comparison::LessThan, 0, 1, false));
+    }
+    #[test]
+    fn comp_less_than_signed() {
+        let expected = Some(asm::Instruction::CmpGt(1, 0));
+
+        assert_eq!(expected, generate(&ir::Comparison::LessThan, 0, 1, true));
+    }
+
+    #[test]
+    fn comp_greater_equal_unsigned() {
+        let expected = Some(asm::Instruction::CmpHs(0, 1));
+
+        assert_eq!(
+            expected,
+            generate(&ir::Comparison::GreaterEqual, 0, 1, false)
+        );
+    }
+    #[test]
+    fn comp_less_equal_signed() {
+        let expected = Some(asm::Instruction::CmpGe(1, 0));
+
+        assert_eq!(expected, generate(&ir::Comparison::LessEqual, 0, 1, true));
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{asm, backend::internal::store};
+
+/// Loads the Address at the given Offset relative to the FP(R14) into the
+/// given Register
+pub fn address(register: u8, offset: i32) -> Vec<asm::Instruction> {
+    // Small Offsets can be added directly using an Immediate
+    if (-128..=127).contains(&offset) {
+        return vec![
+            asm::Instruction::Mov(register, 14),
+            asm::Instruction::AddI(register, offset as u8),
+        ];
+    }
+
+    let mut result = store::store_u32(register, offset as u32);
+    result.push(asm::Instruction::Add(register, 14));
+    result
+}
+
+/// Moves the SP(R15) by the given Number of Bytes, the Scratch-Register
+/// is needed in case the Amount does not fit into an Immediate
+pub fn move_stack(amount: i32, scratch: u8) -> Vec<asm::Instruction> {
+    if (-128..=127).contains(&amount) {
+        return vec![asm::Instruction::AddI(15, amount as u8)];
+    }
+
+    let mut result = store::store_u32(scratch, amount as u32);
+    result.push(asm::Instruction::Add(15, scratch));
+    result
+}
+
+/// Accesses the 32bit Value at the Offset from the FP(R14) using the
+/// given Move. A single Move only reaches 60 Bytes, so the FP is moved
+/// temporarily for larger Offsets
+pub fn spill_access<F>(offset: u32, access: F) -> Vec<asm::Instruction>
+where
+    F: FnOnce(asm::Operand) -> asm::Instruction,
+{
+    let steps = offset / 64;
+    let displacement = (offset % 64) / 4;
+
+    let mut result: Vec<asm::Instruction> =
+        (0..steps).map(|_| asm::Instruction::AddI(14, 64)).collect();
+    result.push(access(asm::Operand::Displacement4Reg(
+        displacement as u8,
+        14,
+    )));
+    result.extend((0..steps).map(|_| asm::Instruction::AddI(14, (-64i8) as u8)));
+    result
+}
@@ -1,28 +1,183 @@
 use crate::{backend::function::VariableSize, ir};
 
-pub fn var_size(tmp: &ir::DataType) -> VariableSize {
+pub fn assign_size(tmp: &ir::DataType) -> VariableSize {
     match tmp {
         ir::DataType::U32 | ir::DataType::I32 | ir::DataType::Ptr(_) => VariableSize::Long,
         ir::DataType::U16 | ir::DataType::I16 => VariableSize::Word,
-        ir::DataType::Void => VariableSize::Byte,
-        ir::DataType::Array(other_tmp, count) => {
-            let single_size = match var_size(&other_tmp) {
-                VariableSize::Byte => 1,
-                VariableSize::Word => 2,
-                VariableSize::Long => 4,
-                VariableSize::Custom(t) => t,
-            };
-            let size = single_size * count;
-            VariableSize::Custom(size)
+        ir::DataType::U8 | ir::DataType::I8 | ir::DataType::Void => VariableSize::Byte,
+        ir::DataType::Array(other_tmp, _) => assign_size(&other_tmp),
+        ir::DataType::Struct(_, _) | ir::DataType::Union(_, _) => VariableSize::Custom(size(tmp)),
+    }
+}
+
+/// The Number of Bytes a Value of the given Datatype takes up in Memory,
+/// including any Padding needed to keep the Members of Structs aligned.
+/// The Parser already rejects Datatypes that are too large
+pub fn size(tmp: &ir::DataType) -> u32 {
+    checked_size(tmp).unwrap_or(u32::MAX)
+}
+
+/// The Number of Bytes a Value of the given Datatype takes up in Memory,
+/// or None if that does not fit into the Address-Space
+pub fn checked_size(tmp: &ir::DataType) -> Option<u32> {
+    match tmp {
+        ir::DataType::U32 | ir::DataType::I32 | ir::DataType::Ptr(_) => Some(4),
+        ir::DataType::U16 | ir::DataType::I16 => Some(2),
+        ir::DataType::U8 | ir::DataType::I8 | ir::DataType::Void => Some(1),
+        ir::DataType::Array(other_tmp, count) => checked_size(other_tmp)?.checked_mul(*count),
+        ir::DataType::Struct(_, members) => {
+            let end = members.iter().try_fold(0u32, |offset, (_, member_type)| {
+                offset
+                    .checked_next_multiple_of(alignment(member_type))?
+                    .checked_add(checked_size(member_type)?)
+            })?;
+            end.checked_next_multiple_of(alignment(tmp))
+        }
+        ir::DataType::Union(_, members) => {
+            let largest = members
+                .iter()
+                .map(|(_, member_type)| checked_size(member_type))
+                .collect::<Option<Vec<u32>>>()?
+                .into_iter()
+                .max()
+                .unwrap_or(0);
+            largest.checked_next_multiple_of(alignment(tmp))
         }
     }
 }
 
-pub fn assign_size(tmp: &ir::DataType) -> VariableSize {
+/// The Alignment in Bytes, that the Address of a Value of the given
+/// Datatype needs to have
+pub fn alignment(tmp: &ir::DataType) -> u32 {
     match tmp {
-        ir::DataType::U32 | ir::DataType::I32 | ir::DataType::Ptr(_) => VariableSize::Long,
-        ir::DataType::U16 | ir::DataType::I16 => VariableSize::Word,
-        ir::DataType::Void => VariableSize::Byte,
-        ir::DataType::Array(other_tmp, _) => assign_size(&other_tmp),
+        ir::DataType::Array(other_tmp, _) => alignment(other_tmp),
+        ir::DataType::Struct(_, members) | ir::DataType::Union(_, members) => members
+            .iter()
+            .map(|(_, member_type)| alignment(member_type))
+            .max()
+            .unwrap_or(1),
+        _ => size(tmp),
+    }
+}
+
+/// Rounds the Offset up to the next multiple of the Alignment
+pub fn align(offset: u32, alignment: u32) -> u32 {
+    match offset % alignment {
+        0 => offset,
+        rest => offset + alignment - rest,
+    }
+}
+
+/// Finds the Member with the given Name in a Struct or Union and returns
+/// its Offset from the Start of the Struct/Union together with its Datatype
+pub fn member(tmp: &ir::DataType, name: &str) -> Option<(u32, ir::DataType)> {
+    match tmp {
+        ir::DataType::Struct(_, members) => {
+            let mut offset = 0;
+            for (member_name, member_type) in members.iter() {
+                offset = align(offset, alignment(member_type));
+                if member_name == name {
+                    return Some((offset, member_type.clone()));
+                }
+                offset += size(member_type);
+            }
+            None
+        }
+        ir::DataType::Union(_, members) => members
+            .iter()
+            .find(|(member_name, _)| member_name == name)
+            .map(|(_, member_type)| (0, member_type.clone())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sprite() -> ir::DataType {
+        ir::DataType::Struct(
+            "sprite".to_owned(),
+            vec![
+                ("flags".to_owned(), ir::DataType::I16),
+                ("x".to_owned(), ir::DataType::I32),
+                ("frame".to_owned(), ir::DataType::U16),
+            ],
+        )
+    }
+
+    #[test]
+    fn struct_padding() {
+        let sprite = sprite();
+
+        assert_eq!(12, size(&sprite));
+        assert_eq!(4, alignment(&sprite));
+        assert_eq!(Some((0, ir::DataType::I16)), member(&sprite, "flags"));
+        assert_eq!(Some((4, ir::DataType::I32)), member(&sprite, "x"));
+        assert_eq!(Some((8, ir::DataType::U16)), member(&sprite, "frame"));
+        assert_eq!(None, member(&sprite, "y"));
+    }
+
+    #[test]
+    fn nested_struct_array() {
+        let table = ir::DataType::Struct(
+            "table".to_owned(),
+            vec![
+                ("count".to_owned(), ir::DataType::I16),
+                (
+                    "sprites".to_owned(),
+                    ir::DataType::Array(Box::new(sprite()), 3),
+                ),
+            ],
+        );
+
+        assert_eq!(40, size(&table));
+        assert_eq!(
+            Some((4, ir::DataType::Array(Box::new(sprite()), 3))),
+            member(&table, "sprites")
+        );
+    }
+
+    #[test]
+    fn union_size() {
+        let value = ir::DataType::Union(
+            "value".to_owned(),
+            vec![
+                ("half".to_owned(), ir::DataType::U16),
+                (
+                    "parts".to_owned(),
+                    ir::DataType::Array(Box::new(ir::DataType::I16), 3),
+                ),
+            ],
+        );
+
+        assert_eq!(6, size(&value));
+        assert_eq!(2, alignment(&value));
+        assert_eq!(Some((0, ir::DataType::U16)), member(&value, "half"));
+    }
+
+    #[test]
+    fn oversized_array() {
+        let huge = ir::DataType::Array(Box::new(ir::DataType::I32), 0x4000_0000);
+        let nested = ir::DataType::Struct(
+            "nested".to_owned(),
+            vec![
+                ("count".to_owned(), ir::DataType::I16),
+                (
+                    "values".to_owned(),
+                    ir::DataType::Array(Box::new(ir::DataType::I32), 0x3fff_ffff),
+                ),
+            ],
+        );
+
+        assert_eq!(None, checked_size(&huge));
+        assert_eq!(None, checked_size(&nested));
+        assert_eq!(
+            Some(0xffff_fffc),
+            checked_size(&ir::DataType::Array(
+                Box::new(ir::DataType::I32),
+                0x3fff_ffff
+            ))
+        );
     }
 }
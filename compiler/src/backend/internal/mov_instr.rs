@@ -14,6 +14,24 @@ pub fn get_mov(
         VariableSize::Byte => asm::Instruction::MovB(target, source),
         VariableSize::Word => asm::Instruction::MovW(target, source),
         VariableSize::Long => asm::Instruction::MovL(target, source),
-        VariableSize::Custom(_) => unimplemented!("Move for  custom Size"),
+        // Copying Structs and Unions as a whole is already reported by the
+        // semantic Checks
+        VariableSize::Custom(size) => unreachable!("Move for custom Size {}", size),
     }
 }
+
+/// Loads the Value of the given Datatype, which is stored at the Address
+/// in the Address-Register, into the Target-Register. Values smaller than
+/// 32bit are sign- or zero-extended depending on the Datatype
+pub fn get_load(target: u8, address: u8, datatype: &ir::DataType) -> Vec<asm::Instruction> {
+    let target_op = asm::Operand::Register(target);
+    let source_op = asm::Operand::AtRegister(address);
+
+    let mut result = vec![get_mov(target_op, source_op, datatype)];
+    match datatype {
+        ir::DataType::U16 => result.push(asm::Instruction::ExtuW(target, target)),
+        ir::DataType::U8 => result.push(asm::Instruction::ExtuB(target, target)),
+        _ => {}
+    };
+    result
+}
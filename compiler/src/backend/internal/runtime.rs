@@ -0,0 +1,128 @@
+use crate::asm;
+
+/// The Label of the unsigned Division Routine
+///
+/// Expects the Dividend in R0 and the Divisor in R1 and returns
+/// the Quotient in R0 and the Remainder in R1. As this does not follow
+/// the Convention of the `__udivsi3` from libgcc, the Label uses its own
+/// Prefix so it can not be mixed up with it when linking
+pub const UDIV: &str = "__prizm_udiv";
+/// The Label of the signed Division Routine, which truncates the
+/// Quotient towards zero and gives the Remainder the Sign of the Dividend
+///
+/// Expects the Dividend in R0 and the Divisor in R1 and returns
+/// the Quotient in R0 and the Remainder in R1, just like `UDIV`
+pub const SDIV: &str = "__prizm_sdiv";
+
+/// Generates the unsigned 32-Bit Division Routine, using a simple
+/// restoring Shift-Subtract Loop that handles one Bit per Iteration.
+/// All Registers, apart from R0 and R1, are preserved
+pub fn udiv() -> Vec<asm::Instruction> {
+    vec![
+        asm::Instruction::Label(UDIV.to_owned()),
+        asm::Instruction::Push(2),
+        asm::Instruction::Push(3),
+        asm::Instruction::Push(4),
+        // R2 holds the partial Remainder
+        asm::Instruction::Xor(2, 2),
+        // R3 holds the number of Bits left
+        asm::Instruction::MovI(3, 32),
+        // Shift the next Bit of the Dividend into the Remainder, the
+        // freed up lowest Bit of R0 will hold the next Bit of the Quotient
+        asm::Instruction::Shll(0),
+        asm::Instruction::MovT(4),
+        asm::Instruction::Shll(2),
+        asm::Instruction::Or(2, 4),
+        // If the Remainder overflowed, it is definetly bigger than the Divisor
+        asm::Instruction::BT(1),
+        // R2 >= R1 (unsigned)
+        asm::Instruction::CmpHs(2, 1),
+        asm::Instruction::BF(1),
+        asm::Instruction::Sub(2, 1),
+        asm::Instruction::AddI(0, 1),
+        // Loop back to the Shift, until all 32 Bits are handled
+        asm::Instruction::Dt(3),
+        asm::Instruction::BF((12 ^ 0xff) + 1),
+        asm::Instruction::Mov(1, 2),
+        asm::Instruction::Pop(4),
+        asm::Instruction::Pop(3),
+        asm::Instruction::Pop(2),
+        asm::Instruction::Rts,
+        asm::Instruction::Nop,
+    ]
+}
+
+/// Generates the Instructions to negate the Value in the given Register
+/// if the Sign-Bit of the Check-Register is set, R4 is used as a
+/// temporary Register
+fn negate_if_negative(register: u8, check: u8) -> Vec<asm::Instruction> {
+    vec![
+        asm::Instruction::CmpPz(check),
+        asm::Instruction::BT(2),
+        asm::Instruction::Xor(4, 4),
+        asm::Instruction::Sub(4, register),
+        asm::Instruction::Mov(register, 4),
+    ]
+}
+
+/// Generates the signed 32-Bit Division Routine, which divides the
+/// absolute Values using the unsigned Routine and then corrects the
+/// Signs of the Results.
+/// All Registers, apart from R0 and R1, are preserved
+pub fn sdiv() -> Vec<asm::Instruction> {
+    let mut result = vec![
+        asm::Instruction::Label(SDIV.to_owned()),
+        asm::Instruction::Push(2),
+        asm::Instruction::Push(3),
+        asm::Instruction::Push(4),
+        asm::Instruction::PushPR,
+        // Keep the original Values to determine the Signs later on
+        asm::Instruction::Mov(2, 0),
+        asm::Instruction::Mov(3, 1),
+    ];
+
+    result.extend(negate_if_negative(0, 0));
+    result.extend(negate_if_negative(1, 1));
+
+    result.push(asm::Instruction::JsrLabel(UDIV.to_owned()));
+    result.push(asm::Instruction::Nop);
+
+    // The Remainder has the same Sign as the Dividend
+    result.extend(negate_if_negative(1, 2));
+    // The Quotient is negative if exactly one of the Inputs was negative
+    result.push(asm::Instruction::Xor(2, 3));
+    result.extend(negate_if_negative(0, 2));
+
+    result.extend_from_slice(&[
+        asm::Instruction::PopPR,
+        asm::Instruction::Pop(4),
+        asm::Instruction::Pop(3),
+        asm::Instruction::Pop(2),
+        asm::Instruction::Rts,
+        asm::Instruction::Nop,
+    ]);
+
+    result
+}
+
+/// Generates all the Runtime-Routines that are called by the given
+/// Instructions, so they can be placed after the rest of the Program
+pub fn generate(instr: &[asm::Instruction]) -> Vec<asm::Instruction> {
+    let calls = |name: &str| {
+        instr
+            .iter()
+            .any(|tmp| matches!(tmp, asm::Instruction::JsrLabel(target) if target == name))
+    };
+
+    let mut result = Vec::new();
+
+    let needs_sdiv = calls(SDIV);
+    if needs_sdiv {
+        result.extend(sdiv());
+    }
+    if needs_sdiv || calls(UDIV) {
+        result.extend(udiv());
+    }
+
+    result
+}
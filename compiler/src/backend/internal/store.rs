@@ -1,64 +1,23 @@
 use crate::asm;
 
+/// Loads the 16-Bit Value into the Register, zero extended to 32-Bit
 pub fn store_u16(register: u8, value: u16) -> Vec<asm::Instruction> {
-    // Optimize for the value 0
-    if value == 0 {
-        return vec![asm::Instruction::Xor(register, register)];
-    }
-    // If it can be set using a move with immediate value, do that
-    if value <= 0x7f {
-        return vec![asm::Instruction::MovI(register, value as u8)];
-    }
-
-    let bytes = value.to_be_bytes();
-    vec![
-        asm::Instruction::MovW(
-            asm::Operand::Register(register),
-            asm::Operand::Displacement8(2),
-        ),
-        asm::Instruction::Nop,
-        asm::Instruction::BRA(1),
-        asm::Instruction::Nop,
-        asm::Instruction::Literal(bytes[0], bytes[1]),
-    ]
+    store_u32(register, value as u32)
 }
 
+/// Loads the 32-Bit Value into the Register. Values that don't fit into
+/// the Immediate of a single Move are loaded from a Literal-Pool
 pub fn store_u32(register: u8, value: u32) -> Vec<asm::Instruction> {
     // Optimize for the value 0
     if value == 0 {
         return vec![asm::Instruction::Xor(register, register)];
     }
-    // If it can be set using a move with immediate value, do that
-    if value <= 0x7f {
+    // If it can be set using a move with sign-extended immediate value, do that
+    if (-128..=127).contains(&(value as i32)) {
         return vec![asm::Instruction::MovI(register, value as u8)];
     }
-    // If the value is only 16bit or less, only generate that code
-    if value <= 0xffff {
-        return store_u16(register, value as u16);
-    }
 
-    let other_reg = if register == 1 { 0 } else { 1 };
-    let mut result = vec![asm::Instruction::Push(other_reg)];
-
-    // First 2 Bytes into the register
-    result.append(&mut store_u16(register, (value >> 16) as u16));
-
-    // Next 2 Bytes into the other register
-    result.append(&mut store_u16(other_reg, value as u16));
-
-    result.extend_from_slice(&[
-        // Shift first 2 bytes into correct spot
-        asm::Instruction::Shll16(register),
-        // Zero out top 2 byte of other_reg
-        asm::Instruction::Shll16(other_reg),
-        asm::Instruction::Shlr16(other_reg),
-        // Add these two together
-        asm::Instruction::Add(register, other_reg),
-        // Restore previous value in other_reg
-        asm::Instruction::Pop(other_reg),
-    ]);
-
-    result
+    vec![asm::Instruction::MovConst(register, value)]
 }
 
 #[cfg(test)]
@@ -71,9 +30,13 @@ mod tests {
 
         let target_pc = (result.len() * 2) as u32 + emulator::CODE_MAPPING_OFFSET;
 
-        let mut input = emulator::MockInput::new(vec![]);
-        let mut display = emulator::MockDisplay::new();
-        let mut test_em = emulator::Emulator::new_test(input, display, result);
+        let input = emulator::MockInput::new(vec![]);
+        let display = emulator::MockDisplay::new();
+        let mut memory = emulator::Memory::new();
+        memory.write_register(15, 0x80000);
+        memory.write_register(14, 0x80000);
+        let code = assembler::assemble(result).unwrap();
+        let mut test_em = emulator::Emulator::new_test_raw(input, display, code, memory);
 
         assert!(test_em.run_until(target_pc).await.is_ok());
 
@@ -92,9 +55,13 @@ mod tests {
         let instr_count = result.len();
         let target_pc = (instr_count * 2) as u32 + emulator::CODE_MAPPING_OFFSET;
 
-        let mut input = emulator::MockInput::new(vec![]);
-        let mut display = emulator::MockDisplay::new();
-        let mut test_em = emulator::Emulator::new_test(input, display, result);
+        let input = emulator::MockInput::new(vec![]);
+        let display = emulator::MockDisplay::new();
+        let mut memory = emulator::Memory::new();
+        memory.write_register(15, 0x80000);
+        memory.write_register(14, 0x80000);
+        let code = assembler::assemble(result).unwrap();
+        let mut test_em = emulator::Emulator::new_test_raw(input, display, code, memory);
 
         assert!(test_em.run_until(target_pc).await.is_ok());
 
@@ -105,4 +72,21 @@ mod tests {
 
         assert_eq!(expected_registers, final_registers);
     }
+
+    #[test]
+    fn small_values() {
+        assert_eq!(vec![asm::Instruction::MovI(2, 0x7f)], store_u32(2, 0x7f));
+        assert_eq!(
+            vec![asm::Instruction::MovI(2, 0xfc)],
+            store_u32(2, -4i32 as u32)
+        );
+    }
+
+    #[test]
+    fn large_value() {
+        assert_eq!(
+            vec![asm::Instruction::MovConst(1, 0xac000000)],
+            store_u32(1, 0xac000000)
+        );
+    }
 }
@@ -0,0 +1,558 @@
+use std::collections::HashMap;
+
+use crate::{
+    ir,
+    mir::{self, BlockId},
+};
+
+use super::{
+    internal::{comparison, runtime},
+    machine::{self, Instr, Op, Reg, Unary},
+};
+
+mod switch;
+
+/// The maximum Number of fixed Shift-Instructions used for a constant
+/// Shift, before the Count is loaded into a Register instead
+const MAX_SHIFT_STEPS: usize = 2;
+
+/// The State needed while selecting the Instructions for a Function
+struct Selector<'a> {
+    func: &'a mir::Function,
+    /// The Offsets of the Slots relative to the Start of the Local Variables
+    slots: &'a [i32],
+    /// The Calling-Conventions of the Functions that may be called
+    conventions: &'a HashMap<String, ir::CallingConvention>,
+    instrs: Vec<Instr>,
+    /// The next free virtual Register, the ones before it are used by the
+    /// Temps of the Function
+    next: u32,
+}
+
+/// The virtual Register that holds the Temp
+fn temp(temp: mir::Temp) -> Reg {
+    Reg::Virtual(temp.0)
+}
+
+/// The Instruction that extends the Register holding a Value of the
+/// Datatype to the full 32 Bits, if it is smaller than that
+fn extension(datatype: &ir::DataType) -> Option<Unary> {
+    match datatype {
+        ir::DataType::I8 => Some(Unary::ExtsB),
+        ir::DataType::U8 => Some(Unary::ExtuB),
+        ir::DataType::I16 => Some(Unary::ExtsW),
+        ir::DataType::U16 => Some(Unary::ExtuW),
+        _ => None,
+    }
+}
+
+/// The Register holding the Argument at the Index, which is passed in a
+/// Register
+fn argument(index: usize) -> Reg {
+    Reg::Fixed(machine::FIRST_ARGUMENT + index as u8)
+}
+
+/// The Label at the Start of the Block in the Function
+pub fn label(func: &str, block: BlockId) -> String {
+    format!("__BB_{}_{}", func, block.0)
+}
+
+impl<'a> Selector<'a> {
+    /// A new virtual Register, that is not used by any Temp
+    fn reg(&mut self) -> Reg {
+        let result = Reg::Virtual(self.next);
+        self.next += 1;
+        result
+    }
+
+    fn push(&mut self, instr: Instr) {
+        self.instrs.push(instr);
+    }
+
+    fn label(&self, block: BlockId) -> String {
+        label(&self.func.name, block)
+    }
+
+    /// The Calling-Convention of the Function with the given Name
+    fn convention(&self, name: &str) -> ir::CallingConvention {
+        self.conventions.get(name).copied().unwrap_or_default()
+    }
+
+    /// The Register holding the Operand, Constants are loaded into a new
+    /// Register
+    fn operand(&mut self, operand: &mir::Operand) -> Reg {
+        match operand {
+            mir::Operand::Temp(id) => temp(*id),
+            mir::Operand::Const(value) => {
+                let result = self.reg();
+                self.push(Instr::Const(result, *value));
+                result
+            }
+        }
+    }
+
+    fn instr(&mut self, instr: &mir::Instr) {
+        match instr {
+            mir::Instr::Copy(target, mir::Operand::Temp(source)) => {
+                self.push(Instr::Mov(temp(*target), temp(*source)))
+            }
+            mir::Instr::Copy(target, mir::Operand::Const(value)) => {
+                self.push(Instr::Const(temp(*target), *value))
+            }
+            mir::Instr::Binary(target, op, left, right) => {
+                self.binary(temp(*target), op, left, right)
+            }
+            mir::Instr::Unary(target, op, value) => {
+                let value = self.operand(value);
+                let op = match op {
+                    mir::UnOp::Not => Unary::Not,
+                    mir::UnOp::Neg => Unary::Neg,
+                    mir::UnOp::Truncate(datatype) => match extension(datatype) {
+                        Some(op) => op,
+                        None => {
+                            self.push(Instr::Mov(temp(*target), value));
+                            return;
+                        }
+                    },
+                };
+                self.push(Instr::Unary(op, temp(*target), value));
+            }
+            mir::Instr::Compare(target, cond, left, right) => {
+                self.compare(cond, left, right);
+                self.push(Instr::MovT(temp(*target)));
+                // The T-Bit holds the inverted Result
+                if comparison::inverted(&cond.comparison) {
+                    let one = self.reg();
+                    self.push(Instr::Const(one, 1));
+                    self.push(Instr::Op(Op::Xor, temp(*target), one));
+                }
+            }
+            mir::Instr::Address(target, mir::Address::Slot(slot)) => {
+                let offset = self.slots[*slot];
+                self.push(Instr::LocalAddress(temp(*target), offset));
+            }
+            mir::Instr::Address(target, mir::Address::Global(name)) => {
+                self.push(Instr::MovLabel(temp(*target), name.clone()))
+            }
+            mir::Instr::Load(target, address, datatype) => {
+                let address = self.operand(address);
+                self.push(Instr::Load(temp(*target), address, datatype.clone()));
+            }
+            mir::Instr::Store(address, value, datatype) => {
+                let address = self.operand(address);
+                let value = self.operand(value);
+                self.push(Instr::Store(address, value, datatype.clone()));
+            }
+            mir::Instr::Call(target, name, args) => {
+                let convention = self.convention(name);
+                let registers = convention.registers(args.len());
+
+                for arg in args[registers..].iter().rev() {
+                    let arg = self.operand(arg);
+                    self.push(Instr::Push(arg));
+                }
+                let values: Vec<Reg> = args[..registers]
+                    .iter()
+                    .map(|arg| self.operand(arg))
+                    .collect();
+                for (index, value) in values.into_iter().enumerate() {
+                    self.push(Instr::Mov(argument(index), value));
+                }
+
+                self.push(match convention {
+                    ir::CallingConvention::Renesas => Instr::Call(name.clone(), registers as u8),
+                    ir::CallingConvention::Stack => Instr::StackCall(name.clone()),
+                });
+                if let Some(target) = target {
+                    self.push(Instr::Mov(temp(*target), Reg::Fixed(0)));
+                }
+
+                // "Popping" all the Arguments from the Stack without storing
+                // them anywhere
+                if args.len() > registers {
+                    self.push(Instr::PopArgs((args.len() - registers) as u32));
+                }
+            }
+            mir::Instr::Syscall(target, id, args) => {
+                let args: Vec<Reg> = args.iter().map(|arg| self.operand(arg)).collect();
+                for (i, arg) in args.into_iter().enumerate() {
+                    self.push(Instr::Mov(Reg::Fixed(4 + i as u8), arg));
+                }
+
+                self.push(Instr::Syscall(*id));
+                self.push(Instr::Mov(temp(*target), Reg::Fixed(0)));
+            }
+        };
+    }
+
+    fn binary(&mut self, target: Reg, op: &mir::BinOp, left: &mir::Operand, right: &mir::Operand) {
+        // Small Constants can be added using an Immediate
+        let immediate = match (op, right) {
+            (mir::BinOp::Add, mir::Operand::Const(value)) => Some(*value as i32),
+            (mir::BinOp::Sub, mir::Operand::Const(value)) => Some((*value as i32).wrapping_neg()),
+            _ => None,
+        };
+        if let Some(amount) = immediate.filter(|amount| (-128..=127).contains(amount)) {
+            let left = self.operand(left);
+            self.push(Instr::Mov(target, left));
+            self.push(Instr::AddI(target, amount as u8));
+            return;
+        }
+
+        // Shifts by a few Bits are done using the fixed Shift-Instructions
+        if let (mir::BinOp::Shl | mir::BinOp::LShr, mir::Operand::Const(bits)) = (op, right) {
+            if *bits < 32 && machine::shift_steps(*bits).len() <= MAX_SHIFT_STEPS {
+                let left = self.operand(left);
+                self.push(Instr::Mov(target, left));
+                if *op == mir::BinOp::Shl {
+                    self.push(Instr::ShiftLeft(target, *bits));
+                } else {
+                    self.push(Instr::ShiftRight(target, *bits));
+                }
+                return;
+            }
+        }
+
+        let simple = match op {
+            mir::BinOp::Add => Op::Add,
+            mir::BinOp::Sub => Op::Sub,
+            mir::BinOp::And => Op::And,
+            mir::BinOp::Or => Op::Or,
+            mir::BinOp::Xor => Op::Xor,
+            mir::BinOp::Shl => Op::Shld,
+            mir::BinOp::Mul => {
+                let left = self.operand(left);
+                let right = self.operand(right);
+                self.push(Instr::Mul(target, left, right));
+                return;
+            }
+            mir::BinOp::SDiv | mir::BinOp::UDiv | mir::BinOp::SRem | mir::BinOp::URem => {
+                let routine = match op {
+                    mir::BinOp::SDiv | mir::BinOp::SRem => runtime::SDIV,
+                    _ => runtime::UDIV,
+                };
+
+                let left = self.operand(left);
+                let right = self.operand(right);
+                self.push(Instr::Mov(Reg::Fixed(0), left));
+                self.push(Instr::Mov(Reg::Fixed(1), right));
+                self.push(Instr::Routine(routine.to_owned()));
+                // The Remainder is returned in R1
+                let source = match op {
+                    mir::BinOp::SRem | mir::BinOp::URem => 1,
+                    _ => 0,
+                };
+                self.push(Instr::Mov(target, Reg::Fixed(source)));
+                return;
+            }
+            mir::BinOp::LShr | mir::BinOp::AShr => {
+                // A negative Shift-Count shifts to the Right
+                let count = self.reg();
+                match right {
+                    mir::Operand::Const(bits) => {
+                        self.push(Instr::Const(count, bits.wrapping_neg()));
+                    }
+                    mir::Operand::Temp(_) => {
+                        let right = self.operand(right);
+                        self.push(Instr::Unary(Unary::Neg, count, right));
+                    }
+                };
+                let left = self.operand(left);
+                self.push(Instr::Mov(target, left));
+                if *op == mir::BinOp::AShr {
+                    self.push(Instr::Op(Op::Shad, target, count));
+                } else {
+                    self.push(Instr::Op(Op::Shld, target, count));
+                }
+                return;
+            }
+        };
+
+        let right = self.operand(right);
+        let left = self.operand(left);
+        // The right Operand is still needed after the left one is moved into
+        // the Target, so they can't share a Register
+        let result = if right == target { self.reg() } else { target };
+        self.push(Instr::Mov(result, left));
+        self.push(Instr::Op(simple, result, right));
+        if result != target {
+            self.push(Instr::Mov(target, result));
+        }
+    }
+
+    /// Compares the two Operands, which sets the T-Bit
+    fn compare(&mut self, cond: &mir::Condition, left: &mir::Operand, right: &mir::Operand) {
+        let left = self.operand(left);
+        let right = self.operand(right);
+        self.push(Instr::Compare(
+            cond.comparison.clone(),
+            cond.signed,
+            left,
+            right,
+        ));
+    }
+
+    /// Selects the Instructions for the Terminator, Jumps to the Block that
+    /// is placed next are left out
+    fn terminator(&mut self, terminator: &mir::Terminator, next: Option<BlockId>) {
+        match terminator {
+            mir::Terminator::Jump(target) => {
+                if next != Some(*target) {
+                    self.push(Instr::Jump(self.label(*target)));
+                }
+            }
+            mir::Terminator::Branch(cond, left, right, then, otherwise) => {
+                self.compare(cond, left, right);
+
+                let on_t = !comparison::inverted(&cond.comparison);
+                if next == Some(*then) {
+                    self.push(Instr::Branch(!on_t, self.label(*otherwise)));
+                    return;
+                }
+
+                self.push(Instr::Branch(on_t, self.label(*then)));
+                if next != Some(*otherwise) {
+                    self.push(Instr::Jump(self.label(*otherwise)));
+                }
+            }
+            mir::Terminator::Switch(value, cases, default) => {
+                switch::generate(self, value, cases, *default, next)
+            }
+            mir::Terminator::Return(Some(value)) => {
+                match value {
+                    mir::Operand::Temp(id) => self.push(Instr::Mov(Reg::Fixed(0), temp(*id))),
+                    mir::Operand::Const(value) => self.push(Instr::Const(Reg::Fixed(0), *value)),
+                };
+                self.push(Instr::Return(true));
+            }
+            mir::Terminator::Return(None) => self.push(Instr::Return(false)),
+            mir::Terminator::TailCall(name, args) => {
+                // The Function uses the same Calling-Convention, so the
+                // Arguments on the Stack replace the own Parameters, which
+                // have all been loaded at the Start of the Function
+                let registers = self.func.attributes.convention.registers(args.len());
+                let args: Vec<Reg> = args.iter().map(|arg| self.operand(arg)).collect();
+                for (index, arg) in args[registers..].iter().enumerate() {
+                    let address = self.reg();
+                    self.push(Instr::ParamAddress(address, 4 * index as u32));
+                    self.push(Instr::Store(address, *arg, ir::DataType::U32));
+                }
+                for (index, arg) in args[..registers].iter().enumerate() {
+                    self.push(Instr::Mov(argument(index), *arg));
+                }
+                self.push(Instr::TailCall(name.clone(), registers as u8));
+            }
+        };
+    }
+}
+
+/// Selects the Machine-Instructions for the Function, using the Offsets of
+/// its Slots in the Frame and the Offsets of its Parameters on the Stack.
+/// Every Temp becomes the virtual Register with the same Number
+pub fn select(
+    func: &mir::Function,
+    slots: &[i32],
+    params: &[u32],
+    conventions: &HashMap<String, ir::CallingConvention>,
+) -> Vec<Instr> {
+    let mut selector = Selector {
+        func,
+        slots,
+        conventions,
+        instrs: Vec::new(),
+        next: func.temps,
+    };
+
+    // The Parameters are moved out of the Registers and loaded from the
+    // Stack once
+    let registers = func.attributes.convention.registers(func.params.len());
+    for (index, (id, datatype)) in func.params[..registers].iter().enumerate() {
+        selector.push(match extension(datatype) {
+            Some(op) => Instr::Unary(op, temp(*id), argument(index)),
+            None => Instr::Mov(temp(*id), argument(index)),
+        });
+    }
+    for ((id, datatype), offset) in func.params[registers..].iter().zip(params) {
+        let address = selector.reg();
+        selector.push(Instr::ParamAddress(address, *offset));
+        selector.push(Instr::Load(temp(*id), address, datatype.clone()));
+    }
+
+    for (index, block) in func.blocks.iter().enumerate() {
+        selector.push(Instr::Label(selector.label(BlockId(index))));
+        for instr in block.instrs.iter() {
+            selector.instr(instr);
+        }
+
+        let next = Some(BlockId(index + 1)).filter(|next| next.0 < func.blocks.len());
+        selector.terminator(&block.terminator, next);
+    }
+
+    selector.instrs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        asm,
+        backend::{
+            machine::{self, Frame},
+            regalloc,
+        },
+    };
+
+    /// A Function with a single Block, that returns the Temp 0
+    fn single(instrs: Vec<mir::Instr>, temps: u32) -> mir::Function {
+        mir::Function {
+            name: "test".to_owned(),
+            return_type: ir::DataType::I32,
+            attributes: ir::Attributes::default(),
+            params: Vec::new(),
+            slots: Vec::new(),
+            strings: Vec::new(),
+            blocks: vec![mir::Block {
+                instrs,
+                terminator: mir::Terminator::Return(Some(mir::Operand::Temp(mir::Temp(0)))),
+            }],
+            temps,
+        }
+    }
+
+    /// Assembles the Function, with the Routines it needs placed in front
+    /// of it. The Return is left out, so the Result stays in R0
+    fn assemble(func: &mir::Function) -> Vec<u8> {
+        let mut instrs = select(func, &[], &[], &HashMap::new());
+        instrs.pop();
+
+        let allocation = regalloc::allocate(instrs);
+        let generated = machine::lower(&allocation.instrs, &Frame::default());
+
+        // Place the Routines in front of the actual Code, so they are not
+        // mistaken for the End of the Function
+        let mut instr = vec![asm::Instruction::JmpLabel("start".to_owned())];
+        instr.extend(runtime::generate(&generated));
+        instr.push(asm::Instruction::Label("start".to_owned()));
+        instr.extend(generated);
+
+        assembler::assemble(instr).unwrap()
+    }
+
+    #[tokio::test]
+    async fn operation_add() {
+        let func = single(
+            vec![
+                mir::Instr::Copy(mir::Temp(1), mir::Operand::Const(1)),
+                mir::Instr::Binary(
+                    mir::Temp(0),
+                    mir::BinOp::Add,
+                    mir::Operand::Temp(mir::Temp(1)),
+                    mir::Operand::Const(2),
+                ),
+            ],
+            2,
+        );
+
+        let code = assemble(&func);
+        let target_pc = code.len() as u32 + emulator::CODE_MAPPING_OFFSET;
+
+        let input = emulator::MockInput::new(vec![]);
+        let display = emulator::MockDisplay::new();
+        let mut memory = emulator::Memory::new();
+        memory.write_register(15, 0x80000);
+        memory.write_register(14, 0x80000);
+        let mut test_em = emulator::Emulator::new_test_raw(input, display, code, memory);
+
+        assert!(test_em.run_until(target_pc).await.is_ok());
+
+        let final_registers = test_em.clone_registers();
+
+        assert_eq!(3, final_registers[0]);
+        // The Stack is left untouched
+        assert_eq!([0x80000, 0x80000], final_registers[14..]);
+    }
+
+    #[tokio::test]
+    async fn operation_modulo_signed() {
+        let func = single(
+            vec![mir::Instr::Binary(
+                mir::Temp(0),
+                mir::BinOp::SRem,
+                mir::Operand::Const(-17i32 as u32),
+                mir::Operand::Const(5),
+            )],
+            1,
+        );
+
+        let code = assemble(&func);
+        let target_pc = code.len() as u32 + emulator::CODE_MAPPING_OFFSET;
+
+        let input = emulator::MockInput::new(vec![]);
+        let display = emulator::MockDisplay::new();
+        let mut memory = emulator::Memory::new();
+        memory.write_register(15, 0x80000);
+        memory.write_register(14, 0x80000);
+        let mut test_em = emulator::Emulator::new_test_raw(input, display, code, memory);
+
+        assert!(test_em.run_until(target_pc).await.is_ok());
+
+        let final_registers = test_em.clone_registers();
+
+        assert_eq!(-2i32 as u32, final_registers[0]);
+        assert_eq!([0x80000, 0x80000], final_registers[14..]);
+    }
+
+    #[test]
+    fn target_is_right_operand() {
+        let func = single(
+            vec![mir::Instr::Binary(
+                mir::Temp(0),
+                mir::BinOp::Sub,
+                mir::Operand::Temp(mir::Temp(1)),
+                mir::Operand::Temp(mir::Temp(0)),
+            )],
+            2,
+        );
+
+        let instrs = select(&func, &[], &[], &HashMap::new());
+
+        // The Difference is calculated in a separate Register, as the
+        // Target is still needed as the right Operand
+        let expected = vec![
+            Instr::Label("__BB_test_0".to_owned()),
+            Instr::Mov(Reg::Virtual(2), Reg::Virtual(1)),
+            Instr::Op(Op::Sub, Reg::Virtual(2), Reg::Virtual(0)),
+            Instr::Mov(Reg::Virtual(0), Reg::Virtual(2)),
+            Instr::Mov(Reg::Fixed(0), Reg::Virtual(0)),
+            Instr::Return(true),
+        ];
+        assert_eq!(expected, instrs);
+    }
+
+    #[test]
+    fn store_in_slot() {
+        let mut func = single(
+            vec![
+                mir::Instr::Address(mir::Temp(1), mir::Address::Slot(1)),
+                mir::Instr::Store(
+                    mir::Operand::Temp(mir::Temp(1)),
+                    mir::Operand::Const(0),
+                    ir::DataType::U32,
+                ),
+            ],
+            2,
+        );
+        func.blocks[0].terminator = mir::Terminator::Return(None);
+
+        let instrs = select(&func, &[0, 4], &[], &HashMap::new());
+
+        let expected = vec![
+            Instr::Label("__BB_test_0".to_owned()),
+            Instr::LocalAddress(Reg::Virtual(1), 4),
+            Instr::Const(Reg::Virtual(2), 0),
+            Instr::Store(Reg::Virtual(1), Reg::Virtual(2), ir::DataType::U32),
+            Instr::Return(false),
+        ];
+        assert_eq!(expected, instrs);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{
+    backend::machine::{Instr, Op, Reg},
+    ir,
+    mir::{self, BlockId},
+};
+
+use super::Selector;
+
+/// The minimum Number of Cases for a Switch to be turned into a Jump-Table
+const MIN_TABLE_CASES: usize = 4;
+
+/// Selects the Instructions for a Switch-Terminator. Dense Switches are
+/// turned into a Table of Jumps and all other Switches into a Chain of
+/// Comparisons
+pub fn generate(
+    sel: &mut Selector,
+    value: &mir::Operand,
+    cases: &[(u32, BlockId)],
+    default: BlockId,
+    next: Option<BlockId>,
+) {
+    let value = sel.operand(value);
+    match table_range(cases) {
+        Some((min, range)) => jump_table(sel, value, cases, min, range, default),
+        None => compare_chain(sel, value, cases, default, next),
+    };
+}
+
+/// Checks if the Values are dense enough to use a Jump-Table and if so
+/// returns the smallest Value and the Number of Entries in the Table
+fn table_range(cases: &[(u32, BlockId)]) -> Option<(u32, u32)> {
+    if cases.len() < MIN_TABLE_CASES {
+        return None;
+    }
+
+    // The Values are compared as signed Integers
+    let min = cases.iter().map(|(value, _)| *value as i32).min()?;
+    let max = cases.iter().map(|(value, _)| *value as i32).max()?;
+    let range = (max as i64 - min as i64 + 1) as u64;
+
+    if range > 2 * cases.len() as u64 {
+        return None;
+    }
+
+    Some((min as u32, range as u32))
+}
+
+/// Generates a Table of Jumps, which is indexed using the Value
+fn jump_table(
+    sel: &mut Selector,
+    value: Reg,
+    cases: &[(u32, BlockId)],
+    min: u32,
+    range: u32,
+    default: BlockId,
+) {
+    // Turn the Value into the Index in the Table
+    let index = sel.reg();
+    let min_reg = sel.reg();
+    sel.push(Instr::Mov(index, value));
+    sel.push(Instr::Const(min_reg, min));
+    sel.push(Instr::Op(Op::Sub, index, min_reg));
+
+    // Every Index outside of the Table goes to the Default, this also
+    // catches Values below the Minimum as they wrapped around
+    let last = sel.reg();
+    sel.push(Instr::Const(last, range - 1));
+    sel.push(Instr::Compare(
+        ir::Comparison::GreaterThan,
+        false,
+        index,
+        last,
+    ));
+    sel.push(Instr::Branch(true, sel.label(default)));
+
+    let labels = (0..range)
+        .map(|offset| {
+            let entry = min.wrapping_add(offset);
+            let target = cases
+                .iter()
+                .find(|(value, _)| *value == entry)
+                .map(|(_, block)| *block)
+                .unwrap_or(default);
+            sel.label(target)
+        })
+        .collect();
+    sel.push(Instr::JumpTable(index, labels));
+}
+
+/// Generates a Comparison against every Value, one after the other
+fn compare_chain(
+    sel: &mut Selector,
+    value: Reg,
+    cases: &[(u32, BlockId)],
+    default: BlockId,
+    next: Option<BlockId>,
+) {
+    for (case_value, block) in cases.iter() {
+        let case_reg = sel.reg();
+        sel.push(Instr::Const(case_reg, *case_value));
+        sel.push(Instr::Compare(ir::Comparison::Equal, true, value, case_reg));
+        sel.push(Instr::Branch(true, sel.label(*block)));
+    }
+
+    if next != Some(default) {
+        sel.push(Instr::Jump(sel.label(default)));
+    }
+}
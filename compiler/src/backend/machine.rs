@@ -0,0 +1,504 @@
+use crate::{asm, ir};
+
+use super::{
+    internal::{comparison, frame, mov_instr, store},
+    syscall,
+};
+
+/// The Registers that may be changed by a called Function, a Syscall or
+/// a Runtime-Routine, everything from R8 on is preserved by the Callee
+pub const CALLER_SAVED: [u8; 8] = [0, 1, 2, 3, 4, 5, 6, 7];
+/// The first Register used for Arguments, the following ones hold the
+/// other Arguments passed in Registers
+pub const FIRST_ARGUMENT: u8 = 4;
+/// The Registers that have to be preserved by every Function that uses them
+pub const CALLEE_SAVED: [u8; 6] = [8, 9, 10, 11, 12, 13];
+
+/// A Register used by the Machine-Instructions, which is either a specific
+/// physical Register or a virtual one that still needs to be assigned to
+/// a physical Register by the Register-Allocator
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub enum Reg {
+    Fixed(u8),
+    Virtual(u32),
+}
+
+impl Reg {
+    /// The physical Register, only valid after the Register-Allocation
+    pub fn physical(&self) -> u8 {
+        match self {
+            Self::Fixed(register) => *register,
+            Self::Virtual(id) => panic!("Virtual Register {} was not allocated", id),
+        }
+    }
+}
+
+/// The Operations, that combine the Target- with the Source-Register and
+/// store the Result in the Target-Register
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Op {
+    Add,
+    Sub,
+    And,
+    Or,
+    Xor,
+    Shld,
+    Shad,
+}
+
+/// The Operations, that store their Result for the Source-Register in the
+/// Target-Register
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Unary {
+    Not,
+    Neg,
+    ExtuB,
+    ExtuW,
+    ExtsB,
+    ExtsW,
+}
+
+/// The Layout of the Frame of a Function, which is only known once the
+/// Register-Allocator decided how many Spill-Slots and callee-saved
+/// Registers are needed.
+///
+/// Starting at the FP(R14) the Frame contains the Spill-Slots, so they can
+/// be reached with a single Move, the Local Variables, the saved Registers,
+/// the PR if the Function saves it, the previous SP and FP and then the
+/// Parameters pushed by the Caller, which are preceded by the PR of the
+/// Caller when using the Stack-Convention
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Frame {
+    /// The Number of 32bit Spill-Slots
+    pub spills: u32,
+    /// The Size of the Local Variables in Bytes, always aligned to 4
+    pub locals: u32,
+    /// The callee-saved Registers that are used by the Function
+    pub saved: Vec<u8>,
+    /// Whether or not the PR is saved, because the Function calls others
+    /// that expect it to be saved by the Callee
+    pub pr: bool,
+    /// How the Function receives its Parameters
+    pub convention: ir::CallingConvention,
+}
+
+impl Frame {
+    /// The Number of Bytes the SP is moved for the Function
+    pub fn size(&self) -> u32 {
+        4 * self.spills + self.locals
+    }
+
+    /// The Offset of the first Parameter on the Stack relative to the FP,
+    /// which is after the saved Registers, the PR, the previous SP and FP
+    /// and the PR saved by the Caller
+    pub fn params(&self) -> u32 {
+        let caller_pr = self.convention == ir::CallingConvention::Stack;
+        let words = self.saved.len() as u32 + self.pr as u32 + 2 + caller_pr as u32;
+        self.size() + 4 * words
+    }
+
+    /// Sets up the Frame at the Start of the Function, by saving the FP, the
+    /// SP, the PR and the callee-saved Registers and reserving the Space
+    /// for the Spill-Slots and the Local Variables
+    pub fn create(&self) -> Vec<asm::Instruction> {
+        // Store the Previous FP(r14)/SP(r15) on the Stack
+        let mut result = vec![asm::Instruction::Push(14), asm::Instruction::Push(15)];
+        if self.pr {
+            result.push(asm::Instruction::PushPR);
+        }
+        for register in self.saved.iter() {
+            result.push(asm::Instruction::Push(*register));
+        }
+
+        if self.size() > 0 {
+            // R0 does not hold any of the Arguments
+            result.extend(frame::move_stack(-(self.size() as i32), 0));
+        }
+
+        // Move the new StackPtr(r15) into FP(r14) as base offset
+        result.push(asm::Instruction::Mov(14, 15));
+        result
+    }
+
+    /// Removes the Frame again and restores the saved Registers, the PR,
+    /// the SP and the FP of the Caller
+    pub fn remove(&self) -> Vec<asm::Instruction> {
+        // R0 contains the Return-Value and R4-R7 the Arguments of a
+        // Tail-Call, so only R1 can be used
+        let mut result = frame::move_stack(self.size() as i32, 1);
+        for register in self.saved.iter().rev() {
+            result.push(asm::Instruction::Pop(*register));
+        }
+        if self.pr {
+            result.push(asm::Instruction::PopPR);
+        }
+        result.extend_from_slice(&[
+            asm::Instruction::Pop(15), // Restore the SP
+            asm::Instruction::Pop(14), // Restore the FP
+        ]);
+        result
+    }
+}
+
+/// The Instructions generated for a Function before the Register-Allocation.
+/// Every Instruction maps to a small fixed Sequence of Assembly-Instructions
+#[derive(Debug, PartialEq, Clone)]
+pub enum Instr {
+    Label(String),
+    Jump(String),
+    /// Jumps to the Label if the T-Bit is set to the given Value
+    Branch(bool, String),
+    /// Jumps to the Label at the Index in the Register, the Register is
+    /// changed in the Process
+    JumpTable(Reg, Vec<String>),
+    Const(Reg, u32),
+    MovLabel(Reg, String),
+    /// (Target, Source)
+    Mov(Reg, Reg),
+    /// (Operation, Target, Source)
+    Op(Op, Reg, Reg),
+    AddI(Reg, u8),
+    /// Shifts the Register to the Left by the constant Number of Bits
+    ShiftLeft(Reg, u32),
+    /// Shifts the Register logically to the Right by the constant Number
+    /// of Bits
+    ShiftRight(Reg, u32),
+    /// (Operation, Target, Source)
+    Unary(Unary, Reg, Reg),
+    /// (Target, Left, Right)
+    Mul(Reg, Reg, Reg),
+    /// (Target, Address, Datatype)
+    Load(Reg, Reg, ir::DataType),
+    /// (Address, Value, Datatype)
+    Store(Reg, Reg, ir::DataType),
+    /// (Comparison, Signed, Left, Right)
+    /// Sets the T-Bit, unless the Comparison is inverted
+    Compare(ir::Comparison, bool, Reg, Reg),
+    MovT(Reg),
+    /// Loads the Address of the Local Variable at the Offset in the Frame
+    LocalAddress(Reg, i32),
+    /// Loads the Address of the Parameter at the Offset from the first
+    /// Parameter
+    ParamAddress(Reg, u32),
+    /// Pushes the Register as an Argument for the next Call
+    Push(Reg),
+    /// Removes the given Number of Arguments from the Stack again
+    PopArgs(u32),
+    /// Calls the Function, which takes the given Number of Arguments in
+    /// R4-R7 and the others on the Stack, saves the PR itself and returns
+    /// its Value in R0
+    Call(String, u8),
+    /// Calls the Function using the Stack-Convention, which takes all its
+    /// Arguments on the Stack and returns its Value in R0
+    StackCall(String),
+    /// Performs the Syscall with the Arguments in R4-R7
+    Syscall(u16),
+    /// Calls the Runtime-Routine, which takes and returns its Values in
+    /// R0 and R1
+    Routine(String),
+    /// Returns from the Function, with the Return-Value in R0 if set
+    Return(bool),
+    /// Removes the Frame and jumps to the Function, which then returns
+    /// directly to the Caller of the current Function. The Function takes
+    /// the given Number of Arguments in R4-R7
+    TailCall(String, u8),
+    /// Loads the Value stored in the Spill-Slot
+    SpillLoad(Reg, u32),
+    /// Stores the Value in the Spill-Slot
+    SpillStore(u32, Reg),
+}
+
+impl Instr {
+    /// The Registers whose Values are read by the Instruction
+    pub fn uses(&self) -> Vec<Reg> {
+        match self {
+            Self::JumpTable(reg, _)
+            | Self::AddI(reg, _)
+            | Self::ShiftLeft(reg, _)
+            | Self::ShiftRight(reg, _)
+            | Self::Push(reg)
+            | Self::SpillStore(_, reg) => vec![*reg],
+            Self::Mov(_, source) | Self::Unary(_, _, source) | Self::Load(_, source, _) => {
+                vec![*source]
+            }
+            Self::Op(_, target, source) => vec![*target, *source],
+            Self::Mul(_, left, right) | Self::Compare(_, _, left, right) => vec![*left, *right],
+            Self::Store(address, value, _) => vec![*address, *value],
+            Self::Syscall(_) => (4..=7).map(Reg::Fixed).collect(),
+            Self::Call(_, count) | Self::TailCall(_, count) => arguments(*count),
+            Self::Routine(_) => vec![Reg::Fixed(0), Reg::Fixed(1)],
+            Self::Return(true) => vec![Reg::Fixed(0)],
+            _ => Vec::new(),
+        }
+    }
+
+    /// The Registers that are set by the Instruction
+    pub fn defs(&self) -> Vec<Reg> {
+        match self {
+            Self::JumpTable(reg, _)
+            | Self::Const(reg, _)
+            | Self::MovLabel(reg, _)
+            | Self::Mov(reg, _)
+            | Self::Op(_, reg, _)
+            | Self::AddI(reg, _)
+            | Self::ShiftLeft(reg, _)
+            | Self::ShiftRight(reg, _)
+            | Self::Unary(_, reg, _)
+            | Self::Mul(reg, _, _)
+            | Self::Load(reg, _, _)
+            | Self::MovT(reg)
+            | Self::LocalAddress(reg, _)
+            | Self::ParamAddress(reg, _)
+            | Self::SpillLoad(reg, _) => vec![*reg],
+            Self::Call(_, _) | Self::StackCall(_) | Self::Syscall(_) => vec![Reg::Fixed(0)],
+            Self::Routine(_) => vec![Reg::Fixed(0), Reg::Fixed(1)],
+            _ => Vec::new(),
+        }
+    }
+
+    /// The physical Registers whose Values are destroyed by the Instruction,
+    /// without being a Result of it
+    pub fn clobbers(&self) -> &'static [u8] {
+        match self {
+            Self::Call(_, _) | Self::StackCall(_) | Self::Syscall(_) => &CALLER_SAVED,
+            _ => &[],
+        }
+    }
+
+    /// Whether or not the Execution does not simply continue with the
+    /// next Instruction
+    pub fn is_jump(&self) -> bool {
+        matches!(
+            self,
+            Self::Jump(_)
+                | Self::Branch(_, _)
+                | Self::JumpTable(_, _)
+                | Self::Return(_)
+                | Self::TailCall(_, _)
+        )
+    }
+
+    /// Replaces every Register of the Instruction with the Result of the
+    /// given Function
+    pub fn map_registers<F>(&mut self, mut f: F)
+    where
+        F: FnMut(Reg) -> Reg,
+    {
+        match self {
+            Self::JumpTable(reg, _)
+            | Self::Const(reg, _)
+            | Self::MovLabel(reg, _)
+            | Self::AddI(reg, _)
+            | Self::ShiftLeft(reg, _)
+            | Self::ShiftRight(reg, _)
+            | Self::MovT(reg)
+            | Self::LocalAddress(reg, _)
+            | Self::ParamAddress(reg, _)
+            | Self::Push(reg)
+            | Self::SpillLoad(reg, _)
+            | Self::SpillStore(_, reg) => *reg = f(*reg),
+            Self::Mov(first, second)
+            | Self::Op(_, first, second)
+            | Self::Unary(_, first, second)
+            | Self::Load(first, second, _)
+            | Self::Store(first, second, _)
+            | Self::Compare(_, _, first, second) => {
+                *first = f(*first);
+                *second = f(*second);
+            }
+            Self::Mul(target, left, right) => {
+                *target = f(*target);
+                *left = f(*left);
+                *right = f(*right);
+            }
+            _ => {}
+        };
+    }
+
+    /// Generates the actual Assembly for the Instruction, which can only be
+    /// done once all the Registers are physical Registers
+    pub fn lower(&self, frame: &Frame) -> Vec<asm::Instruction> {
+        match self {
+            Self::Label(name) => vec![asm::Instruction::Label(name.clone())],
+            Self::Jump(label) => vec![
+                asm::Instruction::JmpLabel(label.clone()),
+                asm::Instruction::Nop,
+            ],
+            Self::Branch(on_t, label) => vec![
+                // Branch over the Jump, if it should not be taken
+                if *on_t {
+                    asm::Instruction::BF(1)
+                } else {
+                    asm::Instruction::BT(1)
+                },
+                asm::Instruction::JmpLabel(label.clone()),
+                asm::Instruction::Nop,
+            ],
+            Self::JumpTable(index, labels) => {
+                let index = index.physical();
+                // Every Entry in the Table is a single Jump of 4 Bytes
+                let mut result = vec![
+                    asm::Instruction::Shll2(index),
+                    asm::Instruction::Braf(index),
+                    asm::Instruction::Nop,
+                ];
+                result.extend(
+                    labels
+                        .iter()
+                        .map(|label| asm::Instruction::JmpLabel(label.clone())),
+                );
+                result
+            }
+            Self::Const(target, value) => store::store_u32(target.physical(), *value),
+            Self::MovLabel(target, label) => {
+                vec![asm::Instruction::MovLabel(target.physical(), label.clone())]
+            }
+            Self::Mov(target, source) if target == source => Vec::new(),
+            Self::Mov(target, source) => {
+                vec![asm::Instruction::Mov(target.physical(), source.physical())]
+            }
+            Self::Op(op, target, source) => {
+                let (target, source) = (target.physical(), source.physical());
+                vec![match op {
+                    Op::Add => asm::Instruction::Add(target, source),
+                    Op::Sub => asm::Instruction::Sub(target, source),
+                    Op::And => asm::Instruction::And(target, source),
+                    Op::Or => asm::Instruction::Or(target, source),
+                    Op::Xor => asm::Instruction::Xor(target, source),
+                    Op::Shld => asm::Instruction::Shld(target, source),
+                    Op::Shad => asm::Instruction::Shad(target, source),
+                }]
+            }
+            Self::AddI(target, value) => vec![asm::Instruction::AddI(target.physical(), *value)],
+            Self::ShiftLeft(target, bits) => {
+                let target = target.physical();
+                shift_steps(*bits)
+                    .into_iter()
+                    .map(|step| match step {
+                        16 => asm::Instruction::Shll16(target),
+                        8 => asm::Instruction::Shll8(target),
+                        2 => asm::Instruction::Shll2(target),
+                        _ => asm::Instruction::Shll(target),
+                    })
+                    .collect()
+            }
+            Self::ShiftRight(target, bits) => {
+                let target = target.physical();
+                shift_steps(*bits)
+                    .into_iter()
+                    .map(|step| match step {
+                        16 => asm::Instruction::Shlr16(target),
+                        8 => asm::Instruction::Shlr8(target),
+                        2 => asm::Instruction::Shlr2(target),
+                        _ => asm::Instruction::Shlr(target),
+                    })
+                    .collect()
+            }
+            Self::Unary(op, target, source) => {
+                let (target, source) = (target.physical(), source.physical());
+                vec![match op {
+                    Unary::Not => asm::Instruction::Not(target, source),
+                    Unary::Neg => asm::Instruction::Neg(target, source),
+                    Unary::ExtuB => asm::Instruction::ExtuB(target, source),
+                    Unary::ExtuW => asm::Instruction::ExtuW(target, source),
+                    Unary::ExtsB => asm::Instruction::ExtsB(target, source),
+                    Unary::ExtsW => asm::Instruction::ExtsW(target, source),
+                }]
+            }
+            Self::Mul(target, left, right) => vec![
+                asm::Instruction::MulL(left.physical(), right.physical()),
+                asm::Instruction::StsMacl(target.physical()),
+            ],
+            Self::Load(target, address, datatype) => {
+                mov_instr::get_load(target.physical(), address.physical(), datatype)
+            }
+            Self::Store(address, value, datatype) => vec![mov_instr::get_mov(
+                asm::Operand::AtRegister(address.physical()),
+                asm::Operand::Register(value.physical()),
+                datatype,
+            )],
+            Self::Compare(comp, signed, left, right) => {
+                vec![
+                    comparison::generate(comp, left.physical(), right.physical(), *signed).unwrap(),
+                ]
+            }
+            Self::MovT(target) => vec![asm::Instruction::MovT(target.physical())],
+            Self::LocalAddress(target, offset) => {
+                frame::address(target.physical(), 4 * frame.spills as i32 + offset)
+            }
+            Self::ParamAddress(target, offset) => {
+                frame::address(target.physical(), (frame.params() + offset) as i32)
+            }
+            Self::Push(source) => vec![asm::Instruction::Push(source.physical())],
+            Self::PopArgs(count) => {
+                // R0 contains the Return-Value, so the Stack is only moved
+                // using Immediates
+                let mut result = Vec::new();
+                let mut remaining = 4 * *count;
+                while remaining > 0 {
+                    let step = remaining.min(124);
+                    result.push(asm::Instruction::AddI(15, step as u8));
+                    remaining -= step;
+                }
+                result
+            }
+            Self::Syscall(id) => syscall::generate(*id),
+            Self::Call(name, _) => vec![
+                asm::Instruction::JsrLabel(name.clone()),
+                asm::Instruction::Nop,
+            ],
+            Self::StackCall(name) | Self::Routine(name) => vec![
+                // Save the previous PR
+                asm::Instruction::PushPR,
+                asm::Instruction::JsrLabel(name.clone()),
+                asm::Instruction::Nop,
+                asm::Instruction::PopPR,
+            ],
+            Self::Return(_) => {
+                let mut result = frame.remove();
+                result.extend_from_slice(&[asm::Instruction::Rts, asm::Instruction::Nop]);
+                result
+            }
+            Self::TailCall(name, _) => {
+                let mut result = frame.remove();
+                result.extend_from_slice(&[
+                    asm::Instruction::JmpLabel(name.clone()),
+                    asm::Instruction::Nop,
+                ]);
+                result
+            }
+            Self::SpillLoad(target, slot) => frame::spill_access(4 * slot, |at| {
+                asm::Instruction::MovL(asm::Operand::Register(target.physical()), at)
+            }),
+            Self::SpillStore(slot, source) => frame::spill_access(4 * slot, |at| {
+                asm::Instruction::MovL(at, asm::Operand::Register(source.physical()))
+            }),
+        }
+    }
+}
+
+/// The Registers holding the given Number of Arguments
+pub fn arguments(count: u8) -> Vec<Reg> {
+    (0..count)
+        .map(|index| Reg::Fixed(FIRST_ARGUMENT + index))
+        .collect()
+}
+
+/// Splits a constant Shift into the Shifts by 16, 8, 2 and 1 Bits, that
+/// the Processor can do directly
+pub fn shift_steps(mut bits: u32) -> Vec<u32> {
+    let mut result = Vec::new();
+    for step in [16, 8, 2, 1].iter() {
+        while bits >= *step {
+            result.push(*step);
+            bits -= step;
+        }
+    }
+    result
+}
+
+/// Generates the Assembly for the Instructions of a Function, whose
+/// Registers have all been allocated
+pub fn lower(instrs: &[Instr], frame: &Frame) -> Vec<asm::Instruction> {
+    instrs.iter().flat_map(|instr| instr.lower(frame)).collect()
+}
@@ -0,0 +1,350 @@
+use std::collections::{HashMap, HashSet};
+
+use super::machine::{Instr, Reg, CALLEE_SAVED};
+
+mod liveness;
+
+/// The Order in which the physical Registers are handed out. The
+/// caller-saved Registers come first, as they don't need to be saved by
+/// the Function itself, with R0 last as it is needed for Return-Values
+const ORDER: [u8; 14] = [1, 2, 3, 4, 5, 6, 7, 0, 8, 9, 10, 11, 12, 13];
+
+/// The Instructions of a Function after the Register-Allocation
+#[derive(Debug, PartialEq)]
+pub struct Allocation {
+    /// The Instructions, which only use physical Registers
+    pub instrs: Vec<Instr>,
+    /// The Number of Spill-Slots needed in the Frame
+    pub spills: u32,
+    /// The callee-saved Registers that are used and need to be saved
+    pub saved: Vec<u8>,
+}
+
+/// The preferred Registers for the virtual Registers, which avoid a Move
+/// if they are used
+#[derive(Debug, Default)]
+struct Hints {
+    /// The physical Register a virtual Register is moved to or from
+    fixed: HashMap<u32, u8>,
+    /// The other virtual Registers a virtual Register is moved to or from
+    copies: HashMap<u32, Vec<u32>>,
+}
+
+/// Assigns a physical Register to every virtual Register, using a
+/// Linear-Scan over their Live-Intervals. If there are not enough Registers
+/// the Values that are needed the furthest in the Future are spilled into
+/// the Frame and the Allocation is repeated
+pub fn allocate(mut instrs: Vec<Instr>) -> Allocation {
+    let mut next = instrs
+        .iter()
+        .flat_map(|instr| instr.uses().into_iter().chain(instr.defs()))
+        .filter_map(|reg| match reg {
+            Reg::Virtual(id) => Some(id + 1),
+            Reg::Fixed(_) => None,
+        })
+        .max()
+        .unwrap_or(0);
+    let mut spills = 0;
+    // The temporary Registers for spilled Values, which must not be
+    // spilled themselves
+    let mut unspillable = HashSet::new();
+
+    loop {
+        let liveness = liveness::analyze(&instrs);
+        let hints = hints(&instrs);
+
+        let assignment = match scan(&liveness, &hints, &unspillable) {
+            Ok(assignment) => assignment,
+            Err(spilled) => {
+                for id in spilled {
+                    instrs = spill(instrs, id, spills, &mut next, &mut unspillable);
+                    spills += 1;
+                }
+                continue;
+            }
+        };
+
+        for instr in instrs.iter_mut() {
+            instr.map_registers(|reg| match reg {
+                Reg::Virtual(id) => Reg::Fixed(assignment[&id]),
+                fixed => fixed,
+            });
+        }
+        let saved = CALLEE_SAVED
+            .iter()
+            .filter(|register| assignment.values().any(|other| other == *register))
+            .copied()
+            .collect();
+
+        return Allocation {
+            instrs,
+            spills,
+            saved,
+        };
+    }
+}
+
+/// Collects the Registers that are moved into each other
+fn hints(instrs: &[Instr]) -> Hints {
+    let mut result = Hints::default();
+    for instr in instrs.iter() {
+        match instr {
+            Instr::Mov(Reg::Virtual(id), Reg::Fixed(register))
+            | Instr::Mov(Reg::Fixed(register), Reg::Virtual(id)) => {
+                result.fixed.entry(*id).or_insert(*register);
+            }
+            Instr::Mov(Reg::Virtual(first), Reg::Virtual(second)) => {
+                result.copies.entry(*first).or_default().push(*second);
+                result.copies.entry(*second).or_default().push(*first);
+            }
+            _ => {}
+        };
+    }
+    result
+}
+
+/// Walks over the Intervals ordered by their Start and assigns every one
+/// of them a Register that is neither used by an overlapping Interval nor
+/// by a physical Register in the Meantime. Returns the virtual Registers
+/// that need to be spilled, if there are not enough Registers
+fn scan(
+    liveness: &liveness::Liveness,
+    hints: &Hints,
+    unspillable: &HashSet<u32>,
+) -> Result<HashMap<u32, u8>, Vec<u32>> {
+    let mut order: Vec<(u32, liveness::Interval)> = liveness
+        .intervals
+        .iter()
+        .map(|(id, interval)| (*id, *interval))
+        .collect();
+    order.sort_by_key(|(id, interval)| (interval.start, *id));
+
+    let mut assignment: HashMap<u32, u8> = HashMap::new();
+    let mut active: Vec<(u32, liveness::Interval)> = Vec::new();
+    let mut spilled = Vec::new();
+
+    for (id, interval) in order {
+        active.retain(|(_, other)| other.end >= interval.start);
+
+        let blocked = |register: u8| {
+            liveness.fixed[register as usize]
+                .range(interval.start..=interval.end)
+                .next()
+                .is_some()
+        };
+        let free: Vec<u8> = ORDER
+            .iter()
+            .copied()
+            .filter(|register| !blocked(*register))
+            .filter(|register| {
+                !active
+                    .iter()
+                    .any(|(other, _)| assignment[other] == *register)
+            })
+            .collect();
+
+        // Prefer the Register of a Copy or the one it is moved into later on
+        let copies = hints.copies.get(&id).into_iter().flatten();
+        let choice = hints
+            .fixed
+            .get(&id)
+            .copied()
+            .into_iter()
+            .chain(
+                copies
+                    .clone()
+                    .filter_map(|other| assignment.get(other).copied()),
+            )
+            .chain(copies.filter_map(|other| hints.fixed.get(other).copied()))
+            .find(|register| free.contains(register))
+            .or_else(|| free.first().copied());
+
+        if let Some(register) = choice {
+            assignment.insert(id, register);
+            active.push((id, interval));
+            continue;
+        }
+
+        // Spill the Value that is needed the furthest in the Future, which
+        // frees up a Register for the current one
+        let victim = active
+            .iter()
+            .enumerate()
+            .filter(|(_, (other, _))| !unspillable.contains(other))
+            .filter(|(_, (other, _))| !blocked(assignment[other]))
+            .max_by_key(|(_, (_, other))| other.end)
+            .map(|(index, (other, other_interval))| (index, *other, other_interval.end));
+        match victim {
+            Some((index, other, end)) if end > interval.end || unspillable.contains(&id) => {
+                let register = assignment.remove(&other).unwrap();
+                active.remove(index);
+                spilled.push(other);
+
+                assignment.insert(id, register);
+                active.push((id, interval));
+            }
+            _ if !unspillable.contains(&id) => spilled.push(id),
+            _ => panic!("Not enough Registers for the Instructions"),
+        };
+    }
+
+    if spilled.is_empty() {
+        Ok(assignment)
+    } else {
+        Err(spilled)
+    }
+}
+
+/// Stores the virtual Register in the Spill-Slot, every Instruction that
+/// uses it gets its own short-lived temporary Register, which is loaded
+/// from the Slot before and stored in it afterwards
+fn spill(
+    instrs: Vec<Instr>,
+    id: u32,
+    slot: u32,
+    next: &mut u32,
+    unspillable: &mut HashSet<u32>,
+) -> Vec<Instr> {
+    let spilled = Reg::Virtual(id);
+
+    let mut result = Vec::with_capacity(instrs.len());
+    for mut instr in instrs {
+        let used = instr.uses().contains(&spilled);
+        let defined = instr.defs().contains(&spilled);
+        if !used && !defined {
+            result.push(instr);
+            continue;
+        }
+
+        let temp = Reg::Virtual(*next);
+        unspillable.insert(*next);
+        *next += 1;
+
+        instr.map_registers(|reg| if reg == spilled { temp } else { reg });
+        if used {
+            result.push(Instr::SpillLoad(temp, slot));
+        }
+        // Nothing is executed after a Jump
+        let store = defined && !instr.is_jump();
+        result.push(instr);
+        if store {
+            result.push(Instr::SpillStore(slot, temp));
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{backend::machine::Op, ir};
+
+    fn virt(id: u32) -> Reg {
+        Reg::Virtual(id)
+    }
+
+    #[test]
+    fn reuse_registers() {
+        let instrs = vec![
+            Instr::Const(virt(0), 1),
+            Instr::Const(virt(1), 2),
+            Instr::Op(Op::Add, virt(0), virt(1)),
+            Instr::Mov(virt(2), virt(0)),
+            Instr::Mov(Reg::Fixed(0), virt(2)),
+            Instr::Return(true),
+        ];
+
+        let result = allocate(instrs);
+
+        assert_eq!(0, result.spills);
+        assert!(result.saved.is_empty());
+        // The Copies end up in the same Register as their Source
+        assert_eq!(
+            vec![
+                Instr::Const(Reg::Fixed(0), 1),
+                Instr::Const(Reg::Fixed(1), 2),
+                Instr::Op(Op::Add, Reg::Fixed(0), Reg::Fixed(1)),
+                Instr::Mov(Reg::Fixed(0), Reg::Fixed(0)),
+                Instr::Mov(Reg::Fixed(0), Reg::Fixed(0)),
+                Instr::Return(true),
+            ],
+            result.instrs
+        );
+    }
+
+    #[test]
+    fn across_call() {
+        let instrs = vec![
+            Instr::Const(virt(0), 1),
+            Instr::Call("other".to_owned(), 0),
+            Instr::Mov(virt(1), Reg::Fixed(0)),
+            Instr::Op(Op::Add, virt(1), virt(0)),
+            Instr::Mov(Reg::Fixed(0), virt(1)),
+            Instr::Return(true),
+        ];
+
+        let result = allocate(instrs);
+
+        // The Value has to survive the Call, so it needs a callee-saved
+        // Register
+        assert_eq!(Instr::Const(Reg::Fixed(8), 1), result.instrs[0]);
+        assert_eq!(vec![8], result.saved);
+        assert_eq!(0, result.spills);
+    }
+
+    #[test]
+    fn spill_under_pressure() {
+        // More Values are live at the same Time than there are Registers
+        let count = 16;
+        let mut instrs: Vec<Instr> = (0..count).map(|id| Instr::Const(virt(id), id)).collect();
+        for id in 1..count {
+            instrs.push(Instr::Op(Op::Add, virt(0), virt(id)));
+        }
+        instrs.push(Instr::Mov(Reg::Fixed(0), virt(0)));
+        instrs.push(Instr::Return(true));
+
+        let result = allocate(instrs);
+
+        assert!(result.spills > 0);
+        assert!(result
+            .instrs
+            .iter()
+            .any(|instr| matches!(instr, Instr::SpillStore(_, _))));
+        assert!(result.instrs.iter().all(|instr| {
+            instr
+                .uses()
+                .into_iter()
+                .chain(instr.defs())
+                .all(|reg| matches!(reg, Reg::Fixed(register) if register < 14))
+        }));
+    }
+
+    #[test]
+    fn live_in_loop() {
+        // The Counter is used at the Start of the Loop again, so it can't
+        // share its Register with the Value inside of the Loop
+        let instrs = vec![
+            Instr::Const(virt(0), 10),
+            Instr::Label("loop".to_owned()),
+            Instr::Const(virt(1), 0),
+            Instr::Compare(ir::Comparison::Equal, true, virt(0), virt(1)),
+            Instr::Branch(true, "end".to_owned()),
+            Instr::Const(virt(2), 1),
+            Instr::Op(Op::Sub, virt(0), virt(2)),
+            Instr::Jump("loop".to_owned()),
+            Instr::Label("end".to_owned()),
+            Instr::Return(false),
+        ];
+
+        let liveness = liveness::analyze(&instrs);
+        let counter = liveness.intervals[&0];
+        assert_eq!(1, counter.start);
+        assert_eq!(15, counter.end);
+
+        let result = allocate(instrs);
+        let counter = result.instrs[0].defs()[0];
+        assert_ne!(counter, result.instrs[2].defs()[0]);
+        assert_ne!(counter, result.instrs[5].defs()[0]);
+    }
+}
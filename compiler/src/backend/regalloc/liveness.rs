@@ -0,0 +1,195 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::backend::machine::{Instr, Reg};
+
+/// The Range of Points in which a virtual Register holds a Value that is
+/// still needed. The Point 2i is right before the i-th Instruction and
+/// the Point 2i+1 right after it
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Interval {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl Interval {
+    fn extend(&mut self, point: u32) {
+        self.start = self.start.min(point);
+        self.end = self.end.max(point);
+    }
+}
+
+/// The Points at which the Registers hold a Value that is still needed
+#[derive(Debug)]
+pub struct Liveness {
+    /// The Interval for every virtual Register
+    pub intervals: BTreeMap<u32, Interval>,
+    /// The Points at which every physical Register is in use, either
+    /// because it holds a needed Value or because it is overwritten
+    pub fixed: Vec<BTreeSet<u32>>,
+}
+
+impl Liveness {
+    fn occupy(&mut self, reg: Reg, point: u32) {
+        match reg {
+            Reg::Fixed(register) => {
+                self.fixed[register as usize].insert(point);
+            }
+            Reg::Virtual(id) => {
+                self.intervals
+                    .entry(id)
+                    .or_insert(Interval {
+                        start: point,
+                        end: point,
+                    })
+                    .extend(point);
+            }
+        };
+    }
+}
+
+/// A Sequence of Instructions, that is only entered at the Start and only
+/// left at the End
+struct Block {
+    start: usize,
+    end: usize,
+    successors: Vec<usize>,
+}
+
+/// Splits the Instructions into Blocks and connects them according to the
+/// Jumps between them
+fn blocks(instrs: &[Instr]) -> Vec<Block> {
+    let mut starts = vec![0];
+    for (index, instr) in instrs.iter().enumerate() {
+        if matches!(instr, Instr::Label(_)) {
+            starts.push(index);
+        }
+        if instr.is_jump() {
+            starts.push(index + 1);
+        }
+    }
+    starts.retain(|start| *start < instrs.len());
+    starts.sort_unstable();
+    starts.dedup();
+
+    let block_at: HashMap<usize, usize> = starts
+        .iter()
+        .enumerate()
+        .map(|(block, start)| (*start, block))
+        .collect();
+    let labels: HashMap<&str, usize> = instrs
+        .iter()
+        .enumerate()
+        .filter_map(|(index, instr)| match instr {
+            Instr::Label(name) => Some((name.as_str(), block_at[&index])),
+            _ => None,
+        })
+        .collect();
+    let target = |label: &String| labels.get(label.as_str()).copied();
+
+    let mut result = Vec::with_capacity(starts.len());
+    for (block, start) in starts.iter().enumerate() {
+        let end = starts.get(block + 1).copied().unwrap_or(instrs.len());
+        let next = Some(block + 1).filter(|next| *next < starts.len());
+
+        let successors = match &instrs[end - 1] {
+            Instr::Jump(label) => target(label).into_iter().collect(),
+            Instr::Branch(_, label) => target(label).into_iter().chain(next).collect(),
+            Instr::JumpTable(_, labels) => labels.iter().filter_map(target).collect(),
+            Instr::Return(_) | Instr::TailCall(_, _) => Vec::new(),
+            _ => next.into_iter().collect(),
+        };
+
+        result.push(Block {
+            start: *start,
+            end,
+            successors,
+        });
+    }
+
+    result
+}
+
+/// All the Registers, whose Values are changed by the Instruction
+fn overwritten(instr: &Instr) -> impl Iterator<Item = Reg> {
+    instr.defs().into_iter().chain(
+        instr
+            .clobbers()
+            .iter()
+            .map(|register| Reg::Fixed(*register)),
+    )
+}
+
+/// Determines at which Points the Registers are in use, by first finding
+/// the Registers that are live at the End of every Block and then going
+/// backwards through every Block
+pub fn analyze(instrs: &[Instr]) -> Liveness {
+    let blocks = blocks(instrs);
+
+    // The Registers read by a Block before they are set in it and the
+    // Registers set in it
+    let mut gen = Vec::with_capacity(blocks.len());
+    let mut kill = Vec::with_capacity(blocks.len());
+    for block in blocks.iter() {
+        let mut read = BTreeSet::new();
+        let mut written = BTreeSet::new();
+        for instr in instrs[block.start..block.end].iter() {
+            for reg in instr.uses() {
+                if !written.contains(&reg) {
+                    read.insert(reg);
+                }
+            }
+            written.extend(overwritten(instr));
+        }
+        gen.push(read);
+        kill.push(written);
+    }
+
+    let mut live_in: Vec<BTreeSet<Reg>> = vec![BTreeSet::new(); blocks.len()];
+    let mut live_out: Vec<BTreeSet<Reg>> = vec![BTreeSet::new(); blocks.len()];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (index, block) in blocks.iter().enumerate().rev() {
+            let out: BTreeSet<Reg> = block
+                .successors
+                .iter()
+                .flat_map(|successor| live_in[*successor].iter().copied())
+                .collect();
+            let mut input = gen[index].clone();
+            input.extend(out.difference(&kill[index]).copied());
+
+            if input != live_in[index] || out != live_out[index] {
+                live_in[index] = input;
+                live_out[index] = out;
+                changed = true;
+            }
+        }
+    }
+
+    let mut result = Liveness {
+        intervals: BTreeMap::new(),
+        fixed: vec![BTreeSet::new(); 16],
+    };
+    for (block, live_out) in blocks.iter().zip(live_out) {
+        let mut live = live_out;
+        for index in (block.start..block.end).rev() {
+            let instr = &instrs[index];
+            let before = 2 * index as u32;
+            let after = before + 1;
+
+            for reg in live.iter().copied().chain(overwritten(instr)) {
+                result.occupy(reg, after);
+            }
+
+            for reg in overwritten(instr) {
+                live.remove(&reg);
+            }
+            live.extend(instr.uses());
+            for reg in live.iter() {
+                result.occupy(*reg, before);
+            }
+        }
+    }
+
+    result
+}
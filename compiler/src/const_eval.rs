@@ -1,6 +1,11 @@
-use crate::ir;
+use crate::{ir, mir, semantics::types};
 
+/// Evaluates the given Expression at compile time, which is only possible
+/// if it is only made up of Constants and Operations on them. Just like in
+/// the generated Code, an Operation is only signed if its Type is signed
 pub fn evaluate(exp: ir::Expression) -> Option<u32> {
+    let signed = types::is_signed(&types::exp_type(&exp));
+
     match exp {
         ir::Expression::Constant(val) => match val {
             ir::Value::U32(tmp) => Some(tmp),
@@ -8,6 +13,190 @@ pub fn evaluate(exp: ir::Expression) -> Option<u32> {
             ir::Value::Short(tmp) => Some(tmp as u32),
             ir::Value::UShort(tmp) => Some(tmp as u32),
         },
+        ir::Expression::Operation(op, mut parts) if parts.len() == 2 => {
+            let right = evaluate(parts.pop()?)?;
+            let left = evaluate(parts.pop()?)?;
+
+            let op = match op {
+                ir::OP::Add => mir::BinOp::Add,
+                ir::OP::Substract => mir::BinOp::Sub,
+                ir::OP::Multiply => mir::BinOp::Mul,
+                ir::OP::Divide if signed => mir::BinOp::SDiv,
+                ir::OP::Divide => mir::BinOp::UDiv,
+                ir::OP::Modulo if signed => mir::BinOp::SRem,
+                ir::OP::Modulo => mir::BinOp::URem,
+                ir::OP::BitAnd => mir::BinOp::And,
+                ir::OP::BitOr => mir::BinOp::Or,
+                ir::OP::BitXor => mir::BinOp::Xor,
+                ir::OP::ShiftLeft => mir::BinOp::Shl,
+                ir::OP::ShiftRight if signed => mir::BinOp::AShr,
+                ir::OP::ShiftRight => mir::BinOp::LShr,
+            };
+            binary(&op, left, right)
+        }
+        ir::Expression::BitNot(inner) => Some(!evaluate(*inner)?),
+        ir::Expression::Condition(cond) => Some(evaluate_condition(*cond)? as u32),
         _ => None,
     }
 }
+
+/// Evaluates the given Condition at compile time, the Values are only
+/// compared as signed Integers if neither Side is unsigned
+pub fn evaluate_condition(cond: ir::Condition) -> Option<bool> {
+    match cond {
+        ir::Condition::Compare(comp, left, right) => {
+            let common = types::arithmetic(&types::exp_type(&left), &types::exp_type(&right));
+            let left = evaluate(left)?;
+            let right = evaluate(right)?;
+
+            let ordering = if types::is_signed(&common) {
+                (left as i32).cmp(&(right as i32))
+            } else {
+                left.cmp(&right)
+            };
+            Some(match comp {
+                ir::Comparison::Equal => ordering.is_eq(),
+                ir::Comparison::NotEqual => ordering.is_ne(),
+                ir::Comparison::LessThan => ordering.is_lt(),
+                ir::Comparison::LessEqual => ordering.is_le(),
+                ir::Comparison::GreaterThan => ordering.is_gt(),
+                ir::Comparison::GreaterEqual => ordering.is_ge(),
+            })
+        }
+        ir::Condition::And(left, right) => {
+            Some(evaluate_condition(*left)? && evaluate_condition(*right)?)
+        }
+        ir::Condition::Or(left, right) => {
+            Some(evaluate_condition(*left)? || evaluate_condition(*right)?)
+        }
+        ir::Condition::Not(inner) => Some(!evaluate_condition(*inner)?),
+    }
+}
+
+/// Performs the Operation on the two Values, like the Calculator would.
+/// Divisions by zero and Shifts by more than 31 Bits are not evaluated
+pub fn binary(op: &mir::BinOp, left: u32, right: u32) -> Option<u32> {
+    match op {
+        mir::BinOp::Add => Some(left.wrapping_add(right)),
+        mir::BinOp::Sub => Some(left.wrapping_sub(right)),
+        mir::BinOp::Mul => Some(left.wrapping_mul(right)),
+        mir::BinOp::SDiv => (left as i32).checked_div(right as i32).map(|r| r as u32),
+        mir::BinOp::UDiv => left.checked_div(right),
+        mir::BinOp::SRem => (left as i32).checked_rem(right as i32).map(|r| r as u32),
+        mir::BinOp::URem => left.checked_rem(right),
+        mir::BinOp::And => Some(left & right),
+        mir::BinOp::Or => Some(left | right),
+        mir::BinOp::Xor => Some(left ^ right),
+        mir::BinOp::Shl => left.checked_shl(right),
+        mir::BinOp::LShr => left.checked_shr(right),
+        mir::BinOp::AShr => (left as i32).checked_shr(right).map(|r| r as u32),
+    }
+}
+
+/// Performs the Operation on the Value, like the Calculator would
+pub fn unary(op: &mir::UnOp, value: u32) -> u32 {
+    match op {
+        mir::UnOp::Not => !value,
+        mir::UnOp::Neg => value.wrapping_neg(),
+        mir::UnOp::Truncate(ir::DataType::I8) => value as i8 as u32,
+        mir::UnOp::Truncate(ir::DataType::U8) => value as u8 as u32,
+        mir::UnOp::Truncate(ir::DataType::I16) => value as i16 as u32,
+        mir::UnOp::Truncate(ir::DataType::U16) => value as u16 as u32,
+        mir::UnOp::Truncate(_) => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negative_operation() {
+        let exp = ir::Expression::Operation(
+            ir::OP::Substract,
+            vec![
+                ir::Expression::Constant(ir::Value::I32(0)),
+                ir::Expression::Operation(
+                    ir::OP::Multiply,
+                    vec![
+                        ir::Expression::Constant(ir::Value::I32(2)),
+                        ir::Expression::Constant(ir::Value::I32(3)),
+                    ],
+                ),
+            ],
+        );
+
+        assert_eq!(Some(-6i32 as u32), evaluate(exp));
+    }
+
+    #[test]
+    fn division_by_zero() {
+        let exp = ir::Expression::Operation(
+            ir::OP::Divide,
+            vec![
+                ir::Expression::Constant(ir::Value::I32(2)),
+                ir::Expression::Constant(ir::Value::I32(0)),
+            ],
+        );
+
+        assert_eq!(None, evaluate(exp));
+    }
+
+    #[test]
+    fn condition() {
+        let exp = ir::Expression::Condition(Box::new(ir::Condition::And(
+            Box::new(ir::Condition::Compare(
+                ir::Comparison::LessThan,
+                ir::Expression::Constant(ir::Value::I32(-1)),
+                ir::Expression::Constant(ir::Value::I32(2)),
+            )),
+            Box::new(ir::Condition::Not(Box::new(ir::Condition::Compare(
+                ir::Comparison::Equal,
+                ir::Expression::Constant(ir::Value::I32(1)),
+                ir::Expression::Constant(ir::Value::I32(2)),
+            )))),
+        )));
+
+        assert_eq!(Some(1), evaluate(exp));
+    }
+
+    #[test]
+    fn unsigned_expressions() {
+        let max = || ir::Expression::Constant(ir::Value::U32(0xFFFFFFFF));
+        let two = || ir::Expression::Constant(ir::Value::I32(2));
+
+        let divide = ir::Expression::Operation(ir::OP::Divide, vec![max(), two()]);
+        let modulo = ir::Expression::Operation(ir::OP::Modulo, vec![max(), two()]);
+        let shift = ir::Expression::Operation(ir::OP::ShiftRight, vec![max(), two()]);
+        let compare = ir::Expression::Condition(Box::new(ir::Condition::Compare(
+            ir::Comparison::GreaterThan,
+            max(),
+            two(),
+        )));
+
+        assert_eq!(Some(0x7FFFFFFF), evaluate(divide));
+        assert_eq!(Some(1), evaluate(modulo));
+        assert_eq!(Some(0x3FFFFFFF), evaluate(shift));
+        assert_eq!(Some(1), evaluate(compare));
+    }
+
+    #[test]
+    fn unsigned_operations() {
+        assert_eq!(Some(0x7FFFFFFF), binary(&mir::BinOp::LShr, -1i32 as u32, 1));
+        assert_eq!(
+            Some(-1i32 as u32),
+            binary(&mir::BinOp::AShr, -1i32 as u32, 1)
+        );
+        assert_eq!(Some(1), binary(&mir::BinOp::UDiv, -1i32 as u32, 0xF0000000));
+        assert_eq!(None, binary(&mir::BinOp::URem, 3, 0));
+    }
+
+    #[test]
+    fn truncate() {
+        let op = mir::UnOp::Truncate(ir::DataType::I8);
+        assert_eq!(-128i32 as u32, unary(&op, 128));
+
+        let op = mir::UnOp::Truncate(ir::DataType::U16);
+        assert_eq!(0xFFFF, unary(&op, -1i32 as u32));
+    }
+}
@@ -0,0 +1,165 @@
+use std::fmt;
+
+use crate::lexer::TokenMetadata;
+
+/// How severe the Problem described by a Diagnostic is
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Level {
+    /// The Program can not be compiled
+    Error,
+    /// The Program can still be compiled, but probably does not
+    /// do what was intended
+    Warning,
+}
+
+/// A Message about some Problem in the compiled Program, which points
+/// to the Location in the Source-Code where it occured
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    /// How severe the Problem is
+    pub level: Level,
+    /// The Description of the Problem
+    pub message: String,
+    /// The File in which the Problem occured
+    pub file_name: String,
+    /// The Line on which the Problem occured, starting at 1
+    pub line: usize,
+    /// The Column in the Line at which the Problem occured, starting at 1
+    pub column: usize,
+}
+
+impl Diagnostic {
+    /// Creates a new Error at the Location of the given Token
+    pub fn error<M>(metadata: &TokenMetadata, message: M) -> Self
+    where
+        M: Into<String>,
+    {
+        Self::new(Level::Error, metadata, message)
+    }
+
+    /// Creates a new Warning at the Location of the given Token
+    pub fn warning<M>(metadata: &TokenMetadata, message: M) -> Self
+    where
+        M: Into<String>,
+    {
+        Self::new(Level::Warning, metadata, message)
+    }
+
+    fn new<M>(level: Level, metadata: &TokenMetadata, message: M) -> Self
+    where
+        M: Into<String>,
+    {
+        Self {
+            level,
+            message: message.into(),
+            file_name: metadata.file_name.clone(),
+            line: metadata.line,
+            column: metadata.column,
+        }
+    }
+
+    /// Renders the Diagnostic in the same Style as rustc, which includes
+    /// the Line from the given Source-Code with a Caret pointing to the
+    /// exact Column
+    pub fn render(&self, source: &str) -> String {
+        let mut result = self.to_string();
+
+        let source_line = match source.lines().nth(self.line.wrapping_sub(1)) {
+            Some(l) => l,
+            None => return result,
+        };
+
+        let line_number = self.line.to_string();
+        let padding = " ".repeat(line_number.len());
+
+        // Tabs are kept so the Caret lines up with the Source-Line
+        let caret_offset: String = source_line
+            .chars()
+            .take(self.column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        result.push_str(&format!("\n{} |", padding));
+        result.push_str(&format!("\n{} | {}", line_number, source_line));
+        result.push_str(&format!("\n{} | {}^", padding, caret_offset));
+
+        result
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let padding = " ".repeat(self.line.to_string().len());
+        write!(
+            f,
+            "{}: {}\n{}--> {}:{}:{}",
+            self.level, self.message, padding, self.file_name, self.line, self.column
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_with_source() {
+        let metadata = TokenMetadata {
+            file_name: "main.c".to_string(),
+            line: 2,
+            column: 13,
+        };
+        let diagnostic = Diagnostic::error(&metadata, "unexpected `;`");
+
+        let source = "int main() {\n    int a = ;\n}";
+        let expected = "error: unexpected `;`
+ --> main.c:2:13
+  |
+2 |     int a = ;
+  |             ^";
+
+        assert_eq!(expected, diagnostic.render(source));
+    }
+
+    #[test]
+    fn render_keeps_tabs() {
+        let metadata = TokenMetadata {
+            file_name: "main.c".to_string(),
+            line: 1,
+            column: 3,
+        };
+        let diagnostic = Diagnostic::warning(&metadata, "test");
+
+        let expected = "warning: test
+ --> main.c:1:3
+  |
+1 | \tx;
+  | \t ^";
+
+        assert_eq!(expected, diagnostic.render("\tx;"));
+    }
+
+    #[test]
+    fn render_without_source() {
+        let metadata = TokenMetadata {
+            file_name: "main.c".to_string(),
+            line: 10,
+            column: 1,
+        };
+        let diagnostic = Diagnostic::error(&metadata, "unexpected end of file");
+
+        assert_eq!(
+            "error: unexpected end of file\n  --> main.c:10:1",
+            diagnostic.render("")
+        );
+    }
+}
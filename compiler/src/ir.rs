@@ -1,3 +1,5 @@
+use std::{collections::HashMap, fmt};
+
 use crate::pretty_print::{self, PrettyFormatter, PrettyPrint};
 
 /// The basic Datatypes present on the Device
@@ -8,12 +10,20 @@ pub enum DataType {
     U32,
     I16,
     U16,
+    I8,
+    U8,
     Ptr(Box<DataType>),
     Array(Box<DataType>, u32),
+    /// (Name, Members)
+    /// All the Members are stored one after another in the given Order
+    Struct(String, Vec<(String, DataType)>),
+    /// (Name, Members)
+    /// All the Members are stored at the same Location
+    Union(String, Vec<(String, DataType)>),
 }
 
 /// A simple Constant Value
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Value {
     I32(i32),
     U32(u32),
@@ -23,7 +33,7 @@ pub enum Value {
 
 /// The Operations that can be Performed on
 /// the Calculator using the Operation-Expression
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum OP {
     /// Represents Addition
     Add,
@@ -33,24 +43,46 @@ pub enum OP {
     Multiply,
     /// Represents Division
     Divide,
+    /// Represents the Remainder of a Division
+    Modulo,
+    /// Represents the bitwise And
+    BitAnd,
+    /// Represents the bitwise Or
+    BitOr,
+    /// Represents the bitwise Xor
+    BitXor,
+    /// Shifts the first Value to the Left by the second Value
+    ShiftLeft,
+    /// Shifts the first Value to the Right by the second Value,
+    /// which keeps the Sign for signed Values
+    ShiftRight,
 }
 
 /// This describes the way two values should
 /// be compared
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Comparison {
     Equal,
+    NotEqual,
     LessThan,
+    LessEqual,
+    GreaterThan,
+    GreaterEqual,
 }
 
-/// Represents a simple Condition where to
-/// Expressions are compared using the given
-/// Comparison
-#[derive(Debug, PartialEq)]
-pub struct Condition {
-    pub left: Expression,
-    pub right: Expression,
-    pub comparison: Comparison,
+/// Represents a boolean Condition, that is either true or false.
+/// The Sides of And and Or are evaluated lazily, so the right Side is
+/// only evaluated if the left Side does not already decide the Result
+#[derive(Debug, PartialEq, Clone)]
+pub enum Condition {
+    /// Compares the two Expressions using the given Comparison
+    Compare(Comparison, Expression, Expression),
+    /// True if both Conditions are true
+    And(Box<Condition>, Box<Condition>),
+    /// True if at least one of the Conditions is true
+    Or(Box<Condition>, Box<Condition>),
+    /// True if the Condition is false
+    Not(Box<Condition>),
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -62,7 +94,7 @@ pub struct Variable {
 /// This represents some kind of Expression that will
 /// evaluate to a specific Value which will be stored
 /// in the Register R0
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Expression {
     /// Some form of Constant Value that won't change
     Constant(Value),
@@ -77,12 +109,30 @@ pub enum Expression {
     /// The Root is the starting Address of the Array
     /// The Offset is the Index of the Element in the Array
     Indexed(Box<Expression>, Box<Expression>),
+    /// (Root, Member)
+    /// The Root is the starting Address of the Struct or Union
+    /// The Member is the Name of the Member whose Address
+    /// should be generated
+    Member(Box<Expression>, String),
     /// Performs some kind of Mathematical or Logical Operation
     /// like Addition, Multiplication, etc.
     Operation(OP, Vec<Expression>),
+    /// (Name, Arguments, Return-Type)
     /// Calls the given Function with the given Expressions
     /// as the arguments to the Function
-    Call(String, Vec<Expression>),
+    Call(String, Vec<Expression>, DataType),
+    /// Evaluates the Condition to 1 if it is true and to 0 otherwise
+    Condition(Box<Condition>),
+    /// The bitwise Complement of the Value
+    BitNot(Box<Expression>),
+    /// (Address, Amount, Postfix)
+    /// Adds the Amount to the Value stored at the Address and
+    /// evaluates to the Value before the Addition if it is a
+    /// Postfix-Operation and to the new Value otherwise
+    Increment(Box<Expression>, i32, bool),
+    /// The Address of the given Bytes, which are stored in read-only
+    /// Memory followed by a 0-Terminator
+    StringLiteral(Vec<u8>),
     /// A simple Nop, that does nothing
     Empty,
 }
@@ -109,10 +159,36 @@ pub enum Statement {
     /// Statements as long as the given Condition is still
     /// evalutating to true
     WhileLoop(Condition, Vec<Statement>),
-    /// A simple If-Statement that executes the given
-    /// Statements if the given Condition is evalutating to
+    /// (Condition, Body, Step)
+    /// A for loop that executes the Body and then the Step as
+    /// long as the given Condition is still evaluating to true.
+    /// A Continue in the Body still executes the Step
+    ForLoop(Condition, Vec<Statement>, Vec<Statement>),
+    /// Executes the given Statements once and then repeats
+    /// them as long as the given Condition is evaluating to
     /// true
-    If(Condition, Vec<Statement>),
+    DoWhile(Vec<Statement>, Condition),
+    /// A simple If-Statement that executes the first List of
+    /// Statements if the given Condition is evalutating to
+    /// true and the second List otherwise
+    If(Condition, Vec<Statement>, Vec<Statement>),
+    /// Evaluates the Expression and jumps to the Case with the
+    /// same Value, or the default Case if there is none. Every
+    /// Case falls through into the next one until a Break
+    Switch(Expression, Vec<Case>),
+    /// Leaves the innermost Loop or Switch
+    Break,
+    /// Jumps to the next Iteration of the innermost Loop
+    Continue,
+}
+
+/// A single Case in a Switch-Statement
+#[derive(Debug, PartialEq)]
+pub struct Case {
+    /// The Value for this Case or None for the default Case
+    pub value: Option<u32>,
+    /// The Statements that are executed for this Case
+    pub statements: Vec<Statement>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -126,8 +202,106 @@ pub struct Function(
     /// The List of Statements contained in this
     /// Function
     pub Vec<Statement>,
+    /// The Attributes given in the Definition of the Function
+    pub Attributes,
 );
 
+/// The Attributes of a Function, which are given in front of its
+/// Return-Type
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Attributes {
+    /// The Function is declared as `inline`, so it is always inlined when
+    /// the Program is optimized
+    pub inline: bool,
+    /// The Function is marked with `__attribute__((noinline))`, so it is
+    /// never inlined
+    pub noinline: bool,
+    /// The Function is declared as `static`, so it can only be used in
+    /// this File
+    pub local: bool,
+    /// How the Function receives its Arguments and returns its Value
+    pub convention: CallingConvention,
+}
+
+/// How the Arguments and the Return-Value are passed between a Function and
+/// its Callers
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum CallingConvention {
+    /// The Convention used by GCC and the OS, the first four Arguments are
+    /// passed in R4-R7 and the Rest on the Stack, the Callee saves the PR.
+    /// It can also be selected explicitly using `__attribute__((renesas))`
+    #[default]
+    Renesas,
+    /// Every Argument is pushed on the Stack and the Caller saves the PR,
+    /// selected using `__attribute__((stackcall))`
+    Stack,
+}
+
+impl CallingConvention {
+    /// The Number of Registers used for Arguments
+    pub const REGISTERS: usize = 4;
+
+    /// How many of the given Number of Arguments are passed in Registers,
+    /// all the others are passed on the Stack
+    pub fn registers(&self, arguments: usize) -> usize {
+        match self {
+            Self::Renesas => arguments.min(Self::REGISTERS),
+            Self::Stack => 0,
+        }
+    }
+}
+
+/// A Variable with static Storage, like a global or static Variable,
+/// which is not stored in the Frame of a Function
+#[derive(Debug, PartialEq, Clone)]
+pub struct Global {
+    /// The Variable itself, whose Name is also used as its Label
+    pub var: Variable,
+    /// The initial Values of the Variable, one for every Element of an
+    /// Array. A Variable without any Values starts out zeroed
+    pub values: Vec<Initializer>,
+}
+
+/// A single initial Value of a Variable with static Storage
+#[derive(Debug, PartialEq, Clone)]
+pub enum Initializer {
+    /// A raw Value
+    Value(u32),
+    /// The Address of the Label with the given Name, which is only known
+    /// once the Program is assembled
+    Address(String),
+}
+
+/// The entire Program made up of all its Functions and Globals
+#[derive(Debug, PartialEq)]
+pub struct Program {
+    /// All the Variables with static Storage
+    pub globals: Vec<Global>,
+    /// All the Functions
+    pub functions: Vec<Function>,
+    /// The Calling-Convention of every declared Function, including the
+    /// ones defined in other Files
+    pub conventions: HashMap<String, CallingConvention>,
+}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Void => write!(f, "void"),
+            Self::I32 => write!(f, "int"),
+            Self::U32 => write!(f, "unsigned int"),
+            Self::I16 => write!(f, "short"),
+            Self::U16 => write!(f, "unsigned short"),
+            Self::I8 => write!(f, "char"),
+            Self::U8 => write!(f, "unsigned char"),
+            Self::Ptr(inner) => write!(f, "{}*", inner),
+            Self::Array(inner, size) => write!(f, "{}[{}]", inner, size),
+            Self::Struct(name, _) => write!(f, "struct {}", name),
+            Self::Union(name, _) => write!(f, "union {}", name),
+        }
+    }
+}
+
 impl pretty_print::PrettyPrint for Function {
     fn print(&self, formatter: &mut pretty_print::PrettyFormatter) {
         formatter.print_str("Function:");
@@ -151,15 +325,8 @@ impl pretty_print::PrettyPrint for Function {
 impl pretty_print::PrettyPrint for Statement {
     fn print(&self, formatter: &mut PrettyFormatter) {
         match self {
-            Self::WhileLoop(
-                Condition {
-                    left,
-                    right,
-                    comparison,
-                },
-                inner,
-            ) => {
-                formatter.print_str(&format!("While ({:?} {:?} {:?}):", left, comparison, right));
+            Self::WhileLoop(cond, inner) => {
+                formatter.print_str(&format!("While ({:?}):", cond));
                 let mut inner_scope = formatter.print_sub();
                 for tmp in inner.iter() {
                     tmp.print(&mut inner_scope);
@@ -193,7 +360,7 @@ impl pretty_print::PrettyPrint for Statement {
 impl pretty_print::PrettyPrint for Expression {
     fn print(&self, formatter: &mut PrettyFormatter) {
         match self {
-            Self::Call(name, vars) => {
+            Self::Call(name, vars, _) => {
                 formatter.print_str("Call:");
                 let mut sub = formatter.print_sub();
 
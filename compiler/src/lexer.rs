@@ -1,3 +1,8 @@
+use std::fmt;
+
+use crate::{diagnostic::Diagnostic, preprocessor::Line};
+
+pub mod literal;
 pub mod seperator;
 pub mod tokenizer;
 pub mod word;
@@ -6,12 +11,26 @@ pub mod word;
 pub enum Keyword {
     Integer,
     Short,
+    Char,
     Unsigned,
+    Signed,
     Void,
     Return,
     While,
     For,
     If,
+    Else,
+    Do,
+    Break,
+    Continue,
+    Switch,
+    Case,
+    Default,
+    Struct,
+    Union,
+    Static,
+    Inline,
+    Attribute,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -31,8 +50,12 @@ pub enum Token {
     OpenCurlyBrace,
     CloseCurlyBrace,
     Semicolon,
+    Colon,
     Comma,
     Constant(Value),
+    /// The Bytes of a String-Literal, with all the Escape-Sequences
+    /// already resolved and without the 0-Terminator
+    StringLiteral(Vec<u8>),
     Asterisk,
     Slash,
     And,
@@ -41,6 +64,123 @@ pub enum Token {
     GreaterThan,
     Plus,
     Minus,
+    Dot,
+    Arrow,
+    Exclamation,
+    Pipe,
+    DoubleEquals,
+    NotEquals,
+    LessEquals,
+    GreaterEquals,
+    LogicalAnd,
+    LogicalOr,
+    Percent,
+    Caret,
+    Tilde,
+    ShiftLeft,
+    ShiftRight,
+    Increment,
+    Decrement,
+    PlusEquals,
+    MinusEquals,
+    AsteriskEquals,
+    SlashEquals,
+    PercentEquals,
+    AndEquals,
+    PipeEquals,
+    CaretEquals,
+    ShiftLeftEquals,
+    ShiftRightEquals,
+}
+
+impl fmt::Display for Keyword {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let word = match self {
+            Self::Integer => "int",
+            Self::Short => "short",
+            Self::Char => "char",
+            Self::Unsigned => "unsigned",
+            Self::Signed => "signed",
+            Self::Void => "void",
+            Self::Return => "return",
+            Self::While => "while",
+            Self::For => "for",
+            Self::If => "if",
+            Self::Else => "else",
+            Self::Do => "do",
+            Self::Break => "break",
+            Self::Continue => "continue",
+            Self::Switch => "switch",
+            Self::Case => "case",
+            Self::Default => "default",
+            Self::Struct => "struct",
+            Self::Union => "union",
+            Self::Static => "static",
+            Self::Inline => "inline",
+            Self::Attribute => "__attribute__",
+        };
+        write!(f, "{}", word)
+    }
+}
+
+/// Displays the Token the same way it was written in the Source-Code
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Self::Identifier(name) => return write!(f, "{}", name),
+            Self::Keyword(keyword) => return write!(f, "{}", keyword),
+            Self::Constant(Value::Integer(value)) => return write!(f, "{}", value),
+            Self::Constant(Value::UInteger(value)) => return write!(f, "{}", value),
+            Self::StringLiteral(content) => {
+                return write!(f, "{:?}", String::from_utf8_lossy(content))
+            }
+            Self::OpenParan => "(",
+            Self::CloseParan => ")",
+            Self::OpenSquareBrace => "[",
+            Self::CloseSquareBrace => "]",
+            Self::OpenCurlyBrace => "{",
+            Self::CloseCurlyBrace => "}",
+            Self::Semicolon => ";",
+            Self::Colon => ":",
+            Self::Comma => ",",
+            Self::Asterisk => "*",
+            Self::Slash => "/",
+            Self::And => "&",
+            Self::Equals => "=",
+            Self::LessThan => "<",
+            Self::GreaterThan => ">",
+            Self::Plus => "+",
+            Self::Minus => "-",
+            Self::Dot => ".",
+            Self::Arrow => "->",
+            Self::Exclamation => "!",
+            Self::Pipe => "|",
+            Self::DoubleEquals => "==",
+            Self::NotEquals => "!=",
+            Self::LessEquals => "<=",
+            Self::GreaterEquals => ">=",
+            Self::LogicalAnd => "&&",
+            Self::LogicalOr => "||",
+            Self::Percent => "%",
+            Self::Caret => "^",
+            Self::Tilde => "~",
+            Self::ShiftLeft => "<<",
+            Self::ShiftRight => ">>",
+            Self::Increment => "++",
+            Self::Decrement => "--",
+            Self::PlusEquals => "+=",
+            Self::MinusEquals => "-=",
+            Self::AsteriskEquals => "*=",
+            Self::SlashEquals => "/=",
+            Self::PercentEquals => "%=",
+            Self::AndEquals => "&=",
+            Self::PipeEquals => "|=",
+            Self::CaretEquals => "^=",
+            Self::ShiftLeftEquals => "<<=",
+            Self::ShiftRightEquals => ">>=",
+        };
+        write!(f, "{}", text)
+    }
 }
 
 /// General Metadata associated with a single Token
@@ -51,23 +191,53 @@ pub struct TokenMetadata {
     pub file_name: String,
     /// The Line on which the Token occured
     pub line: usize,
+    /// The Column in the Line at which the Token starts
+    pub column: usize,
 }
 
 /// Tokenizes the given Content into the Tokens accepted
 /// by the Compiler
-pub fn tokenize(content: &str, file_name: String) -> Vec<(Token, TokenMetadata)> {
+pub fn tokenize(
+    content: &str,
+    file_name: String,
+) -> Result<Vec<(Token, TokenMetadata)>, Diagnostic> {
     tokenizer::tokenize(content, file_name)
 }
 
+/// Tokenizes the Lines produced by the Preprocessor, where every Token
+/// keeps the File and Line it originally came from
+pub fn tokenize_lines(lines: &[Line]) -> Result<Vec<(Token, TokenMetadata)>, Diagnostic> {
+    let mut result = Vec::new();
+
+    for line in lines.iter() {
+        let content = format!("{}\n", line.content);
+        let tokens = tokenizer::tokenize(&content, line.file_name.clone()).map_err(|mut d| {
+            d.line = line.line;
+            d
+        })?;
+
+        result.extend(tokens.into_iter().map(|(token, mut metadata)| {
+            metadata.line = line.line;
+            (token, metadata)
+        }));
+    }
+
+    Ok(result)
+}
+
 #[cfg(test)]
 #[macro_export]
 macro_rules! test_token_pair {
     ($t:expr) => {
+        test_token_pair!($t, 1, 1)
+    };
+    ($t:expr, $line:expr, $column:expr) => {
         (
             $t,
             TokenMetadata {
                 file_name: "test".to_string(),
-                line: 1,
+                line: $line,
+                column: $column,
             },
         )
     };
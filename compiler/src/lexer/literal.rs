@@ -0,0 +1,134 @@
+use std::iter::Peekable;
+
+/// Parses the Content of a String-Literal, right after the opening Quote,
+/// up to and including the closing Quote. All the Escape-Sequences are
+/// resolved and the resulting Bytes are returned without a 0-Terminator
+pub fn parse_string<I>(chars: &mut Peekable<I>) -> Option<Vec<u8>>
+where
+    I: Iterator<Item = (usize, char)>,
+{
+    let mut result = Vec::new();
+
+    loop {
+        match chars.next()? {
+            (_, '"') => return Some(result),
+            (_, '\n') => return None,
+            (_, '\\') => result.push(parse_escape(chars)?),
+            (_, other) => {
+                let mut buffer = [0; 4];
+                result.extend_from_slice(other.encode_utf8(&mut buffer).as_bytes());
+            }
+        };
+    }
+}
+
+/// Parses the Content of a Character-Literal, right after the opening
+/// Quote, up to and including the closing Quote. The Literal has to
+/// contain exactly one Character or Escape-Sequence
+pub fn parse_char<I>(chars: &mut Peekable<I>) -> Option<u8>
+where
+    I: Iterator<Item = (usize, char)>,
+{
+    let result = match chars.next()? {
+        (_, '\\') => parse_escape(chars)?,
+        (_, other) if other.is_ascii() && other != '\'' && other != '\n' => other as u8,
+        _ => return None,
+    };
+
+    match chars.next()? {
+        (_, '\'') => Some(result),
+        _ => None,
+    }
+}
+
+/// Parses a single Escape-Sequence, right after the Backslash
+fn parse_escape<I>(chars: &mut Peekable<I>) -> Option<u8>
+where
+    I: Iterator<Item = (usize, char)>,
+{
+    let result = match chars.next()? {
+        (_, 'n') => b'\n',
+        (_, 't') => b'\t',
+        (_, 'r') => b'\r',
+        (_, 'a') => 0x07,
+        (_, 'b') => 0x08,
+        (_, 'f') => 0x0c,
+        (_, 'v') => 0x0b,
+        (_, '\\') => b'\\',
+        (_, '\'') => b'\'',
+        (_, '"') => b'"',
+        (_, '?') => b'?',
+        (_, 'x') => {
+            let mut value: u32 = 0;
+            let mut digits = 0;
+            while let Some(digit) = chars.peek().and_then(|(_, c)| c.to_digit(16)) {
+                chars.next();
+                value = value * 16 + digit;
+                digits += 1;
+            }
+
+            if digits == 0 || value > 0xff {
+                return None;
+            }
+            value as u8
+        }
+        (_, first) if first.is_digit(8) => {
+            let mut value = first.to_digit(8)?;
+            // Octal-Escapes are made up of at most 3 Digits
+            for _ in 0..2 {
+                match chars.peek().and_then(|(_, c)| c.to_digit(8)) {
+                    Some(digit) => {
+                        chars.next();
+                        value = value * 8 + digit;
+                    }
+                    None => break,
+                };
+            }
+
+            if value > 0xff {
+                return None;
+            }
+            value as u8
+        }
+        _ => return None,
+    };
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_escapes() {
+        let mut chars = "a\\n\\t\\\"\\x41\\101\\0\" rest".char_indices().peekable();
+
+        assert_eq!(
+            Some(vec![b'a', b'\n', b'\t', b'"', 0x41, 0x41, 0]),
+            parse_string(&mut chars)
+        );
+        assert_eq!(Some((18, ' ')), chars.next());
+    }
+
+    #[test]
+    fn unterminated_string() {
+        let mut chars = "test\n\"".char_indices().peekable();
+
+        assert_eq!(None, parse_string(&mut chars));
+    }
+
+    #[test]
+    fn char_literals() {
+        assert_eq!(Some(b'a'), parse_char(&mut "a'".char_indices().peekable()));
+        assert_eq!(
+            Some(b'\''),
+            parse_char(&mut "\\''".char_indices().peekable())
+        );
+        assert_eq!(
+            Some(0xff),
+            parse_char(&mut "\\xff'".char_indices().peekable())
+        );
+        assert_eq!(None, parse_char(&mut "ab'".char_indices().peekable()));
+    }
+}
@@ -1,6 +1,6 @@
 use super::Token;
 
-const SEPERATORS: [(char, Token); 16] = [
+const SEPERATORS: [(char, Token); 23] = [
     ('(', Token::OpenParan),
     (')', Token::CloseParan),
     ('[', Token::OpenSquareBrace),
@@ -8,6 +8,7 @@ const SEPERATORS: [(char, Token); 16] = [
     ('{', Token::OpenCurlyBrace),
     ('}', Token::CloseCurlyBrace),
     (';', Token::Semicolon),
+    (':', Token::Colon),
     ('/', Token::Slash),
     ('*', Token::Asterisk),
     ('&', Token::And),
@@ -17,6 +18,43 @@ const SEPERATORS: [(char, Token); 16] = [
     ('-', Token::Minus),
     ('<', Token::LessThan),
     ('>', Token::GreaterThan),
+    ('.', Token::Dot),
+    ('!', Token::Exclamation),
+    ('|', Token::Pipe),
+    ('%', Token::Percent),
+    ('^', Token::Caret),
+    ('~', Token::Tilde),
+];
+
+/// The Tokens that are made up of two Seperators directly following
+/// each other
+const DOUBLE_SEPERATORS: [(char, char, Token); 19] = [
+    ('-', '>', Token::Arrow),
+    ('=', '=', Token::DoubleEquals),
+    ('!', '=', Token::NotEquals),
+    ('<', '=', Token::LessEquals),
+    ('>', '=', Token::GreaterEquals),
+    ('&', '&', Token::LogicalAnd),
+    ('|', '|', Token::LogicalOr),
+    ('<', '<', Token::ShiftLeft),
+    ('>', '>', Token::ShiftRight),
+    ('+', '+', Token::Increment),
+    ('-', '-', Token::Decrement),
+    ('+', '=', Token::PlusEquals),
+    ('-', '=', Token::MinusEquals),
+    ('*', '=', Token::AsteriskEquals),
+    ('/', '=', Token::SlashEquals),
+    ('%', '=', Token::PercentEquals),
+    ('&', '=', Token::AndEquals),
+    ('|', '=', Token::PipeEquals),
+    ('^', '=', Token::CaretEquals),
+];
+
+/// The Tokens that are made up of a Token from two Seperators directly
+/// followed by another Seperator
+const TRIPLE_SEPERATORS: [(Token, char, Token); 2] = [
+    (Token::ShiftLeft, '=', Token::ShiftLeftEquals),
+    (Token::ShiftRight, '=', Token::ShiftRightEquals),
 ];
 
 /// Parses the given Character as a Token
@@ -27,6 +65,24 @@ pub fn parse(seperator: char) -> Option<Token> {
     Some(tok.clone())
 }
 
+/// Parses the given Pair of Characters as a single Token, if they
+/// form one together
+pub fn parse_double(first: char, second: char) -> Option<Token> {
+    let (_, _, tok) = DOUBLE_SEPERATORS
+        .iter()
+        .find(|(elem_first, elem_second, _)| *elem_first == first && *elem_second == second)?;
+    Some(tok.clone())
+}
+
+/// Extends the Token made up of two Seperators with the following
+/// Character, if they form a single Token together
+pub fn parse_triple(first: &Token, third: char) -> Option<Token> {
+    let (_, _, tok) = TRIPLE_SEPERATORS
+        .iter()
+        .find(|(elem_first, elem_third, _)| elem_first == first && *elem_third == third)?;
+    Some(tok.clone())
+}
+
 /// Checks if the given Character is a valid seperating Token
 pub fn is_token(tmp: char) -> bool {
     SEPERATORS
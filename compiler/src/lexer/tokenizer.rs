@@ -1,69 +1,127 @@
-use super::{seperator, word, Token, TokenMetadata};
+use super::{literal, seperator, word, Token, TokenMetadata, Value};
+use crate::diagnostic::Diagnostic;
 
 /// Splits the Content into seperate Tokens that can be
 /// used by the Rest of the Compiler for further processing
-pub fn tokenize(content: &str, file_name: String) -> Vec<(Token, TokenMetadata)> {
+pub fn tokenize(
+    content: &str,
+    file_name: String,
+) -> Result<Vec<(Token, TokenMetadata)>, Diagnostic> {
     let mut result = Vec::new();
     let mut line = 1;
+    let mut line_start = 0;
     let mut last_char = 0;
-    let mut current = 0;
 
-    for tmp_char in content.chars() {
+    // Creates the Metadata for a Token starting at the given Index
+    let metadata = |index: usize, line: usize, line_start: usize| TokenMetadata {
+        file_name: file_name.clone(),
+        line,
+        column: content[line_start..index].chars().count() + 1,
+    };
+
+    let mut chars = content.char_indices().peekable();
+    while let Some((current, tmp_char)) = chars.next() {
         match tmp_char {
             ' ' | '\t' | '\n' => {
                 let raw_word = &content[last_char..current];
                 if let Some(parsed_word) = word::parse(raw_word) {
-                    result.push((
-                        parsed_word,
-                        TokenMetadata {
-                            file_name: file_name.clone(),
-                            line,
-                        },
-                    ));
+                    result.push((parsed_word, metadata(last_char, line, line_start)));
                 }
 
                 last_char = current + 1;
 
                 if tmp_char == '\n' {
                     line += 1;
+                    line_start = current + 1;
                 }
             }
-            _ if seperator::is_token(tmp_char) => {
+            '"' | '\'' => {
                 let raw_word = &content[last_char..current];
                 if let Some(parsed_word) = word::parse(raw_word) {
-                    result.push((
-                        parsed_word,
-                        TokenMetadata {
-                            file_name: file_name.clone(),
-                            line,
-                        },
-                    ));
+                    result.push((parsed_word, metadata(last_char, line, line_start)));
                 }
 
-                if let Some(tok) = seperator::parse(tmp_char) {
-                    result.push((
-                        tok,
-                        TokenMetadata {
-                            file_name: file_name.clone(),
-                            line,
-                        },
-                    ))
+                let token = if tmp_char == '"' {
+                    literal::parse_string(&mut chars).map(Token::StringLiteral)
+                } else {
+                    literal::parse_char(&mut chars)
+                        .map(|value| Token::Constant(Value::Integer(value as i8 as i32)))
+                };
+
+                let literal_metadata = metadata(current, line, line_start);
+                match token {
+                    Some(tok) => result.push((tok, literal_metadata)),
+                    None if tmp_char == '"' => {
+                        return Err(Diagnostic::error(
+                            &literal_metadata,
+                            "invalid string literal",
+                        ));
+                    }
+                    None => {
+                        return Err(Diagnostic::error(
+                            &literal_metadata,
+                            "invalid character literal",
+                        ));
+                    }
+                };
+
+                last_char = chars
+                    .peek()
+                    .map(|(index, _)| *index)
+                    .unwrap_or(content.len());
+            }
+            _ if seperator::is_token(tmp_char) => {
+                let raw_word = &content[last_char..current];
+                if let Some(parsed_word) = word::parse(raw_word) {
+                    result.push((parsed_word, metadata(last_char, line, line_start)));
                 }
 
-                last_char = current + 1;
+                let double = chars
+                    .peek()
+                    .and_then(|(_, next)| seperator::parse_double(tmp_char, *next));
+                let token = match double {
+                    Some(tok) => {
+                        chars.next();
+
+                        let triple = chars
+                            .peek()
+                            .and_then(|(_, next)| seperator::parse_triple(&tok, *next));
+                        match triple {
+                            Some(tok) => {
+                                chars.next();
+                                last_char = current + 3;
+                                Some(tok)
+                            }
+                            None => {
+                                last_char = current + 2;
+                                Some(tok)
+                            }
+                        }
+                    }
+                    None => {
+                        last_char = current + 1;
+                        seperator::parse(tmp_char)
+                    }
+                };
+
+                if let Some(tok) = token {
+                    result.push((tok, metadata(current, line, line_start)))
+                }
             }
             _ => {}
         };
-        current += 1;
     }
 
-    result
+    Ok(result)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::lexer::{Keyword, Value};
+    use crate::{
+        lexer::{Keyword, Value},
+        test_token_pair,
+    };
 
     #[test]
     fn simple_program() {
@@ -77,6 +135,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -84,6 +143,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 5,
                 },
             ),
             (
@@ -91,6 +151,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 9,
                 },
             ),
             (
@@ -98,6 +159,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 10,
                 },
             ),
             (
@@ -105,6 +167,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 12,
                 },
             ),
             (
@@ -112,6 +175,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 2,
+                    column: 13,
                 },
             ),
             (
@@ -119,6 +183,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 2,
+                    column: 20,
                 },
             ),
             (
@@ -126,6 +191,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 2,
+                    column: 21,
                 },
             ),
             (
@@ -133,10 +199,107 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 3,
+                    column: 9,
                 },
             ),
         ];
 
-        assert_eq!(expected, tokenize(content, "test".to_string()));
+        assert_eq!(Ok(expected), tokenize(content, "test".to_string()));
+    }
+
+    #[test]
+    fn member_access() {
+        let content = "a->b.c - d;";
+
+        let expected = vec![
+            test_token_pair!(Token::Identifier("a".to_string()), 1, 1),
+            test_token_pair!(Token::Arrow, 1, 2),
+            test_token_pair!(Token::Identifier("b".to_string()), 1, 4),
+            test_token_pair!(Token::Dot, 1, 5),
+            test_token_pair!(Token::Identifier("c".to_string()), 1, 6),
+            test_token_pair!(Token::Minus, 1, 8),
+            test_token_pair!(Token::Identifier("d".to_string()), 1, 10),
+            test_token_pair!(Token::Semicolon, 1, 11),
+        ];
+
+        assert_eq!(Ok(expected), tokenize(content, "test".to_string()));
+    }
+
+    #[test]
+    fn literals() {
+        let content = "s = \"a\\\"b\"; c='\\n';";
+
+        let expected = vec![
+            test_token_pair!(Token::Identifier("s".to_string()), 1, 1),
+            test_token_pair!(Token::Equals, 1, 3),
+            test_token_pair!(Token::StringLiteral(b"a\"b".to_vec()), 1, 5),
+            test_token_pair!(Token::Semicolon, 1, 11),
+            test_token_pair!(Token::Identifier("c".to_string()), 1, 13),
+            test_token_pair!(Token::Equals, 1, 14),
+            test_token_pair!(Token::Constant(Value::Integer(10)), 1, 15),
+            test_token_pair!(Token::Semicolon, 1, 19),
+        ];
+
+        assert_eq!(Ok(expected), tokenize(content, "test".to_string()));
+    }
+
+    #[test]
+    fn logical_operators() {
+        let content = "a<=b!=!c||d&&e==f>=g;";
+
+        let expected = vec![
+            test_token_pair!(Token::Identifier("a".to_string()), 1, 1),
+            test_token_pair!(Token::LessEquals, 1, 2),
+            test_token_pair!(Token::Identifier("b".to_string()), 1, 4),
+            test_token_pair!(Token::NotEquals, 1, 5),
+            test_token_pair!(Token::Exclamation, 1, 7),
+            test_token_pair!(Token::Identifier("c".to_string()), 1, 8),
+            test_token_pair!(Token::LogicalOr, 1, 9),
+            test_token_pair!(Token::Identifier("d".to_string()), 1, 11),
+            test_token_pair!(Token::LogicalAnd, 1, 12),
+            test_token_pair!(Token::Identifier("e".to_string()), 1, 14),
+            test_token_pair!(Token::DoubleEquals, 1, 15),
+            test_token_pair!(Token::Identifier("f".to_string()), 1, 17),
+            test_token_pair!(Token::GreaterEquals, 1, 18),
+            test_token_pair!(Token::Identifier("g".to_string()), 1, 20),
+            test_token_pair!(Token::Semicolon, 1, 21),
+        ];
+
+        assert_eq!(Ok(expected), tokenize(content, "test".to_string()));
+    }
+
+    #[test]
+    fn bitwise_operators() {
+        let content = "a<<=b>>c%~d^e|f&g;h++;--i;j+=k;";
+
+        let expected = vec![
+            test_token_pair!(Token::Identifier("a".to_string()), 1, 1),
+            test_token_pair!(Token::ShiftLeftEquals, 1, 2),
+            test_token_pair!(Token::Identifier("b".to_string()), 1, 5),
+            test_token_pair!(Token::ShiftRight, 1, 6),
+            test_token_pair!(Token::Identifier("c".to_string()), 1, 8),
+            test_token_pair!(Token::Percent, 1, 9),
+            test_token_pair!(Token::Tilde, 1, 10),
+            test_token_pair!(Token::Identifier("d".to_string()), 1, 11),
+            test_token_pair!(Token::Caret, 1, 12),
+            test_token_pair!(Token::Identifier("e".to_string()), 1, 13),
+            test_token_pair!(Token::Pipe, 1, 14),
+            test_token_pair!(Token::Identifier("f".to_string()), 1, 15),
+            test_token_pair!(Token::And, 1, 16),
+            test_token_pair!(Token::Identifier("g".to_string()), 1, 17),
+            test_token_pair!(Token::Semicolon, 1, 18),
+            test_token_pair!(Token::Identifier("h".to_string()), 1, 19),
+            test_token_pair!(Token::Increment, 1, 20),
+            test_token_pair!(Token::Semicolon, 1, 22),
+            test_token_pair!(Token::Decrement, 1, 23),
+            test_token_pair!(Token::Identifier("i".to_string()), 1, 25),
+            test_token_pair!(Token::Semicolon, 1, 26),
+            test_token_pair!(Token::Identifier("j".to_string()), 1, 27),
+            test_token_pair!(Token::PlusEquals, 1, 28),
+            test_token_pair!(Token::Identifier("k".to_string()), 1, 30),
+            test_token_pair!(Token::Semicolon, 1, 31),
+        ];
+
+        assert_eq!(Ok(expected), tokenize(content, "test".to_string()));
     }
 }
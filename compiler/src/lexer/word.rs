@@ -4,13 +4,27 @@ use super::{Keyword, Token, Value};
 pub fn parse(word: &str) -> Option<Token> {
     match word {
         "unsigned" => Some(Token::Keyword(Keyword::Unsigned)),
+        "signed" => Some(Token::Keyword(Keyword::Signed)),
         "int" => Some(Token::Keyword(Keyword::Integer)),
         "short" => Some(Token::Keyword(Keyword::Short)),
+        "char" => Some(Token::Keyword(Keyword::Char)),
         "void" => Some(Token::Keyword(Keyword::Void)),
         "return" => Some(Token::Keyword(Keyword::Return)),
         "while" => Some(Token::Keyword(Keyword::While)),
         "for" => Some(Token::Keyword(Keyword::For)),
         "if" => Some(Token::Keyword(Keyword::If)),
+        "else" => Some(Token::Keyword(Keyword::Else)),
+        "do" => Some(Token::Keyword(Keyword::Do)),
+        "break" => Some(Token::Keyword(Keyword::Break)),
+        "continue" => Some(Token::Keyword(Keyword::Continue)),
+        "switch" => Some(Token::Keyword(Keyword::Switch)),
+        "case" => Some(Token::Keyword(Keyword::Case)),
+        "default" => Some(Token::Keyword(Keyword::Default)),
+        "struct" => Some(Token::Keyword(Keyword::Struct)),
+        "union" => Some(Token::Keyword(Keyword::Union)),
+        "static" => Some(Token::Keyword(Keyword::Static)),
+        "inline" => Some(Token::Keyword(Keyword::Inline)),
+        "__attribute__" => Some(Token::Keyword(Keyword::Attribute)),
         _ if !word.is_empty() => {
             if let Ok(int_value) = word.parse() {
                 return Some(Token::Constant(Value::Integer(int_value)));
@@ -1,11 +1,16 @@
+use std::path::PathBuf;
+
 use sh::asm;
 
 pub mod backend;
 pub mod const_eval;
+pub mod diagnostic;
 pub mod ir;
 pub mod lexer;
+pub mod mir;
 pub mod optimizer;
 pub mod parser;
+pub mod preprocessor;
 pub mod semantics;
 
 pub mod pretty_print;
@@ -13,24 +18,116 @@ pub mod pretty_print;
 // The CPU in the casio calculators is 32Bit
 // Instr-DOCS: http://shared-ptr.com/sh_insns.html
 // Memory-Stuff: https://www.cemetech.net/forum/viewtopic.php?t=9334
-/// Returns the Raw Binary Instructions for the Calculator
-pub fn compile(content: &str, file: String) -> Vec<u8> {
-    let tokens = lexer::tokenize(content, file);
+/// Returns the Raw Binary Instructions for the Calculator or all the
+/// Diagnostics that prevented the Program from being compiled
+pub fn compile(content: &str, file: String) -> Result<Vec<u8>, Vec<diagnostic::Diagnostic>> {
+    compile_with_warnings(content, file).map(|(code, _)| code)
+}
+
+/// The Options that control how a Program is compiled
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// The Directories that are searched for included Files
+    pub include_paths: Vec<PathBuf>,
+    /// The Macros that are defined before the Program, as Name and Value
+    pub defines: Vec<(String, String)>,
+    /// How much the Program is optimized
+    pub optimization: optimizer::Level,
+}
+
+/// Compiles the Program like `compile`, but also returns the Warnings
+/// for a Program that could still be compiled
+pub fn compile_with_warnings(
+    content: &str,
+    file: String,
+) -> Result<(Vec<u8>, Vec<diagnostic::Diagnostic>), Vec<diagnostic::Diagnostic>> {
+    compile_with_options(content, file, &Options::default())
+}
+
+/// Compiles the Program like `compile_with_warnings`, but uses the given
+/// Options
+pub fn compile_with_options(
+    content: &str,
+    file: String,
+    options: &Options,
+) -> Result<(Vec<u8>, Vec<diagnostic::Diagnostic>), Vec<diagnostic::Diagnostic>> {
+    let (program, warnings) = analyze(content, &file, options)?;
+
+    let instr = backend::generate(program);
+
+    let code = assembler::assemble(instr).map_err(|error| internal_error(&file, error))?;
+
+    Ok((code, warnings))
+}
 
-    let raw_ir = parser::parse(&tokens);
+/// Compiles the Program into a relocatable Object, that can be linked with
+/// the Objects of other Files, which can call all of its Functions and use
+/// all of its Globals. Only the Object containing the `main` Function
+/// starts with the Code that runs the Program, so it has to be the first
+/// Object passed to the Linker
+pub fn compile_object(
+    content: &str,
+    file: String,
+    options: &Options,
+) -> Result<(assembler::object::Object, Vec<diagnostic::Diagnostic>), Vec<diagnostic::Diagnostic>> {
+    let (program, warnings) = analyze(content, &file, options)?;
 
-    if !semantics::validate(&raw_ir) {
-        panic!("Semantically not correct");
+    let instr = backend::generate_object(program);
+
+    let object = assembler::assemble_object(instr).map_err(|error| internal_error(&file, error))?;
+
+    Ok((object, warnings))
+}
+
+/// Returns the textual Form of the mid-level IR for the Program, which the
+/// Backend would generate the Code for
+pub fn dump_mir(
+    content: &str,
+    file: String,
+    options: &Options,
+) -> Result<String, Vec<diagnostic::Diagnostic>> {
+    let (program, _) = analyze(content, &file, options)?;
+
+    Ok(program.to_string())
+}
+
+/// Preprocesses, parses and optimizes the Program and turns it into the
+/// mid-level IR
+fn analyze(
+    content: &str,
+    file: &str,
+    options: &Options,
+) -> Result<(mir::Program, Vec<diagnostic::Diagnostic>), Vec<diagnostic::Diagnostic>> {
+    let mut preprocessor = preprocessor::Preprocessor::new(options.include_paths.clone());
+    for (name, value) in options.defines.iter() {
+        preprocessor.define(name, value);
     }
+    let lines = preprocessor.process(content, file).map_err(|d| vec![d])?;
+
+    let tokens = lexer::tokenize_lines(&lines).map_err(|d| vec![d])?;
 
-    let ir = optimizer::optimize(raw_ir);
+    let (program, warnings) = parser::parse(&tokens)?;
 
-    let instr = backend::generate(ir);
+    let mut program = mir::build::program(program);
+    optimizer::optimize(&mut program, options.optimization);
+
+    Ok((program, warnings))
+}
 
-    assembler::assemble(instr)
+/// The Backend generated Code that could not be assembled
+fn internal_error(file: &str, error: assembler::AssembleError) -> Vec<diagnostic::Diagnostic> {
+    let metadata = lexer::TokenMetadata {
+        file_name: file.to_owned(),
+        line: 1,
+        column: 1,
+    };
+    vec![diagnostic::Diagnostic::error(
+        &metadata,
+        format!("internal compiler error: {}", error),
+    )]
 }
 
-pub fn compile_file(file: String) -> Vec<u8> {
+pub fn compile_file(file: String) -> Result<Vec<u8>, Vec<diagnostic::Diagnostic>> {
     let content = std::fs::read_to_string(file.clone()).unwrap();
     compile(&content, file)
 }
@@ -10,6 +10,23 @@ struct RizmCompile {
     input: String,
     #[structopt(short = "o")]
     output: String,
+    /// Writes a relocatable Object, that still has to be linked, instead
+    /// of a G3A-File
+    #[structopt(short = "c")]
+    object: bool,
+    /// Writes the mid-level IR of the Program in its textual Form, instead
+    /// of compiling it
+    #[structopt(long = "mir")]
+    mir: bool,
+    /// Adds a Directory to the Search-Path for included Files
+    #[structopt(short = "I", number_of_values = 1)]
+    include: Vec<String>,
+    /// Defines a Macro, either as `NAME` or `NAME=VALUE`
+    #[structopt(short = "D", number_of_values = 1)]
+    define: Vec<String>,
+    /// The Optimization-Level, either 0, 1 or 2
+    #[structopt(short = "O", default_value = "0")]
+    optimization: compiler::optimizer::Level,
 }
 
 /// The Configuration options for the Program that
@@ -24,6 +41,17 @@ struct Config {
     internal_name: String,
 }
 
+/// Renders the Diagnostic with the Source of the File it belongs to, which
+/// can also be one of the included Files
+fn render(diagnostic: &compiler::diagnostic::Diagnostic, input: &str, content: &str) -> String {
+    if diagnostic.file_name == input {
+        return diagnostic.render(content);
+    }
+
+    let source = std::fs::read_to_string(&diagnostic.file_name).unwrap_or_default();
+    diagnostic.render(&source)
+}
+
 fn main() {
     let cmd = RizmCompile::from_args();
 
@@ -33,8 +61,66 @@ fn main() {
         internal_name: "@TEST".to_string(),
     };
 
+    let options = compiler::Options {
+        include_paths: cmd.include.iter().map(std::path::PathBuf::from).collect(),
+        defines: cmd
+            .define
+            .iter()
+            .map(|define| match define.split_once('=') {
+                Some((name, value)) => (name.to_owned(), value.to_owned()),
+                None => (define.clone(), "1".to_owned()),
+            })
+            .collect(),
+        optimization: cmd.optimization,
+    };
+
     // Actually compiling a program
-    let compiled_code = compiler::compile_file(cmd.input);
+    let content = std::fs::read_to_string(&cmd.input).unwrap();
+    if cmd.mir {
+        match compiler::dump_mir(&content, cmd.input.clone(), &options) {
+            Ok(dump) => std::fs::write(&cmd.output, dump).unwrap(),
+            Err(diagnostics) => {
+                for diagnostic in diagnostics.iter() {
+                    eprintln!("{}\n", render(diagnostic, &cmd.input, &content));
+                }
+                std::process::exit(1);
+            }
+        };
+        return;
+    }
+    if cmd.object {
+        match compiler::compile_object(&content, cmd.input.clone(), &options) {
+            Ok((object, warnings)) => {
+                for warning in warnings.iter() {
+                    eprintln!("{}\n", render(warning, &cmd.input, &content));
+                }
+                std::fs::write(&cmd.output, object.serialize()).unwrap();
+            }
+            Err(diagnostics) => {
+                for diagnostic in diagnostics.iter() {
+                    eprintln!("{}\n", render(diagnostic, &cmd.input, &content));
+                }
+                std::process::exit(1);
+            }
+        };
+        return;
+    }
+
+    let compiled_code = match compiler::compile_with_options(&content, cmd.input.clone(), &options)
+    {
+        Ok((code, warnings)) => {
+            for warning in warnings.iter() {
+                eprintln!("{}\n", render(warning, &cmd.input, &content));
+            }
+            code
+        }
+        Err(diagnostics) => {
+            for diagnostic in diagnostics.iter() {
+                eprintln!("{}\n", render(diagnostic, &cmd.input, &content));
+            }
+            std::process::exit(1);
+        }
+    };
 
     let mut compiled_file_builder =
         g3a::FileBuilder::new(conf.name.clone(), Utc::now().naive_utc());
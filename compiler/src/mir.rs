@@ -0,0 +1,616 @@
+//! The mid-level IR between the IR produced by the Parser and the Backend.
+//! Every Function is split into Blocks of three-address Instructions on
+//! virtual Registers, called Temps, and every Block ends in an explicit
+//! Terminator that transfers the Control to other Blocks.
+//!
+//! Variables whose Address is never needed are simply Temps, which can be
+//! assigned more than once, all other Variables are stored in Slots in the
+//! Frame of the Function
+
+use std::{collections::HashMap, fmt};
+
+use crate::ir;
+
+pub mod build;
+
+/// A virtual Register, that holds a single 32bit Value
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub struct Temp(pub u32);
+
+/// The Index of a Block in its Function
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub struct BlockId(pub usize);
+
+/// A Value used by an Instruction
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Operand {
+    Temp(Temp),
+    Const(u32),
+}
+
+/// The Operations on two Values
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    SDiv,
+    UDiv,
+    SRem,
+    URem,
+    And,
+    Or,
+    Xor,
+    Shl,
+    /// Shifts to the Right and fills in Zeros
+    LShr,
+    /// Shifts to the Right and keeps the Sign
+    AShr,
+}
+
+/// The Operations on a single Value
+#[derive(Debug, PartialEq, Clone)]
+pub enum UnOp {
+    Not,
+    Neg,
+    /// Truncates the Value to the Size of the Datatype and extends it
+    /// again, like storing and loading it from Memory would
+    Truncate(ir::DataType),
+}
+
+/// A Comparison of two Values, which are either compared as signed or
+/// as unsigned Integers
+#[derive(Debug, PartialEq, Clone)]
+pub struct Condition {
+    pub comparison: ir::Comparison,
+    pub signed: bool,
+}
+
+/// Where the Address of a Memory-Location comes from
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum Address {
+    /// The Slot with the given Index in the Frame
+    Slot(usize),
+    /// The Label of a Global or a String-Literal
+    Global(String),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Instr {
+    /// target = value
+    Copy(Temp, Operand),
+    /// target = left <op> right
+    Binary(Temp, BinOp, Operand, Operand),
+    /// target = <op> value
+    Unary(Temp, UnOp, Operand),
+    /// Sets the Target to 1 if the Condition is true and to 0 otherwise
+    Compare(Temp, Condition, Operand, Operand),
+    /// Loads the Address into the Target
+    Address(Temp, Address),
+    /// (Target, Address, Datatype)
+    Load(Temp, Operand, ir::DataType),
+    /// (Address, Value, Datatype)
+    Store(Operand, Operand, ir::DataType),
+    /// (Result, Name, Arguments)
+    /// The Result is None for Functions returning void
+    Call(Option<Temp>, String, Vec<Operand>),
+    /// (Result, ID, Arguments)
+    Syscall(Temp, u16, Vec<Operand>),
+}
+
+/// How the Control leaves a Block
+#[derive(Debug, PartialEq, Clone)]
+pub enum Terminator {
+    Jump(BlockId),
+    /// Jumps to the first Block if the Comparison is true and to the
+    /// second Block otherwise
+    Branch(Condition, Operand, Operand, BlockId, BlockId),
+    /// Jumps to the Block of the matching Value or the default Block
+    Switch(Operand, Vec<(u32, BlockId)>, BlockId),
+    Return(Option<Operand>),
+    /// Calls the Function with the Arguments and directly returns its
+    /// Result, the Frame of the current Function is reused for it
+    TailCall(String, Vec<Operand>),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Block {
+    pub instrs: Vec<Instr>,
+    pub terminator: Terminator,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Function {
+    pub name: String,
+    pub return_type: ir::DataType,
+    pub attributes: ir::Attributes,
+    /// The Temps that hold the Parameters when the Function is entered
+    pub params: Vec<(Temp, ir::DataType)>,
+    /// The Datatypes of the Variables stored in the Frame
+    pub slots: Vec<ir::DataType>,
+    /// The Labels and Contents of the String-Literals, which are stored
+    /// in read-only Memory
+    pub strings: Vec<(String, Vec<u8>)>,
+    /// The Blocks in the Order they are placed in, the Function starts
+    /// with the first one
+    pub blocks: Vec<Block>,
+    /// The Number of Temps used, every Temp is smaller than it
+    pub temps: u32,
+}
+
+/// The entire Program made up of all its Functions and Globals
+#[derive(Debug, PartialEq)]
+pub struct Program {
+    pub globals: Vec<ir::Global>,
+    pub functions: Vec<Function>,
+    /// The Calling-Convention of every declared Function
+    pub conventions: HashMap<String, ir::CallingConvention>,
+}
+
+impl Operand {
+    pub fn temp(&self) -> Option<Temp> {
+        match self {
+            Self::Temp(temp) => Some(*temp),
+            Self::Const(_) => None,
+        }
+    }
+}
+
+impl Condition {
+    /// The Condition that is true exactly when this one is false
+    pub fn inverse(&self) -> Self {
+        let comparison = match self.comparison {
+            ir::Comparison::Equal => ir::Comparison::NotEqual,
+            ir::Comparison::NotEqual => ir::Comparison::Equal,
+            ir::Comparison::LessThan => ir::Comparison::GreaterEqual,
+            ir::Comparison::LessEqual => ir::Comparison::GreaterThan,
+            ir::Comparison::GreaterThan => ir::Comparison::LessEqual,
+            ir::Comparison::GreaterEqual => ir::Comparison::LessThan,
+        };
+        Self {
+            comparison,
+            signed: self.signed,
+        }
+    }
+
+    /// Compares the two Values
+    pub fn evaluate(&self, left: u32, right: u32) -> bool {
+        let ordering = if self.signed {
+            (left as i32).cmp(&(right as i32))
+        } else {
+            left.cmp(&right)
+        };
+
+        match self.comparison {
+            ir::Comparison::Equal => ordering.is_eq(),
+            ir::Comparison::NotEqual => ordering.is_ne(),
+            ir::Comparison::LessThan => ordering.is_lt(),
+            ir::Comparison::LessEqual => ordering.is_le(),
+            ir::Comparison::GreaterThan => ordering.is_gt(),
+            ir::Comparison::GreaterEqual => ordering.is_ge(),
+        }
+    }
+}
+
+impl Instr {
+    /// The Temp that is set by the Instruction
+    pub fn target(&self) -> Option<Temp> {
+        match self {
+            Self::Copy(target, _)
+            | Self::Binary(target, _, _, _)
+            | Self::Unary(target, _, _)
+            | Self::Compare(target, _, _, _)
+            | Self::Address(target, _)
+            | Self::Load(target, _, _)
+            | Self::Syscall(target, _, _) => Some(*target),
+            Self::Call(target, _, _) => *target,
+            Self::Store(_, _, _) => None,
+        }
+    }
+
+    /// The Temp that is set by the Instruction, which can be replaced
+    pub fn target_mut(&mut self) -> Option<&mut Temp> {
+        match self {
+            Self::Copy(target, _)
+            | Self::Binary(target, _, _, _)
+            | Self::Unary(target, _, _)
+            | Self::Compare(target, _, _, _)
+            | Self::Address(target, _)
+            | Self::Load(target, _, _)
+            | Self::Syscall(target, _, _) => Some(target),
+            Self::Call(target, _, _) => target.as_mut(),
+            Self::Store(_, _, _) => None,
+        }
+    }
+
+    /// The Values read by the Instruction
+    pub fn operands(&self) -> Vec<&Operand> {
+        match self {
+            Self::Copy(_, value) | Self::Unary(_, _, value) | Self::Load(_, value, _) => {
+                vec![value]
+            }
+            Self::Binary(_, _, left, right)
+            | Self::Compare(_, _, left, right)
+            | Self::Store(left, right, _) => vec![left, right],
+            Self::Call(_, _, args) | Self::Syscall(_, _, args) => args.iter().collect(),
+            Self::Address(_, _) => Vec::new(),
+        }
+    }
+
+    /// The Values read by the Instruction, which can be replaced
+    pub fn operands_mut(&mut self) -> Vec<&mut Operand> {
+        match self {
+            Self::Copy(_, value) | Self::Unary(_, _, value) | Self::Load(_, value, _) => {
+                vec![value]
+            }
+            Self::Binary(_, _, left, right)
+            | Self::Compare(_, _, left, right)
+            | Self::Store(left, right, _) => vec![left, right],
+            Self::Call(_, _, args) | Self::Syscall(_, _, args) => args.iter_mut().collect(),
+            Self::Address(_, _) => Vec::new(),
+        }
+    }
+
+    /// Whether or not the Instruction only sets its Target, so it can be
+    /// removed if the Target is never used
+    pub fn is_pure(&self) -> bool {
+        !matches!(
+            self,
+            Self::Store(_, _, _) | Self::Call(_, _, _) | Self::Syscall(_, _, _)
+        )
+    }
+}
+
+impl Terminator {
+    /// The Blocks the Control can be transferred to
+    pub fn successors(&self) -> Vec<BlockId> {
+        match self {
+            Self::Jump(target) => vec![*target],
+            Self::Branch(_, _, _, first, second) => vec![*first, *second],
+            Self::Switch(_, cases, default) => cases
+                .iter()
+                .map(|(_, target)| *target)
+                .chain(std::iter::once(*default))
+                .collect(),
+            Self::Return(_) | Self::TailCall(_, _) => Vec::new(),
+        }
+    }
+
+    /// Replaces every Block the Control can be transferred to with the
+    /// Result of the given Function
+    pub fn map_successors<F>(&mut self, mut f: F)
+    where
+        F: FnMut(BlockId) -> BlockId,
+    {
+        match self {
+            Self::Jump(target) => *target = f(*target),
+            Self::Branch(_, _, _, first, second) => {
+                *first = f(*first);
+                *second = f(*second);
+            }
+            Self::Switch(_, cases, default) => {
+                for (_, target) in cases.iter_mut() {
+                    *target = f(*target);
+                }
+                *default = f(*default);
+            }
+            Self::Return(_) | Self::TailCall(_, _) => {}
+        };
+    }
+
+    /// The Values read by the Terminator
+    pub fn operands(&self) -> Vec<&Operand> {
+        match self {
+            Self::Branch(_, left, right, _, _) => vec![left, right],
+            Self::Switch(value, _, _) | Self::Return(Some(value)) => vec![value],
+            Self::TailCall(_, args) => args.iter().collect(),
+            Self::Jump(_) | Self::Return(None) => Vec::new(),
+        }
+    }
+
+    /// The Values read by the Terminator, which can be replaced
+    pub fn operands_mut(&mut self) -> Vec<&mut Operand> {
+        match self {
+            Self::Branch(_, left, right, _, _) => vec![left, right],
+            Self::Switch(value, _, _) | Self::Return(Some(value)) => vec![value],
+            Self::TailCall(_, args) => args.iter_mut().collect(),
+            Self::Jump(_) | Self::Return(None) => Vec::new(),
+        }
+    }
+}
+
+impl Function {
+    /// The Blocks from which the Control can be transferred to every Block
+    pub fn predecessors(&self) -> Vec<Vec<BlockId>> {
+        let mut result = vec![Vec::new(); self.blocks.len()];
+        for (index, block) in self.blocks.iter().enumerate() {
+            for successor in block.terminator.successors() {
+                if !result[successor.0].contains(&BlockId(index)) {
+                    result[successor.0].push(BlockId(index));
+                }
+            }
+        }
+        result
+    }
+
+    /// A new Temp, that is not used by anything else
+    pub fn temp(&mut self) -> Temp {
+        self.temps += 1;
+        Temp(self.temps - 1)
+    }
+}
+
+impl fmt::Display for Temp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "%{}", self.0)
+    }
+}
+
+impl fmt::Display for BlockId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bb{}", self.0)
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Temp(temp) => write!(f, "{}", temp),
+            Self::Const(value) if (*value as i32) < 0 => write!(f, "{}", *value as i32),
+            Self::Const(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl fmt::Display for BinOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Add => "add",
+            Self::Sub => "sub",
+            Self::Mul => "mul",
+            Self::SDiv => "sdiv",
+            Self::UDiv => "udiv",
+            Self::SRem => "srem",
+            Self::URem => "urem",
+            Self::And => "and",
+            Self::Or => "or",
+            Self::Xor => "xor",
+            Self::Shl => "shl",
+            Self::LShr => "lshr",
+            Self::AShr => "ashr",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self.comparison {
+            ir::Comparison::Equal => "eq",
+            ir::Comparison::NotEqual => "ne",
+            ir::Comparison::LessThan => "lt",
+            ir::Comparison::LessEqual => "le",
+            ir::Comparison::GreaterThan => "gt",
+            ir::Comparison::GreaterEqual => "ge",
+        };
+        match self.comparison {
+            ir::Comparison::Equal | ir::Comparison::NotEqual => write!(f, "{}", name),
+            _ if self.signed => write!(f, "s{}", name),
+            _ => write!(f, "u{}", name),
+        }
+    }
+}
+
+/// Writes the Operands separated by Commas
+fn list(f: &mut fmt::Formatter<'_>, operands: &[Operand]) -> fmt::Result {
+    for (index, operand) in operands.iter().enumerate() {
+        if index > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}", operand)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for Instr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Copy(target, value) => write!(f, "{} = {}", target, value),
+            Self::Binary(target, op, left, right) => {
+                write!(f, "{} = {} {}, {}", target, op, left, right)
+            }
+            Self::Unary(target, UnOp::Not, value) => write!(f, "{} = not {}", target, value),
+            Self::Unary(target, UnOp::Neg, value) => write!(f, "{} = neg {}", target, value),
+            Self::Unary(target, UnOp::Truncate(datatype), value) => {
+                write!(f, "{} = trunc ({}) {}", target, datatype, value)
+            }
+            Self::Compare(target, cond, left, right) => {
+                write!(f, "{} = {} {}, {}", target, cond, left, right)
+            }
+            Self::Address(target, Address::Slot(slot)) => {
+                write!(f, "{} = addr slot{}", target, slot)
+            }
+            Self::Address(target, Address::Global(label)) => {
+                write!(f, "{} = addr @{}", target, label)
+            }
+            Self::Load(target, address, datatype) => {
+                write!(f, "{} = load ({}) {}", target, datatype, address)
+            }
+            Self::Store(address, value, datatype) => {
+                write!(f, "store ({}) {}, {}", datatype, address, value)
+            }
+            Self::Call(target, name, args) => {
+                if let Some(target) = target {
+                    write!(f, "{} = ", target)?;
+                }
+                write!(f, "call {}(", name)?;
+                list(f, args)?;
+                write!(f, ")")
+            }
+            Self::Syscall(target, id, args) => {
+                write!(f, "{} = syscall 0x{:x}(", target, id)?;
+                list(f, args)?;
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Terminator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Jump(target) => write!(f, "jump {}", target),
+            Self::Branch(cond, left, right, first, second) => write!(
+                f,
+                "branch {} {}, {} ? {} : {}",
+                cond, left, right, first, second
+            ),
+            Self::Switch(value, cases, default) => {
+                write!(f, "switch {} [", value)?;
+                for (index, (case, target)) in cases.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", *case as i32, target)?;
+                }
+                write!(f, "] default {}", default)
+            }
+            Self::Return(Some(value)) => write!(f, "return {}", value),
+            Self::Return(None) => write!(f, "return"),
+            Self::TailCall(name, args) => {
+                write!(f, "tailcall {}(", name)?;
+                for (index, arg) in args.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", arg)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.attributes.local {
+            write!(f, "static ")?;
+        }
+        if self.attributes.inline {
+            write!(f, "inline ")?;
+        }
+        write!(f, "fn {}(", self.name)?;
+        for (index, (temp, datatype)) in self.params.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {}", temp, datatype)?;
+        }
+        writeln!(f, ") -> {} {{", self.return_type)?;
+
+        for (index, datatype) in self.slots.iter().enumerate() {
+            writeln!(f, "    slot{}: {}", index, datatype)?;
+        }
+        for (label, content) in self.strings.iter() {
+            writeln!(f, "    @{} = {:?}", label, String::from_utf8_lossy(content))?;
+        }
+
+        for (index, block) in self.blocks.iter().enumerate() {
+            writeln!(f, "{}:", BlockId(index))?;
+            for instr in block.instrs.iter() {
+                writeln!(f, "    {}", instr)?;
+            }
+            writeln!(f, "    {}", block.terminator)?;
+        }
+
+        write!(f, "}}")
+    }
+}
+
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for global in self.globals.iter() {
+            writeln!(f, "global @{}: {}", global.var.name, global.var.ty)?;
+        }
+        for func in self.functions.iter() {
+            writeln!(f, "{}", func)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dump() {
+        let func = Function {
+            name: "count".to_owned(),
+            return_type: ir::DataType::I32,
+            attributes: ir::Attributes::default(),
+            params: vec![(Temp(0), ir::DataType::I32)],
+            slots: vec![ir::DataType::Array(Box::new(ir::DataType::U8), 4)],
+            strings: Vec::new(),
+            blocks: vec![
+                Block {
+                    instrs: vec![
+                        Instr::Copy(Temp(1), Operand::Const(0)),
+                        Instr::Address(Temp(2), Address::Slot(0)),
+                    ],
+                    terminator: Terminator::Jump(BlockId(1)),
+                },
+                Block {
+                    instrs: vec![
+                        Instr::Binary(
+                            Temp(1),
+                            BinOp::Add,
+                            Operand::Temp(Temp(1)),
+                            Operand::Const(-1i32 as u32),
+                        ),
+                        Instr::Store(
+                            Operand::Temp(Temp(2)),
+                            Operand::Temp(Temp(1)),
+                            ir::DataType::U8,
+                        ),
+                    ],
+                    terminator: Terminator::Branch(
+                        Condition {
+                            comparison: ir::Comparison::LessThan,
+                            signed: true,
+                        },
+                        Operand::Temp(Temp(1)),
+                        Operand::Temp(Temp(0)),
+                        BlockId(1),
+                        BlockId(2),
+                    ),
+                },
+                Block {
+                    instrs: vec![Instr::Call(
+                        Some(Temp(3)),
+                        "other".to_owned(),
+                        vec![Operand::Temp(Temp(1)), Operand::Const(2)],
+                    )],
+                    terminator: Terminator::Return(Some(Operand::Temp(Temp(3)))),
+                },
+            ],
+            temps: 4,
+        };
+
+        let expected = "fn count(%0: int) -> int {
+    slot0: unsigned char[4]
+bb0:
+    %1 = 0
+    %2 = addr slot0
+    jump bb1
+bb1:
+    %1 = add %1, -1
+    store (unsigned char) %2, %1
+    branch slt %1, %0 ? bb1 : bb2
+bb2:
+    %3 = call other(%1, 2)
+    return %3
+}";
+        assert_eq!(expected, func.to_string());
+    }
+}
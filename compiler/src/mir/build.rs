@@ -0,0 +1,651 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    ir,
+    mir::{Address, Block, BlockId, Function, Instr, Operand, Program, Temp, Terminator, UnOp},
+    semantics::types,
+};
+
+mod address;
+mod condition;
+mod expression;
+
+/// Where the Value of a Variable is stored
+#[derive(Debug, PartialEq, Clone)]
+enum Location {
+    /// In the Temp, for Variables whose Address is never needed
+    Temp(Temp),
+    /// In the Slot of the Frame with the given Index
+    Slot(usize),
+    /// At the Label of a Variable with static Storage
+    Global(String),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+struct Variable {
+    location: Location,
+    datatype: ir::DataType,
+}
+
+/// The State needed while turning a single Function into Blocks
+struct Builder {
+    name: String,
+    vars: HashMap<String, Variable>,
+    slots: Vec<ir::DataType>,
+    strings: Vec<(String, Vec<u8>)>,
+    /// All the Blocks created so far, with the Terminator still missing for
+    /// the ones that are not finished yet
+    blocks: Vec<(Vec<Instr>, Option<Terminator>)>,
+    /// The Order in which the Blocks were started, which is also the Order
+    /// in which they are placed
+    order: Vec<BlockId>,
+    current: BlockId,
+    temps: u32,
+    /// The Blocks a Break jumps to, the innermost one last
+    breaks: Vec<BlockId>,
+    /// The Blocks a Continue jumps to, the innermost one last
+    continues: Vec<BlockId>,
+}
+
+impl Builder {
+    fn new(name: String, vars: HashMap<String, Variable>) -> Self {
+        Self {
+            name,
+            vars,
+            slots: Vec::new(),
+            strings: Vec::new(),
+            blocks: vec![(Vec::new(), None)],
+            order: vec![BlockId(0)],
+            current: BlockId(0),
+            temps: 0,
+            breaks: Vec::new(),
+            continues: Vec::new(),
+        }
+    }
+
+    /// A new Temp, that is not used by anything else
+    fn temp(&mut self) -> Temp {
+        let result = Temp(self.temps);
+        self.temps += 1;
+        result
+    }
+
+    /// A new Block, which is only placed once it is started
+    fn block(&mut self) -> BlockId {
+        self.blocks.push((Vec::new(), None));
+        BlockId(self.blocks.len() - 1)
+    }
+
+    fn terminated(&self) -> bool {
+        self.blocks[self.current.0].1.is_some()
+    }
+
+    /// Continues in the given Block, the current Block falls through into
+    /// it if it was not terminated already
+    fn start(&mut self, block: BlockId) {
+        self.terminate(Terminator::Jump(block));
+        self.order.push(block);
+        self.current = block;
+    }
+
+    /// Adds the Instruction to the current Block. Instructions following a
+    /// Terminator can never be reached, but they still get their own Block
+    fn emit(&mut self, instr: Instr) {
+        if self.terminated() {
+            let dead = self.block();
+            self.order.push(dead);
+            self.current = dead;
+        }
+        self.blocks[self.current.0].0.push(instr);
+    }
+
+    /// Ends the current Block, unless it already ended and the Terminator
+    /// could never be reached anyway
+    fn terminate(&mut self, terminator: Terminator) {
+        if !self.terminated() {
+            self.blocks[self.current.0].1 = Some(terminator);
+        }
+    }
+
+    /// Loads the Address of the Variable into a new Temp
+    fn address(&mut self, name: &str) -> Temp {
+        let address = match &self.vars[name].location {
+            Location::Slot(slot) => Address::Slot(*slot),
+            Location::Global(label) => Address::Global(label.clone()),
+            Location::Temp(_) => panic!("Variable '{}' has no Address", name),
+        };
+
+        let result = self.temp();
+        self.emit(Instr::Address(result, address));
+        result
+    }
+
+    /// Assigns the Value to the Temp of a Variable, truncated to the Size
+    /// of its Datatype like a Store into Memory followed by a Load
+    fn truncate(&mut self, target: Temp, value: Operand, datatype: &ir::DataType) {
+        match datatype {
+            ir::DataType::I8 | ir::DataType::U8 | ir::DataType::I16 | ir::DataType::U16 => {
+                self.emit(Instr::Unary(
+                    target,
+                    UnOp::Truncate(datatype.clone()),
+                    value,
+                ));
+            }
+            _ => self.emit(Instr::Copy(target, value)),
+        };
+    }
+
+    /// Places the Blocks in the Order they were started and numbers them
+    /// accordingly
+    fn finish(mut self, func: &ir::Function, params: Vec<(Temp, ir::DataType)>) -> Function {
+        // Functions without a Return at the End still need to return
+        self.terminate(Terminator::Return(None));
+
+        let numbers: HashMap<BlockId, BlockId> = self
+            .order
+            .iter()
+            .enumerate()
+            .map(|(index, block)| (*block, BlockId(index)))
+            .collect();
+
+        let mut blocks = Vec::with_capacity(self.order.len());
+        for id in self.order.iter() {
+            let (instrs, terminator) = std::mem::take(&mut self.blocks[id.0]);
+            let mut terminator = terminator.unwrap();
+            terminator.map_successors(|target| numbers[&target]);
+            blocks.push(Block { instrs, terminator });
+        }
+
+        Function {
+            name: func.0.clone(),
+            return_type: func.1.clone(),
+            attributes: func.4.clone(),
+            params,
+            slots: self.slots,
+            strings: self.strings,
+            blocks,
+            temps: self.temps,
+        }
+    }
+}
+
+/// Turns the entire Program into the mid-level IR
+pub fn program(program: ir::Program) -> Program {
+    let functions = program
+        .functions
+        .iter()
+        .map(|func| function(func, &program.globals))
+        .collect();
+
+    Program {
+        globals: program.globals,
+        functions,
+        conventions: program.conventions,
+    }
+}
+
+/// Turns the Function into Blocks, every Variable whose Address is never
+/// needed becomes a Temp and all the others are stored in Slots
+pub fn function(func: &ir::Function, globals: &[ir::Global]) -> Function {
+    let mut taken = HashSet::new();
+    address::statements(&func.3, &mut taken);
+
+    // Local Variables hide the Globals with the same Name
+    let vars = globals
+        .iter()
+        .map(|global| {
+            let var = Variable {
+                location: Location::Global(global.var.name.clone()),
+                datatype: global.var.ty.clone(),
+            };
+            (global.var.name.clone(), var)
+        })
+        .collect();
+    let mut builder = Builder::new(func.0.clone(), vars);
+
+    // The Parameters arrive in the first Temps
+    let params: Vec<(Temp, ir::DataType)> = func
+        .2
+        .iter()
+        .map(|(_, datatype)| (builder.temp(), datatype.clone()))
+        .collect();
+
+    declarations(&func.3, &taken, &mut builder);
+
+    // Parameters whose Address is needed are copied into the Frame
+    for ((name, datatype), (temp, _)) in func.2.iter().zip(params.iter()) {
+        let location = if in_memory(name, datatype, &taken) {
+            builder.slots.push(datatype.clone());
+            Location::Slot(builder.slots.len() - 1)
+        } else {
+            Location::Temp(*temp)
+        };
+        builder.vars.insert(
+            name.clone(),
+            Variable {
+                location: location.clone(),
+                datatype: datatype.clone(),
+            },
+        );
+
+        if let Location::Slot(_) = location {
+            let address = builder.address(name);
+            builder.emit(Instr::Store(
+                Operand::Temp(address),
+                Operand::Temp(*temp),
+                datatype.clone(),
+            ));
+        }
+    }
+
+    for statement in func.3.iter() {
+        generate(statement, &mut builder);
+    }
+
+    builder.finish(func, params)
+}
+
+/// Whether or not the Variable has to be stored in Memory, because its
+/// Address is needed. Arrays, Structs and Unions are always used by their
+/// Address
+fn in_memory(name: &str, datatype: &ir::DataType, taken: &HashSet<String>) -> bool {
+    match datatype {
+        ir::DataType::Array(_, _) | ir::DataType::Struct(_, _) | ir::DataType::Union(_, _) => true,
+        _ => taken.contains(name),
+    }
+}
+
+/// Decides where every Variable declared in the Statements is stored
+fn declarations(statements: &[ir::Statement], taken: &HashSet<String>, builder: &mut Builder) {
+    for tmp in statements.iter() {
+        match tmp {
+            ir::Statement::Declaration(var) => {
+                let location = if in_memory(&var.name, &var.ty, taken) {
+                    builder.slots.push(var.ty.clone());
+                    Location::Slot(builder.slots.len() - 1)
+                } else {
+                    Location::Temp(builder.temp())
+                };
+
+                builder.vars.insert(
+                    var.name.clone(),
+                    Variable {
+                        location,
+                        datatype: var.ty.clone(),
+                    },
+                );
+            }
+            ir::Statement::WhileLoop(_, inner) | ir::Statement::DoWhile(inner, _) => {
+                declarations(inner, taken, builder);
+            }
+            ir::Statement::If(_, first, second) | ir::Statement::ForLoop(_, first, second) => {
+                declarations(first, taken, builder);
+                declarations(second, taken, builder);
+            }
+            ir::Statement::Switch(_, cases) => {
+                for case in cases.iter() {
+                    declarations(&case.statements, taken, builder);
+                }
+            }
+            _ => {}
+        };
+    }
+}
+
+/// Generates the Instructions and Blocks for the given Statement
+fn generate(statement: &ir::Statement, builder: &mut Builder) {
+    match statement {
+        ir::Statement::DerefAssignment(destination, exp) => {
+            // Evaluate the Target first
+            let address = expression::generate(destination, builder);
+            let value = expression::generate(exp, builder);
+
+            let datatype = match types::exp_type(destination) {
+                ir::DataType::Ptr(data_type) => *data_type,
+                _ => ir::DataType::U8,
+            };
+            builder.emit(Instr::Store(address, value, datatype));
+        }
+        ir::Statement::Assignment(variable, exp) => {
+            let value = expression::generate(exp, builder);
+
+            let var = builder.vars[&variable.name].clone();
+            match var.location {
+                Location::Temp(temp) => builder.truncate(temp, value, &var.datatype),
+                _ => {
+                    let address = builder.address(&variable.name);
+                    builder.emit(Instr::Store(Operand::Temp(address), value, var.datatype));
+                }
+            };
+        }
+        ir::Statement::Return(ir::Expression::Empty) => {
+            builder.terminate(Terminator::Return(None));
+        }
+        ir::Statement::Return(exp) => {
+            let value = expression::generate(exp, builder);
+            builder.terminate(Terminator::Return(Some(value)));
+        }
+        ir::Statement::SingleExpression(ir::Expression::Empty) => {}
+        ir::Statement::SingleExpression(exp) => {
+            expression::generate(exp, builder);
+        }
+        ir::Statement::WhileLoop(cond, inner) => {
+            let header = builder.block();
+            let body = builder.block();
+            let end = builder.block();
+
+            builder.start(header);
+            condition::branch(cond, body, end, builder);
+
+            builder.start(body);
+            body_of_loop(inner, end, header, builder);
+            builder.terminate(Terminator::Jump(header));
+
+            builder.start(end);
+        }
+        ir::Statement::ForLoop(cond, inner, step) => {
+            let header = builder.block();
+            let body = builder.block();
+            let step_block = builder.block();
+            let end = builder.block();
+
+            builder.start(header);
+            condition::branch(cond, body, end, builder);
+
+            builder.start(body);
+            body_of_loop(inner, end, step_block, builder);
+
+            builder.start(step_block);
+            for tmp in step.iter() {
+                generate(tmp, builder);
+            }
+            builder.terminate(Terminator::Jump(header));
+
+            builder.start(end);
+        }
+        ir::Statement::DoWhile(inner, cond) => {
+            let body = builder.block();
+            let cond_block = builder.block();
+            let end = builder.block();
+
+            builder.start(body);
+            body_of_loop(inner, end, cond_block, builder);
+
+            builder.start(cond_block);
+            condition::branch(cond, body, end, builder);
+
+            builder.start(end);
+        }
+        ir::Statement::If(cond, inner, other) => {
+            let then = builder.block();
+            let end = builder.block();
+            // Without an Else-Branch there is nothing to skip at the End
+            let otherwise = if other.is_empty() {
+                end
+            } else {
+                builder.block()
+            };
+
+            condition::branch(cond, then, otherwise, builder);
+
+            builder.start(then);
+            for tmp in inner.iter() {
+                generate(tmp, builder);
+            }
+
+            if !other.is_empty() {
+                // Skip the Else-Branch
+                builder.terminate(Terminator::Jump(end));
+
+                builder.start(otherwise);
+                for tmp in other.iter() {
+                    generate(tmp, builder);
+                }
+            }
+
+            builder.start(end);
+        }
+        ir::Statement::Switch(value, cases) => {
+            let value = expression::generate(value, builder);
+
+            let blocks: Vec<BlockId> = cases.iter().map(|_| builder.block()).collect();
+            let end = builder.block();
+
+            // Where to go if no Case matches the Value
+            let default = cases
+                .iter()
+                .position(|case| case.value.is_none())
+                .map(|index| blocks[index])
+                .unwrap_or(end);
+            let values = cases
+                .iter()
+                .zip(blocks.iter())
+                .filter_map(|(case, block)| case.value.map(|value| (value, *block)))
+                .collect();
+            builder.terminate(Terminator::Switch(value, values, default));
+
+            // The Bodies of all the Cases, which simply fall through into
+            // the next one
+            builder.breaks.push(end);
+            for (case, block) in cases.iter().zip(blocks) {
+                builder.start(block);
+                for tmp in case.statements.iter() {
+                    generate(tmp, builder);
+                }
+            }
+            builder.breaks.pop();
+
+            builder.start(end);
+        }
+        // Breaks and Continues outside of a Loop have already been reported
+        ir::Statement::Break => {
+            if let Some(&target) = builder.breaks.last() {
+                builder.terminate(Terminator::Jump(target));
+            }
+        }
+        ir::Statement::Continue => {
+            if let Some(&target) = builder.continues.last() {
+                builder.terminate(Terminator::Jump(target));
+            }
+        }
+        ir::Statement::Declaration(_) => {}
+    };
+}
+
+/// Generates the Body of a Loop, in which a Break jumps to the End-Block
+/// and a Continue to the Block that starts the next Iteration
+fn body_of_loop(statements: &[ir::Statement], end: BlockId, next: BlockId, builder: &mut Builder) {
+    builder.breaks.push(end);
+    builder.continues.push(next);
+    for tmp in statements.iter() {
+        generate(tmp, builder);
+    }
+    builder.continues.pop();
+    builder.breaks.pop();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir::Variable,
+        mir::{BinOp, Condition},
+    };
+
+    fn var(name: &str) -> ir::Expression {
+        ir::Expression::Variable(Variable::new_str(name, ir::DataType::I32))
+    }
+
+    fn int(value: i32) -> ir::Expression {
+        ir::Expression::Constant(ir::Value::I32(value))
+    }
+
+    #[test]
+    fn variables_in_temps_and_slots() {
+        let func = ir::Function(
+            "test".to_owned(),
+            ir::DataType::Void,
+            vec![("param".to_owned(), ir::DataType::U8)],
+            vec![
+                ir::Statement::Declaration(Variable::new_str("value", ir::DataType::I32)),
+                ir::Statement::Declaration(Variable::new_str("stored", ir::DataType::I32)),
+                ir::Statement::Assignment(Variable::new_str("value", ir::DataType::I32), int(1)),
+                ir::Statement::SingleExpression(ir::Expression::Reference(Variable::new_str(
+                    "stored",
+                    ir::DataType::I32,
+                ))),
+                ir::Statement::SingleExpression(ir::Expression::Reference(Variable::new_str(
+                    "param",
+                    ir::DataType::U8,
+                ))),
+            ],
+            ir::Attributes::default(),
+        );
+
+        let result = function(&func, &[]);
+
+        // The Parameter is copied into the Frame, as its Address is needed
+        assert_eq!(vec![(Temp(0), ir::DataType::U8)], result.params);
+        assert_eq!(vec![ir::DataType::I32, ir::DataType::U8], result.slots);
+        assert_eq!(
+            vec![Block {
+                instrs: vec![
+                    Instr::Address(Temp(2), Address::Slot(1)),
+                    Instr::Store(
+                        Operand::Temp(Temp(2)),
+                        Operand::Temp(Temp(0)),
+                        ir::DataType::U8
+                    ),
+                    Instr::Copy(Temp(1), Operand::Const(1)),
+                    Instr::Address(Temp(3), Address::Slot(0)),
+                    Instr::Address(Temp(4), Address::Slot(1)),
+                ],
+                terminator: Terminator::Return(None),
+            }],
+            result.blocks
+        );
+    }
+
+    #[test]
+    fn while_loop() {
+        let func = ir::Function(
+            "test".to_owned(),
+            ir::DataType::I32,
+            vec![("n".to_owned(), ir::DataType::I32)],
+            vec![
+                ir::Statement::WhileLoop(
+                    ir::Condition::Compare(ir::Comparison::GreaterThan, var("n"), int(0)),
+                    vec![ir::Statement::Assignment(
+                        Variable::new_str("n", ir::DataType::I32),
+                        ir::Expression::Operation(ir::OP::Substract, vec![var("n"), int(1)]),
+                    )],
+                ),
+                ir::Statement::Return(var("n")),
+            ],
+            ir::Attributes::default(),
+        );
+
+        let result = function(&func, &[]);
+
+        let expected = vec![
+            Block {
+                instrs: vec![],
+                terminator: Terminator::Jump(BlockId(1)),
+            },
+            Block {
+                instrs: vec![],
+                terminator: Terminator::Branch(
+                    Condition {
+                        comparison: ir::Comparison::GreaterThan,
+                        signed: true,
+                    },
+                    Operand::Temp(Temp(0)),
+                    Operand::Const(0),
+                    BlockId(2),
+                    BlockId(3),
+                ),
+            },
+            Block {
+                instrs: vec![
+                    Instr::Binary(
+                        Temp(1),
+                        BinOp::Sub,
+                        Operand::Temp(Temp(0)),
+                        Operand::Const(1),
+                    ),
+                    Instr::Copy(Temp(0), Operand::Temp(Temp(1))),
+                ],
+                terminator: Terminator::Jump(BlockId(1)),
+            },
+            Block {
+                instrs: vec![],
+                terminator: Terminator::Return(Some(Operand::Temp(Temp(0)))),
+            },
+        ];
+        assert_eq!(expected, result.blocks);
+    }
+
+    #[test]
+    fn short_circuit() {
+        let func = ir::Function(
+            "test".to_owned(),
+            ir::DataType::Void,
+            vec![("a".to_owned(), ir::DataType::I32)],
+            vec![ir::Statement::If(
+                ir::Condition::Or(
+                    Box::new(ir::Condition::Compare(
+                        ir::Comparison::Equal,
+                        var("a"),
+                        int(1),
+                    )),
+                    Box::new(ir::Condition::Compare(
+                        ir::Comparison::Equal,
+                        var("a"),
+                        int(2),
+                    )),
+                ),
+                vec![ir::Statement::Return(ir::Expression::Empty)],
+                vec![],
+            )],
+            ir::Attributes::default(),
+        );
+
+        let result = function(&func, &[]);
+
+        // The second Comparison is only made if the first one is false
+        let successors: Vec<Vec<BlockId>> = result
+            .blocks
+            .iter()
+            .map(|block| block.terminator.successors())
+            .collect();
+        assert_eq!(
+            vec![
+                vec![BlockId(2), BlockId(1)],
+                vec![BlockId(2), BlockId(3)],
+                vec![],
+                vec![],
+            ],
+            successors
+        );
+    }
+
+    #[test]
+    fn dead_code_after_return() {
+        let func = ir::Function(
+            "test".to_owned(),
+            ir::DataType::I32,
+            vec![],
+            vec![ir::Statement::Return(int(1)), ir::Statement::Return(int(2))],
+            ir::Attributes::default(),
+        );
+
+        let result = function(&func, &[]);
+
+        // The second Return can never be reached, so it is dropped
+        assert_eq!(1, result.blocks.len());
+        assert_eq!(
+            Terminator::Return(Some(Operand::Const(1))),
+            result.blocks[0].terminator
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use std::collections::HashSet;
+
+use crate::ir;
+
+/// Collects the Names of all the Variables whose Address is needed by the
+/// Statements
+pub fn statements(list: &[ir::Statement], taken: &mut HashSet<String>) {
+    for tmp in list.iter() {
+        match tmp {
+            ir::Statement::Assignment(_, exp)
+            | ir::Statement::Return(exp)
+            | ir::Statement::SingleExpression(exp) => expression(exp, taken),
+            ir::Statement::DerefAssignment(target, exp) => {
+                expression(target, taken);
+                expression(exp, taken);
+            }
+            ir::Statement::WhileLoop(cond, inner) | ir::Statement::DoWhile(inner, cond) => {
+                condition(cond, taken);
+                statements(inner, taken);
+            }
+            ir::Statement::ForLoop(cond, first, second)
+            | ir::Statement::If(cond, first, second) => {
+                condition(cond, taken);
+                statements(first, taken);
+                statements(second, taken);
+            }
+            ir::Statement::Switch(value, cases) => {
+                expression(value, taken);
+                for case in cases.iter() {
+                    statements(&case.statements, taken);
+                }
+            }
+            ir::Statement::Declaration(_) | ir::Statement::Break | ir::Statement::Continue => {}
+        };
+    }
+}
+
+fn condition(cond: &ir::Condition, taken: &mut HashSet<String>) {
+    match cond {
+        ir::Condition::Compare(_, left, right) => {
+            expression(left, taken);
+            expression(right, taken);
+        }
+        ir::Condition::And(first, second) | ir::Condition::Or(first, second) => {
+            condition(first, taken);
+            condition(second, taken);
+        }
+        ir::Condition::Not(inner) => condition(inner, taken),
+    };
+}
+
+fn expression(exp: &ir::Expression, taken: &mut HashSet<String>) {
+    match exp {
+        ir::Expression::Reference(var) => {
+            taken.insert(var.name.clone());
+        }
+        // Incrementing a Variable directly does not need its Address
+        ir::Expression::Increment(address, _, _) => {
+            if !matches!(address.as_ref(), ir::Expression::Reference(_)) {
+                expression(address, taken);
+            }
+        }
+        ir::Expression::Dereference(inner) | ir::Expression::BitNot(inner) => {
+            expression(inner, taken)
+        }
+        ir::Expression::Member(root, _) => expression(root, taken),
+        ir::Expression::Indexed(root, offset) => {
+            expression(root, taken);
+            expression(offset, taken);
+        }
+        ir::Expression::Operation(_, parts) | ir::Expression::Call(_, parts, _) => {
+            for part in parts.iter() {
+                expression(part, taken);
+            }
+        }
+        ir::Expression::Condition(cond) => condition(cond, taken),
+        ir::Expression::Constant(_)
+        | ir::Expression::Variable(_)
+        | ir::Expression::StringLiteral(_)
+        | ir::Expression::Empty => {}
+    };
+}
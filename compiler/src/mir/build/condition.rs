@@ -0,0 +1,79 @@
+use crate::{
+    ir,
+    mir::{BlockId, Condition, Instr, Operand, Terminator},
+    semantics::types,
+};
+
+use super::{expression, Builder};
+
+/// Ends the current Block with Branches to the first Block if the
+/// Condition is true and to the second Block otherwise. The right Side of
+/// And and Or is only evaluated, if the left Side does not already decide
+/// the Result
+pub fn branch(cond: &ir::Condition, then: BlockId, otherwise: BlockId, builder: &mut Builder) {
+    match cond {
+        ir::Condition::Compare(comp, left, right) => {
+            let (condition, left, right) = compare(comp, left, right, builder);
+            builder.terminate(Terminator::Branch(condition, left, right, then, otherwise));
+        }
+        ir::Condition::Not(inner) => branch(inner, otherwise, then, builder),
+        ir::Condition::And(first, second) => {
+            let next = builder.block();
+            branch(first, next, otherwise, builder);
+            builder.start(next);
+            branch(second, then, otherwise, builder);
+        }
+        ir::Condition::Or(first, second) => {
+            let next = builder.block();
+            branch(first, then, next, builder);
+            builder.start(next);
+            branch(second, then, otherwise, builder);
+        }
+    };
+}
+
+/// Evaluates the Condition as a Value, which is 1 if it is true and 0
+/// otherwise
+pub fn value(cond: &ir::Condition, builder: &mut Builder) -> Operand {
+    let result = builder.temp();
+    match cond {
+        ir::Condition::Compare(comp, left, right) => {
+            let (condition, left, right) = compare(comp, left, right, builder);
+            builder.emit(Instr::Compare(result, condition, left, right));
+        }
+        _ => {
+            let then = builder.block();
+            let otherwise = builder.block();
+            let end = builder.block();
+
+            branch(cond, then, otherwise, builder);
+            builder.start(then);
+            builder.emit(Instr::Copy(result, Operand::Const(1)));
+            builder.terminate(Terminator::Jump(end));
+            builder.start(otherwise);
+            builder.emit(Instr::Copy(result, Operand::Const(0)));
+            builder.start(end);
+        }
+    };
+    Operand::Temp(result)
+}
+
+/// Evaluates both Sides of the Comparison
+fn compare(
+    comp: &ir::Comparison,
+    left: &ir::Expression,
+    right: &ir::Expression,
+    builder: &mut Builder,
+) -> (Condition, Operand, Operand) {
+    // Like for other Operations, the Comparison is only unsigned if one of
+    // the Sides is unsigned after the usual arithmetic Conversions
+    let common = types::arithmetic(&types::exp_type(left), &types::exp_type(right));
+    let condition = Condition {
+        comparison: comp.clone(),
+        signed: types::is_signed(&common),
+    };
+
+    let left = expression::generate(left, builder);
+    let right = expression::generate(right, builder);
+    (condition, left, right)
+}
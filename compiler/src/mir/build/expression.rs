@@ -0,0 +1,250 @@
+use crate::{
+    backend::internal::get_size,
+    ir,
+    mir::{Address, BinOp, Instr, Operand, UnOp},
+    semantics::{self, types},
+};
+
+use super::{condition, Builder, Location};
+
+/// Generates the Instructions for the given Expression and returns the
+/// Operand holding its Result, which may be the Temp of a Variable
+pub fn generate(exp: &ir::Expression, builder: &mut Builder) -> Operand {
+    match exp {
+        ir::Expression::Call(name, exps, return_type) => match name.as_str() {
+            // The Arguments have already been checked
+            semantics::SYSCALL => {
+                let syscall_id = match exps.first() {
+                    Some(ir::Expression::Constant(ir::Value::I32(val))) => *val,
+                    _ => return Operand::Const(0),
+                };
+
+                let args = (1..=4)
+                    .map(|i| generate(exps.get(i).unwrap(), builder))
+                    .collect();
+
+                let result = builder.temp();
+                builder.emit(Instr::Syscall(result, syscall_id as u16, args));
+                Operand::Temp(result)
+            }
+            _ => {
+                // The Arguments are evaluated starting with the last one
+                let mut args: Vec<Operand> = exps
+                    .iter()
+                    .rev()
+                    .map(|arg| generate(arg, builder))
+                    .collect();
+                args.reverse();
+
+                if *return_type == ir::DataType::Void {
+                    builder.emit(Instr::Call(None, name.clone(), args));
+                    return Operand::Const(0);
+                }
+
+                let result = builder.temp();
+                builder.emit(Instr::Call(Some(result), name.clone(), args));
+                Operand::Temp(result)
+            }
+        },
+        ir::Expression::Constant(ir::Value::I32(val)) => Operand::Const(*val as u32),
+        ir::Expression::Constant(ir::Value::U32(val)) => Operand::Const(*val),
+        ir::Expression::Constant(ir::Value::Short(val)) => Operand::Const(*val as u32),
+        ir::Expression::Constant(ir::Value::UShort(val)) => Operand::Const(*val as u32),
+        ir::Expression::Variable(variable) => {
+            let var = builder.vars[&variable.name].clone();
+
+            match (&var.location, &var.datatype) {
+                (Location::Temp(temp), _) => Operand::Temp(*temp),
+                // These are used by their Address
+                (_, ir::DataType::Array(_, _))
+                | (_, ir::DataType::Struct(_, _))
+                | (_, ir::DataType::Union(_, _)) => Operand::Temp(builder.address(&variable.name)),
+                (_, datatype) => {
+                    let address = builder.address(&variable.name);
+                    let result = builder.temp();
+                    builder.emit(Instr::Load(
+                        result,
+                        Operand::Temp(address),
+                        datatype.clone(),
+                    ));
+                    Operand::Temp(result)
+                }
+            }
+        }
+        ir::Expression::Reference(variable) => Operand::Temp(builder.address(&variable.name)),
+        ir::Expression::Operation(op, parts) => {
+            let second = generate(parts.get(1).unwrap(), builder);
+            let first = generate(parts.first().unwrap(), builder);
+
+            // Pointers are moved by whole Elements, so the Integer added to
+            // or subtracted from them is scaled by the Size of an Element
+            let left_type = types::exp_type(&parts[0]);
+            let right_type = types::exp_type(&parts[1]);
+            let (first, second) = match (op, &left_type, &right_type) {
+                (ir::OP::Add, ir::DataType::Ptr(element), other)
+                | (ir::OP::Substract, ir::DataType::Ptr(element), other)
+                    if types::is_integer(other) =>
+                {
+                    let size = Operand::Const(get_size::size(element));
+                    (first, binary(builder, BinOp::Mul, second, size))
+                }
+                (ir::OP::Add, other, ir::DataType::Ptr(element)) if types::is_integer(other) => {
+                    let size = Operand::Const(get_size::size(element));
+                    (binary(builder, BinOp::Mul, first, size), second)
+                }
+                // The Difference of two Pointers is counted in Elements
+                (ir::OP::Substract, ir::DataType::Ptr(element), ir::DataType::Ptr(_)) => {
+                    let difference = binary(builder, BinOp::Sub, first, second);
+                    let size = Operand::Const(get_size::size(element));
+                    return binary(builder, BinOp::SDiv, difference, size);
+                }
+                _ => (first, second),
+            };
+
+            let signed = types::is_signed(&types::exp_type(exp));
+            let op = match op {
+                ir::OP::Add => BinOp::Add,
+                ir::OP::Substract => BinOp::Sub,
+                ir::OP::Multiply => BinOp::Mul,
+                ir::OP::Divide if signed => BinOp::SDiv,
+                ir::OP::Divide => BinOp::UDiv,
+                ir::OP::Modulo if signed => BinOp::SRem,
+                ir::OP::Modulo => BinOp::URem,
+                ir::OP::BitAnd => BinOp::And,
+                ir::OP::BitOr => BinOp::Or,
+                ir::OP::BitXor => BinOp::Xor,
+                ir::OP::ShiftLeft => BinOp::Shl,
+                ir::OP::ShiftRight if signed => BinOp::AShr,
+                ir::OP::ShiftRight => BinOp::LShr,
+            };
+
+            binary(builder, op, first, second)
+        }
+        ir::Expression::Indexed(root, offset) => {
+            let element_size = match types::exp_type(root) {
+                ir::DataType::Ptr(element) => get_size::size(&element),
+                _ => 4,
+            };
+
+            let root = generate(root, builder);
+            let offset = generate(offset, builder);
+            let scaled = binary(builder, BinOp::Mul, offset, Operand::Const(element_size));
+            binary(builder, BinOp::Add, root, scaled)
+        }
+        ir::Expression::Member(root, name) => {
+            // The Member-Access has already been checked
+            let member_offset = match types::exp_type(root) {
+                ir::DataType::Ptr(target) => match get_size::member(&target, name) {
+                    Some((offset, _)) => offset,
+                    None => return Operand::Const(0),
+                },
+                _ => return Operand::Const(0),
+            };
+
+            let root = generate(root, builder);
+            if member_offset == 0 {
+                return root;
+            }
+
+            binary(builder, BinOp::Add, root, Operand::Const(member_offset))
+        }
+        ir::Expression::Dereference(inner) => {
+            let address = generate(inner, builder);
+            let datatype = match types::exp_type(inner) {
+                // Arrays, Structs and Unions are used by their Address,
+                // so there is nothing to load
+                ir::DataType::Ptr(target)
+                    if matches!(
+                        *target,
+                        ir::DataType::Array(_, _)
+                            | ir::DataType::Struct(_, _)
+                            | ir::DataType::Union(_, _)
+                    ) =>
+                {
+                    return address;
+                }
+                ir::DataType::Ptr(target) => *target,
+                _ => ir::DataType::U32,
+            };
+
+            let result = builder.temp();
+            builder.emit(Instr::Load(result, address, datatype));
+            Operand::Temp(result)
+        }
+        ir::Expression::Condition(cond) => condition::value(cond, builder),
+        ir::Expression::BitNot(inner) => {
+            let inner = generate(inner, builder);
+            let result = builder.temp();
+            builder.emit(Instr::Unary(result, UnOp::Not, inner));
+            Operand::Temp(result)
+        }
+        ir::Expression::Increment(address, amount, postfix) => {
+            let target_type = types::exp_type(exp);
+            // Pointers are moved by whole Elements
+            let step = match &target_type {
+                ir::DataType::Ptr(element) => *amount * get_size::size(element) as i32,
+                _ => *amount,
+            };
+            let step = Operand::Const(step as u32);
+
+            // Variables in Temps are changed directly
+            if let ir::Expression::Reference(variable) = address.as_ref() {
+                let var = builder.vars[&variable.name].clone();
+                if let Location::Temp(temp) = var.location {
+                    let previous = if *postfix {
+                        let previous = builder.temp();
+                        builder.emit(Instr::Copy(previous, Operand::Temp(temp)));
+                        Some(previous)
+                    } else {
+                        None
+                    };
+
+                    let next = binary(builder, BinOp::Add, Operand::Temp(temp), step);
+                    builder.truncate(temp, next, &var.datatype);
+                    return Operand::Temp(previous.unwrap_or(temp));
+                }
+            }
+
+            // Load the current Value and calculate the new one
+            let address = generate(address, builder);
+            let current = builder.temp();
+            builder.emit(Instr::Load(current, address, target_type.clone()));
+            let next = binary(builder, BinOp::Add, Operand::Temp(current), step);
+
+            // Store the new Value
+            builder.emit(Instr::Store(address, next, target_type.clone()));
+
+            if *postfix {
+                return Operand::Temp(current);
+            }
+
+            // The new Value is loaded again to get it truncated to the
+            // Size of the Target
+            let result = builder.temp();
+            builder.emit(Instr::Load(result, address, target_type));
+            Operand::Temp(result)
+        }
+        ir::Expression::StringLiteral(content) => {
+            let label = format!("__STRING_{}_{}", builder.name, builder.strings.len());
+
+            // The Content itself is placed in the read-only Data, followed
+            // by the 0-Terminator
+            let mut data = content.clone();
+            data.push(0);
+            builder.strings.push((label.clone(), data));
+
+            let result = builder.temp();
+            builder.emit(Instr::Address(result, Address::Global(label)));
+            Operand::Temp(result)
+        }
+        // Only a Return without a Value is empty, which has no Result
+        ir::Expression::Empty => Operand::Const(0),
+    }
+}
+
+/// Combines the two Operands into a new Temp
+fn binary(builder: &mut Builder, op: BinOp, left: Operand, right: Operand) -> Operand {
+    let result = builder.temp();
+    builder.emit(Instr::Binary(result, op, left, right));
+    Operand::Temp(result)
+}
@@ -1,5 +1,186 @@
-use super::ir;
+use std::str::FromStr;
 
-pub fn optimize(ir: Vec<ir::Function>) -> Vec<ir::Function> {
-    ir
+use super::mir;
+
+mod cfg;
+mod constants;
+mod copies;
+mod cse;
+mod dead;
+mod inline;
+mod liveness;
+mod simplify;
+mod strength;
+mod tail;
+
+/// How much the Program is optimized, which is set using `-O0`, `-O1` or
+/// `-O2`
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Level {
+    /// The Code is generated exactly as the Program is written
+    #[default]
+    Disabled,
+    /// Constants are folded and propagated, Expressions are simplified,
+    /// dead Code and unreachable Blocks are removed, Functions declared as
+    /// `inline` are inlined and Tail-Calls reuse the Frame
+    Basic,
+    /// Additionally eliminates common Subexpressions and dead Stores,
+    /// reduces the Strength of Operations, also inside of Loops, and
+    /// inlines small Functions
+    Full,
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "0" => Ok(Self::Disabled),
+            "1" => Ok(Self::Basic),
+            "2" => Ok(Self::Full),
+            other => Err(format!("Unknown Optimization-Level '{}'", other)),
+        }
+    }
+}
+
+/// The maximum Number of Times the Passes are repeated, as every Pass can
+/// create new Opportunities for the other ones
+const MAX_ROUNDS: usize = 16;
+
+/// Optimizes all the Functions of the Program according to the Level
+pub fn optimize(program: &mut mir::Program, level: Level) {
+    if level == Level::Disabled {
+        return;
+    }
+
+    for func in program.functions.iter_mut() {
+        cleanup(func, level);
+    }
+
+    let inlined = inline::inline(program, level == Level::Full);
+    for (func, inlined) in program.functions.iter_mut().zip(inlined) {
+        if inlined {
+            cleanup(func, level);
+        }
+        if level == Level::Full && strength::induction::reduce(func) {
+            cleanup(func, level);
+        }
+        if tail::calls(func, &program.conventions) {
+            cfg::simplify(func);
+        }
+    }
+    inline::remove_unused(program);
+}
+
+/// Runs the Passes until none of them changes the Function anymore
+fn cleanup(func: &mut mir::Function, level: Level) {
+    let full = level == Level::Full;
+    for _ in 0..MAX_ROUNDS {
+        let mut changed = constants::propagate(func);
+        changed |= simplify::simplify(func);
+        if full {
+            changed |= strength::reduce(func);
+        }
+        changed |= copies::propagate(func);
+        if full {
+            changed |= cse::eliminate(func);
+        }
+        changed |= dead::remove_code(func);
+        if full {
+            changed |= dead::remove_stores(func);
+        }
+        changed |= cfg::simplify(func);
+
+        if !changed {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{self, Variable};
+
+    fn int(value: i32) -> ir::Expression {
+        ir::Expression::Constant(ir::Value::I32(value))
+    }
+
+    fn var(name: &str) -> ir::Expression {
+        ir::Expression::Variable(Variable::new_str(name, ir::DataType::I32))
+    }
+
+    fn optimized(func: ir::Function, level: Level) -> mir::Function {
+        let mut program = mir::Program {
+            globals: Vec::new(),
+            functions: vec![mir::build::function(&func, &[])],
+            conventions: Default::default(),
+        };
+        optimize(&mut program, level);
+        program.functions.remove(0)
+    }
+
+    #[test]
+    fn levels() {
+        assert_eq!(Ok(Level::Disabled), "0".parse());
+        assert_eq!(Ok(Level::Full), "2".parse());
+        assert!("3".parse::<Level>().is_err());
+    }
+
+    #[test]
+    fn fold_constants() {
+        // int a = 3; int b = a * 4; if (b > 10) { return b + 1; } return 0;
+        let func = ir::Function(
+            "test".to_owned(),
+            ir::DataType::I32,
+            vec![],
+            vec![
+                ir::Statement::Declaration(Variable::new_str("a", ir::DataType::I32)),
+                ir::Statement::Assignment(Variable::new_str("a", ir::DataType::I32), int(3)),
+                ir::Statement::Declaration(Variable::new_str("b", ir::DataType::I32)),
+                ir::Statement::Assignment(
+                    Variable::new_str("b", ir::DataType::I32),
+                    ir::Expression::Operation(ir::OP::Multiply, vec![var("a"), int(4)]),
+                ),
+                ir::Statement::If(
+                    ir::Condition::Compare(ir::Comparison::GreaterThan, var("b"), int(10)),
+                    vec![ir::Statement::Return(ir::Expression::Operation(
+                        ir::OP::Add,
+                        vec![var("b"), int(1)],
+                    ))],
+                    vec![],
+                ),
+                ir::Statement::Return(int(0)),
+            ],
+            ir::Attributes::default(),
+        );
+
+        let result = optimized(func, Level::Basic);
+
+        assert_eq!(
+            vec![mir::Block {
+                instrs: vec![],
+                terminator: mir::Terminator::Return(Some(mir::Operand::Const(13))),
+            }],
+            result.blocks
+        );
+    }
+
+    #[test]
+    fn disabled() {
+        let func = ir::Function(
+            "test".to_owned(),
+            ir::DataType::I32,
+            vec![],
+            vec![ir::Statement::Return(ir::Expression::Operation(
+                ir::OP::Add,
+                vec![int(1), int(2)],
+            ))],
+            ir::Attributes::default(),
+        );
+
+        let result = optimized(func, Level::Disabled);
+
+        assert_eq!(1, result.blocks[0].instrs.len());
+    }
 }
@@ -0,0 +1,212 @@
+use crate::mir::{Block, BlockId, Function, Terminator};
+
+/// Simplifies the Graph of Blocks, by skipping empty Blocks, merging Blocks
+/// that always follow each other and removing unreachable Blocks
+pub fn simplify(func: &mut Function) -> bool {
+    let mut changed = false;
+    loop {
+        let mut round = same_targets(func);
+        round |= thread_jumps(func);
+        round |= merge_blocks(func);
+        round |= remove_unreachable(func);
+
+        if !round {
+            return changed;
+        }
+        changed = true;
+    }
+}
+
+/// Turns Branches and Switches, that always go to the same Block, into Jumps
+fn same_targets(func: &mut Function) -> bool {
+    let mut changed = false;
+    for block in func.blocks.iter_mut() {
+        if matches!(
+            block.terminator,
+            Terminator::Jump(_) | Terminator::Return(_) | Terminator::TailCall(_, _)
+        ) {
+            continue;
+        }
+
+        let successors = block.terminator.successors();
+        if successors.iter().all(|s| *s == successors[0]) {
+            block.terminator = Terminator::Jump(successors[0]);
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Lets Jumps to empty Blocks, which only jump somewhere else, go directly
+/// to the final Block
+fn thread_jumps(func: &mut Function) -> bool {
+    let count = func.blocks.len();
+    // The first Block is never skipped, as the Function starts there
+    let forward: Vec<Option<BlockId>> = func
+        .blocks
+        .iter()
+        .enumerate()
+        .map(|(index, block)| match block.terminator {
+            Terminator::Jump(target)
+                if index != 0 && block.instrs.is_empty() && target.0 != index =>
+            {
+                Some(target)
+            }
+            _ => None,
+        })
+        .collect();
+
+    // Follows the Chain of Jumps, which may also be an endless Loop
+    let resolve = |start: BlockId| {
+        let mut current = start;
+        for _ in 0..count {
+            match forward[current.0] {
+                Some(next) => current = next,
+                None => break,
+            };
+        }
+        current
+    };
+
+    let mut changed = false;
+    for block in func.blocks.iter_mut() {
+        block.terminator.map_successors(|target| {
+            let resolved = resolve(target);
+            changed |= resolved != target;
+            resolved
+        });
+    }
+    changed
+}
+
+/// Appends a Block to the Block before it, if that is the only Block
+/// jumping to it
+fn merge_blocks(func: &mut Function) -> bool {
+    let mut changed = false;
+    let mut preds = func.predecessors();
+    for index in 0..func.blocks.len() {
+        let target = match func.blocks[index].terminator {
+            Terminator::Jump(target) => target,
+            _ => continue,
+        };
+        if target.0 == index || target.0 == 0 || preds[target.0] != [BlockId(index)] {
+            continue;
+        }
+
+        // The merged Block is now unreachable and is removed afterwards
+        let merged = std::mem::replace(
+            &mut func.blocks[target.0],
+            Block {
+                instrs: Vec::new(),
+                terminator: Terminator::Return(None),
+            },
+        );
+        let block = &mut func.blocks[index];
+        block.instrs.extend(merged.instrs);
+        block.terminator = merged.terminator;
+
+        preds = func.predecessors();
+        changed = true;
+    }
+    changed
+}
+
+/// Removes all the Blocks that can not be reached from the first Block and
+/// updates the Jumps to the remaining ones
+fn remove_unreachable(func: &mut Function) -> bool {
+    let mut reachable = vec![false; func.blocks.len()];
+    let mut pending = vec![0];
+    while let Some(index) = pending.pop() {
+        if reachable[index] {
+            continue;
+        }
+        reachable[index] = true;
+        pending.extend(
+            func.blocks[index]
+                .terminator
+                .successors()
+                .iter()
+                .map(|s| s.0),
+        );
+    }
+
+    if reachable.iter().all(|r| *r) {
+        return false;
+    }
+
+    // The new Index of every remaining Block
+    let mut ids = Vec::with_capacity(func.blocks.len());
+    let mut next = 0;
+    for r in reachable.iter() {
+        ids.push(BlockId(next));
+        if *r {
+            next += 1;
+        }
+    }
+
+    let blocks = std::mem::take(&mut func.blocks);
+    func.blocks = blocks
+        .into_iter()
+        .zip(reachable)
+        .filter(|(_, r)| *r)
+        .map(|(mut block, _)| {
+            block.terminator.map_successors(|target| ids[target.0]);
+            block
+        })
+        .collect();
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir,
+        mir::{self, Instr, Operand, Temp},
+    };
+
+    #[test]
+    fn merge_and_remove() {
+        // bb0: jump bb2
+        // bb1: return 1
+        // bb2: jump bb3
+        // bb3: %0 = 2; return %0
+        let mut func = mir::Function {
+            name: "test".to_owned(),
+            return_type: ir::DataType::I32,
+            attributes: ir::Attributes::default(),
+            params: vec![],
+            slots: vec![],
+            strings: vec![],
+            blocks: vec![
+                Block {
+                    instrs: vec![],
+                    terminator: Terminator::Jump(BlockId(2)),
+                },
+                Block {
+                    instrs: vec![],
+                    terminator: Terminator::Return(Some(Operand::Const(1))),
+                },
+                Block {
+                    instrs: vec![],
+                    terminator: Terminator::Jump(BlockId(3)),
+                },
+                Block {
+                    instrs: vec![Instr::Copy(Temp(0), Operand::Const(2))],
+                    terminator: Terminator::Return(Some(Operand::Temp(Temp(0)))),
+                },
+            ],
+            temps: 1,
+        };
+
+        assert_eq!(true, simplify(&mut func));
+
+        assert_eq!(
+            vec![Block {
+                instrs: vec![Instr::Copy(Temp(0), Operand::Const(2))],
+                terminator: Terminator::Return(Some(Operand::Temp(Temp(0)))),
+            }],
+            func.blocks
+        );
+    }
+}
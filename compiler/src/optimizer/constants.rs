@@ -0,0 +1,322 @@
+use std::collections::HashMap;
+
+use crate::{
+    const_eval,
+    mir::{BlockId, Function, Instr, Operand, Temp, Terminator},
+};
+
+/// What is known about the Value of a Temp, a Temp that is not known at all
+/// has not been set yet on any Path
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Value {
+    Const(u32),
+    /// The Temp can hold different Values
+    Varying,
+}
+
+type State = HashMap<Temp, Value>;
+
+/// Replaces every Temp that always holds the same Constant with the
+/// Constant and calculates the Results of Operations on Constants.
+/// Branches on Constants become Jumps and the Blocks that are only reached
+/// through the other Side are not considered for the Values
+pub fn propagate(func: &mut Function) -> bool {
+    let states = analyze(func);
+
+    let mut changed = false;
+    for (block, state) in func.blocks.iter_mut().zip(states) {
+        // Unreachable Blocks are removed later on
+        let mut state = match state {
+            Some(s) => s,
+            None => continue,
+        };
+
+        for instr in block.instrs.iter_mut() {
+            for operand in instr.operands_mut() {
+                if let Some(value) = lookup(&state, operand) {
+                    if *operand != Operand::Const(value) {
+                        *operand = Operand::Const(value);
+                        changed = true;
+                    }
+                }
+            }
+
+            if !matches!(instr, Instr::Copy(_, Operand::Const(_))) {
+                if let (Some(target), Some(value)) = (instr.target(), evaluate(instr, &state)) {
+                    *instr = Instr::Copy(target, Operand::Const(value));
+                    changed = true;
+                }
+            }
+
+            transfer(instr, &mut state);
+        }
+
+        for operand in block.terminator.operands_mut() {
+            if let Some(value) = lookup(&state, operand) {
+                if *operand != Operand::Const(value) {
+                    *operand = Operand::Const(value);
+                    changed = true;
+                }
+            }
+        }
+
+        if matches!(
+            block.terminator,
+            Terminator::Branch(_, _, _, _, _) | Terminator::Switch(_, _, _)
+        ) {
+            let targets = taken(&block.terminator, &state);
+            if targets.len() == 1 {
+                block.terminator = Terminator::Jump(targets[0]);
+                changed = true;
+            }
+        }
+    }
+
+    changed
+}
+
+/// Determines the known Values at the Start of every Block, Blocks that
+/// can never be reached have no State
+fn analyze(func: &Function) -> Vec<Option<State>> {
+    let mut states: Vec<Option<State>> = vec![None; func.blocks.len()];
+    states[0] = Some(
+        func.params
+            .iter()
+            .map(|(temp, _)| (*temp, Value::Varying))
+            .collect(),
+    );
+
+    let mut pending = vec![BlockId(0)];
+    while let Some(current) = pending.pop() {
+        let mut state = states[current.0].clone().unwrap();
+        let block = &func.blocks[current.0];
+        for instr in block.instrs.iter() {
+            transfer(instr, &mut state);
+        }
+
+        for successor in taken(&block.terminator, &state) {
+            let updated = match &mut states[successor.0] {
+                Some(existing) => meet(existing, &state),
+                empty => {
+                    *empty = Some(state.clone());
+                    true
+                }
+            };
+            if updated && !pending.contains(&successor) {
+                pending.push(successor);
+            }
+        }
+    }
+
+    states
+}
+
+/// Combines the Values known on another Path into the State and returns
+/// whether or not the State changed
+fn meet(state: &mut State, other: &State) -> bool {
+    let mut changed = false;
+    for (temp, value) in other.iter() {
+        match state.get(temp) {
+            None => {
+                state.insert(*temp, *value);
+                changed = true;
+            }
+            Some(Value::Varying) => {}
+            Some(existing) if existing != value => {
+                state.insert(*temp, Value::Varying);
+                changed = true;
+            }
+            Some(_) => {}
+        };
+    }
+    changed
+}
+
+/// The constant Value of the Operand, if it is known
+fn lookup(state: &State, operand: &Operand) -> Option<u32> {
+    match operand {
+        Operand::Const(value) => Some(*value),
+        Operand::Temp(temp) => match state.get(temp) {
+            Some(Value::Const(value)) => Some(*value),
+            _ => None,
+        },
+    }
+}
+
+/// Calculates the Result of the Instruction, if it only depends on known
+/// Values
+fn evaluate(instr: &Instr, state: &State) -> Option<u32> {
+    match instr {
+        Instr::Copy(_, value) => lookup(state, value),
+        Instr::Binary(_, op, left, right) => {
+            const_eval::binary(op, lookup(state, left)?, lookup(state, right)?)
+        }
+        Instr::Unary(_, op, value) => Some(const_eval::unary(op, lookup(state, value)?)),
+        Instr::Compare(_, cond, left, right) => {
+            Some(cond.evaluate(lookup(state, left)?, lookup(state, right)?) as u32)
+        }
+        _ => None,
+    }
+}
+
+/// Updates the State with the Value of the Target after the Instruction
+fn transfer(instr: &Instr, state: &mut State) {
+    let target = match instr.target() {
+        Some(t) => t,
+        None => return,
+    };
+
+    match instr {
+        // Copying a Temp that was not set yet, does not set the Target either
+        Instr::Copy(_, Operand::Temp(source)) if !state.contains_key(source) => {
+            state.remove(&target);
+        }
+        _ => {
+            let value = evaluate(instr, state)
+                .map(Value::Const)
+                .unwrap_or(Value::Varying);
+            state.insert(target, value);
+        }
+    };
+}
+
+/// The Blocks the Terminator can actually transfer the Control to
+fn taken(terminator: &Terminator, state: &State) -> Vec<BlockId> {
+    match terminator {
+        Terminator::Branch(cond, left, right, then, otherwise) => {
+            match (lookup(state, left), lookup(state, right)) {
+                (Some(left), Some(right)) if cond.evaluate(left, right) => vec![*then],
+                (Some(_), Some(_)) => vec![*otherwise],
+                _ => vec![*then, *otherwise],
+            }
+        }
+        Terminator::Switch(value, cases, default) => match lookup(state, value) {
+            Some(value) => vec![cases
+                .iter()
+                .find(|(case, _)| *case == value)
+                .map(|(_, target)| *target)
+                .unwrap_or(*default)],
+            None => terminator.successors(),
+        },
+        _ => terminator.successors(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ir, mir};
+
+    #[test]
+    fn loop_variable_varies() {
+        // bb0: %0 = 0; jump bb1
+        // bb1: branch %0 < 10 ? bb2 : bb3
+        // bb2: %0 = add %0, 1; jump bb1
+        // bb3: return %0
+        let mut func = mir::Function {
+            name: "test".to_owned(),
+            return_type: ir::DataType::I32,
+            attributes: ir::Attributes::default(),
+            params: vec![],
+            slots: vec![],
+            strings: vec![],
+            blocks: vec![
+                mir::Block {
+                    instrs: vec![Instr::Copy(Temp(0), Operand::Const(0))],
+                    terminator: Terminator::Jump(BlockId(1)),
+                },
+                mir::Block {
+                    instrs: vec![],
+                    terminator: Terminator::Branch(
+                        mir::Condition {
+                            comparison: ir::Comparison::LessThan,
+                            signed: true,
+                        },
+                        Operand::Temp(Temp(0)),
+                        Operand::Const(10),
+                        BlockId(2),
+                        BlockId(3),
+                    ),
+                },
+                mir::Block {
+                    instrs: vec![Instr::Binary(
+                        Temp(0),
+                        mir::BinOp::Add,
+                        Operand::Temp(Temp(0)),
+                        Operand::Const(1),
+                    )],
+                    terminator: Terminator::Jump(BlockId(1)),
+                },
+                mir::Block {
+                    instrs: vec![],
+                    terminator: Terminator::Return(Some(Operand::Temp(Temp(0)))),
+                },
+            ],
+            temps: 1,
+        };
+        let expected = func.clone();
+
+        assert_eq!(false, propagate(&mut func));
+        assert_eq!(expected, func);
+    }
+
+    #[test]
+    fn constant_branch() {
+        // bb0: %0 = 3; %1 = mul %0, 4; branch %1 > 10 ? bb1 : bb2
+        let mut func = mir::Function {
+            name: "test".to_owned(),
+            return_type: ir::DataType::I32,
+            attributes: ir::Attributes::default(),
+            params: vec![],
+            slots: vec![],
+            strings: vec![],
+            blocks: vec![
+                mir::Block {
+                    instrs: vec![
+                        Instr::Copy(Temp(0), Operand::Const(3)),
+                        Instr::Binary(
+                            Temp(1),
+                            mir::BinOp::Mul,
+                            Operand::Temp(Temp(0)),
+                            Operand::Const(4),
+                        ),
+                    ],
+                    terminator: Terminator::Branch(
+                        mir::Condition {
+                            comparison: ir::Comparison::GreaterThan,
+                            signed: true,
+                        },
+                        Operand::Temp(Temp(1)),
+                        Operand::Const(10),
+                        BlockId(1),
+                        BlockId(2),
+                    ),
+                },
+                mir::Block {
+                    instrs: vec![],
+                    terminator: Terminator::Return(Some(Operand::Temp(Temp(1)))),
+                },
+                mir::Block {
+                    instrs: vec![],
+                    terminator: Terminator::Return(Some(Operand::Const(0))),
+                },
+            ],
+            temps: 2,
+        };
+
+        assert_eq!(true, propagate(&mut func));
+
+        assert_eq!(
+            vec![
+                Instr::Copy(Temp(0), Operand::Const(3)),
+                Instr::Copy(Temp(1), Operand::Const(12)),
+            ],
+            func.blocks[0].instrs
+        );
+        assert_eq!(Terminator::Jump(BlockId(1)), func.blocks[0].terminator);
+        assert_eq!(
+            Terminator::Return(Some(Operand::Const(12))),
+            func.blocks[1].terminator
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::mir::{Function, Instr, Operand, Temp};
+
+use super::liveness;
+
+/// Removes unnecessary Copies between Temps, by setting the Target of the
+/// Copy directly or by using the Source of the Copy instead of its Target
+pub fn propagate(func: &mut Function) -> bool {
+    let mut changed = coalesce(func);
+
+    for block in func.blocks.iter_mut() {
+        // The Temps that currently hold the same Value as another Temp
+        let mut copies: HashMap<Temp, Temp> = HashMap::new();
+        for instr in block.instrs.iter_mut() {
+            changed |= replace(instr.operands_mut(), &copies);
+
+            if let Some(target) = instr.target() {
+                copies.retain(|copy, source| *copy != target && *source != target);
+            }
+            if let Instr::Copy(target, Operand::Temp(source)) = instr {
+                if target != source {
+                    copies.insert(*target, *source);
+                }
+            }
+        }
+        changed |= replace(block.terminator.operands_mut(), &copies);
+
+        let before = block.instrs.len();
+        block
+            .instrs
+            .retain(|instr| !matches!(instr, Instr::Copy(target, Operand::Temp(source)) if target == source));
+        changed |= before != block.instrs.len();
+    }
+
+    changed
+}
+
+/// Replaces every Operand that is a Copy with its Source
+fn replace(operands: Vec<&mut Operand>, copies: &HashMap<Temp, Temp>) -> bool {
+    let mut changed = false;
+    for operand in operands {
+        if let Operand::Temp(temp) = operand {
+            if let Some(source) = copies.get(temp) {
+                *operand = Operand::Temp(*source);
+                changed = true;
+            }
+        }
+    }
+    changed
+}
+
+/// Turns `t = ...; x = t` into `x = ...`, if `t` is not used anywhere else
+fn coalesce(func: &mut Function) -> bool {
+    let mut defs: HashMap<Temp, usize> = HashMap::new();
+    let mut uses: HashMap<Temp, usize> = HashMap::new();
+    for block in func.blocks.iter() {
+        for instr in block.instrs.iter() {
+            if let Some(target) = instr.target() {
+                *defs.entry(target).or_default() += 1;
+            }
+            for temp in liveness::uses(instr) {
+                *uses.entry(temp).or_default() += 1;
+            }
+        }
+        for temp in liveness::temps(block.terminator.operands()) {
+            *uses.entry(temp).or_default() += 1;
+        }
+    }
+    let params: HashSet<Temp> = func.params.iter().map(|(temp, _)| *temp).collect();
+
+    let mut changed = false;
+    for block in func.blocks.iter_mut() {
+        let mut index = 0;
+        while index + 1 < block.instrs.len() {
+            let (temp, target) = match &block.instrs[index + 1] {
+                Instr::Copy(target, Operand::Temp(temp)) => (*temp, *target),
+                _ => {
+                    index += 1;
+                    continue;
+                }
+            };
+
+            let single = defs.get(&temp) == Some(&1)
+                && uses.get(&temp) == Some(&1)
+                && !params.contains(&temp);
+            if temp != target && single {
+                if let Some(result) = block.instrs[index].target_mut().filter(|t| **t == temp) {
+                    *result = target;
+                    block.instrs.remove(index + 1);
+                    changed = true;
+                }
+            }
+            index += 1;
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir,
+        mir::{self, BinOp},
+    };
+
+    #[test]
+    fn coalesce_and_propagate() {
+        // %1 = add %0, 1; %2 = %1; %3 = %2; return %3
+        let mut func = mir::Function {
+            name: "test".to_owned(),
+            return_type: ir::DataType::I32,
+            attributes: ir::Attributes::default(),
+            params: vec![(Temp(0), ir::DataType::I32)],
+            slots: vec![],
+            strings: vec![],
+            blocks: vec![mir::Block {
+                instrs: vec![
+                    Instr::Binary(
+                        Temp(1),
+                        BinOp::Add,
+                        Operand::Temp(Temp(0)),
+                        Operand::Const(1),
+                    ),
+                    Instr::Copy(Temp(2), Operand::Temp(Temp(1))),
+                    Instr::Copy(Temp(3), Operand::Temp(Temp(2))),
+                    Instr::Copy(Temp(4), Operand::Temp(Temp(3))),
+                ],
+                terminator: mir::Terminator::Return(Some(Operand::Temp(Temp(4)))),
+            }],
+            temps: 5,
+        };
+
+        assert_eq!(true, propagate(&mut func));
+
+        assert_eq!(
+            vec![
+                Instr::Binary(
+                    Temp(2),
+                    BinOp::Add,
+                    Operand::Temp(Temp(0)),
+                    Operand::Const(1)
+                ),
+                Instr::Copy(Temp(4), Operand::Temp(Temp(2))),
+            ],
+            func.blocks[0].instrs
+        );
+        assert_eq!(
+            mir::Terminator::Return(Some(Operand::Temp(Temp(2)))),
+            func.blocks[0].terminator
+        );
+    }
+}
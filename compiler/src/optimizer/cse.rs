@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use crate::{
+    ir,
+    mir::{Address, BinOp, Function, Instr, Operand, Temp},
+};
+
+/// The Calculation done by an Instruction, which can be reused if the same
+/// Calculation was already done before
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+enum Value {
+    Binary(BinOp, Operand, Operand),
+    /// The Operation is stored as its Text, as the Datatypes can not be
+    /// hashed
+    Unary(String, Operand),
+    Compare(String, Operand, Operand),
+    Address(Address),
+    Load(Operand, String),
+}
+
+impl Value {
+    fn new(instr: &Instr) -> Option<Self> {
+        match instr {
+            Instr::Binary(_, op, left, right) => Some(Self::Binary(*op, *left, *right)),
+            Instr::Unary(_, op, value) => Some(Self::Unary(format!("{:?}", op), *value)),
+            Instr::Compare(_, cond, left, right) => {
+                Some(Self::Compare(format!("{:?}", cond), *left, *right))
+            }
+            Instr::Address(_, address) => Some(Self::Address(address.clone())),
+            Instr::Load(_, address, datatype) => Some(Self::Load(*address, type_key(datatype))),
+            _ => None,
+        }
+    }
+
+    /// Whether or not the Calculation uses the Temp
+    fn uses(&self, temp: Temp) -> bool {
+        let operand = Operand::Temp(temp);
+        match self {
+            Self::Binary(_, left, right) | Self::Compare(_, left, right) => {
+                *left == operand || *right == operand
+            }
+            Self::Unary(_, value) | Self::Load(value, _) => *value == operand,
+            Self::Address(_) => false,
+        }
+    }
+}
+
+fn type_key(datatype: &ir::DataType) -> String {
+    format!("{:?}", datatype)
+}
+
+/// Reuses the Results of Calculations, which were already done before in
+/// the same Block and whose Operands have not changed since then
+pub fn eliminate(func: &mut Function) -> bool {
+    let mut changed = false;
+    for block in func.blocks.iter_mut() {
+        let mut available: HashMap<Value, Temp> = HashMap::new();
+        for instr in block.instrs.iter_mut() {
+            let value = Value::new(instr);
+            if let (Some(value), Some(target)) = (&value, instr.target()) {
+                if let Some(existing) = available.get(value) {
+                    if *existing != target {
+                        *instr = Instr::Copy(target, Operand::Temp(*existing));
+                        changed = true;
+                    }
+                }
+            }
+
+            // Memory may have been changed
+            if matches!(
+                instr,
+                Instr::Store(_, _, _) | Instr::Call(_, _, _) | Instr::Syscall(_, _, _)
+            ) {
+                available.retain(|value, _| !matches!(value, Value::Load(_, _)));
+            }
+
+            if let Some(target) = instr.target() {
+                available.retain(|value, temp| *temp != target && !value.uses(target));
+                if let (Some(value), false) = (value, matches!(instr, Instr::Copy(_, _))) {
+                    if !value.uses(target) {
+                        available.insert(value, target);
+                    }
+                }
+            }
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mir;
+
+    #[test]
+    fn reuse_until_changed() {
+        let x = Operand::Temp(Temp(0));
+        let mut func = mir::Function {
+            name: "test".to_owned(),
+            return_type: ir::DataType::Void,
+            attributes: ir::Attributes::default(),
+            params: vec![(Temp(0), ir::DataType::I32)],
+            slots: vec![],
+            strings: vec![],
+            blocks: vec![mir::Block {
+                instrs: vec![
+                    Instr::Binary(Temp(1), BinOp::Add, x, Operand::Const(4)),
+                    Instr::Load(Temp(2), Operand::Temp(Temp(1)), ir::DataType::I32),
+                    Instr::Binary(Temp(3), BinOp::Add, x, Operand::Const(4)),
+                    Instr::Store(Operand::Temp(Temp(3)), x, ir::DataType::I32),
+                    Instr::Load(Temp(4), Operand::Temp(Temp(1)), ir::DataType::I32),
+                    Instr::Copy(Temp(0), Operand::Const(2)),
+                    Instr::Binary(Temp(5), BinOp::Add, x, Operand::Const(4)),
+                ],
+                terminator: mir::Terminator::Return(None),
+            }],
+            temps: 6,
+        };
+
+        assert_eq!(true, eliminate(&mut func));
+
+        assert_eq!(
+            vec![
+                Instr::Binary(Temp(1), BinOp::Add, x, Operand::Const(4)),
+                Instr::Load(Temp(2), Operand::Temp(Temp(1)), ir::DataType::I32),
+                Instr::Copy(Temp(3), Operand::Temp(Temp(1))),
+                Instr::Store(Operand::Temp(Temp(3)), x, ir::DataType::I32),
+                Instr::Load(Temp(4), Operand::Temp(Temp(1)), ir::DataType::I32),
+                Instr::Copy(Temp(0), Operand::Const(2)),
+                Instr::Binary(Temp(5), BinOp::Add, x, Operand::Const(4)),
+            ],
+            func.blocks[0].instrs
+        );
+    }
+}
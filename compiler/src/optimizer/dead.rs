@@ -0,0 +1,204 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    ir,
+    mir::{Address, Function, Instr, Operand, Temp},
+};
+
+use super::liveness;
+
+/// Removes the Instructions whose Results are never used, Calls and
+/// Syscalls are kept, as they may do other Things as well
+pub fn remove_code(func: &mut Function) -> bool {
+    let live_out = liveness::live_out(func);
+
+    let mut changed = false;
+    for (block, mut live) in func.blocks.iter_mut().zip(live_out) {
+        live.extend(liveness::temps(block.terminator.operands()));
+
+        let mut keep = vec![true; block.instrs.len()];
+        for (index, instr) in block.instrs.iter_mut().enumerate().rev() {
+            if let Some(target) = instr.target() {
+                if !live.contains(&target) {
+                    if instr.is_pure() {
+                        keep[index] = false;
+                        changed = true;
+                        continue;
+                    }
+                    if let Instr::Call(result, _, _) = instr {
+                        *result = None;
+                        changed = true;
+                    }
+                }
+                live.remove(&target);
+            }
+            live.extend(liveness::uses(instr));
+        }
+
+        let mut keep = keep.into_iter();
+        block.instrs.retain(|_| keep.next().unwrap());
+    }
+    changed
+}
+
+/// Removes the Stores to Slots that are never read afterwards, either
+/// because the Address of the Slot is only ever used to store to it or
+/// because the Value is overwritten before it is read
+pub fn remove_stores(func: &mut Function) -> bool {
+    let slots = slot_addresses(func);
+
+    // Whether or not the Address of the Slot is used for anything else than
+    // storing to it
+    let mut read = HashSet::new();
+    for block in func.blocks.iter() {
+        let instr_uses = block.instrs.iter().flat_map(|instr| match instr {
+            Instr::Store(_, value, _) => vec![value],
+            other => other.operands(),
+        });
+        for temp in liveness::temps(instr_uses.chain(block.terminator.operands())) {
+            if let Some(slot) = slots.get(&temp) {
+                read.insert(*slot);
+            }
+        }
+    }
+
+    let mut changed = false;
+    for block in func.blocks.iter_mut() {
+        let mut keep = vec![true; block.instrs.len()];
+        // The last Store to each Slot, which has not been read yet
+        let mut pending: HashMap<usize, (usize, ir::DataType)> = HashMap::new();
+        for (index, instr) in block.instrs.iter().enumerate() {
+            match instr {
+                Instr::Store(Operand::Temp(address), _, datatype) => {
+                    let slot = match slots.get(address) {
+                        Some(s) => *s,
+                        None => continue,
+                    };
+
+                    if !read.contains(&slot) {
+                        keep[index] = false;
+                        continue;
+                    }
+                    if let Some((previous, previous_type)) = pending.get(&slot) {
+                        if previous_type == datatype {
+                            keep[*previous] = false;
+                        }
+                    }
+                    pending.insert(slot, (index, datatype.clone()));
+                }
+                Instr::Load(_, _, _) | Instr::Call(_, _, _) | Instr::Syscall(_, _, _) => {
+                    pending.clear();
+                }
+                _ => {}
+            };
+        }
+
+        if keep.contains(&false) {
+            let mut keep = keep.into_iter();
+            block.instrs.retain(|_| keep.next().unwrap());
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// The Temps that hold the Address of a Slot and are not set to anything
+/// else. Slots whose Address is put into Temps that are set multiple
+/// Times are left out completely
+fn slot_addresses(func: &Function) -> HashMap<Temp, usize> {
+    let mut defs: HashMap<Temp, usize> = HashMap::new();
+    for instr in func.blocks.iter().flat_map(|b| b.instrs.iter()) {
+        if let Some(target) = instr.target() {
+            *defs.entry(target).or_default() += 1;
+        }
+    }
+
+    let mut result = HashMap::new();
+    let mut unknown = HashSet::new();
+    for instr in func.blocks.iter().flat_map(|b| b.instrs.iter()) {
+        if let Instr::Address(target, Address::Slot(slot)) = instr {
+            if defs[target] == 1 {
+                result.insert(*target, *slot);
+            } else {
+                unknown.insert(*slot);
+            }
+        }
+    }
+    result.retain(|_, slot| !unknown.contains(slot));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mir::{self, BinOp};
+
+    #[test]
+    fn unused_results() {
+        let x = Operand::Temp(Temp(0));
+        let mut func = mir::Function {
+            name: "test".to_owned(),
+            return_type: ir::DataType::I32,
+            attributes: ir::Attributes::default(),
+            params: vec![(Temp(0), ir::DataType::I32)],
+            slots: vec![],
+            strings: vec![],
+            blocks: vec![mir::Block {
+                instrs: vec![
+                    Instr::Binary(Temp(1), BinOp::Add, x, Operand::Const(1)),
+                    Instr::Call(Some(Temp(2)), "other".to_owned(), vec![]),
+                    Instr::Binary(Temp(3), BinOp::Mul, x, Operand::Temp(Temp(1))),
+                ],
+                terminator: mir::Terminator::Return(Some(Operand::Temp(Temp(1)))),
+            }],
+            temps: 4,
+        };
+
+        assert_eq!(true, remove_code(&mut func));
+
+        assert_eq!(
+            vec![
+                Instr::Binary(Temp(1), BinOp::Add, x, Operand::Const(1)),
+                Instr::Call(None, "other".to_owned(), vec![]),
+            ],
+            func.blocks[0].instrs
+        );
+    }
+
+    #[test]
+    fn overwritten_stores() {
+        let address = Operand::Temp(Temp(0));
+        let mut func = mir::Function {
+            name: "test".to_owned(),
+            return_type: ir::DataType::I32,
+            attributes: ir::Attributes::default(),
+            params: vec![],
+            slots: vec![ir::DataType::I32, ir::DataType::I32],
+            strings: vec![],
+            blocks: vec![mir::Block {
+                instrs: vec![
+                    Instr::Address(Temp(0), Address::Slot(0)),
+                    Instr::Address(Temp(1), Address::Slot(1)),
+                    Instr::Store(address, Operand::Const(1), ir::DataType::I32),
+                    Instr::Store(address, Operand::Const(2), ir::DataType::I32),
+                    Instr::Store(Operand::Temp(Temp(1)), address, ir::DataType::I32),
+                    Instr::Load(Temp(2), address, ir::DataType::I32),
+                ],
+                terminator: mir::Terminator::Return(Some(Operand::Temp(Temp(2)))),
+            }],
+            temps: 3,
+        };
+
+        assert_eq!(true, remove_stores(&mut func));
+
+        assert_eq!(
+            vec![
+                Instr::Address(Temp(0), Address::Slot(0)),
+                Instr::Address(Temp(1), Address::Slot(1)),
+                Instr::Store(address, Operand::Const(2), ir::DataType::I32),
+                Instr::Load(Temp(2), address, ir::DataType::I32),
+            ],
+            func.blocks[0].instrs
+        );
+    }
+}
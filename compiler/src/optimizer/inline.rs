@@ -0,0 +1,326 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    ir,
+    mir::{Address, Block, BlockId, Function, Instr, Operand, Program, Temp, Terminator, UnOp},
+};
+
+/// The maximum Number of Instructions and Blocks of a Function, for it to
+/// be inlined without being declared as `inline`
+const SMALL_FUNCTION: usize = 12;
+/// The Number of Instructions and Blocks after which nothing more is inlined
+/// into a Function
+const MAX_SIZE: usize = 1000;
+/// The maximum Number of Calls inlined into a single Function, which stops
+/// Functions that call each other from being inlined forever
+const MAX_INLINED: usize = 64;
+
+/// Replaces the Calls to Functions declared as `inline` with the Body of the
+/// Function, if `small` is set the same is done for all small Functions.
+/// Returns for every Function whether or not it changed
+pub fn inline(program: &mut Program, small: bool) -> Vec<bool> {
+    let callees: HashMap<String, Function> = program
+        .functions
+        .iter()
+        .filter(|func| inlinable(func, small))
+        .map(|func| (func.name.clone(), func.clone()))
+        .collect();
+
+    program
+        .functions
+        .iter_mut()
+        .map(|func| {
+            let mut count = 0;
+            while count < MAX_INLINED && size(func) < MAX_SIZE {
+                let found = calls(func)
+                    .find(|(_, _, name)| *name != func.name && callees.contains_key(*name))
+                    .map(|(b_index, i_index, name)| (b_index, i_index, name.to_owned()));
+
+                match found {
+                    Some((b_index, i_index, name)) => {
+                        body(func, (b_index, i_index), &callees[&name]);
+                        count += 1;
+                    }
+                    None => break,
+                };
+            }
+            count > 0
+        })
+        .collect()
+}
+
+/// Removes the Functions declared as `static`, which are not called
+/// anymore after all Calls to them have been inlined
+pub fn remove_unused(program: &mut Program) {
+    loop {
+        let mut called: HashSet<String> = HashSet::new();
+        for func in program.functions.iter() {
+            let tail_calls = func
+                .blocks
+                .iter()
+                .filter_map(|block| match &block.terminator {
+                    Terminator::TailCall(name, _) => Some(name.as_str()),
+                    _ => None,
+                });
+            for name in calls(func).map(|(_, _, name)| name).chain(tail_calls) {
+                if name != func.name {
+                    called.insert(name.to_owned());
+                }
+            }
+        }
+
+        let before = program.functions.len();
+        program.functions.retain(|func| {
+            !func.attributes.local || func.name == "main" || called.contains(&func.name)
+        });
+
+        if program.functions.len() == before {
+            break;
+        }
+    }
+}
+
+/// The Size of the Function, which is the Number of its Instructions and
+/// Blocks
+fn size(func: &Function) -> usize {
+    func.blocks.iter().map(|block| block.instrs.len() + 1).sum()
+}
+
+/// All the Calls in the Function, with the Positions and the Names of the
+/// called Functions
+fn calls(func: &Function) -> impl Iterator<Item = (usize, usize, &str)> {
+    func.blocks.iter().enumerate().flat_map(|(b_index, block)| {
+        block
+            .instrs
+            .iter()
+            .enumerate()
+            .filter_map(move |(i_index, instr)| match instr {
+                Instr::Call(_, name, _) => Some((b_index, i_index, name.as_str())),
+                _ => None,
+            })
+    })
+}
+
+/// Whether or not the Calls to the Function should be inlined, which is
+/// never the Case for Functions calling themselves or marked as `noinline`
+fn inlinable(func: &Function, small: bool) -> bool {
+    let recursive = calls(func).any(|(_, _, name)| name == func.name)
+        || func.blocks.iter().any(
+            |block| matches!(&block.terminator, Terminator::TailCall(name, _) if *name == func.name),
+        );
+
+    !recursive
+        && !func.attributes.noinline
+        && func.name != "main"
+        && (func.attributes.inline || (small && size(func) <= SMALL_FUNCTION))
+}
+
+/// Replaces the Call at the Position with the Blocks of the called
+/// Function, which use their own Temps and Slots. The Instructions after
+/// the Call are moved into a new Block, that all the Returns jump to
+fn body(func: &mut Function, (b_index, i_index): (usize, usize), callee: &Function) {
+    let temps = func.temps;
+    func.temps += callee.temps;
+    let rename = |temp: Temp| Temp(temp.0 + temps);
+
+    let slots = func.slots.len();
+    func.slots.extend(callee.slots.iter().cloned());
+
+    // The String-Literals get new Labels, in case the Function itself is
+    // removed afterwards
+    let mut labels = HashMap::new();
+    for (label, content) in callee.strings.iter() {
+        let renamed = format!("__STRING_{}_{}", func.name, func.strings.len());
+        func.strings.push((renamed.clone(), content.clone()));
+        labels.insert(label.clone(), renamed);
+    }
+
+    let start = BlockId(func.blocks.len());
+    let continuation = BlockId(func.blocks.len() + callee.blocks.len());
+
+    let block = &mut func.blocks[b_index];
+    let rest = block.instrs.split_off(i_index + 1);
+    let (result, args) = match block.instrs.pop() {
+        Some(Instr::Call(result, _, args)) => (result, args),
+        other => panic!("Expected a Call but got {:?}", other),
+    };
+
+    // The Parameters are truncated like when they are loaded from the Stack
+    for ((param, datatype), arg) in callee.params.iter().zip(args) {
+        block.instrs.push(match datatype {
+            ir::DataType::I8 | ir::DataType::U8 | ir::DataType::I16 | ir::DataType::U16 => {
+                Instr::Unary(rename(*param), UnOp::Truncate(datatype.clone()), arg)
+            }
+            _ => Instr::Copy(rename(*param), arg),
+        });
+    }
+    let terminator = std::mem::replace(&mut block.terminator, Terminator::Jump(start));
+
+    for callee_block in callee.blocks.iter() {
+        let mut instrs = callee_block.instrs.clone();
+        for instr in instrs.iter_mut() {
+            if let Some(target) = instr.target_mut() {
+                *target = rename(*target);
+            }
+            for operand in instr.operands_mut() {
+                if let Operand::Temp(temp) = operand {
+                    *temp = rename(*temp);
+                }
+            }
+            match instr {
+                Instr::Address(_, Address::Slot(slot)) => *slot += slots,
+                Instr::Address(_, Address::Global(label)) => {
+                    if let Some(renamed) = labels.get(label) {
+                        *label = renamed.clone();
+                    }
+                }
+                _ => {}
+            };
+        }
+
+        let mut terminator = callee_block.terminator.clone();
+        for operand in terminator.operands_mut() {
+            if let Operand::Temp(temp) = operand {
+                *temp = rename(*temp);
+            }
+        }
+        terminator.map_successors(|target| BlockId(target.0 + start.0));
+
+        // Returning means continuing after the Call
+        let terminator = match terminator {
+            Terminator::Return(value) => {
+                if let (Some(result), Some(value)) = (result, value) {
+                    instrs.push(Instr::Copy(result, value));
+                }
+                Terminator::Jump(continuation)
+            }
+            Terminator::TailCall(name, args) => {
+                instrs.push(Instr::Call(result, name, args));
+                Terminator::Jump(continuation)
+            }
+            other => other,
+        };
+
+        func.blocks.push(Block { instrs, terminator });
+    }
+
+    func.blocks.push(Block {
+        instrs: rest,
+        terminator,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mir::BinOp;
+
+    fn function(name: &str, params: u32, blocks: Vec<Block>, temps: u32) -> Function {
+        Function {
+            name: name.to_owned(),
+            return_type: ir::DataType::I32,
+            attributes: ir::Attributes::default(),
+            params: (0..params).map(|p| (Temp(p), ir::DataType::I32)).collect(),
+            slots: vec![],
+            strings: vec![],
+            blocks,
+            temps,
+        }
+    }
+
+    #[test]
+    fn small_function() {
+        // int add(int a, int b) { return a + b; }
+        let add = function(
+            "add",
+            2,
+            vec![Block {
+                instrs: vec![Instr::Binary(
+                    Temp(2),
+                    BinOp::Add,
+                    Operand::Temp(Temp(0)),
+                    Operand::Temp(Temp(1)),
+                )],
+                terminator: Terminator::Return(Some(Operand::Temp(Temp(2)))),
+            }],
+            3,
+        );
+        // int main() { return add(1, 2) + 3; }
+        let main = function(
+            "main",
+            0,
+            vec![Block {
+                instrs: vec![
+                    Instr::Call(
+                        Some(Temp(0)),
+                        "add".to_owned(),
+                        vec![Operand::Const(1), Operand::Const(2)],
+                    ),
+                    Instr::Binary(
+                        Temp(1),
+                        BinOp::Add,
+                        Operand::Temp(Temp(0)),
+                        Operand::Const(3),
+                    ),
+                ],
+                terminator: Terminator::Return(Some(Operand::Temp(Temp(1)))),
+            }],
+            2,
+        );
+        let mut program = Program {
+            globals: vec![],
+            functions: vec![add, main],
+            conventions: HashMap::new(),
+        };
+
+        // Functions marked as `noinline` are never inlined
+        program.functions[0].attributes.noinline = true;
+        assert_eq!(vec![false, false], inline(&mut program, true));
+        program.functions[0].attributes.noinline = false;
+
+        assert_eq!(vec![false, false], inline(&mut program, false));
+        assert_eq!(vec![false, true], inline(&mut program, true));
+
+        assert_eq!(
+            vec![
+                Block {
+                    instrs: vec![
+                        Instr::Copy(Temp(2), Operand::Const(1)),
+                        Instr::Copy(Temp(3), Operand::Const(2)),
+                    ],
+                    terminator: Terminator::Jump(BlockId(1)),
+                },
+                Block {
+                    instrs: vec![
+                        Instr::Binary(
+                            Temp(4),
+                            BinOp::Add,
+                            Operand::Temp(Temp(2)),
+                            Operand::Temp(Temp(3)),
+                        ),
+                        Instr::Copy(Temp(0), Operand::Temp(Temp(4))),
+                    ],
+                    terminator: Terminator::Jump(BlockId(2)),
+                },
+                Block {
+                    instrs: vec![Instr::Binary(
+                        Temp(1),
+                        BinOp::Add,
+                        Operand::Temp(Temp(0)),
+                        Operand::Const(3)
+                    )],
+                    terminator: Terminator::Return(Some(Operand::Temp(Temp(1)))),
+                },
+            ],
+            program.functions[1].blocks
+        );
+        assert_eq!(5, program.functions[1].temps);
+
+        // Only Functions declared as static are removed
+        remove_unused(&mut program);
+        assert_eq!(2, program.functions.len());
+        program.functions[0].attributes.local = true;
+        remove_unused(&mut program);
+        assert_eq!(1, program.functions.len());
+    }
+}
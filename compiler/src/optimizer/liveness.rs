@@ -0,0 +1,70 @@
+use std::collections::HashSet;
+
+use crate::mir::{Function, Instr, Operand, Temp};
+
+/// The Temps read by the Operands
+pub fn temps<'a, I>(operands: I) -> impl Iterator<Item = Temp> + 'a
+where
+    I: IntoIterator<Item = &'a Operand> + 'a,
+{
+    operands.into_iter().filter_map(|operand| operand.temp())
+}
+
+/// The Temps read by the Instruction
+pub fn uses(instr: &Instr) -> Vec<Temp> {
+    temps(instr.operands()).collect()
+}
+
+/// Determines the Temps whose Values are still needed at the End of every
+/// Block, by repeatedly propagating the needed Temps backwards until
+/// nothing changes anymore
+pub fn live_out(func: &Function) -> Vec<HashSet<Temp>> {
+    // The Temps read by a Block before they are set in it and the Temps
+    // set in it
+    let mut gen = Vec::with_capacity(func.blocks.len());
+    let mut kill = Vec::with_capacity(func.blocks.len());
+    for block in func.blocks.iter() {
+        let mut read = HashSet::new();
+        let mut written = HashSet::new();
+        for instr in block.instrs.iter() {
+            for temp in uses(instr) {
+                if !written.contains(&temp) {
+                    read.insert(temp);
+                }
+            }
+            written.extend(instr.target());
+        }
+        for temp in temps(block.terminator.operands()) {
+            if !written.contains(&temp) {
+                read.insert(temp);
+            }
+        }
+        gen.push(read);
+        kill.push(written);
+    }
+
+    let mut live_in: Vec<HashSet<Temp>> = vec![HashSet::new(); func.blocks.len()];
+    let mut live_out: Vec<HashSet<Temp>> = vec![HashSet::new(); func.blocks.len()];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (index, block) in func.blocks.iter().enumerate().rev() {
+            let out: HashSet<Temp> = block
+                .terminator
+                .successors()
+                .iter()
+                .flat_map(|successor| live_in[successor.0].iter().copied())
+                .collect();
+            let mut input = gen[index].clone();
+            input.extend(out.difference(&kill[index]).copied());
+
+            if input != live_in[index] || out != live_out[index] {
+                live_in[index] = input;
+                live_out[index] = out;
+                changed = true;
+            }
+        }
+    }
+
+    live_out
+}
@@ -0,0 +1,147 @@
+use crate::mir::{BinOp, Function, Instr, Operand, Terminator, UnOp};
+
+/// Applies algebraic Identities to the Instructions, like `x + 0 = x` or
+/// `x - x = 0`, and turns Branches that can only go one Way into Jumps
+pub fn simplify(func: &mut Function) -> bool {
+    let mut changed = false;
+    for block in func.blocks.iter_mut() {
+        for instr in block.instrs.iter_mut() {
+            if let Some(simpler) = instruction(instr) {
+                *instr = simpler;
+                changed = true;
+            }
+        }
+
+        let target = match &block.terminator {
+            Terminator::Branch(
+                cond,
+                Operand::Temp(left),
+                Operand::Temp(right),
+                then,
+                otherwise,
+            ) if left == right => Some(if cond.evaluate(0, 0) {
+                *then
+            } else {
+                *otherwise
+            }),
+            Terminator::Branch(_, _, _, then, otherwise) if then == otherwise => Some(*then),
+            _ => None,
+        };
+        if let Some(target) = target {
+            block.terminator = Terminator::Jump(target);
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Whether or not the Operands of the Operation can be swapped
+fn commutative(op: &BinOp) -> bool {
+    matches!(
+        op,
+        BinOp::Add | BinOp::Mul | BinOp::And | BinOp::Or | BinOp::Xor
+    )
+}
+
+/// The simpler Instruction with the same Result, if there is one
+fn instruction(instr: &Instr) -> Option<Instr> {
+    match instr {
+        // Constants are moved to the right Side, so the other Rules only
+        // need to check there
+        Instr::Binary(target, op, left @ Operand::Const(_), right @ Operand::Temp(_))
+            if commutative(op) =>
+        {
+            Some(Instr::Binary(*target, *op, *right, *left))
+        }
+        Instr::Binary(target, BinOp::Sub, Operand::Const(0), right @ Operand::Temp(_)) => {
+            Some(Instr::Unary(*target, UnOp::Neg, *right))
+        }
+        Instr::Binary(target, op, Operand::Temp(left), Operand::Temp(right)) if left == right => {
+            match op {
+                BinOp::Sub | BinOp::Xor => Some(Instr::Copy(*target, Operand::Const(0))),
+                BinOp::And | BinOp::Or => Some(Instr::Copy(*target, Operand::Temp(*left))),
+                _ => None,
+            }
+        }
+        Instr::Binary(target, op, left @ Operand::Temp(_), Operand::Const(value)) => {
+            let target = *target;
+            let left = *left;
+            match (op, *value) {
+                (BinOp::Add, 0)
+                | (BinOp::Sub, 0)
+                | (BinOp::Or, 0)
+                | (BinOp::Xor, 0)
+                | (BinOp::Shl, 0)
+                | (BinOp::LShr, 0)
+                | (BinOp::AShr, 0)
+                | (BinOp::Mul, 1)
+                | (BinOp::SDiv, 1)
+                | (BinOp::UDiv, 1)
+                | (BinOp::And, u32::MAX) => Some(Instr::Copy(target, left)),
+                (BinOp::Mul, 0) | (BinOp::And, 0) | (BinOp::SRem, 1) | (BinOp::URem, 1) => {
+                    Some(Instr::Copy(target, Operand::Const(0)))
+                }
+                (BinOp::Or, u32::MAX) => Some(Instr::Copy(target, Operand::Const(u32::MAX))),
+                (BinOp::Mul, u32::MAX) | (BinOp::SDiv, u32::MAX) => {
+                    Some(Instr::Unary(target, UnOp::Neg, left))
+                }
+                (BinOp::Xor, u32::MAX) => Some(Instr::Unary(target, UnOp::Not, left)),
+                // Subtracting a Constant is the same as adding its negative,
+                // which makes the Steps of Loops easier to find
+                (BinOp::Sub, value) => Some(Instr::Binary(
+                    target,
+                    BinOp::Add,
+                    left,
+                    Operand::Const(value.wrapping_neg()),
+                )),
+                _ => None,
+            }
+        }
+        Instr::Compare(target, cond, Operand::Temp(left), Operand::Temp(right))
+            if left == right =>
+        {
+            Some(Instr::Copy(
+                *target,
+                Operand::Const(cond.evaluate(0, 0) as u32),
+            ))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mir::Temp;
+
+    #[test]
+    fn identities() {
+        let x = Operand::Temp(Temp(0));
+
+        assert_eq!(
+            Some(Instr::Copy(Temp(1), x)),
+            instruction(&Instr::Binary(Temp(1), BinOp::Add, x, Operand::Const(0)))
+        );
+        assert_eq!(
+            Some(Instr::Binary(Temp(1), BinOp::Mul, x, Operand::Const(3))),
+            instruction(&Instr::Binary(Temp(1), BinOp::Mul, Operand::Const(3), x))
+        );
+        assert_eq!(
+            Some(Instr::Copy(Temp(1), Operand::Const(0))),
+            instruction(&Instr::Binary(Temp(1), BinOp::Sub, x, x))
+        );
+        assert_eq!(
+            Some(Instr::Binary(
+                Temp(1),
+                BinOp::Add,
+                x,
+                Operand::Const(u32::MAX)
+            )),
+            instruction(&Instr::Binary(Temp(1), BinOp::Sub, x, Operand::Const(1)))
+        );
+        assert_eq!(
+            None,
+            instruction(&Instr::Binary(Temp(1), BinOp::Add, x, Operand::Const(2)))
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use crate::mir::{BinOp, Function, Instr, Operand};
+
+pub mod induction;
+
+/// Replaces expensive Operations with a Power of two by cheaper ones, a
+/// Multiplication becomes a Shift to the Left and an unsigned Division or
+/// Modulo becomes a Shift to the Right or a Mask
+pub fn reduce(func: &mut Function) -> bool {
+    let mut changed = false;
+    for instr in func.blocks.iter_mut().flat_map(|b| b.instrs.iter_mut()) {
+        let (target, op, left, value) = match instr {
+            Instr::Binary(target, op, left @ Operand::Temp(_), Operand::Const(value))
+                if *value > 1 && value.is_power_of_two() =>
+            {
+                (*target, *op, *left, *value)
+            }
+            _ => continue,
+        };
+
+        let bits = Operand::Const(value.trailing_zeros());
+        let reduced = match op {
+            BinOp::Mul => Instr::Binary(target, BinOp::Shl, left, bits),
+            BinOp::UDiv => Instr::Binary(target, BinOp::LShr, left, bits),
+            BinOp::URem => Instr::Binary(target, BinOp::And, left, Operand::Const(value - 1)),
+            _ => continue,
+        };
+        *instr = reduced;
+        changed = true;
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir,
+        mir::{self, Temp},
+    };
+
+    #[test]
+    fn power_of_two() {
+        let x = Operand::Temp(Temp(0));
+        let mut func = mir::Function {
+            name: "test".to_owned(),
+            return_type: ir::DataType::Void,
+            attributes: ir::Attributes::default(),
+            params: vec![(Temp(0), ir::DataType::U32)],
+            slots: vec![],
+            strings: vec![],
+            blocks: vec![mir::Block {
+                instrs: vec![
+                    Instr::Binary(Temp(1), BinOp::Mul, x, Operand::Const(8)),
+                    Instr::Binary(Temp(2), BinOp::UDiv, x, Operand::Const(16)),
+                    Instr::Binary(Temp(3), BinOp::URem, x, Operand::Const(4)),
+                    Instr::Binary(Temp(4), BinOp::SDiv, x, Operand::Const(4)),
+                    Instr::Binary(Temp(5), BinOp::Mul, x, Operand::Const(6)),
+                ],
+                terminator: mir::Terminator::Return(None),
+            }],
+            temps: 6,
+        };
+
+        assert_eq!(true, reduce(&mut func));
+
+        assert_eq!(
+            vec![
+                Instr::Binary(Temp(1), BinOp::Shl, x, Operand::Const(3)),
+                Instr::Binary(Temp(2), BinOp::LShr, x, Operand::Const(4)),
+                Instr::Binary(Temp(3), BinOp::And, x, Operand::Const(3)),
+                Instr::Binary(Temp(4), BinOp::SDiv, x, Operand::Const(4)),
+                Instr::Binary(Temp(5), BinOp::Mul, x, Operand::Const(6)),
+            ],
+            func.blocks[0].instrs
+        );
+    }
+}
@@ -0,0 +1,507 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::mir::{Address, BinOp, BlockId, Function, Instr, Operand, Temp, Terminator};
+
+/// A Loop made up of the Header, which dominates all the other Blocks in
+/// it, and the Preheader, which is the only Block outside of the Loop that
+/// jumps to the Header
+struct Loop {
+    blocks: HashSet<usize>,
+    preheader: usize,
+}
+
+/// Where an Instruction is located in the Function
+type Position = (usize, usize);
+
+/// The Start of the Address-Calculation that does not change inside of
+/// the Loop
+#[derive(Debug, PartialEq, Clone)]
+enum Base {
+    Operand(Operand),
+    /// The Address is calculated again in the Preheader
+    Address(Address),
+}
+
+/// An Address-Calculation `base + iv * scale` inside of a Loop, that can be
+/// replaced by a Pointer which is moved along with the Variable
+struct Candidate {
+    position: Position,
+    target: Temp,
+    iv: Temp,
+    base: Base,
+    scale: u32,
+}
+
+/// Replaces Calculations like `base + i * size` inside of Loops, which are
+/// mostly used for indexing Arrays, with a Pointer that starts at
+/// `base + i * size` and is moved by `step * size` every time the
+/// Variable `i` is moved by `step`
+pub fn reduce(func: &mut Function) -> bool {
+    let mut changed = false;
+    // Every Transformation turns the Candidates into Copies, so this always
+    // comes to an End
+    while let Some((lp, candidates)) = find(func) {
+        transform(func, &lp, candidates);
+        changed = true;
+    }
+    changed
+}
+
+/// Finds the first Loop with Candidates and returns all the Candidates,
+/// that share the Variable, Base and Scale of the first one
+fn find(func: &Function) -> Option<(Loop, Vec<Candidate>)> {
+    let defs = definitions(func);
+    for lp in loops(func) {
+        let steps = induction_variables(func, &lp);
+        if steps.is_empty() {
+            continue;
+        }
+
+        let mut candidates = candidates(func, &lp, &steps, &defs);
+        if candidates.is_empty() {
+            continue;
+        }
+
+        let first = candidates.remove(0);
+        let mut result: Vec<Candidate> = candidates
+            .into_iter()
+            .filter(|c| c.iv == first.iv && c.base == first.base && c.scale == first.scale)
+            .collect();
+        result.insert(0, first);
+        return Some((lp, result));
+    }
+    None
+}
+
+/// The Positions of all the Instructions that set each Temp
+fn definitions(func: &Function) -> HashMap<Temp, Vec<Position>> {
+    let mut result: HashMap<Temp, Vec<Position>> = HashMap::new();
+    for (b_index, block) in func.blocks.iter().enumerate() {
+        for (i_index, instr) in block.instrs.iter().enumerate() {
+            if let Some(target) = instr.target() {
+                result.entry(target).or_default().push((b_index, i_index));
+            }
+        }
+    }
+    result
+}
+
+/// The Blocks that dominate each Block, meaning every Path from the Start
+/// of the Function to the Block goes through them
+fn dominators(func: &Function) -> Vec<HashSet<usize>> {
+    let count = func.blocks.len();
+    let preds = func.predecessors();
+    let all: HashSet<usize> = (0..count).collect();
+
+    let mut result = vec![all; count];
+    result[0] = std::iter::once(0).collect();
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for index in 1..count {
+            let mut dom = preds[index]
+                .iter()
+                .map(|pred| result[pred.0].clone())
+                .fold(None, |acc: Option<HashSet<usize>>, set| match acc {
+                    Some(acc) => Some(acc.intersection(&set).copied().collect()),
+                    None => Some(set),
+                })
+                .unwrap_or_default();
+            dom.insert(index);
+
+            if dom != result[index] {
+                result[index] = dom;
+                changed = true;
+            }
+        }
+    }
+    result
+}
+
+/// Finds all the Loops, which have a Preheader
+fn loops(func: &Function) -> Vec<Loop> {
+    let doms = dominators(func);
+    let preds = func.predecessors();
+
+    // The Blocks jumping back to each Header
+    let mut latches: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (index, block) in func.blocks.iter().enumerate() {
+        for successor in block.terminator.successors() {
+            if doms[index].contains(&successor.0) {
+                latches.entry(successor.0).or_default().push(index);
+            }
+        }
+    }
+
+    let mut headers: Vec<usize> = latches.keys().copied().collect();
+    headers.sort_unstable();
+
+    let mut result = Vec::new();
+    for header in headers {
+        let mut blocks: HashSet<usize> = std::iter::once(header).collect();
+        let mut pending = latches[&header].clone();
+        while let Some(current) = pending.pop() {
+            if blocks.insert(current) {
+                pending.extend(preds[current].iter().map(|pred| pred.0));
+            }
+        }
+
+        let outside: Vec<usize> = preds[header]
+            .iter()
+            .map(|pred| pred.0)
+            .filter(|pred| !blocks.contains(pred))
+            .collect();
+        if outside.len() != 1 {
+            continue;
+        }
+        let preheader = outside[0];
+        if func.blocks[preheader].terminator != Terminator::Jump(BlockId(header)) {
+            continue;
+        }
+
+        result.push(Loop { blocks, preheader });
+    }
+    result
+}
+
+/// The Step of a Definition, if it only adds a Constant to the Temp
+fn step(instr: &Instr) -> Option<u32> {
+    match instr {
+        Instr::Binary(target, BinOp::Add, Operand::Temp(source), Operand::Const(step))
+            if target == source =>
+        {
+            Some(*step)
+        }
+        Instr::Binary(target, BinOp::Sub, Operand::Temp(source), Operand::Const(step))
+            if target == source =>
+        {
+            Some(step.wrapping_neg())
+        }
+        _ => None,
+    }
+}
+
+/// The Temps, which are only changed by adding a constant Step to them
+/// inside of the Loop, together with the Positions and Steps of these
+/// Changes
+fn induction_variables(func: &Function, lp: &Loop) -> HashMap<Temp, Vec<(Position, u32)>> {
+    let mut result: HashMap<Temp, Vec<(Position, u32)>> = HashMap::new();
+    let mut invalid = HashSet::new();
+    for &b_index in lp.blocks.iter() {
+        for (i_index, instr) in func.blocks[b_index].instrs.iter().enumerate() {
+            let target = match instr.target() {
+                Some(t) => t,
+                None => continue,
+            };
+            match step(instr) {
+                Some(step) => result
+                    .entry(target)
+                    .or_default()
+                    .push(((b_index, i_index), step)),
+                None => {
+                    invalid.insert(target);
+                }
+            };
+        }
+    }
+    result.retain(|temp, _| !invalid.contains(temp));
+    result
+}
+
+/// Finds all the Candidates inside of the Loop
+fn candidates(
+    func: &Function,
+    lp: &Loop,
+    steps: &HashMap<Temp, Vec<(Position, u32)>>,
+    defs: &HashMap<Temp, Vec<Position>>,
+) -> Vec<Candidate> {
+    let mut b_indices: Vec<usize> = lp.blocks.iter().copied().collect();
+    b_indices.sort_unstable();
+
+    let mut result = Vec::new();
+    for b_index in b_indices {
+        for (i_index, instr) in func.blocks[b_index].instrs.iter().enumerate() {
+            let (target, left, right) = match instr {
+                // The Changes of the Variables themselves are left alone
+                Instr::Binary(target, BinOp::Add, left, right) if !steps.contains_key(target) => {
+                    (*target, left, right)
+                }
+                _ => continue,
+            };
+
+            // The Variable may be on either Side of the Addition
+            let found = [(left, right), (right, left)].iter().find_map(|(a, b)| {
+                let (iv, scale) = scaled(func, a, (b_index, i_index), steps, defs)?;
+                let base = invariant(func, lp, b, iv, defs)?;
+                Some((iv, scale, base))
+            });
+
+            if let Some((iv, scale, base)) = found {
+                result.push(Candidate {
+                    position: (b_index, i_index),
+                    target,
+                    iv,
+                    base,
+                    scale,
+                });
+            }
+        }
+    }
+    result
+}
+
+/// Checks if the Operand holds a Variable of the Loop multiplied by a
+/// Constant, which is either the Variable itself or calculated right
+/// before in the same Block
+fn scaled(
+    func: &Function,
+    operand: &Operand,
+    (b_index, i_index): Position,
+    steps: &HashMap<Temp, Vec<(Position, u32)>>,
+    defs: &HashMap<Temp, Vec<Position>>,
+) -> Option<(Temp, u32)> {
+    let temp = operand.temp()?;
+    if steps.contains_key(&temp) {
+        return Some((temp, 1));
+    }
+
+    let (def_block, def_index) = match defs.get(&temp)?.as_slice() {
+        [single] => *single,
+        _ => return None,
+    };
+    if def_block != b_index || def_index >= i_index {
+        return None;
+    }
+
+    let (iv, scale) = match &func.blocks[def_block].instrs[def_index] {
+        Instr::Binary(_, BinOp::Mul, Operand::Temp(iv), Operand::Const(scale)) => (*iv, *scale),
+        Instr::Binary(_, BinOp::Shl, Operand::Temp(iv), Operand::Const(bits)) if *bits < 32 => {
+            (*iv, 1 << bits)
+        }
+        _ => return None,
+    };
+    if iv == temp || !steps.contains_key(&iv) {
+        return None;
+    }
+
+    // The Variable must not change between the Multiplication and its Use
+    let changed = steps[&iv]
+        .iter()
+        .any(|((block, index), _)| *block == b_index && *index > def_index && *index < i_index);
+    if changed {
+        return None;
+    }
+
+    Some((iv, scale))
+}
+
+/// Checks if the Operand holds the same Value everywhere in the Loop.
+/// Constants are not used, as these are only Offsets and not Pointers
+fn invariant(
+    func: &Function,
+    lp: &Loop,
+    operand: &Operand,
+    iv: Temp,
+    defs: &HashMap<Temp, Vec<Position>>,
+) -> Option<Base> {
+    let temp = operand.temp()?;
+    if temp == iv {
+        return None;
+    }
+
+    let positions = defs.get(&temp).map(|p| p.as_slice()).unwrap_or(&[]);
+    if positions
+        .iter()
+        .all(|(block, _)| !lp.blocks.contains(block))
+    {
+        return Some(Base::Operand(*operand));
+    }
+
+    // Addresses can simply be calculated again
+    match positions {
+        [(block, index)] => match &func.blocks[*block].instrs[*index] {
+            Instr::Address(_, address) => Some(Base::Address(address.clone())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Calculates the Pointer in the Preheader, moves it along with the
+/// Variable and replaces the Candidates with it
+fn transform(func: &mut Function, lp: &Loop, candidates: Vec<Candidate>) {
+    let first = &candidates[0];
+    let iv = first.iv;
+    let scale = first.scale;
+
+    let base = match &first.base {
+        Base::Operand(operand) => *operand,
+        Base::Address(address) => {
+            let temp = func.temp();
+            func.blocks[lp.preheader]
+                .instrs
+                .push(Instr::Address(temp, address.clone()));
+            Operand::Temp(temp)
+        }
+    };
+    let offset = func.temp();
+    let pointer = func.temp();
+    let preheader = &mut func.blocks[lp.preheader].instrs;
+    preheader.push(Instr::Binary(
+        offset,
+        BinOp::Mul,
+        Operand::Temp(iv),
+        Operand::Const(scale),
+    ));
+    preheader.push(Instr::Binary(
+        pointer,
+        BinOp::Add,
+        base,
+        Operand::Temp(offset),
+    ));
+
+    for candidate in candidates.iter() {
+        let (b_index, i_index) = candidate.position;
+        func.blocks[b_index].instrs[i_index] =
+            Instr::Copy(candidate.target, Operand::Temp(pointer));
+    }
+
+    // The Pointer is moved right after every Change of the Variable,
+    // starting at the End so the Positions stay the same
+    let mut changes = induction_variables(func, lp)
+        .remove(&iv)
+        .unwrap_or_default();
+    changes.sort_unstable_by_key(|(position, _)| std::cmp::Reverse(*position));
+    for ((b_index, i_index), step) in changes {
+        func.blocks[b_index].instrs.insert(
+            i_index + 1,
+            Instr::Binary(
+                pointer,
+                BinOp::Add,
+                Operand::Temp(pointer),
+                Operand::Const(step.wrapping_mul(scale)),
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ir, mir};
+
+    #[test]
+    fn array_index() {
+        // bb0: %1 = &slot0; %0 = 0; jump bb1
+        // bb1: branch %0 < 10 ? bb2 : bb3
+        // bb2: %2 = shl %0, 2; %3 = add %1, %2; store [%3], 0; %0 = add %0, 1; jump bb1
+        // bb3: return
+        let mut func = mir::Function {
+            name: "test".to_owned(),
+            return_type: ir::DataType::Void,
+            attributes: ir::Attributes::default(),
+            params: vec![],
+            slots: vec![ir::DataType::Array(Box::new(ir::DataType::I32), 10)],
+            strings: vec![],
+            blocks: vec![
+                mir::Block {
+                    instrs: vec![
+                        Instr::Address(Temp(1), Address::Slot(0)),
+                        Instr::Copy(Temp(0), Operand::Const(0)),
+                    ],
+                    terminator: Terminator::Jump(BlockId(1)),
+                },
+                mir::Block {
+                    instrs: vec![],
+                    terminator: Terminator::Branch(
+                        mir::Condition {
+                            comparison: ir::Comparison::LessThan,
+                            signed: true,
+                        },
+                        Operand::Temp(Temp(0)),
+                        Operand::Const(10),
+                        BlockId(2),
+                        BlockId(3),
+                    ),
+                },
+                mir::Block {
+                    instrs: vec![
+                        Instr::Binary(
+                            Temp(2),
+                            BinOp::Shl,
+                            Operand::Temp(Temp(0)),
+                            Operand::Const(2),
+                        ),
+                        Instr::Binary(
+                            Temp(3),
+                            BinOp::Add,
+                            Operand::Temp(Temp(1)),
+                            Operand::Temp(Temp(2)),
+                        ),
+                        Instr::Store(Operand::Temp(Temp(3)), Operand::Const(0), ir::DataType::I32),
+                        Instr::Binary(
+                            Temp(0),
+                            BinOp::Add,
+                            Operand::Temp(Temp(0)),
+                            Operand::Const(1),
+                        ),
+                    ],
+                    terminator: Terminator::Jump(BlockId(1)),
+                },
+                mir::Block {
+                    instrs: vec![],
+                    terminator: Terminator::Return(None),
+                },
+            ],
+            temps: 4,
+        };
+
+        assert_eq!(true, reduce(&mut func));
+
+        assert_eq!(
+            vec![
+                Instr::Address(Temp(1), Address::Slot(0)),
+                Instr::Copy(Temp(0), Operand::Const(0)),
+                Instr::Binary(
+                    Temp(4),
+                    BinOp::Mul,
+                    Operand::Temp(Temp(0)),
+                    Operand::Const(4)
+                ),
+                Instr::Binary(
+                    Temp(5),
+                    BinOp::Add,
+                    Operand::Temp(Temp(1)),
+                    Operand::Temp(Temp(4))
+                ),
+            ],
+            func.blocks[0].instrs
+        );
+        assert_eq!(
+            vec![
+                Instr::Binary(
+                    Temp(2),
+                    BinOp::Shl,
+                    Operand::Temp(Temp(0)),
+                    Operand::Const(2)
+                ),
+                Instr::Copy(Temp(3), Operand::Temp(Temp(5))),
+                Instr::Store(Operand::Temp(Temp(3)), Operand::Const(0), ir::DataType::I32),
+                Instr::Binary(
+                    Temp(0),
+                    BinOp::Add,
+                    Operand::Temp(Temp(0)),
+                    Operand::Const(1)
+                ),
+                Instr::Binary(
+                    Temp(5),
+                    BinOp::Add,
+                    Operand::Temp(Temp(5)),
+                    Operand::Const(4)
+                ),
+            ],
+            func.blocks[2].instrs
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use crate::{
+    ir::CallingConvention,
+    mir::{Function, Instr, Operand, Terminator},
+};
+
+/// Turns the Calls, whose Result is returned right away, into Tail-Calls,
+/// which reuse the Frame instead of building a new one on top of it. The
+/// Return can also be in an empty Block that is jumped to after the Call,
+/// which is what the Inliner leaves behind.
+///
+/// The Arguments on the Stack are placed where the own Parameters were, so
+/// the called Function has to use the same Calling-Convention and can not
+/// take more of them. Functions with Slots are left alone, as the called
+/// Function may still use their Addresses
+pub fn calls(func: &mut Function, conventions: &HashMap<String, CallingConvention>) -> bool {
+    if !func.slots.is_empty() {
+        return false;
+    }
+
+    let convention = func.attributes.convention;
+    let params = func.params.len();
+    // Whether or not the Function can reuse the Frame for the Call
+    let fits = |name: &String, args: &Vec<Operand>| {
+        let callee = conventions.get(name).copied().unwrap_or_default();
+        let stack = |count: usize| count - convention.registers(count);
+        callee == convention && stack(args.len()) <= stack(params)
+    };
+
+    // The Returns of the Blocks that do nothing else
+    let returns: Vec<Option<Terminator>> = func
+        .blocks
+        .iter()
+        .map(|block| match &block.terminator {
+            Terminator::Return(_) if block.instrs.is_empty() => Some(block.terminator.clone()),
+            _ => None,
+        })
+        .collect();
+
+    let mut changed = false;
+    for block in func.blocks.iter_mut() {
+        let terminator = match &block.terminator {
+            Terminator::Jump(target) => match &returns[target.0] {
+                Some(terminator) => terminator,
+                None => continue,
+            },
+            terminator => terminator,
+        };
+        let tail = match (block.instrs.last(), terminator) {
+            (
+                Some(Instr::Call(Some(result), name, args)),
+                Terminator::Return(Some(Operand::Temp(value))),
+            ) => result == value && fits(name, args),
+            (Some(Instr::Call(None, name, args)), Terminator::Return(None)) => fits(name, args),
+            _ => false,
+        };
+        if !tail {
+            continue;
+        }
+
+        if let Some(Instr::Call(_, name, args)) = block.instrs.pop() {
+            block.terminator = Terminator::TailCall(name, args);
+            changed = true;
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir,
+        mir::{self, Temp},
+    };
+
+    fn call(args: Vec<Operand>) -> mir::Block {
+        mir::Block {
+            instrs: vec![Instr::Call(Some(Temp(1)), "other".to_owned(), args)],
+            terminator: Terminator::Return(Some(Operand::Temp(Temp(1)))),
+        }
+    }
+
+    #[test]
+    fn returned_result() {
+        let mut func = mir::Function {
+            name: "test".to_owned(),
+            return_type: ir::DataType::I32,
+            attributes: ir::Attributes {
+                convention: CallingConvention::Stack,
+                ..Default::default()
+            },
+            params: vec![(Temp(0), ir::DataType::I32)],
+            slots: vec![],
+            strings: vec![],
+            blocks: vec![
+                call(vec![Operand::Temp(Temp(0))]),
+                // Takes more Arguments than there are Parameters
+                call(vec![Operand::Const(1), Operand::Const(2)]),
+            ],
+            temps: 2,
+        };
+        let mut conventions = HashMap::new();
+        conventions.insert("other".to_owned(), CallingConvention::Stack);
+
+        assert_eq!(true, calls(&mut func, &conventions));
+
+        assert_eq!(
+            mir::Block {
+                instrs: vec![],
+                terminator: Terminator::TailCall("other".to_owned(), vec![Operand::Temp(Temp(0))]),
+            },
+            func.blocks[0]
+        );
+        assert_eq!(1, func.blocks[1].instrs.len());
+    }
+
+    #[test]
+    fn register_arguments() {
+        let mut func = mir::Function {
+            name: "test".to_owned(),
+            return_type: ir::DataType::I32,
+            attributes: ir::Attributes::default(),
+            params: vec![(Temp(0), ir::DataType::I32)],
+            slots: vec![],
+            strings: vec![],
+            blocks: vec![
+                // All the Arguments are passed in Registers
+                call(vec![Operand::Const(1), Operand::Const(2)]),
+                // The last Argument would need to be on the Stack
+                call((0..5).map(Operand::Const).collect()),
+            ],
+            temps: 2,
+        };
+        let mut conventions = HashMap::new();
+        conventions.insert("other".to_owned(), CallingConvention::Renesas);
+
+        assert_eq!(true, calls(&mut func, &conventions));
+        assert_eq!(0, func.blocks[0].instrs.len());
+        assert_eq!(1, func.blocks[1].instrs.len());
+
+        // The Callee uses a different Convention
+        let mut func = mir::Function {
+            blocks: vec![call(vec![Operand::Const(1)])],
+            ..func
+        };
+        conventions.insert("other".to_owned(), CallingConvention::Stack);
+        assert_eq!(false, calls(&mut func, &conventions));
+    }
+
+    #[test]
+    fn return_after_jump() {
+        // %1 = call other(%0); jump bb1; bb1: return %1
+        let mut func = mir::Function {
+            name: "test".to_owned(),
+            return_type: ir::DataType::I32,
+            attributes: ir::Attributes::default(),
+            params: vec![(Temp(0), ir::DataType::I32)],
+            slots: vec![],
+            strings: vec![],
+            blocks: vec![
+                mir::Block {
+                    instrs: vec![Instr::Call(
+                        Some(Temp(1)),
+                        "other".to_owned(),
+                        vec![Operand::Temp(Temp(0))],
+                    )],
+                    terminator: Terminator::Jump(mir::BlockId(1)),
+                },
+                mir::Block {
+                    instrs: vec![],
+                    terminator: Terminator::Return(Some(Operand::Temp(Temp(1)))),
+                },
+                // Returns a different Value than the Result of the Call
+                mir::Block {
+                    instrs: vec![Instr::Call(
+                        Some(Temp(0)),
+                        "other".to_owned(),
+                        vec![Operand::Temp(Temp(0))],
+                    )],
+                    terminator: Terminator::Jump(mir::BlockId(1)),
+                },
+            ],
+            temps: 2,
+        };
+
+        assert_eq!(true, calls(&mut func, &HashMap::new()));
+
+        assert_eq!(
+            Terminator::TailCall("other".to_owned(), vec![Operand::Temp(Temp(0))]),
+            func.blocks[0].terminator
+        );
+        assert!(func.blocks[0].instrs.is_empty());
+        assert_eq!(Terminator::Jump(mir::BlockId(1)), func.blocks[2].terminator);
+    }
+}
@@ -1,4 +1,7 @@
+use std::cell::Cell;
+
 use super::{
+    diagnostic::{Diagnostic, Level},
     ir,
     lexer::{Token, TokenMetadata},
 };
@@ -10,21 +13,74 @@ pub mod datatype;
 pub mod expression;
 pub mod func_args;
 pub mod function;
+pub mod global;
 pub mod statements;
 
 /// Parses the Tokens into the Compilers-IR that represents the actual
-/// Program in a more Abstract way
-pub fn parse(tokens: &[(Token, TokenMetadata)]) -> Vec<ir::Function> {
+/// Program in a more Abstract way, while also checking it semantically.
+/// The Warnings for the Program are returned alongside of it
+///
+/// If the Tokens do not form a valid Program, the Diagnostic points to
+/// the furthest Token that was looked at before the Parsing failed
+pub fn parse(
+    tokens: &[(Token, TokenMetadata)],
+) -> Result<(ir::Program, Vec<Diagnostic>), Vec<Diagnostic>> {
     let mut functions = Vec::new();
 
-    let mut iter = tokens.iter().peekable();
+    // Keeps track of the last Token that was taken out of the Stream,
+    // which includes Tokens that were only peeked at
+    let furthest: Cell<Option<&(Token, TokenMetadata)>> = Cell::new(None);
+
+    let mut globals = statements::Variables::new();
+    let mut iter = tokens
+        .iter()
+        .inspect(|token| furthest.set(Some(token)))
+        .peekable();
     while iter.peek().is_some() {
-        if let Some(func) = function::parse(&mut iter) {
-            functions.push(func);
-        }
+        match function::parse(&mut iter, &mut globals) {
+            Some(function::Definition::Function(func)) => functions.push(func),
+            Some(function::Definition::Declaration) => {}
+            None => {
+                // Needs to be read before peeking, as that could move it further
+                let last = furthest.get();
+                let exhausted = iter.peek().is_none();
+
+                let mut diagnostics = globals.take_diagnostics();
+                diagnostics.push(unexpected(tokens, last, exhausted));
+                return Err(diagnostics);
+            }
+        };
+    }
+
+    let diagnostics = globals.take_diagnostics();
+    if diagnostics.iter().any(|d| d.level == Level::Error) {
+        return Err(diagnostics);
     }
 
-    functions
+    let program = ir::Program {
+        globals: globals.take_globals(),
+        functions,
+        conventions: globals.conventions(),
+    };
+    Ok((program, diagnostics))
+}
+
+/// Creates the Diagnostic for the Token at which the Parsing failed, if
+/// there are no Tokens left the Input simply ended too early
+fn unexpected(
+    tokens: &[(Token, TokenMetadata)],
+    furthest: Option<&(Token, TokenMetadata)>,
+    exhausted: bool,
+) -> Diagnostic {
+    match furthest {
+        Some((token, metadata)) if !exhausted => {
+            Diagnostic::error(metadata, format!("unexpected `{}`", token))
+        }
+        _ => {
+            let (_, metadata) = tokens.last().expect("Parsing only fails with Tokens");
+            Diagnostic::error(metadata, "unexpected end of file")
+        }
+    }
 }
 
 #[cfg(test)]
@@ -56,9 +112,10 @@ mod tests {
             vec![ir::Statement::Return(ir::Expression::Constant(
                 ir::Value::I32(0),
             ))],
+            ir::Attributes::default(),
         )];
 
-        assert_eq!(expected, parse(tokens));
+        assert_eq!(expected, parse(tokens).unwrap().0.functions);
     }
 
     #[test]
@@ -92,9 +149,10 @@ mod tests {
                 ),
                 ir::Statement::Return(ir::Expression::Constant(ir::Value::I32(0))),
             ],
+            ir::Attributes::default(),
         )];
 
-        assert_eq!(expected, parse(tokens));
+        assert_eq!(expected, parse(tokens).unwrap().0.functions);
     }
 
     #[test]
@@ -132,9 +190,10 @@ mod tests {
                     ),
                 ),
             ],
+            ir::Attributes::default(),
         )];
 
-        assert_eq!(expected, parse(tokens));
+        assert_eq!(expected, parse(tokens).unwrap().0.functions);
     }
     #[test]
     fn nested_addition() {
@@ -167,21 +226,22 @@ mod tests {
                     ir::Expression::Operation(
                         ir::OP::Add,
                         vec![
-                            ir::Expression::Constant(ir::Value::I32(2)),
                             ir::Expression::Operation(
                                 ir::OP::Add,
                                 vec![
+                                    ir::Expression::Constant(ir::Value::I32(2)),
                                     ir::Expression::Constant(ir::Value::I32(3)),
-                                    ir::Expression::Constant(ir::Value::I32(4)),
                                 ],
                             ),
+                            ir::Expression::Constant(ir::Value::I32(4)),
                         ],
                     ),
                 ),
             ],
+            ir::Attributes::default(),
         )];
 
-        assert_eq!(expected, parse(tokens));
+        assert_eq!(expected, parse(tokens).unwrap().0.functions);
     }
 
     #[test]
@@ -219,14 +279,20 @@ mod tests {
                     ),
                 ),
             ],
+            ir::Attributes::default(),
         )];
 
-        assert_eq!(expected, parse(tokens));
+        assert_eq!(expected, parse(tokens).unwrap().0.functions);
     }
 
     #[test]
     fn simple_call() {
         let tokens = &[
+            test_token_pair!(Token::Keyword(Keyword::Void)),
+            test_token_pair!(Token::Identifier("test_func".to_string())),
+            test_token_pair!(Token::OpenParan),
+            test_token_pair!(Token::CloseParan),
+            test_token_pair!(Token::Semicolon),
             test_token_pair!(Token::Keyword(Keyword::Integer)),
             test_token_pair!(Token::Identifier("main".to_string())),
             test_token_pair!(Token::OpenParan),
@@ -250,16 +316,23 @@ mod tests {
                 ir::Statement::SingleExpression(ir::Expression::Call(
                     "test_func".to_string(),
                     vec![],
+                    ir::DataType::Void,
                 )),
                 ir::Statement::Return(ir::Expression::Constant(ir::Value::I32(0))),
             ],
+            ir::Attributes::default(),
         )];
 
-        assert_eq!(expected, parse(tokens));
+        assert_eq!(expected, parse(tokens).unwrap().0.functions);
     }
     #[test]
     fn call_as_assignment() {
         let tokens = &[
+            test_token_pair!(Token::Keyword(Keyword::Integer)),
+            test_token_pair!(Token::Identifier("test_func".to_string())),
+            test_token_pair!(Token::OpenParan),
+            test_token_pair!(Token::CloseParan),
+            test_token_pair!(Token::Semicolon),
             test_token_pair!(Token::Keyword(Keyword::Integer)),
             test_token_pair!(Token::Identifier("main".to_string())),
             test_token_pair!(Token::OpenParan),
@@ -286,12 +359,13 @@ mod tests {
                 ir::Statement::Declaration(ir::Variable::new_str("test_var", ir::DataType::I32)),
                 ir::Statement::Assignment(
                     ir::Variable::new_str("test_var", ir::DataType::I32),
-                    ir::Expression::Call("test_func".to_string(), vec![]),
+                    ir::Expression::Call("test_func".to_string(), vec![], ir::DataType::I32),
                 ),
                 ir::Statement::Return(ir::Expression::Constant(ir::Value::I32(0))),
             ],
+            ir::Attributes::default(),
         )];
 
-        assert_eq!(expected, parse(tokens));
+        assert_eq!(expected, parse(tokens).unwrap().0.functions);
     }
 }
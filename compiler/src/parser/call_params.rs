@@ -23,7 +23,7 @@ use crate::{
 /// # use compiler::ir::{Variable, DataType};
 /// # let mut variables = Variables::new();
 /// # variables.insert("test_name".to_string(), Variable::new_str("test_name", DataType::U32));
-/// # let empty_metadata = TokenMetadata { file_name: "test".to_string(), line: 1, };
+/// # let empty_metadata = TokenMetadata { file_name: "test".to_string(), line: 1, column: 1, };
 /// let tokens = &[
 ///     (Token::Identifier("test_name".to_owned()), empty_metadata.clone()),
 ///     (Token::CloseParan, empty_metadata.clone()),
@@ -78,6 +78,7 @@ mod tests {
             TokenMetadata {
                 file_name: "test".to_string(),
                 line: 1,
+                column: 1,
             },
         )];
 
@@ -97,6 +98,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -104,6 +106,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
@@ -124,6 +127,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -131,6 +135,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -138,6 +143,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -145,6 +151,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
@@ -5,18 +5,17 @@ use crate::{
     lexer::{Token, TokenMetadata},
 };
 
-/// Parses the Comparison Operator itself only consuming 2 entries
-/// from the Iterator and most and only consuming them if they
-/// are actually part of the Comparison
+/// Parses the Comparison Operator itself only consuming the next entry
+/// from the Iterator and only consuming it if it is actually a
+/// Comparison
 ///
 /// # Example:
 /// ```rust
 /// # use compiler::lexer::{Token, TokenMetadata};
 /// # use compiler::parser::comparison::parse;
-/// # let empty_metadata = TokenMetadata { file_name: "test".to_owned(), line: 1, };
+/// # let empty_metadata = TokenMetadata { file_name: "test".to_owned(), line: 1, column: 1, };
 /// let tokens = &[
-///     (Token::Equals, empty_metadata.clone()),
-///     (Token::Equals, empty_metadata.clone()),
+///     (Token::DoubleEquals, empty_metadata.clone()),
 /// ];
 ///
 /// // Parse the Tokens
@@ -30,25 +29,22 @@ pub fn parse<'a, I>(iter: &mut Peekable<I>) -> Option<ir::Comparison>
 where
     I: Iterator<Item = &'a (Token, TokenMetadata)>,
 {
-    let peeked = iter.peek();
-    match peeked {
-        Some((Token::Equals, _)) => {
-            iter.next();
-
-            match iter.peek() {
-                Some((Token::Equals, _)) => {
-                    iter.next();
-
-                    Some(ir::Comparison::Equal)
-                }
-                _ => None,
-            }
-        }
-        Some((Token::LessThan, _)) => {
-            iter.next();
-
-            Some(ir::Comparison::LessThan)
-        }
+    let (token, _) = iter.peek()?;
+    let result = from_token(token)?;
+    iter.next();
+
+    Some(result)
+}
+
+/// Returns the Comparison that the given Token stands for, if there is any
+pub fn from_token(token: &Token) -> Option<ir::Comparison> {
+    match token {
+        Token::DoubleEquals => Some(ir::Comparison::Equal),
+        Token::NotEquals => Some(ir::Comparison::NotEqual),
+        Token::LessThan => Some(ir::Comparison::LessThan),
+        Token::LessEquals => Some(ir::Comparison::LessEqual),
+        Token::GreaterThan => Some(ir::Comparison::GreaterThan),
+        Token::GreaterEquals => Some(ir::Comparison::GreaterEqual),
         _ => None,
     }
 }
@@ -59,22 +55,14 @@ mod tests {
 
     #[test]
     fn parse_equals() {
-        let tokens = &[
-            (
-                Token::Equals,
-                TokenMetadata {
-                    file_name: "test".to_string(),
-                    line: 1,
-                },
-            ),
-            (
-                Token::Equals,
-                TokenMetadata {
-                    file_name: "test".to_string(),
-                    line: 1,
-                },
-            ),
-        ];
+        let tokens = &[(
+            Token::DoubleEquals,
+            TokenMetadata {
+                file_name: "test".to_string(),
+                line: 1,
+                column: 1,
+            },
+        )];
 
         let expected = Some(ir::Comparison::Equal);
 
@@ -88,6 +76,7 @@ mod tests {
             TokenMetadata {
                 file_name: "test".to_string(),
                 line: 1,
+                column: 1,
             },
         )];
 
@@ -95,4 +84,36 @@ mod tests {
 
         assert_eq!(expected, parse(&mut tokens.iter().peekable()));
     }
+
+    #[test]
+    fn parse_greater_equal() {
+        let tokens = &[(
+            Token::GreaterEquals,
+            TokenMetadata {
+                file_name: "test".to_string(),
+                line: 1,
+                column: 1,
+            },
+        )];
+
+        let expected = Some(ir::Comparison::GreaterEqual);
+
+        assert_eq!(expected, parse(&mut tokens.iter().peekable()));
+    }
+
+    #[test]
+    fn no_comparison() {
+        let tokens = &[(
+            Token::Equals,
+            TokenMetadata {
+                file_name: "test".to_string(),
+                line: 1,
+                column: 1,
+            },
+        )];
+
+        let mut iter = tokens.iter().peekable();
+        assert_eq!(None, parse(&mut iter));
+        assert!(iter.next().is_some());
+    }
 }
@@ -5,11 +5,11 @@ use crate::{
     lexer::{Token, TokenMetadata},
 };
 
-use super::{comparison, expression, statements::Variables};
+use super::{expression, statements::Variables};
 
-/// Parses an Conditional like `(i < 0)` or `i < 0;` into a proper
-/// ir::Conditional Struct to allow for easier and more uniform usage
-/// in the entire Repo
+/// Parses an Conditional like `(i < 0)` or `i < 0 && j;` into a proper
+/// ir::Condition to allow for easier and more uniform usage in the
+/// entire Repo. Any other Expression is true if it is not 0
 ///
 /// # Example:
 /// ```rust
@@ -19,11 +19,10 @@ use super::{comparison, expression, statements::Variables};
 /// # use compiler::ir::{Variable, DataType};
 /// # let mut variables = Variables::new();
 /// # variables.insert("test".to_owned(), Variable::new_str("test", DataType::U32));
-/// # let empty_metadata = TokenMetadata { file_name: "test".to_owned(), line: 1, };
+/// # let empty_metadata = TokenMetadata { file_name: "test".to_owned(), line: 1, column: 1, };
 /// let tokens = &[
 ///     (Token::Identifier("test".to_owned()), empty_metadata.clone()),
-///     (Token::Equals, empty_metadata.clone()),
-///     (Token::Equals, empty_metadata.clone()),
+///     (Token::DoubleEquals, empty_metadata.clone()),
 ///     (Token::Identifier("test".to_owned()), empty_metadata.clone()),
 ///     (Token::CloseParan, empty_metadata.clone()),
 /// ];
@@ -39,20 +38,27 @@ pub fn parse<'a, I>(iter: &mut Peekable<I>, vars: &Variables) -> Option<ir::Cond
 where
     I: Iterator<Item = &'a (Token, TokenMetadata)>,
 {
-    let left_comp = expression::parse(iter, vars).unwrap();
-    let comp = comparison::parse(iter).unwrap();
-    let right_comp = expression::parse(iter, vars).unwrap();
+    let exp = expression::parse(iter, vars)?;
 
-    Some(ir::Condition {
-        left: left_comp,
-        right: right_comp,
-        comparison: comp,
-    })
+    Some(from_expression(exp))
+}
+
+/// Converts the Expression into a Condition, Expressions that are not
+/// already a Condition are true if they are not 0
+pub fn from_expression(exp: ir::Expression) -> ir::Condition {
+    match exp {
+        ir::Expression::Condition(cond) => *cond,
+        other => ir::Condition::Compare(
+            ir::Comparison::NotEqual,
+            other,
+            ir::Expression::Constant(ir::Value::I32(0)),
+        ),
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::ir::Variable;
+    use crate::{ir::Variable, lexer::Value, test_token_pair};
 
     use super::*;
 
@@ -64,20 +70,15 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
-                Token::Equals,
-                TokenMetadata {
-                    file_name: "test".to_string(),
-                    line: 1,
-                },
-            ),
-            (
-                Token::Equals,
+                Token::DoubleEquals,
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -85,6 +86,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
@@ -95,11 +97,54 @@ mod tests {
         vars.insert(left_var.name.clone(), left_var.clone());
         vars.insert(right_var.name.clone(), right_var.clone());
 
-        let expected = Some(ir::Condition {
-            left: ir::Expression::Variable(left_var),
-            right: ir::Expression::Variable(right_var),
-            comparison: ir::Comparison::Equal,
-        });
+        let expected = Some(ir::Condition::Compare(
+            ir::Comparison::Equal,
+            ir::Expression::Variable(left_var),
+            ir::Expression::Variable(right_var),
+        ));
+
+        assert_eq!(expected, parse(&mut tokens.iter().peekable(), &vars));
+    }
+
+    #[test]
+    fn logical_operators() {
+        let tokens = &[
+            test_token_pair!(Token::Exclamation),
+            test_token_pair!(Token::Identifier("a".to_owned())),
+            test_token_pair!(Token::LogicalOr),
+            test_token_pair!(Token::Identifier("a".to_owned())),
+            test_token_pair!(Token::LessEquals),
+            test_token_pair!(Token::Constant(Value::Integer(2))),
+            test_token_pair!(Token::LogicalAnd),
+            test_token_pair!(Token::Identifier("a".to_owned())),
+            test_token_pair!(Token::NotEquals),
+            test_token_pair!(Token::Constant(Value::Integer(1))),
+        ];
+
+        let mut vars = Variables::new();
+        let var = Variable::new_str("a", ir::DataType::I32);
+        vars.insert(var.name.clone(), var.clone());
+
+        // And binds stronger than Or
+        let expected = Some(ir::Condition::Or(
+            Box::new(ir::Condition::Not(Box::new(ir::Condition::Compare(
+                ir::Comparison::NotEqual,
+                ir::Expression::Variable(var.clone()),
+                ir::Expression::Constant(ir::Value::I32(0)),
+            )))),
+            Box::new(ir::Condition::And(
+                Box::new(ir::Condition::Compare(
+                    ir::Comparison::LessEqual,
+                    ir::Expression::Variable(var.clone()),
+                    ir::Expression::Constant(ir::Value::I32(2)),
+                )),
+                Box::new(ir::Condition::Compare(
+                    ir::Comparison::NotEqual,
+                    ir::Expression::Variable(var),
+                    ir::Expression::Constant(ir::Value::I32(1)),
+                )),
+            )),
+        ));
 
         assert_eq!(expected, parse(&mut tokens.iter().peekable(), &vars));
     }
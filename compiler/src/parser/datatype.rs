@@ -5,15 +5,20 @@ use crate::{
     lexer::{Keyword, Token, TokenMetadata},
 };
 
+use super::statements::Variables;
+
+mod aggregate;
 mod parse_dt;
 
-/// Parses a Token-Stream into a concrete Datatype
+/// Parses a Token-Stream into a concrete Datatype, any Struct or Union
+/// that gets defined as part of this is also registered in the Variables
 ///
 /// # Example:
 /// ```rust
 /// # use compiler::lexer::{Token, TokenMetadata, Keyword};
 /// # use compiler::parser::datatype::parse;
-/// # let empty_metadata = TokenMetadata { file_name: "test".to_owned(), line: 1, };
+/// # use compiler::parser::statements::Variables;
+/// # let empty_metadata = TokenMetadata { file_name: "test".to_owned(), line: 1, column: 1, };
 /// let tokens = &[
 ///     (Token::Keyword(Keyword::Integer), empty_metadata.clone()),
 ///     (Token::Identifier("test".to_owned()), empty_metadata.clone()),
@@ -21,29 +26,42 @@ mod parse_dt;
 ///
 /// // Parse the Tokens
 /// let mut iter = tokens.iter().peekable();
-/// parse(&mut iter);
+/// parse(&mut iter, &mut Variables::new());
 ///
 /// // Expect the Identifier to be left in the Token-Stream
 /// assert_eq!(Some(&(Token::Identifier("test".to_owned()), empty_metadata)), iter.next());
 /// ```
-pub fn parse<'a, I>(iter: &mut Peekable<I>) -> Option<ir::DataType>
+pub fn parse<'a, I>(iter: &mut Peekable<I>, vars: &mut Variables) -> Option<ir::DataType>
 where
     I: Iterator<Item = &'a (Token, TokenMetadata)>,
 {
-    let unsigned = match iter.peek() {
+    let mut result = match iter.peek() {
+        Some((Token::Keyword(Keyword::Struct), _)) | Some((Token::Keyword(Keyword::Union), _)) => {
+            aggregate::parse(iter, vars)?
+        }
         Some((Token::Keyword(Keyword::Unsigned), _)) => {
             iter.next();
-            true
+            parse_dt::parse(iter, true)?
+        }
+        Some((Token::Keyword(Keyword::Signed), _)) => {
+            iter.next();
+            parse_dt::parse(iter, false)?
         }
-        _ => false,
+        _ => parse_dt::parse(iter, false)?,
     };
 
-    parse_dt::parse(iter, unsigned)
+    while let Some((Token::Asterisk, _)) = iter.peek() {
+        iter.next();
+        result = ir::DataType::Ptr(Box::new(result));
+    }
+
+    Some(result)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_token_pair;
 
     #[test]
     fn int() {
@@ -53,6 +71,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -60,13 +79,14 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
 
         assert_eq!(
             Some(ir::DataType::I32),
-            parse(&mut tokens.iter().peekable())
+            parse(&mut tokens.iter().peekable(), &mut Variables::new())
         );
     }
     #[test]
@@ -77,6 +97,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -84,6 +105,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -91,13 +113,14 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
 
         assert_eq!(
             Some(ir::DataType::U32),
-            parse(&mut tokens.iter().peekable())
+            parse(&mut tokens.iter().peekable(), &mut Variables::new())
         );
     }
 
@@ -109,6 +132,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -116,13 +140,14 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
 
         assert_eq!(
             Some(ir::DataType::I16),
-            parse(&mut tokens.iter().peekable())
+            parse(&mut tokens.iter().peekable(), &mut Variables::new())
         );
     }
     #[test]
@@ -133,6 +158,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -140,6 +166,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -147,13 +174,35 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
 
         assert_eq!(
             Some(ir::DataType::U16),
-            parse(&mut tokens.iter().peekable())
+            parse(&mut tokens.iter().peekable(), &mut Variables::new())
+        );
+    }
+
+    #[test]
+    fn chars() {
+        let tokens = &[
+            test_token_pair!(Token::Keyword(Keyword::Char)),
+            test_token_pair!(Token::Keyword(Keyword::Signed)),
+            test_token_pair!(Token::Keyword(Keyword::Char)),
+            test_token_pair!(Token::Keyword(Keyword::Unsigned)),
+            test_token_pair!(Token::Keyword(Keyword::Char)),
+            test_token_pair!(Token::Asterisk),
+        ];
+
+        let mut iter = tokens.iter().peekable();
+        let mut vars = Variables::new();
+        assert_eq!(Some(ir::DataType::I8), parse(&mut iter, &mut vars));
+        assert_eq!(Some(ir::DataType::I8), parse(&mut iter, &mut vars));
+        assert_eq!(
+            Some(ir::DataType::Ptr(Box::new(ir::DataType::U8))),
+            parse(&mut iter, &mut vars)
         );
     }
 
@@ -165,6 +214,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -172,13 +222,14 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
 
         assert_eq!(
             Some(ir::DataType::Void),
-            parse(&mut tokens.iter().peekable())
+            parse(&mut tokens.iter().peekable(), &mut Variables::new())
         );
     }
 
@@ -190,6 +241,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -197,6 +249,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -204,13 +257,14 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
 
         assert_eq!(
             Some(ir::DataType::Ptr(Box::new(ir::DataType::I32))),
-            parse(&mut tokens.iter().peekable())
+            parse(&mut tokens.iter().peekable(), &mut Variables::new())
         );
     }
 }
@@ -0,0 +1,212 @@
+use std::iter::Peekable;
+
+use crate::{
+    const_eval,
+    diagnostic::Diagnostic,
+    ir,
+    lexer::{Keyword, Token, TokenMetadata},
+    parser::{expression, statements::Variables},
+};
+
+/// Parses a Struct or Union, starting with the `struct` or `union` Keyword.
+///
+/// If the Keyword and Name are followed by a Body, the Type gets defined
+/// and registered in the Variables, otherwise the Type is looked up using
+/// its Name
+pub fn parse<'a, I>(iter: &mut Peekable<I>, vars: &mut Variables) -> Option<ir::DataType>
+where
+    I: Iterator<Item = &'a (Token, TokenMetadata)>,
+{
+    let is_struct = match iter.next() {
+        Some((Token::Keyword(Keyword::Struct), _)) => true,
+        Some((Token::Keyword(Keyword::Union), _)) => false,
+        _ => return None,
+    };
+
+    let name = match iter.peek() {
+        Some((Token::Identifier(name), _)) => {
+            iter.next();
+            name.to_owned()
+        }
+        _ => String::new(),
+    };
+    let key = if is_struct {
+        format!("struct {}", name)
+    } else {
+        format!("union {}", name)
+    };
+
+    match iter.peek() {
+        Some((Token::OpenCurlyBrace, _)) => {
+            iter.next();
+        }
+        _ => return vars.get_type(&key).cloned(),
+    };
+
+    let members = parse_members(iter, vars)?;
+    let result = if is_struct {
+        ir::DataType::Struct(name.clone(), members)
+    } else {
+        ir::DataType::Union(name.clone(), members)
+    };
+
+    if !name.is_empty() {
+        vars.insert_type(key, result.clone());
+    }
+
+    Some(result)
+}
+
+/// Parses all the Member-Declarations until and including the
+/// closing Curly-Brace
+fn parse_members<'a, I>(
+    iter: &mut Peekable<I>,
+    vars: &mut Variables,
+) -> Option<Vec<(String, ir::DataType)>>
+where
+    I: Iterator<Item = &'a (Token, TokenMetadata)>,
+{
+    let mut result: Vec<(String, ir::DataType)> = Vec::new();
+
+    loop {
+        match iter.peek() {
+            Some((Token::CloseCurlyBrace, _)) => {
+                iter.next();
+                return Some(result);
+            }
+            Some(_) => {}
+            None => return None,
+        };
+
+        let d_type = super::parse(iter, vars)?;
+
+        loop {
+            let (name, metadata) = match iter.next() {
+                Some((Token::Identifier(name), metadata)) => (name.to_owned(), metadata),
+                _ => return None,
+            };
+
+            let duplicate = result.iter().any(|(member, _)| *member == name);
+            if duplicate {
+                vars.report(Diagnostic::error(
+                    metadata,
+                    format!("duplicate member `{}`", name),
+                ));
+            }
+
+            let member_type = match iter.peek() {
+                Some((Token::OpenSquareBrace, brace)) => {
+                    iter.next();
+
+                    let raw_size = expression::parse(iter, vars)?;
+                    let size = const_eval::evaluate(raw_size)?;
+                    vars.check_array(brace, &name, &d_type, size);
+
+                    match iter.next() {
+                        Some((Token::CloseSquareBrace, _)) => {}
+                        _ => return None,
+                    };
+
+                    ir::DataType::Array(Box::new(d_type.clone()), size)
+                }
+                _ => d_type.clone(),
+            };
+            if !duplicate {
+                result.push((name, member_type));
+            }
+
+            match iter.next() {
+                Some((Token::Comma, _)) => {}
+                Some((Token::Semicolon, _)) => break,
+                _ => return None,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::Value, test_token_pair};
+
+    #[test]
+    fn struct_definition() {
+        let tokens = &[
+            test_token_pair!(Token::Keyword(Keyword::Struct)),
+            test_token_pair!(Token::Identifier("point".to_string())),
+            test_token_pair!(Token::OpenCurlyBrace),
+            test_token_pair!(Token::Keyword(Keyword::Integer)),
+            test_token_pair!(Token::Identifier("x".to_string())),
+            test_token_pair!(Token::Comma),
+            test_token_pair!(Token::Identifier("y".to_string())),
+            test_token_pair!(Token::Semicolon),
+            test_token_pair!(Token::Keyword(Keyword::Short)),
+            test_token_pair!(Token::Identifier("tags".to_string())),
+            test_token_pair!(Token::OpenSquareBrace),
+            test_token_pair!(Token::Constant(Value::Integer(3))),
+            test_token_pair!(Token::CloseSquareBrace),
+            test_token_pair!(Token::Semicolon),
+            test_token_pair!(Token::CloseCurlyBrace),
+        ];
+
+        let expected = ir::DataType::Struct(
+            "point".to_string(),
+            vec![
+                ("x".to_string(), ir::DataType::I32),
+                ("y".to_string(), ir::DataType::I32),
+                (
+                    "tags".to_string(),
+                    ir::DataType::Array(Box::new(ir::DataType::I16), 3),
+                ),
+            ],
+        );
+
+        let mut vars = Variables::new();
+        assert_eq!(
+            Some(expected.clone()),
+            parse(&mut tokens.iter().peekable(), &mut vars)
+        );
+        assert_eq!(Some(&expected), vars.get_type("struct point"));
+    }
+
+    #[test]
+    fn union_reference() {
+        let tokens = &[
+            test_token_pair!(Token::Keyword(Keyword::Union)),
+            test_token_pair!(Token::Identifier("value".to_string())),
+            test_token_pair!(Token::Identifier("test".to_string())),
+        ];
+
+        let expected = ir::DataType::Union(
+            "value".to_string(),
+            vec![
+                ("i".to_string(), ir::DataType::I32),
+                ("s".to_string(), ir::DataType::I16),
+            ],
+        );
+
+        let mut vars = Variables::new();
+        vars.insert_type("union value".to_string(), expected.clone());
+
+        let mut iter = tokens.iter().peekable();
+        assert_eq!(Some(expected), parse(&mut iter, &mut vars));
+        assert_eq!(
+            Some(&test_token_pair!(Token::Identifier("test".to_string()))),
+            iter.next()
+        );
+    }
+
+    #[test]
+    fn unknown_struct() {
+        let tokens = &[
+            test_token_pair!(Token::Keyword(Keyword::Struct)),
+            test_token_pair!(Token::Identifier("point".to_string())),
+            test_token_pair!(Token::Identifier("test".to_string())),
+        ];
+
+        assert_eq!(
+            None,
+            parse(&mut tokens.iter().peekable(), &mut Variables::new())
+        );
+    }
+}
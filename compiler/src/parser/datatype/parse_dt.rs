@@ -5,7 +5,7 @@ use crate::{
     lexer::{Keyword, Token, TokenMetadata},
 };
 
-/// Parses the next Datatype
+/// Parses the next basic Datatype, without any Pointers
 ///
 /// Params:
 /// `unsigned`: Whether or not the unsigend modifier was applied
@@ -17,21 +17,16 @@ where
         Some((Token::Keyword(tmp), _)) => {
             iter.next();
 
-            let raw = match tmp {
-                Keyword::Integer if !unsigned => ir::DataType::I32,
-                Keyword::Integer if unsigned => ir::DataType::U32,
-                Keyword::Short if !unsigned => ir::DataType::I16,
-                Keyword::Short if unsigned => ir::DataType::U16,
-                Keyword::Void => ir::DataType::Void,
-                _ => return None,
-            };
-
-            match iter.peek() {
-                Some((Token::Asterisk, _)) => {
-                    iter.next();
-                    Some(ir::DataType::Ptr(Box::new(raw)))
-                }
-                _ => Some(raw),
+            match tmp {
+                Keyword::Integer if !unsigned => Some(ir::DataType::I32),
+                Keyword::Integer if unsigned => Some(ir::DataType::U32),
+                Keyword::Short if !unsigned => Some(ir::DataType::I16),
+                Keyword::Short if unsigned => Some(ir::DataType::U16),
+                // A plain char is signed on the Device
+                Keyword::Char if !unsigned => Some(ir::DataType::I8),
+                Keyword::Char if unsigned => Some(ir::DataType::U8),
+                Keyword::Void => Some(ir::DataType::Void),
+                _ => None,
             }
         }
         _ => None,
@@ -5,8 +5,9 @@ use crate::{
     lexer::{Token, TokenMetadata},
 };
 
-use super::statements::Variables;
+use super::{comparison, condition, statements::Variables};
 
+pub mod postfix;
 mod single;
 
 /// Parses the Token-Stream into a single Expression that may
@@ -20,7 +21,7 @@ mod single;
 /// # use compiler::ir::{Variable, DataType};
 /// # let mut variables = Variables::new();
 /// # variables.insert("test".to_owned(), Variable::new_str("test", DataType::U32));
-/// # let empty_metadata = TokenMetadata { file_name: "test".to_owned(), line: 1, };
+/// # let empty_metadata = TokenMetadata { file_name: "test".to_owned(), line: 1, column: 1, };
 /// let tokens = &[
 ///     (Token::Identifier("test".to_owned()), empty_metadata.clone()),
 ///     (Token::Semicolon, empty_metadata.clone()),
@@ -34,6 +35,113 @@ mod single;
 /// assert_eq!(Some(&(Token::Semicolon, empty_metadata)), iter.next());
 /// ```
 pub fn parse<'a, I>(iter: &mut Peekable<I>, vars: &Variables) -> Option<ir::Expression>
+where
+    I: Iterator<Item = &'a (Token, TokenMetadata)>,
+{
+    parse_binary(iter, vars, 0)
+}
+
+/// The different kinds of binary Operators
+enum Operator {
+    /// A mathematical Operation
+    Arithmetic(ir::OP),
+    /// A Comparison of both Sides
+    Compare(ir::Comparison),
+    /// The logical And `&&`
+    And,
+    /// The logical Or `||`
+    Or,
+}
+
+/// Returns the Operator and its Precedence for the given Token, if the
+/// Token is a binary Operator, higher Precedences bind stronger
+fn binary_operator(token: &Token) -> Option<(Operator, u8)> {
+    match token {
+        Token::LogicalOr => Some((Operator::Or, 1)),
+        Token::LogicalAnd => Some((Operator::And, 2)),
+        Token::Pipe => Some((Operator::Arithmetic(ir::OP::BitOr), 3)),
+        Token::Caret => Some((Operator::Arithmetic(ir::OP::BitXor), 4)),
+        Token::And => Some((Operator::Arithmetic(ir::OP::BitAnd), 5)),
+        Token::DoubleEquals | Token::NotEquals => {
+            Some((Operator::Compare(comparison::from_token(token)?), 6))
+        }
+        Token::LessThan | Token::LessEquals | Token::GreaterThan | Token::GreaterEquals => {
+            Some((Operator::Compare(comparison::from_token(token)?), 7))
+        }
+        Token::ShiftLeft => Some((Operator::Arithmetic(ir::OP::ShiftLeft), 8)),
+        Token::ShiftRight => Some((Operator::Arithmetic(ir::OP::ShiftRight), 8)),
+        Token::Plus => Some((Operator::Arithmetic(ir::OP::Add), 9)),
+        Token::Minus => Some((Operator::Arithmetic(ir::OP::Substract), 9)),
+        Token::Asterisk => Some((Operator::Arithmetic(ir::OP::Multiply), 10)),
+        Token::Slash => Some((Operator::Arithmetic(ir::OP::Divide), 10)),
+        Token::Percent => Some((Operator::Arithmetic(ir::OP::Modulo), 10)),
+        _ => None,
+    }
+}
+
+/// Returns the Operation of a compound Assignment, like `+=`, if the
+/// Token is one
+pub fn compound_operator(token: &Token) -> Option<ir::OP> {
+    match token {
+        Token::PlusEquals => Some(ir::OP::Add),
+        Token::MinusEquals => Some(ir::OP::Substract),
+        Token::AsteriskEquals => Some(ir::OP::Multiply),
+        Token::SlashEquals => Some(ir::OP::Divide),
+        Token::PercentEquals => Some(ir::OP::Modulo),
+        Token::AndEquals => Some(ir::OP::BitAnd),
+        Token::PipeEquals => Some(ir::OP::BitOr),
+        Token::CaretEquals => Some(ir::OP::BitXor),
+        Token::ShiftLeftEquals => Some(ir::OP::ShiftLeft),
+        Token::ShiftRightEquals => Some(ir::OP::ShiftRight),
+        _ => None,
+    }
+}
+
+/// Parses a chain of binary Operations, which only contains Operators
+/// with a Precedence of at least `min_precedence`. All the Operators
+/// are left-associative, so `a - b - c` is parsed as `(a - b) - c`
+fn parse_binary<'a, I>(
+    iter: &mut Peekable<I>,
+    vars: &Variables,
+    min_precedence: u8,
+) -> Option<ir::Expression>
+where
+    I: Iterator<Item = &'a (Token, TokenMetadata)>,
+{
+    let mut left_side = parse_unary(iter, vars)?;
+
+    while let Some((op, precedence)) = iter.peek().and_then(|(tok, _)| binary_operator(tok)) {
+        if precedence < min_precedence {
+            break;
+        }
+        let (_, metadata) = iter.next()?;
+
+        let right_side = parse_binary(iter, vars, precedence + 1)?;
+        left_side = match op {
+            Operator::Arithmetic(op) => {
+                vars.check_operation(metadata, &op, &left_side, &right_side);
+                ir::Expression::Operation(op, vec![left_side, right_side])
+            }
+            Operator::Compare(comp) => ir::Expression::Condition(Box::new(ir::Condition::Compare(
+                comp, left_side, right_side,
+            ))),
+            Operator::And => ir::Expression::Condition(Box::new(ir::Condition::And(
+                Box::new(condition::from_expression(left_side)),
+                Box::new(condition::from_expression(right_side)),
+            ))),
+            Operator::Or => ir::Expression::Condition(Box::new(ir::Condition::Or(
+                Box::new(condition::from_expression(left_side)),
+                Box::new(condition::from_expression(right_side)),
+            ))),
+        };
+    }
+
+    Some(left_side)
+}
+
+/// Parses a single Operand of a binary Operation, including all the
+/// prefix Operators that are applied to it
+fn parse_unary<'a, I>(iter: &mut Peekable<I>, vars: &Variables) -> Option<ir::Expression>
 where
     I: Iterator<Item = &'a (Token, TokenMetadata)>,
 {
@@ -49,71 +157,70 @@ where
 
             inner
         }
-        Some((Token::Constant(_), _)) | Some((Token::Identifier(_), _)) => {
-            let left_side = single::parse_single(iter, vars)?;
-
-            match iter.peek() {
-                Some((Token::Plus, _))
-                | Some((Token::Minus, _))
-                | Some((Token::Asterisk, _))
-                | Some((Token::Slash, _)) => {
-                    let operation = match iter.next() {
-                        Some((Token::Plus, _)) => ir::OP::Add,
-                        Some((Token::Minus, _)) => ir::OP::Substract,
-                        Some((Token::Asterisk, _)) => ir::OP::Multiply,
-                        Some((Token::Slash, _)) => ir::OP::Divide,
-                        _ => return None,
-                    };
-
-                    let right_side = match parse(iter, vars) {
-                        Some(r) => r,
-                        None => return None,
-                    };
-
-                    match (&operation, right_side) {
-                        (ir::OP::Multiply, ir::Expression::Operation(other_op, mut additions))
-                        | (ir::OP::Divide, ir::Expression::Operation(other_op, mut additions)) => {
-                            let right_left = additions.remove(0);
-
-                            let exp =
-                                ir::Expression::Operation(operation, vec![left_side, right_left]);
-
-                            Some(ir::Expression::Operation(
-                                other_op,
-                                vec![exp, additions.remove(0)],
-                            ))
-                        }
-                        (_, right) => {
-                            Some(ir::Expression::Operation(operation, vec![left_side, right]))
-                        }
-                    }
-                }
-                _ => Some(left_side),
-            }
-        }
+        Some((Token::Constant(_), _))
+        | Some((Token::Identifier(_), _))
+        | Some((Token::StringLiteral(_), _)) => single::parse_single(iter, vars),
         Some((Token::And, _)) => {
             iter.next().unwrap();
 
-            let var_name = match iter.peek() {
-                Some((Token::Identifier(name), _)) => {
-                    iter.next();
-                    name.to_string()
-                }
+            let variable = match iter.next() {
+                Some((Token::Identifier(name), metadata)) => vars.resolve(metadata, name),
                 _ => return None,
             };
 
-            match vars.get(&var_name) {
-                Some(variable) => Some(ir::Expression::Reference(variable.clone())),
-                None => None,
-            }
+            let target = postfix::parse(iter, vars, ir::Expression::Variable(variable))?;
+
+            postfix::address(target)
         }
         Some((Token::Asterisk, _)) => {
-            iter.next().unwrap();
+            let (_, metadata) = iter.next().unwrap();
 
-            let inner = parse(iter, vars).unwrap();
+            let inner = parse_unary(iter, vars)?;
+            vars.check_dereference(metadata, &inner);
 
             Some(ir::Expression::Dereference(Box::new(inner)))
         }
+        Some((Token::Exclamation, _)) => {
+            iter.next().unwrap();
+
+            let inner = parse_unary(iter, vars)?;
+
+            Some(ir::Expression::Condition(Box::new(ir::Condition::Not(
+                Box::new(condition::from_expression(inner)),
+            ))))
+        }
+        Some((Token::Tilde, _)) => {
+            iter.next().unwrap();
+
+            let inner = parse_unary(iter, vars)?;
+
+            Some(ir::Expression::BitNot(Box::new(inner)))
+        }
+        Some((Token::Increment, _)) | Some((Token::Decrement, _)) => {
+            let amount = match iter.next() {
+                Some((Token::Increment, _)) => 1,
+                _ => -1,
+            };
+
+            let target = parse_unary(iter, vars)?;
+
+            Some(ir::Expression::Increment(
+                Box::new(postfix::address(target)?),
+                amount,
+                false,
+            ))
+        }
+        Some((Token::Minus, _)) => {
+            iter.next().unwrap();
+
+            // A Negation is simply treated as a Substraction from 0
+            let inner = parse_unary(iter, vars)?;
+
+            Some(ir::Expression::Operation(
+                ir::OP::Substract,
+                vec![ir::Expression::Constant(ir::Value::I32(0)), inner],
+            ))
+        }
         _ => None,
     }
 }
@@ -121,7 +228,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{ir::Variable, lexer::Value};
+    use crate::{ir::Variable, lexer::Value, test_token_pair};
 
     #[test]
     fn constant() {
@@ -130,6 +237,7 @@ mod tests {
             TokenMetadata {
                 file_name: "test".to_string(),
                 line: 1,
+                column: 1,
             },
         )];
 
@@ -147,6 +255,7 @@ mod tests {
             TokenMetadata {
                 file_name: "test".to_string(),
                 line: 1,
+                column: 1,
             },
         )];
 
@@ -158,6 +267,28 @@ mod tests {
 
         assert_eq!(expected, parse(&mut tokens.iter().peekable(), &vars));
     }
+    #[test]
+    fn joined_strings() {
+        let tokens = &[
+            test_token_pair!(Token::StringLiteral(b"ab".to_vec())),
+            test_token_pair!(Token::StringLiteral(b"c".to_vec())),
+            test_token_pair!(Token::OpenSquareBrace),
+            test_token_pair!(Token::Constant(Value::Integer(1))),
+            test_token_pair!(Token::CloseSquareBrace),
+        ];
+
+        let expected = Some(ir::Expression::Dereference(Box::new(
+            ir::Expression::Indexed(
+                Box::new(ir::Expression::StringLiteral(b"abc".to_vec())),
+                Box::new(ir::Expression::Constant(ir::Value::I32(1))),
+            ),
+        )));
+
+        assert_eq!(
+            expected,
+            parse(&mut tokens.iter().peekable(), &Variables::new())
+        );
+    }
 
     #[test]
     fn constant_plus_constant() {
@@ -167,6 +298,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -174,6 +306,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -181,6 +314,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
@@ -206,6 +340,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -213,6 +348,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -220,6 +356,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
@@ -248,6 +385,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -255,6 +393,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -262,6 +401,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
@@ -290,6 +430,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -297,6 +438,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -304,6 +446,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -311,6 +454,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -318,6 +462,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
@@ -354,6 +499,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -361,6 +507,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -368,6 +515,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
@@ -396,6 +544,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -403,6 +552,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -410,6 +560,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -417,6 +568,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -424,6 +576,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
@@ -453,6 +606,185 @@ mod tests {
         assert_eq!(expected, parse(&mut tokens.iter().peekable(), &vars));
     }
 
+    #[test]
+    fn substraction_left_associative() {
+        let tokens = &[
+            (
+                Token::Constant(Value::Integer(10)),
+                TokenMetadata {
+                    file_name: "test".to_string(),
+                    line: 1,
+                    column: 1,
+                },
+            ),
+            (
+                Token::Minus,
+                TokenMetadata {
+                    file_name: "test".to_string(),
+                    line: 1,
+                    column: 1,
+                },
+            ),
+            (
+                Token::Constant(Value::Integer(2)),
+                TokenMetadata {
+                    file_name: "test".to_string(),
+                    line: 1,
+                    column: 1,
+                },
+            ),
+            (
+                Token::Minus,
+                TokenMetadata {
+                    file_name: "test".to_string(),
+                    line: 1,
+                    column: 1,
+                },
+            ),
+            (
+                Token::Constant(Value::Integer(3)),
+                TokenMetadata {
+                    file_name: "test".to_string(),
+                    line: 1,
+                    column: 1,
+                },
+            ),
+        ];
+
+        let expected = Some(ir::Expression::Operation(
+            ir::OP::Substract,
+            vec![
+                ir::Expression::Operation(
+                    ir::OP::Substract,
+                    vec![
+                        ir::Expression::Constant(ir::Value::I32(10)),
+                        ir::Expression::Constant(ir::Value::I32(2)),
+                    ],
+                ),
+                ir::Expression::Constant(ir::Value::I32(3)),
+            ],
+        ));
+
+        assert_eq!(
+            expected,
+            parse(&mut tokens.iter().peekable(), &Variables::new())
+        );
+    }
+    #[test]
+    fn divide_parenthesized_substraction() {
+        let tokens = &[
+            (
+                Token::Constant(Value::Integer(12)),
+                TokenMetadata {
+                    file_name: "test".to_string(),
+                    line: 1,
+                    column: 1,
+                },
+            ),
+            (
+                Token::Slash,
+                TokenMetadata {
+                    file_name: "test".to_string(),
+                    line: 1,
+                    column: 1,
+                },
+            ),
+            (
+                Token::OpenParan,
+                TokenMetadata {
+                    file_name: "test".to_string(),
+                    line: 1,
+                    column: 1,
+                },
+            ),
+            (
+                Token::Constant(Value::Integer(5)),
+                TokenMetadata {
+                    file_name: "test".to_string(),
+                    line: 1,
+                    column: 1,
+                },
+            ),
+            (
+                Token::Minus,
+                TokenMetadata {
+                    file_name: "test".to_string(),
+                    line: 1,
+                    column: 1,
+                },
+            ),
+            (
+                Token::Constant(Value::Integer(2)),
+                TokenMetadata {
+                    file_name: "test".to_string(),
+                    line: 1,
+                    column: 1,
+                },
+            ),
+            (
+                Token::CloseParan,
+                TokenMetadata {
+                    file_name: "test".to_string(),
+                    line: 1,
+                    column: 1,
+                },
+            ),
+        ];
+
+        let expected = Some(ir::Expression::Operation(
+            ir::OP::Divide,
+            vec![
+                ir::Expression::Constant(ir::Value::I32(12)),
+                ir::Expression::Operation(
+                    ir::OP::Substract,
+                    vec![
+                        ir::Expression::Constant(ir::Value::I32(5)),
+                        ir::Expression::Constant(ir::Value::I32(2)),
+                    ],
+                ),
+            ],
+        ));
+
+        assert_eq!(
+            expected,
+            parse(&mut tokens.iter().peekable(), &Variables::new())
+        );
+    }
+    #[test]
+    fn negation() {
+        let tokens = &[
+            (
+                Token::Minus,
+                TokenMetadata {
+                    file_name: "test".to_string(),
+                    line: 1,
+                    column: 1,
+                },
+            ),
+            (
+                Token::Constant(Value::Integer(5)),
+                TokenMetadata {
+                    file_name: "test".to_string(),
+                    line: 1,
+                    column: 1,
+                },
+            ),
+        ];
+
+        let expected = Some(ir::Expression::Operation(
+            ir::OP::Substract,
+            vec![
+                ir::Expression::Constant(ir::Value::I32(0)),
+                ir::Expression::Constant(ir::Value::I32(5)),
+            ],
+        ));
+
+        assert_eq!(
+            expected,
+            parse(&mut tokens.iter().peekable(), &Variables::new())
+        );
+    }
+
     #[test]
     fn reference_to_variable() {
         let tokens = &[
@@ -461,6 +793,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -468,6 +801,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
@@ -488,6 +822,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -495,6 +830,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
@@ -518,6 +854,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -525,6 +862,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
@@ -546,6 +884,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -553,6 +892,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -560,6 +900,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -567,6 +908,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -574,6 +916,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -581,6 +924,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
@@ -600,4 +944,57 @@ mod tests {
             parse(&mut tokens.iter().peekable(), &Variables::new())
         );
     }
+    #[test]
+    fn bitwise_precedence() {
+        let tokens = &[
+            test_token_pair!(Token::Identifier("a".to_owned())),
+            test_token_pair!(Token::Pipe),
+            test_token_pair!(Token::Tilde),
+            test_token_pair!(Token::Identifier("a".to_owned())),
+            test_token_pair!(Token::And),
+            test_token_pair!(Token::Identifier("a".to_owned())),
+            test_token_pair!(Token::ShiftLeft),
+            test_token_pair!(Token::Constant(Value::Integer(1))),
+            test_token_pair!(Token::Plus),
+            test_token_pair!(Token::Identifier("a".to_owned())),
+            test_token_pair!(Token::Increment),
+        ];
+
+        let mut vars = Variables::new();
+        let var = Variable::new_str("a", ir::DataType::I32);
+        vars.insert(var.name.clone(), var.clone());
+
+        // a | ((~a) & (a << (1 + a++)))
+        let expected = Some(ir::Expression::Operation(
+            ir::OP::BitOr,
+            vec![
+                ir::Expression::Variable(var.clone()),
+                ir::Expression::Operation(
+                    ir::OP::BitAnd,
+                    vec![
+                        ir::Expression::BitNot(Box::new(ir::Expression::Variable(var.clone()))),
+                        ir::Expression::Operation(
+                            ir::OP::ShiftLeft,
+                            vec![
+                                ir::Expression::Variable(var.clone()),
+                                ir::Expression::Operation(
+                                    ir::OP::Add,
+                                    vec![
+                                        ir::Expression::Constant(ir::Value::I32(1)),
+                                        ir::Expression::Increment(
+                                            Box::new(ir::Expression::Reference(var)),
+                                            1,
+                                            true,
+                                        ),
+                                    ],
+                                ),
+                            ],
+                        ),
+                    ],
+                ),
+            ],
+        ));
+
+        assert_eq!(expected, parse(&mut tokens.iter().peekable(), &vars));
+    }
 }
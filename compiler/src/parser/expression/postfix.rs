@@ -0,0 +1,171 @@
+use std::iter::Peekable;
+
+use crate::{
+    diagnostic::Diagnostic,
+    ir,
+    lexer::{Token, TokenMetadata},
+    parser::statements::Variables,
+    semantics::types,
+};
+
+use super::parse as parse_expression;
+
+/// Parses all the Postfix-Operators, like Indexing `[]`, the
+/// Member-Accesses `.` and `->` or the Increments `++` and `--`, that
+/// are applied to the given Expression
+pub fn parse<'a, I>(
+    iter: &mut Peekable<I>,
+    vars: &Variables,
+    base: ir::Expression,
+) -> Option<ir::Expression>
+where
+    I: Iterator<Item = &'a (Token, TokenMetadata)>,
+{
+    let mut result = base;
+
+    loop {
+        result = match iter.peek() {
+            Some((Token::OpenSquareBrace, _)) => {
+                let (_, metadata) = iter.next()?;
+
+                let index = parse_expression(iter, vars)?;
+                vars.check_subscript(metadata, &result);
+
+                match iter.next() {
+                    Some((Token::CloseSquareBrace, _)) => {}
+                    _ => return None,
+                };
+
+                ir::Expression::Dereference(Box::new(ir::Expression::Indexed(
+                    Box::new(result),
+                    Box::new(index),
+                )))
+            }
+            Some((Token::Dot, _)) => {
+                iter.next();
+
+                let (metadata, member) = parse_member(iter)?;
+                vars.check_member(metadata, &types::exp_type(&result), &member);
+                ir::Expression::Dereference(Box::new(ir::Expression::Member(
+                    Box::new(address(result)?),
+                    member,
+                )))
+            }
+            Some((Token::Arrow, _)) => {
+                let (_, arrow) = iter.next()?;
+
+                let (metadata, member) = parse_member(iter)?;
+                match types::exp_type(&result) {
+                    ir::DataType::Ptr(target) => vars.check_member(metadata, &target, &member),
+                    other => vars.report(Diagnostic::error(
+                        arrow,
+                        format!("member reference type `{}` is not a pointer", other),
+                    )),
+                };
+                ir::Expression::Dereference(Box::new(ir::Expression::Member(
+                    Box::new(result),
+                    member,
+                )))
+            }
+            Some((Token::Increment, _)) | Some((Token::Decrement, _)) => {
+                let amount = match iter.next() {
+                    Some((Token::Increment, _)) => 1,
+                    _ => -1,
+                };
+
+                ir::Expression::Increment(Box::new(address(result)?), amount, true)
+            }
+            _ => return Some(result),
+        };
+    }
+}
+
+/// Parses the Name of the accessed Member, together with its Location
+fn parse_member<'a, I>(iter: &mut Peekable<I>) -> Option<(&'a TokenMetadata, String)>
+where
+    I: Iterator<Item = &'a (Token, TokenMetadata)>,
+{
+    match iter.next() {
+        Some((Token::Identifier(name), metadata)) => Some((metadata, name.to_owned())),
+        _ => None,
+    }
+}
+
+/// Converts an Expression that refers to some Location in Memory, like a
+/// Variable or a Dereference, into the Expression for the Address of that
+/// Location. Returns None if the Expression does not refer to any Location
+pub fn address(exp: ir::Expression) -> Option<ir::Expression> {
+    match exp {
+        ir::Expression::Variable(var) => Some(ir::Expression::Reference(var)),
+        ir::Expression::Dereference(inner) => Some(*inner),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ir::Variable, test_token_pair};
+
+    #[test]
+    fn struct_member() {
+        let tokens = &[
+            test_token_pair!(Token::Dot),
+            test_token_pair!(Token::Identifier("x".to_string())),
+        ];
+
+        let point = ir::DataType::Struct(
+            "point".to_string(),
+            vec![("x".to_string(), ir::DataType::I32)],
+        );
+        let var = Variable::new_str("test", point);
+
+        let expected = ir::Expression::Dereference(Box::new(ir::Expression::Member(
+            Box::new(ir::Expression::Reference(var.clone())),
+            "x".to_string(),
+        )));
+
+        assert_eq!(
+            Some(expected),
+            parse(
+                &mut tokens.iter().peekable(),
+                &Variables::new(),
+                ir::Expression::Variable(var)
+            )
+        );
+    }
+
+    #[test]
+    fn nested_pointer_member() {
+        let tokens = &[
+            test_token_pair!(Token::Arrow),
+            test_token_pair!(Token::Identifier("pos".to_string())),
+            test_token_pair!(Token::Dot),
+            test_token_pair!(Token::Identifier("x".to_string())),
+        ];
+
+        let point = ir::DataType::Struct(
+            "point".to_string(),
+            vec![("x".to_string(), ir::DataType::I32)],
+        );
+        let sprite = ir::DataType::Struct("sprite".to_string(), vec![("pos".to_string(), point)]);
+        let var = Variable::new_str("test", ir::DataType::Ptr(Box::new(sprite)));
+
+        let expected = ir::Expression::Dereference(Box::new(ir::Expression::Member(
+            Box::new(ir::Expression::Member(
+                Box::new(ir::Expression::Variable(var.clone())),
+                "pos".to_string(),
+            )),
+            "x".to_string(),
+        )));
+
+        assert_eq!(
+            Some(expected),
+            parse(
+                &mut tokens.iter().peekable(),
+                &Variables::new(),
+                ir::Expression::Variable(var)
+            )
+        );
+    }
+}
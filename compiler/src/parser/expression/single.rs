@@ -6,7 +6,7 @@ use crate::{
     parser::{call_params, statements::Variables},
 };
 
-use super::parse;
+use super::postfix;
 
 /// Parses a single Expression, so only Constants and Variables
 pub fn parse_single<'a, I>(iter: &mut Peekable<I>, vars: &Variables) -> Option<ir::Expression>
@@ -21,41 +21,29 @@ where
                 Value::UInteger(value) => Some(ir::Expression::Constant(ir::Value::U32(*value))),
             }
         }
-        Some((Token::Identifier(name), _)) => {
+        Some((Token::StringLiteral(_), _)) => {
+            // Neighbouring String-Literals are joined into a single one
+            let mut content = Vec::new();
+            while let Some((Token::StringLiteral(part), _)) = iter.peek() {
+                iter.next();
+                content.extend_from_slice(part);
+            }
+
+            postfix::parse(iter, vars, ir::Expression::StringLiteral(content))
+        }
+        Some((Token::Identifier(name), metadata)) => {
             iter.next().unwrap();
             match iter.peek() {
                 Some((Token::OpenParan, _)) => {
                     iter.next();
 
                     let params = call_params::parse(iter, vars)?;
-                    Some(ir::Expression::Call(name.to_owned(), params))
+                    Some(vars.call(metadata, name, params))
                 }
-                Some((Token::OpenSquareBrace, _)) => {
-                    iter.next();
-
-                    let index = parse(iter, vars)?;
-
-                    match iter.next() {
-                        Some((Token::CloseSquareBrace, _)) => {}
-                        _ => return None,
-                    };
-
-                    let variable = match vars.get(name) {
-                        Some(v) => v.clone(),
-                        None => return None,
-                    };
-
-                    Some(ir::Expression::Dereference(Box::new(
-                        ir::Expression::Indexed(
-                            Box::new(ir::Expression::Variable(variable)),
-                            Box::new(index),
-                        ),
-                    )))
+                _ => {
+                    let variable = vars.resolve(metadata, name);
+                    postfix::parse(iter, vars, ir::Expression::Variable(variable))
                 }
-                _ => match vars.get(name) {
-                    Some(variable) => Some(ir::Expression::Variable(variable.clone())),
-                    None => None,
-                },
             }
         }
         _ => None,
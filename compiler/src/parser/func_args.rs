@@ -1,7 +1,8 @@
 use std::iter::Peekable;
 
-use super::datatype::parse as parse_datatype;
+use super::{datatype::parse as parse_datatype, statements::Variables};
 use crate::{
+    diagnostic::Diagnostic,
     ir,
     lexer::{Token, TokenMetadata},
 };
@@ -12,7 +13,8 @@ use crate::{
 /// ```rust
 /// # use compiler::lexer::{Token, TokenMetadata, Keyword};
 /// # use compiler::parser::func_args::parse;
-/// # let empty_metadata = TokenMetadata { file_name: "test".to_owned(), line: 1, };
+/// # use compiler::parser::statements::Variables;
+/// # let empty_metadata = TokenMetadata { file_name: "test".to_owned(), line: 1, column: 1, };
 /// let tokens = &[
 ///     (Token::Keyword(Keyword::Integer), empty_metadata.clone()),
 ///     (Token::Identifier("test".to_owned()), empty_metadata.clone()),
@@ -21,12 +23,15 @@ use crate::{
 ///
 /// // Parse the Tokens
 /// let mut iter = tokens.iter().peekable();
-/// parse(&mut iter);
+/// parse(&mut iter, &mut Variables::new());
 ///
 /// // Expects that the Closing-Paran has also been consumed
 /// assert_eq!(None, iter.next());
 /// ```
-pub fn parse<'a, I>(iter: &mut Peekable<I>) -> Option<Vec<(String, ir::DataType)>>
+pub fn parse<'a, I>(
+    iter: &mut Peekable<I>,
+    vars: &mut Variables,
+) -> Option<Vec<(String, ir::DataType)>>
 where
     I: Iterator<Item = &'a (Token, TokenMetadata)>,
 {
@@ -42,10 +47,17 @@ where
                 iter.next();
             }
             _ => {
-                let datatype = parse_datatype(iter)?;
+                let datatype = parse_datatype(iter, vars)?;
                 let name = match iter.peek() {
-                    Some((Token::Identifier(n), _)) => {
+                    Some((Token::Identifier(n), metadata)) => {
                         iter.next();
+
+                        if result.iter().any(|(other, _)| other == n) {
+                            vars.report(Diagnostic::error(
+                                metadata,
+                                format!("redefinition of parameter `{}`", n),
+                            ));
+                        }
                         n.to_owned()
                     }
                     _ => return None,
@@ -71,12 +83,16 @@ mod tests {
             TokenMetadata {
                 file_name: "test".to_string(),
                 line: 1,
+                column: 1,
             },
         )];
 
         let expected = Some(vec![]);
 
-        assert_eq!(expected, parse(&mut tokens.iter().peekable()));
+        assert_eq!(
+            expected,
+            parse(&mut tokens.iter().peekable(), &mut Variables::new())
+        );
     }
 
     #[test]
@@ -87,6 +103,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -94,6 +111,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -101,13 +119,17 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
 
         let expected = Some(vec![("test_param".to_string(), ir::DataType::I32)]);
 
-        assert_eq!(expected, parse(&mut tokens.iter().peekable()));
+        assert_eq!(
+            expected,
+            parse(&mut tokens.iter().peekable(), &mut Variables::new())
+        );
     }
 
     #[test]
@@ -118,6 +140,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -125,6 +148,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -132,6 +156,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -139,6 +164,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -146,6 +172,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -153,6 +180,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
@@ -162,6 +190,9 @@ mod tests {
             ("test_param_2".to_string(), ir::DataType::I32),
         ]);
 
-        assert_eq!(expected, parse(&mut tokens.iter().peekable()));
+        assert_eq!(
+            expected,
+            parse(&mut tokens.iter().peekable(), &mut Variables::new())
+        );
     }
 }
@@ -1,19 +1,33 @@
 use std::iter::Peekable;
 
 use crate::{
-    ir::{self, Variable},
-    lexer::{Token, TokenMetadata},
+    diagnostic::Diagnostic,
+    ir,
+    lexer::{Keyword, Token, TokenMetadata},
+    semantics::{self, Signature},
 };
 
-use super::{datatype, func_args, statements};
+use super::{datatype, func_args, global, statements};
 
-/// Parses the Token-Stream into a single Function defined in the Program
+/// Something that is defined at the Top-Level of the Program
+#[derive(Debug, PartialEq)]
+pub enum Definition {
+    /// A Function including its Body
+    Function(ir::Function),
+    /// A Type-Definition, like `struct point { int x; };`, or a global
+    /// Variable, which is only registered in the Variables
+    Declaration,
+}
+
+/// Parses the Token-Stream into a single Definition in the Program,
+/// returns None if the Tokens do not form a valid Definition
 ///
 /// # Example
 /// ```rust
 /// # use compiler::lexer::{Token, TokenMetadata, Keyword};
 /// # use compiler::parser::function::parse;
-/// # let empty_metadata = TokenMetadata { file_name: "test".to_owned(), line: 1, };
+/// # use compiler::parser::statements::Variables;
+/// # let empty_metadata = TokenMetadata { file_name: "test".to_owned(), line: 1, column: 1, };
 /// let tokens = &[
 ///     (Token::Keyword(Keyword::Void), empty_metadata.clone()),
 ///     (Token::Identifier("test".to_owned()), empty_metadata.clone()),
@@ -25,64 +39,164 @@ use super::{datatype, func_args, statements};
 ///
 /// // Parse the Tokens
 /// let mut iter = tokens.iter().peekable();
-/// parse(&mut iter);
+/// assert!(parse(&mut iter, &mut Variables::new()).is_some());
 ///
 /// // Expect
 /// assert_eq!(None, iter.next());
 /// ```
-pub fn parse<'a, I>(iter: &mut Peekable<I>) -> Option<ir::Function>
+pub fn parse<'a, I>(
+    iter: &mut Peekable<I>,
+    globals: &mut statements::Variables,
+) -> Option<Definition>
 where
     I: Iterator<Item = &'a (Token, TokenMetadata)>,
 {
-    let dt = datatype::parse(iter)?;
+    // The Attributes can be given in any Order, the Calling-Convention is
+    // only set if it is given explicitly
+    let mut attributes = ir::Attributes::default();
+    let mut convention = None;
+    loop {
+        match iter.peek() {
+            Some((Token::Keyword(Keyword::Static), _)) => attributes.local = true,
+            Some((Token::Keyword(Keyword::Inline), _)) => attributes.inline = true,
+            Some((Token::Keyword(Keyword::Attribute), _)) => {
+                iter.next();
+                attribute(iter, globals, &mut attributes, &mut convention)?;
+                continue;
+            }
+            _ => break,
+        };
+        iter.next();
+    }
 
-    let name = match iter.next() {
-        Some((Token::Identifier(n), _)) => n.to_owned(),
-        Some((_, metadata)) => {
-            println!("Expected Identifier: {:?}", metadata);
-            return None;
-        }
-        None => return None,
+    let dt = datatype::parse(iter, globals)?;
+
+    if let Some((Token::Semicolon, _)) = iter.peek() {
+        iter.next();
+        return Some(Definition::Declaration);
+    }
+
+    let (name, name_metadata) = match iter.next() {
+        Some((Token::Identifier(n), metadata)) => (n.to_owned(), metadata),
+        _ => return None,
     };
 
-    match iter.next() {
-        Some((Token::OpenParan, _)) => {}
-        Some((_, metadata)) => {
-            println!("Expected Open-Paranthese: {:?}", metadata);
-            return None;
+    match iter.peek() {
+        Some((Token::OpenParan, _)) => {
+            iter.next();
+        }
+        Some(_) => {
+            globals.declare_name(name_metadata, &name);
+            global::parse(iter, globals, dt, name.clone(), name)?;
+            return Some(Definition::Declaration);
         }
         None => return None,
     };
 
-    let args = func_args::parse(iter)?;
+    let args = func_args::parse(iter, globals)?;
+    let signature = Signature {
+        returns: dt.clone(),
+        arguments: args.iter().map(|(_, ty)| ty.clone()).collect(),
+        convention,
+    };
+    for diagnostic in semantics::signature(name_metadata, &name, &signature) {
+        globals.report(diagnostic);
+    }
 
     match iter.next() {
         Some((Token::OpenCurlyBrace, _)) => {}
-        Some((_, metadata)) => {
-            println!("Expected Open-Curly-Brace: {:?}", metadata);
-            return None;
+        // Only the Signature is declared, like `int add(int a, int b);`
+        Some((Token::Semicolon, _)) => {
+            globals.declare_function(name_metadata, &name, signature, false);
+            return Some(Definition::Declaration);
         }
-        None => return None,
+        _ => return None,
     };
 
-    let mut vars = statements::Variables::new();
-    for (arg_name, arg_ty) in args.iter() {
-        let name = arg_name.to_owned();
-        let ty = arg_ty.clone();
-
-        vars.insert(name.clone(), Variable { name, ty });
-    }
+    // The Function is declared before its Body, so it can call itself
+    attributes.convention = globals.declare_function(name_metadata, &name, signature, true);
 
+    let mut vars = globals.function_scope(dt.clone(), &args);
     let statements = statements::parse(iter, &mut vars);
 
-    match iter.next() {
-        Some((Token::CloseCurlyBrace, _)) => {}
-        Some((_, metadata)) => {
-            println!("Expected Closing-Curly-Brace: {:?}", metadata);
-            return None;
-        }
-        None => return None,
+    // The static Variables declared in the Function belong to the Program
+    let known = globals.globals().len();
+    for global in vars.take_globals().into_iter().skip(known) {
+        globals.add_global(global);
+    }
+
+    let end_metadata = match iter.next() {
+        Some((Token::CloseCurlyBrace, metadata)) => metadata,
+        _ => return None,
     };
 
-    Some(ir::Function(name, dt, args, statements))
+    if dt != ir::DataType::Void && !semantics::returns(&statements) {
+        globals.report(Diagnostic::warning(
+            end_metadata,
+            format!(
+                "non-void function `{}` does not return a value in all control paths",
+                name
+            ),
+        ));
+    }
+
+    Some(Definition::Function(ir::Function(
+        name, dt, args, statements, attributes,
+    )))
+}
+
+/// Parses the comma separated List of Attributes in the Form
+/// `((first, second))`, which follows `__attribute__`, unknown Attributes
+/// are ignored
+fn attribute<'a, I>(
+    iter: &mut Peekable<I>,
+    globals: &statements::Variables,
+    attributes: &mut ir::Attributes,
+    convention: &mut Option<ir::CallingConvention>,
+) -> Option<()>
+where
+    I: Iterator<Item = &'a (Token, TokenMetadata)>,
+{
+    for _ in 0..2 {
+        match iter.next() {
+            Some((Token::OpenParan, _)) => {}
+            _ => return None,
+        };
+    }
+
+    loop {
+        match iter.next() {
+            Some((Token::Identifier(name), _)) if name == "stackcall" => {
+                *convention = Some(ir::CallingConvention::Stack);
+            }
+            Some((Token::Identifier(name), _)) if name == "renesas" => {
+                *convention = Some(ir::CallingConvention::Renesas);
+            }
+            Some((Token::Identifier(name), _)) if name == "noinline" => {
+                attributes.noinline = true;
+            }
+            Some((Token::Identifier(name), metadata)) => {
+                globals.report(Diagnostic::warning(
+                    metadata,
+                    format!("unknown attribute `{}` ignored", name),
+                ));
+            }
+            _ => return None,
+        };
+
+        match iter.peek() {
+            Some((Token::Comma, _)) => {
+                iter.next();
+            }
+            _ => break,
+        };
+    }
+
+    for _ in 0..2 {
+        match iter.next() {
+            Some((Token::CloseParan, _)) => {}
+            _ => return None,
+        };
+    }
+    Some(())
 }
@@ -0,0 +1,364 @@
+use std::iter::Peekable;
+
+use crate::{
+    const_eval,
+    diagnostic::Diagnostic,
+    ir,
+    lexer::{Token, TokenMetadata},
+};
+
+use super::{expression, statements::Variables};
+
+/// Parses the Rest of the Declaration of a Variable with static Storage,
+/// following its Datatype and Name, including the optional constant
+/// Initializer and the ending Semicolon.
+///
+/// The Variable is visible under its Name, but uses the Label as its
+/// actual Name in the IR, which has to be unique in the Program
+///
+/// # Example:
+/// ```rust
+/// # use compiler::lexer::{Token, TokenMetadata, Value};
+/// # use compiler::parser::global::parse;
+/// # use compiler::parser::statements::Variables;
+/// # use compiler::ir::{DataType, Initializer};
+/// # let empty_metadata = TokenMetadata { file_name: "test".to_owned(), line: 1, column: 1, };
+/// let tokens = &[
+///     (Token::Equals, empty_metadata.clone()),
+///     (Token::Constant(Value::Integer(3)), empty_metadata.clone()),
+///     (Token::Semicolon, empty_metadata.clone()),
+/// ];
+///
+/// // Parse the Tokens
+/// let mut vars = Variables::new();
+/// let mut iter = tokens.iter().peekable();
+/// parse(&mut iter, &mut vars, DataType::I32, "test".to_owned(), "test".to_owned());
+///
+/// // Expect the Global to be registered with its initial Value
+/// assert_eq!(vec![Initializer::Value(3)], vars.globals()[0].values);
+/// assert_eq!(None, iter.next());
+/// ```
+pub fn parse<'a, I>(
+    iter: &mut Peekable<I>,
+    vars: &mut Variables,
+    d_type: ir::DataType,
+    name: String,
+    label: String,
+) -> Option<()>
+where
+    I: Iterator<Item = &'a (Token, TokenMetadata)>,
+{
+    // The Size of an Array can be left out, in which case it is taken from
+    // the Initializer
+    let mut without_size = None;
+    let mut ty = match iter.peek() {
+        Some((Token::OpenSquareBrace, metadata)) => {
+            iter.next();
+
+            let size = match iter.peek() {
+                Some((Token::CloseSquareBrace, _)) => {
+                    without_size = Some(metadata);
+                    u32::MAX
+                }
+                _ => {
+                    let raw_size = expression::parse(iter, vars)?;
+                    let size = const_eval::evaluate(raw_size)?;
+                    vars.check_array(metadata, &name, &d_type, size);
+                    size
+                }
+            };
+
+            match iter.next() {
+                Some((Token::CloseSquareBrace, _)) => {}
+                _ => return None,
+            };
+
+            ir::DataType::Array(Box::new(d_type), size)
+        }
+        _ => d_type,
+    };
+
+    let values = match iter.peek() {
+        Some((Token::Equals, _)) => {
+            iter.next();
+            parse_initializer(iter, vars, &ty, &label)?
+        }
+        _ => {
+            if let Some(metadata) = without_size {
+                vars.report(Diagnostic::error(
+                    metadata,
+                    format!("array size missing in `{}`", name),
+                ));
+            }
+            Vec::new()
+        }
+    };
+
+    if let ir::DataType::Array(_, size) = &mut ty {
+        if without_size.is_some() {
+            *size = values.len() as u32;
+        }
+    }
+
+    match iter.next() {
+        Some((Token::Semicolon, _)) => {}
+        _ => return None,
+    };
+
+    let var = ir::Variable { name: label, ty };
+    vars.insert(name, var.clone());
+    vars.add_global(ir::Global { var, values });
+
+    Some(())
+}
+
+/// Parses the constant Initializer for a Variable of the given Type, which
+/// is either a single Value or a List of Values in Curly-Braces for Arrays.
+/// Initializers that are not supported are reported and skipped
+fn parse_initializer<'a, I>(
+    iter: &mut Peekable<I>,
+    vars: &mut Variables,
+    ty: &ir::DataType,
+    label: &str,
+) -> Option<Vec<ir::Initializer>>
+where
+    I: Iterator<Item = &'a (Token, TokenMetadata)>,
+{
+    let (_, start) = iter.peek()?;
+    let count = match ty {
+        ir::DataType::Array(inner, count)
+            if matches!(**inner, ir::DataType::I8 | ir::DataType::U8) =>
+        {
+            if let Some((Token::StringLiteral(_), _)) = iter.peek() {
+                let mut result: Vec<ir::Initializer> = Vec::new();
+                while let Some((Token::StringLiteral(part), _)) = iter.peek() {
+                    iter.next();
+                    result.extend(part.iter().map(|byte| ir::Initializer::Value(*byte as u32)));
+                }
+
+                if result.len() > *count as usize {
+                    vars.report(Diagnostic::error(
+                        start,
+                        format!("initializer-string for array of size {} is too long", count),
+                    ));
+                    result.truncate(*count as usize);
+                }
+                // The 0-Terminator is only dropped if it does not fit
+                if result.len() < *count as usize {
+                    result.push(ir::Initializer::Value(0));
+                }
+                return Some(result);
+            }
+            *count
+        }
+        ir::DataType::Array(inner, count) => match **inner {
+            ir::DataType::Array(_, _) | ir::DataType::Struct(_, _) | ir::DataType::Union(_, _) => {
+                vars.report(Diagnostic::error(
+                    start,
+                    "initializers are only supported for arrays of simple values",
+                ));
+                return skip_initializer(iter);
+            }
+            _ => *count,
+        },
+        ir::DataType::Struct(_, _) | ir::DataType::Union(_, _) => {
+            vars.report(Diagnostic::error(
+                start,
+                "initializers for structs and unions are not supported",
+            ));
+            return skip_initializer(iter);
+        }
+        _ => {
+            let exp = expression::parse(iter, vars)?;
+            return Some(vec![initial_value(vars, start, exp, label)]);
+        }
+    };
+
+    match iter.next() {
+        Some((Token::OpenCurlyBrace, _)) => {}
+        _ => return None,
+    };
+
+    let mut result = Vec::new();
+    loop {
+        match iter.peek() {
+            Some((Token::CloseCurlyBrace, _)) => {
+                iter.next();
+                break;
+            }
+            Some((Token::Comma, _)) => {
+                iter.next();
+            }
+            Some((_, metadata)) => {
+                let exp = expression::parse(iter, vars)?;
+                result.push(initial_value(vars, metadata, exp, label));
+            }
+            None => return None,
+        };
+    }
+
+    if result.len() > count as usize {
+        vars.report(Diagnostic::error(
+            start,
+            format!("excess elements in initializer for array of size {}", count),
+        ));
+        result.truncate(count as usize);
+    }
+
+    Some(result)
+}
+
+/// Evaluates a single Value of an Initializer, which is either a constant
+/// Expression or the Address of another Global. String-Literals are placed
+/// in a Global of their own, whose Label is derived from the given one.
+/// Any other Expression is reported and replaced with 0
+fn initial_value(
+    vars: &mut Variables,
+    metadata: &TokenMetadata,
+    exp: ir::Expression,
+    label: &str,
+) -> ir::Initializer {
+    if let Some(value) = const_eval::evaluate(exp.clone()) {
+        return ir::Initializer::Value(value);
+    }
+
+    let address = match &exp {
+        ir::Expression::Reference(var) => Some(var),
+        // Arrays are used by the Address of their first Element
+        ir::Expression::Variable(var) if matches!(var.ty, ir::DataType::Array(_, _)) => Some(var),
+        _ => None,
+    };
+    if let Some(var) = address.filter(|var| vars.is_global(&var.name)) {
+        return ir::Initializer::Address(var.name.clone());
+    }
+
+    if let ir::Expression::StringLiteral(mut content) = exp {
+        let name = format!("__STRING_{}_{}", label, vars.globals().len());
+        content.push(0);
+        let ty = ir::DataType::Array(Box::new(ir::DataType::I8), content.len() as u32);
+        vars.add_global(ir::Global {
+            var: ir::Variable {
+                name: name.clone(),
+                ty,
+            },
+            values: content
+                .into_iter()
+                .map(|byte| ir::Initializer::Value(byte as u32))
+                .collect(),
+        });
+        return ir::Initializer::Address(name);
+    }
+
+    vars.report(Diagnostic::error(
+        metadata,
+        "initializer element is not constant",
+    ));
+    ir::Initializer::Value(0)
+}
+
+/// Skips over an Initializer in Curly-Braces, including the nested ones,
+/// so the Parsing can continue after it
+fn skip_initializer<'a, I>(iter: &mut Peekable<I>) -> Option<Vec<ir::Initializer>>
+where
+    I: Iterator<Item = &'a (Token, TokenMetadata)>,
+{
+    let mut depth = 0;
+    loop {
+        match iter.peek()? {
+            (Token::OpenCurlyBrace, _) => depth += 1,
+            (Token::CloseCurlyBrace, _) => depth -= 1,
+            (Token::Semicolon, _) | (Token::Comma, _) if depth == 0 => break,
+            _ => {}
+        };
+        iter.next();
+    }
+    Some(Vec::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::Value, test_token_pair};
+
+    #[test]
+    fn array_initializer() {
+        let tokens = &[
+            test_token_pair!(Token::OpenSquareBrace),
+            test_token_pair!(Token::Constant(Value::Integer(3))),
+            test_token_pair!(Token::CloseSquareBrace),
+            test_token_pair!(Token::Equals),
+            test_token_pair!(Token::OpenCurlyBrace),
+            test_token_pair!(Token::Constant(Value::Integer(1))),
+            test_token_pair!(Token::Comma),
+            test_token_pair!(Token::Minus),
+            test_token_pair!(Token::Constant(Value::Integer(2))),
+            test_token_pair!(Token::CloseCurlyBrace),
+            test_token_pair!(Token::Semicolon),
+        ];
+
+        let mut vars = Variables::new();
+        assert_eq!(
+            Some(()),
+            parse(
+                &mut tokens.iter().peekable(),
+                &mut vars,
+                ir::DataType::I16,
+                "table".to_owned(),
+                "table.0".to_owned()
+            )
+        );
+
+        let expected_var = ir::Variable::new_str(
+            "table.0",
+            ir::DataType::Array(Box::new(ir::DataType::I16), 3),
+        );
+        assert_eq!(Some(&expected_var), vars.get("table"));
+        assert_eq!(
+            &[ir::Global {
+                var: expected_var,
+                values: vec![
+                    ir::Initializer::Value(1),
+                    ir::Initializer::Value(-2i32 as u32)
+                ],
+            }],
+            vars.globals()
+        );
+    }
+
+    #[test]
+    fn too_many_values() {
+        let tokens = &[
+            test_token_pair!(Token::OpenSquareBrace),
+            test_token_pair!(Token::Constant(Value::Integer(1))),
+            test_token_pair!(Token::CloseSquareBrace),
+            test_token_pair!(Token::Equals),
+            test_token_pair!(Token::OpenCurlyBrace),
+            test_token_pair!(Token::Constant(Value::Integer(1))),
+            test_token_pair!(Token::Comma),
+            test_token_pair!(Token::Constant(Value::Integer(2))),
+            test_token_pair!(Token::CloseCurlyBrace),
+            test_token_pair!(Token::Semicolon),
+        ];
+
+        let mut vars = Variables::new();
+        assert_eq!(
+            Some(()),
+            parse(
+                &mut tokens.iter().peekable(),
+                &mut vars,
+                ir::DataType::I32,
+                "table".to_owned(),
+                "table".to_owned()
+            )
+        );
+
+        // The Error is reported and the extra Values are dropped
+        let diagnostics = vars.take_diagnostics();
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(
+            "excess elements in initializer for array of size 1",
+            diagnostics[0].message
+        );
+        assert_eq!(vec![ir::Initializer::Value(1)], vars.globals()[0].values);
+    }
+}
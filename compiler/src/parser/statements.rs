@@ -1,14 +1,376 @@
-use std::{collections::HashMap, iter::Peekable};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    iter::Peekable,
+    rc::Rc,
+};
 
 use crate::{
+    diagnostic::Diagnostic,
     ir::{self, Variable},
     lexer::{Token, TokenMetadata},
+    semantics::{self, Signature},
 };
 
 mod scope;
 mod single;
 
-pub type Variables = HashMap<String, Variable>;
+/// The Variables and Names of an enclosing Scope, while an inner Scope is
+/// parsed
+#[derive(Debug)]
+pub struct Scope {
+    vars: HashMap<String, Variable>,
+    names: HashSet<String>,
+}
+
+/// The Statements whose Body can be left with a `break`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Breakable {
+    /// A Loop, which can also be continued
+    Loop,
+    /// A Switch
+    Switch,
+}
+
+/// All the Variables, Functions and Type-Definitions that are currently
+/// visible while parsing
+#[derive(Debug, Clone, Default)]
+pub struct Variables {
+    vars: HashMap<String, Variable>,
+    types: HashMap<String, ir::DataType>,
+    globals: Vec<ir::Global>,
+    /// The Signatures of all the Functions and whether or not their Body
+    /// has already been defined
+    functions: HashMap<String, (Signature, bool)>,
+    /// The Names declared in the current Scope, which can not be declared
+    /// again in it
+    scope: HashSet<String>,
+    /// The Names of all the Variables declared in the current Function, in
+    /// any Scope
+    declared: HashSet<String>,
+    /// The Return-Type of the Function that is currently being parsed
+    return_type: Option<ir::DataType>,
+    /// The Loops and Switches around the current Statement, the innermost
+    /// one last
+    bodies: Vec<Breakable>,
+    /// All the Diagnostics found so far, which are shared by every Copy
+    diagnostics: Rc<RefCell<Vec<Diagnostic>>>,
+}
+
+impl Variables {
+    /// Creates a new empty Set of Variables and Types
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a Copy of the Variables for the Body of a Function, in which
+    /// only its Arguments are declared, so the Names of all the Globals can
+    /// be declared again
+    pub fn function_scope(
+        &self,
+        return_type: ir::DataType,
+        args: &[(String, ir::DataType)],
+    ) -> Self {
+        let mut result = self.clone();
+        result.scope.clear();
+        result.declared.clear();
+        result.return_type = Some(return_type);
+
+        for (name, ty) in args.iter() {
+            result.scope.insert(name.clone());
+            result.declared.insert(name.clone());
+            result.insert(name.clone(), Variable::new_str(name, ty.clone()));
+        }
+
+        result
+    }
+
+    /// Starts a new Scope, like the Body of a Loop, in which the Names of
+    /// the enclosing Scopes can be declared again. Returns the Variables
+    /// of the enclosing Scope, which are restored by `leave_scope`
+    pub fn enter_scope(&mut self) -> Scope {
+        Scope {
+            vars: self.vars.clone(),
+            names: std::mem::take(&mut self.scope),
+        }
+    }
+
+    /// Ends the current Scope, so only the Variables of the enclosing Scope
+    /// are visible again
+    pub fn leave_scope(&mut self, outer: Scope) {
+        self.vars = outer.vars;
+        self.scope = outer.names;
+    }
+
+    /// Adds the Variable under the given Name, replacing any
+    /// previous Variable with the same Name
+    pub fn insert(&mut self, name: String, var: Variable) -> Option<Variable> {
+        self.vars.insert(name, var)
+    }
+
+    /// Declares the Variable in the current Scope, which is an Error if
+    /// there already is something with the same Name in it.
+    ///
+    /// Every Variable of a Function needs its own Name, so a Variable that
+    /// shadows another one or reuses the Name of one in a previous Scope
+    /// gets a unique Name, which is returned as part of the Variable
+    pub fn declare(&mut self, metadata: &TokenMetadata, mut var: Variable) -> Variable {
+        self.declare_name(metadata, &var.name);
+
+        let name = var.name.clone();
+        if self.vars.contains_key(&name) || self.declared.contains(&name) {
+            var.name = format!("{}#{}", name, self.declared.len());
+        }
+        self.declared.insert(var.name.clone());
+
+        self.insert(name, var.clone());
+        var
+    }
+
+    /// Only declares the Name in the current Scope, without adding a
+    /// Variable for it
+    pub fn declare_name(&mut self, metadata: &TokenMetadata, name: &str) {
+        if !self.scope.insert(name.to_owned()) {
+            self.report(Diagnostic::error(
+                metadata,
+                format!("redefinition of `{}`", name),
+            ));
+        }
+    }
+
+    /// Looks up the Variable with the given Name
+    pub fn get(&self, name: &str) -> Option<&Variable> {
+        self.vars.get(name)
+    }
+
+    /// Looks up the Variable with the given Name that is used at the given
+    /// Location. An unknown Variable is reported and replaced by an `int`,
+    /// so the rest of the Program can still be checked
+    pub fn resolve(&self, metadata: &TokenMetadata, name: &str) -> Variable {
+        match self.get(name) {
+            Some(var) => var.clone(),
+            None => {
+                self.report(Diagnostic::error(
+                    metadata,
+                    format!("cannot find value `{}` in this scope", name),
+                ));
+                Variable::new_str(name, ir::DataType::I32)
+            }
+        }
+    }
+
+    /// Adds the Type-Definition under the given Name, like
+    /// `struct point`
+    pub fn insert_type(&mut self, name: String, ty: ir::DataType) -> Option<ir::DataType> {
+        self.types.insert(name, ty)
+    }
+
+    /// Looks up the Type-Definition with the given Name
+    pub fn get_type(&self, name: &str) -> Option<&ir::DataType> {
+        self.types.get(name)
+    }
+
+    /// Declares the Function with the given Signature, its Body is only
+    /// allowed to be defined once and every Declaration needs to match.
+    /// Returns the Calling-Convention of the Function, which is inherited
+    /// from a previous Declaration if the Signature does not give one
+    pub fn declare_function(
+        &mut self,
+        metadata: &TokenMetadata,
+        name: &str,
+        mut signature: Signature,
+        defined: bool,
+    ) -> ir::CallingConvention {
+        if let Some((previous, _)) = self.functions.get(name) {
+            if signature.convention.is_none() {
+                signature.convention = previous.convention;
+            }
+        }
+        let convention = signature.convention.unwrap_or_default();
+
+        match self.functions.get(name) {
+            Some((previous, _)) if previous.conflicts(&signature) => {
+                self.report(Diagnostic::error(
+                    metadata,
+                    format!("conflicting types for `{}`", name),
+                ));
+            }
+            Some((_, true)) if defined => {
+                self.report(Diagnostic::error(
+                    metadata,
+                    format!("redefinition of function `{}`", name),
+                ));
+            }
+            Some((previous, true)) => return previous.convention.unwrap_or_default(),
+            _ => {}
+        };
+
+        self.functions.insert(name.to_owned(), (signature, defined));
+        convention
+    }
+
+    /// Builds the Call to the Function with the given Name and checks the
+    /// Arguments against its Signature
+    pub fn call(
+        &self,
+        metadata: &TokenMetadata,
+        name: &str,
+        arguments: Vec<ir::Expression>,
+    ) -> ir::Expression {
+        if name == semantics::SYSCALL {
+            if let Some(diagnostic) = semantics::syscall(metadata, &arguments) {
+                self.report(diagnostic);
+            }
+            return ir::Expression::Call(name.to_owned(), arguments, ir::DataType::I32);
+        }
+
+        let return_type = match self.functions.get(name) {
+            Some((signature, _)) => {
+                for diagnostic in semantics::call(metadata, name, signature, &arguments) {
+                    self.report(diagnostic);
+                }
+                signature.returns.clone()
+            }
+            None => {
+                self.report(Diagnostic::error(
+                    metadata,
+                    format!("cannot find function `{}` in this scope", name),
+                ));
+                ir::DataType::I32
+            }
+        };
+
+        ir::Expression::Call(name.to_owned(), arguments, return_type)
+    }
+
+    /// The Return-Type of the Function that is currently being parsed
+    pub fn return_type(&self) -> Option<&ir::DataType> {
+        self.return_type.as_ref()
+    }
+
+    /// Starts the Body of a Loop or Switch, until the matching `leave_body`
+    pub fn enter_body(&mut self, kind: Breakable) {
+        self.bodies.push(kind);
+    }
+
+    /// Ends the innermost Body of a Loop or Switch
+    pub fn leave_body(&mut self) {
+        self.bodies.pop();
+    }
+
+    /// Whether or not a `break` is allowed in the current Statement
+    pub fn can_break(&self) -> bool {
+        !self.bodies.is_empty()
+    }
+
+    /// Whether or not a `continue` is allowed in the current Statement
+    pub fn can_continue(&self) -> bool {
+        self.bodies.contains(&Breakable::Loop)
+    }
+
+    /// Checks that the Value can be assigned to a Location of the given
+    /// Datatype and reports the Problem if not
+    pub fn check_assignment(
+        &self,
+        metadata: &TokenMetadata,
+        target: &ir::DataType,
+        value: &ir::Expression,
+    ) {
+        if let Some(diagnostic) = semantics::assignment(metadata, target, value) {
+            self.report(diagnostic);
+        }
+    }
+
+    /// Checks that the binary Operation can be applied to its Operands and
+    /// reports the Problem if not
+    pub fn check_operation(
+        &self,
+        metadata: &TokenMetadata,
+        op: &ir::OP,
+        left: &ir::Expression,
+        right: &ir::Expression,
+    ) {
+        if let Some(diagnostic) = semantics::operation(metadata, op, left, right) {
+            self.report(diagnostic);
+        }
+    }
+
+    /// Checks that the Address can be dereferenced and reports the Problem
+    /// if not
+    pub fn check_dereference(&self, metadata: &TokenMetadata, address: &ir::Expression) {
+        if let Some(diagnostic) = semantics::dereference(metadata, address) {
+            self.report(diagnostic);
+        }
+    }
+
+    /// Checks that the Value can be indexed and reports the Problem if not
+    pub fn check_subscript(&self, metadata: &TokenMetadata, root: &ir::Expression) {
+        if let Some(diagnostic) = semantics::subscript(metadata, root) {
+            self.report(diagnostic);
+        }
+    }
+
+    /// Checks that the Array can be declared with the given Number of
+    /// Elements and reports the Problem if not
+    pub fn check_array(
+        &self,
+        metadata: &TokenMetadata,
+        name: &str,
+        element: &ir::DataType,
+        count: u32,
+    ) {
+        if let Some(diagnostic) = semantics::array(metadata, name, element, count) {
+            self.report(diagnostic);
+        }
+    }
+
+    /// Checks that the Struct or Union has a Member with the given Name and
+    /// reports the Problem if not
+    pub fn check_member(&self, metadata: &TokenMetadata, base: &ir::DataType, name: &str) {
+        if let Some(diagnostic) = semantics::member(metadata, base, name) {
+            self.report(diagnostic);
+        }
+    }
+
+    /// Adds a Variable with static Storage to the Program
+    pub fn add_global(&mut self, global: ir::Global) {
+        self.globals.push(global);
+    }
+
+    /// Whether or not the Variable with the given Name in the IR has static
+    /// Storage
+    pub fn is_global(&self, name: &str) -> bool {
+        self.globals.iter().any(|global| global.var.name == name)
+    }
+
+    /// All the Variables with static Storage in the Program so far
+    pub fn globals(&self) -> &[ir::Global] {
+        &self.globals
+    }
+
+    /// The Calling-Conventions of all the declared Functions
+    pub fn conventions(&self) -> HashMap<String, ir::CallingConvention> {
+        self.functions
+            .iter()
+            .map(|(name, (signature, _))| (name.clone(), signature.convention.unwrap_or_default()))
+            .collect()
+    }
+
+    /// Takes all the Variables with static Storage out of this
+    pub fn take_globals(&mut self) -> Vec<ir::Global> {
+        std::mem::take(&mut self.globals)
+    }
+
+    /// Reports a Problem in the Program, parsing still continues
+    pub fn report(&self, diagnostic: Diagnostic) {
+        self.diagnostics.borrow_mut().push(diagnostic);
+    }
+
+    /// Takes all the Diagnostics that have been reported so far
+    pub fn take_diagnostics(&self) -> Vec<Diagnostic> {
+        std::mem::take(&mut *self.diagnostics.borrow_mut())
+    }
+}
 
 /// Parses the Token-Stream into a List of Statements
 ///
@@ -19,7 +381,7 @@ pub type Variables = HashMap<String, Variable>;
 /// # use compiler::ir::{Variable, DataType};
 /// # let mut variables = Variables::new();
 /// # variables.insert("test".to_owned(), Variable::new_str("test", DataType::U32));
-/// # let empty_metadata = TokenMetadata { file_name: "test".to_owned(), line: 1, };
+/// # let empty_metadata = TokenMetadata { file_name: "test".to_owned(), line: 1, column: 1, };
 /// let tokens = &[
 ///     (Token::Identifier("test".to_owned()), empty_metadata.clone()),
 ///     (Token::Semicolon, empty_metadata.clone()),
@@ -49,6 +411,7 @@ where
 mod tests {
     use crate::ir::DataType;
     use crate::lexer::{Keyword, Value};
+    use crate::test_token_pair;
 
     use super::*;
 
@@ -60,6 +423,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -67,6 +431,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -74,20 +439,15 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
-                Token::Equals,
-                TokenMetadata {
-                    file_name: "test".to_string(),
-                    line: 1,
-                },
-            ),
-            (
-                Token::Equals,
+                Token::DoubleEquals,
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -95,6 +455,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -102,6 +463,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -109,6 +471,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -116,6 +479,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -123,6 +487,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -130,6 +495,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -137,6 +503,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -144,6 +511,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
@@ -153,14 +521,15 @@ mod tests {
         vars.insert("i".to_string(), i_var.clone());
 
         let expected: Vec<ir::Statement> = vec![ir::Statement::WhileLoop(
-            ir::Condition {
-                left: ir::Expression::Variable(i_var),
-                comparison: ir::Comparison::Equal,
-                right: ir::Expression::Constant(ir::Value::I32(0)),
-            },
+            ir::Condition::Compare(
+                ir::Comparison::Equal,
+                ir::Expression::Variable(i_var),
+                ir::Expression::Constant(ir::Value::I32(0)),
+            ),
             vec![ir::Statement::SingleExpression(ir::Expression::Call(
                 "test".to_owned(),
                 vec![],
+                ir::DataType::I32,
             ))],
         )];
 
@@ -175,6 +544,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -182,6 +552,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -189,6 +560,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -196,6 +568,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -203,6 +576,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -210,6 +584,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -217,6 +592,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -224,6 +600,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -231,6 +608,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -238,6 +616,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -245,6 +624,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -252,6 +632,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -259,6 +640,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -266,6 +648,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -273,6 +656,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -280,6 +664,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -287,6 +672,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -294,6 +680,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -301,6 +688,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -308,6 +696,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -315,6 +704,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -322,6 +712,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -329,6 +720,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
@@ -338,28 +730,27 @@ mod tests {
         let expected: Vec<ir::Statement> = vec![
             ir::Statement::Declaration(i_var.clone()),
             ir::Statement::Assignment(i_var.clone(), ir::Expression::Constant(ir::Value::I32(0))),
-            ir::Statement::WhileLoop(
-                ir::Condition {
-                    left: ir::Expression::Variable(i_var.clone()),
-                    comparison: ir::Comparison::LessThan,
-                    right: ir::Expression::Constant(ir::Value::I32(10)),
-                },
-                vec![
-                    ir::Statement::SingleExpression(ir::Expression::Call(
-                        "test".to_owned(),
-                        vec![],
-                    )),
-                    ir::Statement::Assignment(
-                        i_var.clone(),
-                        ir::Expression::Operation(
-                            ir::OP::Add,
-                            vec![
-                                ir::Expression::Variable(i_var.clone()),
-                                ir::Expression::Constant(ir::Value::I32(1)),
-                            ],
-                        ),
+            ir::Statement::ForLoop(
+                ir::Condition::Compare(
+                    ir::Comparison::LessThan,
+                    ir::Expression::Variable(i_var.clone()),
+                    ir::Expression::Constant(ir::Value::I32(10)),
+                ),
+                vec![ir::Statement::SingleExpression(ir::Expression::Call(
+                    "test".to_owned(),
+                    vec![],
+                    ir::DataType::I32,
+                ))],
+                vec![ir::Statement::Assignment(
+                    i_var.clone(),
+                    ir::Expression::Operation(
+                        ir::OP::Add,
+                        vec![
+                            ir::Expression::Variable(i_var.clone()),
+                            ir::Expression::Constant(ir::Value::I32(1)),
+                        ],
                     ),
-                ],
+                )],
             ),
         ];
 
@@ -377,6 +768,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -384,6 +776,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -391,6 +784,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -398,6 +792,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -405,6 +800,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
@@ -429,6 +825,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -436,6 +833,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -443,6 +841,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -450,6 +849,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -457,6 +857,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -464,6 +865,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -471,6 +873,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -478,6 +881,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
             (
@@ -485,6 +889,7 @@ mod tests {
                 TokenMetadata {
                     file_name: "test".to_string(),
                     line: 1,
+                    column: 1,
                 },
             ),
         ];
@@ -505,4 +910,101 @@ mod tests {
             parse(&mut tokens.iter().peekable(), &mut Variables::new())
         );
     }
+
+    #[test]
+    fn else_if() {
+        let tokens = &[
+            test_token_pair!(Token::Keyword(Keyword::If)),
+            test_token_pair!(Token::OpenParan),
+            test_token_pair!(Token::Identifier("a".to_owned())),
+            test_token_pair!(Token::CloseParan),
+            test_token_pair!(Token::OpenCurlyBrace),
+            test_token_pair!(Token::Keyword(Keyword::Break)),
+            test_token_pair!(Token::Semicolon),
+            test_token_pair!(Token::CloseCurlyBrace),
+            test_token_pair!(Token::Keyword(Keyword::Else)),
+            test_token_pair!(Token::Keyword(Keyword::If)),
+            test_token_pair!(Token::OpenParan),
+            test_token_pair!(Token::Identifier("a".to_owned())),
+            test_token_pair!(Token::CloseParan),
+            test_token_pair!(Token::OpenCurlyBrace),
+            test_token_pair!(Token::CloseCurlyBrace),
+            test_token_pair!(Token::Keyword(Keyword::Else)),
+            test_token_pair!(Token::OpenCurlyBrace),
+            test_token_pair!(Token::Keyword(Keyword::Continue)),
+            test_token_pair!(Token::Semicolon),
+            test_token_pair!(Token::CloseCurlyBrace),
+        ];
+
+        let mut vars = Variables::new();
+        let a_var = Variable::new_str("a", DataType::I32);
+        vars.insert(a_var.name.clone(), a_var.clone());
+
+        let a_cond = || {
+            ir::Condition::Compare(
+                ir::Comparison::NotEqual,
+                ir::Expression::Variable(a_var.clone()),
+                ir::Expression::Constant(ir::Value::I32(0)),
+            )
+        };
+        let expected = vec![ir::Statement::If(
+            a_cond(),
+            vec![ir::Statement::Break],
+            vec![ir::Statement::If(
+                a_cond(),
+                vec![],
+                vec![ir::Statement::Continue],
+            )],
+        )];
+
+        assert_eq!(expected, parse(&mut tokens.iter().peekable(), &mut vars));
+    }
+
+    #[test]
+    fn switch_cases() {
+        let tokens = &[
+            test_token_pair!(Token::Keyword(Keyword::Switch)),
+            test_token_pair!(Token::OpenParan),
+            test_token_pair!(Token::Identifier("a".to_owned())),
+            test_token_pair!(Token::CloseParan),
+            test_token_pair!(Token::OpenCurlyBrace),
+            test_token_pair!(Token::Keyword(Keyword::Case)),
+            test_token_pair!(Token::Constant(Value::Integer(1))),
+            test_token_pair!(Token::Colon),
+            test_token_pair!(Token::Keyword(Keyword::Case)),
+            test_token_pair!(Token::Constant(Value::Integer(2))),
+            test_token_pair!(Token::Colon),
+            test_token_pair!(Token::Keyword(Keyword::Break)),
+            test_token_pair!(Token::Semicolon),
+            test_token_pair!(Token::Keyword(Keyword::Default)),
+            test_token_pair!(Token::Colon),
+            test_token_pair!(Token::Keyword(Keyword::Break)),
+            test_token_pair!(Token::Semicolon),
+            test_token_pair!(Token::CloseCurlyBrace),
+        ];
+
+        let mut vars = Variables::new();
+        let a_var = Variable::new_str("a", DataType::I32);
+        vars.insert(a_var.name.clone(), a_var.clone());
+
+        let expected = vec![ir::Statement::Switch(
+            ir::Expression::Variable(a_var),
+            vec![
+                ir::Case {
+                    value: Some(1),
+                    statements: vec![],
+                },
+                ir::Case {
+                    value: Some(2),
+                    statements: vec![ir::Statement::Break],
+                },
+                ir::Case {
+                    value: None,
+                    statements: vec![ir::Statement::Break],
+                },
+            ],
+        )];
+
+        assert_eq!(expected, parse(&mut tokens.iter().peekable(), &mut vars));
+    }
 }
@@ -7,8 +7,8 @@ use crate::{
 
 use super::{parse, Variables};
 
-/// Parses all the Statements between two Curly Brackets `{}`, but
-/// treats them all the same (no scope based variables or the like)
+/// Parses all the Statements between two Curly Brackets `{}` in their own
+/// Scope, so the Variables declared in it are not visible afterwards
 pub fn parse_scope<'a, I>(
     iter: &mut Peekable<I>,
     vars: &mut Variables,
@@ -22,7 +22,9 @@ where
         _ => return None,
     };
 
+    let outer = vars.enter_scope();
     let inner = parse(iter, vars);
+    vars.leave_scope(outer);
 
     // Expect a closing curly brace at the end
     match iter.next() {
@@ -1,12 +1,15 @@
 use std::iter::Peekable;
 
 use super::scope;
-use super::Variables;
+use super::{Breakable, Variables};
 use crate::ir::Variable;
-use crate::parser::{call_params, condition, datatype, expression};
+use crate::parser::{call_params, condition, datatype, expression, global};
 use crate::{
-    const_eval, ir,
+    const_eval,
+    diagnostic::Diagnostic,
+    ir,
     lexer::{Keyword, Token, TokenMetadata},
+    semantics::types,
 };
 
 /// Parses a Single Statement, like a single Line or a Loop
@@ -16,13 +19,22 @@ where
 {
     let peeked = iter.peek()?;
     match peeked {
-        (Token::Keyword(Keyword::Return), _) => {
+        (Token::Keyword(Keyword::Return), metadata) => {
             iter.next();
             let expression = match expression::parse(iter, &vars) {
                 Some(exp) => exp,
                 None => ir::Expression::Empty,
             };
 
+            match vars.return_type() {
+                Some(ir::DataType::Void) | None => {}
+                Some(_) if expression == ir::Expression::Empty => vars.report(Diagnostic::error(
+                    metadata,
+                    "non-void function should return a value",
+                )),
+                Some(return_type) => vars.check_assignment(metadata, return_type, &expression),
+            };
+
             // Removes the next item if its a semicolon
             match iter.peek() {
                 Some((Token::Semicolon, _)) => {
@@ -41,14 +53,16 @@ where
                 _ => return None,
             };
 
-            let cond = condition::parse(iter, &vars).unwrap();
+            let cond = condition::parse(iter, &vars)?;
 
             match iter.next() {
                 Some((Token::CloseParan, _)) => {}
                 _ => return None,
             };
 
-            let inner = scope::parse_scope(iter, vars).unwrap();
+            vars.enter_body(Breakable::Loop);
+            let inner = scope::parse_scope(iter, vars)?;
+            vars.leave_body();
 
             Some(vec![ir::Statement::WhileLoop(cond, inner)])
         }
@@ -60,9 +74,12 @@ where
                 _ => return None,
             };
 
+            // The Variables declared in the Initialization are only visible
+            // in the Loop
+            let outer = vars.enter_scope();
             let first = parse(iter, vars)?;
 
-            let cond = condition::parse(iter, &vars).unwrap();
+            let cond = condition::parse(iter, &vars)?;
 
             match iter.peek() {
                 Some((Token::Semicolon, _)) => {
@@ -79,14 +96,46 @@ where
                 _ => {}
             };
 
-            let mut inner_loop = scope::parse_scope(iter, vars).unwrap();
-            inner_loop.extend(third);
+            vars.enter_body(Breakable::Loop);
+            let inner_loop = scope::parse_scope(iter, vars)?;
+            vars.leave_body();
+            vars.leave_scope(outer);
 
             let mut result = first;
-            result.push(ir::Statement::WhileLoop(cond, inner_loop));
+            result.push(ir::Statement::ForLoop(cond, inner_loop, third));
 
             Some(result)
         }
+        (Token::Keyword(Keyword::Do), _) => {
+            iter.next();
+
+            vars.enter_body(Breakable::Loop);
+            let inner = scope::parse_scope(iter, vars)?;
+            vars.leave_body();
+
+            match iter.next() {
+                Some((Token::Keyword(Keyword::While), _)) => {}
+                _ => return None,
+            };
+            match iter.next() {
+                Some((Token::OpenParan, _)) => {}
+                _ => return None,
+            };
+
+            let cond = condition::parse(iter, vars)?;
+
+            match iter.next() {
+                Some((Token::CloseParan, _)) => {}
+                _ => return None,
+            };
+
+            // Removes the next item if its a semicolon
+            if let Some((Token::Semicolon, _)) = iter.peek() {
+                iter.next();
+            }
+
+            Some(vec![ir::Statement::DoWhile(inner, cond)])
+        }
         (Token::Keyword(Keyword::If), _) => {
             iter.next();
 
@@ -95,44 +144,181 @@ where
                 _ => return None,
             };
 
-            let cond = condition::parse(iter, &vars).unwrap();
+            let cond = condition::parse(iter, &vars)?;
 
             match iter.next() {
                 Some((Token::CloseParan, _)) => {}
                 _ => return None,
             };
 
-            let inner = scope::parse_scope(iter, vars).unwrap();
+            // Without Braces, the Body is a single Statement just like the
+            // Else-Branch
+            let inner = match iter.peek() {
+                Some((Token::OpenCurlyBrace, _)) => scope::parse_scope(iter, vars)?,
+                _ => parse(iter, vars)?,
+            };
+
+            let other = match iter.peek() {
+                Some((Token::Keyword(Keyword::Else), _)) => {
+                    iter.next();
+
+                    match iter.peek() {
+                        Some((Token::OpenCurlyBrace, _)) => scope::parse_scope(iter, vars)?,
+                        // Else-If Chains are simply nested in the Else-Branch
+                        _ => parse(iter, vars)?,
+                    }
+                }
+                _ => Vec::new(),
+            };
 
-            Some(vec![ir::Statement::If(cond, inner)])
+            Some(vec![ir::Statement::If(cond, inner, other)])
+        }
+        (Token::Keyword(Keyword::Switch), _) => {
+            iter.next();
+
+            match iter.next() {
+                Some((Token::OpenParan, _)) => {}
+                _ => return None,
+            };
+
+            let value = expression::parse(iter, vars)?;
+
+            match iter.next() {
+                Some((Token::CloseParan, _)) => {}
+                _ => return None,
+            };
+            match iter.next() {
+                Some((Token::OpenCurlyBrace, _)) => {}
+                _ => return None,
+            };
+
+            // All the Cases share the Scope of the Body
+            let outer = vars.enter_scope();
+            vars.enter_body(Breakable::Switch);
+            let mut cases: Vec<ir::Case> = Vec::new();
+            loop {
+                let (case_value, case_metadata) = match iter.next() {
+                    Some((Token::Keyword(Keyword::Case), metadata)) => {
+                        let raw_value = expression::parse(iter, vars)?;
+                        (Some(const_eval::evaluate(raw_value)?), metadata)
+                    }
+                    Some((Token::Keyword(Keyword::Default), metadata)) => (None, metadata),
+                    Some((Token::CloseCurlyBrace, _)) => break,
+                    _ => return None,
+                };
+
+                match iter.next() {
+                    Some((Token::Colon, _)) => {}
+                    _ => return None,
+                };
+
+                let duplicate = cases.iter().any(|case| case.value == case_value);
+                if duplicate {
+                    let message = match case_value {
+                        Some(value) => format!("duplicate case value `{}`", value as i32),
+                        None => "multiple default labels in one switch".to_owned(),
+                    };
+                    vars.report(Diagnostic::error(case_metadata, message));
+                }
+
+                let statements = super::parse(iter, vars);
+                if !duplicate {
+                    cases.push(ir::Case {
+                        value: case_value,
+                        statements,
+                    });
+                }
+            }
+
+            vars.leave_body();
+            vars.leave_scope(outer);
+
+            Some(vec![ir::Statement::Switch(value, cases)])
+        }
+        (Token::Keyword(Keyword::Break), _) | (Token::Keyword(Keyword::Continue), _) => {
+            let statement = match iter.next() {
+                Some((Token::Keyword(Keyword::Break), metadata)) => {
+                    if !vars.can_break() {
+                        vars.report(Diagnostic::error(
+                            metadata,
+                            "`break` statement not in loop or switch statement",
+                        ));
+                    }
+                    ir::Statement::Break
+                }
+                Some((_, metadata)) => {
+                    if !vars.can_continue() {
+                        vars.report(Diagnostic::error(
+                            metadata,
+                            "`continue` statement not in loop statement",
+                        ));
+                    }
+                    ir::Statement::Continue
+                }
+                None => return None,
+            };
+
+            // Removes the next item if its a semicolon
+            if let Some((Token::Semicolon, _)) = iter.peek() {
+                iter.next();
+            }
+
+            Some(vec![statement])
+        }
+        // These end the Statements of the previous Case
+        (Token::Keyword(Keyword::Case), _) | (Token::Keyword(Keyword::Default), _) => None,
+        (Token::Keyword(Keyword::Static), _) => {
+            iter.next();
+
+            let d_type = datatype::parse(iter, vars)?;
+            let var_name = match iter.next() {
+                Some((Token::Identifier(raw_name), metadata)) => {
+                    vars.declare_name(metadata, raw_name);
+                    raw_name.to_owned()
+                }
+                _ => return None,
+            };
+
+            // The Label needs to be unique in the entire Program
+            let label = format!("{}.{}", var_name, vars.globals().len());
+            global::parse(iter, vars, d_type, var_name, label)?;
+
+            Some(Vec::new())
         }
         (Token::Keyword(_), _) => {
-            let d_type = match datatype::parse(iter) {
+            let d_type = match datatype::parse(iter, vars) {
                 Some(d) => d,
                 None => return None,
             };
 
-            let var_name = match iter.peek() {
-                Some((Token::Identifier(raw_name), _)) => {
+            // Only a Type was defined, like `struct point { int x; };`
+            if let Some((Token::Semicolon, _)) = iter.peek() {
+                iter.next();
+                return Some(Vec::new());
+            }
+
+            let (var_name, metadata) = match iter.peek() {
+                Some((Token::Identifier(raw_name), metadata)) => {
                     iter.next();
-                    raw_name.to_owned()
+                    (raw_name.to_owned(), metadata)
                 }
                 _ => return None,
             };
 
             match iter.next() {
-                Some((Token::OpenSquareBrace, _)) => {
+                Some((Token::OpenSquareBrace, brace)) => {
                     let raw_size = expression::parse(iter, &vars)?;
                     let size = const_eval::evaluate(raw_size)?;
+                    vars.check_array(brace, &var_name, &d_type, size);
                     iter.next();
                     iter.next();
 
                     let variable = Variable {
-                        name: var_name.clone(),
+                        name: var_name,
                         ty: ir::DataType::Array(Box::new(d_type), size),
                     };
 
-                    vars.insert(var_name, variable.clone());
+                    let variable = vars.declare(metadata, variable);
 
                     Some(vec![ir::Statement::Declaration(variable)])
                 }
@@ -148,11 +334,12 @@ where
                     };
 
                     let variable = Variable {
-                        name: var_name.clone(),
+                        name: var_name,
                         ty: d_type,
                     };
 
-                    vars.insert(var_name, variable.clone());
+                    vars.check_assignment(metadata, &variable.ty, &value);
+                    let variable = vars.declare(metadata, variable);
 
                     Some(vec![
                         ir::Statement::Declaration(variable.clone()),
@@ -161,24 +348,22 @@ where
                 }
                 Some((Token::Semicolon, _)) => {
                     let variable = Variable {
-                        name: var_name.clone(),
+                        name: var_name,
                         ty: d_type,
                     };
-                    vars.insert(var_name, variable.clone());
+                    let variable = vars.declare(metadata, variable);
                     Some(vec![ir::Statement::Declaration(variable)])
                 }
-                Some((_, metadata)) => {
-                    println!("{:?}", metadata);
-                    None
-                }
                 _ => None,
             }
         }
-        (Token::Identifier(name), _) => {
+        (Token::Identifier(name), metadata) => {
             iter.next();
 
-            match iter.next() {
+            match iter.peek() {
                 Some((Token::Equals, _)) => {
+                    iter.next();
+
                     let expression = match expression::parse(iter, &vars) {
                         Some(exp) => exp,
                         None => return None,
@@ -192,54 +377,53 @@ where
                         _ => {}
                     };
 
-                    let variable = match vars.get(name) {
-                        Some(var) => var.clone(),
-                        None => return None,
-                    };
+                    let variable = vars.resolve(metadata, name);
+                    vars.check_assignment(metadata, &variable.ty, &expression);
 
                     Some(vec![ir::Statement::Assignment(variable, expression)])
                 }
-                Some((Token::OpenSquareBrace, _)) => {
-                    let index_exp = expression::parse(iter, &vars)?;
+                Some((tok, _)) if expression::compound_operator(tok).is_some() => {
+                    let op = expression::compound_operator(tok)?;
+                    iter.next();
 
-                    match iter.peek() {
-                        Some((Token::CloseSquareBrace, _)) => {
-                            iter.next();
-                        }
-                        _ => {}
-                    };
+                    let variable = vars.resolve(metadata, name);
+                    let value = expression::parse(iter, vars)?;
 
-                    match iter.next() {
-                        Some((Token::Equals, _)) => {}
-                        _ => return None,
-                    };
+                    // Removes the next item if its a semicolon
+                    if let Some((Token::Semicolon, _)) = iter.peek() {
+                        iter.next();
+                    }
 
-                    let exp = match expression::parse(iter, &vars) {
-                        Some(e) => e,
-                        None => return None,
-                    };
+                    let current = ir::Expression::Variable(variable.clone());
+                    vars.check_operation(metadata, &op, &current, &value);
+                    let expression = ir::Expression::Operation(op, vec![current, value]);
+                    vars.check_assignment(metadata, &variable.ty, &expression);
 
-                    match iter.peek() {
-                        Some((Token::Semicolon, _)) => {
+                    Some(vec![ir::Statement::Assignment(variable, expression)])
+                }
+                Some((Token::OpenSquareBrace, _))
+                | Some((Token::Dot, _))
+                | Some((Token::Arrow, _))
+                | Some((Token::Increment, _))
+                | Some((Token::Decrement, _)) => {
+                    let variable = vars.resolve(metadata, name);
+                    let target =
+                        expression::postfix::parse(iter, vars, ir::Expression::Variable(variable))?;
+
+                    // Something like `x++` is only evaluated for its Side-Effects
+                    if let ir::Expression::Increment(_, _, _) = target {
+                        if let Some((Token::Semicolon, _)) = iter.peek() {
                             iter.next();
                         }
-                        _ => {}
-                    };
-
-                    let variable = match vars.get(name) {
-                        Some(var) => var.clone(),
-                        None => return None,
-                    };
+                        return Some(vec![ir::Statement::SingleExpression(target)]);
+                    }
 
-                    Some(vec![ir::Statement::DerefAssignment(
-                        ir::Expression::Indexed(
-                            Box::new(ir::Expression::Variable(variable)),
-                            Box::new(index_exp),
-                        ),
-                        exp,
-                    )])
+                    let address = expression::postfix::address(target)?;
+                    assignment(iter, vars, address)
                 }
                 Some((Token::OpenParan, _)) => {
+                    iter.next();
+
                     let params = match call_params::parse(iter, &vars) {
                         Some(p) => p,
                         None => return None,
@@ -252,42 +436,85 @@ where
                         _ => {}
                     };
 
-                    Some(vec![ir::Statement::SingleExpression(ir::Expression::Call(
-                        name.to_owned(),
-                        params,
-                    ))])
+                    Some(vec![ir::Statement::SingleExpression(
+                        vars.call(metadata, name, params),
+                    )])
+                }
+                _ => {
+                    iter.next();
+                    None
                 }
-                _ => return None,
             }
         }
-        (Token::Asterisk, _) => {
+        // A Block only has its own Scope, its Statements are simply part of
+        // the surrounding ones
+        (Token::OpenCurlyBrace, _) => scope::parse_scope(iter, vars),
+        (Token::Asterisk, metadata) => {
             iter.next();
 
             let expression = expression::parse(iter, &vars)?;
+            vars.check_dereference(metadata, &expression);
+
+            // Something like `*ptr++` is only evaluated for its Side-Effects
+            if let Some((Token::Semicolon, _)) = iter.peek() {
+                iter.next();
+                return Some(vec![ir::Statement::SingleExpression(
+                    ir::Expression::Dereference(Box::new(expression)),
+                )]);
+            }
 
-            match iter.next() {
-                Some((Token::Equals, _)) => {
-                    let exp = match expression::parse(iter, &vars) {
-                        Some(e) => e,
-                        None => return None,
-                    };
-
-                    match iter.peek() {
-                        Some((Token::Semicolon, _)) => {
-                            iter.next();
-                        }
-                        _ => {}
-                    };
+            assignment(iter, vars, expression)
+        }
+        (Token::Increment, _) | (Token::Decrement, _) => {
+            let expression = expression::parse(iter, vars)?;
 
-                    Some(vec![ir::Statement::DerefAssignment(expression, exp)])
-                }
-                _ => return None,
+            // Removes the next item if its a semicolon
+            if let Some((Token::Semicolon, _)) = iter.peek() {
+                iter.next();
             }
+
+            Some(vec![ir::Statement::SingleExpression(expression)])
         }
-        (Token::CloseCurlyBrace, _) => return None,
-        _ => {
-            println!("[Parse-Statements] Unexpected: {:?}", peeked);
-            return None;
+        _ => return None,
+    }
+}
+
+/// Parses the Rest of an Assignment to the Location at the given Address,
+/// which is either a simple Assignment `=` or a compound one like `+=`
+fn assignment<'a, I>(
+    iter: &mut Peekable<I>,
+    vars: &mut Variables,
+    address: ir::Expression,
+) -> Option<Vec<ir::Statement>>
+where
+    I: Iterator<Item = &'a (Token, TokenMetadata)>,
+{
+    let (op, metadata) = match iter.next() {
+        Some((Token::Equals, metadata)) => (None, metadata),
+        Some((tok, metadata)) => (Some(expression::compound_operator(tok)?), metadata),
+        None => return None,
+    };
+
+    let value = expression::parse(iter, vars)?;
+
+    // Removes the next item if its a semicolon
+    if let Some((Token::Semicolon, _)) = iter.peek() {
+        iter.next();
+    }
+
+    let value = match op {
+        Some(op) => {
+            let current = ir::Expression::Dereference(Box::new(address.clone()));
+            vars.check_operation(metadata, &op, &current, &value);
+            ir::Expression::Operation(op, vec![current, value])
         }
+        None => value,
+    };
+
+    // Assignments to raw Addresses, like `*13120 = 1`, are not checked
+    if let ir::DataType::Ptr(target) = types::exp_type(&address) {
+        vars.check_assignment(metadata, &target, &value);
     }
+
+    Some(vec![ir::Statement::DerefAssignment(address, value)])
 }
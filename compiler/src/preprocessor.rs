@@ -0,0 +1,340 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use crate::{diagnostic::Diagnostic, lexer::TokenMetadata};
+
+mod condition;
+pub mod macros;
+mod source;
+
+/// The maximum Depth of nested Includes, which stops Files that include
+/// themselves without an Include-Guard
+const MAX_INCLUDE_DEPTH: usize = 200;
+
+/// A single Line of the preprocessed Program
+#[derive(Debug, PartialEq, Clone)]
+pub struct Line {
+    /// The Content of the Line after all the Macros have been expanded
+    pub content: String,
+    /// The File the Line originally comes from
+    pub file_name: String,
+    /// The Number of the Line in the original File
+    pub line: usize,
+}
+
+/// The State of a single `#if` and all of its `#elif`/`#else` Branches
+struct Conditional {
+    /// Whether or not the Lines in the current Branch are used
+    active: bool,
+    /// Whether or not one of the Branches has already been used
+    taken: bool,
+    /// Whether or not the Lines around the whole Conditional are used
+    parent_active: bool,
+    /// Where the Conditional started, for unterminated Conditionals
+    metadata: TokenMetadata,
+}
+
+/// Runs all the Directives, like `#include` or `#define`, and expands the
+/// Macros in the Program before it is tokenized
+pub struct Preprocessor {
+    /// The Directories that are searched for included Files
+    include_paths: Vec<PathBuf>,
+    /// All the currently defined Macros
+    macros: macros::Macros,
+    /// The Files that contained `#pragma once`
+    once: HashSet<PathBuf>,
+    /// The current Depth of nested Includes
+    depth: usize,
+}
+
+impl Preprocessor {
+    /// Creates a new Preprocessor that searches the given Directories for
+    /// included Files, after the Directory of the including File itself
+    pub fn new(include_paths: Vec<PathBuf>) -> Self {
+        Self {
+            include_paths,
+            macros: macros::Macros::new(),
+            once: HashSet::new(),
+            depth: 0,
+        }
+    }
+
+    /// Defines an object-like Macro with the given Value, like `#define`
+    pub fn define(&mut self, name: &str, value: &str) {
+        if let Ok((name, definition)) = macros::define(&format!("{} {}", name, value)) {
+            self.macros.insert(name, definition);
+        }
+    }
+
+    /// Preprocesses the Content of the File with the given Name
+    pub fn process(&mut self, content: &str, file_name: &str) -> Result<Vec<Line>, Diagnostic> {
+        let mut result = Vec::new();
+        let mut conditionals: Vec<Conditional> = Vec::new();
+
+        let stripped = source::strip_comments(content);
+        for (line, text) in source::logical_lines(&stripped) {
+            let metadata = TokenMetadata {
+                file_name: file_name.to_owned(),
+                line,
+                column: text.find(|c: char| !c.is_whitespace()).unwrap_or(0) + 1,
+            };
+            let active = conditionals.last().map(|c| c.active).unwrap_or(true);
+
+            let directive = match text.trim_start().strip_prefix('#') {
+                Some(directive) => directive.trim_start(),
+                None => {
+                    if active {
+                        let pieces = macros::expand(&macros::scan(&text), &self.macros, &[])
+                            .map_err(|message| Diagnostic::error(&metadata, message))?;
+                        result.push(Line {
+                            content: macros::render(&pieces),
+                            file_name: file_name.to_owned(),
+                            line,
+                        });
+                    }
+                    continue;
+                }
+            };
+
+            let name_end = directive
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(directive.len());
+            let (name, rest) = directive.split_at(name_end);
+            let rest = rest.trim();
+
+            let error = |message: String| Diagnostic::error(&metadata, message);
+
+            match name {
+                "if" | "ifdef" | "ifndef" => {
+                    let value = active
+                        && match name {
+                            "if" => condition::evaluate(rest, &self.macros).map_err(error)?,
+                            "ifdef" => self.macros.contains_key(rest),
+                            _ => !self.macros.contains_key(rest),
+                        };
+
+                    conditionals.push(Conditional {
+                        active: value,
+                        taken: value,
+                        parent_active: active,
+                        metadata: metadata.clone(),
+                    });
+                }
+                "elif" | "else" => {
+                    let current = conditionals
+                        .last_mut()
+                        .ok_or_else(|| error(format!("#{} without #if", name)))?;
+
+                    let value = current.parent_active
+                        && !current.taken
+                        && (name == "else"
+                            || condition::evaluate(rest, &self.macros).map_err(error)?);
+                    current.active = value;
+                    current.taken |= value;
+                }
+                "endif" => {
+                    conditionals
+                        .pop()
+                        .ok_or_else(|| error("#endif without #if".to_owned()))?;
+                }
+                _ if !active => {}
+                "define" => {
+                    let (name, definition) = macros::define(rest).map_err(error)?;
+                    self.macros.insert(name, definition);
+                }
+                "undef" => {
+                    self.macros.remove(rest);
+                }
+                "include" => {
+                    result.extend(self.include(rest, file_name, &metadata)?);
+                }
+                "error" => return Err(error(format!("#error {}", rest))),
+                "pragma" if rest == "once" => {
+                    self.once.insert(canonical(Path::new(file_name)));
+                }
+                // Unknown Pragmas are ignored, like in most Compilers
+                "pragma" | "" => {}
+                other => {
+                    return Err(error(format!(
+                        "invalid preprocessing directive `#{}`",
+                        other
+                    )))
+                }
+            };
+        }
+
+        match conditionals.first() {
+            Some(unterminated) => Err(Diagnostic::error(
+                &unterminated.metadata,
+                "unterminated conditional directive",
+            )),
+            None => Ok(result),
+        }
+    }
+
+    /// Preprocesses the File referenced by an `#include`, which is either
+    /// `"name"` or `<name>`
+    fn include(
+        &mut self,
+        target: &str,
+        file_name: &str,
+        metadata: &TokenMetadata,
+    ) -> Result<Vec<Line>, Diagnostic> {
+        // The Target itself could also be a Macro
+        let target = match target.chars().next() {
+            Some('"') | Some('<') => target.to_owned(),
+            _ => macros::expand(&macros::scan(target), &self.macros, &[])
+                .map(|pieces| macros::render(&pieces).trim().to_owned())
+                .map_err(|message| Diagnostic::error(metadata, message))?,
+        };
+
+        let (name, local) = match (target.chars().next(), target.chars().last()) {
+            (Some('"'), Some('"')) if target.len() >= 2 => (&target[1..target.len() - 1], true),
+            (Some('<'), Some('>')) => (&target[1..target.len() - 1], false),
+            _ => {
+                return Err(Diagnostic::error(
+                    metadata,
+                    "#include expects \"FILENAME\" or <FILENAME>",
+                ))
+            }
+        };
+
+        // Quoted Includes are first searched next to the including File
+        let local_dir = Path::new(file_name)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let path = local
+            .then(|| local_dir.join(name))
+            .into_iter()
+            .chain(self.include_paths.iter().map(|dir| dir.join(name)))
+            .find(|path| path.is_file())
+            .ok_or_else(|| {
+                Diagnostic::error(metadata, format!("cannot find include file `{}`", name))
+            })?;
+
+        if self.once.contains(&canonical(&path)) {
+            return Ok(Vec::new());
+        }
+        if self.depth >= MAX_INCLUDE_DEPTH {
+            return Err(Diagnostic::error(metadata, "#include nested too deeply"));
+        }
+
+        let content = std::fs::read_to_string(&path).map_err(|err| {
+            Diagnostic::error(metadata, format!("cannot read `{}`: {}", name, err))
+        })?;
+
+        self.depth += 1;
+        let result = self.process(&content, &path.to_string_lossy());
+        self.depth -= 1;
+
+        result
+    }
+}
+
+/// The canonical Form of the Path, to recognize the same File included
+/// under different Paths
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contents(lines: &[Line]) -> Vec<&str> {
+        lines.iter().map(|line| line.content.as_str()).collect()
+    }
+
+    #[test]
+    fn keeps_line_numbers() {
+        let content = "#define SIZE 4\n\nint a[SIZE];\n// comment\nint b;";
+        let result = Preprocessor::new(Vec::new())
+            .process(content, "main.c")
+            .unwrap();
+
+        let expected = vec![
+            Line {
+                content: "".to_owned(),
+                file_name: "main.c".to_owned(),
+                line: 2,
+            },
+            Line {
+                content: "int a[4];".to_owned(),
+                file_name: "main.c".to_owned(),
+                line: 3,
+            },
+            Line {
+                content: " ".to_owned(),
+                file_name: "main.c".to_owned(),
+                line: 4,
+            },
+            Line {
+                content: "int b;".to_owned(),
+                file_name: "main.c".to_owned(),
+                line: 5,
+            },
+        ];
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn conditionals() {
+        let content = "#define A 2
+#if A == 1
+one;
+#elif A == 2
+two;
+#ifdef B
+nested;
+#else
+not_nested;
+#endif
+#else
+other;
+#endif
+#undef A
+#ifndef A
+undefined;
+#endif";
+        let result = Preprocessor::new(Vec::new())
+            .process(content, "main.c")
+            .unwrap();
+
+        assert_eq!(vec!["two;", "not_nested;", "undefined;"], contents(&result));
+    }
+
+    #[test]
+    fn error_directive() {
+        let content = "#ifndef TARGET\n  #error no target\n#endif";
+        let result = Preprocessor::new(Vec::new()).process(content, "main.c");
+
+        let diagnostic = result.unwrap_err();
+        assert_eq!("#error no target", diagnostic.message);
+        assert_eq!(2, diagnostic.line);
+        assert_eq!(3, diagnostic.column);
+    }
+
+    #[test]
+    fn unterminated_conditional() {
+        let content = "int a;\n#if 1\nint b;";
+        let result = Preprocessor::new(Vec::new()).process(content, "main.c");
+
+        let diagnostic = result.unwrap_err();
+        assert_eq!("unterminated conditional directive", diagnostic.message);
+        assert_eq!(2, diagnostic.line);
+    }
+
+    #[test]
+    fn predefined() {
+        let mut preprocessor = Preprocessor::new(Vec::new());
+        preprocessor.define("TARGET", "2");
+
+        let result = preprocessor
+            .process("#if TARGET == 2\nint a;\n#endif", "main.c")
+            .unwrap();
+        assert_eq!(vec!["int a;"], contents(&result));
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{const_eval, lexer, parser};
+
+use super::macros::{self, Macros, Piece};
+
+/// Evaluates the Expression of an `#if` or `#elif`, which is true if it is
+/// not 0. Every Identifier that is left after expanding all the Macros
+/// counts as 0
+pub fn evaluate(text: &str, macros: &Macros) -> Result<bool, String> {
+    let pieces = replace_defined(macros::scan(text), macros)?;
+    let expanded = macros::expand(&pieces, macros, &[])?;
+
+    let replaced: Vec<Piece> = expanded
+        .into_iter()
+        .map(|piece| match piece {
+            Piece::Identifier(_) => Piece::Other("0".to_owned()),
+            other => other,
+        })
+        .collect();
+
+    let content = format!("{}\n", macros::render(&replaced));
+    let tokens = lexer::tokenize(&content, String::new()).map_err(|d| d.message)?;
+
+    let invalid = || format!("invalid expression `{}` in condition", text.trim());
+
+    let mut iter = tokens.iter().peekable();
+    let exp = parser::expression::parse(&mut iter, &parser::statements::Variables::new())
+        .ok_or_else(invalid)?;
+    if iter.next().is_some() {
+        return Err(invalid());
+    }
+
+    const_eval::evaluate(exp)
+        .map(|value| value != 0)
+        .ok_or_else(invalid)
+}
+
+/// Replaces every `defined NAME` or `defined(NAME)` with 1 if the Macro is
+/// defined and 0 otherwise
+fn replace_defined(pieces: Vec<Piece>, macros: &Macros) -> Result<Vec<Piece>, String> {
+    let mut result = Vec::new();
+    let mut iter = pieces.into_iter();
+
+    // The next Piece that is not a Space
+    let next =
+        |iter: &mut std::vec::IntoIter<Piece>| iter.find(|piece| !matches!(piece, Piece::Space(_)));
+
+    while let Some(piece) = iter.next() {
+        match piece {
+            Piece::Identifier(name) if name == "defined" => {
+                let name = match next(&mut iter) {
+                    Some(Piece::Identifier(name)) => name,
+                    Some(Piece::Other(paran)) if paran == "(" => {
+                        match (next(&mut iter), next(&mut iter)) {
+                            (Some(Piece::Identifier(name)), Some(Piece::Other(close)))
+                                if close == ")" =>
+                            {
+                                name
+                            }
+                            _ => return Err("expected `)` after macro name".to_owned()),
+                        }
+                    }
+                    _ => return Err("`defined` needs a macro name".to_owned()),
+                };
+
+                let value = if macros.contains_key(&name) { "1" } else { "0" };
+                result.push(Piece::Other(value.to_owned()));
+                result.push(Piece::Space(" ".to_owned()));
+            }
+            other => result.push(other),
+        };
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn macros(definitions: &[&str]) -> Macros {
+        definitions
+            .iter()
+            .map(|definition| macros::define(definition).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn defined() {
+        let macros = macros(&["DEBUG"]);
+
+        assert_eq!(Ok(true), evaluate("defined(DEBUG)", &macros));
+        assert_eq!(
+            Ok(true),
+            evaluate("defined DEBUG && !defined RELEASE", &macros)
+        );
+        assert_eq!(Ok(false), evaluate("defined(RELEASE)", &macros));
+    }
+
+    #[test]
+    fn arithmetic() {
+        let macros = macros(&["VERSION 3"]);
+
+        assert_eq!(
+            Ok(true),
+            evaluate("VERSION >= 2 && VERSION * 2 == 6", &macros)
+        );
+        assert_eq!(Ok(false), evaluate("UNKNOWN", &macros));
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(evaluate("1 +", &Macros::new()).is_err());
+    }
+}
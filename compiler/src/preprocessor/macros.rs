@@ -0,0 +1,506 @@
+use std::collections::HashMap;
+
+/// The Name under which the variable Arguments of a Macro are available
+const VARIADIC: &str = "__VA_ARGS__";
+
+/// A single Piece of a Line, as seen by the Preprocessor
+#[derive(Debug, PartialEq, Clone)]
+pub enum Piece {
+    /// A Name, which could be a Macro or a Parameter
+    Identifier(String),
+    /// A String- or Character-Literal including its Quotes, which is never
+    /// expanded
+    Literal(String),
+    /// The Whitespace between other Pieces, as it was written
+    Space(String),
+    /// Anything else, like a Number or an Operator
+    Other(String),
+}
+
+impl Piece {
+    fn text(&self) -> &str {
+        match self {
+            Self::Identifier(text)
+            | Self::Literal(text)
+            | Self::Space(text)
+            | Self::Other(text) => text,
+        }
+    }
+
+    fn is_space(&self) -> bool {
+        matches!(self, Self::Space(_))
+    }
+
+    fn is_other(&self, text: &str) -> bool {
+        matches!(self, Self::Other(other) if other == text)
+    }
+}
+
+/// A defined Macro
+#[derive(Debug, PartialEq, Clone)]
+pub struct Macro {
+    /// The Names of the Parameters for a function-like Macro, or None for
+    /// an object-like Macro
+    pub params: Option<Vec<String>>,
+    /// Whether or not the Macro accepts variable Arguments using `...`
+    pub variadic: bool,
+    /// The Pieces the Macro is replaced with
+    pub body: Vec<Piece>,
+}
+
+/// All the currently defined Macros by their Name
+pub type Macros = HashMap<String, Macro>;
+
+/// Splits the Text into its Pieces
+pub fn scan(text: &str) -> Vec<Piece> {
+    let mut result = Vec::new();
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((start, current)) = chars.next() {
+        // Consumes Characters as long as they match and returns the End
+        let mut take_while = |condition: &dyn Fn(char) -> bool| {
+            while let Some((_, next)) = chars.peek() {
+                if !condition(*next) {
+                    break;
+                }
+                chars.next();
+            }
+            chars.peek().map(|(index, _)| *index).unwrap_or(text.len())
+        };
+
+        let piece = match current {
+            _ if current.is_whitespace() => {
+                let end = take_while(&|c| c.is_whitespace());
+                Piece::Space(text[start..end].to_owned())
+            }
+            _ if current.is_alphabetic() || current == '_' => {
+                let end = take_while(&|c| c.is_alphanumeric() || c == '_');
+                Piece::Identifier(text[start..end].to_owned())
+            }
+            // Numbers can contain Letters as well, like `10u`
+            _ if current.is_ascii_digit() => {
+                let end = take_while(&|c| c.is_alphanumeric() || c == '_' || c == '.');
+                Piece::Other(text[start..end].to_owned())
+            }
+            '"' | '\'' => {
+                let mut escaped = false;
+                let mut end = text.len();
+                for (index, inner) in chars.by_ref() {
+                    if !escaped && inner == current {
+                        end = index + inner.len_utf8();
+                        break;
+                    }
+                    escaped = !escaped && inner == '\\';
+                }
+                Piece::Literal(text[start..end].to_owned())
+            }
+            '#' if matches!(chars.peek(), Some((_, '#'))) => {
+                chars.next();
+                Piece::Other("##".to_owned())
+            }
+            _ => Piece::Other(current.to_string()),
+        };
+        result.push(piece);
+    }
+
+    result
+}
+
+/// Turns the Pieces back into Text
+pub fn render(pieces: &[Piece]) -> String {
+    pieces.iter().map(|piece| piece.text()).collect()
+}
+
+/// Parses the Definition of a Macro, everything following `#define`
+pub fn define(text: &str) -> Result<(String, Macro), String> {
+    let pieces = scan(text.trim());
+    let mut iter = pieces.into_iter().peekable();
+
+    let name = match iter.next() {
+        Some(Piece::Identifier(name)) => name,
+        _ => return Err("macro name must be an identifier".to_owned()),
+    };
+
+    // Only a Parenthesis directly after the Name starts the Parameters
+    let mut params = None;
+    let mut variadic = false;
+    if let Some(Piece::Other(paran)) = iter.peek() {
+        if paran == "(" {
+            iter.next();
+
+            let mut names = Vec::new();
+            loop {
+                match iter.next() {
+                    Some(Piece::Identifier(param)) if !variadic => names.push(param),
+                    Some(Piece::Other(dot)) if dot == "." && !variadic => {
+                        for _ in 0..2 {
+                            match iter.next() {
+                                Some(Piece::Other(dot)) if dot == "." => {}
+                                _ => return Err("expected `...` in macro parameters".to_owned()),
+                            };
+                        }
+                        variadic = true;
+                    }
+                    Some(Piece::Space(_)) => continue,
+                    Some(Piece::Other(close)) if close == ")" => break,
+                    _ => return Err("invalid macro parameters".to_owned()),
+                };
+
+                // Every Parameter is followed by a Comma or the End
+                while let Some(Piece::Space(_)) = iter.peek() {
+                    iter.next();
+                }
+                match iter.next() {
+                    Some(Piece::Other(comma)) if comma == "," => {}
+                    Some(Piece::Other(close)) if close == ")" => break,
+                    _ => return Err("expected `,` or `)` in macro parameters".to_owned()),
+                };
+            }
+            params = Some(names);
+        }
+    }
+
+    let body: Vec<Piece> = iter.skip_while(|piece| piece.is_space()).collect();
+
+    Ok((
+        name,
+        Macro {
+            params,
+            variadic,
+            body,
+        },
+    ))
+}
+
+/// Expands all the Macros in the Pieces, the disabled Macros are not
+/// expanded again to prevent endless Recursion
+pub fn expand(
+    pieces: &[Piece],
+    macros: &Macros,
+    disabled: &[String],
+) -> Result<Vec<Piece>, String> {
+    let mut result = Vec::new();
+
+    let mut index = 0;
+    while index < pieces.len() {
+        let (name, definition) = match &pieces[index] {
+            Piece::Identifier(name) if !disabled.contains(name) => match macros.get(name) {
+                Some(definition) => (name, definition),
+                None => {
+                    result.push(pieces[index].clone());
+                    index += 1;
+                    continue;
+                }
+            },
+            other => {
+                result.push(other.clone());
+                index += 1;
+                continue;
+            }
+        };
+
+        let replacement = match &definition.params {
+            None => {
+                index += 1;
+                substitute(definition, &HashMap::new(), macros, disabled)?
+            }
+            Some(params) => {
+                // A function-like Macro without Arguments is left alone
+                let open = match pieces[index + 1..].iter().position(|p| !p.is_space()) {
+                    Some(offset) if pieces[index + 1 + offset].is_other("(") => index + 1 + offset,
+                    _ => {
+                        result.push(pieces[index].clone());
+                        index += 1;
+                        continue;
+                    }
+                };
+
+                let (args, end) = arguments(pieces, open).ok_or_else(|| {
+                    format!("unterminated argument list invoking macro `{}`", name)
+                })?;
+                index = end;
+
+                let args = bind(name, params, definition.variadic, args)?;
+                substitute(definition, &args, macros, disabled)?
+            }
+        };
+
+        let mut inner_disabled = disabled.to_vec();
+        inner_disabled.push(name.to_owned());
+        result.extend(expand(&replacement, macros, &inner_disabled)?);
+    }
+
+    Ok(result)
+}
+
+/// Splits the Arguments of a Macro-Call, starting at the opening
+/// Parenthesis, and returns them with the Index after the closing one
+fn arguments(pieces: &[Piece], open: usize) -> Option<(Vec<Vec<Piece>>, usize)> {
+    let mut result = vec![Vec::new()];
+    let mut depth = 0;
+
+    for (index, piece) in pieces.iter().enumerate().skip(open) {
+        if piece.is_other("(") {
+            depth += 1;
+            if depth == 1 {
+                continue;
+            }
+        } else if piece.is_other(")") {
+            depth -= 1;
+            if depth == 0 {
+                let args = result.into_iter().map(trim).collect();
+                return Some((args, index + 1));
+            }
+        } else if piece.is_other(",") && depth == 1 {
+            result.push(Vec::new());
+            continue;
+        }
+
+        result.last_mut()?.push(piece.clone());
+    }
+
+    None
+}
+
+/// Matches the Arguments of a Call to the Parameters of the Macro
+fn bind(
+    name: &str,
+    params: &[String],
+    variadic: bool,
+    mut args: Vec<Vec<Piece>>,
+) -> Result<HashMap<String, Vec<Piece>>, String> {
+    // `FOO()` is a Call with no Arguments, not a single empty one
+    if params.is_empty() && args.len() == 1 && args[0].is_empty() {
+        args.clear();
+    }
+
+    let count_matches = if variadic {
+        args.len() >= params.len()
+    } else {
+        args.len() == params.len()
+    };
+    if !count_matches {
+        return Err(format!(
+            "macro `{}` takes {} argument{} but {} were supplied",
+            name,
+            params.len(),
+            if params.len() == 1 { "" } else { "s" },
+            args.len()
+        ));
+    }
+
+    let rest = args.split_off(params.len().min(args.len()));
+    let mut result: HashMap<String, Vec<Piece>> = params.iter().cloned().zip(args).collect();
+
+    if variadic {
+        let mut joined = Vec::new();
+        for (index, arg) in rest.into_iter().enumerate() {
+            if index > 0 {
+                joined.push(Piece::Other(",".to_owned()));
+                joined.push(Piece::Space(" ".to_owned()));
+            }
+            joined.extend(arg);
+        }
+        result.insert(VARIADIC.to_owned(), joined);
+    }
+
+    Ok(result)
+}
+
+/// Replaces the Parameters in the Body of the Macro with their Arguments,
+/// while also applying the `#` and `##` Operators
+fn substitute(
+    definition: &Macro,
+    args: &HashMap<String, Vec<Piece>>,
+    macros: &Macros,
+    disabled: &[String],
+) -> Result<Vec<Piece>, String> {
+    let body = &definition.body;
+    let mut result: Vec<Piece> = Vec::new();
+    let mut paste = false;
+
+    // The Index of the next Piece after the given one, ignoring Spaces
+    let next = |index: usize| {
+        body[index + 1..]
+            .iter()
+            .position(|p| !p.is_space())
+            .map(|offset| index + 1 + offset)
+    };
+
+    let mut index = 0;
+    while index < body.len() {
+        let piece = &body[index];
+        index += 1;
+
+        let pieces = match piece {
+            Piece::Other(op) if op == "##" => {
+                while result.last().map(|p| p.is_space()).unwrap_or(false) {
+                    result.pop();
+                }
+                // Skip the Spaces following the Operator
+                index = next(index - 1).unwrap_or(body.len());
+                paste = true;
+                continue;
+            }
+            Piece::Other(op) if op == "#" && definition.params.is_some() => {
+                let param = next(index - 1)
+                    .and_then(|target| match &body[target] {
+                        Piece::Identifier(name) => args.get(name).map(|arg| (target, arg)),
+                        _ => None,
+                    })
+                    .ok_or_else(|| "`#` is not followed by a macro parameter".to_owned())?;
+                index = param.0 + 1;
+                vec![Piece::Literal(stringify(param.1))]
+            }
+            Piece::Identifier(name) if args.contains_key(name) => {
+                let arg = &args[name];
+                let pasted = paste
+                    || next(index - 1)
+                        .map(|n| body[n].is_other("##"))
+                        .unwrap_or(false);
+
+                // The Operands of `##` are used as they were written
+                if pasted {
+                    arg.clone()
+                } else {
+                    expand(arg, macros, disabled)?
+                }
+            }
+            other => vec![other.clone()],
+        };
+
+        if paste && !pieces.is_empty() {
+            paste = false;
+
+            let left = result
+                .pop()
+                .map(|p| p.text().to_owned())
+                .unwrap_or_default();
+            let joined = format!("{}{}", left, pieces[0].text());
+            result.extend(scan(&joined));
+            result.extend(pieces.into_iter().skip(1));
+        } else {
+            result.extend(pieces);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Turns the Argument into a String-Literal, as done by the `#` Operator
+fn stringify(arg: &[Piece]) -> String {
+    let mut result = String::from("\"");
+    for piece in arg {
+        match piece {
+            Piece::Space(_) => result.push(' '),
+            Piece::Literal(text) => {
+                for c in text.chars() {
+                    if c == '"' || c == '\\' {
+                        result.push('\\');
+                    }
+                    result.push(c);
+                }
+            }
+            other => result.push_str(other.text()),
+        };
+    }
+    result.push('"');
+    result
+}
+
+/// Removes the Spaces at the Start and End of the Pieces
+fn trim(mut pieces: Vec<Piece>) -> Vec<Piece> {
+    while pieces.last().map(|p| p.is_space()).unwrap_or(false) {
+        pieces.pop();
+    }
+    let start = pieces
+        .iter()
+        .position(|p| !p.is_space())
+        .unwrap_or(pieces.len());
+    pieces.split_off(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_text(definitions: &[&str], text: &str) -> Result<String, String> {
+        let mut macros = Macros::new();
+        for definition in definitions {
+            let (name, value) = define(definition)?;
+            macros.insert(name, value);
+        }
+
+        expand(&scan(text), &macros, &[]).map(|pieces| render(&pieces))
+    }
+
+    #[test]
+    fn object_like() {
+        assert_eq!(
+            Ok("int a = 10 * 2;".to_owned()),
+            expand_text(&["SIZE 10", "DOUBLE SIZE * 2"], "int a = DOUBLE;")
+        );
+    }
+
+    #[test]
+    fn function_like() {
+        assert_eq!(
+            Ok("((1) + (2 * 3));".to_owned()),
+            expand_text(&["ADD(a, b) ((a) + (b))"], "ADD(1, 2 * 3);")
+        );
+    }
+
+    #[test]
+    fn nested_arguments() {
+        assert_eq!(
+            Ok("((f(1, 2)) * (f(1, 2)));".to_owned()),
+            expand_text(&["SQUARE(x) ((x) * (x))"], "SQUARE(f(1, 2));")
+        );
+    }
+
+    #[test]
+    fn function_like_without_call() {
+        assert_eq!(
+            Ok("int MAX;".to_owned()),
+            expand_text(&["MAX(a, b) a"], "int MAX;")
+        );
+    }
+
+    #[test]
+    fn self_reference() {
+        assert_eq!(
+            Ok("int b = a + 1;".to_owned()),
+            expand_text(&["a a + 1"], "int b = a;")
+        );
+    }
+
+    #[test]
+    fn stringify_and_paste() {
+        assert_eq!(
+            Ok("char *name = \"value\"; int value_count;".to_owned()),
+            expand_text(
+                &["NAME(x) char *name = #x;", "COUNT(x) int x ## _count"],
+                "NAME(value) COUNT(value);"
+            )
+        );
+    }
+
+    #[test]
+    fn variadic() {
+        assert_eq!(
+            Ok("__syscall(1, a, b, 0, 0);".to_owned()),
+            expand_text(
+                &["CALL(id, ...) __syscall(id, __VA_ARGS__, 0, 0)"],
+                "CALL(1, a, b);"
+            )
+        );
+    }
+
+    #[test]
+    fn wrong_argument_count() {
+        assert_eq!(
+            Err("macro `ADD` takes 2 arguments but 1 were supplied".to_owned()),
+            expand_text(&["ADD(a, b) a + b"], "ADD(1);")
+        );
+    }
+}
@@ -0,0 +1,113 @@
+/// Replaces all the Comments in the Content with a single Space, but keeps
+/// the Line-Breaks in Block-Comments so every Line stays where it was
+pub fn strip_comments(content: &str) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+
+    while let Some(current) = chars.next() {
+        match current {
+            '"' | '\'' => {
+                result.push(current);
+
+                // Comments inside of Literals are part of the Literal
+                while let Some(inner) = chars.next() {
+                    result.push(inner);
+                    match inner {
+                        '\\' => {
+                            if let Some(escaped) = chars.next() {
+                                result.push(escaped);
+                            }
+                        }
+                        '\n' => break,
+                        _ if inner == current => break,
+                        _ => {}
+                    };
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                while let Some(inner) = chars.peek() {
+                    if *inner == '\n' {
+                        break;
+                    }
+                    chars.next();
+                }
+                result.push(' ');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+
+                let mut last = ' ';
+                for inner in chars.by_ref() {
+                    if last == '*' && inner == '/' {
+                        break;
+                    }
+                    if inner == '\n' {
+                        result.push('\n');
+                    }
+                    last = inner;
+                }
+                result.push(' ');
+            }
+            _ => result.push(current),
+        };
+    }
+
+    result
+}
+
+/// Splits the Content into its logical Lines, where a Backslash at the End
+/// of a Line joins it with the next one. Every Line is returned together
+/// with the Number of the Line it started on
+pub fn logical_lines(content: &str) -> Vec<(usize, String)> {
+    let mut result: Vec<(usize, String)> = Vec::new();
+    let mut continued = false;
+
+    for (index, raw_line) in content.lines().enumerate() {
+        let raw_line = raw_line.strip_suffix('\r').unwrap_or(raw_line);
+        let (line, continues) = match raw_line.strip_suffix('\\') {
+            Some(stripped) => (stripped, true),
+            None => (raw_line, false),
+        };
+
+        match result.last_mut() {
+            Some((_, previous)) if continued => previous.push_str(line),
+            _ => result.push((index + 1, line.to_owned())),
+        };
+        continued = continues;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_comment() {
+        assert_eq!("int a;  \nint b;", strip_comments("int a; // test\nint b;"));
+    }
+
+    #[test]
+    fn block_comment_keeps_lines() {
+        assert_eq!(
+            "int a; \n \nint b;",
+            strip_comments("int a; /* first\nsecond */\nint b;")
+        );
+    }
+
+    #[test]
+    fn comment_in_string() {
+        assert_eq!(
+            "char *a = \"// test\";",
+            strip_comments("char *a = \"// test\";")
+        );
+    }
+
+    #[test]
+    fn continued_lines() {
+        let expected = vec![(1, "#define A 1 + 2".to_owned()), (3, "int a;".to_owned())];
+
+        assert_eq!(expected, logical_lines("#define A 1 \\\n+ 2\nint a;"));
+    }
+}
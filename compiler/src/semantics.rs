@@ -1,25 +1,376 @@
-use crate::ir;
+//! The semantic Checks for the Program, which are performed while the
+//! Program is being parsed, as the Parser already resolves every Name
+//! using its Table of Variables and Functions. The resulting IR is
+//! therefore already checked and every Expression in it has a Type
+//! that the Backend can rely on
 
-fn validate_statement(_statement: &ir::Statement) -> bool {
-    true
+use crate::{
+    backend::internal::get_size, const_eval, diagnostic::Diagnostic, ir, lexer::TokenMetadata,
+};
+
+pub mod types;
+
+/// The Name of the builtin Function used to call the Syscalls of the OS
+pub const SYSCALL: &str = "__syscall";
+
+/// The Number of Arguments for a Syscall (ID, p1, p2, p3, p4)
+const SYSCALL_ARGUMENTS: usize = 5;
+
+/// Everything needed to check a Call to a Function
+#[derive(Debug, PartialEq, Clone)]
+pub struct Signature {
+    /// The Datatype of the returned Value
+    pub returns: ir::DataType,
+    /// The Datatypes of all the Arguments in Order
+    pub arguments: Vec<ir::DataType>,
+    /// How the Arguments are passed to the Function, `None` if it was not
+    /// given explicitly and is inherited from a previous Declaration
+    pub convention: Option<ir::CallingConvention>,
 }
 
-fn validate_func(func: &ir::Function) -> bool {
-    for statement in func.3.iter() {
-        if !validate_statement(statement) {
-            return false;
-        }
+impl Signature {
+    /// Whether or not the two Declarations of the same Function disagree,
+    /// the Conventions only conflict if both were given explicitly
+    pub fn conflicts(&self, other: &Signature) -> bool {
+        let conventions = match (self.convention, other.convention) {
+            (Some(first), Some(second)) => first != second,
+            _ => false,
+        };
+        self.returns != other.returns || self.arguments != other.arguments || conventions
+    }
+}
+
+/// Checks that the Value can be assigned to a Location of the given
+/// Datatype, the Diagnostic is placed at the given Location
+pub fn assignment(
+    metadata: &TokenMetadata,
+    target: &ir::DataType,
+    value: &ir::Expression,
+) -> Option<Diagnostic> {
+    if let ir::DataType::Array(_, _) = target {
+        return Some(Diagnostic::error(
+            metadata,
+            format!("array type `{}` is not assignable", target),
+        ));
+    }
+    if is_aggregate(target) {
+        return Some(Diagnostic::error(
+            metadata,
+            format!("copying a value of type `{}` is not supported", target),
+        ));
+    }
+
+    match types::conversion(target, value) {
+        types::Conversion::Implicit => None,
+        types::Conversion::Suspicious(message) => Some(Diagnostic::warning(metadata, message)),
+        types::Conversion::Invalid(message) => Some(Diagnostic::error(metadata, message)),
+    }
+}
+
+/// Checks that an Array with the given Number of Elements can be declared,
+/// which needs at least one Element and has to fit into the Address-Space
+pub fn array(
+    metadata: &TokenMetadata,
+    name: &str,
+    element: &ir::DataType,
+    count: u32,
+) -> Option<Diagnostic> {
+    if count as i32 <= 0 {
+        return Some(Diagnostic::error(
+            metadata,
+            format!(
+                "size of array `{}` must be greater than zero (found `{}`)",
+                name, count as i32
+            ),
+        ));
+    }
+
+    let datatype = ir::DataType::Array(Box::new(element.clone()), count);
+    match get_size::checked_size(&datatype) {
+        Some(_) => None,
+        None => Some(Diagnostic::error(
+            metadata,
+            format!("array `{}` of type `{}` is too large", name, datatype),
+        )),
+    }
+}
+
+/// Checks that the Value can be dereferenced. Besides Pointers, constant
+/// Addresses like `*13120` are accepted, while dereferencing any other
+/// Integer is most likely a mistake
+pub fn dereference(metadata: &TokenMetadata, address: &ir::Expression) -> Option<Diagnostic> {
+    match types::exp_type(address) {
+        ir::DataType::Ptr(_) => None,
+        _ if const_eval::evaluate(address.clone()).is_some() => None,
+        datatype if types::is_integer(&datatype) => Some(Diagnostic::warning(
+            metadata,
+            format!("dereferencing integer `{}` as an address", datatype),
+        )),
+        datatype => Some(Diagnostic::error(
+            metadata,
+            format!(
+                "indirection requires pointer operand (`{}` invalid)",
+                datatype
+            ),
+        )),
+    }
+}
+
+/// Checks that the Value can be indexed, which is only the Case for Arrays
+/// and Pointers
+pub fn subscript(metadata: &TokenMetadata, root: &ir::Expression) -> Option<Diagnostic> {
+    match types::exp_type(root) {
+        ir::DataType::Ptr(_) => None,
+        datatype => Some(Diagnostic::error(
+            metadata,
+            format!(
+                "subscripted value `{}` is not an array or pointer",
+                datatype
+            ),
+        )),
+    }
+}
+
+/// Checks that the Member with the given Name can be accessed in a Value
+/// of the given Datatype, which has to be a Struct or Union
+pub fn member(metadata: &TokenMetadata, base: &ir::DataType, name: &str) -> Option<Diagnostic> {
+    if !is_aggregate(base) {
+        return Some(Diagnostic::error(
+            metadata,
+            format!(
+                "member reference base type `{}` is not a structure or union",
+                base
+            ),
+        ));
+    }
+
+    match types::member(base, name) {
+        Some(_) => None,
+        None => Some(Diagnostic::error(
+            metadata,
+            format!("no member named `{}` in `{}`", name, base),
+        )),
+    }
+}
+
+/// Checks that the binary Operation is defined for the Types of its two
+/// Operands. Every Operation works on Integers, but Pointers can only be
+/// added to or subtracted from Integers or subtracted from each other
+pub fn operation(
+    metadata: &TokenMetadata,
+    op: &ir::OP,
+    left: &ir::Expression,
+    right: &ir::Expression,
+) -> Option<Diagnostic> {
+    let left_type = types::exp_type(left);
+    let right_type = types::exp_type(right);
+
+    let valid = match (
+        op,
+        types::is_pointer(&left_type),
+        types::is_pointer(&right_type),
+    ) {
+        (ir::OP::Add, true, false) => types::is_integer(&right_type),
+        (ir::OP::Add, false, true) => types::is_integer(&left_type),
+        (ir::OP::Substract, true, false) => types::is_integer(&right_type),
+        // Only Pointers to the same Type of Elements can be subtracted
+        (ir::OP::Substract, true, true) => left_type == right_type,
+        _ => types::is_integer(&left_type) && types::is_integer(&right_type),
+    };
+    if valid {
+        return None;
+    }
+
+    Some(Diagnostic::error(
+        metadata,
+        format!(
+            "invalid operands to binary `{}` (have `{}` and `{}`)",
+            symbol(op),
+            left_type,
+            right_type
+        ),
+    ))
+}
+
+/// The Symbol the Operation is written with in the Source-Code
+fn symbol(op: &ir::OP) -> &'static str {
+    match op {
+        ir::OP::Add => "+",
+        ir::OP::Substract => "-",
+        ir::OP::Multiply => "*",
+        ir::OP::Divide => "/",
+        ir::OP::Modulo => "%",
+        ir::OP::BitAnd => "&",
+        ir::OP::BitOr => "|",
+        ir::OP::BitXor => "^",
+        ir::OP::ShiftLeft => "<<",
+        ir::OP::ShiftRight => ">>",
+    }
+}
+
+/// Checks that the Parameters and the Return-Value of the Function can be
+/// passed in Registers or on the Stack, which is not the Case for Structs
+/// and Unions
+pub fn signature(metadata: &TokenMetadata, name: &str, signature: &Signature) -> Vec<Diagnostic> {
+    let mut result: Vec<Diagnostic> = signature
+        .arguments
+        .iter()
+        .filter(|argument| is_aggregate(argument))
+        .map(|argument| {
+            Diagnostic::error(
+                metadata,
+                format!(
+                    "passing `{}` by value to `{}` is not supported",
+                    argument, name
+                ),
+            )
+        })
+        .collect();
+    if is_aggregate(&signature.returns) {
+        result.push(Diagnostic::error(
+            metadata,
+            format!(
+                "returning `{}` by value from `{}` is not supported",
+                signature.returns, name
+            ),
+        ));
+    }
+    result
+}
+
+/// Whether or not the Datatype is a Struct or Union, whose Values can only
+/// be accessed through their Members
+fn is_aggregate(datatype: &ir::DataType) -> bool {
+    matches!(
+        datatype,
+        ir::DataType::Struct(_, _) | ir::DataType::Union(_, _)
+    )
+}
+
+/// Checks the Arguments of a Call to the Function with the given Signature
+pub fn call(
+    metadata: &TokenMetadata,
+    name: &str,
+    signature: &Signature,
+    arguments: &[ir::Expression],
+) -> Vec<Diagnostic> {
+    if signature.arguments.len() != arguments.len() {
+        return vec![Diagnostic::error(
+            metadata,
+            format!(
+                "function `{}` takes {} argument{} but {} {} supplied",
+                name,
+                signature.arguments.len(),
+                if signature.arguments.len() == 1 {
+                    ""
+                } else {
+                    "s"
+                },
+                arguments.len(),
+                if arguments.len() == 1 { "was" } else { "were" },
+            ),
+        )];
+    }
+
+    signature
+        .arguments
+        .iter()
+        .zip(arguments.iter())
+        .filter_map(|(target, value)| assignment(metadata, target, value))
+        .collect()
+}
+
+/// Checks the Arguments of a Syscall, whose ID needs to be known at
+/// compile-time while the Parameters can be anything
+pub fn syscall(metadata: &TokenMetadata, arguments: &[ir::Expression]) -> Option<Diagnostic> {
+    if arguments.len() != SYSCALL_ARGUMENTS {
+        return Some(Diagnostic::error(
+            metadata,
+            format!(
+                "`{}` takes {} arguments (ID, p1, p2, p3, p4) but {} were supplied",
+                SYSCALL,
+                SYSCALL_ARGUMENTS,
+                arguments.len()
+            ),
+        ));
+    }
+
+    match arguments.first() {
+        Some(ir::Expression::Constant(ir::Value::I32(_))) => None,
+        _ => Some(Diagnostic::error(
+            metadata,
+            format!("the ID for `{}` needs to be a constant", SYSCALL),
+        )),
     }
+}
 
-    true
+/// Whether or not the Statements always end in a Return, regardless of
+/// which Path is taken through them
+pub fn returns(statements: &[ir::Statement]) -> bool {
+    match statements.last() {
+        Some(ir::Statement::Return(_)) => true,
+        Some(ir::Statement::If(_, inner, other)) => returns(inner) && returns(other),
+        _ => false,
+    }
 }
 
-pub fn validate(ir: &[ir::Function]) -> bool {
-    for func in ir.iter() {
-        if !validate_func(func) {
-            return false;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> TokenMetadata {
+        TokenMetadata {
+            file_name: "test".to_owned(),
+            line: 1,
+            column: 1,
         }
     }
 
-    true
+    #[test]
+    fn assign_to_array() {
+        let target = ir::DataType::Array(Box::new(ir::DataType::I32), 4);
+        let value = ir::Expression::Constant(ir::Value::I32(0));
+
+        let diagnostic = assignment(&metadata(), &target, &value).unwrap();
+        assert_eq!("array type `int[4]` is not assignable", diagnostic.message);
+    }
+
+    #[test]
+    fn call_argument_count() {
+        let signature = Signature {
+            returns: ir::DataType::I32,
+            arguments: vec![ir::DataType::I32, ir::DataType::I32],
+            convention: None,
+        };
+        let arguments = vec![ir::Expression::Constant(ir::Value::I32(0))];
+
+        let diagnostics = call(&metadata(), "add", &signature, &arguments);
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(
+            "function `add` takes 2 arguments but 1 was supplied",
+            diagnostics[0].message
+        );
+    }
+
+    #[test]
+    fn returns_in_all_branches() {
+        let condition = ir::Condition::Compare(
+            ir::Comparison::Equal,
+            ir::Expression::Constant(ir::Value::I32(0)),
+            ir::Expression::Constant(ir::Value::I32(0)),
+        );
+        let ret = || ir::Statement::Return(ir::Expression::Constant(ir::Value::I32(0)));
+
+        assert!(returns(&[ir::Statement::If(
+            condition.clone(),
+            vec![ret()],
+            vec![ret()]
+        )]));
+        assert!(!returns(&[ir::Statement::If(
+            condition,
+            vec![ret()],
+            vec![]
+        )]));
+        assert!(!returns(&[]));
+    }
 }
@@ -0,0 +1,289 @@
+use crate::ir;
+
+/// Determines the Datatype of the Value the given Expression evaluates to,
+/// by applying the usual arithmetic Conversions of C to Operations
+pub fn exp_type(exp: &ir::Expression) -> ir::DataType {
+    match exp {
+        ir::Expression::Constant(ir::Value::I32(_)) => ir::DataType::I32,
+        ir::Expression::Constant(ir::Value::U32(_)) => ir::DataType::U32,
+        ir::Expression::Constant(ir::Value::Short(_)) => ir::DataType::I16,
+        ir::Expression::Constant(ir::Value::UShort(_)) => ir::DataType::U16,
+        ir::Expression::Variable(var) => match &var.ty {
+            ir::DataType::Array(inner, _) => ir::DataType::Ptr(inner.clone()),
+            other => other.clone(),
+        },
+        ir::Expression::Reference(var) => ir::DataType::Ptr(Box::new(var.ty.clone())),
+        ir::Expression::Dereference(inner) => match exp_type(inner) {
+            // Arrays are used as a Pointer to their first Element
+            ir::DataType::Ptr(target) => match *target {
+                ir::DataType::Array(element, _) => ir::DataType::Ptr(element),
+                other => other,
+            },
+            _ => ir::DataType::U32,
+        },
+        ir::Expression::Indexed(root, _) => exp_type(root),
+        ir::Expression::Member(root, name) => {
+            let member_type = match exp_type(root) {
+                ir::DataType::Ptr(target) => member(&target, name),
+                _ => None,
+            };
+            // Invalid Member-Accesses are already reported, so they are
+            // treated as an `int` to avoid any further Errors
+            ir::DataType::Ptr(Box::new(member_type.unwrap_or(ir::DataType::I32)))
+        }
+        // Shifts keep the Type of the shifted Value
+        ir::Expression::Operation(ir::OP::ShiftLeft, parts)
+        | ir::Expression::Operation(ir::OP::ShiftRight, parts) => match parts.first() {
+            Some(value) => promote(exp_type(value)),
+            None => ir::DataType::I32,
+        },
+        // The Difference of two Pointers is the Number of Elements between them
+        ir::Expression::Operation(ir::OP::Substract, parts)
+            if parts.len() == 2 && parts.iter().all(|part| is_pointer(&exp_type(part))) =>
+        {
+            ir::DataType::I32
+        }
+        ir::Expression::Operation(_, parts) => parts
+            .iter()
+            .map(exp_type)
+            .fold(ir::DataType::I32, |left, right| arithmetic(&left, &right)),
+        ir::Expression::Call(_, _, return_type) => return_type.clone(),
+        ir::Expression::Condition(_) => ir::DataType::I32,
+        ir::Expression::StringLiteral(_) => ir::DataType::Ptr(Box::new(ir::DataType::I8)),
+        ir::Expression::BitNot(inner) => promote(exp_type(inner)),
+        ir::Expression::Increment(address, _, _) => match exp_type(address) {
+            ir::DataType::Ptr(target) => *target,
+            _ => ir::DataType::U32,
+        },
+        ir::Expression::Empty => ir::DataType::Void,
+    }
+}
+
+/// The Datatype of the Member with the given Name in a Struct or Union
+pub fn member(tmp: &ir::DataType, name: &str) -> Option<ir::DataType> {
+    match tmp {
+        ir::DataType::Struct(_, members) | ir::DataType::Union(_, members) => members
+            .iter()
+            .find(|(member_name, _)| member_name == name)
+            .map(|(_, member_type)| member_type.clone()),
+        _ => None,
+    }
+}
+
+/// Applies the Integer-Promotion to the Datatype of an Operand, so every
+/// Integer smaller than an `int` becomes an `int`
+pub fn promote(tmp: ir::DataType) -> ir::DataType {
+    match tmp {
+        ir::DataType::U32 | ir::DataType::Ptr(_) => tmp,
+        _ => ir::DataType::I32,
+    }
+}
+
+/// Applies the usual arithmetic Conversions to the Datatypes of the two
+/// Operands and returns the common Type the Operation is performed in.
+/// As `int` and `unsigned int` are the only promoted Types, the Operation
+/// is unsigned as soon as one of the Operands is unsigned
+///
+/// Pointer-Arithmetic keeps the Type of the Pointer
+pub fn arithmetic(left: &ir::DataType, right: &ir::DataType) -> ir::DataType {
+    match (promote(left.clone()), promote(right.clone())) {
+        (ptr @ ir::DataType::Ptr(_), _) | (_, ptr @ ir::DataType::Ptr(_)) => ptr,
+        (ir::DataType::U32, _) | (_, ir::DataType::U32) => ir::DataType::U32,
+        _ => ir::DataType::I32,
+    }
+}
+
+/// Whether or not the given Datatype represents signed Values
+pub fn is_signed(tmp: &ir::DataType) -> bool {
+    matches!(
+        tmp,
+        ir::DataType::I32 | ir::DataType::I16 | ir::DataType::I8
+    )
+}
+
+/// Whether or not the given Datatype is a Pointer
+pub fn is_pointer(tmp: &ir::DataType) -> bool {
+    matches!(tmp, ir::DataType::Ptr(_))
+}
+
+/// Whether or not the given Datatype is any kind of Integer
+pub fn is_integer(tmp: &ir::DataType) -> bool {
+    matches!(
+        tmp,
+        ir::DataType::I32
+            | ir::DataType::U32
+            | ir::DataType::I16
+            | ir::DataType::U16
+            | ir::DataType::I8
+            | ir::DataType::U8
+    )
+}
+
+/// How a Value is converted to the Datatype it is assigned to
+#[derive(Debug, PartialEq)]
+pub enum Conversion {
+    /// The Value can simply be converted
+    Implicit,
+    /// The Value is converted, but the Conversion is most likely a mistake,
+    /// like turning a Pointer into an Integer
+    Suspicious(String),
+    /// The Value can not be converted at all
+    Invalid(String),
+}
+
+/// Determines how the Value of the given Expression is converted when it
+/// is assigned to a Location of the given Datatype, like for an Assignment,
+/// a Return or an Argument of a Call
+pub fn conversion(target: &ir::DataType, value: &ir::Expression) -> Conversion {
+    let value_type = exp_type(value);
+
+    match (target, &value_type) {
+        (_, ir::DataType::Void) => Conversion::Invalid("void value not ignored".to_owned()),
+        (ir::DataType::Void, _) => Conversion::Implicit,
+        (target, value_type) if is_integer(target) && is_integer(value_type) => {
+            Conversion::Implicit
+        }
+        (ir::DataType::Ptr(target_inner), ir::DataType::Ptr(value_inner)) => {
+            let compatible = target_inner == value_inner
+                || **target_inner == ir::DataType::Void
+                || **value_inner == ir::DataType::Void
+                || matches!(**value_inner, ir::DataType::Array(ref element, _) if element == target_inner);
+            if compatible {
+                Conversion::Implicit
+            } else {
+                Conversion::Suspicious(format!(
+                    "incompatible pointer types converting `{}` to `{}`",
+                    value_type, target
+                ))
+            }
+        }
+        // The Constant 0 is the Null-Pointer
+        (ir::DataType::Ptr(_), _) if is_null(value) => Conversion::Implicit,
+        (ir::DataType::Ptr(_), value_type) if is_integer(value_type) => {
+            Conversion::Suspicious(format!(
+                "implicit conversion from integer `{}` to pointer `{}`",
+                value_type, target
+            ))
+        }
+        (target, ir::DataType::Ptr(_)) if is_integer(target) => Conversion::Suspicious(format!(
+            "implicit conversion from pointer `{}` to integer `{}`",
+            value_type, target
+        )),
+        (target, value_type) if target == value_type => Conversion::Implicit,
+        (target, value_type) => Conversion::Invalid(format!(
+            "mismatched types: expected `{}`, found `{}`",
+            target, value_type
+        )),
+    }
+}
+
+/// Whether or not the Expression is a Null-Pointer Constant
+fn is_null(exp: &ir::Expression) -> bool {
+    matches!(
+        exp,
+        ir::Expression::Constant(ir::Value::I32(0)) | ir::Expression::Constant(ir::Value::U32(0))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signed_operation() {
+        let exp = ir::Expression::Operation(
+            ir::OP::Divide,
+            vec![
+                ir::Expression::Constant(ir::Value::I32(4)),
+                ir::Expression::Variable(ir::Variable::new_str("test", ir::DataType::I16)),
+            ],
+        );
+
+        assert_eq!(ir::DataType::I32, exp_type(&exp));
+        assert!(is_signed(&exp_type(&exp)));
+    }
+    #[test]
+    fn unsigned_operation() {
+        let exp = ir::Expression::Operation(
+            ir::OP::Divide,
+            vec![
+                ir::Expression::Constant(ir::Value::I32(4)),
+                ir::Expression::Constant(ir::Value::U32(2)),
+            ],
+        );
+
+        assert_eq!(ir::DataType::U32, exp_type(&exp));
+        assert!(!is_signed(&exp_type(&exp)));
+    }
+
+    #[test]
+    fn promoted_operation() {
+        let exp = ir::Expression::Operation(
+            ir::OP::Add,
+            vec![
+                ir::Expression::Variable(ir::Variable::new_str("a", ir::DataType::U8)),
+                ir::Expression::Variable(ir::Variable::new_str("b", ir::DataType::U16)),
+            ],
+        );
+
+        assert_eq!(ir::DataType::I32, exp_type(&exp));
+    }
+
+    #[test]
+    fn pointer_difference() {
+        let int_ptr = ir::DataType::Ptr(Box::new(ir::DataType::I32));
+        let p = ir::Expression::Variable(ir::Variable::new_str("p", int_ptr.clone()));
+        let q = ir::Expression::Variable(ir::Variable::new_str("q", int_ptr.clone()));
+        let one = ir::Expression::Constant(ir::Value::I32(1));
+
+        let difference = ir::Expression::Operation(ir::OP::Substract, vec![p.clone(), q]);
+        let moved = ir::Expression::Operation(ir::OP::Substract, vec![p, one]);
+
+        assert_eq!(ir::DataType::I32, exp_type(&difference));
+        assert_eq!(int_ptr, exp_type(&moved));
+    }
+
+    #[test]
+    fn call_has_return_type() {
+        let exp = ir::Expression::Call("test".to_owned(), vec![], ir::DataType::U8);
+
+        assert_eq!(ir::DataType::U8, exp_type(&exp));
+    }
+
+    #[test]
+    fn integer_conversions() {
+        let value = ir::Expression::Constant(ir::Value::I32(300));
+
+        assert_eq!(Conversion::Implicit, conversion(&ir::DataType::U8, &value));
+    }
+
+    #[test]
+    fn pointer_conversions() {
+        let int_ptr = ir::DataType::Ptr(Box::new(ir::DataType::I32));
+        let null = ir::Expression::Constant(ir::Value::I32(0));
+        let address = ir::Expression::Constant(ir::Value::I32(13120));
+        let ptr = ir::Expression::Variable(ir::Variable::new_str("p", int_ptr.clone()));
+
+        assert_eq!(Conversion::Implicit, conversion(&int_ptr, &null));
+        assert!(matches!(
+            conversion(&int_ptr, &address),
+            Conversion::Suspicious(_)
+        ));
+        assert!(matches!(
+            conversion(&ir::DataType::I32, &ptr),
+            Conversion::Suspicious(_)
+        ));
+        assert_eq!(Conversion::Implicit, conversion(&int_ptr, &ptr));
+    }
+
+    #[test]
+    fn struct_conversions() {
+        let point = ir::DataType::Struct(
+            "point".to_owned(),
+            vec![("x".to_owned(), ir::DataType::I32)],
+        );
+        let value = ir::Expression::Constant(ir::Value::I32(1));
+
+        assert!(matches!(conversion(&point, &value), Conversion::Invalid(_)));
+    }
+}
@@ -0,0 +1,180 @@
+use compiler;
+use emulator;
+
+fn read_long(heap: &[u8], address: usize) -> u32 {
+    u32::from_be_bytes([
+        heap[address],
+        heap[address + 1],
+        heap[address + 2],
+        heap[address + 3],
+    ])
+}
+
+#[tokio::test]
+async fn substraction() {
+    let program = "int main() {
+        int* result = 13120;
+        *result = 10 - 2 - 3;
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(5, read_long(&heap, 13120));
+}
+
+#[tokio::test]
+async fn substraction_negative_result() {
+    let program = "int main() {
+        int a = 3;
+        int* result = 13120;
+        *result = a - 5;
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(-2i32 as u32, read_long(&heap, 13120));
+}
+
+#[tokio::test]
+async fn unsigned_division() {
+    let program = "int main() {
+        unsigned int a = 4000000000;
+        unsigned int b = 3;
+        unsigned int* first = 13120;
+        unsigned int* second = 13124;
+        *first = a / b;
+        *second = a / 3000000000;
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(4000000000 / 3, read_long(&heap, 13120));
+    assert_eq!(1, read_long(&heap, 13124));
+}
+
+#[tokio::test]
+async fn signed_division() {
+    let program = "int main() {
+        int a = 100;
+        int* first = 13120;
+        int* second = 13124;
+        int* third = 13128;
+        *first = -17 / 5;
+        *second = a / -7;
+        *third = (0 - a) / (0 - 7);
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(-3i32 as u32, read_long(&heap, 13120));
+    assert_eq!(-14i32 as u32, read_long(&heap, 13124));
+    assert_eq!(14, read_long(&heap, 13128));
+}
+
+#[tokio::test]
+async fn division_precedence() {
+    let program = "int main() {
+        int* result = 13120;
+        *result = 2 + 20 / 4 - 12 / (5 - 2);
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(3, read_long(&heap, 13120));
+}
+
+#[tokio::test]
+async fn large_constants() {
+    let program = "int main() {
+        unsigned int* first = 13120;
+        unsigned int* second = 13124;
+        unsigned int* third = 13128;
+        *first = 2885681152;
+        *second = 305419896 + 40000;
+        *third = 0 - 1000;
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(0xac000000, read_long(&heap, 13120));
+    assert_eq!(0x12345678 + 40000, read_long(&heap, 13124));
+    assert_eq!(-1000i32 as u32, read_long(&heap, 13128));
+}
@@ -4,7 +4,8 @@ use emulator;
 #[tokio::test]
 async fn simple_aray_based_assignemnt() {
     let target_address: usize = 13124;
-    let target_value: u8 = 1;
+    // The int is stored Big-Endian
+    let target_value: [u8; 4] = [0, 0, 0, 1];
     let program = "int store() {
         int* raw_addr = 13120;
         raw_addr[1] = 1;
@@ -15,7 +16,7 @@ async fn simple_aray_based_assignemnt() {
         return 0;
     }";
 
-    let compiled = compiler::compile(program, "test".to_string());
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
 
     let mock_input = emulator::MockInput::new(vec![]);
     let mut memory = emulator::Memory::new();
@@ -29,13 +30,14 @@ async fn simple_aray_based_assignemnt() {
 
     let heap = test_em.clone_heap();
 
-    assert_eq!(target_value, *heap.get(target_address).unwrap());
+    assert_eq!(target_value, heap[target_address..target_address + 4]);
 }
 
 #[tokio::test]
 async fn simple_aray_based_assignemnt_load() {
     let target_address: usize = 13124;
-    let target_value: u8 = 1;
+    // The int is stored Big-Endian
+    let target_value: [u8; 4] = [0, 0, 0, 1];
     let program = "int store() {
         int* raw_addr = 13120;
         raw_addr[1] = 1;
@@ -46,7 +48,7 @@ async fn simple_aray_based_assignemnt_load() {
         return 0;
     }";
 
-    let compiled = compiler::compile(program, "test".to_string());
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
 
     let mock_input = emulator::MockInput::new(vec![]);
     let display = emulator::MockDisplay::new();
@@ -60,20 +62,21 @@ async fn simple_aray_based_assignemnt_load() {
 
     let heap = test_em.clone_heap();
 
-    assert_eq!(target_value, *heap.get(target_address).unwrap());
+    assert_eq!(target_value, heap[target_address..target_address + 4]);
 }
 
 #[tokio::test]
 async fn array_variable() {
     let target_address: usize = 0x80000 - 4 * 5 - 8;
-    let target_value: u8 = 1;
+    // The int is stored Big-Endian
+    let target_value: [u8; 4] = [0, 0, 0, 1];
     let program = "int main() {
         int test[5];
         test[0] = 1;
         return 0;
     }";
 
-    let compiled = compiler::compile(program, "test".to_string());
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
 
     let mock_input = emulator::MockInput::new(vec![]);
     let display = emulator::MockDisplay::new();
@@ -87,5 +90,89 @@ async fn array_variable() {
 
     let heap = test_em.clone_heap();
 
-    assert_eq!(target_value, *heap.get(target_address).unwrap());
+    assert_eq!(target_value, heap[target_address..target_address + 4]);
+}
+
+#[tokio::test]
+async fn pointer_arithmetic() {
+    let target_address: usize = 13120;
+    // a = 2, b = 4, c = 2, d = 4, e = 7
+    let target_value: [u8; 4] = 24247u32.to_be_bytes();
+    let program = "int main() {
+        int values[4];
+        values[0] = 1;
+        values[1] = 2;
+        values[2] = 3;
+        values[3] = 4;
+        int* p = values;
+        p += 1;
+        int a = *p;
+        p = p + 2;
+        int b = *p;
+        p -= 1;
+        int c = *(p - 1);
+        int d = *(1 + p);
+        short halves[3];
+        halves[0] = 5;
+        halves[1] = 6;
+        halves[2] = 7;
+        short* h = halves;
+        h += 2;
+        int e = *h;
+        int* result = 13120;
+        *result = a * 10000 + b * 1000 + c * 100 + d * 10 + e;
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(target_value, heap[target_address..target_address + 4]);
+}
+
+#[tokio::test]
+async fn pointer_difference() {
+    let target_address: usize = 13120;
+    // forward = 3, backward = -3, halves = 2
+    let target_value: [u8; 4] = 299u32.to_be_bytes();
+    let program = "int main() {
+        int values[4];
+        int* first = values;
+        int* last = &values[3];
+        int forward = last - first;
+        int backward = first - last;
+        short halves[4];
+        short* start = halves;
+        short* middle = start + 2;
+        int* result = 13120;
+        *result = forward * 100 + backward + (middle - start);
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(target_value, heap[target_address..target_address + 4]);
 }
@@ -0,0 +1,191 @@
+use compiler;
+use emulator;
+
+fn read_long(heap: &[u8], address: usize) -> u32 {
+    u32::from_be_bytes([
+        heap[address],
+        heap[address + 1],
+        heap[address + 2],
+        heap[address + 3],
+    ])
+}
+
+#[tokio::test]
+async fn bitwise_operators() {
+    let program = "int main() {
+        int a = 12;
+        int b = 10;
+        int* result = 13120;
+        *result = (a & b) * 1000000 + (a | b) * 10000 + (a ^ b) * 100 + (~a + 20) + 17 % 5;
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(8140609, read_long(&heap, 13120));
+}
+
+#[tokio::test]
+async fn shifts() {
+    let program = "int main() {
+        int negative = -64;
+        unsigned int positive = 2147483648;
+        int count = 3;
+        int* first = 13120;
+        *first = 1 << count;
+        int* second = 13124;
+        *second = negative >> count;
+        int* third = 13128;
+        *third = positive >> 31;
+        int* fourth = 13132;
+        *fourth = 1 << 2 + 1;
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(8, read_long(&heap, 13120));
+    assert_eq!(-8i32 as u32, read_long(&heap, 13124));
+    assert_eq!(1, read_long(&heap, 13128));
+    assert_eq!(8, read_long(&heap, 13132));
+}
+
+#[tokio::test]
+async fn compound_assignment() {
+    let program = "int main() {
+        int a = 5;
+        a += 10;
+        a -= 3;
+        a *= 4;
+        a /= 6;
+        a %= 5;
+        a <<= 4;
+        a |= 3;
+        a &= 62;
+        a ^= 1;
+        a >>= 1;
+        int values[2];
+        values[1] = 7;
+        values[1] += a;
+        int* result = 13120;
+        *result = 100;
+        *result -= values[1];
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    // a = 15, 12, 48, 8, 3, 48, 51, 50, 51, 25
+    assert_eq!(68, read_long(&heap, 13120));
+}
+
+#[tokio::test]
+async fn increment_decrement() {
+    let program = "int main() {
+        int a = 5;
+        int b = a++;
+        int c = ++a;
+        int d = a--;
+        --a;
+        a++;
+        unsigned char small = 255;
+        small++;
+        int values[3];
+        int* ptr = &values[0];
+        *ptr++ = 1;
+        *ptr++ = 2;
+        *ptr = 3;
+        for (int i = 0; i < 3; i++) {
+            values[i] += i;
+        }
+        int* result = 13120;
+        *result = a * 1000000 + b * 100000 + c * 10000 + d * 1000 + small * 100
+            + values[0] * 10 + values[2] - values[1];
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    // a = 6, b = 5, c = 7, d = 7, small = 0, values = [1, 3, 5]
+    assert_eq!(6577012, read_long(&heap, 13120));
+}
+
+#[tokio::test]
+async fn rgb565_pixel() {
+    let program = "int main() {
+        unsigned short* pixel = 13120;
+        int red = 31;
+        int green = 21;
+        int blue = 10;
+        *pixel = (red << 11) | (green << 5) | blue;
+        unsigned short color = *pixel;
+        int* result = 13124;
+        *result = ((color >> 11) & 31) * 10000 + ((color >> 5) & 63) * 100 + (color & 31);
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(0xfaaa, u16::from_be_bytes([heap[13120], heap[13121]]));
+    assert_eq!(312110, read_long(&heap, 13124));
+}
@@ -1,6 +1,15 @@
 use compiler;
 use emulator;
 
+fn read_long(heap: &[u8], address: usize) -> u32 {
+    u32::from_be_bytes([
+        heap[address],
+        heap[address + 1],
+        heap[address + 2],
+        heap[address + 3],
+    ])
+}
+
 #[tokio::test]
 async fn simple_condition() {
     let program = "int main() {
@@ -14,7 +23,7 @@ async fn simple_condition() {
         return 0;
     }";
 
-    let compiled = compiler::compile(program, "test".to_string());
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
 
     let mock_input = emulator::MockInput::new(vec![]);
     let display = emulator::MockDisplay::new();
@@ -31,3 +40,162 @@ async fn simple_condition() {
     assert_eq!(1, *heap.get(100).unwrap());
     assert_eq!(0, *heap.get(101).unwrap());
 }
+
+#[tokio::test]
+async fn all_comparisons() {
+    let program = "int check(int a, int b) {
+        int result = 0;
+        if (a == b) {
+            result = result + 1;
+        }
+        if (a != b) {
+            result = result + 2;
+        }
+        if (a < b) {
+            result = result + 4;
+        }
+        if (a <= b) {
+            result = result + 8;
+        }
+        if (a > b) {
+            result = result + 16;
+        }
+        if (a >= b) {
+            result = result + 32;
+        }
+        return result;
+    }
+    int main() {
+        int* result = 13120;
+        *result = check(-1, 2) * 10000 + check(3, 3) * 100 + check(5, 2);
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(144150, read_long(&heap, 13120));
+}
+
+#[tokio::test]
+async fn logical_operators() {
+    let program = "int calls;
+    int touch() {
+        calls = calls + 1;
+        return 1;
+    }
+    int main() {
+        int a = 0;
+        if (a != 0 && touch()) {
+            a = 10;
+        }
+        if (a == 0 || touch()) {
+            a = a + 1;
+        }
+        if (!(a < 1) && touch()) {
+            a = a + 2;
+        }
+        int b = a < 5;
+        int c = !a;
+        int d = a == 3 || calls == 9;
+        int* result = 13120;
+        *result = a * 10000 + calls * 1000 + b * 100 + c * 10 + d;
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(31101, read_long(&heap, 13120));
+}
+
+#[tokio::test]
+async fn else_if() {
+    let program = "int classify(int a) {
+        if (a < 0) {
+            return 1;
+        } else if (a == 0) {
+            return 2;
+        } else if (a < 10) {
+            return 3;
+        } else {
+            return 4;
+        }
+    }
+    int main() {
+        int* result = 13120;
+        *result = classify(-5) * 1000 + classify(0) * 100 + classify(7) * 10 + classify(42);
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(1234, read_long(&heap, 13120));
+}
+
+#[tokio::test]
+async fn without_braces() {
+    let program = "int classify(int a) {
+        if (a < 0)
+            return 1;
+        else if (a == 0)
+            return 2;
+        if (a < 10) return 3;
+        return 4;
+    }
+    int main() {
+        int* result = 13120;
+        *result = classify(-5) * 1000 + classify(0) * 100 + classify(7) * 10 + classify(42);
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(1234, read_long(&heap, 13120));
+}
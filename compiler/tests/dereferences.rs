@@ -10,7 +10,7 @@ async fn simple_dereference() {
         return 0;
     }";
 
-    let compiled = compiler::compile(program, "test".to_string());
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
 
     let mock_input = emulator::MockInput::new(vec![]);
     let display = emulator::MockDisplay::new();
@@ -36,7 +36,7 @@ async fn dereference_expression_1() {
         return 0;
     }";
 
-    let compiled = compiler::compile(program, "test".to_string());
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
 
     let mock_input = emulator::MockInput::new(vec![]);
     let display = emulator::MockDisplay::new();
@@ -63,7 +63,7 @@ async fn dereference_expression_2() {
         return 0;
     }";
 
-    let compiled = compiler::compile(program, "test".to_string());
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
 
     let mock_input = emulator::MockInput::new(vec![]);
     let display = emulator::MockDisplay::new();
@@ -90,7 +90,7 @@ async fn dereference_expression_3() {
         return 0;
     }";
 
-    let compiled = compiler::compile(program, "test".to_string());
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
 
     let mock_input = emulator::MockInput::new(vec![]);
     let display = emulator::MockDisplay::new();
@@ -118,7 +118,7 @@ async fn dereference_expression_4() {
         return 0;
     }";
 
-    let compiled = compiler::compile(program, "test".to_string());
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
 
     let mock_input = emulator::MockInput::new(vec![]);
     let display = emulator::MockDisplay::new();
@@ -0,0 +1,523 @@
+use compiler::diagnostic::Level;
+
+#[test]
+fn missing_expression() {
+    let program = "int main() {
+    int a = 1;
+    a = ;
+    return a;
+}";
+
+    let diagnostics = compiler::compile(program, "test.c".to_string()).unwrap_err();
+
+    assert_eq!(1, diagnostics.len());
+    let diagnostic = &diagnostics[0];
+    assert_eq!(Level::Error, diagnostic.level);
+    assert_eq!("test.c", diagnostic.file_name);
+    assert_eq!(3, diagnostic.line);
+    assert_eq!(9, diagnostic.column);
+    assert_eq!("unexpected `;`", diagnostic.message);
+}
+
+#[test]
+fn unexpected_end_of_file() {
+    let program = "int main() {
+    return 0;";
+
+    let diagnostics = compiler::compile(program, "test.c".to_string()).unwrap_err();
+
+    assert_eq!(1, diagnostics.len());
+    assert_eq!("unexpected end of file", diagnostics[0].message);
+    assert_eq!(2, diagnostics[0].line);
+}
+
+#[test]
+fn invalid_character_literal() {
+    let program = "int main() {
+    char c = 'ab';
+    return c;
+}";
+
+    let diagnostics = compiler::compile(program, "test.c".to_string()).unwrap_err();
+
+    assert_eq!(1, diagnostics.len());
+    assert_eq!("invalid character literal", diagnostics[0].message);
+    assert_eq!(2, diagnostics[0].line);
+    assert_eq!(14, diagnostics[0].column);
+}
+
+#[test]
+fn undeclared_variable() {
+    let program = "int main() {
+    int a = b;
+    return a;
+}";
+
+    let diagnostics = compiler::compile(program, "test.c".to_string()).unwrap_err();
+
+    assert_eq!(1, diagnostics.len());
+    assert_eq!(
+        "cannot find value `b` in this scope",
+        diagnostics[0].message
+    );
+    assert_eq!(2, diagnostics[0].line);
+    assert_eq!(13, diagnostics[0].column);
+}
+
+#[test]
+fn duplicate_variable() {
+    let program = "int main(int a) {
+    int b;
+    int a;
+    int b = 2;
+    return b;
+}";
+
+    let diagnostics = compiler::compile(program, "test.c".to_string()).unwrap_err();
+
+    let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(vec!["redefinition of `a`", "redefinition of `b`"], messages);
+}
+
+#[test]
+fn block_scopes() {
+    // Every Block and Loop has its own Scope, which can shadow the outer
+    // ones, only a Redefinition in the same Scope is an Error
+    let program = "int x;
+int main(int a) {
+    for (int i = 0; i < 2; i++) {}
+    for (int i = 0; i < 2; i++) {
+        int a;
+    }
+    {
+        char x;
+        int y;
+    }
+    {
+        short y;
+        int y;
+    }
+    return x;
+}";
+
+    let diagnostics = compiler::compile(program, "test.c".to_string()).unwrap_err();
+
+    let messages: Vec<(usize, &str)> = diagnostics
+        .iter()
+        .map(|d| (d.line, d.message.as_str()))
+        .collect();
+    assert_eq!(vec![(13, "redefinition of `y`")], messages);
+}
+
+#[test]
+fn call_arguments() {
+    let program = "int add(int a, int b) {
+    return a + b;
+}
+struct point {
+    int x;
+};
+int main() {
+    struct point p;
+    add(1);
+    return add(p, 2);
+}";
+
+    let diagnostics = compiler::compile(program, "test.c".to_string()).unwrap_err();
+
+    assert_eq!(2, diagnostics.len());
+    assert_eq!(
+        "function `add` takes 2 arguments but 1 was supplied",
+        diagnostics[0].message
+    );
+    assert_eq!(9, diagnostics[0].line);
+    assert_eq!(
+        "mismatched types: expected `int`, found `struct point`",
+        diagnostics[1].message
+    );
+}
+
+#[test]
+fn undeclared_function() {
+    let program = "int main() {
+    return missing();
+}";
+
+    let diagnostics = compiler::compile(program, "test.c".to_string()).unwrap_err();
+
+    assert_eq!(1, diagnostics.len());
+    assert_eq!(
+        "cannot find function `missing` in this scope",
+        diagnostics[0].message
+    );
+}
+
+#[test]
+fn function_attributes() {
+    // The Definition inherits the Convention of the Prototype, only an
+    // explicitly different one conflicts
+    let program = "__attribute__((stackcall)) int add(int a, int b);
+int add(int a, int b) {
+    return a + b;
+}
+__attribute__((renesas)) int add(int a, int b);
+__attribute__((noinline, unknown)) int main() {
+    return add(1, 2);
+}";
+
+    let diagnostics = compiler::compile(program, "test.c".to_string()).unwrap_err();
+
+    let messages: Vec<(Level, usize, &str)> = diagnostics
+        .iter()
+        .map(|d| (d.level.clone(), d.line, d.message.as_str()))
+        .collect();
+    assert_eq!(
+        vec![
+            (Level::Error, 5, "conflicting types for `add`"),
+            (Level::Warning, 6, "unknown attribute `unknown` ignored"),
+        ],
+        messages
+    );
+}
+
+#[test]
+fn assignment_to_array() {
+    let program = "int main() {
+    int values[4];
+    values = 0;
+    return 0;
+}";
+
+    let diagnostics = compiler::compile(program, "test.c".to_string()).unwrap_err();
+
+    assert_eq!(1, diagnostics.len());
+    assert_eq!(
+        "array type `int[4]` is not assignable",
+        diagnostics[0].message
+    );
+    assert_eq!(3, diagnostics[0].line);
+}
+
+#[test]
+fn unsupported_declarations() {
+    let program = "int missing[];
+char name[2] = \"long\";
+int values[1] = {1, 2};
+struct point {
+    int x;
+    int x;
+};
+int main() {
+    switch (1) {
+        case 1:
+            break;
+        case 1:
+            break;
+    }
+    return 0;
+}";
+
+    let diagnostics = compiler::compile(program, "test.c".to_string()).unwrap_err();
+
+    let messages: Vec<(usize, &str)> = diagnostics
+        .iter()
+        .map(|d| (d.line, d.message.as_str()))
+        .collect();
+    assert_eq!(
+        vec![
+            (1, "array size missing in `missing`"),
+            (2, "initializer-string for array of size 2 is too long"),
+            (3, "excess elements in initializer for array of size 1"),
+            (6, "duplicate member `x`"),
+            (12, "duplicate case value `1`"),
+        ],
+        messages
+    );
+}
+
+#[test]
+fn array_sizes() {
+    let program = "int table[0];
+struct buffer {
+    char data[-4];
+};
+int main() {
+    int a[-1];
+    int b[1073741824];
+    int c[16];
+    return c[0];
+}";
+
+    let diagnostics = compiler::compile(program, "test.c".to_string()).unwrap_err();
+
+    let messages: Vec<(usize, &str)> = diagnostics
+        .iter()
+        .map(|d| (d.line, d.message.as_str()))
+        .collect();
+    assert_eq!(
+        vec![
+            (
+                1,
+                "size of array `table` must be greater than zero (found `0`)"
+            ),
+            (
+                3,
+                "size of array `data` must be greater than zero (found `-4`)"
+            ),
+            (
+                6,
+                "size of array `a` must be greater than zero (found `-1`)"
+            ),
+            (7, "array `b` of type `int[1073741824]` is too large"),
+        ],
+        messages
+    );
+}
+
+#[test]
+fn constant_initializers() {
+    let program = "int step = 5;
+int twice = step * 2;
+int values[2] = {1, step};
+int main() {
+    int local = 1;
+    static int* pointer = &local;
+    return 0;
+}";
+
+    let diagnostics = compiler::compile(program, "test.c".to_string()).unwrap_err();
+
+    let messages: Vec<(usize, &str)> = diagnostics
+        .iter()
+        .map(|d| (d.line, d.message.as_str()))
+        .collect();
+    assert_eq!(
+        vec![
+            (2, "initializer element is not constant"),
+            (3, "initializer element is not constant"),
+            (6, "initializer element is not constant"),
+        ],
+        messages
+    );
+}
+
+#[test]
+fn struct_values() {
+    let program = "struct point {
+    int x;
+};
+int length(struct point p) {
+    return p.x;
+}
+int main() {
+    struct point a;
+    struct point b;
+    a = b;
+    return 0;
+}";
+
+    let diagnostics = compiler::compile(program, "test.c".to_string()).unwrap_err();
+
+    let messages: Vec<(usize, &str)> = diagnostics
+        .iter()
+        .map(|d| (d.line, d.message.as_str()))
+        .collect();
+    assert_eq!(
+        vec![
+            (
+                4,
+                "passing `struct point` by value to `length` is not supported"
+            ),
+            (
+                10,
+                "copying a value of type `struct point` is not supported"
+            ),
+        ],
+        messages
+    );
+}
+
+#[test]
+fn member_access() {
+    let program = "struct point {
+    int x;
+};
+int main() {
+    struct point p;
+    int i = 0;
+    int *ptr = &i;
+    p.x = 1;
+    int a = i.x;
+    int b = p.y;
+    int c = ptr->x;
+    int d = i->x;
+    return;
+}";
+
+    let diagnostics = compiler::compile(program, "test.c".to_string()).unwrap_err();
+
+    let messages: Vec<(usize, usize, &str)> = diagnostics
+        .iter()
+        .map(|d| (d.line, d.column, d.message.as_str()))
+        .collect();
+    assert_eq!(
+        vec![
+            (
+                9,
+                15,
+                "member reference base type `int` is not a structure or union"
+            ),
+            (10, 15, "no member named `y` in `struct point`"),
+            (
+                11,
+                18,
+                "member reference base type `int` is not a structure or union"
+            ),
+            (12, 14, "member reference type `int` is not a pointer"),
+            (13, 5, "non-void function should return a value"),
+        ],
+        messages
+    );
+}
+
+#[test]
+fn jumps_outside_of_loops() {
+    let program = "int main() {
+    int i = 0;
+    while (i < 10) {
+        switch (i) {
+            case 1:
+                continue;
+            default:
+                break;
+        }
+        i++;
+    }
+    break;
+    switch (i) {
+        default:
+            continue;
+    }
+    return i;
+}";
+
+    let diagnostics = compiler::compile(program, "test.c".to_string()).unwrap_err();
+
+    let messages: Vec<(usize, &str)> = diagnostics
+        .iter()
+        .map(|d| (d.line, d.message.as_str()))
+        .collect();
+    assert_eq!(
+        vec![
+            (12, "`break` statement not in loop or switch statement"),
+            (15, "`continue` statement not in loop statement"),
+        ],
+        messages
+    );
+}
+
+#[test]
+fn conversion_warnings() {
+    let program = "int value(int *ptr) {
+    int raw = ptr;
+    if (raw) {
+        return raw;
+    }
+}
+int main() {
+    int *ptr = 13120;
+    int *null = 0;
+    return value(ptr);
+}";
+
+    let (_, warnings) = compiler::compile_with_warnings(program, "test.c".to_string()).unwrap();
+
+    assert!(warnings.iter().all(|w| w.level == Level::Warning));
+    let positions: Vec<(usize, &str)> = warnings
+        .iter()
+        .map(|w| (w.line, w.message.as_str()))
+        .collect();
+    assert_eq!(
+        vec![
+            (
+                2,
+                "implicit conversion from pointer `int*` to integer `int`"
+            ),
+            (
+                6,
+                "non-void function `value` does not return a value in all control paths"
+            ),
+            (
+                8,
+                "implicit conversion from integer `int` to pointer `int*`"
+            ),
+        ],
+        positions
+    );
+}
+
+#[test]
+fn pointer_type_errors() {
+    let program = "int main() {
+    int i = 2;
+    int values[2];
+    int *p = values;
+    int *q = values;
+    int a = *i;
+    int b = i[0];
+    int *c = p * 2;
+    int *d = p + q;
+    p *= 2;
+    *(13120 + i) = *13121;
+    short *s = 0;
+    int e = p - s;
+    return *p;
+}";
+
+    let diagnostics = compiler::compile(program, "test.c".to_string()).unwrap_err();
+
+    let messages: Vec<(Level, usize, &str)> = diagnostics
+        .iter()
+        .map(|d| (d.level, d.line, d.message.as_str()))
+        .collect();
+    assert_eq!(
+        vec![
+            (
+                Level::Warning,
+                6,
+                "dereferencing integer `int` as an address"
+            ),
+            (
+                Level::Error,
+                7,
+                "subscripted value `int` is not an array or pointer"
+            ),
+            (
+                Level::Error,
+                8,
+                "invalid operands to binary `*` (have `int*` and `int`)"
+            ),
+            (
+                Level::Error,
+                9,
+                "invalid operands to binary `+` (have `int*` and `int*`)"
+            ),
+            (
+                Level::Error,
+                10,
+                "invalid operands to binary `*` (have `int*` and `int`)"
+            ),
+            (
+                Level::Warning,
+                11,
+                "dereferencing integer `int` as an address"
+            ),
+            (
+                Level::Error,
+                13,
+                "invalid operands to binary `-` (have `int*` and `short*`)"
+            ),
+        ],
+        messages
+    );
+}
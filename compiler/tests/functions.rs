@@ -14,7 +14,7 @@ async fn simple_function_no_args() {
         return 0;
     }";
 
-    let compiled = compiler::compile(program, "test".to_string());
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
 
     let mock_input = emulator::MockInput::new(vec![]);
     let display = emulator::MockDisplay::new();
@@ -43,7 +43,7 @@ async fn function_return_value() {
         return 0;
     }";
 
-    let compiled = compiler::compile(program, "test".to_string());
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
 
     let mock_input = emulator::MockInput::new(vec![]);
     let display = emulator::MockDisplay::new();
@@ -74,7 +74,7 @@ async fn function_arguments() {
         return 0;
     }";
 
-    let compiled = compiler::compile(program, "test".to_string());
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
 
     let mock_input = emulator::MockInput::new(vec![]);
     let display = emulator::MockDisplay::new();
@@ -104,7 +104,7 @@ async fn function_arguments_calc() {
         return 0;
     }";
 
-    let compiled = compiler::compile(program, "test".to_string());
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
 
     let mock_input = emulator::MockInput::new(vec![]);
     let display = emulator::MockDisplay::new();
@@ -120,3 +120,116 @@ async fn function_arguments_calc() {
 
     assert_eq!(target_value, *heap.get(target_address).unwrap());
 }
+
+#[tokio::test]
+async fn prototype_and_recursion() {
+    let program = "int factorial(int n);
+    int main() {
+        int *result = 13120;
+        *result = factorial(6);
+        return 0;
+    }
+    int factorial(int n) {
+        if (n <= 1) {
+            return 1;
+        }
+        return n * factorial(n - 1);
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    let result = u32::from_be_bytes([heap[13120], heap[13121], heap[13122], heap[13123]]);
+    assert_eq!(720, result);
+}
+
+#[tokio::test]
+async fn many_values_across_calls() {
+    // More Variables are live across the Calls than there are Registers,
+    // so some of them have to be kept in the Frame
+    let program = "int twice(int value) {
+        return value + value;
+    }
+    int main() {
+        int *result = 13120;
+        int a = 1; int b = 2; int c = 3; int d = 4; int e = 5; int f = 6;
+        int g = 7; int h = 8; int i = 9; int j = 10; int k = 11; int l = 12;
+        int m = 13; int n = 14; int o = 15; int p = 16;
+        int sum = twice(a) + twice(p);
+        sum = sum + a + b + c + d + e + f + g + h + i + j + k + l + m + n + o + p;
+        int local = 0;
+        int *address = &local;
+        *address = 5;
+        char small = 127;
+        small++;
+        *result = sum + local + small;
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    let result = u32::from_be_bytes([heap[13120], heap[13121], heap[13122], heap[13123]]);
+    // 34 from the Calls, 136 from the Sum, 5 through the Pointer and the
+    // Char wrapping around to -128
+    assert_eq!(34 + 136 + 5 - 128, result as i32);
+}
+
+#[tokio::test]
+async fn calling_conventions() {
+    // The last two Arguments do not fit into R4-R7 and are passed on the
+    // Stack, the Function with the Attribute takes all of them on the Stack,
+    // its Definition inherits the Convention from the Prototype
+    let program = "__attribute__((stackcall, noinline)) int legacy(int a, int b, int c, int d, int e, char f);
+    int weighted(int a, char b, int c, int d, short e, int f) {
+        return a + 2 * b + 3 * c + 4 * d + 5 * e + 6 * f;
+    }
+    int legacy(int a, int b, int c, int d, int e, char f) {
+        return weighted(a, b, c, d, e, f) - a;
+    }
+    int main() {
+        int *result = 13120;
+        *result = legacy(1, 2, 3, 4, 5, weighted(1, 1, 1, 1, 1, 1));
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    let result = u32::from_be_bytes([heap[13120], heap[13121], heap[13122], heap[13123]]);
+    // 1 + 4 + 9 + 16 + 25 + 6 * 21 - 1
+    assert_eq!(180, result);
+}
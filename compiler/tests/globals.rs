@@ -0,0 +1,164 @@
+use compiler;
+use emulator;
+
+fn read_long(heap: &[u8], address: usize) -> u32 {
+    u32::from_be_bytes([
+        heap[address],
+        heap[address + 1],
+        heap[address + 2],
+        heap[address + 3],
+    ])
+}
+
+#[tokio::test]
+async fn global_variables() {
+    let program = "struct point {
+        int x;
+        int y;
+    };
+    int counter;
+    int step = 5;
+    struct point origin;
+    void bump() {
+        counter = counter + step;
+    }
+    int main() {
+        bump();
+        bump();
+        origin.y = 3;
+        int* y = &origin.y;
+        int* result = 13120;
+        *result = counter + *y + origin.x;
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    // Fill the Memory after the Code with Garbage, which needs to be
+    // zeroed before main runs
+    for offset in 0..64 {
+        memory.write_byte(
+            emulator::CODE_MAPPING_OFFSET + compiled.len() as u32 + offset,
+            0xff,
+        );
+    }
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(13, read_long(&heap, 13120));
+}
+
+#[tokio::test]
+async fn static_locals() {
+    let program = "int next() {
+        static int calls = 0;
+        static short seen;
+        calls = calls + 1;
+        seen = seen + 2;
+        return calls * 10 + seen;
+    }
+    int main() {
+        next();
+        next();
+        int* result = 13120;
+        *result = next();
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    // Fill the Memory after the Code with Garbage, which needs to be
+    // zeroed before main runs
+    for offset in 0..64 {
+        memory.write_byte(
+            emulator::CODE_MAPPING_OFFSET + compiled.len() as u32 + offset,
+            0xff,
+        );
+    }
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(36, read_long(&heap, 13120));
+}
+
+#[tokio::test]
+async fn initialized_array() {
+    let program = "short table[4] = {1, -2, 3};
+    int main() {
+        int* result = 13120;
+        *result = table[0] + table[1] * 10 + table[2] * 100 + table[3];
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(281, read_long(&heap, 13120));
+}
+
+#[tokio::test]
+async fn address_initializers() {
+    let program = "int step = 5;
+    int values[3] = {1, 2, 3};
+    int* pointer = &step;
+    int* first = values;
+    int* table[2] = {&step, values};
+    char* message = \"hi\";
+    char buffer[] = \"abc\";
+    int main() {
+        int* result = 13120;
+        *result = *pointer + first[2] * 10 + *table[0] * 100 + table[1][1] * 1000;
+        int* text = 13124;
+        *text = message[1] + buffer[2] * 1000 + buffer[3];
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(2535, read_long(&heap, 13120));
+    // 'i' = 105, 'c' = 99 and the 0-Terminator
+    assert_eq!(99105, read_long(&heap, 13124));
+}
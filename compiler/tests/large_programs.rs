@@ -0,0 +1,172 @@
+use compiler;
+use emulator;
+
+/// Repeats the Statement often enough to make the Code bigger than the
+/// Range of a single Branch
+fn repeated(statement: &str) -> String {
+    statement.repeat(300)
+}
+
+#[tokio::test]
+async fn loop_with_large_body() {
+    let target_address: usize = 13120;
+    let target_value: u32 = 3600;
+    let program = format!(
+        "int main() {{
+        int *result = 13120;
+        int value = 0;
+        int i = 0;
+        while (i < 3) {{
+            {}
+            i++;
+        }}
+        *result = value;
+        return 0;
+    }}",
+        // Variables in Registers need only a few Instructions per Addition
+        repeated(&"value = value + 1;\n".repeat(4))
+    );
+
+    let compiled = compiler::compile(&program, "test".to_string()).unwrap();
+    assert!(compiled.len() > 4096);
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    let mut raw = [0; 4];
+    raw.copy_from_slice(&heap[target_address..target_address + 4]);
+    assert_eq!(target_value, u32::from_be_bytes(raw));
+}
+
+#[tokio::test]
+async fn call_over_large_function() {
+    let target_address: usize = 13120;
+    let target_value: u32 = 305;
+    let program = format!(
+        "int add(int value);
+    int main() {{
+        int *result = 13120;
+        *result = add(5);
+        return 0;
+    }}
+    int large(int value) {{
+        {}
+        return value;
+    }}
+    int add(int value) {{
+        return value + 300;
+    }}",
+        repeated("value = value + 1;\n")
+    );
+
+    let compiled = compiler::compile(&program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    let mut raw = [0; 4];
+    raw.copy_from_slice(&heap[target_address..target_address + 4]);
+    assert_eq!(target_value, u32::from_be_bytes(raw));
+}
+
+#[tokio::test]
+async fn switch_with_large_cases() {
+    let target_address: usize = 13120;
+    let target_value: u32 = 302;
+    let program = format!(
+        "int main() {{
+        int *result = 13120;
+        int value = 0;
+        int selector = 2;
+        switch (selector) {{
+            case 0:
+                {}
+                break;
+            case 1:
+                value = 1;
+                break;
+            case 2:
+                value = 2;
+                {}
+                break;
+            case 3:
+                value = 3;
+                break;
+        }}
+        *result = value;
+        return 0;
+    }}",
+        repeated("value = value + 1;\n"),
+        repeated("value = value + 1;\n")
+    );
+
+    let compiled = compiler::compile(&program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    let mut raw = [0; 4];
+    raw.copy_from_slice(&heap[target_address..target_address + 4]);
+    assert_eq!(target_value, u32::from_be_bytes(raw));
+}
+
+#[tokio::test]
+async fn constants_in_large_function() {
+    let target_address: usize = 13120;
+    let target_value: u32 = 300 * 100000;
+    let program = format!(
+        "int main() {{
+        int *result = 13120;
+        int value = 0;
+        {}
+        *result = value;
+        return 0;
+    }}",
+        repeated("value = value + 100000;\n")
+    );
+
+    let compiled = compiler::compile(&program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    let mut raw = [0; 4];
+    raw.copy_from_slice(&heap[target_address..target_address + 4]);
+    assert_eq!(target_value, u32::from_be_bytes(raw));
+}
@@ -0,0 +1,151 @@
+use compiler;
+use emulator;
+use sh::asm;
+
+fn read_long(heap: &[u8], address: usize) -> u32 {
+    u32::from_be_bytes([
+        heap[address],
+        heap[address + 1],
+        heap[address + 2],
+        heap[address + 3],
+    ])
+}
+
+fn object(program: &str, file: &str) -> assembler::object::Object {
+    let (object, _) =
+        compiler::compile_object(program, file.to_owned(), &compiler::Options::default()).unwrap();
+    object
+}
+
+#[tokio::test]
+async fn separate_files() {
+    let main = "int add(int a, int b);
+    int total;
+    int main() {
+        int* result = 13120;
+        total = total + add(3, 4);
+        *result = total;
+        return 0;
+    }";
+    let library = "int offset = 10;
+    int add(int a, int b) {
+        return a + b + offset;
+    }";
+
+    let (compiled, _) = assembler::link::link(
+        vec![object(main, "main.c"), object(library, "library.c")],
+        &[],
+    )
+    .unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(17, read_long(&heap, 13120));
+}
+
+#[tokio::test]
+async fn assembly_routine() {
+    // The Routine follows the Calling-Convention of GCC, so it takes its
+    // Arguments in R4 and R5 and returns its Value in R0
+    let routine = assembler::assemble_object(vec![
+        asm::Instruction::Global("difference".to_owned()),
+        asm::Instruction::Label("difference".to_owned()),
+        asm::Instruction::Mov(0, 4),
+        asm::Instruction::Sub(0, 5),
+        asm::Instruction::Rts,
+        asm::Instruction::Nop,
+    ])
+    .unwrap();
+    let main = "int difference(int a, int b);
+    int main() {
+        int* result = 13120;
+        *result = difference(50, 8);
+        return 0;
+    }";
+
+    let (compiled, _) = assembler::link::link(vec![object(main, "main.c"), routine], &[]).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(42, read_long(&heap, 13120));
+}
+
+#[test]
+fn undefined_function() {
+    let main = "int add(int a, int b);
+    int main() {
+        return add(1, 2);
+    }";
+
+    assert_eq!(
+        Err(vec![assembler::link::LinkError::Undefined(
+            "add".to_owned()
+        )]),
+        assembler::link::link(vec![object(main, "main.c")], &[]).map(|_| ())
+    );
+}
+
+#[tokio::test]
+async fn elf_files() {
+    let main = "int add(int a, int b);
+    int main() {
+        int* result = 13120;
+        *result = add(3, 4);
+        return 0;
+    }";
+    let library = "int add(int a, int b) {
+        return a + b;
+    }";
+
+    // The Objects are passed to the Linker as ELF-Files
+    let objects = vec![object(main, "main.c"), object(library, "library.c")]
+        .iter()
+        .map(|object| {
+            assembler::object::Object::parse(&assembler::elf::write_object(object)).unwrap()
+        })
+        .collect();
+    let (code, symbols) = assembler::link::link(objects, &[]).unwrap();
+
+    let end = symbols.address(assembler::link::BSS_END).unwrap();
+    let executable = assembler::elf::Executable {
+        entry: assembler::BASE_ADDRESS,
+        segments: vec![assembler::elf::Segment {
+            address: assembler::BASE_ADDRESS,
+            data: code,
+            size: end - assembler::BASE_ADDRESS,
+        }],
+    };
+    let executable =
+        assembler::elf::Executable::parse(&assembler::elf::write_executable(&executable, &symbols))
+            .unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut test_em = emulator::Emulator::new_elf(executable, mock_input, display);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(7, read_long(&heap, 13120));
+}
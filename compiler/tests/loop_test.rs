@@ -1,6 +1,15 @@
 use compiler;
 use emulator::{self, Key, Modifier};
 
+fn read_long(heap: &[u8], address: usize) -> u32 {
+    u32::from_be_bytes([
+        heap[address],
+        heap[address + 1],
+        heap[address + 2],
+        heap[address + 3],
+    ])
+}
+
 #[tokio::test]
 async fn simple_loop() {
     let program = "int main() {
@@ -13,7 +22,7 @@ async fn simple_loop() {
         return 0;
     }";
 
-    let compiled = compiler::compile(program, "test".to_string());
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
 
     let mock_input = emulator::MockInput::new(vec![
         (Key::Number(0), Modifier::None),
@@ -44,7 +53,7 @@ async fn nested_loop() {
         return 0;
     }";
 
-    let compiled = compiler::compile(program, "test".to_string());
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
 
     let mock_input = emulator::MockInput::new(vec![
         (Key::Number(0), Modifier::None),
@@ -78,7 +87,7 @@ async fn nested_deref() {
         return 0;
     }";
 
-    let compiled = compiler::compile(program, "test".to_string());
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
 
     let mock_input = emulator::MockInput::new(vec![]);
     let display = emulator::MockDisplay::new();
@@ -94,3 +103,126 @@ async fn nested_deref() {
     let expected = vec![1; 25];
     assert_eq!(&expected, &heap[100..125]);
 }
+
+#[tokio::test]
+async fn do_while() {
+    let program = "int main() {
+        int count = 0;
+        do {
+            count = count + 1;
+        } while (count < 5);
+        do {
+            count = count + 10;
+        } while (0);
+        int* result = 13120;
+        *result = count;
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+    assert_eq!(15, read_long(&heap, 13120));
+}
+
+#[tokio::test]
+async fn break_continue() {
+    let program = "int main() {
+        int sum = 0;
+        for (int i = 0; i < 10; i = i + 1) {
+            if (i == 2) {
+                continue;
+            }
+            if (i == 6) {
+                break;
+            }
+            for (int j = 0; j < 10; j = j + 1) {
+                if (j == 1) {
+                    break;
+                }
+                sum = sum + 100;
+            }
+            sum = sum + i;
+        }
+        int k = 0;
+        while (1) {
+            k = k + 1;
+            if (k < 3) {
+                continue;
+            }
+            break;
+        }
+        do {
+            k = k + 1;
+            continue;
+        } while (k < 5);
+        int* result = 13120;
+        *result = sum * 10 + k;
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+    // i = 0, 1, 3, 4, 5 each add 100 and i
+    assert_eq!(5135, read_long(&heap, 13120));
+}
+
+#[tokio::test]
+async fn block_scopes() {
+    let program = "int x = 1;
+    int main() {
+        int sum = 0;
+        for (int i = 0; i < 3; i++) {
+            sum = sum + i;
+        }
+        for (int i = 10; i < 12; i++) {
+            int x = i * 100;
+            sum = sum + x;
+        }
+        {
+            char sum = 5;
+            x = x + sum;
+        }
+        int* result = 13120;
+        *result = sum * 10 + x;
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+    // The inner Variables shadow the outer ones only in their Block, so
+    // the global `x` is only changed by the last Block
+    assert_eq!((3 + 1000 + 1100) * 10 + 6, read_long(&heap, 13120));
+}
@@ -0,0 +1,182 @@
+use compiler::{self, optimizer::Level};
+use emulator;
+
+fn compile(program: &str, level: Level) -> Vec<u8> {
+    let options = compiler::Options {
+        optimization: level,
+        ..Default::default()
+    };
+    let (compiled, _) =
+        compiler::compile_with_options(program, "test".to_string(), &options).unwrap();
+    compiled
+}
+
+#[tokio::test]
+async fn same_results_on_all_levels() {
+    let target_address: usize = 13120;
+    let program = "int square(int x) {
+        return x * x;
+    }
+    int main() {
+        int values[8];
+        int i;
+        for (i = 0; i < 8; i++) {
+            values[i] = square(i) * 4 - i / 2;
+        }
+
+        int total = 0;
+        int unused = 3 * 7;
+        for (i = 7; i >= 0; i = i - 1) {
+            if (i % 2 == 0) {
+                total = total + values[i];
+            } else {
+                total = total - (values[i] >> 1);
+            }
+        }
+
+        unsigned int mask = 37;
+        *13120 = total;
+        *13121 = (mask / 8) + (mask % 8) * 16;
+        return 0;
+    }";
+
+    for level in [Level::Disabled, Level::Basic, Level::Full].iter() {
+        let compiled = compile(program, *level);
+
+        let mock_input = emulator::MockInput::new(vec![]);
+        let display = emulator::MockDisplay::new();
+        let mut memory = emulator::Memory::new();
+        memory.write_register(15, 0x80000);
+        memory.write_register(14, 0x80000);
+
+        let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+        assert!(test_em.run_completion().await.is_ok());
+
+        let heap = test_em.clone_heap();
+
+        // (0 + 15 + 62 + 141) - (2 + 17 + 49 + 96)
+        assert_eq!(54, heap[target_address], "{:?}", level);
+        assert_eq!(84, heap[target_address + 1], "{:?}", level);
+    }
+}
+
+#[test]
+fn smaller_code() {
+    let program = "int main() {
+        int a = 3;
+        int b = a * 4 + 0;
+        if (b > 100) {
+            b = b / 7;
+        }
+        *13120 = b;
+        return 0;
+    }";
+
+    let unoptimized = compile(program, Level::Disabled);
+    let optimized = compile(program, Level::Basic);
+
+    assert!(optimized.len() < unoptimized.len());
+}
+
+#[tokio::test]
+async fn inline_and_tail_calls() {
+    let target_address: usize = 13120;
+    // Without Tail-Calls every Step of the Recursion would need its own Frame,
+    // which would not fit on the Stack
+    let program = "static inline int twice(int x) {
+        return x + x;
+    }
+    int count(int n, int total) {
+        if (n == 0) {
+            return total;
+        }
+        return count(n - 1, total + 1);
+    }
+    int main() {
+        *13120 = twice(count(200000, 0)) / 10000;
+        *13121 = twice(7);
+        return 0;
+    }";
+
+    for level in [Level::Basic, Level::Full].iter() {
+        let compiled = compile(program, *level);
+
+        let mock_input = emulator::MockInput::new(vec![]);
+        let display = emulator::MockDisplay::new();
+        let mut memory = emulator::Memory::new();
+        memory.write_register(15, 0x80000);
+        memory.write_register(14, 0x80000);
+
+        let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+        assert!(test_em.run_completion().await.is_ok());
+
+        let heap = test_em.clone_heap();
+
+        assert_eq!(40, heap[target_address], "{:?}", level);
+        assert_eq!(14, heap[target_address + 1], "{:?}", level);
+    }
+}
+
+#[tokio::test]
+async fn mutual_tail_calls() {
+    // The Function returning the Address of a Local Variable is in its own
+    // Object, so it is never inlined and shows how deep the Stack got
+    let main = "int depth();
+    int odd(int n);
+    int even(int n) {
+        if (n == 0) {
+            return depth();
+        }
+        return odd(n - 1);
+    }
+    int odd(int n) {
+        if (n == 0) {
+            return depth();
+        }
+        return even(n - 1);
+    }
+    int main() {
+        int *result = 13120;
+        *result = even(20001);
+        return 0;
+    }";
+    let library = "int depth() {
+        int local = 0;
+        int *address = &local;
+        return address;
+    }";
+
+    for level in [Level::Basic, Level::Full].iter() {
+        let options = compiler::Options {
+            optimization: *level,
+            ..Default::default()
+        };
+        let object = |program: &str, file: &str| {
+            let (object, _) = compiler::compile_object(program, file.to_owned(), &options).unwrap();
+            object
+        };
+        let (compiled, _) = assembler::link::link(
+            vec![object(main, "main.c"), object(library, "library.c")],
+            &[],
+        )
+        .unwrap();
+
+        let mock_input = emulator::MockInput::new(vec![]);
+        let display = emulator::MockDisplay::new();
+        let mut memory = emulator::Memory::new();
+        memory.write_register(15, 0x80000);
+        memory.write_register(14, 0x80000);
+
+        let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+        assert!(test_em.run_completion().await.is_ok());
+
+        let heap = test_em.clone_heap();
+
+        // Only the Frames of main, the last Call and depth are on the Stack
+        let address = u32::from_be_bytes([heap[13120], heap[13121], heap[13122], heap[13123]]);
+        assert!(address > 0x80000 - 0x100, "{:?}: {:#x}", level, address);
+    }
+}
@@ -0,0 +1,166 @@
+use std::path::PathBuf;
+
+use compiler;
+use emulator;
+
+/// Creates a new empty Directory for the Files of a single Test
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "prizm-preprocessor-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[tokio::test]
+async fn macros_and_conditionals() {
+    let target_address: usize = 13123;
+    let target_value: u8 = 12;
+    let program = "#define WIDTH 3
+    #define AREA(w, h) ((w) * (h))
+    // The Target can be changed
+    #if AREA(WIDTH, 2) > 4
+    #define RESULT AREA(WIDTH, 4)
+    #else
+    #define RESULT 0
+    #endif
+    int main() {
+        /* store the
+           result */
+        *13123 = RESULT;
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(target_value, *heap.get(target_address).unwrap());
+}
+
+#[tokio::test]
+async fn include_files() {
+    let dir = test_dir("include");
+    let include_dir = dir.join("include");
+    std::fs::create_dir_all(&include_dir).unwrap();
+
+    std::fs::write(
+        include_dir.join("math.h"),
+        "#pragma once\n#define DOUBLE(x) ((x) * 2)\nint triple(int x);\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("values.h"),
+        "#include <math.h>\n#include <math.h>\n#define START 2\n",
+    )
+    .unwrap();
+
+    let target_address: usize = 13123;
+    let target_value: u8 = 12;
+    let program = "#include \"values.h\"
+    #include <math.h>
+    int triple(int x) {
+        return x * 3;
+    }
+    int main() {
+        *13123 = DOUBLE(triple(START));
+        return 0;
+    }";
+
+    let options = compiler::Options {
+        include_paths: vec![include_dir],
+        ..Default::default()
+    };
+    let file = dir.join("main.c").to_string_lossy().to_string();
+    let (compiled, _) = compiler::compile_with_options(program, file, &options).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(target_value, *heap.get(target_address).unwrap());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn error_in_included_file() {
+    let dir = test_dir("error");
+    let header = dir.join("broken.h");
+    std::fs::write(&header, "int first;\nint second = ;\n").unwrap();
+
+    let program = "#include \"broken.h\"
+    int main() {
+        return 0;
+    }";
+
+    let file = dir.join("main.c").to_string_lossy().to_string();
+    let result = compiler::compile(program, file);
+
+    let diagnostics = result.unwrap_err();
+    assert_eq!(1, diagnostics.len());
+    assert_eq!(header.to_string_lossy(), diagnostics[0].file_name);
+    assert_eq!(2, diagnostics[0].line);
+    assert_eq!(14, diagnostics[0].column);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn missing_include() {
+    let program = "#include <missing.h>
+    int main() {
+        return 0;
+    }";
+
+    let result = compiler::compile(program, "test".to_string());
+
+    let diagnostics = result.unwrap_err();
+    assert_eq!(
+        "cannot find include file `missing.h`",
+        diagnostics[0].message
+    );
+    assert_eq!(1, diagnostics[0].line);
+}
+
+#[test]
+fn predefined_macros() {
+    let program = "#ifndef TARGET
+    #error TARGET has to be defined
+    #endif
+    int main() {
+        return TARGET;
+    }";
+
+    let result = compiler::compile(program, "test".to_string());
+    let diagnostics = result.unwrap_err();
+    assert_eq!("#error TARGET has to be defined", diagnostics[0].message);
+    assert_eq!(2, diagnostics[0].line);
+
+    let options = compiler::Options {
+        defines: vec![("TARGET".to_owned(), "1".to_owned())],
+        ..Default::default()
+    };
+    assert!(compiler::compile_with_options(program, "test".to_string(), &options).is_ok());
+}
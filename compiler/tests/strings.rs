@@ -0,0 +1,74 @@
+use compiler;
+use emulator;
+
+fn read_long(heap: &[u8], address: usize) -> u32 {
+    u32::from_be_bytes([
+        heap[address],
+        heap[address + 1],
+        heap[address + 2],
+        heap[address + 3],
+    ])
+}
+
+#[tokio::test]
+async fn string_literal() {
+    let program = "int length(char* text) {
+        int count = 0;
+        while (0 < text[count]) {
+            count = count + 1;
+        }
+        return count;
+    }
+    int main() {
+        char* text = \"Hello\" \" World\\n\";
+        int* result = 13120;
+        *result = length(text) * 1000 + text[4];
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(12111, read_long(&heap, 13120));
+}
+
+#[tokio::test]
+async fn char_types() {
+    let program = "char greeting[] = \"Hi\";
+    int main() {
+        char c = '\\xff';
+        unsigned char u = 200;
+        unsigned char buffer[4];
+        buffer[1] = u;
+        int* result = 13120;
+        *result = c + u + buffer[1] + greeting[1] + greeting[2] * 1000;
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(504, read_long(&heap, 13120));
+}
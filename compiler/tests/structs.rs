@@ -0,0 +1,151 @@
+use compiler;
+use emulator;
+
+fn read_long(heap: &[u8], address: usize) -> u32 {
+    u32::from_be_bytes([
+        heap[address],
+        heap[address + 1],
+        heap[address + 2],
+        heap[address + 3],
+    ])
+}
+
+#[tokio::test]
+async fn struct_members() {
+    let program = "struct point {
+        short x;
+        int y;
+    };
+    int main() {
+        struct point p;
+        p.x = 3;
+        p.y = 4;
+        int* result = 13120;
+        *result = p.x * 10 + p.y;
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(34, read_long(&heap, 13120));
+}
+
+#[tokio::test]
+async fn nested_struct_pointer() {
+    let program = "struct point {
+        short x;
+        int y;
+    };
+    struct sprite {
+        struct point pos;
+        unsigned short frame;
+        int tiles[2];
+    };
+    int update(struct sprite* s, int dx) {
+        s->pos.y = s->pos.y + dx;
+        s->tiles[1] = 7;
+        return s->pos.y;
+    }
+    int main() {
+        struct sprite sprites[3];
+        sprites[2].pos.y = 5;
+        sprites[2].frame = 40000;
+        int moved = update(&sprites[2], 3);
+        int* result = 13120;
+        *result = moved + sprites[2].tiles[1] + sprites[2].frame;
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(40015, read_long(&heap, 13120));
+}
+
+#[tokio::test]
+async fn large_struct_array() {
+    let program = "struct sprite {
+        int x;
+        int y;
+        short tiles[12];
+    };
+    int main() {
+        struct sprite sprites[10];
+        int index = 9;
+        sprites[index].tiles[11] = 21;
+        sprites[0].x = 2;
+        int* result = 13120;
+        *result = sprites[9].tiles[11] * sprites[0].x;
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(42, read_long(&heap, 13120));
+}
+
+#[tokio::test]
+async fn union_members() {
+    let program = "union value {
+        int whole;
+        short halves[2];
+    };
+    int main() {
+        union value v;
+        v.whole = 65538;
+        int* result = 13120;
+        *result = v.halves[0] * 10 + v.halves[1];
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(12, read_long(&heap, 13120));
+}
@@ -0,0 +1,130 @@
+use compiler;
+use emulator;
+
+fn read_long(heap: &[u8], address: usize) -> u32 {
+    u32::from_be_bytes([
+        heap[address],
+        heap[address + 1],
+        heap[address + 2],
+        heap[address + 3],
+    ])
+}
+
+#[tokio::test]
+async fn sparse_switch() {
+    let program = "int pick(int a) {
+        int result = 0;
+        switch (a) {
+            case 1:
+                result = 1;
+                break;
+            case 200:
+                result = 2;
+            case 3000:
+                result = result + 3;
+                break;
+            default:
+                result = 9;
+        }
+        return result;
+    }
+    int main() {
+        int* result = 13120;
+        *result = pick(1) * 1000 + pick(200) * 100 + pick(3000) * 10 + pick(7);
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(1539, read_long(&heap, 13120));
+}
+
+#[tokio::test]
+async fn dense_switch() {
+    let program = "int pick(int a) {
+        switch (a) {
+            case -1:
+                return 8;
+            case 0:
+                return 1;
+            case 1:
+            case 2:
+                return 2;
+            case 4:
+                return 3;
+            default:
+                return 5;
+        }
+        return 0;
+    }
+    int main() {
+        int* result = 13120;
+        *result = pick(-1) * 1000000 + pick(0) * 100000 + pick(1) * 10000
+            + pick(2) * 1000 + pick(3) * 100 + pick(4) * 10 + pick(100);
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(8122535, read_long(&heap, 13120));
+}
+
+#[tokio::test]
+async fn switch_without_default() {
+    let program = "int main() {
+        int value = 5;
+        for (int i = 0; i < 6; i = i + 1) {
+            switch (i) {
+                case 0:
+                case 1:
+                case 2:
+                case 3:
+                    continue;
+            }
+            value = value + i;
+        }
+        int* result = 13120;
+        *result = value;
+        return 0;
+    }";
+
+    let compiled = compiler::compile(program, "test".to_string()).unwrap();
+
+    let mock_input = emulator::MockInput::new(vec![]);
+    let display = emulator::MockDisplay::new();
+    let mut memory = emulator::Memory::new();
+    memory.write_register(15, 0x80000);
+    memory.write_register(14, 0x80000);
+
+    let mut test_em = emulator::Emulator::new_test_raw(mock_input, display, compiled, memory);
+
+    assert!(test_em.run_completion().await.is_ok());
+
+    let heap = test_em.clone_heap();
+
+    assert_eq!(14, read_long(&heap, 13120));
+}
@@ -1,8 +1,11 @@
 use sh::asm::{self, Instruction};
 
 use crate::{
-    general, instructiontype::InstructionType, system, traits::Debugger, Display, Exception, Input,
-    Memory,
+    general,
+    memory::{SR_BL, SR_M, SR_MD, SR_Q, SR_RB, SR_S},
+    system,
+    traits::Debugger,
+    Display, Exception, Input, Memory,
 };
 
 /// Emulates the CPU of the Calculator
@@ -28,23 +31,100 @@ impl CPU {
         self.pc
     }
 
-    fn fetch_instruction(pc: u32, memory: &mut Memory) -> asm::Instruction {
+    fn fetch_instruction(pc: u32, memory: &mut Memory) -> Result<asm::Instruction, Exception> {
         let word_bytes = memory.read_word(pc);
-        asm::Instruction::parse(word_bytes)
-    }
-    fn fetch_instruction_type(pc: u32, memory: &mut Memory) -> InstructionType {
-        InstructionType::parse(&Self::fetch_instruction(pc, memory))
+        asm::Instruction::decode(word_bytes)
+            .map_err(|error| Exception::IllegalInstruction(pc, error))
     }
 
     /// Handles Jump instructions accordingly and queues up the next instruction
-    /// in case of a delayed branch
-    fn handle_jump(&mut self, memory: &mut Memory, destination: u32, delayed: bool) {
+    /// in case of a delayed branch, which must not be illegal in its Delay-Slot
+    fn handle_jump(
+        &mut self,
+        memory: &mut Memory,
+        destination: u32,
+        delayed: bool,
+    ) -> Result<(), Exception> {
         if delayed {
-            let tmp = Self::fetch_instruction(self.pc + 2, memory);
+            let tmp = Self::fetch_instruction(self.pc + 2, memory)?;
+            if tmp.is_slot_illegal() {
+                return Err(Exception::SlotIllegal);
+            }
             self.queued_instr = Some((self.pc + 2, tmp));
         }
 
         self.pc = destination;
+        Ok(())
+    }
+
+    /// Calculates the Address the given Memory-Operand refers to, for an
+    /// Access with the given Size in Bytes. Pre-Decrement and
+    /// Post-Increment Operands also update their Register
+    fn operand_address(&self, operand: &asm::Operand, size: u32, memory: &mut Memory) -> u32 {
+        match operand {
+            asm::Operand::AtRegister(register) => memory.read_register(*register),
+            asm::Operand::PostIncrement(register) => {
+                let addr = memory.read_register(*register);
+                memory.write_register(*register, addr.wrapping_add(size));
+                addr
+            }
+            asm::Operand::PreDecrement(register) => {
+                let addr = memory.read_register(*register).wrapping_sub(size);
+                memory.write_register(*register, addr);
+                addr
+            }
+            asm::Operand::Displacement8(raw_disp) => {
+                let disp = 0x000000FF & (*raw_disp as u32);
+                if size == 4 {
+                    (self.pc & 0xFFFFFFFC) + 4 + disp * 4
+                } else {
+                    self.pc + 4 + disp * size
+                }
+            }
+            asm::Operand::Displacement4Reg(raw_disp, register) => {
+                let disp = 0x0000000F & (*raw_disp as u32);
+                memory.read_register(*register).wrapping_add(disp * size)
+            }
+            asm::Operand::OffsetR0(register) => memory
+                .read_register(0)
+                .wrapping_add(memory.read_register(*register)),
+            asm::Operand::DisplacementGBR(raw_disp) => {
+                let disp = 0x000000FF & (*raw_disp as u32);
+                memory.gbr.wrapping_add(disp * size)
+            }
+            asm::Operand::OffsetR0GBR => memory.read_register(0).wrapping_add(memory.gbr),
+            asm::Operand::Register(_) => unreachable!("Registers have no Address"),
+        }
+    }
+
+    /// Reads the Value of the given Operand with the given Size in Bytes,
+    /// Bytes and Words loaded from Memory are sign-extended
+    fn read_operand(&self, operand: &asm::Operand, size: u32, memory: &mut Memory) -> u32 {
+        if let asm::Operand::Register(register) = operand {
+            return memory.read_register(*register);
+        }
+
+        let addr = self.operand_address(operand, size, memory);
+        match size {
+            1 => general::sign_extend_u8(memory.read_byte(addr)),
+            2 => general::sign_extend_u16(memory.read_word(addr)),
+            _ => memory.read_long(addr),
+        }
+    }
+
+    /// Writes the Value to the given Operand with the given Size in Bytes
+    fn write_operand(&self, operand: &asm::Operand, size: u32, value: u32, memory: &mut Memory) {
+        if let asm::Operand::Register(register) = operand {
+            memory.write_register(*register, value);
+            return;
+        }
+
+        let addr = self.operand_address(operand, size, memory);
+        match size {
+            1 => memory.write_byte(addr, value as u8),
+            2 => memory.write_word(addr, value as u16),
+            _ => memory.write_long(addr, value),
+        };
     }
 
     /// Executes the single given Instruction
@@ -76,95 +156,18 @@ impl CPU {
                 self.pc += 2;
             }
             Instruction::MovB(target, source) => {
-                let value = match source {
-                    asm::Operand::AtRegister(m_register) => {
-                        let raw_value = memory.read_byte(memory.read_register(*m_register));
-                        general::sign_extend_u8(raw_value)
-                    }
-                    asm::Operand::Register(m_register) => {
-                        memory.read_register(*m_register) & 0x000000FF
-                    }
-                    _ => unimplemented!("Unknown Source: {:?}", source),
-                };
-
-                match target {
-                    asm::Operand::Register(n_register) => {
-                        memory.write_register(*n_register, value);
-                    }
-                    asm::Operand::AtRegister(n_register) => {
-                        let target_addr = memory.read_register(*n_register);
-                        memory.write_byte(target_addr, value as u8);
-                    }
-                    _ => unimplemented!("Unknown Target: {:?}", target),
-                };
+                let value = self.read_operand(source, 1, memory);
+                self.write_operand(target, 1, value, memory);
                 self.pc += 2;
             }
             Instruction::MovW(target, source) => {
-                let value = match source {
-                    asm::Operand::Register(m_register) => memory.read_register(*m_register),
-                    asm::Operand::Displacement8(raw_disp) => {
-                        let raw_immediate: u32 = 0x000000FF & (*raw_disp as u32);
-                        let addr = self.pc + 4 + (raw_immediate * 2);
-                        memory.read_word(addr) as u32
-                    }
-                    asm::Operand::OffsetR0(offset_reg) => {
-                        let addr = memory.read_register(0) + memory.read_register(*offset_reg);
-                        general::sign_extend_u16(memory.read_word(addr))
-                    }
-                    _ => unimplemented!("Unknown Source: {:?}", source),
-                };
-
-                match target {
-                    asm::Operand::AtRegister(n_register) => {
-                        let target_addr = memory.read_register(*n_register);
-                        memory.write_word(target_addr, value as u16);
-                    }
-                    asm::Operand::Register(n_register) => {
-                        memory.write_register(*n_register, value);
-                    }
-                    _ => unimplemented!("Unknown Target: {:?}", target),
-                };
+                let value = self.read_operand(source, 2, memory);
+                self.write_operand(target, 2, value, memory);
                 self.pc += 2;
             }
             Instruction::MovL(target, source) => {
-                let value = match source {
-                    asm::Operand::Register(m_register) => memory.read_register(*m_register),
-                    asm::Operand::AtRegister(m_register) => {
-                        memory.read_long(memory.read_register(*m_register))
-                    }
-                    asm::Operand::Displacement8(raw_disp) => {
-                        let raw_immediate: u32 = 0x000000FF & (*raw_disp) as u32;
-                        let addr = (self.pc & 0xFFFFFFFC) + 4 + (raw_immediate * 4);
-                        memory.read_long(addr)
-                    }
-                    asm::Operand::Displacement4Reg(disp, other) => {
-                        let extended = 0x0000000F & (*disp as u32);
-                        let addr = memory.read_register(*other) + extended * 4;
-                        memory.read_long(addr)
-                    }
-                    _ => unimplemented!("Unknown Source: {:?}", source),
-                };
-
-                match target {
-                    asm::Operand::Register(n_register) => {
-                        memory.write_register(*n_register, value);
-                    }
-                    asm::Operand::AtRegister(n_register) => {
-                        let addr = memory.read_register(*n_register);
-                        memory.write_long(addr, value);
-                    }
-                    asm::Operand::OffsetR0(offset_reg) => {
-                        let addr = memory.read_register(0) + memory.read_register(*offset_reg);
-                        memory.write_long(addr, value);
-                    }
-                    asm::Operand::Displacement4Reg(disp, n_register) => {
-                        let extended = 0x0000000F & (*disp as u32);
-                        let addr = memory.read_register(*n_register) + extended * 4;
-                        memory.write_long(addr, value);
-                    }
-                    _ => unimplemented!("Unknown Target: {:?}", target),
-                };
-
+                let value = self.read_operand(source, 4, memory);
+                self.write_operand(target, 4, value, memory);
                 self.pc += 2;
             }
             Instruction::PopOther(n_register, m_register) => {
@@ -190,6 +193,13 @@ impl CPU {
                 memory.write_long(n, memory.read_register(*m_register));
                 self.pc += 2;
             }
+            Instruction::ExtuB(n_register, m_register) => {
+                let prev_value = memory.read_register(*m_register);
+                let extended_value = 0x000000FF & prev_value;
+                memory.write_register(*n_register, extended_value);
+
+                self.pc += 2;
+            }
             Instruction::ExtuW(n_register, m_register) => {
                 let prev_value = memory.read_register(*m_register);
                 let extended_value = 0x0000FFFF & prev_value;
@@ -197,6 +207,42 @@ impl CPU {
 
                 self.pc += 2;
             }
+            Instruction::ExtsB(n_register, m_register) => {
+                let prev_value = memory.read_register(*m_register);
+                let extended_value = general::sign_extend_u8(prev_value as u8);
+                memory.write_register(*n_register, extended_value);
+
+                self.pc += 2;
+            }
+            Instruction::ExtsW(n_register, m_register) => {
+                let prev_value = memory.read_register(*m_register);
+                let extended_value = general::sign_extend_u16(prev_value as u16);
+                memory.write_register(*n_register, extended_value);
+
+                self.pc += 2;
+            }
+            Instruction::SwapB(n_register, m_register) => {
+                let value = memory.read_register(*m_register);
+                let swapped = (value & 0xFFFF0000)
+                    | ((value & 0x000000FF) << 8)
+                    | ((value >> 8) & 0x000000FF);
+                memory.write_register(*n_register, swapped);
+
+                self.pc += 2;
+            }
+            Instruction::SwapW(n_register, m_register) => {
+                let value = memory.read_register(*m_register);
+                memory.write_register(*n_register, value.rotate_left(16));
+
+                self.pc += 2;
+            }
+            Instruction::Xtrct(n_register, m_register) => {
+                let n_value = memory.read_register(*n_register);
+                let m_value = memory.read_register(*m_register);
+                memory.write_register(*n_register, (m_value << 16) | (n_value >> 16));
+
+                self.pc += 2;
+            }
 
             // Shift instructions
             Instruction::Shar(n_register) => {
@@ -234,6 +280,43 @@ impl CPU {
                 memory.write_register(*register, data << 16);
                 self.pc += 2;
             }
+            Instruction::Shal(n_register) => {
+                let value = memory.read_register(*n_register);
+                memory.t = (value & 0x80000000) != 0;
+
+                memory.write_register(*n_register, value << 1);
+                self.pc += 2;
+            }
+            Instruction::Rotl(n_register) => {
+                let value = memory.read_register(*n_register);
+                memory.t = (value & 0x80000000) != 0;
+
+                memory.write_register(*n_register, value.rotate_left(1));
+                self.pc += 2;
+            }
+            Instruction::Rotr(n_register) => {
+                let value = memory.read_register(*n_register);
+                memory.t = (value & 0x00000001) != 0;
+
+                memory.write_register(*n_register, value.rotate_right(1));
+                self.pc += 2;
+            }
+            Instruction::Rotcl(n_register) => {
+                let value = memory.read_register(*n_register);
+                let carry = if memory.t { 0x00000001 } else { 0 };
+                memory.t = (value & 0x80000000) != 0;
+
+                memory.write_register(*n_register, (value << 1) | carry);
+                self.pc += 2;
+            }
+            Instruction::Rotcr(n_register) => {
+                let value = memory.read_register(*n_register);
+                let carry = if memory.t { 0x80000000 } else { 0 };
+                memory.t = (value & 0x00000001) != 0;
+
+                memory.write_register(*n_register, (value >> 1) | carry);
+                self.pc += 2;
+            }
             Instruction::Shld(n_register, m_register) => {
                 let raw_shift = memory.read_register(*m_register);
                 let shift_value: u8 = (raw_shift as u8) & 0x1f;
@@ -241,14 +324,34 @@ impl CPU {
                 let prev_value = memory.read_register(*n_register);
                 let new_value = if (raw_shift & 0x80000000) == 0 {
                     prev_value << shift_value
+                } else if shift_value == 0 {
+                    0
                 } else {
-                    unimplemented!("[Shld] Shift-Right");
+                    prev_value >> (32 - shift_value as u32)
                 };
 
                 memory.write_register(*n_register, new_value);
 
                 self.pc += 2;
             }
+            Instruction::Shad(n_register, m_register) => {
+                let raw_shift = memory.read_register(*m_register);
+                let shift_value: u8 = (raw_shift as u8) & 0x1f;
+
+                let prev_value = memory.read_register(*n_register) as i32;
+                let new_value = if (raw_shift & 0x80000000) == 0 {
+                    prev_value << shift_value
+                } else if shift_value == 0 {
+                    // Shifting right by 32 only leaves the Sign
+                    prev_value >> 31
+                } else {
+                    prev_value >> (32 - shift_value as u32)
+                };
+
+                memory.write_register(*n_register, new_value as u32);
+
+                self.pc += 2;
+            }
             Instruction::Shlr(n_register) => {
                 let value = memory.read_register(*n_register);
                 memory.t = (value & 0x00000001) != 0;
@@ -284,7 +387,7 @@ impl CPU {
             Instruction::Subc(target, other) => {
                 let target_value = memory.read_register(*target);
                 let other_value = memory.read_register(*other);
-                let t_value = if memory.t { 0 } else { 1 };
+                let t_value = if memory.t { 1 } else { 0 };
 
                 let tmp1 = target_value.wrapping_sub(other_value);
                 let tmp0 = target_value;
@@ -298,6 +401,59 @@ impl CPU {
 
                 self.pc += 2;
             }
+            Instruction::Subv(target, other) => {
+                let target_value = memory.read_register(*target) as i32;
+                let other_value = memory.read_register(*other) as i32;
+                let (n_value, overflow) = target_value.overflowing_sub(other_value);
+
+                memory.write_register(*target, n_value as u32);
+                memory.t = overflow;
+
+                self.pc += 2;
+            }
+            Instruction::Addc(target, other) => {
+                let target_value = memory.read_register(*target);
+                let other_value = memory.read_register(*other);
+                let t_value = if memory.t { 1 } else { 0 };
+
+                let (tmp, first_carry) = target_value.overflowing_add(other_value);
+                let (n_value, second_carry) = tmp.overflowing_add(t_value);
+                memory.write_register(*target, n_value);
+                memory.t = first_carry || second_carry;
+
+                self.pc += 2;
+            }
+            Instruction::Addv(target, other) => {
+                let target_value = memory.read_register(*target) as i32;
+                let other_value = memory.read_register(*other) as i32;
+                let (n_value, overflow) = target_value.overflowing_add(other_value);
+
+                memory.write_register(*target, n_value as u32);
+                memory.t = overflow;
+
+                self.pc += 2;
+            }
+            Instruction::Neg(target, source) => {
+                let value = memory.read_register(*source);
+                memory.write_register(*target, 0u32.wrapping_sub(value));
+
+                self.pc += 2;
+            }
+            Instruction::Negc(target, source) => {
+                let value = memory.read_register(*source);
+                let t_value = if memory.t { 1 } else { 0 };
+
+                let tmp = 0u32.wrapping_sub(value);
+                let n_value = tmp.wrapping_sub(t_value);
+                memory.write_register(*target, n_value);
+
+                memory.t = 0 < tmp;
+                if tmp < n_value {
+                    memory.t = true;
+                }
+
+                self.pc += 2;
+            }
             Instruction::Add(target, other) => {
                 let target_value = memory.read_register(*target);
                 let other_value = memory.read_register(*other);
@@ -337,22 +493,149 @@ impl CPU {
 
                 self.pc += 2;
             }
+            Instruction::DmuluL(first, second) => {
+                let first_data = memory.read_register(*first) as u64;
+                let second_data = memory.read_register(*second) as u64;
 
-            // Branch Instructions
-            Instruction::Jmp(register) => {
-                match Self::fetch_instruction_type(self.pc + 2, memory) {
-                    InstructionType::Branch => return Err(Exception::SlotIllegal),
-                    _ => {}
+                let result = first_data * second_data;
+
+                memory.mach = (result >> 32) as u32;
+                memory.macl = result as u32;
+
+                self.pc += 2;
+            }
+            Instruction::MulsW(first, second) => {
+                let first_data = memory.read_register(*first) as i16 as i32;
+                let second_data = memory.read_register(*second) as i16 as i32;
+
+                memory.macl = first_data.wrapping_mul(second_data) as u32;
+
+                self.pc += 2;
+            }
+            Instruction::MuluW(first, second) => {
+                let first_data = memory.read_register(*first) as u16 as u32;
+                let second_data = memory.read_register(*second) as u16 as u32;
+
+                memory.macl = first_data * second_data;
+
+                self.pc += 2;
+            }
+            Instruction::MacL(n_register, m_register) => {
+                let n_address = memory.read_register(*n_register);
+                let n_data = memory.read_long(n_address) as i32 as i64;
+                memory.write_register(*n_register, n_address.wrapping_add(4));
+                let m_address = memory.read_register(*m_register);
+                let m_data = memory.read_long(m_address) as i32 as i64;
+                memory.write_register(*m_register, m_address.wrapping_add(4));
+
+                let mac = (((memory.mach as u64) << 32) | memory.macl as u64) as i64;
+                let mut result = mac.wrapping_add(n_data * m_data);
+                if memory.sr_bit(SR_S) {
+                    // Saturates the Result to 48 Bits
+                    result = result.clamp(-0x0000800000000000, 0x00007FFFFFFFFFFF);
+                }
+
+                memory.mach = (result >> 32) as u32;
+                memory.macl = result as u32;
+
+                self.pc += 2;
+            }
+            Instruction::MacW(n_register, m_register) => {
+                let n_address = memory.read_register(*n_register);
+                let n_data = memory.read_word(n_address) as i16 as i64;
+                memory.write_register(*n_register, n_address.wrapping_add(2));
+                let m_address = memory.read_register(*m_register);
+                let m_data = memory.read_word(m_address) as i16 as i64;
+                memory.write_register(*m_register, m_address.wrapping_add(2));
+
+                if memory.sr_bit(SR_S) {
+                    // Only MACL is used and saturated to 32 Bits, an
+                    // Overflow is signaled by setting the lowest Bit of MACH
+                    let result = (memory.macl as i32 as i64) + n_data * m_data;
+                    if result > i32::MAX as i64 || result < i32::MIN as i64 {
+                        memory.mach |= 0x00000001;
+                    }
+                    memory.macl = result.clamp(i32::MIN as i64, i32::MAX as i64) as u32;
+                } else {
+                    let mac = (((memory.mach as u64) << 32) | memory.macl as u64) as i64;
+                    let result = mac.wrapping_add(n_data * m_data);
+
+                    memory.mach = (result >> 32) as u32;
+                    memory.macl = result as u32;
+                }
+
+                self.pc += 2;
+            }
+            Instruction::Div0s(n_register, m_register) => {
+                let q = (memory.read_register(*n_register) & 0x80000000) != 0;
+                let m = (memory.read_register(*m_register) & 0x80000000) != 0;
+                memory.set_sr_bit(SR_Q, q);
+                memory.set_sr_bit(SR_M, m);
+                memory.t = q != m;
+
+                self.pc += 2;
+            }
+            Instruction::Div0u => {
+                memory.set_sr_bit(SR_Q, false);
+                memory.set_sr_bit(SR_M, false);
+                memory.t = false;
+
+                self.pc += 2;
+            }
+            Instruction::Div1(n_register, m_register) => {
+                let old_q = memory.sr_bit(SR_Q);
+                let m = memory.sr_bit(SR_M);
+                let divisor = memory.read_register(*m_register);
+
+                let prev_value = memory.read_register(*n_register);
+                let q = (prev_value & 0x80000000) != 0;
+                let shifted = (prev_value << 1) | if memory.t { 1 } else { 0 };
+
+                // The Divisor is subtracted if the previous Step did not
+                // change the Sign of the partial Remainder, otherwise it
+                // is added back
+                let (n_value, carry) = if old_q == m {
+                    shifted.overflowing_sub(divisor)
+                } else {
+                    shifted.overflowing_add(divisor)
                 };
+                memory.write_register(*n_register, n_value);
 
+                let q = q ^ carry ^ m;
+                memory.set_sr_bit(SR_Q, q);
+                memory.t = q == m;
+
+                self.pc += 2;
+            }
+
+            // Branch Instructions
+            Instruction::Jmp(register) => {
                 let destination = memory.read_register(*register);
 
-                self.handle_jump(memory, destination, true);
+                self.handle_jump(memory, destination, true)?;
+            }
+            Instruction::Braf(register) => {
+                let destination = self
+                    .pc
+                    .wrapping_add(4)
+                    .wrapping_add(memory.read_register(*register));
+
+                self.handle_jump(memory, destination, true)?;
+            }
+            Instruction::Bsrf(register) => {
+                let destination = self
+                    .pc
+                    .wrapping_add(4)
+                    .wrapping_add(memory.read_register(*register));
+
+                memory.pr = self.pc + 4;
+                self.handle_jump(memory, destination, true)?;
             }
             Instruction::BT(raw_disp) => {
                 let disp = general::sign_extend_u8(*raw_disp) << 1;
                 if memory.t {
-                    self.handle_jump(memory, self.pc + 4 + disp, false);
+                    let target = self.pc.wrapping_add(disp).wrapping_add(4);
+                    self.handle_jump(memory, target, false)?;
                 } else {
                     self.pc += 2;
                 }
@@ -360,7 +643,8 @@ impl CPU {
             Instruction::BTs(raw_disp) => {
                 let disp = general::sign_extend_u8(*raw_disp) << 1;
                 if memory.t {
-                    self.handle_jump(memory, self.pc + 4 + disp, true);
+                    let target = self.pc.wrapping_add(disp).wrapping_add(4);
+                    self.handle_jump(memory, target, true)?;
                 } else {
                     self.pc += 2;
                 }
@@ -368,7 +652,8 @@ impl CPU {
             Instruction::BF(raw_disp) => {
                 let disp = general::sign_extend_u8(*raw_disp) << 1;
                 if !memory.t {
-                    self.handle_jump(memory, self.pc + 4 + disp, false);
+                    let target = self.pc.wrapping_add(disp).wrapping_add(4);
+                    self.handle_jump(memory, target, false)?;
                 } else {
                     self.pc += 2;
                 }
@@ -377,52 +662,32 @@ impl CPU {
                 let disp = general::sign_extend_u8(*raw_disp) << 1;
                 if !memory.t {
                     let target = self.pc.wrapping_add(disp).wrapping_add(4);
-                    self.handle_jump(memory, target, true);
+                    self.handle_jump(memory, target, true)?;
                 } else {
                     self.pc += 2;
                 }
             }
             Instruction::Jsr(m_register) => {
-                match Self::fetch_instruction_type(self.pc + 2, memory) {
-                    InstructionType::Branch => return Err(Exception::SlotIllegal),
-                    _ => {}
-                };
-
                 let destination = memory.read_register(*m_register);
                 memory.pr = self.pc + 4;
-                self.handle_jump(memory, destination, true);
+                self.handle_jump(memory, destination, true)?;
             }
             Instruction::Rts => {
-                match Self::fetch_instruction_type(self.pc + 2, memory) {
-                    InstructionType::Branch => return Err(Exception::SlotIllegal),
-                    _ => {}
-                };
-
                 let destination = memory.pr;
-                self.handle_jump(memory, destination, true);
+                self.handle_jump(memory, destination, true)?;
             }
             Instruction::BRA(raw_disp) => {
                 let disp = general::sign_extend_u12(*raw_disp) << 1;
 
-                match Self::fetch_instruction_type(self.pc + 2, memory) {
-                    InstructionType::Branch => return Err(Exception::SlotIllegal),
-                    _ => {}
-                };
-
                 let target = self.pc.wrapping_add(4).wrapping_add(disp);
-                self.handle_jump(memory, target, true);
+                self.handle_jump(memory, target, true)?;
             }
             Instruction::BSR(raw_disp) => {
                 let disp = general::sign_extend_u12(*raw_disp) << 1;
 
-                match Self::fetch_instruction_type(self.pc + 2, memory) {
-                    InstructionType::Branch => return Err(Exception::SlotIllegal),
-                    _ => {}
-                };
-
                 memory.pr = self.pc + 4;
                 let target = self.pc.wrapping_add(4).wrapping_add(disp);
-                self.handle_jump(memory, target, true);
+                self.handle_jump(memory, target, true)?;
             }
 
             // Comparisons
@@ -442,6 +707,12 @@ impl CPU {
 
                 self.pc += 2;
             }
+            Instruction::CmpGe(n_register, m_register) => {
+                memory.t = (memory.read_register(*n_register) as i32)
+                    >= (memory.read_register(*m_register) as i32);
+
+                self.pc += 2;
+            }
             Instruction::CmpHi(n_register, m_register) => {
                 memory.t = memory.read_register(*n_register) > memory.read_register(*m_register);
 
@@ -466,6 +737,14 @@ impl CPU {
 
                 self.pc += 2;
             }
+            Instruction::Sett => {
+                memory.t = true;
+                self.pc += 2;
+            }
+            Instruction::Clrt => {
+                memory.t = false;
+                self.pc += 2;
+            }
 
             // Control Registers
             Instruction::StsPr(register) => {
@@ -517,6 +796,47 @@ impl CPU {
 
                 self.pc += 2;
             }
+            Instruction::Ldc(control, m_register) => {
+                let value = memory.read_register(*m_register);
+                match control {
+                    asm::ControlRegister::Sr => memory.set_sr(value),
+                    asm::ControlRegister::Gbr => memory.gbr = value,
+                    asm::ControlRegister::Vbr => memory.vbr = value,
+                };
+                self.pc += 2;
+            }
+            Instruction::Stc(n_register, control) => {
+                let value = match control {
+                    asm::ControlRegister::Sr => memory.sr(),
+                    asm::ControlRegister::Gbr => memory.gbr,
+                    asm::ControlRegister::Vbr => memory.vbr,
+                };
+                memory.write_register(*n_register, value);
+                self.pc += 2;
+            }
+
+            // Exceptions and System Control
+            Instruction::Trapa(raw_im) => {
+                memory.ssr = memory.sr();
+                memory.spc = self.pc + 2;
+                memory.sgr = memory.read_register(15);
+                memory.tra = (*raw_im as u32) << 2;
+
+                memory.set_sr_bit(SR_MD, true);
+                memory.set_sr_bit(SR_RB, true);
+                memory.set_sr_bit(SR_BL, true);
+
+                self.pc = memory.vbr.wrapping_add(0x00000100);
+            }
+            Instruction::Sleep => {
+                // There are no Interrupts that could wake the CPU up again,
+                // so execution simply resumes
+                self.pc += 2;
+            }
+            Instruction::Pref(_) => {
+                // The Cache is not emulated, so there is nothing to prefetch
+                self.pc += 2;
+            }
 
             // Logic Instructions
             Instruction::Tst(n_register, m_register) => {
@@ -541,6 +861,61 @@ impl CPU {
 
                 self.pc += 2;
             }
+            Instruction::And(n_register, m_register) => {
+                let n_value = memory.read_register(*n_register);
+                let m_value = memory.read_register(*m_register);
+                let value = n_value & m_value;
+                memory.write_register(*n_register, value);
+
+                self.pc += 2;
+            }
+            Instruction::TstI(raw_im) => {
+                memory.t = (memory.read_register(0) & (*raw_im as u32)) == 0;
+
+                self.pc += 2;
+            }
+            Instruction::XorI(raw_im) => {
+                memory.write_register(0, memory.read_register(0) ^ (*raw_im as u32));
+                self.pc += 2;
+            }
+            Instruction::OrI(raw_im) => {
+                memory.write_register(0, memory.read_register(0) | (*raw_im as u32));
+                self.pc += 2;
+            }
+            Instruction::AndI(raw_im) => {
+                memory.write_register(0, memory.read_register(0) & (*raw_im as u32));
+                self.pc += 2;
+            }
+            Instruction::TstB(target, raw_im) => {
+                let addr = self.operand_address(target, 1, memory);
+                memory.t = (memory.read_byte(addr) & *raw_im) == 0;
+
+                self.pc += 2;
+            }
+            Instruction::XorB(target, raw_im) => {
+                let addr = self.operand_address(target, 1, memory);
+                let value = memory.read_byte(addr) ^ *raw_im;
+                memory.write_byte(addr, value);
+                self.pc += 2;
+            }
+            Instruction::OrB(target, raw_im) => {
+                let addr = self.operand_address(target, 1, memory);
+                let value = memory.read_byte(addr) | *raw_im;
+                memory.write_byte(addr, value);
+                self.pc += 2;
+            }
+            Instruction::AndB(target, raw_im) => {
+                let addr = self.operand_address(target, 1, memory);
+                let value = memory.read_byte(addr) & *raw_im;
+                memory.write_byte(addr, value);
+                self.pc += 2;
+            }
+            Instruction::Not(n_register, m_register) => {
+                let value = !memory.read_register(*m_register);
+                memory.write_register(*n_register, value);
+
+                self.pc += 2;
+            }
 
             Instruction::Nop => {
                 self.pc += 2;
@@ -587,10 +962,305 @@ impl CPU {
                 Ok(())
             }
             _ => {
-                let instr = Self::fetch_instruction(self.pc, memory);
+                let instr = Self::fetch_instruction(self.pc, memory)?;
                 debugger.print_instr(self.pc, &instr);
                 self.execute(instr, memory)
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CODE_MAPPING_OFFSET;
+    use asm::{ControlRegister, Instruction::*};
+
+    /// Places the Instructions at the Start of the Code and executes them
+    /// until the PC leaves them, returning the CPU and the final Memory
+    fn run(instructions: &[Instruction], mut memory: Memory) -> (CPU, Memory) {
+        for (index, instr) in instructions.iter().enumerate() {
+            let address = CODE_MAPPING_OFFSET + index as u32 * 2;
+            memory.write_word(address, u16::from_be_bytes(instr.to_byte()));
+        }
+
+        let end = CODE_MAPPING_OFFSET + instructions.len() as u32 * 2;
+        let mut cpu = CPU::new(CODE_MAPPING_OFFSET);
+        while (CODE_MAPPING_OFFSET..end).contains(&cpu.pc) {
+            let instr = CPU::fetch_instruction(cpu.pc, &mut memory).unwrap();
+            cpu.execute(instr, &mut memory).unwrap();
+        }
+
+        (cpu, memory)
+    }
+
+    fn with_registers(registers: &[(u8, u32)]) -> Memory {
+        let mut memory = Memory::new();
+        memory.write_register(15, 0x80000);
+        for (register, value) in registers {
+            memory.write_register(*register, *value);
+        }
+        memory
+    }
+
+    #[test]
+    fn div0s() {
+        // Negative Dividend and positive Divisor
+        let (_, memory) = run(&[Div0s(1, 0)], with_registers(&[(0, 7), (1, 0xffffff9c)]));
+        assert!(memory.sr_bit(SR_Q));
+        assert!(!memory.sr_bit(SR_M));
+        assert!(memory.t);
+
+        // Both negative
+        let (_, memory) = run(
+            &[Div0s(1, 0)],
+            with_registers(&[(0, 0xfffffff9), (1, 0xffffff9c)]),
+        );
+        assert!(memory.sr_bit(SR_Q));
+        assert!(memory.sr_bit(SR_M));
+        assert!(!memory.t);
+    }
+
+    #[test]
+    fn div0u() {
+        let mut initial = with_registers(&[]);
+        initial.set_sr_bit(SR_Q, true);
+        initial.set_sr_bit(SR_M, true);
+        initial.t = true;
+
+        let (_, memory) = run(&[Div0u], initial);
+        assert!(!memory.sr_bit(SR_Q));
+        assert!(!memory.sr_bit(SR_M));
+        assert!(!memory.t);
+    }
+
+    #[test]
+    fn div1_unsigned() {
+        // R1 / R0 = R1 with a 16 Bit Divisor
+        let mut code = vec![Shll16(0), Div0u];
+        code.extend(std::iter::repeat_n(Div1(1, 0), 16));
+        code.extend([Rotcl(1), ExtuW(1, 1)]);
+
+        let (_, memory) = run(&code, with_registers(&[(0, 7), (1, 100)]));
+        assert_eq!(14, memory.read_register(1));
+
+        let (_, memory) = run(&code, with_registers(&[(0, 0x1234), (1, 0x00ffffff)]));
+        assert_eq!(0x00ffffff / 0x1234, memory.read_register(1));
+    }
+
+    #[test]
+    fn div1_signed() {
+        // R1 / R0 = R1 with a 16 Bit Dividend and Divisor
+        let mut code = vec![
+            Shll16(0),
+            ExtsW(1, 1),
+            Xor(2, 2),
+            Mov(3, 1),
+            Rotcl(3),
+            Subc(1, 2),
+            Div0s(1, 0),
+        ];
+        code.extend(std::iter::repeat_n(Div1(1, 0), 16));
+        code.extend([ExtsW(1, 1), Rotcl(1), Addc(1, 2), ExtsW(1, 1)]);
+
+        let (_, memory) = run(&code, with_registers(&[(0, 7), (1, (-100i32) as u32)]));
+        assert_eq!(-14, memory.read_register(1) as i32);
+
+        let (_, memory) = run(
+            &code,
+            with_registers(&[(0, (-7i32) as u32), (1, (-100i32) as u32)]),
+        );
+        assert_eq!(14, memory.read_register(1) as i32);
+
+        let (_, memory) = run(&code, with_registers(&[(0, (-7i32) as u32), (1, 100)]));
+        assert_eq!(-14, memory.read_register(1) as i32);
+    }
+
+    #[test]
+    fn mac_l() {
+        // 2 * 5 + (-3) * 7
+        let mut initial = with_registers(&[(4, 0x1000), (5, 0x2000)]);
+        initial.write_long(0x1000, 2);
+        initial.write_long(0x1004, (-3i32) as u32);
+        initial.write_long(0x2000, 5);
+        initial.write_long(0x2004, 7);
+
+        let (_, memory) = run(&[MacL(5, 4), MacL(5, 4)], initial);
+        assert_eq!(0xffffffff, memory.mach);
+        assert_eq!((-11i32) as u32, memory.macl);
+        assert_eq!(0x1008, memory.read_register(4));
+        assert_eq!(0x2008, memory.read_register(5));
+    }
+
+    #[test]
+    fn mac_l_saturation() {
+        let setup = |saturate: bool| {
+            let mut memory = with_registers(&[(4, 0x1000), (5, 0x2000)]);
+            memory.write_long(0x1000, 0x7fffffff);
+            memory.write_long(0x2000, 0x7fffffff);
+            memory.set_sr_bit(SR_S, saturate);
+            memory
+        };
+
+        // Without the S bit the full 64 Bits are used
+        let (_, memory) = run(&[MacL(5, 4)], setup(false));
+        assert_eq!(0x3fffffff, memory.mach);
+        assert_eq!(0x00000001, memory.macl);
+
+        // With the S bit the Result is limited to 48 Bits
+        let (_, memory) = run(&[MacL(5, 4)], setup(true));
+        assert_eq!(0x00007fff, memory.mach);
+        assert_eq!(0xffffffff, memory.macl);
+    }
+
+    #[test]
+    fn mac_w() {
+        // 2 * 5 + (-3) * 7
+        let mut initial = with_registers(&[(4, 0x1000), (5, 0x2000)]);
+        initial.write_word(0x1000, 2);
+        initial.write_word(0x1002, (-3i16) as u16);
+        initial.write_word(0x2000, 5);
+        initial.write_word(0x2002, 7);
+
+        let (_, memory) = run(&[MacW(5, 4), MacW(5, 4)], initial);
+        assert_eq!(0xffffffff, memory.mach);
+        assert_eq!((-11i32) as u32, memory.macl);
+        assert_eq!(0x1004, memory.read_register(4));
+        assert_eq!(0x2004, memory.read_register(5));
+    }
+
+    #[test]
+    fn mac_w_saturation() {
+        let setup = |saturate: bool| {
+            let mut memory = with_registers(&[(4, 0x1000), (5, 0x2000)]);
+            memory.write_word(0x1000, 0x7fff);
+            memory.write_word(0x2000, 0x7fff);
+            memory.macl = 0x7fff0000;
+            memory.set_sr_bit(SR_S, saturate);
+            memory
+        };
+
+        // Without the S bit the Result simply continues in MACH
+        let (_, memory) = run(&[MacW(5, 4)], setup(false));
+        assert_eq!(0x00000000, memory.mach);
+        assert_eq!(0xbffe0001, memory.macl);
+
+        // With the S bit MACL saturates and the Overflow is marked in MACH
+        let (_, memory) = run(&[MacW(5, 4)], setup(true));
+        assert_eq!(0x00000001, memory.mach);
+        assert_eq!(0x7fffffff, memory.macl);
+    }
+
+    #[test]
+    fn rotcl() {
+        let (_, memory) = run(&[Clrt, Rotcl(2)], with_registers(&[(2, 0x80000001)]));
+        assert_eq!(0x00000002, memory.read_register(2));
+        assert!(memory.t);
+
+        let (_, memory) = run(&[Sett, Rotcl(2)], with_registers(&[(2, 0x40000001)]));
+        assert_eq!(0x80000003, memory.read_register(2));
+        assert!(!memory.t);
+    }
+
+    #[test]
+    fn rotcr() {
+        let (_, memory) = run(&[Sett, Rotcr(2)], with_registers(&[(2, 0x00000001)]));
+        assert_eq!(0x80000000, memory.read_register(2));
+        assert!(memory.t);
+
+        let (_, memory) = run(&[Clrt, Rotcr(2)], with_registers(&[(2, 0x80000002)]));
+        assert_eq!(0x40000001, memory.read_register(2));
+        assert!(!memory.t);
+    }
+
+    #[test]
+    fn negc() {
+        // Negates the 64 Bit Value R1:R0
+        let code = [Clrt, Negc(0, 0), Negc(1, 1)];
+        let (_, memory) = run(&code, with_registers(&[(0, 1), (1, 0)]));
+        assert_eq!(0xffffffff, memory.read_register(0));
+        assert_eq!(0xffffffff, memory.read_register(1));
+        assert!(memory.t);
+
+        let (_, memory) = run(&code, with_registers(&[(0, 0), (1, 0)]));
+        assert_eq!(0, memory.read_register(0));
+        assert_eq!(0, memory.read_register(1));
+        assert!(!memory.t);
+    }
+
+    #[test]
+    fn subc() {
+        // R1:R0 - R3:R2
+        let code = [Clrt, Subc(0, 2), Subc(1, 3)];
+        let (_, memory) = run(&code, with_registers(&[(0, 0), (1, 1), (2, 1), (3, 0)]));
+        assert_eq!(0xffffffff, memory.read_register(0));
+        assert_eq!(0, memory.read_register(1));
+        assert!(!memory.t);
+
+        let (_, memory) = run(&code, with_registers(&[(0, 0), (1, 0), (2, 1), (3, 0)]));
+        assert_eq!(0xffffffff, memory.read_register(0));
+        assert_eq!(0xffffffff, memory.read_register(1));
+        assert!(memory.t);
+    }
+
+    #[test]
+    fn addv() {
+        let (_, memory) = run(&[Addv(0, 1)], with_registers(&[(0, 0x7fffffff), (1, 1)]));
+        assert_eq!(0x80000000, memory.read_register(0));
+        assert!(memory.t);
+
+        let (_, memory) = run(&[Addv(0, 1)], with_registers(&[(0, 0xffffffff), (1, 1)]));
+        assert_eq!(0, memory.read_register(0));
+        assert!(!memory.t);
+    }
+
+    #[test]
+    fn subv() {
+        let (_, memory) = run(&[Subv(0, 1)], with_registers(&[(0, 0x80000000), (1, 1)]));
+        assert_eq!(0x7fffffff, memory.read_register(0));
+        assert!(memory.t);
+
+        let (_, memory) = run(&[Subv(0, 1)], with_registers(&[(0, 0), (1, 1)]));
+        assert_eq!(0xffffffff, memory.read_register(0));
+        assert!(!memory.t);
+    }
+
+    #[test]
+    fn trapa() {
+        let code = [
+            Ldc(ControlRegister::Sr, 0),
+            Ldc(ControlRegister::Vbr, 1),
+            Trapa(0x20),
+        ];
+        let (cpu, memory) = run(&code, with_registers(&[(0, 0x000000f1), (1, 0x1000)]));
+        assert_eq!(0x80, memory.tra);
+        assert_eq!(0x000000f1, memory.ssr);
+        assert_eq!(CODE_MAPPING_OFFSET + 6, memory.spc);
+        assert_eq!(0x80000, memory.sgr);
+        assert_eq!(0x1100, cpu.pc());
+        assert_eq!(SR_MD | SR_RB | SR_BL | 0x000000f1, memory.sr());
+    }
+
+    #[test]
+    fn ldc_stc() {
+        let code = [
+            Ldc(ControlRegister::Sr, 0),
+            Ldc(ControlRegister::Gbr, 1),
+            Ldc(ControlRegister::Vbr, 2),
+            Stc(3, ControlRegister::Sr),
+            Stc(4, ControlRegister::Gbr),
+            Stc(5, ControlRegister::Vbr),
+        ];
+        let sr = SR_MD | SR_M | SR_Q | 0x00000001;
+        let (_, memory) = run(&code, with_registers(&[(0, sr), (1, 0x1234), (2, 0x5678)]));
+        assert!(memory.t);
+        assert!(memory.sr_bit(SR_M));
+        assert!(memory.sr_bit(SR_Q));
+        assert!(!memory.sr_bit(SR_BL));
+        assert_eq!(0x1234, memory.gbr);
+        assert_eq!(0x5678, memory.vbr);
+        assert_eq!(sr, memory.read_register(3));
+        assert_eq!(0x1234, memory.read_register(4));
+        assert_eq!(0x5678, memory.read_register(5));
+    }
+}
@@ -11,7 +11,8 @@ where
     K: Input,
     D: Display,
 {
-    file: Option<g3a::File>,
+    /// The Size of the Code mapped at the `CODE_MAPPING_OFFSET`
+    code_size: usize,
     cpu: CPU,
     memory: Memory,
     input: K,
@@ -34,7 +35,7 @@ where
         }
 
         Self {
-            file: Some(file),
+            code_size: file.executable_code.len(),
             cpu: CPU::new(CODE_MAPPING_OFFSET),
             memory,
             input,
@@ -43,6 +44,35 @@ where
         }
     }
 
+    /// Loads all the Segments of the ELF-Executable into Memory and starts
+    /// at its Entry-Point
+    pub fn new_elf(executable: sh::elf::Executable, input: K, display: D) -> Self {
+        let mut memory = Memory::new();
+        memory.write_register(15, 0x80000);
+        memory.write_register(14, 0x80000);
+
+        for segment in executable.segments.iter() {
+            for offset in 0..segment.size {
+                let byte = segment.data.get(offset as usize).copied().unwrap_or(0);
+                memory.write_byte(segment.address + offset, byte);
+            }
+        }
+        let code_size = executable
+            .segments
+            .iter()
+            .find(|segment| segment.address == CODE_MAPPING_OFFSET)
+            .map_or(0, |segment| segment.data.len());
+
+        Self {
+            code_size,
+            cpu: CPU::new(executable.entry),
+            memory,
+            input,
+            display,
+            debugger: Box::new(EmptyDebugger::new()),
+        }
+    }
+
     pub fn new_test(input: K, display: D, instructions: Vec<asm::Instruction>) -> Self {
         let mut memory = Memory::new();
         memory.write_register(15, 0x80000);
@@ -58,7 +88,7 @@ where
         }
 
         Self {
-            file: None,
+            code_size: instructions.len() * 2,
             cpu: CPU::new(CODE_MAPPING_OFFSET),
             memory,
             input,
@@ -72,7 +102,7 @@ where
         }
 
         Self {
-            file: None,
+            code_size: instr.len(),
             cpu: CPU::new(CODE_MAPPING_OFFSET),
             memory,
             input,
@@ -104,10 +134,7 @@ where
     /// * p_length: How many instructions to print (each instruction is 2 bytes)
     pub fn print_code(&mut self, p_start: Option<usize>, p_length: Option<usize>) {
         let start = p_start.unwrap_or(0);
-        let length = p_length.unwrap_or_else(|| {
-            let file = self.file.as_ref().unwrap();
-            file.executable_code.len() - 1
-        });
+        let length = p_length.unwrap_or(self.code_size.saturating_sub(1));
 
         for offset in start..length {
             if offset % 2 != 0 {
@@ -11,8 +11,6 @@ pub use memory::Memory;
 mod mock;
 pub use mock::{display::MockDisplay, input::MockInput};
 
-mod instructiontype;
-
 mod cpu;
 pub use cpu::CPU;
 
@@ -36,6 +34,8 @@ mod wasm;
 pub enum Exception {
     UnknownInstruction,
     SlotIllegal,
+    /// The Word at the Address could not be decoded into an Instruction
+    IllegalInstruction(u32, asm::DecodeError),
 }
 
 pub enum DisplayBits {
@@ -22,17 +22,23 @@ async fn main() {
     let cmd = RizmEmulate::from_args();
 
     let raw_file = std::fs::read(cmd.input).unwrap();
-    let file = match g3a::File::parse(&raw_file) {
-        Ok(f) => f,
-        Err(e) => panic!("Error parsing File: {:?}", e),
-    };
 
     let mut breakpoints: HashSet<u32> = HashSet::new();
 
     let mut cli_input = target::CLIInput::new();
     let mut mock_display = emulator::MockDisplay::new();
 
-    let mut em = Emulator::new(file, cli_input, mock_display);
+    let mut em = if sh::elf::is_elf(&raw_file) {
+        match sh::elf::Executable::parse(&raw_file) {
+            Ok(executable) => Emulator::new_elf(executable, cli_input, mock_display),
+            Err(e) => panic!("Error parsing File: {}", e),
+        }
+    } else {
+        match g3a::File::parse(&raw_file) {
+            Ok(file) => Emulator::new(file, cli_input, mock_display),
+            Err(e) => panic!("Error parsing File: {:?}", e),
+        }
+    };
     loop {
         let mut cli_in = String::new();
         stdout().write(&[b'>']).expect("Writing to Stdout");
@@ -10,6 +10,21 @@ pub const HEAP_END: u32 = 0x881FFFFF;
 pub const HEAP_SIZE: u32 = HEAP_END - HEAP_START;
 pub const VIRT_STACK_START: u32 = 0x08100000;
 
+/// The Saturation-Bit in SR, used by the MAC-Instructions
+pub const SR_S: u32 = 0x00000002;
+/// The Q-Bit in SR, used by the Division-Instructions
+pub const SR_Q: u32 = 0x00000100;
+/// The M-Bit in SR, used by the Division-Instructions
+pub const SR_M: u32 = 0x00000200;
+/// The Block-Bit in SR, which masks Exceptions and Interrupts
+pub const SR_BL: u32 = 0x10000000;
+/// The Register-Bank-Bit in SR
+pub const SR_RB: u32 = 0x20000000;
+/// The Processor-Mode-Bit in SR, set in privileged Mode
+pub const SR_MD: u32 = 0x40000000;
+/// The Value of SR after a Reset
+const SR_RESET: u32 = SR_MD | SR_RB | SR_BL | 0x000000F0;
+
 const DISPLAY_WIDTH: usize = 384;
 const DISPLAY_HEIGHT: usize = 216;
 
@@ -19,6 +34,19 @@ pub struct Memory {
     pub t: bool,
     pub macl: u32,
     pub mach: u32,
+    /// The Status-Register, apart from the T bit which is stored on its
+    /// own, see `sr` and `set_sr`
+    sr: u32,
+    pub gbr: u32,
+    pub vbr: u32,
+    /// The saved Status-Register of the last Exception
+    pub ssr: u32,
+    /// The saved PC of the last Exception
+    pub spc: u32,
+    /// The saved R15 of the last Exception
+    pub sgr: u32,
+    /// The Value of the last `TRAPA`-Instruction
+    pub tra: u32,
     heap: Vec<u8>,
     vram: [u8; DISPLAY_HEIGHT * DISPLAY_WIDTH * 2],
 }
@@ -31,6 +59,13 @@ impl Memory {
             t: false,
             macl: 0,
             mach: 0,
+            sr: SR_RESET,
+            gbr: 0,
+            vbr: 0,
+            ssr: 0,
+            spc: 0,
+            sgr: 0,
+            tra: 0,
             heap: Vec::with_capacity(CODE_MAPPING_OFFSET as usize),
             vram: [0; DISPLAY_WIDTH * DISPLAY_HEIGHT * 2],
         }
@@ -44,11 +79,45 @@ impl Memory {
             t: false,
             macl: 0,
             mach: 0,
+            sr: SR_RESET,
+            gbr: 0,
+            vbr: 0,
+            ssr: 0,
+            spc: 0,
+            sgr: 0,
+            tra: 0,
             heap: vec![0; heap_size],
             vram: [0; DISPLAY_WIDTH * DISPLAY_HEIGHT * 2],
         }
     }
 
+    /// The complete Status-Register, including the T bit
+    pub fn sr(&self) -> u32 {
+        if self.t {
+            self.sr | 0x00000001
+        } else {
+            self.sr
+        }
+    }
+    /// Sets the complete Status-Register, including the T bit
+    pub fn set_sr(&mut self, value: u32) {
+        self.t = (value & 0x00000001) != 0;
+        self.sr = value & !0x00000001;
+    }
+
+    /// Whether or not the given Bit in SR is set
+    pub fn sr_bit(&self, bit: u32) -> bool {
+        (self.sr & bit) != 0
+    }
+    /// Sets or clears the given Bit in SR
+    pub fn set_sr_bit(&mut self, bit: u32, value: bool) {
+        if value {
+            self.sr |= bit;
+        } else {
+            self.sr &= !bit;
+        }
+    }
+
     pub fn print_registers(&self) {
         print!("Registers:");
         for reg in self.registers.iter() {
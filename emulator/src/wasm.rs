@@ -1,6 +1,7 @@
 use std::{cell::RefCell, future::Future, pin::Pin, rc::Rc};
 
 use g3a::File;
+use sh::elf;
 use wasm_bindgen::prelude::*;
 
 use crate::{
@@ -30,16 +31,15 @@ pub fn create_emulator(
     canvas_id: String,
     debug: bool,
 ) -> Option<EmulatorWrapper> {
-    let file = match File::parse(&raw_file) {
-        Ok(f) => f,
-        Err(_) => {
-            return None;
-        }
-    };
-
     let (input, input_data) = target::WasmInput::new();
     let display = target::WasmDisplay::new(&canvas_id);
-    let mut emulator = Emulator::new(file, input, display);
+    let mut emulator = if elf::is_elf(&raw_file) {
+        let executable = elf::Executable::parse(&raw_file).ok()?;
+        Emulator::new_elf(executable, input, display)
+    } else {
+        let file = File::parse(&raw_file).ok()?;
+        Emulator::new(file, input, display)
+    };
     if debug {
         emulator.set_debug(Box::new(WasmDebugger::new()));
     }
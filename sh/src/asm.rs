@@ -1,6 +1,13 @@
 mod deserialize;
+mod display;
+mod error;
+mod metadata;
 mod serialize;
 
+pub use display::DisplayAt;
+pub use error::{DecodeError, EncodeError};
+pub use metadata::{Metadata, Resource};
+
 // Referene:
 // http://shared-ptr.com/sh_insns.html
 
@@ -15,8 +22,18 @@ pub enum Operand {
     /// Loads the Value from the Memory-Location specified
     /// by the Value in the given Register
     AtRegister(u8),
-    // TODO
-    // Good Docs to describe this Feature
+    /// Accesses the Memory-Location specified by the Value in the
+    /// given Register and afterwards increments the Register by the
+    /// Size of the Access (`@Rm+`), only usable as a Source
+    PostIncrement(u8),
+    /// Decrements the given Register by the Size of the Access and
+    /// then accesses the Memory-Location specified by its new Value
+    /// (`@-Rn`), only usable as a Target
+    PreDecrement(u8),
+    /// Evaluates to the PC-relative Address (disp * 2 + PC + 4) for
+    /// Words and (disp * 4 + (PC & 0xFFFFFFFC) + 4) for Longs
+    /// Disp is 8-bit zero extended
+    /// Format @(disp, PC)
     Displacement8(u8),
     /// Evaluates to the Address (disp * (1|2|4) + Rn)
     /// Disp is 4-bit zero extended
@@ -25,6 +42,40 @@ pub enum Operand {
     /// Loads the Value from the Address at (R0 + Rn(the
     /// given Register))
     OffsetR0(u8),
+    /// Evaluates to the Address (disp * (1|2|4) + GBR)
+    /// Disp is 8-bit zero extended
+    /// Format @(disp, GBR)
+    DisplacementGBR(u8),
+    /// Evaluates to the Address (R0 + GBR)
+    /// Format @(R0, GBR)
+    OffsetR0GBR,
+}
+
+/// The Sections a Program is split into by the Assembler
+#[derive(Debug, PartialEq, Clone)]
+pub enum Section {
+    /// The actual Code of the Program
+    Text,
+    /// Constant Data, like String-Literals, that is never written to
+    RoData,
+    /// Variables that start out with some initial Value
+    Data,
+    /// Variables that start out zeroed, these take up no
+    /// Space in the final ByteCode
+    Bss,
+}
+
+/// The Control-Registers that can be accessed with `LDC` and `STC`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum ControlRegister {
+    /// The Status-Register, which also contains the T bit
+    Sr,
+    /// The Global-Base-Register, used as the Base for GBR-relative
+    /// Accesses
+    Gbr,
+    /// The Vector-Base-Register, the Base-Address of the Exception-
+    /// and Interrupt-Handlers
+    Vbr,
 }
 
 /// These Instructions are in the Intel Format
@@ -51,26 +102,49 @@ pub enum Instruction {
     MovW(Operand, Operand),
     /// Moves a Long(32bit) from the Source to the Destination
     MovL(Operand, Operand),
+    /// Zero extends the lowest Byte of the Source and stores the
+    /// Result in the Target
+    ExtuB(u8, u8),
     /// Zero extends the Source and stores the Result in the Target
     ExtuW(u8, u8),
+    /// Sign extends the lowest Byte of the Source and stores the
+    /// Result in the Target
+    ExtsB(u8, u8),
+    /// Sign extends the lowest Word of the Source and stores the
+    /// Result in the Target
+    ExtsW(u8, u8),
+    /// Swaps the two lowest Bytes of the Source and stores the
+    /// Result in the Target
+    SwapB(u8, u8),
+    /// Swaps the upper and lower Word of the Source and stores the
+    /// Result in the Target
+    SwapW(u8, u8),
+    /// Stores the middle 32 Bits of the 64-Bit Value Source:Target
+    /// in the Target
+    Xtrct(u8, u8),
     /// Moves the PR-Control-Register into the
     /// given Register
     StsPr(u8),
-    /// Pushes the value in the given Register on the Stack
+    /// Pushes the value in the given Register on the Stack,
+    /// a shorthand for `MovL(PreDecrement(15), Register(_))`
     Push(u8),
-    /// In the Format of (Source, StackRegister)
+    /// In the Format of (Source, StackRegister),
+    /// a shorthand for `MovL(PreDecrement(_), Register(_))`
     PushOther(u8, u8),
     /// Pushes the Source-Byte on the Stack.
-    /// Format (Source, StackRegister)
+    /// Format (Source, StackRegister),
+    /// a shorthand for `MovB(PreDecrement(_), Register(_))`
     PushOtherB(u8, u8),
     /// Pushes the PR-Control-Register onto the Stack
     PushPR, // STS.L
     /// The Register that contains the StackPtr
     PushPROther(u8), // STS.L,
     /// Pops the top most Element from the Stack and stores
-    /// it in the given Register
+    /// it in the given Register,
+    /// a shorthand for `MovL(Register(_), PostIncrement(15))`
     Pop(u8),
-    /// In the Format of (Destination, StackRegister)
+    /// In the Format of (Destination, StackRegister),
+    /// a shorthand for `MovL(Register(_), PostIncrement(_))`
     PopOther(u8, u8),
     /// Pops the top most value from the Stack and stores it
     /// in the PR-Control-Register
@@ -81,13 +155,62 @@ pub enum Instruction {
     /// and checks if the result is 0 and sets the T bit to whether
     /// or not it is equal to 0
     Tst(u8, u8),
+    /// ANDs R0 with the zero-extended Value and sets the T bit to
+    /// whether or not the result is equal to 0
+    TstI(u8),
     /// XORs the given two Registers
     Xor(u8, u8),
+    /// XORs R0 with the zero-extended Value
+    XorI(u8),
     /// ORs the two Registers and stores the result in the
     /// Target Register
     Or(u8, u8),
+    /// ORs R0 with the zero-extended Value and stores the result
+    /// in R0
+    OrI(u8),
+    /// ANDs the two Registers and stores the result in the
+    /// Target Register
+    And(u8, u8),
+    /// ANDs R0 with the zero-extended Value and stores the result
+    /// in R0
+    AndI(u8),
+    /// ANDs the Byte at the Target with the zero-extended Value and
+    /// stores the result back, the Target can only be `OffsetR0GBR`
+    /// Format (target, value)
+    AndB(Operand, u8),
+    /// ORs the Byte at the Target with the zero-extended Value and
+    /// stores the result back, the Target can only be `OffsetR0GBR`
+    /// Format (target, value)
+    OrB(Operand, u8),
+    /// XORs the Byte at the Target with the zero-extended Value and
+    /// stores the result back, the Target can only be `OffsetR0GBR`
+    /// Format (target, value)
+    XorB(Operand, u8),
+    /// ANDs the Byte at the Target with the zero-extended Value and
+    /// sets the T bit to whether or not the result is equal to 0, the
+    /// Target can only be `OffsetR0GBR`
+    /// Format (target, value)
+    TstB(Operand, u8),
+    /// Stores the bitwise Complement of the Source-Register
+    /// in the Target-Register
+    /// Format (target_register, source_register)
+    Not(u8, u8),
+    /// Stores the Two's Complement of the Source-Register in the
+    /// Target-Register
+    /// Format (target_register, source_register)
+    Neg(u8, u8),
+    /// Subtracts the Source-Register and the T bit from 0, stores
+    /// the Result in the Target-Register and the borrow in the T bit
+    /// Format (target_register, source_register)
+    Negc(u8, u8),
     /// Adds the two Registers together
     Add(u8, u8),
+    /// Adds the two Registers and the T bit together, stores the
+    /// Result in the Target-Register and the carry in the T bit
+    Addc(u8, u8),
+    /// Adds the two Registers together and sets the T bit to
+    /// whether or not the (signed) Result overflowed
+    Addv(u8, u8),
     /// Adds the Value directly to the given Register.
     /// The Value will be sign-extended before it is added
     /// so it can only represent values in the Range from
@@ -101,6 +224,9 @@ pub enum Instruction {
     /// Target Register and stores the Result in the Target
     /// Register and stores the borrow in the T-Register
     Subc(u8, u8),
+    /// Subtracts the Source-Register from the Target-Register and
+    /// sets the T bit to whether or not the (signed) Result underflowed
+    Subv(u8, u8),
     /// Multiplies the two Registers together and stores
     /// the resulting value in the MACL Register
     /// Rn + Rm -> MACL
@@ -108,6 +234,31 @@ pub enum Instruction {
     /// Performs 32-Bit multiplication of the Two-Registers
     /// and stores the 64-Bit result into MACH:MACL
     DmulSL(u8, u8),
+    /// Performs unsigned 32-Bit multiplication of the Two-Registers
+    /// and stores the 64-Bit result into MACH:MACL
+    DmuluL(u8, u8),
+    /// Performs signed 16-Bit multiplication of the lower Words of
+    /// the two Registers and stores the result in MACL
+    MulsW(u8, u8),
+    /// Performs unsigned 16-Bit multiplication of the lower Words of
+    /// the two Registers and stores the result in MACL
+    MuluW(u8, u8),
+    /// Multiplies the signed Longs at the Addresses in the two Registers,
+    /// adds the result to MACH:MACL and increments both Registers by 4
+    /// Format (first_register, second_register)
+    MacL(u8, u8),
+    /// Multiplies the signed Words at the Addresses in the two Registers,
+    /// adds the result to MACH:MACL and increments both Registers by 2
+    /// Format (first_register, second_register)
+    MacW(u8, u8),
+    /// Prepares a signed Division of the first Register (Dividend) by
+    /// the second Register (Divisor) by setting up the Q, M and T bits
+    Div0s(u8, u8),
+    /// Prepares an unsigned Division by clearing the Q, M and T bits
+    Div0u,
+    /// Performs a single Step of the Division of the first Register
+    /// by the second Register
+    Div1(u8, u8),
     /// Compares R0 to the given immediate Value after
     /// sign extension of it
     CmpEqI(u8),
@@ -126,6 +277,10 @@ pub enum Instruction {
     /// Decrements the Value in the given Register and then
     /// compares the result to 0
     Dt(u8),
+    /// Sets the T bit to 1
+    Sett,
+    /// Clears the T bit
+    Clrt,
     /// This is not an actual Instruction, but is
     /// used to tell the Assembler where something
     /// starts
@@ -152,6 +307,12 @@ pub enum Instruction {
     /// Jumps to the Address stored in the given Register
     /// and execution will resume there
     Jmp(u8),
+    /// Branches to PC + 4 + the Value in the given Register,
+    /// used to jump into Tables of Branches
+    Braf(u8),
+    /// Stores PC + 4 into PR and branches to PC + 4 + the Value in
+    /// the given Register
+    Bsrf(u8),
     /// This is not an actual Instruction, but a
     /// simplification to deal with Jumps in combination
     /// with the Label-Instruction.
@@ -188,10 +349,30 @@ pub enum Instruction {
     /// Shifts the Value in the Register by 16
     /// to the left
     Shll16(u8),
+    /// Shifts the Value in the Register by 1 to the left and
+    /// stores the bit shifted out in the T bit, same as `Shll`
+    Shal(u8),
+    /// Rotates the Value in the Register by 1 to the left and
+    /// stores the bit rotated out in the T bit
+    Rotl(u8),
+    /// Rotates the Value in the Register by 1 to the right and
+    /// stores the bit rotated out in the T bit
+    Rotr(u8),
+    /// Rotates the Value in the Register and the T bit by 1 to
+    /// the left
+    Rotcl(u8),
+    /// Rotates the Value in the Register and the T bit by 1 to
+    /// the right
+    Rotcr(u8),
     /// Shifts the Value in the Shift-Register by the
     /// amount of bits specified in the Shift-Count-Register
     /// Format (shift_register, shift_count_register)
     Shld(u8, u8),
+    /// Arithmetically shifts the Value in the Shift-Register by
+    /// the amount of bits specified in the Shift-Count-Register,
+    /// a negative Count shifts to the right and keeps the Sign
+    /// Format (shift_register, shift_count_register)
+    Shad(u8, u8),
     /// Shifts the Value in the Register by 1
     /// to the right
     Shlr(u8),
@@ -222,23 +403,147 @@ pub enum Instruction {
     /// the MACH Register
     /// The given Register is used as the StackPtr (usually R15)
     LdsLMach(u8),
+    /// Loads the Value of the given Register into the Control-Register
+    /// Format (control_register, source_register)
+    Ldc(ControlRegister, u8),
+    /// Stores the Value of the Control-Register in the given Register
+    /// Format (target_register, control_register)
+    Stc(u8, ControlRegister),
+    /// Starts the Trap-Exception-Handling with the given Value,
+    /// which is stored (multiplied by 4) in the TRA Register
+    Trapa(u8),
+    /// Puts the CPU into the Power-Down Mode until an Interrupt occurs
+    Sleep,
+    /// Prefetches the Cache-Line at the Address in the given Register
+    Pref(u8),
     /// Used to store some literal value or here not documented instruction
     /// This will simply be returned as is, so the user is responsible for
     /// the correctness of this instruction
     Literal(u8, u8),
+    /// This is not an actual Instruction, but is used
+    /// to tell the Assembler in which Section all the
+    /// following Instructions should be placed.
+    /// Everything before the first Section belongs to
+    /// the Text-Section
+    Section(Section),
+    /// This is not an actual Instruction, but raw Bytes
+    /// that will be placed into the final ByteCode as is
+    Data(Vec<u8>),
+    /// This is not an actual Instruction, but pads the
+    /// current Location with zeroes until it is a multiple
+    /// of the given Number of Bytes
+    Align(u32),
+    /// This is not an actual Instruction, but reserves
+    /// the given Number of zeroed Bytes
+    Space(u32),
+    /// This is not an actual Instruction, but places the
+    /// absolute 32-Bit Address of the Label with the
+    /// given Name into the ByteCode, just like Data
+    LabelAddress(String),
+    /// This is not an actual Instruction, but marks the
+    /// Label with the given Name as visible to other
+    /// Objects, when the Instructions are assembled into
+    /// an Object that is linked later on
+    Global(String),
+    /// This is not an actual Instruction, but a
+    /// simplification to load the Address of a Label
+    /// into the given Register.
+    /// This Instruction will be replaced with a
+    /// PC-relative Load of the Address, which is stored
+    /// in a Literal-Pool placed by the Assembler
+    MovLabel(u8, String),
+    /// This is not an actual Instruction, but a
+    /// simplification to load a 32-Bit Constant into
+    /// the given Register.
+    /// This Instruction will be replaced with a
+    /// PC-relative Load of the Constant, which is stored
+    /// in a Literal-Pool placed by the Assembler
+    MovConst(u8, u32),
+    /// This is not an actual Instruction, but a
+    /// simplification to calculate the Address of a
+    /// Label into R0.
+    /// This Instruction will be replaced with a `MOVA`,
+    /// so the Label has to be 4-Byte aligned and at most
+    /// 1020 Bytes after the Instruction
+    MovALabel(String),
+    /// This is not an actual Instruction, but a
+    /// simplification to load the Word at a Label into
+    /// the given Register.
+    /// This Instruction will be replaced with a
+    /// PC-relative `MOV.W`, so the Label has to be at
+    /// most 510 Bytes after the Instruction
+    LoadWord(u8, String),
+    /// This is not an actual Instruction, but a
+    /// simplification to load the Long at a Label into
+    /// the given Register.
+    /// This Instruction will be replaced with a
+    /// PC-relative `MOV.L`, so the Label has to be 4-Byte
+    /// aligned and at most 1020 Bytes after the Instruction
+    LoadLong(u8, String),
 }
 
 impl Instruction {
     /// Converts the given Instruction into its appropriate
     /// ByteCode Variant that can then be run on the Calculator
-    pub fn to_byte(&self) -> [u8; 2] {
+    pub fn encode(&self) -> Result<[u8; 2], EncodeError> {
         serialize::serialize(self)
     }
 
     /// Parses the given 16-Bit-Instruction
-    pub fn parse(raw: u16) -> Self {
+    pub fn decode(raw: u16) -> Result<Self, DecodeError> {
         deserialize::deserialize(raw)
     }
+
+    /// Converts the Instruction just like `encode`, but panics if the
+    /// Instruction can not be encoded
+    pub fn to_byte(&self) -> [u8; 2] {
+        match self.encode() {
+            Ok(bytes) => bytes,
+            Err(error) => panic!("Could not encode {:?}: {}", self, error),
+        }
+    }
+
+    /// Parses the Instruction just like `decode`, but returns Values that
+    /// are not a known Instruction as a `Literal`
+    pub fn parse(raw: u16) -> Self {
+        let bytes = raw.to_be_bytes();
+        Self::decode(raw).unwrap_or(Instruction::Literal(bytes[0], bytes[1]))
+    }
+
+    /// The Timing, Delay-Slot-Behaviour and the Registers and Flags the
+    /// Instruction reads and writes, `None` if it is not an actual
+    /// Instruction
+    pub fn metadata(&self) -> Option<Metadata> {
+        metadata::metadata(self)
+    }
+
+    /// Whether or not the Instruction after this one is executed in its
+    /// Delay-Slot
+    pub fn has_delay_slot(&self) -> bool {
+        self.metadata().is_some_and(|m| m.delay_slot)
+    }
+
+    /// Whether or not the Instruction raises a Slot-Illegal-Exception
+    /// when it is placed in a Delay-Slot
+    pub fn is_slot_illegal(&self) -> bool {
+        self.metadata().is_some_and(|m| m.slot_illegal)
+    }
+
+    /// The Address of the Target of a relative Branch or of the Value
+    /// loaded by a PC-relative Move, when the Instruction is located at
+    /// the given Address
+    pub fn target(&self, address: u32) -> Option<u32> {
+        display::target(self, address)
+    }
+
+    /// Formats the Instruction, located at the given Address, with the
+    /// absolute Addresses of its Branch-Targets
+    pub fn display_at(&self, address: u32) -> DisplayAt<'_> {
+        DisplayAt {
+            instr: self,
+            address,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -265,4 +570,504 @@ mod tests {
         // Pop R0
         assert_eq!([0x60, 0xf6], Instruction::Pop(0).to_byte());
     }
+    #[test]
+    fn pop_other() {
+        // (R1) -> R2, R1 + 4 -> R1
+        assert_eq!([0x62, 0x16], Instruction::PopOther(2, 1).to_byte());
+        assert_eq!(
+            Instruction::MovL(Operand::Register(2), Operand::PostIncrement(1)),
+            Instruction::parse(0x6216)
+        );
+    }
+    #[test]
+    fn push_other() {
+        // R1 - 4 -> R2, R1 -> (R2)
+        assert_eq!([0x22, 0x16], Instruction::PushOther(1, 2).to_byte());
+        assert_eq!(
+            Instruction::MovL(Operand::PreDecrement(2), Operand::Register(1)),
+            Instruction::parse(0x2216)
+        );
+    }
+    #[test]
+    fn sub() {
+        // R0 - R1 -> R0
+        assert_eq!([0x30, 0x18], Instruction::Sub(0, 1).to_byte());
+        assert_eq!(Instruction::Sub(0, 1), Instruction::parse(0x3018));
+    }
+    #[test]
+    fn and() {
+        // R0 & R1 -> R0
+        assert_eq!([0x20, 0x19], Instruction::And(0, 1).to_byte());
+        assert_eq!(Instruction::And(0, 1), Instruction::parse(0x2019));
+    }
+    #[test]
+    fn not() {
+        // ~R1 -> R0
+        assert_eq!([0x60, 0x17], Instruction::Not(0, 1).to_byte());
+        assert_eq!(Instruction::Not(0, 1), Instruction::parse(0x6017));
+    }
+    #[test]
+    fn shad() {
+        // R0 shifted arithmetically by R1 -> R0
+        assert_eq!([0x40, 0x1c], Instruction::Shad(0, 1).to_byte());
+        assert_eq!(Instruction::Shad(0, 1), Instruction::parse(0x401c));
+    }
+    #[test]
+    fn shld() {
+        // R0 shifted logically by R1 -> R0
+        assert_eq!([0x40, 0x1d], Instruction::Shld(0, 1).to_byte());
+        assert_eq!(Instruction::Shld(0, 1), Instruction::parse(0x401d));
+    }
+    #[test]
+    fn braf() {
+        // PC + 4 + R0 -> PC
+        assert_eq!([0x00, 0x23], Instruction::Braf(0).to_byte());
+        assert_eq!(Instruction::Braf(3), Instruction::parse(0x0323));
+    }
+    #[test]
+    fn cmpge() {
+        // R1 >= R0 (signed) -> T
+        assert_eq!([0x31, 0x03], Instruction::CmpGe(1, 0).to_byte());
+        assert_eq!(Instruction::CmpGe(1, 0), Instruction::parse(0x3103));
+    }
+    #[test]
+    fn movt() {
+        // T -> R4
+        assert_eq!([0x04, 0x29], Instruction::MovT(4).to_byte());
+        assert_eq!(Instruction::MovT(4), Instruction::parse(0x0429));
+    }
+    #[test]
+    fn movb_load() {
+        // (R1) -> R0
+        assert_eq!(
+            [0x60, 0x10],
+            Instruction::MovB(Operand::Register(0), Operand::AtRegister(1)).to_byte()
+        );
+        assert_eq!(
+            Instruction::MovB(Operand::Register(0), Operand::AtRegister(1)),
+            Instruction::parse(0x6010)
+        );
+    }
+    #[test]
+    fn extub() {
+        // R1 & 0xFF -> R0
+        assert_eq!([0x60, 0x1c], Instruction::ExtuB(0, 1).to_byte());
+        assert_eq!(Instruction::ExtuB(0, 1), Instruction::parse(0x601c));
+    }
+    #[test]
+    fn extuw() {
+        // R1 & 0xFFFF -> R0
+        assert_eq!([0x60, 0x1d], Instruction::ExtuW(0, 1).to_byte());
+        assert_eq!(Instruction::ExtuW(0, 1), Instruction::parse(0x601d));
+    }
+    #[test]
+    fn and_imm() {
+        // R0 & 0x0F -> R0
+        assert_eq!([0xc9, 0x0f], Instruction::AndI(0x0f).to_byte());
+        assert_eq!(Instruction::AndI(0x0f), Instruction::parse(0xc90f));
+    }
+    #[test]
+    fn or_imm() {
+        // R0 | 0x80 -> R0
+        assert_eq!([0xcb, 0x80], Instruction::OrI(0x80).to_byte());
+        assert_eq!(Instruction::OrI(0x80), Instruction::parse(0xcb80));
+    }
+    #[test]
+    fn xor_imm() {
+        // R0 ^ 0x01 -> R0
+        assert_eq!([0xca, 0x01], Instruction::XorI(0x01).to_byte());
+        assert_eq!(Instruction::XorI(0x01), Instruction::parse(0xca01));
+    }
+    #[test]
+    fn tst_imm() {
+        // (R0 & 0x03) == 0 -> T
+        assert_eq!([0xc8, 0x03], Instruction::TstI(0x03).to_byte());
+        assert_eq!(Instruction::TstI(0x03), Instruction::parse(0xc803));
+    }
+    #[test]
+    fn neg() {
+        // 0 - R1 -> R0
+        assert_eq!([0x60, 0x1b], Instruction::Neg(0, 1).to_byte());
+        assert_eq!(Instruction::Neg(0, 1), Instruction::parse(0x601b));
+    }
+    #[test]
+    fn negc() {
+        // 0 - R1 - T -> R0, borrow -> T
+        assert_eq!([0x60, 0x1a], Instruction::Negc(0, 1).to_byte());
+        assert_eq!(Instruction::Negc(0, 1), Instruction::parse(0x601a));
+    }
+    #[test]
+    fn addc() {
+        // R0 + R1 + T -> R0, carry -> T
+        assert_eq!([0x30, 0x1e], Instruction::Addc(0, 1).to_byte());
+        assert_eq!(Instruction::Addc(0, 1), Instruction::parse(0x301e));
+    }
+    #[test]
+    fn addv() {
+        // R0 + R1 -> R0, overflow -> T
+        assert_eq!([0x30, 0x1f], Instruction::Addv(0, 1).to_byte());
+        assert_eq!(Instruction::Addv(0, 1), Instruction::parse(0x301f));
+    }
+    #[test]
+    fn subv() {
+        // R0 - R1 -> R0, underflow -> T
+        assert_eq!([0x30, 0x1b], Instruction::Subv(0, 1).to_byte());
+        assert_eq!(Instruction::Subv(0, 1), Instruction::parse(0x301b));
+    }
+    #[test]
+    fn extsb() {
+        // R1 sign extended from a Byte -> R0
+        assert_eq!([0x60, 0x1e], Instruction::ExtsB(0, 1).to_byte());
+        assert_eq!(Instruction::ExtsB(0, 1), Instruction::parse(0x601e));
+    }
+    #[test]
+    fn extsw() {
+        // R1 sign extended from a Word -> R0
+        assert_eq!([0x60, 0x1f], Instruction::ExtsW(0, 1).to_byte());
+        assert_eq!(Instruction::ExtsW(0, 1), Instruction::parse(0x601f));
+    }
+    #[test]
+    fn swapb() {
+        // R1 with the lowest two Bytes swapped -> R0
+        assert_eq!([0x60, 0x18], Instruction::SwapB(0, 1).to_byte());
+        assert_eq!(Instruction::SwapB(0, 1), Instruction::parse(0x6018));
+    }
+    #[test]
+    fn swapw() {
+        // R1 with both Words swapped -> R0
+        assert_eq!([0x60, 0x19], Instruction::SwapW(0, 1).to_byte());
+        assert_eq!(Instruction::SwapW(0, 1), Instruction::parse(0x6019));
+    }
+    #[test]
+    fn xtrct() {
+        // Middle 32 Bits of R1:R0 -> R0
+        assert_eq!([0x20, 0x1d], Instruction::Xtrct(0, 1).to_byte());
+        assert_eq!(Instruction::Xtrct(0, 1), Instruction::parse(0x201d));
+    }
+    #[test]
+    fn rotl() {
+        // R2 rotated to the left
+        assert_eq!([0x42, 0x04], Instruction::Rotl(2).to_byte());
+        assert_eq!(Instruction::Rotl(2), Instruction::parse(0x4204));
+    }
+    #[test]
+    fn rotr() {
+        // R2 rotated to the right
+        assert_eq!([0x42, 0x05], Instruction::Rotr(2).to_byte());
+        assert_eq!(Instruction::Rotr(2), Instruction::parse(0x4205));
+    }
+    #[test]
+    fn rotcl() {
+        // T:R2 rotated to the left
+        assert_eq!([0x42, 0x24], Instruction::Rotcl(2).to_byte());
+        assert_eq!(Instruction::Rotcl(2), Instruction::parse(0x4224));
+    }
+    #[test]
+    fn rotcr() {
+        // R2:T rotated to the right
+        assert_eq!([0x42, 0x25], Instruction::Rotcr(2).to_byte());
+        assert_eq!(Instruction::Rotcr(2), Instruction::parse(0x4225));
+    }
+    #[test]
+    fn shal() {
+        // R2 << 1 -> R2, MSB -> T
+        assert_eq!([0x42, 0x20], Instruction::Shal(2).to_byte());
+        assert_eq!(Instruction::Shal(2), Instruction::parse(0x4220));
+    }
+    #[test]
+    fn div0s() {
+        // MSB of R0 -> Q, MSB of R1 -> M, Q ^ M -> T
+        assert_eq!([0x20, 0x17], Instruction::Div0s(0, 1).to_byte());
+        assert_eq!(Instruction::Div0s(0, 1), Instruction::parse(0x2017));
+    }
+    #[test]
+    fn div0u() {
+        // 0 -> M, Q, T
+        assert_eq!([0x00, 0x19], Instruction::Div0u.to_byte());
+        assert_eq!(Instruction::Div0u, Instruction::parse(0x0019));
+    }
+    #[test]
+    fn div1() {
+        // Single Step of R0 / R1
+        assert_eq!([0x30, 0x14], Instruction::Div1(0, 1).to_byte());
+        assert_eq!(Instruction::Div1(0, 1), Instruction::parse(0x3014));
+    }
+    #[test]
+    fn dmulul() {
+        // R0 * R1 (unsigned) -> MACH:MACL
+        assert_eq!([0x30, 0x15], Instruction::DmuluL(0, 1).to_byte());
+        assert_eq!(Instruction::DmuluL(0, 1), Instruction::parse(0x3015));
+    }
+    #[test]
+    fn mulsw() {
+        // R0 * R1 (signed Words) -> MACL
+        assert_eq!([0x20, 0x1f], Instruction::MulsW(0, 1).to_byte());
+        assert_eq!(Instruction::MulsW(0, 1), Instruction::parse(0x201f));
+    }
+    #[test]
+    fn muluw() {
+        // R0 * R1 (unsigned Words) -> MACL
+        assert_eq!([0x20, 0x1e], Instruction::MuluW(0, 1).to_byte());
+        assert_eq!(Instruction::MuluW(0, 1), Instruction::parse(0x201e));
+    }
+    #[test]
+    fn macl() {
+        // (R0) * (R1) + MAC -> MAC, R0 + 4 -> R0, R1 + 4 -> R1
+        assert_eq!([0x00, 0x1f], Instruction::MacL(0, 1).to_byte());
+        assert_eq!(Instruction::MacL(0, 1), Instruction::parse(0x001f));
+    }
+    #[test]
+    fn macw() {
+        // (R0) * (R1) + MAC -> MAC, R0 + 2 -> R0, R1 + 2 -> R1
+        assert_eq!([0x40, 0x1f], Instruction::MacW(0, 1).to_byte());
+        assert_eq!(Instruction::MacW(0, 1), Instruction::parse(0x401f));
+    }
+    #[test]
+    fn sett() {
+        // 1 -> T
+        assert_eq!([0x00, 0x18], Instruction::Sett.to_byte());
+        assert_eq!(Instruction::Sett, Instruction::parse(0x0018));
+    }
+    #[test]
+    fn clrt() {
+        // 0 -> T
+        assert_eq!([0x00, 0x08], Instruction::Clrt.to_byte());
+        assert_eq!(Instruction::Clrt, Instruction::parse(0x0008));
+    }
+    #[test]
+    fn bsrf() {
+        // PC + 4 -> PR, PC + 4 + R3 -> PC
+        assert_eq!([0x03, 0x03], Instruction::Bsrf(3).to_byte());
+        assert_eq!(Instruction::Bsrf(3), Instruction::parse(0x0303));
+    }
+    #[test]
+    fn trapa() {
+        // Trap with the Value 0x20
+        assert_eq!([0xc3, 0x20], Instruction::Trapa(0x20).to_byte());
+        assert_eq!(Instruction::Trapa(0x20), Instruction::parse(0xc320));
+    }
+    #[test]
+    fn sleep() {
+        // Waits for an Interrupt
+        assert_eq!([0x00, 0x1b], Instruction::Sleep.to_byte());
+        assert_eq!(Instruction::Sleep, Instruction::parse(0x001b));
+    }
+    #[test]
+    fn pref() {
+        // Prefetches the Cache-Line at (R4)
+        assert_eq!([0x04, 0x83], Instruction::Pref(4).to_byte());
+        assert_eq!(Instruction::Pref(4), Instruction::parse(0x0483));
+    }
+    #[test]
+    fn ldc() {
+        // R1 -> SR, R2 -> GBR, R3 -> VBR
+        assert_eq!(
+            [0x41, 0x0e],
+            Instruction::Ldc(ControlRegister::Sr, 1).to_byte()
+        );
+        assert_eq!(
+            [0x42, 0x1e],
+            Instruction::Ldc(ControlRegister::Gbr, 2).to_byte()
+        );
+        assert_eq!(
+            [0x43, 0x2e],
+            Instruction::Ldc(ControlRegister::Vbr, 3).to_byte()
+        );
+        assert_eq!(
+            Instruction::Ldc(ControlRegister::Gbr, 2),
+            Instruction::parse(0x421e)
+        );
+    }
+    #[test]
+    fn stc() {
+        // SR -> R1, GBR -> R2, VBR -> R3
+        assert_eq!(
+            [0x01, 0x02],
+            Instruction::Stc(1, ControlRegister::Sr).to_byte()
+        );
+        assert_eq!(
+            [0x02, 0x12],
+            Instruction::Stc(2, ControlRegister::Gbr).to_byte()
+        );
+        assert_eq!(
+            [0x03, 0x22],
+            Instruction::Stc(3, ControlRegister::Vbr).to_byte()
+        );
+        assert_eq!(
+            Instruction::Stc(3, ControlRegister::Vbr),
+            Instruction::parse(0x0322)
+        );
+    }
+    #[test]
+    fn post_increment() {
+        // (R1) -> R0, R1 + 1 -> R1
+        let instr = Instruction::MovB(Operand::Register(0), Operand::PostIncrement(1));
+        assert_eq!([0x60, 0x14], instr.to_byte());
+        assert_eq!(instr, Instruction::parse(0x6014));
+        // (R1) -> R0, R1 + 2 -> R1
+        let instr = Instruction::MovW(Operand::Register(0), Operand::PostIncrement(1));
+        assert_eq!([0x60, 0x15], instr.to_byte());
+        assert_eq!(instr, Instruction::parse(0x6015));
+        // (R1) -> R0, R1 + 4 -> R1
+        let instr = Instruction::MovL(Operand::Register(0), Operand::PostIncrement(1));
+        assert_eq!([0x60, 0x16], instr.to_byte());
+        assert_eq!(instr, Instruction::parse(0x6016));
+        // Long Variant is the same as PopOther
+        assert_eq!(Instruction::PopOther(0, 1).to_byte(), instr.to_byte());
+    }
+    #[test]
+    fn pre_decrement() {
+        // R1 - 2 -> R1, R0 -> (R1)
+        let instr = Instruction::MovW(Operand::PreDecrement(1), Operand::Register(0));
+        assert_eq!([0x21, 0x05], instr.to_byte());
+        assert_eq!(instr, Instruction::parse(0x2105));
+        // R1 - 1 -> R1, R0 -> (R1)
+        let byte = Instruction::MovB(Operand::PreDecrement(1), Operand::Register(0));
+        assert_eq!([0x21, 0x04], byte.to_byte());
+        assert_eq!(byte, Instruction::parse(0x2104));
+        // R1 - 4 -> R1, R0 -> (R1)
+        let long = Instruction::MovL(Operand::PreDecrement(1), Operand::Register(0));
+        assert_eq!([0x21, 0x06], long.to_byte());
+        assert_eq!(long, Instruction::parse(0x2106));
+        // Byte and Long Variants are the same as PushOtherB and PushOther
+        assert_eq!(Instruction::PushOtherB(0, 1).to_byte(), byte.to_byte());
+        assert_eq!(Instruction::PushOther(0, 1).to_byte(), long.to_byte());
+    }
+    #[test]
+    fn stack_round_trip() {
+        // The Stack-Shorthands decode to the general Moves with the same Encoding
+        let shorthands = [
+            Instruction::Push(3),
+            Instruction::Pop(3),
+            Instruction::PushOther(3, 4),
+            Instruction::PushOtherB(3, 4),
+            Instruction::PopOther(3, 4),
+        ];
+        for instr in shorthands.iter() {
+            let bytes = instr.to_byte();
+            let decoded = Instruction::parse(u16::from_be_bytes(bytes));
+            assert!(matches!(
+                decoded,
+                Instruction::MovB(..) | Instruction::MovL(..)
+            ));
+            assert_eq!(bytes, decoded.to_byte());
+        }
+    }
+    #[test]
+    fn round_trip() {
+        // Every decodable Instruction encodes back to the same Bytes
+        for raw in 0..=u16::MAX {
+            if let Ok(instr) = Instruction::decode(raw) {
+                assert_eq!(Ok(raw.to_be_bytes()), instr.encode(), "{:?}", instr);
+            }
+        }
+    }
+    #[test]
+    fn displacement_gbr() {
+        // (disp * 4 + GBR) -> R0
+        let instr = Instruction::MovL(Operand::Register(0), Operand::DisplacementGBR(3));
+        assert_eq!([0xc6, 0x03], instr.to_byte());
+        assert_eq!(instr, Instruction::parse(0xc603));
+        // R0 -> (disp + GBR)
+        let instr = Instruction::MovB(Operand::DisplacementGBR(0x10), Operand::Register(0));
+        assert_eq!([0xc0, 0x10], instr.to_byte());
+        assert_eq!(instr, Instruction::parse(0xc010));
+    }
+    #[test]
+    fn offset_r0_gbr() {
+        // (R0 + GBR) & 0x01 -> (R0 + GBR)
+        let instr = Instruction::AndB(Operand::OffsetR0GBR, 0x01);
+        assert_eq!([0xcd, 0x01], instr.to_byte());
+        assert_eq!(instr, Instruction::parse(0xcd01));
+        // (R0 + GBR) & 0x80 == 0 -> T
+        let instr = Instruction::TstB(Operand::OffsetR0GBR, 0x80);
+        assert_eq!([0xcc, 0x80], instr.to_byte());
+        assert_eq!(instr, Instruction::parse(0xcc80));
+    }
+    #[test]
+    fn displacement_pc() {
+        // (disp * 2 + PC + 4) -> R3
+        let instr = Instruction::MovW(Operand::Register(3), Operand::Displacement8(4));
+        assert_eq!([0x93, 0x04], instr.to_byte());
+        assert_eq!(instr, Instruction::parse(0x9304));
+        // (disp * 4 + (PC & 0xFFFFFFFC) + 4) -> R3
+        let instr = Instruction::MovL(Operand::Register(3), Operand::Displacement8(4));
+        assert_eq!([0xd3, 0x04], instr.to_byte());
+        assert_eq!(instr, Instruction::parse(0xd304));
+    }
+    #[test]
+    fn displacement_reg() {
+        // (disp + R2) -> R0
+        let instr = Instruction::MovB(Operand::Register(0), Operand::Displacement4Reg(5, 2));
+        assert_eq!([0x84, 0x25], instr.to_byte());
+        assert_eq!(instr, Instruction::parse(0x8425));
+        // R0 -> (disp * 2 + R2)
+        let instr = Instruction::MovW(Operand::Displacement4Reg(5, 2), Operand::Register(0));
+        assert_eq!([0x81, 0x25], instr.to_byte());
+        assert_eq!(instr, Instruction::parse(0x8125));
+        // (disp * 4 + R2) -> R1
+        let instr = Instruction::MovL(Operand::Register(1), Operand::Displacement4Reg(5, 2));
+        assert_eq!([0x51, 0x25], instr.to_byte());
+        assert_eq!(instr, Instruction::parse(0x5125));
+    }
+    #[test]
+    fn offset_r0() {
+        // (R0 + R2) -> R1
+        let instr = Instruction::MovB(Operand::Register(1), Operand::OffsetR0(2));
+        assert_eq!([0x01, 0x2c], instr.to_byte());
+        assert_eq!(instr, Instruction::parse(0x012c));
+        // R2 -> (R0 + R1)
+        let instr = Instruction::MovW(Operand::OffsetR0(1), Operand::Register(2));
+        assert_eq!([0x01, 0x25], instr.to_byte());
+        assert_eq!(instr, Instruction::parse(0x0125));
+    }
+    #[test]
+    fn encode_errors() {
+        assert_eq!(
+            Err(EncodeError::InvalidRegister(16)),
+            Instruction::Mov(16, 0).encode()
+        );
+        assert_eq!(
+            Err(EncodeError::DisplacementOutOfRange {
+                displacement: 16,
+                bits: 4
+            }),
+            Instruction::MovL(Operand::Register(0), Operand::Displacement4Reg(16, 1)).encode()
+        );
+        assert_eq!(
+            Err(EncodeError::DisplacementOutOfRange {
+                displacement: 0x1000,
+                bits: 12
+            }),
+            Instruction::BRA(0x1000).encode()
+        );
+        // Negative Displacements can be sign-extended
+        assert_eq!(Ok([0xaf, 0xfd]), Instruction::BRA(0xfffd).encode());
+        // Only R0 can be the Target of a Byte-Load with a Displacement
+        assert_eq!(
+            Err(EncodeError::UnsupportedOperands),
+            Instruction::MovB(Operand::Register(1), Operand::Displacement4Reg(1, 2)).encode()
+        );
+        assert_eq!(
+            Err(EncodeError::PseudoInstruction),
+            Instruction::Label("test".to_owned()).encode()
+        );
+    }
+    #[test]
+    fn decode_errors() {
+        assert_eq!(
+            Err(DecodeError::UnknownOpcode(0x0000)),
+            Instruction::decode(0x0000)
+        );
+        assert_eq!(
+            Err(DecodeError::UnknownOpcode(0x4f3e)),
+            Instruction::decode(0x4f3e)
+        );
+        assert_eq!(
+            Err(DecodeError::FloatingPoint(0xf00c)),
+            Instruction::decode(0xf00c)
+        );
+        // Parsing keeps unknown Values as Literals
+        assert_eq!(Instruction::Literal(0xff, 0xfd), Instruction::parse(0xfffd));
+    }
 }
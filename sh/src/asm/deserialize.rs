@@ -1,7 +1,7 @@
-use crate::asm::{Instruction, Operand};
+use crate::asm::{ControlRegister, DecodeError, Instruction, Operand};
 
 /// Parses the given 16-Bit-Instruction
-pub fn deserialize(raw: u16) -> Instruction {
+pub fn deserialize(raw: u16) -> Result<Instruction, DecodeError> {
     let bytes = raw.to_be_bytes();
     let nibbles = [
         (bytes[0] & 0xf0) >> 4,
@@ -10,8 +10,13 @@ pub fn deserialize(raw: u16) -> Instruction {
         bytes[1] & 0x0f,
     ];
 
-    match (nibbles[0], nibbles[1], nibbles[2], nibbles[3]) {
+    let instr = match (nibbles[0], nibbles[1], nibbles[2], nibbles[3]) {
         (0x0, 0x0, 0x0, 0x9) => Instruction::Nop,
+        (0x0, 0x0, 0x1, 0xb) => Instruction::Sleep,
+        (0x0, 0x0, 0x1, 0x8) => Instruction::Sett,
+        (0x0, 0x0, 0x0, 0x8) => Instruction::Clrt,
+        (0xc, 0x3, im_1, im_2) => Instruction::Trapa((im_1 << 4) | im_2),
+        (0x0, n_reg, 0x8, 0x3) => Instruction::Pref(n_reg),
 
         (0x6, n_reg, m_reg, 0x3) => Instruction::Mov(n_reg, m_reg),
         (0x0, n_reg, 0x2, 0x9) => Instruction::MovT(n_reg),
@@ -34,7 +39,22 @@ pub fn deserialize(raw: u16) -> Instruction {
         (0x6, n_reg, m_reg, 0x2) => {
             Instruction::MovL(Operand::Register(n_reg), Operand::AtRegister(m_reg))
         }
+        (0x6, n_reg, m_reg, 0x4) => {
+            Instruction::MovB(Operand::Register(n_reg), Operand::PostIncrement(m_reg))
+        }
+        (0x6, n_reg, m_reg, 0x5) => {
+            Instruction::MovW(Operand::Register(n_reg), Operand::PostIncrement(m_reg))
+        }
+        (0x6, n_reg, m_reg, 0x6) => {
+            Instruction::MovL(Operand::Register(n_reg), Operand::PostIncrement(m_reg))
+        }
+        (0x6, n_reg, m_reg, 0xc) => Instruction::ExtuB(n_reg, m_reg),
         (0x6, n_reg, m_reg, 0xd) => Instruction::ExtuW(n_reg, m_reg),
+        (0x6, n_reg, m_reg, 0xe) => Instruction::ExtsB(n_reg, m_reg),
+        (0x6, n_reg, m_reg, 0xf) => Instruction::ExtsW(n_reg, m_reg),
+        (0x6, n_reg, m_reg, 0x8) => Instruction::SwapB(n_reg, m_reg),
+        (0x6, n_reg, m_reg, 0x9) => Instruction::SwapW(n_reg, m_reg),
+        (0x2, n_reg, m_reg, 0xd) => Instruction::Xtrct(n_reg, m_reg),
         (0x2, n_reg, m_reg, 0x0) => {
             Instruction::MovB(Operand::AtRegister(n_reg), Operand::Register(m_reg))
         }
@@ -44,6 +64,15 @@ pub fn deserialize(raw: u16) -> Instruction {
         (0x2, n_reg, m_reg, 0x2) => {
             Instruction::MovL(Operand::AtRegister(n_reg), Operand::Register(m_reg))
         }
+        (0x2, n_reg, m_reg, 0x4) => {
+            Instruction::MovB(Operand::PreDecrement(n_reg), Operand::Register(m_reg))
+        }
+        (0x2, n_reg, m_reg, 0x5) => {
+            Instruction::MovW(Operand::PreDecrement(n_reg), Operand::Register(m_reg))
+        }
+        (0x2, n_reg, m_reg, 0x6) => {
+            Instruction::MovL(Operand::PreDecrement(n_reg), Operand::Register(m_reg))
+        }
         (0x1, n_reg, m_reg, disp) => Instruction::MovL(
             Operand::Displacement4Reg(disp, n_reg),
             Operand::Register(m_reg),
@@ -52,24 +81,76 @@ pub fn deserialize(raw: u16) -> Instruction {
             Operand::Register(n_reg),
             Operand::Displacement4Reg(disp, m_reg),
         ),
+        (0x8, 0x0, n_reg, disp) => {
+            Instruction::MovB(Operand::Displacement4Reg(disp, n_reg), Operand::Register(0))
+        }
+        (0x8, 0x1, n_reg, disp) => {
+            Instruction::MovW(Operand::Displacement4Reg(disp, n_reg), Operand::Register(0))
+        }
+        (0x8, 0x4, m_reg, disp) => {
+            Instruction::MovB(Operand::Register(0), Operand::Displacement4Reg(disp, m_reg))
+        }
+        (0x8, 0x5, m_reg, disp) => {
+            Instruction::MovW(Operand::Register(0), Operand::Displacement4Reg(disp, m_reg))
+        }
+        (0x0, n_reg, m_reg, 0x4) => {
+            Instruction::MovB(Operand::OffsetR0(n_reg), Operand::Register(m_reg))
+        }
+        (0x0, n_reg, m_reg, 0x5) => {
+            Instruction::MovW(Operand::OffsetR0(n_reg), Operand::Register(m_reg))
+        }
         (0x0, n_reg, m_reg, 0x6) => {
             Instruction::MovL(Operand::OffsetR0(n_reg), Operand::Register(m_reg))
         }
+        (0x0, n_reg, m_reg, 0xc) => {
+            Instruction::MovB(Operand::Register(n_reg), Operand::OffsetR0(m_reg))
+        }
         (0x0, n_reg, m_reg, 0xd) => {
             Instruction::MovW(Operand::Register(n_reg), Operand::OffsetR0(m_reg))
         }
+        (0x0, n_reg, m_reg, 0xe) => {
+            Instruction::MovL(Operand::Register(n_reg), Operand::OffsetR0(m_reg))
+        }
+        (0xc, 0x0, d_1, d_2) => Instruction::MovB(
+            Operand::DisplacementGBR((d_1 << 4) | d_2),
+            Operand::Register(0),
+        ),
+        (0xc, 0x1, d_1, d_2) => Instruction::MovW(
+            Operand::DisplacementGBR((d_1 << 4) | d_2),
+            Operand::Register(0),
+        ),
+        (0xc, 0x2, d_1, d_2) => Instruction::MovL(
+            Operand::DisplacementGBR((d_1 << 4) | d_2),
+            Operand::Register(0),
+        ),
+        (0xc, 0x4, d_1, d_2) => Instruction::MovB(
+            Operand::Register(0),
+            Operand::DisplacementGBR((d_1 << 4) | d_2),
+        ),
+        (0xc, 0x5, d_1, d_2) => Instruction::MovW(
+            Operand::Register(0),
+            Operand::DisplacementGBR((d_1 << 4) | d_2),
+        ),
+        (0xc, 0x6, d_1, d_2) => Instruction::MovL(
+            Operand::Register(0),
+            Operand::DisplacementGBR((d_1 << 4) | d_2),
+        ),
 
-        (0x6, n_reg, m_reg, 0x6) => Instruction::PopOther(n_reg, m_reg),
         (0x4, n_reg, 0x2, 0x6) => Instruction::PopPROther(n_reg),
-        (0x2, n_reg, m_reg, 0x4) => Instruction::PushOtherB(m_reg, n_reg),
-        (0x2, n_reg, m_reg, 0x6) => Instruction::PushOther(m_reg, n_reg),
         (0x4, n_reg, 0x2, 0x2) => Instruction::PushPROther(n_reg),
         (0x0, n_reg, 0x1, 0xa) => Instruction::StsMacl(n_reg),
         (0x4, n_reg, 0x1, 0x2) => Instruction::StsLMacl(n_reg),
         (0x4, m_reg, 0x1, 0x6) => Instruction::LdsLMacl(m_reg),
         (0x0, n_reg, 0x0, 0xa) => Instruction::StsMach(n_reg),
+        (0x0, n_reg, 0x2, 0xa) => Instruction::StsPr(n_reg),
         (0x4, n_reg, 0x0, 0x2) => Instruction::StsLMach(n_reg),
         (0x4, m_reg, 0x0, 0x6) => Instruction::LdsLMach(m_reg),
+        (0x4, m_reg, control, 0xe) if control <= 0x2 => {
+            Instruction::Ldc(control_register(control), m_reg)
+        }
+        (0x0, n_reg, control, 0x2) if control <= 0x2 => {
+            Instruction::Stc(n_reg, control_register(control))
+        }
 
         (0x8, 0xb, d_1, d_2) => Instruction::BF((d_1 << 4) | d_2),
         (0x8, 0xf, d_1, d_2) => Instruction::BFs((d_1 << 4) | d_2),
@@ -82,12 +163,15 @@ pub fn deserialize(raw: u16) -> Instruction {
             Instruction::BSR(((d_1 as u16) << 8) | ((d_2 as u16) << 4) | (d_3 as u16))
         }
         (0x4, m_reg, 0x2, 0xb) => Instruction::Jmp(m_reg),
+        (0x0, m_reg, 0x2, 0x3) => Instruction::Braf(m_reg),
+        (0x0, m_reg, 0x0, 0x3) => Instruction::Bsrf(m_reg),
         (0x4, m_reg, 0x0, 0xb) => Instruction::Jsr(m_reg),
         (0x0, 0x0, 0x0, 0xb) => Instruction::Rts,
 
         (0x8, 0x8, im_1, im_2) => Instruction::CmpEqI((im_1 << 4) | im_2),
         (0x3, n_reg, m_reg, 0x0) => Instruction::CmpEq(n_reg, m_reg),
         (0x3, n_reg, m_reg, 0x2) => Instruction::CmpHs(n_reg, m_reg),
+        (0x3, n_reg, m_reg, 0x3) => Instruction::CmpGe(n_reg, m_reg),
         (0x3, n_reg, m_reg, 0x6) => Instruction::CmpHi(n_reg, m_reg),
         (0x3, n_reg, m_reg, 0x7) => Instruction::CmpGt(n_reg, m_reg),
         (0x4, n_reg, 0x1, 0x1) => Instruction::CmpPz(n_reg),
@@ -95,26 +179,67 @@ pub fn deserialize(raw: u16) -> Instruction {
 
         (0x3, n_reg, m_reg, 0x8) => Instruction::Sub(n_reg, m_reg),
         (0x3, n_reg, m_reg, 0xa) => Instruction::Subc(n_reg, m_reg),
+        (0x3, n_reg, m_reg, 0xb) => Instruction::Subv(n_reg, m_reg),
         (0x3, n_reg, m_reg, 0xc) => Instruction::Add(n_reg, m_reg),
+        (0x3, n_reg, m_reg, 0xe) => Instruction::Addc(n_reg, m_reg),
+        (0x3, n_reg, m_reg, 0xf) => Instruction::Addv(n_reg, m_reg),
         (0x7, n_reg, val_1, val_2) => Instruction::AddI(n_reg, (val_1 << 4) | val_2),
         (0x0, n_reg, m_reg, 0x7) => Instruction::MulL(n_reg, m_reg),
         (0x3, n_reg, m_reg, 0xd) => Instruction::DmulSL(n_reg, m_reg),
+        (0x3, n_reg, m_reg, 0x5) => Instruction::DmuluL(n_reg, m_reg),
+        (0x2, n_reg, m_reg, 0xf) => Instruction::MulsW(n_reg, m_reg),
+        (0x2, n_reg, m_reg, 0xe) => Instruction::MuluW(n_reg, m_reg),
+        (0x0, n_reg, m_reg, 0xf) => Instruction::MacL(n_reg, m_reg),
+        (0x4, n_reg, m_reg, 0xf) => Instruction::MacW(n_reg, m_reg),
+        (0x2, n_reg, m_reg, 0x7) => Instruction::Div0s(n_reg, m_reg),
+        (0x0, 0x0, 0x1, 0x9) => Instruction::Div0u,
+        (0x3, n_reg, m_reg, 0x4) => Instruction::Div1(n_reg, m_reg),
 
         (0x4, n_reg, 0x2, 0x1) => Instruction::Shar(n_reg),
         (0x4, n_reg, 0x0, 0x0) => Instruction::Shll(n_reg),
         (0x4, n_reg, 0x0, 0x8) => Instruction::Shll2(n_reg),
         (0x4, n_reg, 0x1, 0x8) => Instruction::Shll8(n_reg),
         (0x4, n_reg, 0x2, 0x8) => Instruction::Shll16(n_reg),
+        (0x4, n_reg, 0x2, 0x0) => Instruction::Shal(n_reg),
+        (0x4, n_reg, 0x0, 0x4) => Instruction::Rotl(n_reg),
+        (0x4, n_reg, 0x0, 0x5) => Instruction::Rotr(n_reg),
+        (0x4, n_reg, 0x2, 0x4) => Instruction::Rotcl(n_reg),
+        (0x4, n_reg, 0x2, 0x5) => Instruction::Rotcr(n_reg),
         (0x4, n_reg, m_reg, 0xd) => Instruction::Shld(n_reg, m_reg),
+        (0x4, n_reg, m_reg, 0xc) => Instruction::Shad(n_reg, m_reg),
         (0x4, n_reg, 0x0, 0x1) => Instruction::Shlr(n_reg),
         (0x4, n_reg, 0x0, 0x9) => Instruction::Shlr2(n_reg),
-        (0x4, n_reg, 0x1, 09) => Instruction::Shlr8(n_reg),
+        (0x4, n_reg, 0x1, 0x9) => Instruction::Shlr8(n_reg),
         (0x4, n_reg, 0x2, 0x9) => Instruction::Shlr16(n_reg),
 
         (0x2, n_reg, m_reg, 0x8) => Instruction::Tst(n_reg, m_reg),
         (0x2, n_reg, m_reg, 0xa) => Instruction::Xor(n_reg, m_reg),
         (0x2, n_reg, m_reg, 0xb) => Instruction::Or(n_reg, m_reg),
+        (0x2, n_reg, m_reg, 0x9) => Instruction::And(n_reg, m_reg),
+        (0x6, n_reg, m_reg, 0x7) => Instruction::Not(n_reg, m_reg),
+        (0x6, n_reg, m_reg, 0xb) => Instruction::Neg(n_reg, m_reg),
+        (0x6, n_reg, m_reg, 0xa) => Instruction::Negc(n_reg, m_reg),
+        (0xc, 0x8, im_1, im_2) => Instruction::TstI((im_1 << 4) | im_2),
+        (0xc, 0x9, im_1, im_2) => Instruction::AndI((im_1 << 4) | im_2),
+        (0xc, 0xa, im_1, im_2) => Instruction::XorI((im_1 << 4) | im_2),
+        (0xc, 0xb, im_1, im_2) => Instruction::OrI((im_1 << 4) | im_2),
+        (0xc, 0xc, im_1, im_2) => Instruction::TstB(Operand::OffsetR0GBR, (im_1 << 4) | im_2),
+        (0xc, 0xd, im_1, im_2) => Instruction::AndB(Operand::OffsetR0GBR, (im_1 << 4) | im_2),
+        (0xc, 0xe, im_1, im_2) => Instruction::XorB(Operand::OffsetR0GBR, (im_1 << 4) | im_2),
+        (0xc, 0xf, im_1, im_2) => Instruction::OrB(Operand::OffsetR0GBR, (im_1 << 4) | im_2),
+
+        (0xf, _, _, _) => return Err(DecodeError::FloatingPoint(raw)),
+        _ => return Err(DecodeError::UnknownOpcode(raw)),
+    };
+
+    Ok(instr)
+}
 
-        (p1, p2, p3, p4) => Instruction::Literal((p1 << 4) | p2, (p3 << 4) | p4),
+/// The Control-Register selected by the Bits in `LDC` and `STC`
+fn control_register(bits: u8) -> ControlRegister {
+    match bits {
+        0x0 => ControlRegister::Sr,
+        0x1 => ControlRegister::Gbr,
+        _ => ControlRegister::Vbr,
     }
 }
@@ -0,0 +1,313 @@
+use std::fmt;
+
+use crate::asm::{ControlRegister, Instruction, Operand, Section};
+
+/// Formats an Instruction located at a known Address, so that the Targets
+/// of its Branches can be shown as absolute Addresses
+pub struct DisplayAt<'a> {
+    pub(crate) instr: &'a Instruction,
+    pub(crate) address: u32,
+}
+
+impl fmt::Display for DisplayAt<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_instr(f, self.instr, Some(self.address))
+    }
+}
+
+/// Formats the Instruction in the GNU-Syntax for SH, in which the Source
+/// comes before the Target. Branch-Targets are shown relative to the
+/// Instruction itself (`.+8`)
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_instr(f, self, None)
+    }
+}
+
+/// The Address of the Target of a relative Branch or of the Value loaded by
+/// a PC-relative Move, when the Instruction is located at the given Address
+pub fn target(instr: &Instruction, address: u32) -> Option<u32> {
+    match instr {
+        Instruction::MovA(disp)
+        | Instruction::MovL(Operand::Register(_), Operand::Displacement8(disp)) => {
+            Some((address & !3).wrapping_add(4 + *disp as u32 * 4))
+        }
+        Instruction::MovW(Operand::Register(_), Operand::Displacement8(disp)) => {
+            Some(address.wrapping_add(4 + *disp as u32 * 2))
+        }
+        _ => branch_offset(instr).map(|offset| (address as i64 + offset) as u32),
+    }
+}
+
+/// The Offset of the Branch-Target relative to the Address of the Branch
+fn branch_offset(instr: &Instruction) -> Option<i64> {
+    let disp = match instr {
+        Instruction::BT(disp)
+        | Instruction::BTs(disp)
+        | Instruction::BF(disp)
+        | Instruction::BFs(disp) => *disp as i8 as i64,
+        // Sign-extend the 12-Bit Displacement
+        Instruction::BRA(disp) | Instruction::BSR(disp) => {
+            (((*disp as i64) & 0xfff) ^ 0x800) - 0x800
+        }
+        _ => return None,
+    };
+    Some(4 + disp * 2)
+}
+
+/// Formats the Operand of a Move with the given Size in Bytes
+fn operand(op: &Operand, size: u32) -> String {
+    match op {
+        Operand::Register(register) => format!("r{}", register),
+        Operand::AtRegister(register) => format!("@r{}", register),
+        Operand::Displacement8(disp) => format!("@({},pc)", *disp as u32 * size),
+        Operand::Displacement4Reg(disp, register) => {
+            format!("@({},r{})", *disp as u32 * size, register)
+        }
+        Operand::OffsetR0(register) => format!("@(r0,r{})", register),
+        Operand::PostIncrement(register) => format!("@r{}+", register),
+        Operand::PreDecrement(register) => format!("@-r{}", register),
+        Operand::DisplacementGBR(disp) => format!("@({},gbr)", *disp as u32 * size),
+        Operand::OffsetR0GBR => "@(r0,gbr)".to_owned(),
+    }
+}
+
+fn control_register(control: ControlRegister) -> &'static str {
+    match control {
+        ControlRegister::Sr => "sr",
+        ControlRegister::Gbr => "gbr",
+        ControlRegister::Vbr => "vbr",
+    }
+}
+
+fn write_instr(
+    f: &mut fmt::Formatter<'_>,
+    instr: &Instruction,
+    address: Option<u32>,
+) -> fmt::Result {
+    if let Some(offset) = branch_offset(instr) {
+        let mnemonic = match instr {
+            Instruction::BT(_) => "bt",
+            Instruction::BTs(_) => "bt/s",
+            Instruction::BF(_) => "bf",
+            Instruction::BFs(_) => "bf/s",
+            Instruction::BRA(_) => "bra",
+            _ => "bsr",
+        };
+        return match address {
+            Some(address) => write!(f, "{} 0x{:08x}", mnemonic, (address as i64 + offset) as u32),
+            None if offset < 0 => write!(f, "{} .-{}", mnemonic, -offset),
+            None => write!(f, "{} .+{}", mnemonic, offset),
+        };
+    }
+
+    match instr {
+        Instruction::Nop => write!(f, "nop"),
+        Instruction::Mov(target, source) => write!(f, "mov r{},r{}", source, target),
+        Instruction::MovT(target) => write!(f, "movt r{}", target),
+        Instruction::MovI(target, value) => write!(f, "mov #{},r{}", *value as i8, target),
+        Instruction::MovA(disp) => write!(f, "mova @({},pc),r0", *disp as u32 * 4),
+        Instruction::MovB(target, source) => {
+            write!(f, "mov.b {},{}", operand(source, 1), operand(target, 1))
+        }
+        Instruction::MovW(target, source) => {
+            write!(f, "mov.w {},{}", operand(source, 2), operand(target, 2))
+        }
+        Instruction::MovL(target, source) => {
+            write!(f, "mov.l {},{}", operand(source, 4), operand(target, 4))
+        }
+        Instruction::ExtuB(target, source) => write!(f, "extu.b r{},r{}", source, target),
+        Instruction::ExtuW(target, source) => write!(f, "extu.w r{},r{}", source, target),
+        Instruction::ExtsB(target, source) => write!(f, "exts.b r{},r{}", source, target),
+        Instruction::ExtsW(target, source) => write!(f, "exts.w r{},r{}", source, target),
+        Instruction::SwapB(target, source) => write!(f, "swap.b r{},r{}", source, target),
+        Instruction::SwapW(target, source) => write!(f, "swap.w r{},r{}", source, target),
+        Instruction::Xtrct(target, source) => write!(f, "xtrct r{},r{}", source, target),
+        Instruction::StsPr(target) => write!(f, "sts pr,r{}", target),
+        Instruction::Push(source) => write!(f, "mov.l r{},@-r15", source),
+        Instruction::PushOther(source, stack) => write!(f, "mov.l r{},@-r{}", source, stack),
+        Instruction::PushOtherB(source, stack) => write!(f, "mov.b r{},@-r{}", source, stack),
+        Instruction::PushPR => write!(f, "sts.l pr,@-r15"),
+        Instruction::PushPROther(stack) => write!(f, "sts.l pr,@-r{}", stack),
+        Instruction::Pop(target) => write!(f, "mov.l @r15+,r{}", target),
+        Instruction::PopOther(target, stack) => write!(f, "mov.l @r{}+,r{}", stack, target),
+        Instruction::PopPR => write!(f, "lds.l @r15+,pr"),
+        Instruction::PopPROther(stack) => write!(f, "lds.l @r{}+,pr", stack),
+        Instruction::Tst(target, other) => write!(f, "tst r{},r{}", other, target),
+        Instruction::Xor(target, other) => write!(f, "xor r{},r{}", other, target),
+        Instruction::Or(target, other) => write!(f, "or r{},r{}", other, target),
+        Instruction::And(target, other) => write!(f, "and r{},r{}", other, target),
+        Instruction::Not(target, source) => write!(f, "not r{},r{}", source, target),
+        Instruction::TstI(value) => write!(f, "tst #{},r0", value),
+        Instruction::AndI(value) => write!(f, "and #{},r0", value),
+        Instruction::XorI(value) => write!(f, "xor #{},r0", value),
+        Instruction::OrI(value) => write!(f, "or #{},r0", value),
+        Instruction::TstB(target, value) => write!(f, "tst.b #{},{}", value, operand(target, 1)),
+        Instruction::AndB(target, value) => write!(f, "and.b #{},{}", value, operand(target, 1)),
+        Instruction::XorB(target, value) => write!(f, "xor.b #{},{}", value, operand(target, 1)),
+        Instruction::OrB(target, value) => write!(f, "or.b #{},{}", value, operand(target, 1)),
+        Instruction::Neg(target, source) => write!(f, "neg r{},r{}", source, target),
+        Instruction::Negc(target, source) => write!(f, "negc r{},r{}", source, target),
+        Instruction::Add(target, other) => write!(f, "add r{},r{}", other, target),
+        Instruction::Addc(target, other) => write!(f, "addc r{},r{}", other, target),
+        Instruction::Addv(target, other) => write!(f, "addv r{},r{}", other, target),
+        Instruction::AddI(target, value) => write!(f, "add #{},r{}", *value as i8, target),
+        Instruction::Sub(target, other) => write!(f, "sub r{},r{}", other, target),
+        Instruction::Subc(target, other) => write!(f, "subc r{},r{}", other, target),
+        Instruction::Subv(target, other) => write!(f, "subv r{},r{}", other, target),
+        Instruction::MulL(target, other) => write!(f, "mul.l r{},r{}", other, target),
+        Instruction::DmulSL(target, other) => write!(f, "dmuls.l r{},r{}", other, target),
+        Instruction::DmuluL(target, other) => write!(f, "dmulu.l r{},r{}", other, target),
+        Instruction::MulsW(target, other) => write!(f, "muls.w r{},r{}", other, target),
+        Instruction::MuluW(target, other) => write!(f, "mulu.w r{},r{}", other, target),
+        Instruction::MacL(first, second) => write!(f, "mac.l @r{}+,@r{}+", second, first),
+        Instruction::MacW(first, second) => write!(f, "mac.w @r{}+,@r{}+", second, first),
+        Instruction::Div0s(dividend, divisor) => write!(f, "div0s r{},r{}", divisor, dividend),
+        Instruction::Div0u => write!(f, "div0u"),
+        Instruction::Div1(dividend, divisor) => write!(f, "div1 r{},r{}", divisor, dividend),
+        Instruction::CmpEqI(value) => write!(f, "cmp/eq #{},r0", *value as i8),
+        Instruction::CmpEq(left, right) => write!(f, "cmp/eq r{},r{}", right, left),
+        Instruction::CmpHs(left, right) => write!(f, "cmp/hs r{},r{}", right, left),
+        Instruction::CmpGe(left, right) => write!(f, "cmp/ge r{},r{}", right, left),
+        Instruction::CmpHi(left, right) => write!(f, "cmp/hi r{},r{}", right, left),
+        Instruction::CmpGt(left, right) => write!(f, "cmp/gt r{},r{}", right, left),
+        Instruction::CmpPz(target) => write!(f, "cmp/pz r{}", target),
+        Instruction::Dt(target) => write!(f, "dt r{}", target),
+        Instruction::Sett => write!(f, "sett"),
+        Instruction::Clrt => write!(f, "clrt"),
+        Instruction::Label(name) => write!(f, "{}:", name),
+        Instruction::Jmp(target) => write!(f, "jmp @r{}", target),
+        Instruction::Braf(target) => write!(f, "braf r{}", target),
+        Instruction::Bsrf(target) => write!(f, "bsrf r{}", target),
+        Instruction::JmpLabel(name) => write!(f, "bra {}", name),
+        Instruction::Jsr(target) => write!(f, "jsr @r{}", target),
+        Instruction::JsrLabel(name) => write!(f, "bsr {}", name),
+        Instruction::Rts => write!(f, "rts"),
+        Instruction::Shar(target) => write!(f, "shar r{}", target),
+        Instruction::Shll(target) => write!(f, "shll r{}", target),
+        Instruction::Shll2(target) => write!(f, "shll2 r{}", target),
+        Instruction::Shll8(target) => write!(f, "shll8 r{}", target),
+        Instruction::Shll16(target) => write!(f, "shll16 r{}", target),
+        Instruction::Shal(target) => write!(f, "shal r{}", target),
+        Instruction::Rotl(target) => write!(f, "rotl r{}", target),
+        Instruction::Rotr(target) => write!(f, "rotr r{}", target),
+        Instruction::Rotcl(target) => write!(f, "rotcl r{}", target),
+        Instruction::Rotcr(target) => write!(f, "rotcr r{}", target),
+        Instruction::Shld(target, count) => write!(f, "shld r{},r{}", count, target),
+        Instruction::Shad(target, count) => write!(f, "shad r{},r{}", count, target),
+        Instruction::Shlr(target) => write!(f, "shlr r{}", target),
+        Instruction::Shlr2(target) => write!(f, "shlr2 r{}", target),
+        Instruction::Shlr8(target) => write!(f, "shlr8 r{}", target),
+        Instruction::Shlr16(target) => write!(f, "shlr16 r{}", target),
+        Instruction::StsMacl(target) => write!(f, "sts macl,r{}", target),
+        Instruction::StsLMacl(stack) => write!(f, "sts.l macl,@-r{}", stack),
+        Instruction::LdsLMacl(stack) => write!(f, "lds.l @r{}+,macl", stack),
+        Instruction::StsMach(target) => write!(f, "sts mach,r{}", target),
+        Instruction::StsLMach(stack) => write!(f, "sts.l mach,@-r{}", stack),
+        Instruction::LdsLMach(stack) => write!(f, "lds.l @r{}+,mach", stack),
+        Instruction::Ldc(control, source) => {
+            write!(f, "ldc r{},{}", source, control_register(*control))
+        }
+        Instruction::Stc(target, control) => {
+            write!(f, "stc {},r{}", control_register(*control), target)
+        }
+        Instruction::Trapa(value) => write!(f, "trapa #{}", value),
+        Instruction::Sleep => write!(f, "sleep"),
+        Instruction::Pref(target) => write!(f, "pref @r{}", target),
+        Instruction::Literal(first, second) => write!(f, ".word 0x{:02x}{:02x}", first, second),
+        Instruction::Section(Section::Text) => write!(f, ".text"),
+        Instruction::Section(Section::RoData) => write!(f, ".section .rodata"),
+        Instruction::Section(Section::Data) => write!(f, ".data"),
+        Instruction::Section(Section::Bss) => write!(f, ".bss"),
+        Instruction::Data(data) => {
+            let bytes: Vec<String> = data.iter().map(|byte| format!("0x{:02x}", byte)).collect();
+            write!(f, ".byte {}", bytes.join(","))
+        }
+        Instruction::Align(alignment) if alignment.is_power_of_two() => {
+            write!(f, ".align {}", alignment.trailing_zeros())
+        }
+        Instruction::Align(alignment) => write!(f, ".balign {}", alignment),
+        Instruction::Space(size) => write!(f, ".space {}", size),
+        Instruction::LabelAddress(name) => write!(f, ".long {}", name),
+        Instruction::Global(name) => write!(f, ".global {}", name),
+        Instruction::MovLabel(target, name) => write!(f, "mov.l #{},r{}", name, target),
+        Instruction::MovConst(target, value) => write!(f, "mov.l #0x{:08x},r{}", value, target),
+        Instruction::MovALabel(name) => write!(f, "mova {},r0", name),
+        Instruction::LoadWord(target, name) => write!(f, "mov.w {},r{}", name, target),
+        Instruction::LoadLong(target, name) => write!(f, "mov.l {},r{}", name, target),
+        Instruction::BT(_)
+        | Instruction::BTs(_)
+        | Instruction::BF(_)
+        | Instruction::BFs(_)
+        | Instruction::BRA(_)
+        | Instruction::BSR(_) => unreachable!("Branches are formatted with their Target"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operand_order() {
+        assert_eq!("mov r1,r0", Instruction::Mov(0, 1).to_string());
+        assert_eq!("sub r1,r0", Instruction::Sub(0, 1).to_string());
+        assert_eq!("cmp/ge r0,r1", Instruction::CmpGe(1, 0).to_string());
+        assert_eq!("mov #-1,r3", Instruction::MovI(3, 0xff).to_string());
+    }
+
+    #[test]
+    fn moves() {
+        assert_eq!(
+            "mov.l @r1,r0",
+            Instruction::MovL(Operand::Register(0), Operand::AtRegister(1)).to_string()
+        );
+        assert_eq!(
+            "mov.w r0,@r1",
+            Instruction::MovW(Operand::AtRegister(1), Operand::Register(0)).to_string()
+        );
+        assert_eq!(
+            "mov.l @(8,r2),r1",
+            Instruction::MovL(Operand::Register(1), Operand::Displacement4Reg(2, 2)).to_string()
+        );
+        assert_eq!("mov.l @r1+,r2", Instruction::PopOther(2, 1).to_string());
+        assert_eq!("mov.l r2,@-r15", Instruction::Push(2).to_string());
+        assert_eq!(
+            "mov.w @r1+,r0",
+            Instruction::MovW(Operand::Register(0), Operand::PostIncrement(1)).to_string()
+        );
+        assert_eq!(
+            "mov.l r0,@(12,gbr)",
+            Instruction::MovL(Operand::DisplacementGBR(3), Operand::Register(0)).to_string()
+        );
+        assert_eq!(
+            "or.b #1,@(r0,gbr)",
+            Instruction::OrB(Operand::OffsetR0GBR, 1).to_string()
+        );
+    }
+
+    #[test]
+    fn branches() {
+        assert_eq!("bra .+8", Instruction::BRA(2).to_string());
+        assert_eq!("bf .-2", Instruction::BF(0xfd).to_string());
+        assert_eq!(
+            "bra 0x00300002",
+            Instruction::BRA(0xfff).display_at(0x00300000).to_string()
+        );
+        assert_eq!(
+            "bt 0x0030000a",
+            Instruction::BT(3).display_at(0x00300000).to_string()
+        );
+    }
+
+    #[test]
+    fn targets() {
+        assert_eq!(Some(0x00300002), Instruction::BRA(0xfff).target(0x00300000));
+        // PC-relative Loads are based on the aligned Address
+        assert_eq!(
+            Some(0x00300008),
+            Instruction::MovL(Operand::Register(0), Operand::Displacement8(1)).target(0x00300002)
+        );
+        assert_eq!(None, Instruction::Rts.target(0x00300000));
+    }
+}
@@ -0,0 +1,63 @@
+use std::fmt;
+
+/// The Reasons why an Instruction can not be converted into its ByteCode
+#[derive(Debug, PartialEq, Clone)]
+pub enum EncodeError {
+    /// Only the Registers R0 to R15 exist
+    InvalidRegister(u8),
+    /// The Displacement does not fit into the given Number of Bits of the
+    /// Encoding
+    DisplacementOutOfRange { displacement: i32, bits: u8 },
+    /// SH has no Encoding for this Combination of Operands
+    UnsupportedOperands,
+    /// The Instruction is only used to structure the Program and has to
+    /// be replaced by the Assembler
+    PseudoInstruction,
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidRegister(register) => {
+                write!(f, "the register r{} does not exist", register)
+            }
+            Self::DisplacementOutOfRange { displacement, bits } => write!(
+                f,
+                "the displacement {} does not fit into {} bits",
+                displacement, bits
+            ),
+            Self::UnsupportedOperands => write!(f, "the operands are not supported by SH"),
+            Self::PseudoInstruction => write!(
+                f,
+                "the instruction is only used by the assembler and has no encoding"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+/// The Reasons why a 16-Bit Value can not be decoded into an Instruction
+#[derive(Debug, PartialEq, Clone)]
+pub enum DecodeError {
+    /// No Instruction is encoded by the Value
+    UnknownOpcode(u16),
+    /// The Value encodes a Floating-Point Instruction, but the Calculator
+    /// has no FPU
+    FloatingPoint(u16),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownOpcode(raw) => write!(f, "unknown opcode 0x{:04x}", raw),
+            Self::FloatingPoint(raw) => write!(
+                f,
+                "the floating-point instruction 0x{:04x} is not supported",
+                raw
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
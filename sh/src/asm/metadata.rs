@@ -0,0 +1,459 @@
+use super::{ControlRegister, Instruction, Operand};
+
+/// Something that can be read or written by an Instruction
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Resource {
+    /// One of the General-Registers R0 to R15
+    Register(u8),
+    /// The T bit of the Status-Register
+    T,
+    /// The upper 32 Bits of the Multiply-and-Accumulate-Register
+    Mach,
+    /// The lower 32 Bits of the Multiply-and-Accumulate-Register
+    Macl,
+    /// The Procedure-Register, which holds the Return-Address
+    Pr,
+    /// One of the Control-Registers, for the Status-Register this
+    /// covers every bit except the T bit
+    Control(ControlRegister),
+    /// Any Location in Memory
+    Memory,
+}
+
+/// Information about an Instruction, that is needed to schedule it
+/// and to fill the Delay-Slots of Branches
+#[derive(Debug, PartialEq, Clone)]
+pub struct Metadata {
+    /// The Instruction is a delayed Branch, so the Instruction after it
+    /// is executed before the Branch is taken
+    pub delay_slot: bool,
+    /// The Instruction raises a Slot-Illegal-Exception if it is placed
+    /// in the Delay-Slot of a delayed Branch
+    pub slot_illegal: bool,
+    /// The Number of Cycles it takes to issue the Instruction on the
+    /// SH-4A
+    pub issue: u8,
+    /// The Number of Cycles after which the Result of the Instruction
+    /// can be used by the following Instructions on the SH-4A
+    pub latency: u8,
+    /// Everything the Instruction reads
+    pub reads: Vec<Resource>,
+    /// Everything the Instruction writes
+    pub writes: Vec<Resource>,
+}
+
+impl Metadata {
+    fn new(issue: u8, latency: u8) -> Self {
+        Self {
+            delay_slot: false,
+            slot_illegal: false,
+            issue,
+            latency,
+            reads: Vec::new(),
+            writes: Vec::new(),
+        }
+    }
+
+    /// A Branch, which therefore is illegal in a Delay-Slot
+    fn branch(delayed: bool) -> Self {
+        Self {
+            delay_slot: delayed,
+            slot_illegal: true,
+            ..Self::new(1, 1)
+        }
+    }
+
+    fn read(mut self, resources: &[Resource]) -> Self {
+        for resource in resources {
+            if !self.reads.contains(resource) {
+                self.reads.push(*resource);
+            }
+        }
+        self
+    }
+
+    fn write(mut self, resources: &[Resource]) -> Self {
+        for resource in resources {
+            if !self.writes.contains(resource) {
+                self.writes.push(*resource);
+            }
+        }
+        self
+    }
+
+    /// Adds the Effects of accessing the Operand, where `write` decides
+    /// whether the Operand is the Target or the Source
+    fn operand(self, operand: &Operand, write: bool) -> Self {
+        let access = if write { Self::write } else { Self::read };
+
+        match operand {
+            Operand::Register(register) => access(self, &[reg(*register)]),
+            Operand::AtRegister(register) | Operand::Displacement4Reg(_, register) => {
+                access(self.read(&[reg(*register)]), &[Resource::Memory])
+            }
+            Operand::PostIncrement(register) | Operand::PreDecrement(register) => access(
+                self.read(&[reg(*register)]).write(&[reg(*register)]),
+                &[Resource::Memory],
+            ),
+            Operand::Displacement8(_) => access(self, &[Resource::Memory]),
+            Operand::OffsetR0(register) => {
+                access(self.read(&[reg(0), reg(*register)]), &[Resource::Memory])
+            }
+            Operand::DisplacementGBR(_) => access(
+                self.read(&[Resource::Control(ControlRegister::Gbr)]),
+                &[Resource::Memory],
+            ),
+            Operand::OffsetR0GBR => access(
+                self.read(&[reg(0), Resource::Control(ControlRegister::Gbr)]),
+                &[Resource::Memory],
+            ),
+        }
+    }
+}
+
+fn reg(register: u8) -> Resource {
+    Resource::Register(register)
+}
+
+/// A Move from the Source to the Target
+fn mov(target: &Operand, source: &Operand) -> Metadata {
+    Metadata::new(1, 1)
+        .operand(source, false)
+        .operand(target, true)
+}
+
+/// An Operation that reads both Registers and stores its Result in the
+/// Target
+fn binary(target: u8, source: u8) -> Metadata {
+    Metadata::new(1, 1)
+        .read(&[reg(target), reg(source)])
+        .write(&[reg(target)])
+}
+
+/// An Operation that only reads the Source and stores its Result in the
+/// Target
+fn unary(target: u8, source: u8) -> Metadata {
+    Metadata::new(1, 1)
+        .read(&[reg(source)])
+        .write(&[reg(target)])
+}
+
+/// A Comparison of the two Registers, that stores the Result in the T bit
+fn compare(first: u8, second: u8) -> Metadata {
+    Metadata::new(1, 1)
+        .read(&[reg(first), reg(second)])
+        .write(&[Resource::T])
+}
+
+/// A Shift of the Register, that may also store the bit shifted out in
+/// the T bit
+fn shift(register: u8, t: bool) -> Metadata {
+    let result = Metadata::new(1, 1)
+        .read(&[reg(register)])
+        .write(&[reg(register)]);
+    if t {
+        result.write(&[Resource::T])
+    } else {
+        result
+    }
+}
+
+/// A Logic-Operation on the Byte at `@(R0, GBR)`
+fn logic_byte(target: &Operand, t: bool) -> Metadata {
+    let result = Metadata::new(3, 3).operand(target, false);
+    if t {
+        result.write(&[Resource::T])
+    } else {
+        result.operand(target, true)
+    }
+}
+
+/// A Multiply-and-Accumulate of the Values at the Addresses in the two
+/// Registers, which also depends on the S bit of the Status-Register
+fn mac(first: u8, second: u8, latency: u8) -> Metadata {
+    Metadata::new(2, latency)
+        .operand(&Operand::PostIncrement(first), false)
+        .operand(&Operand::PostIncrement(second), false)
+        .read(&[
+            Resource::Mach,
+            Resource::Macl,
+            Resource::Control(ControlRegister::Sr),
+        ])
+        .write(&[Resource::Mach, Resource::Macl])
+}
+
+/// Stores the Value in the Resource on the Stack with the given
+/// Stack-Pointer
+fn push(resource: Resource, stack: u8) -> Metadata {
+    Metadata::new(1, 1)
+        .read(&[resource])
+        .operand(&Operand::PreDecrement(stack), true)
+}
+
+/// Loads the Value from the Stack with the given Stack-Pointer into the
+/// Resource
+fn pop(resource: Resource, stack: u8) -> Metadata {
+    Metadata::new(1, 1)
+        .operand(&Operand::PostIncrement(stack), false)
+        .write(&[resource])
+}
+
+/// Determines the Metadata of the Instruction, this is `None` for
+/// everything that is not an actual Instruction, like Labels and Data,
+/// and for `Literal`s
+pub fn metadata(instr: &Instruction) -> Option<Metadata> {
+    use Resource::{Control, Mach, Macl, Pr, T};
+
+    let result = match instr {
+        Instruction::Nop => Metadata::new(1, 1),
+        Instruction::Mov(target, source) => unary(*target, *source),
+        Instruction::MovT(target) => Metadata::new(1, 1).read(&[T]).write(&[reg(*target)]),
+        Instruction::MovI(target, _) => Metadata::new(1, 1).write(&[reg(*target)]),
+        Instruction::MovA(_) => Metadata::new(1, 1).write(&[reg(0)]),
+        Instruction::MovB(target, source)
+        | Instruction::MovW(target, source)
+        | Instruction::MovL(target, source) => mov(target, source),
+        Instruction::ExtuB(target, source)
+        | Instruction::ExtuW(target, source)
+        | Instruction::ExtsB(target, source)
+        | Instruction::ExtsW(target, source)
+        | Instruction::SwapB(target, source)
+        | Instruction::SwapW(target, source)
+        | Instruction::Not(target, source)
+        | Instruction::Neg(target, source) => unary(*target, *source),
+        Instruction::Xtrct(target, source)
+        | Instruction::Xor(target, source)
+        | Instruction::Or(target, source)
+        | Instruction::And(target, source)
+        | Instruction::Add(target, source)
+        | Instruction::Sub(target, source)
+        | Instruction::Shld(target, source)
+        | Instruction::Shad(target, source) => binary(*target, *source),
+        Instruction::StsPr(target) => Metadata::new(1, 1).read(&[Pr]).write(&[reg(*target)]),
+        Instruction::Push(source) => push(reg(*source), 15),
+        Instruction::PushOther(source, stack) => push(reg(*source), *stack),
+        Instruction::PushOtherB(source, stack) => push(reg(*source), *stack),
+        Instruction::PushPR => push(Pr, 15),
+        Instruction::PushPROther(stack) => push(Pr, *stack),
+        Instruction::Pop(target) => pop(reg(*target), 15),
+        Instruction::PopOther(target, stack) => pop(reg(*target), *stack),
+        Instruction::PopPR => pop(Pr, 15),
+        Instruction::PopPROther(stack) => pop(Pr, *stack),
+        Instruction::Tst(first, second) => compare(*first, *second),
+        Instruction::TstI(_) | Instruction::CmpEqI(_) => {
+            Metadata::new(1, 1).read(&[reg(0)]).write(&[T])
+        }
+        Instruction::XorI(_) | Instruction::OrI(_) | Instruction::AndI(_) => {
+            Metadata::new(1, 1).read(&[reg(0)]).write(&[reg(0)])
+        }
+        Instruction::AndB(target, _)
+        | Instruction::OrB(target, _)
+        | Instruction::XorB(target, _) => logic_byte(target, false),
+        Instruction::TstB(target, _) => logic_byte(target, true),
+        Instruction::Negc(target, source) => unary(*target, *source).read(&[T]).write(&[T]),
+        Instruction::Addc(target, source) | Instruction::Subc(target, source) => {
+            binary(*target, *source).read(&[T]).write(&[T])
+        }
+        Instruction::Addv(target, source) | Instruction::Subv(target, source) => {
+            binary(*target, *source).write(&[T])
+        }
+        Instruction::AddI(target, _) => Metadata::new(1, 1)
+            .read(&[reg(*target)])
+            .write(&[reg(*target)]),
+        Instruction::MulL(first, second)
+        | Instruction::MulsW(first, second)
+        | Instruction::MuluW(first, second) => Metadata::new(1, 2)
+            .read(&[reg(*first), reg(*second)])
+            .write(&[Macl]),
+        Instruction::DmulSL(first, second) | Instruction::DmuluL(first, second) => {
+            Metadata::new(1, 2)
+                .read(&[reg(*first), reg(*second)])
+                .write(&[Mach, Macl])
+        }
+        Instruction::MacL(first, second) => mac(*first, *second, 5),
+        Instruction::MacW(first, second) => mac(*first, *second, 4),
+        Instruction::Div0s(dividend, divisor) => Metadata::new(1, 1)
+            .read(&[reg(*dividend), reg(*divisor)])
+            .write(&[T, Control(ControlRegister::Sr)]),
+        Instruction::Div0u => Metadata::new(1, 1).write(&[T, Control(ControlRegister::Sr)]),
+        Instruction::Div1(dividend, divisor) => binary(*dividend, *divisor)
+            .read(&[T, Control(ControlRegister::Sr)])
+            .write(&[T, Control(ControlRegister::Sr)]),
+        Instruction::CmpEq(first, second)
+        | Instruction::CmpHs(first, second)
+        | Instruction::CmpGe(first, second)
+        | Instruction::CmpHi(first, second)
+        | Instruction::CmpGt(first, second) => compare(*first, *second),
+        Instruction::CmpPz(register) => Metadata::new(1, 1).read(&[reg(*register)]).write(&[T]),
+        Instruction::Dt(register) => shift(*register, true),
+        Instruction::Sett | Instruction::Clrt => Metadata::new(1, 1).write(&[T]),
+        Instruction::BT(_) | Instruction::BF(_) => Metadata::branch(false).read(&[T]),
+        Instruction::BTs(_) | Instruction::BFs(_) => Metadata::branch(true).read(&[T]),
+        Instruction::BRA(_) => Metadata::branch(true),
+        Instruction::BSR(_) => Metadata::branch(true).write(&[Pr]),
+        Instruction::Jmp(register) | Instruction::Braf(register) => {
+            Metadata::branch(true).read(&[reg(*register)])
+        }
+        Instruction::Jsr(register) | Instruction::Bsrf(register) => {
+            Metadata::branch(true).read(&[reg(*register)]).write(&[Pr])
+        }
+        Instruction::Rts => Metadata::branch(true).read(&[Pr]),
+        Instruction::Shll2(register)
+        | Instruction::Shll8(register)
+        | Instruction::Shll16(register)
+        | Instruction::Shlr2(register)
+        | Instruction::Shlr8(register)
+        | Instruction::Shlr16(register) => shift(*register, false),
+        Instruction::Shar(register)
+        | Instruction::Shll(register)
+        | Instruction::Shal(register)
+        | Instruction::Shlr(register)
+        | Instruction::Rotl(register)
+        | Instruction::Rotr(register) => shift(*register, true),
+        Instruction::Rotcl(register) | Instruction::Rotcr(register) => {
+            shift(*register, true).read(&[T])
+        }
+        Instruction::StsMacl(target) => Metadata::new(1, 1).read(&[Macl]).write(&[reg(*target)]),
+        Instruction::StsMach(target) => Metadata::new(1, 1).read(&[Mach]).write(&[reg(*target)]),
+        Instruction::StsLMacl(stack) => push(Macl, *stack),
+        Instruction::StsLMach(stack) => push(Mach, *stack),
+        Instruction::LdsLMacl(stack) => pop(Macl, *stack),
+        Instruction::LdsLMach(stack) => pop(Mach, *stack),
+        Instruction::Ldc(ControlRegister::Sr, source) => Metadata {
+            slot_illegal: true,
+            ..Metadata::new(4, 4)
+                .read(&[reg(*source)])
+                .write(&[Control(ControlRegister::Sr), T])
+        },
+        Instruction::Ldc(control, source) => Metadata::new(1, 1)
+            .read(&[reg(*source)])
+            .write(&[Control(*control)]),
+        Instruction::Stc(target, ControlRegister::Sr) => Metadata::new(1, 1)
+            .read(&[Control(ControlRegister::Sr), T])
+            .write(&[reg(*target)]),
+        Instruction::Stc(target, control) => Metadata::new(1, 1)
+            .read(&[Control(*control)])
+            .write(&[reg(*target)]),
+        Instruction::Trapa(_) => Metadata {
+            slot_illegal: true,
+            ..Metadata::new(8, 8)
+                .read(&[
+                    Control(ControlRegister::Sr),
+                    T,
+                    Control(ControlRegister::Vbr),
+                    reg(15),
+                ])
+                .write(&[Control(ControlRegister::Sr)])
+        },
+        Instruction::Sleep => Metadata::new(1, 1),
+        Instruction::Pref(register) => Metadata::new(1, 1).read(&[reg(*register)]),
+        Instruction::Label(_)
+        | Instruction::JmpLabel(_)
+        | Instruction::JsrLabel(_)
+        | Instruction::Literal(_, _)
+        | Instruction::Section(_)
+        | Instruction::Data(_)
+        | Instruction::Align(_)
+        | Instruction::Space(_)
+        | Instruction::LabelAddress(_)
+        | Instruction::Global(_)
+        | Instruction::MovLabel(_, _)
+        | Instruction::MovConst(_, _)
+        | Instruction::MovALabel(_)
+        | Instruction::LoadWord(_, _)
+        | Instruction::LoadLong(_, _) => return None,
+    };
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_slots() {
+        for instr in [
+            Instruction::BTs(0),
+            Instruction::BFs(0),
+            Instruction::BRA(0),
+            Instruction::BSR(0),
+            Instruction::Jmp(1),
+            Instruction::Jsr(1),
+            Instruction::Braf(1),
+            Instruction::Bsrf(1),
+            Instruction::Rts,
+        ]
+        .iter()
+        {
+            assert!(instr.has_delay_slot(), "{:?}", instr);
+            assert!(instr.is_slot_illegal(), "{:?}", instr);
+        }
+
+        for instr in [
+            Instruction::BT(0),
+            Instruction::BF(0),
+            Instruction::Trapa(0),
+            Instruction::Ldc(ControlRegister::Sr, 1),
+        ]
+        .iter()
+        {
+            assert!(!instr.has_delay_slot(), "{:?}", instr);
+            assert!(instr.is_slot_illegal(), "{:?}", instr);
+        }
+
+        assert!(!Instruction::Add(1, 2).is_slot_illegal());
+        assert!(!Instruction::Ldc(ControlRegister::Gbr, 1).is_slot_illegal());
+    }
+
+    #[test]
+    fn register_effects() {
+        let metadata = Instruction::Addc(1, 2).metadata().unwrap();
+        assert_eq!(
+            vec![Resource::Register(1), Resource::Register(2), Resource::T],
+            metadata.reads
+        );
+        assert_eq!(vec![Resource::Register(1), Resource::T], metadata.writes);
+
+        let metadata = Instruction::MovL(Operand::Register(1), Operand::PostIncrement(15))
+            .metadata()
+            .unwrap();
+        assert_eq!(
+            vec![Resource::Register(15), Resource::Memory],
+            metadata.reads
+        );
+        assert_eq!(
+            vec![Resource::Register(15), Resource::Register(1)],
+            metadata.writes
+        );
+
+        let metadata = Instruction::Jsr(3).metadata().unwrap();
+        assert_eq!(vec![Resource::Register(3)], metadata.reads);
+        assert_eq!(vec![Resource::Pr], metadata.writes);
+
+        let metadata = Instruction::DmulSL(1, 2).metadata().unwrap();
+        assert_eq!(vec![Resource::Mach, Resource::Macl], metadata.writes);
+    }
+
+    #[test]
+    fn timing() {
+        let metadata = Instruction::Add(1, 2).metadata().unwrap();
+        assert_eq!((1, 1), (metadata.issue, metadata.latency));
+
+        let metadata = Instruction::MacL(1, 2).metadata().unwrap();
+        assert_eq!((2, 5), (metadata.issue, metadata.latency));
+
+        let metadata = Instruction::TstB(Operand::OffsetR0GBR, 1)
+            .metadata()
+            .unwrap();
+        assert_eq!((3, 3), (metadata.issue, metadata.latency));
+    }
+
+    #[test]
+    fn pseudo_instructions() {
+        assert_eq!(None, Instruction::Label("test".to_owned()).metadata());
+        assert_eq!(None, Instruction::MovConst(1, 0x12345678).metadata());
+        assert_eq!(None, Instruction::Literal(0xff, 0xff).metadata());
+        assert!(!Instruction::JmpLabel("test".to_owned()).has_delay_slot());
+    }
+}
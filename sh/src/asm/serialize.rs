@@ -1,64 +1,232 @@
-use crate::asm::{Instruction, Operand};
+use crate::asm::{ControlRegister, EncodeError, Instruction, Operand};
 
 /// Converts the given Instruction into its appropriate
 /// ByteCode Variant that can then be run on the Calculator
-pub fn serialize(instr: &Instruction) -> [u8; 2] {
-    match instr {
+pub fn serialize(instr: &Instruction) -> Result<[u8; 2], EncodeError> {
+    let bytes = match instr {
         Instruction::Nop => [0x00, 0x09],
-        Instruction::Mov(target, source) => [0x60 | (target & 0x0f), 0x03 | ((source << 4) & 0xf0)],
-        Instruction::MovI(target, value) => [0xe0 | (target & 0x0f), *value],
-        Instruction::MovW(Operand::Register(target), Operand::AtRegister(source)) => {
-            [0x60 | (target & 0x0f), 0x01 | ((source << 4) & 0xf0)]
+        Instruction::Mov(target, source) => [0x60 | reg(*target)?, 0x03 | (reg(*source)? << 4)],
+        Instruction::MovI(target, value) => [0xe0 | reg(*target)?, *value],
+        Instruction::MovT(target) => [reg(*target)?, 0x29],
+        Instruction::MovA(disp) => [0xc7, *disp],
+        Instruction::MovB(target, source) => serialize_mov(0x0, target, source)?,
+        Instruction::MovW(target, source) => serialize_mov(0x1, target, source)?,
+        Instruction::MovL(target, source) => serialize_mov(0x2, target, source)?,
+        Instruction::ExtuB(target, source) => [0x60 | reg(*target)?, 0x0c | (reg(*source)? << 4)],
+        Instruction::ExtuW(target, source) => [0x60 | reg(*target)?, 0x0d | (reg(*source)? << 4)],
+        Instruction::ExtsB(target, source) => [0x60 | reg(*target)?, 0x0e | (reg(*source)? << 4)],
+        Instruction::ExtsW(target, source) => [0x60 | reg(*target)?, 0x0f | (reg(*source)? << 4)],
+        Instruction::SwapB(target, source) => [0x60 | reg(*target)?, 0x08 | (reg(*source)? << 4)],
+        Instruction::SwapW(target, source) => [0x60 | reg(*target)?, 0x09 | (reg(*source)? << 4)],
+        Instruction::Xtrct(target, source) => [0x20 | reg(*target)?, 0x0d | (reg(*source)? << 4)],
+        Instruction::StsPr(target) => [reg(*target)?, 0x2a],
+        Instruction::Push(register) => [0x2f, 0x06 | (reg(*register)? << 4)],
+        Instruction::PushOther(source, stack) => [0x20 | reg(*stack)?, 0x06 | (reg(*source)? << 4)],
+        Instruction::PushOtherB(source, stack) => {
+            [0x20 | reg(*stack)?, 0x04 | (reg(*source)? << 4)]
         }
-        Instruction::MovL(Operand::Register(target), Operand::AtRegister(source)) => {
-            [0x60 | (target & 0x0f), 0x02 | ((source << 4) & 0xf0)]
+        Instruction::PushPR => [0x4f, 0x22],
+        Instruction::PushPROther(stack) => [0x40 | reg(*stack)?, 0x22],
+        Instruction::Pop(register) => [0x60 | reg(*register)?, 0xf6],
+        Instruction::PopOther(target, stack) => [0x60 | reg(*target)?, 0x06 | (reg(*stack)? << 4)],
+        Instruction::PopPR => [0x4f, 0x26],
+        Instruction::PopPROther(stack) => [0x40 | reg(*stack)?, 0x26],
+        Instruction::Tst(target, other) => [0x20 | reg(*target)?, 0x08 | (reg(*other)? << 4)],
+        Instruction::Xor(target, other) => [0x20 | reg(*target)?, 0x0a | (reg(*other)? << 4)],
+        Instruction::Or(target, other) => [0x20 | reg(*target)?, 0x0b | (reg(*other)? << 4)],
+        Instruction::And(target, other) => [0x20 | reg(*target)?, 0x09 | (reg(*other)? << 4)],
+        Instruction::Not(target, source) => [0x60 | reg(*target)?, 0x07 | (reg(*source)? << 4)],
+        Instruction::TstI(value) => [0xc8, *value],
+        Instruction::AndI(value) => [0xc9, *value],
+        Instruction::XorI(value) => [0xca, *value],
+        Instruction::OrI(value) => [0xcb, *value],
+        Instruction::TstB(Operand::OffsetR0GBR, value) => [0xcc, *value],
+        Instruction::AndB(Operand::OffsetR0GBR, value) => [0xcd, *value],
+        Instruction::XorB(Operand::OffsetR0GBR, value) => [0xce, *value],
+        Instruction::OrB(Operand::OffsetR0GBR, value) => [0xcf, *value],
+        Instruction::Neg(target, source) => [0x60 | reg(*target)?, 0x0b | (reg(*source)? << 4)],
+        Instruction::Negc(target, source) => [0x60 | reg(*target)?, 0x0a | (reg(*source)? << 4)],
+        Instruction::Add(target, other) => [0x30 | reg(*target)?, 0x0c | (reg(*other)? << 4)],
+        Instruction::Addc(target, other) => [0x30 | reg(*target)?, 0x0e | (reg(*other)? << 4)],
+        Instruction::Addv(target, other) => [0x30 | reg(*target)?, 0x0f | (reg(*other)? << 4)],
+        Instruction::AddI(target, value) => [0x70 | reg(*target)?, *value],
+        Instruction::Sub(target, other) => [0x30 | reg(*target)?, 0x08 | (reg(*other)? << 4)],
+        Instruction::Subc(target, other) => [0x30 | reg(*target)?, 0x0a | (reg(*other)? << 4)],
+        Instruction::Subv(target, other) => [0x30 | reg(*target)?, 0x0b | (reg(*other)? << 4)],
+        Instruction::MulL(first, second) => [0x00 | reg(*first)?, (reg(*second)? << 4) | 0x07],
+        Instruction::DmulSL(first, second) => [0x30 | reg(*first)?, (reg(*second)? << 4) | 0x0d],
+        Instruction::DmuluL(first, second) => [0x30 | reg(*first)?, (reg(*second)? << 4) | 0x05],
+        Instruction::MulsW(first, second) => [0x20 | reg(*first)?, (reg(*second)? << 4) | 0x0f],
+        Instruction::MuluW(first, second) => [0x20 | reg(*first)?, (reg(*second)? << 4) | 0x0e],
+        Instruction::MacL(first, second) => [0x00 | reg(*first)?, (reg(*second)? << 4) | 0x0f],
+        Instruction::MacW(first, second) => [0x40 | reg(*first)?, (reg(*second)? << 4) | 0x0f],
+        Instruction::Div0s(first, second) => [0x20 | reg(*first)?, (reg(*second)? << 4) | 0x07],
+        Instruction::Div0u => [0x00, 0x19],
+        Instruction::Div1(first, second) => [0x30 | reg(*first)?, (reg(*second)? << 4) | 0x04],
+        Instruction::CmpEqI(value) => [0x88, *value],
+        Instruction::CmpEq(left, right) => [0x30 | reg(*left)?, (reg(*right)? << 4) | 0x00],
+        Instruction::CmpHs(left, right) => [0x30 | reg(*left)?, (reg(*right)? << 4) | 0x02],
+        Instruction::CmpGe(left, right) => [0x30 | reg(*left)?, (reg(*right)? << 4) | 0x03],
+        Instruction::CmpHi(left, right) => [0x30 | reg(*left)?, (reg(*right)? << 4) | 0x06],
+        Instruction::CmpGt(left, right) => [0x30 | reg(*left)?, (reg(*right)? << 4) | 0x07],
+        Instruction::CmpPz(target) => [0x40 | reg(*target)?, 0x11],
+        Instruction::Dt(target) => [0x40 | reg(*target)?, 0x10],
+        Instruction::Sett => [0x00, 0x18],
+        Instruction::Clrt => [0x00, 0x08],
+        Instruction::BT(disp) => [0x89, *disp],
+        Instruction::BF(disp) => [0x8b, *disp],
+        Instruction::BTs(disp) => [0x8d, *disp],
+        Instruction::BFs(disp) => [0x8f, *disp],
+        Instruction::BRA(disp) => {
+            let disp = disp12(*disp)?;
+            [0xa0 | (((disp & 0x0f00) >> 8) as u8), (disp & 0x00ff) as u8]
         }
-        Instruction::MovB(Operand::AtRegister(target), Operand::Register(source)) => {
-            [0x20 | (target & 0x0f), 0x00 | ((source << 4) & 0xf0)]
+        Instruction::BSR(disp) => {
+            let disp = disp12(*disp)?;
+            [0xb0 | (((disp & 0x0f00) >> 8) as u8), (disp & 0xff) as u8]
         }
-        Instruction::MovW(Operand::AtRegister(target), Operand::Register(source)) => {
-            [0x20 | (target & 0x0f), 0x01 | ((source << 4) & 0xf0)]
+        Instruction::Jmp(target) => [0x40 | reg(*target)?, 0x2b],
+        Instruction::Braf(target) => [reg(*target)?, 0x23],
+        Instruction::Bsrf(target) => [reg(*target)?, 0x03],
+        Instruction::Jsr(target) => [0x40 | reg(*target)?, 0x0b],
+        Instruction::Rts => [0x00, 0x0b],
+        Instruction::Shll(target) => [0x40 | reg(*target)?, 0x00],
+        Instruction::Shll2(target) => [0x40 | reg(*target)?, 0x08],
+        Instruction::Shll8(target) => [0x40 | reg(*target)?, 0x18],
+        Instruction::Shll16(target) => [0x40 | reg(*target)?, 0x28],
+        Instruction::Shal(target) => [0x40 | reg(*target)?, 0x20],
+        Instruction::Shar(target) => [0x40 | reg(*target)?, 0x21],
+        Instruction::Rotl(target) => [0x40 | reg(*target)?, 0x04],
+        Instruction::Rotr(target) => [0x40 | reg(*target)?, 0x05],
+        Instruction::Rotcl(target) => [0x40 | reg(*target)?, 0x24],
+        Instruction::Rotcr(target) => [0x40 | reg(*target)?, 0x25],
+        Instruction::Shld(target, count) => [0x40 | reg(*target)?, 0x0d | (reg(*count)? << 4)],
+        Instruction::Shad(target, count) => [0x40 | reg(*target)?, 0x0c | (reg(*count)? << 4)],
+        Instruction::Shlr(target) => [0x40 | reg(*target)?, 0x01],
+        Instruction::Shlr2(target) => [0x40 | reg(*target)?, 0x09],
+        Instruction::Shlr8(target) => [0x40 | reg(*target)?, 0x19],
+        Instruction::Shlr16(target) => [0x40 | reg(*target)?, 0x29],
+        Instruction::StsMacl(target) => [0x00 | reg(*target)?, 0x1a],
+        Instruction::StsLMacl(stack) => [0x40 | reg(*stack)?, 0x12],
+        Instruction::LdsLMacl(stack) => [0x40 | reg(*stack)?, 0x16],
+        Instruction::StsMach(target) => [0x00 | reg(*target)?, 0x0a],
+        Instruction::StsLMach(stack) => [0x40 | reg(*stack)?, 0x02],
+        Instruction::LdsLMach(stack) => [0x40 | reg(*stack)?, 0x06],
+        Instruction::Ldc(control, source) => {
+            [0x40 | reg(*source)?, 0x0e | (control_bits(*control) << 4)]
         }
-        Instruction::MovL(Operand::AtRegister(target), Operand::Register(source)) => {
-            [0x20 | (target & 0x0f), 0x02 | ((source << 4) & 0xf0)]
+        Instruction::Stc(target, control) => {
+            [0x00 | reg(*target)?, 0x02 | (control_bits(*control) << 4)]
         }
-        Instruction::MovW(Operand::Register(target), Operand::Displacement8(disp)) => {
-            [0x90 | (target & 0x0f), *disp]
+        Instruction::Trapa(value) => [0xc3, *value],
+        Instruction::Sleep => [0x00, 0x1b],
+        Instruction::Pref(target) => [0x00 | reg(*target)?, 0x83],
+        Instruction::Literal(first, second) => [*first, *second],
+        Instruction::Label(_)
+        | Instruction::JmpLabel(_)
+        | Instruction::JsrLabel(_)
+        | Instruction::MovLabel(_, _)
+        | Instruction::MovConst(_, _)
+        | Instruction::MovALabel(_)
+        | Instruction::LoadWord(_, _)
+        | Instruction::LoadLong(_, _)
+        | Instruction::Section(_)
+        | Instruction::Data(_)
+        | Instruction::Align(_)
+        | Instruction::Space(_)
+        | Instruction::LabelAddress(_)
+        | Instruction::Global(_) => return Err(EncodeError::PseudoInstruction),
+        _ => return Err(EncodeError::UnsupportedOperands),
+    };
+
+    Ok(bytes)
+}
+
+/// Converts the given Move into its ByteCode, the Size selects between
+/// the Byte- (0), Word- (1) and Long-Variant (2) of the Move
+fn serialize_mov(size: u8, target: &Operand, source: &Operand) -> Result<[u8; 2], EncodeError> {
+    let bytes = match (target, source) {
+        (Operand::Register(target), Operand::AtRegister(source)) => {
+            [0x60 | reg(*target)?, size | (reg(*source)? << 4)]
         }
-        Instruction::MovL(Operand::Register(target), Operand::Displacement8(disp)) => {
-            [0xd0 | (target & 0x0f), *disp]
+        (Operand::Register(target), Operand::PostIncrement(source)) => {
+            [0x60 | reg(*target)?, 0x04 | size | (reg(*source)? << 4)]
         }
-        Instruction::Push(register) => [0x2f, 0x06 | ((register << 4) & 0xf0)],
-        Instruction::PushPR => [0x4f, 0x22],
-        Instruction::Pop(register) => [0x60 | (register & 0x0f), 0xf6],
-        Instruction::PopPR => [0x4f, 0x26],
-        Instruction::Xor(target, other) => [0x20 | (target & 0x0f), 0x0a | ((other << 4) & 0xf0)],
-        Instruction::Add(target, other) => [0x30 | (target & 0x0f), 0x0c | ((other << 4) & 0xf0)],
-        Instruction::AddI(target, value) => [0x70 | (target & 0x0f), *value],
-        Instruction::MulL(first, second) => [0x00 | (first & 0x0f), (second << 4) | 0x07],
-        Instruction::CmpEq(left, right) => [0x30 | (left & 0x0f), (right << 4) | 0x00],
-        Instruction::CmpHs(left, right) => [0x30 | (left & 0x0f), (right << 4) | 0x02],
-        Instruction::CmpHi(left, right) => [0x30 | (left & 0x0f), (right << 4) | 0x06],
-        Instruction::BT(disp) => [0x89, *disp],
-        Instruction::BRA(disp) => [0xa0 | (((disp & 0x0f00) >> 8) as u8), (disp & 0x00ff) as u8],
-        Instruction::BSR(disp) => [0xb0 | (((disp & 0x0f00) >> 8) as u8), (disp & 0xff) as u8],
-        Instruction::Jmp(target) => [0x40 | (target & 0x0f), 0x2b],
-        Instruction::Jsr(target) => [0x40 | (target & 0x0f), 0x0b],
-        Instruction::Rts => [0x00, 0x0b],
-        Instruction::Shll(target) => [0x40 | (target & 0x0f), 0x00],
-        Instruction::Shll2(target) => [0x40 | (target & 0x0f), 0x08],
-        Instruction::Shll8(target) => [0x40 | (target & 0x0f), 0x18],
-        Instruction::Shll16(target) => [0x40 | (target & 0x0f), 0x28],
-        Instruction::Shlr(target) => [0x40 | (target & 0x0f), 0x01],
-        Instruction::Shlr2(target) => [0x40 | (target & 0x0f), 0x09],
-        Instruction::Shlr8(target) => [0x40 | (target & 0x0f), 0x19],
-        Instruction::Shlr16(target) => [0x40 | (target & 0x0f), 0x29],
-        Instruction::StsMacl(target) => [0x00 | (target & 0x0f), 0x1a],
-        Instruction::StsLMacl(stack) => [0x40 | (stack & 0x0f), 0x12],
-        Instruction::Literal(first, second) => [*first, *second],
-        Instruction::Label(_) => panic!("Labels are not an actual underlying instruction and only used to provide more structure"),
-        Instruction::JmpLabel(_) => panic!("Jump-Labels are not an actual underlying instruction and only used to provide more structure"),
-        Instruction::JsrLabel(_) => panic!("Jump-Subroutine-Labels are not an actual underlying instruction and only used to provide more structure"),
-        _ => unimplemented!("Combination {:?} is not yet implemented", instr),
+        (Operand::Register(target), Operand::OffsetR0(source)) => {
+            [reg(*target)?, 0x0c | size | (reg(*source)? << 4)]
+        }
+        (Operand::Register(target), Operand::Displacement4Reg(disp, source)) if size == 0x2 => {
+            [0x50 | reg(*target)?, disp4(*disp)? | (reg(*source)? << 4)]
+        }
+        (Operand::Register(0), Operand::Displacement4Reg(disp, source)) => {
+            [0x84 | size, disp4(*disp)? | (reg(*source)? << 4)]
+        }
+        (Operand::Register(0), Operand::DisplacementGBR(disp)) => [0xc4 | size, *disp],
+        (Operand::Register(target), Operand::Displacement8(disp)) if size == 0x1 => {
+            [0x90 | reg(*target)?, *disp]
+        }
+        (Operand::Register(target), Operand::Displacement8(disp)) if size == 0x2 => {
+            [0xd0 | reg(*target)?, *disp]
+        }
+        (Operand::AtRegister(target), Operand::Register(source)) => {
+            [0x20 | reg(*target)?, size | (reg(*source)? << 4)]
+        }
+        (Operand::PreDecrement(target), Operand::Register(source)) => {
+            [0x20 | reg(*target)?, 0x04 | size | (reg(*source)? << 4)]
+        }
+        (Operand::OffsetR0(target), Operand::Register(source)) => {
+            [reg(*target)?, 0x04 | size | (reg(*source)? << 4)]
+        }
+        (Operand::Displacement4Reg(disp, target), Operand::Register(source)) if size == 0x2 => {
+            [0x10 | reg(*target)?, disp4(*disp)? | (reg(*source)? << 4)]
+        }
+        (Operand::Displacement4Reg(disp, target), Operand::Register(0)) => {
+            [0x80 | size, disp4(*disp)? | (reg(*target)? << 4)]
+        }
+        (Operand::DisplacementGBR(disp), Operand::Register(0)) => [0xc0 | size, *disp],
+        _ => return Err(EncodeError::UnsupportedOperands),
+    };
+
+    Ok(bytes)
+}
+
+/// Checks that the Register exists, as only R0 to R15 can be encoded
+fn reg(register: u8) -> Result<u8, EncodeError> {
+    if register > 0x0f {
+        return Err(EncodeError::InvalidRegister(register));
+    }
+    Ok(register)
+}
+
+/// Checks that the (already scaled) Displacement fits into 4 Bits
+fn disp4(disp: u8) -> Result<u8, EncodeError> {
+    if disp > 0x0f {
+        return Err(EncodeError::DisplacementOutOfRange {
+            displacement: disp as i32,
+            bits: 4,
+        });
+    }
+    Ok(disp)
+}
+
+/// Checks that the Displacement of a Branch fits into 12 Bits, it can
+/// either already be 12 Bits wide or be sign-extended to 16 Bits
+fn disp12(disp: u16) -> Result<u16, EncodeError> {
+    let signed = disp as i16;
+    if disp > 0x0fff && !(-0x0800..0).contains(&signed) {
+        return Err(EncodeError::DisplacementOutOfRange {
+            displacement: signed as i32,
+            bits: 12,
+        });
+    }
+    Ok(disp & 0x0fff)
+}
+
+/// The Bits that select the Control-Register in `LDC` and `STC`
+fn control_bits(control: ControlRegister) -> u8 {
+    match control {
+        ControlRegister::Sr => 0x0,
+        ControlRegister::Gbr => 0x1,
+        ControlRegister::Vbr => 0x2,
     }
 }
@@ -0,0 +1,277 @@
+use std::fmt;
+
+/// The Magic-Bytes at the Start of every ELF-File
+pub const MAGIC: &[u8; 4] = b"\x7fELF";
+pub const CLASS_32: u8 = 1;
+pub const DATA_BIG_ENDIAN: u8 = 2;
+pub const VERSION: u8 = 1;
+
+pub const TYPE_RELOCATABLE: u16 = 1;
+pub const TYPE_EXECUTABLE: u16 = 2;
+pub const MACHINE_SH: u16 = 42;
+
+pub const HEADER_SIZE: usize = 52;
+pub const PROGRAM_HEADER_SIZE: usize = 32;
+pub const SECTION_HEADER_SIZE: usize = 40;
+
+pub const PT_LOAD: u32 = 1;
+
+/// The Reasons why an ELF-File could not be parsed
+#[derive(Debug, PartialEq, Clone)]
+pub enum ElfError {
+    /// The Data does not start with the expected Magic-Bytes
+    WrongIdentifier,
+    /// The Data ended before the whole File was read
+    UnexpectedEnd,
+    /// The Data is not a valid ELF-File, with the Reason
+    Malformed(String),
+}
+
+impl fmt::Display for ElfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongIdentifier => write!(f, "not an ELF-file"),
+            Self::UnexpectedEnd => write!(f, "unexpected end of the ELF-file"),
+            Self::Malformed(reason) => write!(f, "malformed ELF-file: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ElfError {}
+
+/// Whether or not the Data looks like an ELF-File
+pub fn is_elf(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// A Part of an Executable that is loaded into Memory
+#[derive(Debug, PartialEq, Clone)]
+pub struct Segment {
+    /// The Address at which the Segment is loaded
+    pub address: u32,
+    /// The Content of the Segment
+    pub data: Vec<u8>,
+    /// The Size in Memory, everything after the Data is filled with Zeros
+    pub size: u32,
+}
+
+/// A linked Program in the ELF-Format, as it can be loaded by the Emulator
+/// or inspected with the usual `binutils`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Executable {
+    /// The Address of the first Instruction
+    pub entry: u32,
+    pub segments: Vec<Segment>,
+}
+
+impl Executable {
+    /// Parses an ELF-Executable, only the loadable Segments are used
+    pub fn parse(data: &[u8]) -> Result<Self, ElfError> {
+        let header = Header::parse(data)?;
+        if header.kind != TYPE_EXECUTABLE {
+            return Err(ElfError::Malformed(
+                "expected an executable ELF-file".to_owned(),
+            ));
+        }
+
+        let mut segments = Vec::new();
+        for index in 0..header.program_headers as usize {
+            let mut reader = Reader::at(
+                data,
+                header.program_offset as usize + index * PROGRAM_HEADER_SIZE,
+            );
+            let kind = reader.u32()?;
+            let offset = reader.u32()? as usize;
+            let address = reader.u32()?;
+            let _physical = reader.u32()?;
+            let file_size = reader.u32()?;
+            let size = reader.u32()?;
+            if kind != PT_LOAD {
+                continue;
+            }
+            if size < file_size {
+                return Err(ElfError::Malformed(
+                    "segment is smaller than its data".to_owned(),
+                ));
+            }
+
+            let content = Reader::at(data, offset).bytes(file_size as usize)?;
+            segments.push(Segment {
+                address,
+                data: content.to_vec(),
+                size,
+            });
+        }
+
+        Ok(Self {
+            entry: header.entry,
+            segments,
+        })
+    }
+}
+
+/// The Parts of the ELF-Header that are not always the same
+#[derive(Debug, Default)]
+pub struct Header {
+    pub kind: u16,
+    pub entry: u32,
+    pub program_offset: u32,
+    pub section_offset: u32,
+    pub program_headers: u16,
+    pub section_headers: u16,
+    /// The Index of the Section that contains the Section-Names
+    pub names: u16,
+}
+
+impl Header {
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(HEADER_SIZE);
+        result.extend_from_slice(MAGIC);
+        result.extend_from_slice(&[CLASS_32, DATA_BIG_ENDIAN, VERSION]);
+        result.resize(16, 0);
+
+        result.extend_from_slice(&self.kind.to_be_bytes());
+        result.extend_from_slice(&MACHINE_SH.to_be_bytes());
+        result.extend_from_slice(&(VERSION as u32).to_be_bytes());
+        result.extend_from_slice(&self.entry.to_be_bytes());
+        result.extend_from_slice(&self.program_offset.to_be_bytes());
+        result.extend_from_slice(&self.section_offset.to_be_bytes());
+        // The Flags, which are only used to tell the different SH-Variants
+        // apart and are left as unknown
+        result.extend_from_slice(&0u32.to_be_bytes());
+        for value in [
+            HEADER_SIZE as u16,
+            PROGRAM_HEADER_SIZE as u16,
+            self.program_headers,
+            SECTION_HEADER_SIZE as u16,
+            self.section_headers,
+            self.names,
+        ]
+        .iter()
+        {
+            result.extend_from_slice(&value.to_be_bytes());
+        }
+        result
+    }
+
+    /// Parses the Header of a 32-Bit Big-Endian ELF-File for SH
+    pub fn parse(data: &[u8]) -> Result<Self, ElfError> {
+        if !is_elf(data) {
+            return Err(ElfError::WrongIdentifier);
+        }
+        let mut reader = Reader::at(data, MAGIC.len());
+        let identification = reader.bytes(3)?;
+        if identification[0] != CLASS_32 || identification[1] != DATA_BIG_ENDIAN {
+            return Err(ElfError::Malformed(
+                "expected a 32-bit big-endian ELF-file".to_owned(),
+            ));
+        }
+
+        let mut reader = Reader::at(data, 16);
+        let kind = reader.u16()?;
+        let machine = reader.u16()?;
+        if machine != MACHINE_SH {
+            return Err(ElfError::Malformed(format!(
+                "expected an ELF-file for SH, found machine {}",
+                machine
+            )));
+        }
+        let _version = reader.u32()?;
+        let entry = reader.u32()?;
+        let program_offset = reader.u32()?;
+        let section_offset = reader.u32()?;
+        let _flags = reader.u32()?;
+        let _header_size = reader.u16()?;
+        let _program_header_size = reader.u16()?;
+        let program_headers = reader.u16()?;
+        let _section_header_size = reader.u16()?;
+        let section_headers = reader.u16()?;
+        let names = reader.u16()?;
+
+        Ok(Self {
+            kind,
+            entry,
+            program_offset,
+            section_offset,
+            program_headers,
+            section_headers,
+            names,
+        })
+    }
+}
+
+/// Reads the Big-Endian Values of a File
+pub struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Starts reading at the given Offset in the Data
+    pub fn at(data: &'a [u8], position: usize) -> Self {
+        Self { data, position }
+    }
+
+    pub fn bytes(&mut self, count: usize) -> Result<&'a [u8], ElfError> {
+        let end = self
+            .position
+            .checked_add(count)
+            .filter(|end| *end <= self.data.len())
+            .ok_or(ElfError::UnexpectedEnd)?;
+        let result = &self.data[self.position..end];
+        self.position = end;
+        Ok(result)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, ElfError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn u16(&mut self) -> Result<u16, ElfError> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub fn u32(&mut self) -> Result<u32, ElfError> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_roundtrip() {
+        let header = Header {
+            kind: TYPE_EXECUTABLE,
+            entry: 0x00300000,
+            program_offset: HEADER_SIZE as u32,
+            program_headers: 1,
+            ..Header::default()
+        };
+
+        let parsed = Header::parse(&header.serialize()).unwrap();
+        assert_eq!(TYPE_EXECUTABLE, parsed.kind);
+        assert_eq!(0x00300000, parsed.entry);
+        assert_eq!(1, parsed.program_headers);
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(Err(ElfError::WrongIdentifier), Executable::parse(b"RZOB"));
+        assert_eq!(Err(ElfError::UnexpectedEnd), Executable::parse(MAGIC));
+
+        let relocatable = Header {
+            kind: TYPE_RELOCATABLE,
+            ..Header::default()
+        };
+        assert_eq!(
+            Err(ElfError::Malformed(
+                "expected an executable ELF-file".to_owned()
+            )),
+            Executable::parse(&relocatable.serialize())
+        );
+    }
+}
@@ -1 +1,2 @@
 pub mod asm;
+pub mod elf;